@@ -0,0 +1,38 @@
+//! Benchmarks of the hot RPC methods, served by the Kakarot RPC over Katana and its database
+//! filled with random documents. Run with `cargo bench --features testing`.
+use criterion::{criterion_group, criterion_main, Criterion};
+use kakarot_rpc::bench::{send_request, BenchMethod};
+use kakarot_rpc::test_utils::eoa::Eoa as _;
+use kakarot_rpc::test_utils::katana::Katana;
+use kakarot_rpc::test_utils::mongo::RANDOM_BYTES_SIZE;
+use kakarot_rpc::test_utils::rpc::start_kakarot_rpc_server;
+use reth_primitives::Address;
+
+fn rpc_benchmarks(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to start the runtime");
+    let (katana, server_addr, server_handle) = runtime.block_on(async {
+        let katana = Katana::new(RANDOM_BYTES_SIZE).await;
+        let (server_addr, server_handle) =
+            start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+        (katana, server_addr, server_handle)
+    });
+    let url = format!("http://localhost:{}", server_addr.port());
+    let client = reqwest::Client::new();
+    // The calls target the EOA of Katana, which holds no code
+    let call_to = katana.eoa().evm_address().unwrap_or(Address::ZERO);
+
+    let mut group = c.benchmark_group("rpc");
+    for method in BenchMethod::ALL {
+        group.bench_function(method.name(), |b| {
+            b.to_async(&runtime).iter(|| async {
+                send_request(&client, &url, method.request(0, call_to)).await.expect("Failed to call the RPC")
+            })
+        });
+    }
+    group.finish();
+
+    drop(server_handle);
+}
+
+criterion_group!(benches, rpc_benchmarks);
+criterion_main!(benches);
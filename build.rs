@@ -0,0 +1,16 @@
+use std::process::Command;
+
+/// Exposes the commit hash of the build as `KAKAROT_RPC_GIT_HASH`, reported by `web3_clientVersion`.
+/// The hash can be set through the environment when building outside of the git repository,
+/// e.g. in a Docker image.
+fn main() {
+    println!("cargo:rerun-if-env-changed=KAKAROT_RPC_GIT_HASH");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+
+    let git_hash = std::env::var("KAKAROT_RPC_GIT_HASH").ok().filter(|hash| !hash.trim().is_empty()).or_else(|| {
+        let output = Command::new("git").args(["rev-parse", "--short=8", "HEAD"]).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+    println!("cargo:rustc-env=KAKAROT_RPC_GIT_HASH={}", git_hash.as_deref().unwrap_or("unknown"));
+}
@@ -0,0 +1,260 @@
+//! Load test of a live RPC endpoint, run with `kakarot-rpc bench`. Each benchmarked method is
+//! called a fixed number of times with a bounded number of concurrent requests, and its
+//! throughput and latency percentiles are reported. The same requests are measured against
+//! Katana by the criterion benchmarks of `benches/rpc.rs`.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use eyre::{eyre, Result};
+use futures::StreamExt;
+use reth_primitives::Address;
+use serde_json::{json, Value};
+
+/// Default number of requests sent for each method.
+const DEFAULT_BENCH_REQUESTS: usize = 1000;
+/// Default number of concurrent requests.
+const DEFAULT_BENCH_CONCURRENCY: usize = 16;
+
+/// A benchmarked RPC method, called with parameters which only depend on the latest block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchMethod {
+    /// `eth_call` of the target address at the latest block.
+    Call,
+    /// `eth_getLogs` of the latest block.
+    GetLogs,
+    /// `eth_getBlockByNumber` of the latest block, with its transaction hashes.
+    GetBlockByNumber,
+}
+
+impl BenchMethod {
+    /// The methods benchmarked by default.
+    pub const ALL: [Self; 3] = [Self::Call, Self::GetLogs, Self::GetBlockByNumber];
+
+    /// Returns the name of the RPC method.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Call => "eth_call",
+            Self::GetLogs => "eth_getLogs",
+            Self::GetBlockByNumber => "eth_getBlockByNumber",
+        }
+    }
+
+    /// Returns the body of a request calling the method, where `eth_call` calls the given address.
+    pub fn request(&self, id: usize, call_to: Address) -> String {
+        let params = match self {
+            Self::Call => json!([{ "to": call_to, "data": "0x" }, "latest"]),
+            Self::GetLogs => json!([{ "fromBlock": "latest", "toBlock": "latest" }]),
+            Self::GetBlockByNumber => json!(["latest", false]),
+        };
+        json!({ "jsonrpc": "2.0", "id": id, "method": self.name(), "params": params }).to_string()
+    }
+}
+
+impl FromStr for BenchMethod {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL.into_iter().find(|method| method.name().eq_ignore_ascii_case(s.trim())).ok_or_else(|| {
+            eyre!("Invalid benchmarked method {s}, expected eth_call, eth_getLogs or eth_getBlockByNumber")
+        })
+    }
+}
+
+/// Configuration of the benchmark, read from the arguments of `kakarot-rpc bench`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// URL of the benchmarked endpoint.
+    pub url: String,
+    pub methods: Vec<BenchMethod>,
+    /// Number of requests sent for each method.
+    pub requests: usize,
+    /// Number of concurrent requests.
+    pub concurrency: usize,
+    /// Address called by `eth_call`.
+    pub call_to: Address,
+}
+
+impl BenchConfig {
+    /// Create a new [`BenchConfig`] benchmarking all the methods of the endpoint at the URL.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            methods: BenchMethod::ALL.to_vec(),
+            requests: DEFAULT_BENCH_REQUESTS,
+            concurrency: DEFAULT_BENCH_CONCURRENCY,
+            call_to: Address::ZERO,
+        }
+    }
+
+    /// Reads the configuration of the benchmark from the arguments of the command, e.g.
+    /// `bench --url http://localhost:3030 --methods eth_call,eth_getLogs --requests 1000
+    /// --concurrency 16 --call-to 0x...`. Returns None if the command isn't `bench`. The other
+    /// flags, such as `--config`, are ignored.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut args = args.into_iter().peekable();
+        if args.next_if(|arg| arg == "bench").is_none() {
+            return Ok(None);
+        }
+
+        let mut url = None;
+        let mut config = Self::new(String::new());
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            if !["--url", "--methods", "--requests", "--concurrency", "--call-to"].contains(&flag.as_str()) {
+                continue;
+            }
+            let value = value.or_else(|| args.next()).ok_or_else(|| eyre!("Missing value for {flag}"))?;
+            let number = || usize::from_str(value.trim()).map_err(|err| eyre!("Invalid {flag} {value}: {err}"));
+            match flag.as_str() {
+                "--url" => url = Some(value.trim().to_string()),
+                "--methods" => config.methods = value.split(',').map(str::parse).collect::<Result<_>>()?,
+                "--requests" => config.requests = number()?.max(1),
+                "--concurrency" => config.concurrency = number()?.max(1),
+                _ => config.call_to = value.trim().parse().map_err(|err| eyre!("Invalid {flag} {value}: {err}"))?,
+            }
+        }
+
+        config.url = url.ok_or_else(|| eyre!("Missing --url for the benchmark"))?;
+        Ok(Some(config))
+    }
+}
+
+/// Throughput and latency of the requests sent to a method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub method: &'static str,
+    pub requests: usize,
+    /// Number of requests which failed or returned an error.
+    pub errors: usize,
+    /// Requests per second.
+    pub throughput: f64,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl BenchReport {
+    /// Builds the report of a method from the latencies of its successful requests, the number of
+    /// failed requests and the duration of the whole run.
+    pub fn new(method: &'static str, mut latencies: Vec<Duration>, errors: usize, elapsed: Duration) -> Self {
+        latencies.sort_unstable();
+        let percentile = |percentile: usize| {
+            let rank = (latencies.len() * percentile).div_ceil(100).max(1);
+            latencies.get(rank - 1).copied().unwrap_or_default()
+        };
+        let requests = latencies.len() + errors;
+        Self {
+            method,
+            requests,
+            errors,
+            throughput: requests as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: latencies.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.;
+        write!(
+            f,
+            "{:<22} {:>8} requests {:>6} errors {:>10.1} req/s  p50 {:>8.2}ms  p90 {:>8.2}ms  p99 {:>8.2}ms  max {:>8.2}ms",
+            self.method,
+            self.requests,
+            self.errors,
+            self.throughput,
+            ms(self.p50),
+            ms(self.p90),
+            ms(self.p99),
+            ms(self.max)
+        )
+    }
+}
+
+/// Sends a request to the endpoint and returns its latency, or an error if the request failed or
+/// the endpoint returned an error.
+pub async fn send_request(client: &reqwest::Client, url: &str, body: String) -> Result<Duration> {
+    let start = Instant::now();
+    let response = client.post(url).header("Content-Type", "application/json").body(body).send().await?.text().await?;
+    let latency = start.elapsed();
+
+    let response: Value = serde_json::from_str(&response)?;
+    if let Some(error) = response.get("error") {
+        return Err(eyre!("RPC error: {error}"));
+    }
+    Ok(latency)
+}
+
+/// Benchmarks each method of the configuration in turn, and returns their reports.
+pub async fn run_bench(config: &BenchConfig) -> Result<Vec<BenchReport>> {
+    let client = reqwest::Client::new();
+    let mut reports = Vec::with_capacity(config.methods.len());
+
+    for method in &config.methods {
+        let start = Instant::now();
+        let results = futures::stream::iter(0..config.requests)
+            .map(|id| send_request(&client, &config.url, method.request(id, config.call_to)))
+            .buffer_unordered(config.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+        let elapsed = start.elapsed();
+
+        if let Some(Err(err)) = results.iter().find(|result| result.is_err()) {
+            tracing::warn!("Failed request to {}: {:?}", method.name(), err);
+        }
+        let errors = results.iter().filter(|result| result.is_err()).count();
+        let latencies = results.into_iter().filter_map(Result::ok).collect();
+        reports.push(BenchReport::new(method.name(), latencies, errors, elapsed));
+    }
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_config_from_args() {
+        // Given
+        let args = ["bench", "--url", "http://localhost:3030", "--methods=eth_call,eth_getLogs", "--requests", "10"];
+
+        // When
+        let config = BenchConfig::from_args(args.map(String::from)).unwrap().unwrap();
+
+        // Then
+        assert_eq!(config.url, "http://localhost:3030");
+        assert_eq!(config.methods, vec![BenchMethod::Call, BenchMethod::GetLogs]);
+        assert_eq!(config.requests, 10);
+        assert_eq!(config.concurrency, DEFAULT_BENCH_CONCURRENCY);
+        assert!(BenchConfig::from_args(["export".to_string()]).unwrap().is_none());
+        assert!(BenchConfig::from_args(["bench".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_bench_report() {
+        // Given
+        let latencies = (1..=100).rev().map(Duration::from_millis).collect::<Vec<_>>();
+
+        // When
+        let report = BenchReport::new("eth_call", latencies, 2, Duration::from_secs(2));
+
+        // Then
+        assert_eq!(report.requests, 102);
+        assert_eq!(report.errors, 2);
+        assert_eq!(report.throughput, 51.);
+        assert_eq!(report.p50, Duration::from_millis(50));
+        assert_eq!(report.p90, Duration::from_millis(90));
+        assert_eq!(report.p99, Duration::from_millis(99));
+        assert_eq!(report.max, Duration::from_millis(100));
+    }
+}
@@ -34,6 +34,11 @@ fn main() {
     builder = builder.with_eoa(pk).expect("Failed to set up EOA").fund(pk, U256::from(u128::MAX)).unwrap();
     builder = builder.with_dev_allocation(10);
 
+    // Check the genesis before Katana has to load it.
+    let report = builder.validate();
+    print!("{report}");
+    assert!(report.is_valid(), "Invalid genesis");
+
     let manifest = builder.manifest();
 
     let genesis = builder.build().expect("Failed to build genesis");
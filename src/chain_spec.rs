@@ -0,0 +1,201 @@
+use std::env::var;
+use std::path::Path;
+
+use eyre::{eyre, Result};
+use serde::Deserialize;
+use starknet::core::types::FieldElement;
+
+use crate::eth_provider::fee_policy::BaseFeePolicy;
+use crate::eth_provider::provider::EthereumProvider;
+
+/// Address of the ETH token on Starknet, used as the fee token by the known deployments.
+const STARKNET_ETH_TOKEN: &str = "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+
+/// Specification of a Kakarot deployment: the contracts the RPC interacts with and the Starknet
+/// block from which the deployment's blocks are indexed.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSpec {
+    /// Name of the deployment.
+    pub name: String,
+    /// Expected chain id, checked against the chain id derived from the Starknet provider.
+    pub chain_id: Option<u64>,
+    /// Network name or comma separated JSON-RPC URLs of the Starknet providers.
+    pub starknet_network: Option<String>,
+    /// Address of the Kakarot contract.
+    pub kakarot_address: Option<FieldElement>,
+    /// Class hash of the uninitialized account contract.
+    pub uninitialized_account_class_hash: Option<FieldElement>,
+    /// Class hash of the account contract.
+    pub account_contract_class_hash: Option<FieldElement>,
+    /// Address of the Starknet token used to pay the fees, whose balance is the Ethereum balance.
+    pub fee_token: FieldElement,
+    /// First Starknet block of the deployment.
+    #[serde(default)]
+    pub genesis_block: u64,
+    /// Conversion of the Starknet gas price into the reported base fee.
+    #[serde(default)]
+    pub base_fee_policy: BaseFeePolicy,
+}
+
+impl ChainSpec {
+    /// Returns the specification of the chain given by the `--chain` flag or by the `KAKAROT_CHAIN`
+    /// environment variable, if any. The chain is either the name of a known deployment or the
+    /// path of a JSON file holding a custom specification.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut args = args.into_iter();
+        let mut chain = var("KAKAROT_CHAIN").ok().filter(|chain| !chain.trim().is_empty());
+        while let Some(arg) = args.next() {
+            if let Some(value) = arg.strip_prefix("--chain=") {
+                chain = Some(value.to_string());
+            } else if arg == "--chain" {
+                chain = Some(args.next().ok_or_else(|| eyre!("Missing value for --chain"))?);
+            }
+        }
+        chain
+            .map(|chain| match Self::named(&chain) {
+                Some(spec) => Ok(spec),
+                None if chain.trim().eq_ignore_ascii_case("mainnet") => {
+                    Err(eyre!("Kakarot isn't deployed on mainnet yet, use a chain spec file"))
+                }
+                None => Self::from_file(chain.trim()),
+            })
+            .transpose()
+    }
+
+    /// Returns the specification of a known deployment.
+    pub fn named(name: &str) -> Option<Self> {
+        let fee_token = FieldElement::from_hex_be(STARKNET_ETH_TOKEN).expect("Invalid fee token address");
+        let felt = |hex: &str| Some(FieldElement::from_hex_be(hex).expect("Invalid chain spec field element"));
+
+        match name.trim().to_lowercase().as_str() {
+            "sepolia" => Some(Self {
+                name: "sepolia".to_string(),
+                chain_id: None,
+                starknet_network: None,
+                kakarot_address: felt("0x70c14f7fe5968975cff5cc4614c9d9a8cbb9051da19a28990772e3b55c23328"),
+                uninitialized_account_class_hash: felt(
+                    "0x600f6862938312a05a0cfecba0dcaf37693efc9e4075a6adfb62e196022678e",
+                ),
+                account_contract_class_hash: felt("0x490cccb64e3917ecf0a80a59d0c3e449766f745c1d6db54e0050f89eb59aba1"),
+                fee_token,
+                genesis_block: 0,
+                base_fee_policy: BaseFeePolicy::Oracle,
+            }),
+            // The addresses of a local deployment change with each deployment, and are read
+            // from the environment
+            "local" => Some(Self {
+                name: "local".to_string(),
+                chain_id: None,
+                starknet_network: Some("katana".to_string()),
+                kakarot_address: None,
+                uninitialized_account_class_hash: None,
+                account_contract_class_hash: None,
+                fee_token,
+                genesis_block: 0,
+                base_fee_policy: BaseFeePolicy::Oracle,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Reads a custom specification from the JSON file at the given path.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|err| {
+            eyre!("Unknown chain {}, expected one of sepolia, local or a chain spec file: {err}", path.display())
+        })?;
+        serde_json::from_str(&content).map_err(|err| eyre!("Invalid chain spec file {}: {err}", path.display()))
+    }
+
+    /// Returns the environment variables corresponding to the specification.
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let hex = |felt: &Option<FieldElement>| felt.map(|felt| format!("{felt:#x}"));
+        [
+            ("STARKNET_NETWORK", self.starknet_network.clone()),
+            ("KAKAROT_ADDRESS", hex(&self.kakarot_address)),
+            ("UNINITIALIZED_ACCOUNT_CLASS_HASH", hex(&self.uninitialized_account_class_hash)),
+            ("ACCOUNT_CONTRACT_CLASS_HASH", hex(&self.account_contract_class_hash)),
+            ("STARKNET_NATIVE_TOKEN", hex(&Some(self.fee_token))),
+            ("INDEXER_STARTING_BLOCK", Some(self.genesis_block.to_string())),
+            ("BASE_FEE_POLICY", Some(self.base_fee_policy.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect()
+    }
+
+    /// Sets the environment variables corresponding to the specification, unless they are
+    /// already set. Must be called before the configuration is read from the environment.
+    pub fn apply_to_env(&self) {
+        for (name, value) in self.env_vars() {
+            // Empty variables, as left by the .env.example file, are considered unset
+            if var(name).map_or(true, |value| value.trim().is_empty()) {
+                std::env::set_var(name, value);
+            }
+        }
+    }
+
+    /// Checks that the chain id served by the provider is the one of the specification.
+    pub async fn check_chain_id<P: EthereumProvider>(&self, provider: &P) -> Result<()> {
+        let Some(expected) = self.chain_id else {
+            return Ok(());
+        };
+        let chain_id = provider.chain_id().await?.unwrap_or_default().to::<u64>();
+        if chain_id != expected {
+            return Err(eyre!("Chain id {chain_id} doesn't match the chain id {expected} of the {} chain", self.name));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_chain_specs() {
+        // Given
+        let sepolia = ChainSpec::named("Sepolia").expect("Missing sepolia chain spec");
+        let local = ChainSpec::named("local").expect("Missing local chain spec");
+
+        // When
+        let sepolia_env = sepolia.env_vars();
+        let local_env = local.env_vars();
+
+        // Then
+        assert!(sepolia_env.contains(&(
+            "KAKAROT_ADDRESS",
+            "0x70c14f7fe5968975cff5cc4614c9d9a8cbb9051da19a28990772e3b55c23328".to_string()
+        )));
+        assert!(local_env.iter().all(|(name, _)| *name != "KAKAROT_ADDRESS"));
+        assert!(local_env.contains(&("STARKNET_NETWORK", "katana".to_string())));
+        assert!(ChainSpec::named("mainnet").is_none());
+    }
+
+    #[test]
+    fn test_custom_chain_spec() {
+        // Given
+        let spec = serde_json::json!({
+            "name": "custom",
+            "chain_id": 1_263_227_476,
+            "kakarot_address": "0x1",
+            "uninitialized_account_class_hash": "0x2",
+            "account_contract_class_hash": "0x3",
+            "fee_token": "0x4",
+            "genesis_block": 100,
+            "base_fee_policy": { "type": "multiplier", "basis_points": 12000 }
+        });
+
+        // When
+        let spec: ChainSpec = serde_json::from_value(spec).expect("Invalid chain spec");
+
+        // Then
+        assert_eq!(spec.chain_id, Some(1_263_227_476));
+        assert_eq!(spec.kakarot_address, Some(FieldElement::ONE));
+        assert!(spec.env_vars().contains(&("INDEXER_STARTING_BLOCK", "100".to_string())));
+        assert!(spec.env_vars().contains(&("STARKNET_NATIVE_TOKEN", "0x4".to_string())));
+        assert_eq!(spec.base_fee_policy, BaseFeePolicy::Multiplier { basis_points: 12000 });
+        assert!(spec.env_vars().contains(&("BASE_FEE_POLICY", "multiplier:12000".to_string())));
+    }
+}
@@ -0,0 +1,52 @@
+//! Typed Rust client of the Kakarot RPC, enabled by the `client` feature, so that the Rust
+//! services don't need to write the JSON requests of the Kakarot specific namespaces by hand.
+use jsonrpsee::core::ClientError;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+
+pub use crate::eth_rpc::api::alchemy_api::AlchemyApiClient;
+pub use crate::eth_rpc::api::kakarot_api::KakarotApiClient;
+pub use crate::eth_rpc::api::ots_api::OtterscanApiClient;
+
+/// Client of the Kakarot RPC over HTTP, with the typed methods of the namespaces which aren't
+/// part of the Ethereum JSON-RPC specification: `kakarot`, `ots` and `alchemy`. The methods of
+/// each namespace are called on the client returned by its accessor, e.g.
+/// `client.kakarot().get_l1_messages(from_block, to_block)`, with the client trait of the
+/// namespace in scope.
+#[derive(Debug, Clone)]
+pub struct KakarotRpcClient {
+    inner: HttpClient,
+}
+
+impl KakarotRpcClient {
+    /// Create a new [`KakarotRpcClient`] sending its requests to the given URL.
+    pub fn new(url: impl AsRef<str>) -> Result<Self, ClientError> {
+        Ok(Self { inner: HttpClientBuilder::default().build(url)? })
+    }
+
+    /// Returns the client of the `kakarot` namespace.
+    pub fn kakarot(&self) -> &impl KakarotApiClient {
+        &self.inner
+    }
+
+    /// Returns the client of the `ots` namespace.
+    pub fn ots(&self) -> &impl OtterscanApiClient {
+        &self.inner
+    }
+
+    /// Returns the client of the `alchemy` namespace.
+    pub fn alchemy(&self) -> &impl AlchemyApiClient {
+        &self.inner
+    }
+
+    /// Returns the underlying HTTP client, which serves the other namespaces through the raw
+    /// requests of [`jsonrpsee::core::client::ClientT`].
+    pub const fn inner(&self) -> &HttpClient {
+        &self.inner
+    }
+}
+
+impl From<HttpClient> for KakarotRpcClient {
+    fn from(inner: HttpClient) -> Self {
+        Self { inner }
+    }
+}
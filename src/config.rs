@@ -1,8 +1,11 @@
 use eyre::eyre;
+use serde::Deserialize;
 use starknet::core::types::FieldElement;
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcTransport};
 use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
+use std::collections::BTreeMap;
 use std::env::var;
+use std::path::Path;
 use url::Url;
 
 fn env_var_to_field_element(var_name: &str) -> Result<FieldElement, eyre::Error> {
@@ -21,6 +24,8 @@ pub enum Network {
     Goerli1Gateway,
     Goerli2Gateway,
     JsonRpcProvider(Url),
+    /// Multiple JSON-RPC providers, between which the requests are load balanced.
+    JsonRpcProviders(Vec<Url>),
 }
 
 impl From<String> for Network {
@@ -33,11 +38,13 @@ impl From<String> for Network {
             "goerli1" => Network::Goerli1Gateway,
             "goerli2" => Network::Goerli2Gateway,
             "testnet" => Network::Goerli1Gateway,
-            network_url => {
-                if let Ok(url) = Url::parse(network_url) {
-                    Network::JsonRpcProvider(url)
-                } else {
-                    Network::Katana
+            network_urls => {
+                // Multiple providers can be set by separating their URLs with a comma
+                let urls = network_urls.split(',').map(|url| Url::parse(url.trim())).collect::<Result<Vec<_>, _>>();
+                match urls {
+                    Ok(mut urls) if urls.len() == 1 => Network::JsonRpcProvider(urls.remove(0)),
+                    Ok(urls) if !urls.is_empty() => Network::JsonRpcProviders(urls),
+                    _ => Network::Katana,
                 }
             }
         }
@@ -62,9 +69,18 @@ impl Network {
                 var("SHARINGAN_RPC_URL").map_err(|_| eyre!("Missing env var SHARINGAN_RPC_URL".to_string()))?.as_str(),
             )?),
             Self::JsonRpcProvider(url) => Ok(url.clone()),
+            Self::JsonRpcProviders(urls) => Ok(urls[0].clone()),
             _ => Err(eyre!("Network {:?} is not supported for provider url", self)),
         }
     }
+
+    /// Returns all the provider urls of the network.
+    pub fn provider_urls(&self) -> Result<Vec<Url>, eyre::Error> {
+        match self {
+            Self::JsonRpcProviders(urls) => Ok(urls.clone()),
+            _ => Ok(vec![self.provider_url()?]),
+        }
+    }
 }
 
 #[derive(Default, Clone, Debug)]
@@ -94,6 +110,8 @@ impl KakarotRpcConfig {
     /// When using non-standard providers (i.e. not "katana", "madara", "mainnet"), the
     /// `STARKNET_NETWORK` environment variable should be set the URL of a JsonRpc
     /// starknet provider, e.g. https://starknet-goerli.g.alchemy.com/v2/some_key.
+    /// Multiple comma separated URLs can be provided in order to load balance the
+    /// requests between the providers.
     pub fn from_env() -> Result<Self, eyre::Error> {
         Ok(Self {
             network: var("STARKNET_NETWORK")?.into(),
@@ -169,3 +187,436 @@ impl SequencerGatewayProviderBuilder {
         self.0
     }
 }
+
+/// Configuration of the RPC loaded from a TOML file. Each value maps to an environment variable,
+/// which takes precedence over the file, so that a deployment can share a file and override some
+/// of its values. See `config.example.toml` for the list of values.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub network: NetworkConfig,
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub cache: CacheConfig,
+    pub rate_limit: RateLimitFileConfig,
+    pub logging: LoggingConfig,
+    pub gas_price_oracle: GasPriceOracleFileConfig,
+    pub pruning: PruningFileConfig,
+    pub features: FeaturesConfig,
+}
+
+/// Starknet network and Kakarot contracts.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Name or chain spec file of the Kakarot deployment (`KAKAROT_CHAIN`).
+    pub chain: Option<String>,
+    /// Network name or JSON-RPC URLs of the Starknet providers (`STARKNET_NETWORK`).
+    pub starknet_network: Option<Vec<String>>,
+    /// `KAKAROT_ADDRESS`
+    pub kakarot_address: Option<String>,
+    /// `UNINITIALIZED_ACCOUNT_CLASS_HASH`
+    pub uninitialized_account_class_hash: Option<String>,
+    /// `ACCOUNT_CONTRACT_CLASS_HASH`
+    pub account_contract_class_hash: Option<String>,
+    /// `STARKNET_PROOF_PROVIDER_URL`
+    pub proof_provider_url: Option<String>,
+    /// `MULTICALL3_ADDRESS`
+    pub multicall3_address: Option<String>,
+    /// `STARKNET_BROADCAST_TRANSACTIONS`
+    pub broadcast_transactions: Option<bool>,
+    /// `STARKNET_RETRY_MAX_RETRIES`
+    pub retry_max_retries: Option<u64>,
+    /// `STARKNET_RETRY_INITIAL_BACKOFF_MS`
+    pub retry_initial_backoff_ms: Option<u64>,
+    /// `STARKNET_RETRY_MAX_BACKOFF_MS`
+    pub retry_max_backoff_ms: Option<u64>,
+    /// `STARKNET_CIRCUIT_BREAKER_THRESHOLD`
+    pub circuit_breaker_threshold: Option<u64>,
+    /// `STARKNET_CIRCUIT_BREAKER_OPEN_DURATION`
+    pub circuit_breaker_open_duration: Option<u64>,
+    /// `STARKNET_HTTP_MAX_CONNECTIONS`
+    pub http_max_connections: Option<u64>,
+    /// `STARKNET_HTTP_MAX_IDLE_CONNECTIONS`
+    pub http_max_idle_connections: Option<u64>,
+    /// `STARKNET_HTTP_IDLE_TIMEOUT`
+    pub http_idle_timeout: Option<u64>,
+    /// `STARKNET_HTTP_KEEPALIVE`
+    pub http_keepalive: Option<u64>,
+    /// `STARKNET_HTTP2`
+    pub http2: Option<bool>,
+    /// `RELAYER_ACCOUNT_ADDRESSES`
+    pub relayer_account_addresses: Option<Vec<String>>,
+    /// `RELAYER_PRIVATE_KEYS`
+    pub relayer_private_keys: Option<Vec<String>>,
+    /// `RELAYER_MIN_BALANCE`, in wei of the Starknet native token.
+    pub relayer_min_balance: Option<String>,
+    /// `SIGNER_PRIVATE_KEYS`
+    pub signer_private_keys: Option<Vec<String>>,
+    /// `SIGNER_KEYSTORE_DIR`
+    pub signer_keystore_dir: Option<String>,
+    /// `SIGNER_KEYSTORE_PASSWORD`
+    pub signer_keystore_password: Option<String>,
+}
+
+/// RPC servers and limits of the requests.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// `KAKAROT_RPC_URL`
+    pub rpc_url: Option<String>,
+    /// `KAKAROT_AUTH_RPC_URL`
+    pub auth_rpc_url: Option<String>,
+    /// `RPC_AUTH_API_KEYS`
+    pub auth_api_keys: Option<Vec<String>>,
+    /// `RPC_AUTH_JWT_SECRET`
+    pub auth_jwt_secret: Option<String>,
+    /// `RPC_IPC_PATH`
+    pub ipc_path: Option<String>,
+    /// `RPC_HTTP_API`
+    pub http_api: Option<Vec<String>>,
+    /// `RPC_AUTHRPC_API`
+    pub authrpc_api: Option<Vec<String>>,
+    /// `RPC_IPC_API`
+    pub ipc_api: Option<Vec<String>>,
+    /// `RPC_MAX_CONNECTIONS`
+    pub max_connections: Option<u32>,
+    /// `RPC_MAX_SUBSCRIPTIONS_PER_CONNECTION`
+    pub max_subscriptions_per_connection: Option<u32>,
+    /// `RPC_MAX_BATCH_SIZE`
+    pub max_batch_size: Option<u32>,
+    /// `RPC_CORS_ALLOWED_ORIGINS`
+    pub cors_allowed_origins: Option<Vec<String>>,
+    /// `RPC_CORS_ALLOWED_METHODS`
+    pub cors_allowed_methods: Option<Vec<String>>,
+    /// `RPC_CORS_ALLOWED_HEADERS`
+    pub cors_allowed_headers: Option<Vec<String>>,
+    /// `RPC_ALLOWED_HOSTS`
+    pub allowed_hosts: Option<Vec<String>>,
+    /// `RPC_SHUTDOWN_TIMEOUT`
+    pub shutdown_timeout: Option<u64>,
+    /// `PROMETHEUS_PORT`
+    pub prometheus_port: Option<u16>,
+    /// `MAX_FELTS_IN_CALLDATA`
+    pub max_felts_in_calldata: Option<u64>,
+    /// `MAX_LOGS_BLOCK_RANGE`
+    pub max_logs_block_range: Option<u64>,
+    /// `MAX_LOGS_PER_RESPONSE`
+    pub max_logs_per_response: Option<u64>,
+    /// `RPC_MAX_RESPONSE_SIZE`
+    pub max_response_size: Option<u32>,
+    /// `OTS_MAX_PAGE_SIZE`
+    pub ots_max_page_size: Option<u64>,
+    /// `EOA_EMPTY_CODE`
+    pub eoa_empty_code: Option<bool>,
+    /// `READINESS_MAX_BLOCK_AGE`
+    pub readiness_max_block_age: Option<u64>,
+    /// `TRACE_BLOCK_TIMEOUT`
+    pub trace_block_timeout: Option<u64>,
+    /// `TRACE_BLOCK_MAX_CONCURRENCY`
+    pub trace_block_max_concurrency: Option<u64>,
+    /// `TRACE_CACHE_TTL`
+    pub trace_cache_ttl: Option<u64>,
+    /// `TRACE_CACHE_MAX_SIZE`
+    pub trace_cache_max_size: Option<u64>,
+    /// `RPC_TIMEOUT`
+    pub timeout: Option<u64>,
+    /// `RPC_TIMEOUT_METHODS`
+    pub timeout_methods: Option<BTreeMap<String, u64>>,
+}
+
+/// MongoDB database filled by the indexer.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// `MONGO_CONNECTION_STRING`
+    pub connection_string: Option<String>,
+    /// `MONGO_DATABASE_NAME`
+    pub name: Option<String>,
+}
+
+/// Caches of the immutable responses.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// `RESPONSE_CACHE_SIZE`
+    pub response_cache_size: Option<u64>,
+    /// `RESPONSE_CACHE_REDIS_URL`
+    pub redis_url: Option<String>,
+}
+
+/// Rate limits, in requests per second.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitFileConfig {
+    /// `RPC_RATE_LIMIT_PER_IP`
+    pub per_ip: Option<u32>,
+    /// `RPC_RATE_LIMIT_PER_METHOD`
+    pub per_method: Option<u32>,
+    /// `RPC_RATE_LIMIT_METHODS`
+    pub methods: Option<BTreeMap<String, u32>>,
+    /// `RPC_TRUSTED_PROXIES`
+    pub trusted_proxies: Option<Vec<String>>,
+}
+
+/// Logging of the RPC calls.
+#[derive(Default, Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// `RPC_LOG_SAMPLE_RATE`
+    pub sample_rate: Option<f64>,
+    /// `RPC_LOG_ERROR_SAMPLE_RATE`
+    pub error_sample_rate: Option<f64>,
+    /// `RPC_LOG_PARAMS`
+    pub log_params: Option<bool>,
+    /// `RPC_LOG_REDACTED_METHODS`
+    pub redacted_methods: Option<Vec<String>>,
+    /// `RPC_OPENRPC_SPEC`
+    pub openrpc_spec: Option<String>,
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT`
+    pub otlp_endpoint: Option<String>,
+    /// `OTEL_SERVICE_NAME`
+    pub otel_service_name: Option<String>,
+    /// `OTEL_TRACES_SAMPLER_ARG`
+    pub otel_sample_ratio: Option<f64>,
+}
+
+/// Suggestion of the gas price and of the priority fee from the recent blocks.
+#[derive(Default, Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GasPriceOracleFileConfig {
+    /// `GAS_PRICE_ORACLE_BLOCKS`
+    pub blocks: Option<u64>,
+    /// `GAS_PRICE_ORACLE_PERCENTILE`
+    pub percentile: Option<f64>,
+}
+
+/// Retention policies of the database records, in number of most recent blocks.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PruningFileConfig {
+    /// `PRUNE_INTERVAL`
+    pub interval: Option<u64>,
+    /// `PRUNE_LOGS_BLOCKS`
+    pub logs_blocks: Option<u64>,
+    /// `PRUNE_TRACES_BLOCKS`
+    pub traces_blocks: Option<u64>,
+}
+
+/// Optional services of the RPC.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeaturesConfig {
+    /// Runs the built-in indexer, as the `--index` flag.
+    pub index: bool,
+    /// Serves the Anvil compatible dev API backed by Katana, as the `--dev` flag.
+    pub dev: bool,
+    /// Serves the `kakarot_requestFunds` faucet, as the `--faucet` flag.
+    pub faucet: bool,
+    /// Serves the GraphQL queries on `/graphql`, as the `--graphql` flag.
+    pub graphql: bool,
+    /// `FAUCET_PRIVATE_KEY`
+    pub faucet_private_key: Option<String>,
+    /// `FAUCET_AMOUNT`, in wei.
+    pub faucet_amount: Option<String>,
+    /// `FAUCET_ADDRESS_INTERVAL`
+    pub faucet_address_interval: Option<u64>,
+    /// `FAUCET_IP_INTERVAL`
+    pub faucet_ip_interval: Option<u64>,
+    /// Serves the operator's signers without authentication, as the `--allow-insecure-unlock` flag.
+    pub allow_insecure_unlock: bool,
+    /// Proxies the `starknet_` methods to the Starknet provider, as the `--starknet-passthrough` flag.
+    pub starknet_passthrough: bool,
+    /// `STARKNET_PASSTHROUGH_METHODS`
+    pub starknet_passthrough_methods: Option<Vec<String>>,
+    /// `INDEXER_POLL_INTERVAL`
+    pub indexer_poll_interval: Option<u64>,
+    /// `INDEXER_STARTING_BLOCK`
+    pub indexer_starting_block: Option<u64>,
+    /// `INDEXER_MAX_REORG_DEPTH`
+    pub indexer_max_reorg_depth: Option<u64>,
+    /// `RETRY_TX_INTERVAL`
+    pub retry_tx_interval: Option<u64>,
+    /// `TRACE_BACKFILL_INTERVAL`
+    pub trace_backfill_interval: Option<u64>,
+}
+
+impl Config {
+    /// Loads the configuration from the file given by the `--config` flag or by the
+    /// `KAKAROT_RPC_CONFIG` environment variable. Returns the default configuration, which
+    /// leaves the environment untouched, if neither is set.
+    pub fn load(args: impl IntoIterator<Item = String>) -> Result<Self, eyre::Error> {
+        let mut args = args.into_iter();
+        let mut path = var("KAKAROT_RPC_CONFIG").ok().filter(|path| !path.trim().is_empty());
+        while let Some(arg) = args.next() {
+            if let Some(value) = arg.strip_prefix("--config=") {
+                path = Some(value.to_string());
+            } else if arg == "--config" {
+                path = Some(args.next().ok_or_else(|| eyre!("Missing value for --config"))?);
+            }
+        }
+        path.map_or_else(|| Ok(Self::default()), Self::from_file)
+    }
+
+    /// Reads the configuration from the TOML file at the given path.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, eyre::Error> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| eyre!("Failed to read config file {}: {err}", path.display()))?;
+        toml::from_str(&content).map_err(|err| eyre!("Invalid config file {}: {err}", path.display()))
+    }
+
+    /// Returns the environment variables corresponding to the values set in the configuration.
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let Self { network, server, database, cache, rate_limit, logging, gas_price_oracle, pruning, features } = self;
+        let list = |values: &Option<Vec<String>>| values.as_ref().map(|values| values.join(","));
+        let number = |value: Option<u64>| value.map(|value| value.to_string());
+
+        [
+            ("KAKAROT_CHAIN", network.chain.clone()),
+            ("STARKNET_NETWORK", list(&network.starknet_network)),
+            ("KAKAROT_ADDRESS", network.kakarot_address.clone()),
+            ("UNINITIALIZED_ACCOUNT_CLASS_HASH", network.uninitialized_account_class_hash.clone()),
+            ("ACCOUNT_CONTRACT_CLASS_HASH", network.account_contract_class_hash.clone()),
+            ("STARKNET_PROOF_PROVIDER_URL", network.proof_provider_url.clone()),
+            ("MULTICALL3_ADDRESS", network.multicall3_address.clone()),
+            ("STARKNET_BROADCAST_TRANSACTIONS", network.broadcast_transactions.map(|broadcast| broadcast.to_string())),
+            ("STARKNET_RETRY_MAX_RETRIES", number(network.retry_max_retries)),
+            ("STARKNET_RETRY_INITIAL_BACKOFF_MS", number(network.retry_initial_backoff_ms)),
+            ("STARKNET_RETRY_MAX_BACKOFF_MS", number(network.retry_max_backoff_ms)),
+            ("STARKNET_CIRCUIT_BREAKER_THRESHOLD", number(network.circuit_breaker_threshold)),
+            ("STARKNET_CIRCUIT_BREAKER_OPEN_DURATION", number(network.circuit_breaker_open_duration)),
+            ("STARKNET_HTTP_MAX_CONNECTIONS", number(network.http_max_connections)),
+            ("STARKNET_HTTP_MAX_IDLE_CONNECTIONS", number(network.http_max_idle_connections)),
+            ("STARKNET_HTTP_IDLE_TIMEOUT", number(network.http_idle_timeout)),
+            ("STARKNET_HTTP_KEEPALIVE", number(network.http_keepalive)),
+            ("STARKNET_HTTP2", network.http2.map(|http2| http2.to_string())),
+            ("RELAYER_ACCOUNT_ADDRESSES", list(&network.relayer_account_addresses)),
+            ("RELAYER_PRIVATE_KEYS", list(&network.relayer_private_keys)),
+            ("RELAYER_MIN_BALANCE", network.relayer_min_balance.clone()),
+            ("SIGNER_PRIVATE_KEYS", list(&network.signer_private_keys)),
+            ("SIGNER_KEYSTORE_DIR", network.signer_keystore_dir.clone()),
+            ("SIGNER_KEYSTORE_PASSWORD", network.signer_keystore_password.clone()),
+            ("KAKAROT_RPC_URL", server.rpc_url.clone()),
+            ("KAKAROT_AUTH_RPC_URL", server.auth_rpc_url.clone()),
+            ("RPC_AUTH_API_KEYS", list(&server.auth_api_keys)),
+            ("RPC_AUTH_JWT_SECRET", server.auth_jwt_secret.clone()),
+            ("RPC_IPC_PATH", server.ipc_path.clone()),
+            ("RPC_HTTP_API", list(&server.http_api)),
+            ("RPC_AUTHRPC_API", list(&server.authrpc_api)),
+            ("RPC_IPC_API", list(&server.ipc_api)),
+            ("RPC_MAX_CONNECTIONS", number(server.max_connections.map(Into::into))),
+            ("RPC_MAX_SUBSCRIPTIONS_PER_CONNECTION", number(server.max_subscriptions_per_connection.map(Into::into))),
+            ("RPC_MAX_BATCH_SIZE", number(server.max_batch_size.map(Into::into))),
+            ("RPC_CORS_ALLOWED_ORIGINS", list(&server.cors_allowed_origins)),
+            ("RPC_CORS_ALLOWED_METHODS", list(&server.cors_allowed_methods)),
+            ("RPC_CORS_ALLOWED_HEADERS", list(&server.cors_allowed_headers)),
+            ("RPC_ALLOWED_HOSTS", list(&server.allowed_hosts)),
+            ("RPC_SHUTDOWN_TIMEOUT", number(server.shutdown_timeout)),
+            ("PROMETHEUS_PORT", number(server.prometheus_port.map(Into::into))),
+            ("MAX_FELTS_IN_CALLDATA", number(server.max_felts_in_calldata)),
+            ("MAX_LOGS_BLOCK_RANGE", number(server.max_logs_block_range)),
+            ("MAX_LOGS_PER_RESPONSE", number(server.max_logs_per_response)),
+            ("RPC_MAX_RESPONSE_SIZE", number(server.max_response_size.map(Into::into))),
+            ("OTS_MAX_PAGE_SIZE", number(server.ots_max_page_size)),
+            ("EOA_EMPTY_CODE", server.eoa_empty_code.map(|eoa_empty_code| eoa_empty_code.to_string())),
+            ("READINESS_MAX_BLOCK_AGE", number(server.readiness_max_block_age)),
+            ("TRACE_BLOCK_TIMEOUT", number(server.trace_block_timeout)),
+            ("TRACE_BLOCK_MAX_CONCURRENCY", number(server.trace_block_max_concurrency)),
+            ("TRACE_CACHE_TTL", number(server.trace_cache_ttl)),
+            ("TRACE_CACHE_MAX_SIZE", number(server.trace_cache_max_size)),
+            ("RPC_TIMEOUT", number(server.timeout)),
+            (
+                "RPC_TIMEOUT_METHODS",
+                server.timeout_methods.as_ref().map(|methods| {
+                    methods.iter().map(|(method, timeout)| format!("{method}={timeout}")).collect::<Vec<_>>().join(",")
+                }),
+            ),
+            ("MONGO_CONNECTION_STRING", database.connection_string.clone()),
+            ("MONGO_DATABASE_NAME", database.name.clone()),
+            ("RESPONSE_CACHE_SIZE", number(cache.response_cache_size)),
+            ("RESPONSE_CACHE_REDIS_URL", cache.redis_url.clone()),
+            ("RPC_RATE_LIMIT_PER_IP", number(rate_limit.per_ip.map(Into::into))),
+            ("RPC_RATE_LIMIT_PER_METHOD", number(rate_limit.per_method.map(Into::into))),
+            (
+                "RPC_RATE_LIMIT_METHODS",
+                rate_limit.methods.as_ref().map(|methods| {
+                    methods.iter().map(|(method, limit)| format!("{method}={limit}")).collect::<Vec<_>>().join(",")
+                }),
+            ),
+            ("RPC_TRUSTED_PROXIES", list(&rate_limit.trusted_proxies)),
+            ("RPC_LOG_SAMPLE_RATE", logging.sample_rate.map(|rate| rate.to_string())),
+            ("RPC_LOG_ERROR_SAMPLE_RATE", logging.error_sample_rate.map(|rate| rate.to_string())),
+            ("RPC_LOG_PARAMS", logging.log_params.map(|log_params| log_params.to_string())),
+            ("RPC_LOG_REDACTED_METHODS", list(&logging.redacted_methods)),
+            ("RPC_OPENRPC_SPEC", logging.openrpc_spec.clone()),
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", logging.otlp_endpoint.clone()),
+            ("OTEL_SERVICE_NAME", logging.otel_service_name.clone()),
+            ("OTEL_TRACES_SAMPLER_ARG", logging.otel_sample_ratio.map(|ratio| ratio.to_string())),
+            ("GAS_PRICE_ORACLE_BLOCKS", number(gas_price_oracle.blocks)),
+            ("GAS_PRICE_ORACLE_PERCENTILE", gas_price_oracle.percentile.map(|percentile| percentile.to_string())),
+            ("PRUNE_INTERVAL", number(pruning.interval)),
+            ("PRUNE_LOGS_BLOCKS", number(pruning.logs_blocks)),
+            ("PRUNE_TRACES_BLOCKS", number(pruning.traces_blocks)),
+            ("FAUCET_PRIVATE_KEY", features.faucet_private_key.clone()),
+            ("FAUCET_AMOUNT", features.faucet_amount.clone()),
+            ("FAUCET_ADDRESS_INTERVAL", number(features.faucet_address_interval)),
+            ("FAUCET_IP_INTERVAL", number(features.faucet_ip_interval)),
+            ("STARKNET_PASSTHROUGH_METHODS", list(&features.starknet_passthrough_methods)),
+            ("INDEXER_POLL_INTERVAL", number(features.indexer_poll_interval)),
+            ("INDEXER_STARTING_BLOCK", number(features.indexer_starting_block)),
+            ("INDEXER_MAX_REORG_DEPTH", number(features.indexer_max_reorg_depth)),
+            ("RETRY_TX_INTERVAL", number(features.retry_tx_interval)),
+            ("TRACE_BACKFILL_INTERVAL", number(features.trace_backfill_interval)),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect()
+    }
+
+    /// Sets the environment variables corresponding to the values of the configuration, unless
+    /// they are already set. Must be called before the configuration is read from the environment.
+    pub fn apply_to_env(&self) {
+        for (name, value) in self.env_vars() {
+            // Empty variables, as left by the .env.example file, are considered unset
+            if var(name).map_or(true, |value| value.trim().is_empty()) {
+                std::env::set_var(name, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_config() {
+        // Given
+        let config: Config = toml::from_str(include_str!("../config.example.toml")).expect("Invalid example config");
+
+        // When
+        let env_vars = config.env_vars().into_iter().collect::<BTreeMap<_, _>>();
+
+        // Then
+        assert_eq!(env_vars["STARKNET_NETWORK"], "http://0.0.0.0:5050");
+        assert_eq!(env_vars["KAKAROT_RPC_URL"], "127.0.0.1:3030");
+        assert_eq!(env_vars["RPC_MAX_CONNECTIONS"], "100");
+        assert_eq!(env_vars["RPC_RATE_LIMIT_METHODS"], "eth_call=50,eth_getLogs=10");
+        assert_eq!(env_vars["RESPONSE_CACHE_SIZE"], "10000");
+        assert_eq!(env_vars["RPC_LOG_ERROR_SAMPLE_RATE"], "1");
+        assert_eq!(env_vars["GAS_PRICE_ORACLE_PERCENTILE"], "60");
+        assert_eq!(env_vars["PRUNE_LOGS_BLOCKS"], "0");
+        assert!(!config.features.index);
+        assert!(!config.features.dev);
+        assert!(!config.features.faucet);
+        assert!(!config.features.allow_insecure_unlock);
+        assert_eq!(env_vars["FAUCET_AMOUNT"], "1000000000000000000");
+        assert!(!env_vars.contains_key("RPC_AUTH_JWT_SECRET"));
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        assert!(toml::from_str::<Config>("[server]\nunknown = 1").is_err());
+    }
+}
@@ -0,0 +1,607 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use eyre::Result;
+use lazy_static::lazy_static;
+use reth_primitives::{Address, Bytes, B256, U256};
+use reth_rpc_types::{BlockHashOrNumber, RichBlock, TransactionReceipt};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::cache_backend::CacheBackend;
+
+lazy_static! {
+    // Maximum number of entries in each of the response caches. Setting it to 0 disables the caches.
+    pub static ref RESPONSE_CACHE_SIZE: usize = usize::from_str(
+        &std::env::var("RESPONSE_CACHE_SIZE").unwrap_or_else(|_| "10000".to_string())
+    ).expect("failing to parse RESPONSE_CACHE_SIZE");
+}
+
+/// Ratio between the capacity of the response caches and the one of the cache of the receipts of
+/// the blocks, whose entries hold the receipts of a whole block.
+const BLOCK_RECEIPTS_CACHE_RATIO: usize = 10;
+
+/// A thread safe least recently used cache, holding at most `capacity` entries.
+pub struct LruCache<K, V> {
+    capacity: usize,
+    inner: Mutex<LruCacheInner<K, V>>,
+}
+
+struct LruCacheInner<K, V> {
+    /// The cached values along with the tick of their last access.
+    entries: HashMap<K, (V, u64)>,
+    /// The keys ordered by their last access.
+    order: BTreeMap<u64, K>,
+    /// Monotonic counter incremented on each access.
+    tick: u64,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    /// Create a new [`LruCache`]. A capacity of 0 disables the cache.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, inner: Mutex::new(LruCacheInner { entries: HashMap::new(), order: BTreeMap::new(), tick: 0 }) }
+    }
+
+    /// Returns a copy of the value for the key, marking it as the most recently used.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut inner = self.inner.lock().expect("Failed to lock cache");
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        let (value, last_access) = inner.entries.get_mut(key)?;
+        let value = value.clone();
+        let previous_access = std::mem::replace(last_access, tick);
+
+        inner.order.remove(&previous_access);
+        inner.order.insert(tick, key.clone());
+        Some(value)
+    }
+
+    /// Inserts the value for the key, evicting the least recently used entry if the cache is full.
+    pub fn insert(&self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().expect("Failed to lock cache");
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if let Some((_, previous_access)) = inner.entries.insert(key.clone(), (value, tick)) {
+            inner.order.remove(&previous_access);
+        }
+        inner.order.insert(tick, key);
+
+        if inner.entries.len() > self.capacity {
+            if let Some((_, evicted)) = inner.order.pop_first() {
+                inner.entries.remove(&evicted);
+            }
+        }
+    }
+
+    /// Returns the number of entries in the cache.
+    pub fn len(&self) -> usize {
+        self.inner.lock().expect("Failed to lock cache").entries.len()
+    }
+
+    /// Returns true if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the entries of the cache and returns their number.
+    pub fn clear(&self) -> usize {
+        let mut inner = self.inner.lock().expect("Failed to lock cache");
+        let len = inner.entries.len();
+        inner.entries.clear();
+        inner.order.clear();
+        len
+    }
+
+    /// Removes the entries for which the predicate returns false and returns their number.
+    pub fn retain(&self, mut predicate: impl FnMut(&K, &V) -> bool) -> usize {
+        let mut inner = self.inner.lock().expect("Failed to lock cache");
+        let LruCacheInner { entries, order, .. } = &mut *inner;
+        let len = entries.len();
+        entries.retain(|key, (value, last_access)| {
+            let retained = predicate(key, value);
+            if !retained {
+                order.remove(last_access);
+            }
+            retained
+        });
+        len - entries.len()
+    }
+}
+
+impl<K, V> fmt::Debug for LruCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LruCache").field("capacity", &self.capacity).finish_non_exhaustive()
+    }
+}
+
+/// A response which is also cached in the [`CacheBackend`] shared by the instances of the RPC.
+pub trait SharedResponse: Clone + Serialize + DeserializeOwned {
+    /// Key of the response in the cache.
+    type Key: Hash + Eq + Clone;
+
+    /// Returns the key of the response in the backend, unique among the responses of its type.
+    fn backend_key(key: &Self::Key) -> String;
+
+    /// Returns the number of the block of the response, whose reorg invalidates it.
+    fn block_number(&self, key: &Self::Key) -> Option<u64>;
+}
+
+impl SharedResponse for RichBlock {
+    type Key = (BlockHashOrNumber, bool);
+
+    fn backend_key((block, full): &Self::Key) -> String {
+        match block {
+            BlockHashOrNumber::Hash(hash) => format!("{hash:#x}:{full}"),
+            BlockHashOrNumber::Number(number) => format!("{number}:{full}"),
+        }
+    }
+
+    fn block_number(&self, _key: &Self::Key) -> Option<u64> {
+        self.header.number
+    }
+}
+
+impl SharedResponse for TransactionReceipt {
+    type Key = B256;
+
+    fn backend_key(hash: &Self::Key) -> String {
+        format!("{hash:#x}")
+    }
+
+    fn block_number(&self, _key: &Self::Key) -> Option<u64> {
+        self.block_number
+    }
+}
+
+impl SharedResponse for Bytes {
+    type Key = (Address, u64);
+
+    fn backend_key((address, number): &Self::Key) -> String {
+        format!("{address:#x}:{number}")
+    }
+
+    fn block_number(&self, (_, number): &Self::Key) -> Option<u64> {
+        Some(*number)
+    }
+}
+
+/// A cache of responses held in a local [`LruCache`], in front of the optional [`CacheBackend`]
+/// shared by the instances of the RPC. The responses read from the backend are kept in the local
+/// cache. The failures of the backend are logged and handled as cache misses.
+pub struct SharedCache<V: SharedResponse> {
+    local: LruCache<V::Key, V>,
+    /// Prefix of the keys of the responses in the backend.
+    namespace: &'static str,
+    backend: Option<Arc<dyn CacheBackend>>,
+}
+
+impl<V: SharedResponse> SharedCache<V> {
+    /// Create a new [`SharedCache`] holding at most `capacity` entries locally. A capacity of 0
+    /// disables the cache, including its backend.
+    pub fn new(capacity: usize, namespace: &'static str, backend: Option<Arc<dyn CacheBackend>>) -> Self {
+        let backend = backend.filter(|_| capacity > 0);
+        Self { local: LruCache::new(capacity), namespace, backend }
+    }
+
+    fn backend_key(&self, key: &V::Key) -> String {
+        format!("{}:{}", self.namespace, V::backend_key(key))
+    }
+
+    /// Returns the response for the key, from the local cache or else from the backend.
+    pub async fn get(&self, key: &V::Key) -> Option<V> {
+        if let Some(value) = self.local.get(key) {
+            return Some(value);
+        }
+
+        let backend = self.backend.as_ref()?;
+        let value = match backend.get(&self.backend_key(key)).await {
+            Ok(value) => value?,
+            Err(err) => {
+                tracing::warn!("Failed to read the cached {} from the backend: {err}", self.namespace);
+                return None;
+            }
+        };
+        match serde_json::from_slice::<V>(&value) {
+            Ok(value) => {
+                self.local.insert(key.clone(), value.clone());
+                Some(value)
+            }
+            Err(err) => {
+                tracing::warn!("Failed to decode the cached {} from the backend: {err}", self.namespace);
+                None
+            }
+        }
+    }
+
+    /// Inserts the response for the key, in the local cache and in the backend.
+    pub async fn insert(&self, key: V::Key, value: V) {
+        if let (Some(backend), Some(block_number)) = (&self.backend, value.block_number(&key)) {
+            let stored = match serde_json::to_vec(&value) {
+                Ok(bytes) => backend.set(&self.backend_key(&key), bytes, block_number).await,
+                Err(err) => Err(err.into()),
+            };
+            if let Err(err) = stored {
+                tracing::warn!("Failed to write the cached {} to the backend: {err}", self.namespace);
+            }
+        }
+        self.local.insert(key, value);
+    }
+
+    /// Removes all the entries of the local cache and returns their number.
+    pub fn clear(&self) -> usize {
+        self.local.clear()
+    }
+
+    /// Removes the entries of the local cache for which the predicate returns false and returns
+    /// their number.
+    pub fn retain(&self, predicate: impl FnMut(&V::Key, &V) -> bool) -> usize {
+        self.local.retain(predicate)
+    }
+}
+
+impl<V: SharedResponse> fmt::Debug for SharedCache<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedCache")
+            .field("local", &self.local)
+            .field("namespace", &self.namespace)
+            .field("backend", &self.backend)
+            .finish()
+    }
+}
+
+/// Caches for the responses which can't change once they are returned:
+/// sealed blocks, receipts of mined transactions and sealed blocks, code and token URIs at a
+/// sealed block, code by code hash and the translation between the numbers and hashes of the
+/// canonical blocks.
+/// The blocks, the receipts and the code are also stored in the optional [`CacheBackend`], shared
+/// by the instances of the RPC.
+/// The responses of the blocks removed by a reorg are dropped by [`ResponseCache::invalidate_from`].
+#[derive(Debug)]
+pub struct ResponseCache {
+    /// Blocks by hash or number, with full transactions or hashes only.
+    pub blocks: SharedCache<RichBlock>,
+    /// Receipts by transaction hash.
+    pub receipts: SharedCache<TransactionReceipt>,
+    /// Receipts of the sealed blocks by number, with their cumulative gas used and logs bloom.
+    pub block_receipts: LruCache<u64, Arc<Vec<TransactionReceipt>>>,
+    /// Code by address and block number.
+    pub code: SharedCache<Bytes>,
+    /// Code by code hash, shared by the accounts deployed with the same bytecode.
+    pub code_by_hash: LruCache<B256, Bytes>,
+    /// Addresses whose Kakarot account is deployed.
+    pub deployed_accounts: LruCache<Address, ()>,
+    /// Timestamps of the sealed blocks by number, searched to find a block by timestamp.
+    pub block_timestamps: LruCache<u64, u64>,
+    /// Metadata URIs of the ERC721 and ERC1155 tokens by contract, token id and block number.
+    pub token_uris: LruCache<(Address, U256, u64), String>,
+    /// Hashes of the sealed blocks of the canonical chain by number.
+    block_hashes: LruCache<u64, B256>,
+    /// Numbers of the sealed blocks of the canonical chain by hash.
+    block_numbers: LruCache<B256, u64>,
+    /// Backend shared by the instances of the RPC, if any.
+    backend: Option<Arc<dyn CacheBackend>>,
+}
+
+impl ResponseCache {
+    /// Create a new [`ResponseCache`], where each cache holds at most `capacity` entries, except
+    /// the cache of the receipts of the blocks which holds [`BLOCK_RECEIPTS_CACHE_RATIO`] times
+    /// fewer entries.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_backend(capacity, None)
+    }
+
+    /// Create a new [`ResponseCache`] like [`ResponseCache::new`], storing the blocks, the receipts
+    /// and the code in the given backend as well.
+    pub fn with_backend(capacity: usize, backend: Option<Arc<dyn CacheBackend>>) -> Self {
+        Self {
+            blocks: SharedCache::new(capacity, "blocks", backend.clone()),
+            receipts: SharedCache::new(capacity, "receipts", backend.clone()),
+            block_receipts: LruCache::new(capacity.div_ceil(BLOCK_RECEIPTS_CACHE_RATIO)),
+            code: SharedCache::new(capacity, "code", backend.clone()),
+            code_by_hash: LruCache::new(capacity),
+            deployed_accounts: LruCache::new(capacity),
+            block_timestamps: LruCache::new(capacity),
+            token_uris: LruCache::new(capacity),
+            block_hashes: LruCache::new(capacity),
+            block_numbers: LruCache::new(capacity),
+            backend: backend.filter(|_| capacity > 0),
+        }
+    }
+
+    /// Create a new [`ResponseCache`] holding at most `RESPONSE_CACHE_SIZE` entries in each cache.
+    /// The responses are also stored in the Redis server at `RESPONSE_CACHE_REDIS_URL`, if set,
+    /// under keys prefixed by the chain id. Redis is only supported with the `redis` feature.
+    #[cfg_attr(not(feature = "redis"), allow(unused_variables))]
+    pub async fn from_env(chain_id: u64) -> Result<Self> {
+        let backend: Option<Arc<dyn CacheBackend>> =
+            match std::env::var("RESPONSE_CACHE_REDIS_URL").ok().filter(|url| !url.trim().is_empty()) {
+                #[cfg(feature = "redis")]
+                Some(url) => Some(Arc::new(
+                    super::cache_backend::RedisBackend::new(url.trim(), format!("kakarot:{chain_id}")).await?,
+                )),
+                #[cfg(not(feature = "redis"))]
+                Some(_) => return Err(eyre::eyre!("RESPONSE_CACHE_REDIS_URL requires the redis feature")),
+                None => None,
+            };
+        Ok(Self::with_backend(*RESPONSE_CACHE_SIZE, backend))
+    }
+
+    /// Returns the hash of the sealed block of the canonical chain with the given number.
+    pub fn block_hash(&self, block_number: u64) -> Option<B256> {
+        self.block_hashes.get(&block_number)
+    }
+
+    /// Returns the number of the sealed block of the canonical chain with the given hash.
+    pub fn block_number(&self, block_hash: &B256) -> Option<u64> {
+        self.block_numbers.get(block_hash)
+    }
+
+    /// Caches the hash and the number of a sealed block of the canonical chain, read from its
+    /// indexed header. The hash previously cached for the number is replaced, so that both
+    /// translations stay consistent.
+    pub fn insert_block_hash(&self, block_number: u64, block_hash: B256) {
+        if let Some(previous) = self.block_hashes.get(&block_number) {
+            if previous != block_hash {
+                self.block_numbers.retain(|hash, _| *hash != previous);
+            }
+        }
+        self.block_hashes.insert(block_number, block_hash);
+        self.block_numbers.insert(block_hash, block_number);
+    }
+
+    /// Removes all the locally cached responses and returns their number. The responses stored in
+    /// the backend are kept, since they are shared with the other instances of the RPC.
+    pub fn clear(&self) -> usize {
+        self.blocks.clear()
+            + self.receipts.clear()
+            + self.block_receipts.clear()
+            + self.code.clear()
+            + self.code_by_hash.clear()
+            + self.deployed_accounts.clear()
+            + self.block_timestamps.clear()
+            + self.token_uris.clear()
+            + self.block_hashes.clear()
+            + self.block_numbers.clear()
+    }
+
+    /// Removes the cached responses of the blocks starting at `block_number`, after they were
+    /// removed from the chain by a reorg, and returns their number. The deployed accounts are
+    /// all removed, since their deployment may have been reorged out.
+    pub async fn invalidate_from(&self, block_number: u64) -> usize {
+        let is_reorged = |number: Option<u64>| number.map_or(true, |number| number >= block_number);
+        let removed = match &self.backend {
+            Some(backend) => backend.invalidate_from(block_number).await.unwrap_or_else(|err| {
+                tracing::warn!("Failed to invalidate the cached responses of the backend: {err}");
+                0
+            }),
+            None => 0,
+        };
+        removed
+            + self.blocks.retain(|_, block| !is_reorged(block.header.number))
+            + self.receipts.retain(|_, receipt| !is_reorged(receipt.block_number))
+            + self.block_receipts.retain(|number, _| *number < block_number)
+            + self.code.retain(|(_, number), _| *number < block_number)
+            + self.deployed_accounts.clear()
+            + self.block_timestamps.retain(|number, _| *number < block_number)
+            + self.token_uris.retain(|(_, _, number), _| *number < block_number)
+            + self.block_hashes.retain(|number, _| *number < block_number)
+            + self.block_numbers.retain(|_, number| *number < block_number)
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(*RESPONSE_CACHE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_rpc_types::{Block, Header};
+
+    #[test]
+    fn test_lru_cache_evicts_least_recently_used() {
+        // Given
+        let cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+
+        // When
+        // Access 1 so that 2 becomes the least recently used entry
+        assert_eq!(cache.get(&1), Some("one"));
+        cache.insert(3, "three");
+
+        // Then
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some("three"));
+    }
+
+    #[test]
+    fn test_lru_cache_overwrite() {
+        // Given
+        let cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+
+        // When
+        cache.insert(1, "uno");
+        cache.insert(3, "three");
+
+        // Then
+        assert_eq!(cache.get(&1), Some("uno"));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some("three"));
+    }
+
+    #[test]
+    fn test_lru_cache_disabled() {
+        // Given
+        let cache = LruCache::new(0);
+
+        // When
+        cache.insert(1, "one");
+
+        // Then
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[test]
+    fn test_lru_cache_clear() {
+        // Given
+        let cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+
+        // When
+        let cleared = cache.clear();
+        cache.insert(3, "three");
+
+        // Then
+        assert_eq!(cleared, 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&3), Some("three"));
+    }
+
+    #[test]
+    fn test_lru_cache_retain() {
+        // Given
+        let cache = LruCache::new(3);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        cache.insert(3, "three");
+
+        // When
+        let removed = cache.retain(|key, _| *key < 2);
+        cache.insert(4, "four");
+        cache.insert(5, "five");
+
+        // Then
+        assert_eq!(removed, 2);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&4), Some("four"));
+    }
+
+    #[tokio::test]
+    async fn test_response_cache_invalidate_from() {
+        // Given
+        let cache = ResponseCache::new(30);
+        for number in 1..=3 {
+            let block = Block { header: Header { number: Some(number), ..Default::default() }, ..Default::default() };
+            cache.blocks.insert((BlockHashOrNumber::Number(number), false), block.into()).await;
+            cache.block_timestamps.insert(number, number);
+            cache.code.insert((Address::ZERO, number), Bytes::default()).await;
+            cache.block_receipts.insert(number, Arc::new(Vec::new()));
+        }
+        cache.deployed_accounts.insert(Address::ZERO, ());
+
+        // When
+        let removed = cache.invalidate_from(2).await;
+
+        // Then
+        assert_eq!(removed, 9);
+        assert!(cache.blocks.get(&(BlockHashOrNumber::Number(1), false)).await.is_some());
+        assert!(cache.blocks.get(&(BlockHashOrNumber::Number(2), false)).await.is_none());
+        assert_eq!(cache.block_timestamps.get(&1), Some(1));
+        assert_eq!(cache.block_timestamps.get(&3), None);
+        assert!(cache.code.get(&(Address::ZERO, 3)).await.is_none());
+        assert!(cache.block_receipts.get(&1).is_some());
+        assert!(cache.block_receipts.get(&2).is_none());
+        assert!(cache.deployed_accounts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_response_cache_block_hashes() {
+        // Given
+        let cache = ResponseCache::new(10);
+        for number in 1..=3 {
+            cache.insert_block_hash(number, B256::with_last_byte(number as u8));
+        }
+
+        // When
+        // Block 3 is replaced by a reorg, then block 2 is removed
+        cache.insert_block_hash(3, B256::with_last_byte(4));
+        let removed = cache.invalidate_from(2).await;
+
+        // Then
+        assert_eq!(removed, 4);
+        assert_eq!(cache.block_hash(1), Some(B256::with_last_byte(1)));
+        assert_eq!(cache.block_number(&B256::with_last_byte(1)), Some(1));
+        assert_eq!(cache.block_hash(3), None);
+        assert_eq!(cache.block_number(&B256::with_last_byte(3)), None);
+        assert_eq!(cache.block_number(&B256::with_last_byte(4)), None);
+    }
+
+    /// A backend holding the values in memory, along with their block number.
+    #[derive(Debug, Default)]
+    struct MemoryBackend {
+        values: Mutex<HashMap<String, (Vec<u8>, u64)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CacheBackend for MemoryBackend {
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            Ok(self.values.lock().unwrap().get(key).map(|(value, _)| value.clone()))
+        }
+
+        async fn set(&self, key: &str, value: Vec<u8>, block_number: u64) -> Result<()> {
+            self.values.lock().unwrap().insert(key.to_string(), (value, block_number));
+            Ok(())
+        }
+
+        async fn invalidate_from(&self, block_number: u64) -> Result<usize> {
+            let mut values = self.values.lock().unwrap();
+            let len = values.len();
+            values.retain(|_, (_, number)| *number < block_number);
+            Ok(len - values.len())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_response_cache_shared_backend() {
+        // Given
+        let backend: Arc<dyn CacheBackend> = Arc::new(MemoryBackend::default());
+        let cache = ResponseCache::with_backend(10, Some(backend.clone()));
+        let other_cache = ResponseCache::with_backend(10, Some(backend.clone()));
+        for number in 1..=3 {
+            let block = Block { header: Header { number: Some(number), ..Default::default() }, ..Default::default() };
+            cache.blocks.insert((BlockHashOrNumber::Number(number), false), block.into()).await;
+        }
+        cache.code.insert((Address::ZERO, 3), Bytes::from_static(&[1])).await;
+
+        // When
+        let block = other_cache.blocks.get(&(BlockHashOrNumber::Number(1), false)).await;
+        let code = other_cache.code.get(&(Address::ZERO, 3)).await;
+        let removed = cache.invalidate_from(2).await;
+
+        // Then
+        assert_eq!(block.and_then(|block| block.header.number), Some(1));
+        assert_eq!(code, Some(Bytes::from_static(&[1])));
+        // The blocks 2 and 3 and the code are removed from the backend and from the local cache
+        assert_eq!(removed, 6);
+        assert!(ResponseCache::with_backend(10, Some(backend.clone()))
+            .blocks
+            .get(&(BlockHashOrNumber::Number(1), false))
+            .await
+            .is_some());
+        assert!(other_cache.blocks.get(&(BlockHashOrNumber::Number(2), false)).await.is_none());
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use eyre::Result;
+
+/// Backend storing the cached responses outside of the process, so that they are shared by the
+/// instances of the RPC. Each value belongs to a block, whose reorg invalidates it.
+#[async_trait]
+pub trait CacheBackend: fmt::Debug + Send + Sync {
+    /// Returns the value stored for the key, if any.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Stores the value for the key, belonging to the block with the given number.
+    async fn set(&self, key: &str, value: Vec<u8>, block_number: u64) -> Result<()>;
+
+    /// Removes the values of the blocks starting at `block_number` and returns their number.
+    async fn invalidate_from(&self, block_number: u64) -> Result<usize>;
+}
+
+#[cfg(feature = "redis")]
+pub use self::redis_backend::RedisBackend;
+
+#[cfg(feature = "redis")]
+mod redis_backend {
+    use super::*;
+    use redis::aio::ConnectionManager;
+
+    /// A [`CacheBackend`] storing the values in Redis. The keys of the values are indexed by block
+    /// number in a sorted set, so that the values of the reorged blocks are removed without
+    /// scanning the keys. The server should evict the least recently used keys once it is full.
+    #[derive(Clone)]
+    pub struct RedisBackend {
+        connection: ConnectionManager,
+        prefix: String,
+    }
+
+    impl RedisBackend {
+        /// Connects to the Redis server at the given URL. The keys are prefixed with the given
+        /// prefix, so that several chains share a server.
+        pub async fn new(url: &str, prefix: String) -> Result<Self> {
+            let client = redis::Client::open(url)?;
+            let connection = ConnectionManager::new(client).await?;
+            Ok(Self { connection, prefix })
+        }
+
+        fn key(&self, key: &str) -> String {
+            format!("{}:{key}", self.prefix)
+        }
+
+        fn index(&self) -> String {
+            format!("{}:blocks", self.prefix)
+        }
+    }
+
+    impl fmt::Debug for RedisBackend {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("RedisBackend").field("prefix", &self.prefix).finish_non_exhaustive()
+        }
+    }
+
+    #[async_trait]
+    impl CacheBackend for RedisBackend {
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            let mut connection = self.connection.clone();
+            Ok(redis::cmd("GET").arg(self.key(key)).query_async(&mut connection).await?)
+        }
+
+        async fn set(&self, key: &str, value: Vec<u8>, block_number: u64) -> Result<()> {
+            let mut connection = self.connection.clone();
+            let key = self.key(key);
+            redis::pipe()
+                .atomic()
+                .set(&key, value)
+                .ignore()
+                .zadd(self.index(), &key, block_number)
+                .ignore()
+                .query_async::<_, ()>(&mut connection)
+                .await?;
+            Ok(())
+        }
+
+        async fn invalidate_from(&self, block_number: u64) -> Result<usize> {
+            let mut connection = self.connection.clone();
+            let keys: Vec<String> = redis::cmd("ZRANGEBYSCORE")
+                .arg(self.index())
+                .arg(block_number)
+                .arg("+inf")
+                .query_async(&mut connection)
+                .await?;
+            if keys.is_empty() {
+                return Ok(0);
+            }
+            redis::pipe()
+                .atomic()
+                .del(&keys)
+                .ignore()
+                .zrembyscore(self.index(), block_number, "+inf")
+                .ignore()
+                .query_async::<_, ()>(&mut connection)
+                .await?;
+            Ok(keys.len())
+        }
+    }
+}
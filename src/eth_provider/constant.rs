@@ -1,12 +1,72 @@
+use std::str::FromStr;
+
 use lazy_static::lazy_static;
 use reth_primitives::U256;
+use url::Url;
 
 lazy_static! {
-    pub static ref MAX_PRIORITY_FEE_PER_GAS: u64 = 0;
+    // Maximum number of blocks which can be queried by a single eth_getLogs request. Setting it to 0 disables the limit.
+    pub static ref MAX_LOGS_BLOCK_RANGE: u64 = u64::from_str(
+        &std::env::var("MAX_LOGS_BLOCK_RANGE").unwrap_or_else(|_| "10000".to_string())
+    ).expect("failing to parse MAX_LOGS_BLOCK_RANGE");
+    // Maximum number of logs returned by eth_getLogs and by a page of kakarot_getLogsPage. Setting it to 0 disables the limit.
+    pub static ref MAX_LOGS_PER_RESPONSE: u64 = u64::from_str(
+        &std::env::var("MAX_LOGS_PER_RESPONSE").unwrap_or_else(|_| "10000".to_string())
+    ).expect("failing to parse MAX_LOGS_PER_RESPONSE");
+    // Maximum size (in bytes) of a response. The block traces are paginated or rejected before reaching it.
+    pub static ref RPC_MAX_RESPONSE_SIZE: u32 = u32::from_str(
+        &std::env::var("RPC_MAX_RESPONSE_SIZE").unwrap_or_else(|_| "10485760".to_string())
+    ).expect("failing to parse RPC_MAX_RESPONSE_SIZE");
+    // Maximum number of transactions of a page of ots_searchTransactionsBefore and ots_searchTransactionsAfter. Larger page sizes are reduced to it.
+    pub static ref OTS_MAX_PAGE_SIZE: usize = usize::from_str(
+        &std::env::var("OTS_MAX_PAGE_SIZE").unwrap_or_else(|_| "100".to_string())
+    ).expect("failing to parse OTS_MAX_PAGE_SIZE");
+    // Whether the transactions are broadcast to all the Starknet providers of STARKNET_NETWORK, returning the first acceptance, instead of being sent to one of them.
+    pub static ref STARKNET_BROADCAST_TRANSACTIONS: bool = bool::from_str(
+        &std::env::var("STARKNET_BROADCAST_TRANSACTIONS").unwrap_or_else(|_| "false".to_string())
+    ).expect("failing to parse STARKNET_BROADCAST_TRANSACTIONS");
+    // Whether eth_getCode returns an empty code for the Kakarot accounts of the EOAs, which are detected as the senders of transactions.
+    pub static ref EOA_EMPTY_CODE: bool = bool::from_str(
+        &std::env::var("EOA_EMPTY_CODE").unwrap_or_else(|_| "true".to_string())
+    ).expect("failing to parse EOA_EMPTY_CODE");
+    // Maximum duration (in seconds) without a new block before the node is reported as not ready. Disabled by default (0), since the devnets only produce blocks on demand.
+    pub static ref READINESS_MAX_BLOCK_AGE: u64 = u64::from_str(
+        &std::env::var("READINESS_MAX_BLOCK_AGE").unwrap_or_else(|_| "0".to_string())
+    ).expect("failing to parse READINESS_MAX_BLOCK_AGE");
+    // Maximum duration (in seconds) of the tracing of a block by debug_traceBlockByNumber and debug_traceBlockByHash. Setting it to 0 disables the timeout.
+    pub static ref TRACE_BLOCK_TIMEOUT: u64 = u64::from_str(
+        &std::env::var("TRACE_BLOCK_TIMEOUT").unwrap_or_else(|_| "300".to_string())
+    ).expect("failing to parse TRACE_BLOCK_TIMEOUT");
+    // Duration (in seconds) during which the transaction traces are cached in the database. Setting it to 0 disables the trace cache.
+    pub static ref TRACE_CACHE_TTL: u64 = u64::from_str(
+        &std::env::var("TRACE_CACHE_TTL").unwrap_or_else(|_| "604800".to_string())
+    ).expect("failing to parse TRACE_CACHE_TTL");
+    // Maximum size (in bytes) of a cached transaction trace. Larger traces are generated again on each request.
+    pub static ref TRACE_CACHE_MAX_SIZE: usize = usize::from_str(
+        &std::env::var("TRACE_CACHE_MAX_SIZE").unwrap_or_else(|_| "1000000".to_string())
+    ).expect("failing to parse TRACE_CACHE_MAX_SIZE");
+    // Interval (in seconds) at which the transactions of the new blocks are traced and cached. Setting it to 0 disables the backfill.
+    pub static ref TRACE_BACKFILL_INTERVAL: u64 = u64::from_str(
+        &std::env::var("TRACE_BACKFILL_INTERVAL").unwrap_or_else(|_| "0".to_string())
+    ).expect("failing to parse TRACE_BACKFILL_INTERVAL");
+    // Maximum number of blocks traced concurrently by debug_traceBlockByNumber and debug_traceBlockByHash.
+    pub static ref TRACE_BLOCK_MAX_CONCURRENCY: usize = usize::from_str(
+        &std::env::var("TRACE_BLOCK_MAX_CONCURRENCY").unwrap_or_else(|_| "4".to_string())
+    ).expect("failing to parse TRACE_BLOCK_MAX_CONCURRENCY");
+    // URL of a Pathfinder node serving the Starknet state proofs used by eth_getProof. Leaving it unset disables eth_getProof.
+    pub static ref STARKNET_PROOF_PROVIDER_URL: Option<Url> = std::env::var("STARKNET_PROOF_PROVIDER_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map(|url| Url::parse(&url).expect("failing to parse STARKNET_PROOF_PROVIDER_URL"));
 }
 
 /// Gas limit for estimate gas and call
 pub const CALL_REQUEST_GAS_LIMIT: u128 = 5_000_000;
+/// Maximum relative error of the gas estimates, as a fraction of the estimate
+pub const ESTIMATE_GAS_ERROR_RATIO: f64 = 0.015;
+/// Margin added to the gas required by Kakarot for the first upper bound of the gas estimates, in
+/// percent of the required gas
+pub const ESTIMATE_GAS_MARGIN_PERCENT: u128 = 20;
 /// Number of characters for representing a U256 in a hex string form. Used for padding hashes
 pub const HASH_HEX_STRING_LEN: usize = 64;
 /// Number of characters for representing logs topics in a hex string form. Used for padding logs topics
@@ -17,10 +77,61 @@ pub const U64_HEX_STRING_LEN: usize = 16;
 pub const BLOCK_NUMBER_HEX_STRING_LEN: usize = U64_HEX_STRING_LEN;
 /// Number of characters for representing an address in a hex string form. Used for padding addresses
 pub const ADDRESS_HEX_STRING_LEN: usize = 40;
+/// Gas limit of the indexed blocks when it can't be read from the Kakarot contract
+pub const DEFAULT_BLOCK_GAS_LIMIT: u128 = 7_000_000;
 /// Starknet Modulus: 0x800000000000011000000000000000000000000000000000000000000000001
 pub const STARKNET_MODULUS: U256 = U256::from_limbs([0x1, 0, 0, 0x800000000000011]);
+/// Minimum fee bump (in percent) of a transaction replacing a pending transaction with the same nonce
+pub const TRANSACTION_REPLACEMENT_PRICE_BUMP: u128 = 10;
 /// Maximum number of times a transaction can be retried
 pub const TRANSACTION_MAX_RETRIES: u64 = 10;
+/// Maximum duration (in seconds) to wait for the deployment of the account of a sender before relaying its transaction
+pub const EOA_DEPLOYMENT_TIMEOUT: u64 = 60;
+/// Interval between two checks of the execution of the deployment of an account (in milliseconds)
+pub const EOA_DEPLOYMENT_POLL_INTERVAL_MS: u64 = 1_000;
+/// Interval after which the balance of a relayer is read again (in seconds)
+pub const RELAYER_BALANCE_REFRESH_INTERVAL: u64 = 60;
+/// Number of blocks after which a submitted transaction which isn't included in a block is considered stuck
+pub const TRANSACTION_STUCK_BLOCKS: u64 = 3;
+/// Number of blocks during which a failed transaction is kept in the pending transactions collection
+pub const FAILED_TRANSACTION_RETENTION_BLOCKS: u64 = 1_000;
+/// Number of blocks queried at once when fetching logs
+pub const LOGS_QUERY_CHUNK_SIZE: usize = 500;
+/// Maximum number of concurrent database queries when fetching logs
+pub const LOGS_QUERY_CONCURRENCY: usize = 4;
+/// Blob base fee (in wei), which is the minimum blob gas price of EIP-4844 since Kakarot doesn't
+/// support the blob transactions and the excess blob gas of its blocks is always zero
+pub const BLOB_BASE_FEE: u128 = 1;
+/// Number of transactions queried at once when assembling a block with its full transactions
+pub const BLOCK_TRANSACTIONS_QUERY_CHUNK_SIZE: usize = 100;
+/// Maximum number of concurrent database queries when assembling a block with its full transactions
+pub const BLOCK_TRANSACTIONS_QUERY_CONCURRENCY: usize = 8;
+/// Number of blocks the database can lag behind the Starknet tip before being reported as syncing
+pub const SYNCING_BLOCK_LAG_THRESHOLD: u64 = 2;
+/// Maximum number of token addresses in a single alchemy_getTokenBalances request
+pub const MAX_TOKEN_BALANCES_ADDRESSES: usize = 1000;
+/// Maximum number of concurrent balance reads when serving alchemy_getTokenBalances
+pub const TOKEN_BALANCES_CONCURRENCY: usize = 16;
+/// Default and maximum number of transfers in a single alchemy_getAssetTransfers response
+pub const MAX_ASSET_TRANSFERS: u64 = 1000;
+/// Maximum number of internal transactions in a single kakarot_getInternalTransactions page
+pub const MAX_INTERNAL_TRANSACTIONS_PER_PAGE: u64 = 1000;
+/// Default and maximum number of tokens in a single alchemy_getNFTs response
+pub const MAX_NFTS_PAGE_SIZE: u64 = 100;
+
+pub const MAX_CALL_BUNDLE_SIZE: usize = 100;
+/// Maximum number of blocks in a single kakarot_getL1Messages request
+pub const MAX_L1_MESSAGES_BLOCK_RANGE: u64 = 1000;
+/// Maximum number of concurrent Starknet block fetches when serving kakarot_getL1Messages
+pub const L1_MESSAGES_QUERY_CONCURRENCY: usize = 8;
+/// Maximum number of concurrent Starknet receipt fetches when serving kakarot_getBlockFeeBreakdown
+pub const FEE_BREAKDOWN_QUERY_CONCURRENCY: usize = 16;
+/// Maximum number of concurrent Starknet reads per query when serving kakarot_getStateDiff
+pub const STATE_DIFF_QUERY_CONCURRENCY: usize = 16;
+/// Interval between two polls of the provider when serving subscriptions (in milliseconds)
+pub const SUBSCRIPTION_POLL_INTERVAL_MS: u64 = 1_000;
+/// Number of recent blocks tracked by the filters and subscriptions to detect the reorgs
+pub const REORG_TRACKING_DEPTH: usize = 64;
 
 #[cfg(feature = "hive")]
 use {
@@ -1,15 +1,18 @@
-use ethers::abi::AbiEncode;
+use ethers::abi::{AbiDecode, AbiEncode};
 use ethers::core::types::Address as EthersAddress;
 use ethers::prelude::abigen;
 use reth_primitives::Address;
 
-use reth_primitives::{BlockId, U256};
+use reth_primitives::{BlockId, Bytes, U256};
 use reth_rpc_types::request::TransactionInput;
 use reth_rpc_types::TransactionRequest;
 
 use crate::eth_provider::error::KakarotError;
 use crate::eth_provider::provider::EthProviderResult;
 use crate::eth_provider::provider::EthereumProvider;
+use crate::models::token::TokenMetadata;
+
+use super::multicall::Multicall;
 
 // abigen generates a lot of unused code, needs to be benchmarked if performances ever become a
 // concern
@@ -18,9 +21,25 @@ abigen!(
     r#"[
         function balanceOf(address account) external view returns (uint256)
         function allowance(address owner, address spender) external view returns (uint256)
+        function name() external view returns (string)
+        function symbol() external view returns (string)
+        function decimals() external view returns (uint8)
     ]"#,
 );
 
+/// Returns the calldata of a `balanceOf` call for the given address.
+pub fn balance_of_calldata(evm_address: Address) -> Bytes {
+    let address = EthersAddress::from_slice(evm_address.as_slice());
+    IERC20Calls::BalanceOf(BalanceOfCall { account: address }).encode().into()
+}
+
+/// Decodes the output of a `balanceOf` call.
+pub fn decode_balance(output: &[u8]) -> EthProviderResult<U256> {
+    Ok(U256::try_from_be_slice(output).ok_or(KakarotError::CallError(cainome::cairo_serde::Error::Deserialize(
+        "failed to deserialize balance".to_string(),
+    )))?)
+}
+
 /// Abstraction for a Kakarot ERC20 contract.
 #[derive(Debug)]
 pub struct EthereumErc20<P: EthereumProvider> {
@@ -35,24 +54,57 @@ impl<P: EthereumProvider> EthereumErc20<P> {
 
     pub async fn balance_of(self, evm_address: Address, block_id: BlockId) -> EthProviderResult<U256> {
         // Prepare the calldata for the bytecode function call
-        let address = EthersAddress::from_slice(evm_address.as_slice());
-        let calldata = IERC20Calls::BalanceOf(BalanceOfCall { account: address }).encode();
+        let calldata = balance_of_calldata(evm_address);
+        let ret = self.call(calldata, block_id).await?;
+        decode_balance(&ret)
+    }
 
+    /// Calls the token contract with the given calldata.
+    async fn call(&self, calldata: Bytes, block_id: BlockId) -> EthProviderResult<Bytes> {
         let request = TransactionRequest {
             from: Some(Address::default()),
             to: Some(self.address),
             gas_price: Some(0),
             gas: Some(1_000_000),
             value: Some(U256::ZERO),
-            input: TransactionInput { input: Some(calldata.into()), data: None },
+            input: TransactionInput { input: Some(calldata), data: None },
             ..Default::default()
         };
 
-        let ret = self.provider.call(request, Some(block_id)).await?;
-        let balance = U256::try_from_be_slice(&ret).ok_or(KakarotError::CallError(
-            cainome::cairo_serde::Error::Deserialize("failed to deserialize balance".to_string()),
-        ))?;
+        self.provider.call(request, Some(block_id)).await
+    }
+
+    /// Returns the name, symbol and decimals of the token, read in a single multicall.
+    /// The fields which can't be read are left empty.
+    pub async fn metadata(self, block_id: BlockId) -> EthProviderResult<TokenMetadata> {
+        let calls = [
+            IERC20Calls::Name(NameCall).encode(),
+            IERC20Calls::Symbol(SymbolCall).encode(),
+            IERC20Calls::Decimals(DecimalsCall).encode(),
+        ]
+        .into_iter()
+        .map(|calldata| (self.address, Bytes::from(calldata)))
+        .collect::<Vec<_>>();
+
+        let outputs = match Multicall::new(&self.provider).aggregate(calls.clone(), block_id).await {
+            Ok(outputs) => outputs,
+            // Fall back to one call per field if the Multicall3 contract isn't available
+            Err(_) => {
+                let mut outputs = Vec::with_capacity(calls.len());
+                for (_, calldata) in calls {
+                    outputs.push(self.call(calldata, block_id).await.ok());
+                }
+                outputs
+            }
+        };
+        let mut outputs = outputs.into_iter();
+        let mut next_output = || outputs.next().flatten();
 
-        Ok(balance)
+        Ok(TokenMetadata {
+            name: next_output().and_then(|output| String::decode(&output).ok()),
+            symbol: next_output().and_then(|output| String::decode(&output).ok()),
+            decimals: next_output().and_then(|output| u8::decode(&output).ok()),
+            logo: None,
+        })
     }
 }
@@ -1 +1,3 @@
 pub mod erc20;
+pub mod multicall;
+pub mod nft;
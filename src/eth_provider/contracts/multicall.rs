@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use ethers::abi::{AbiDecode, AbiEncode};
+use ethers::core::types::{Address as EthersAddress, Bytes as EthersBytes};
+use ethers::prelude::abigen;
+use futures::{StreamExt, TryStreamExt};
+use lazy_static::lazy_static;
+use reth_primitives::{Address, BlockId, Bytes, U256};
+use reth_rpc_types::request::TransactionInput;
+use reth_rpc_types::TransactionRequest;
+
+use crate::eth_provider::error::KakarotError;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
+
+lazy_static! {
+    // Address of the Multicall3 contract used to aggregate calls. Defaults to the address at which
+    // Multicall3 is deployed on most EVM chains.
+    pub static ref MULTICALL3_ADDRESS: Address = Address::from_str(
+        &std::env::var("MULTICALL3_ADDRESS").unwrap_or_else(|_| "0xcA11bde05977b3631167028862bE2a173976CA11".to_string())
+    ).expect("failing to parse MULTICALL3_ADDRESS");
+}
+
+/// Maximum number of calls aggregated in a single call to the Multicall3 contract
+pub const MULTICALL_BATCH_SIZE: usize = 100;
+/// Maximum number of concurrent calls to the Multicall3 contract
+pub const MULTICALL_CONCURRENCY: usize = 4;
+/// Gas limit allocated to each aggregated call
+pub const MULTICALL_GAS_PER_CALL: u128 = 1_000_000;
+
+abigen!(
+    IMulticall3,
+    r#"[
+        struct Call3 { address target; bool allowFailure; bytes callData; }
+        struct CallResult { bool success; bytes returnData; }
+        function aggregate3(Call3[] calls) external payable returns (CallResult[] returnData)
+    ]"#,
+);
+
+/// Aggregates multiple calls into a single `eth_call` to the Multicall3 contract,
+/// hence into a single Starknet call to Kakarot.
+#[derive(Debug)]
+pub struct Multicall<P: EthereumProvider> {
+    pub address: Address,
+    pub provider: P,
+}
+
+impl<P: EthereumProvider> Multicall<P> {
+    /// Create a new [`Multicall`] using the Multicall3 contract at [`MULTICALL3_ADDRESS`].
+    pub fn new(provider: P) -> Self {
+        Self { address: *MULTICALL3_ADDRESS, provider }
+    }
+
+    /// Create a new [`Multicall`] using the Multicall3 contract at the given address.
+    pub const fn with_address(address: Address, provider: P) -> Self {
+        Self { address, provider }
+    }
+
+    /// Executes the calls, given as target and calldata, at the given block. Returns the output
+    /// of each call in the same order, or None for the calls which failed. The calls are sent
+    /// by batches of [`MULTICALL_BATCH_SIZE`].
+    pub async fn aggregate(
+        &self,
+        calls: Vec<(Address, Bytes)>,
+        block_id: BlockId,
+    ) -> EthProviderResult<Vec<Option<Bytes>>> {
+        let batches = calls.chunks(MULTICALL_BATCH_SIZE).map(|batch| self.aggregate_batch(batch, block_id));
+        let outputs =
+            futures::stream::iter(batches).buffered(MULTICALL_CONCURRENCY).try_collect::<Vec<Vec<_>>>().await?;
+        Ok(outputs.into_iter().flatten().collect())
+    }
+
+    /// Executes a batch of calls in a single call to the Multicall3 contract.
+    async fn aggregate_batch(
+        &self,
+        calls: &[(Address, Bytes)],
+        block_id: BlockId,
+    ) -> EthProviderResult<Vec<Option<Bytes>>> {
+        let calls = calls
+            .iter()
+            .map(|(target, calldata)| Call3 {
+                target: EthersAddress::from_slice(target.as_slice()),
+                allow_failure: true,
+                call_data: EthersBytes::from(calldata.to_vec()),
+            })
+            .collect::<Vec<_>>();
+        let gas = MULTICALL_GAS_PER_CALL * calls.len() as u128;
+        let calldata = IMulticall3Calls::Aggregate3(Aggregate3Call { calls }).encode();
+
+        let request = TransactionRequest {
+            from: Some(Address::default()),
+            to: Some(self.address),
+            gas_price: Some(0),
+            gas: Some(gas),
+            value: Some(U256::ZERO),
+            input: TransactionInput { input: Some(calldata.into()), data: None },
+            ..Default::default()
+        };
+
+        let ret = self.provider.call(request, Some(block_id)).await?;
+        let results = Aggregate3Return::decode(&ret)
+            .map_err(|_| {
+                KakarotError::CallError(cainome::cairo_serde::Error::Deserialize(
+                    "failed to deserialize multicall results".to_string(),
+                ))
+            })?
+            .return_data;
+
+        Ok(results.into_iter().map(|result| result.success.then(|| Bytes::from(result.return_data.to_vec()))).collect())
+    }
+}
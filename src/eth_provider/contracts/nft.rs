@@ -0,0 +1,63 @@
+use ethers::abi::{AbiDecode, AbiEncode};
+use ethers::prelude::abigen;
+use reth_primitives::{Address, BlockId, Bytes, U256};
+use reth_rpc_types::request::TransactionInput;
+use reth_rpc_types::TransactionRequest;
+
+use crate::eth_provider::provider::EthProviderResult;
+use crate::eth_provider::provider::EthereumProvider;
+use crate::models::transfer::TransferCategory;
+
+abigen!(
+    INFT,
+    r#"[
+        function tokenURI(uint256 tokenId) external view returns (string)
+        function uri(uint256 id) external view returns (string)
+    ]"#,
+);
+
+/// Abstraction for a Kakarot ERC721 or ERC1155 contract.
+#[derive(Debug)]
+pub struct EthereumNft<P: EthereumProvider> {
+    pub address: Address,
+    pub provider: P,
+}
+
+impl<P: EthereumProvider> EthereumNft<P> {
+    pub const fn new(address: Address, provider: P) -> Self {
+        Self { address, provider }
+    }
+
+    /// Returns the metadata URI of the token: the `tokenURI` of an ERC721 token, or the `uri` of
+    /// an ERC1155 token with its `{id}` placeholder replaced by the id of the token. Returns None
+    /// if the contract doesn't implement the metadata extension of its standard.
+    pub async fn token_uri(
+        &self,
+        token_id: U256,
+        category: TransferCategory,
+        block_id: BlockId,
+    ) -> EthProviderResult<Option<String>> {
+        let id = ethers::types::U256::from_big_endian(&token_id.to_be_bytes::<32>());
+        let calldata = match category {
+            TransferCategory::Erc1155 => INFTCalls::Uri(UriCall { id }).encode(),
+            _ => INFTCalls::TokenURI(TokenURICall { token_id: id }).encode(),
+        };
+
+        let request = TransactionRequest {
+            from: Some(Address::default()),
+            to: Some(self.address),
+            gas_price: Some(0),
+            gas: Some(1_000_000),
+            value: Some(U256::ZERO),
+            input: TransactionInput { input: Some(Bytes::from(calldata)), data: None },
+            ..Default::default()
+        };
+        let uri =
+            self.provider.call(request, Some(block_id)).await.ok().and_then(|output| String::decode(&output).ok());
+
+        Ok(uri.map(|uri| match category {
+            TransferCategory::Erc1155 => uri.replace("{id}", &format!("{token_id:064x}")),
+            _ => uri,
+        }))
+    }
+}
@@ -1,24 +1,32 @@
 pub mod types;
 
 use super::error::KakarotError;
+use crate::eth_provider::constant::BLOCK_NUMBER_HEX_STRING_LEN;
 use crate::eth_provider::database::types::{
-    header::StoredHeader,
-    log::StoredLog,
+    checkpoint::StoredIndexerCheckpoint,
+    header::{StoredHeader, StoredHeaderLogsBloom},
+    internal_transaction::StoredInternalTransaction,
+    log::{StoredLog, StoredRemovedLog},
     receipt::StoredTransactionReceipt,
+    trace::StoredTrace,
     transaction::{StoredPendingTransaction, StoredTransaction, StoredTransactionHash},
+    transfer::StoredTransfer,
 };
+use crate::eth_provider::utils::format_hex;
 use futures::TryStreamExt;
 use itertools::Itertools;
 use mongodb::{
     bson::{doc, Document},
-    options::{FindOneOptions, FindOptions, UpdateModifications, UpdateOptions},
-    Collection, Database as MongoDatabase,
+    error::{BulkWriteFailure, ErrorKind},
+    options::{FindOneOptions, FindOptions, IndexOptions, InsertManyOptions, UpdateModifications, UpdateOptions},
+    Collection, Database as MongoDatabase, IndexModel,
 };
 use serde::{de::DeserializeOwned, Serialize};
 
 type DatabaseResult<T> = eyre::Result<T, KakarotError>;
 
-/// Wrapper around a MongoDB database
+/// Wrapper around a MongoDB database. Each query is traced by a `db_query` span, nested in the
+/// span of the RPC call which made it.
 #[derive(Clone, Debug)]
 pub struct Database(MongoDatabase);
 
@@ -46,6 +54,11 @@ impl Database {
     }
 
     /// Get a list of documents from a collection
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "find")
+    )]
     pub async fn get<T>(
         &self,
         filter: impl Into<Option<Document>>,
@@ -58,6 +71,25 @@ impl Database {
         Ok(self.collection::<T>().find(filter, find_options).await?.try_collect().await?)
     }
 
+    /// Get a list of documents from a collection, sorted and holding at most `limit` documents
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "find")
+    )]
+    pub async fn get_sorted<T>(
+        &self,
+        filter: impl Into<Option<Document>>,
+        sort: impl Into<Option<Document>>,
+        limit: impl Into<Option<i64>>,
+    ) -> DatabaseResult<Vec<T>>
+    where
+        T: DeserializeOwned + CollectionName,
+    {
+        let find_options = FindOptions::builder().sort(sort).limit(limit).build();
+        Ok(self.collection::<T>().find(filter, find_options).await?.try_collect().await?)
+    }
+
     /// Retrieves documents from a collection and converts them into another type.
     ///
     /// Returns a vector of documents of type `D` if successful, or an error.
@@ -75,6 +107,11 @@ impl Database {
     }
 
     /// Get a single document from a collection
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "find_one")
+    )]
     pub async fn get_one<T>(
         &self,
         filter: impl Into<Option<Document>>,
@@ -88,6 +125,11 @@ impl Database {
     }
 
     /// Get a single document from aggregated collections
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "aggregate")
+    )]
     pub async fn get_one_aggregate<T>(&self, pipeline: impl IntoIterator<Item = Document>) -> DatabaseResult<Option<T>>
     where
         T: DeserializeOwned + CollectionName,
@@ -98,6 +140,11 @@ impl Database {
     }
 
     /// Update a single document in a collection
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "update_one")
+    )]
     pub async fn update_one<T>(&self, doc: T, filter: impl Into<Document>, upsert: bool) -> DatabaseResult<()>
     where
         T: Serialize + CollectionName,
@@ -115,7 +162,33 @@ impl Database {
         Ok(())
     }
 
+    /// Upsert a single raw document in the collection of `T`
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "update_one")
+    )]
+    pub async fn upsert_document<T>(&self, document: Document, filter: impl Into<Document>) -> DatabaseResult<()>
+    where
+        T: CollectionName,
+    {
+        self.collection::<T>()
+            .update_one(
+                filter.into(),
+                UpdateModifications::Document(doc! {"$set": document}),
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
     /// Delete a single document from a collection
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "delete_one")
+    )]
     pub async fn delete_one<T>(&self, filter: impl Into<Document>) -> DatabaseResult<()>
     where
         T: CollectionName,
@@ -124,7 +197,151 @@ impl Database {
         Ok(())
     }
 
+    /// Delete all the documents from a collection matching the filter
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "delete_many")
+    )]
+    pub async fn delete_many<T>(&self, filter: impl Into<Document>) -> DatabaseResult<()>
+    where
+        T: CollectionName,
+    {
+        self.collection::<T>().delete_many(filter.into(), None).await?;
+        Ok(())
+    }
+
+    /// Rolls back the blocks starting at `block_number`, after they were removed from the chain by
+    /// a reorg: the headers, transactions, receipts, transfers, internal transactions and logs of
+    /// these blocks are deleted. The logs are flagged as removed and kept in the removed logs collection, from
+    /// which they are notified to the filters and subscriptions which returned them. The rollback is idempotent,
+    /// so that it can be run again after an interruption.
+    pub async fn rollback_from(&self, block_number: u64) -> DatabaseResult<()> {
+        let from = doc! {"$gte": format_hex(block_number, BLOCK_NUMBER_HEX_STRING_LEN)};
+
+        // The headers are deleted first, since a block is considered as indexed once its header
+        // is in the database
+        self.delete_many::<StoredHeader>(doc! {"header.number": from.clone()}).await?;
+        self.delete_many::<StoredTransaction>(doc! {"tx.blockNumber": from.clone()}).await?;
+        self.delete_many::<StoredTransactionReceipt>(doc! {"receipt.blockNumber": from.clone()}).await?;
+        self.delete_many::<StoredTransfer>(doc! {"transfer.blockNumber": from.clone()}).await?;
+        self.delete_many::<StoredInternalTransaction>(doc! {"internal_tx.blockNumber": from.clone()}).await?;
+
+        let filter = doc! {"log.blockNumber": from};
+        let mut logs: Vec<Document> = self
+            .0
+            .collection::<Document>(StoredLog::collection_name())
+            .find(filter.clone(), None)
+            .await?
+            .try_collect()
+            .await?;
+        for log in &mut logs {
+            if let Ok(inner) = log.get_document_mut("log") {
+                inner.insert("removed", true);
+            }
+        }
+        // The removed logs keep the id of the logs, so that the logs already copied by an
+        // interrupted rollback are skipped as duplicates, before the logs are deleted
+        if !logs.is_empty() {
+            let options = InsertManyOptions::builder().ordered(false).build();
+            let inserted =
+                self.0.collection::<Document>(StoredRemovedLog::collection_name()).insert_many(logs, options).await;
+            match inserted {
+                Ok(_) => {}
+                Err(err) if is_duplicate_key_error(&err) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        self.delete_many::<StoredLog>(filter).await?;
+
+        Ok(())
+    }
+
+    /// Creates the indexes of the logs collection, used by `eth_getLogs`: the logs are queried by
+    /// block range, optionally restricted to some addresses and first topics. Creating an index
+    /// which already exists is a no-op.
+    pub async fn create_log_indexes(&self) -> DatabaseResult<()> {
+        let index = |name: &str, keys: Document| {
+            IndexModel::builder().keys(keys).options(IndexOptions::builder().name(name.to_string()).build()).build()
+        };
+        let indexes = [
+            index("log_address_topic0_block_number", doc! {"log.address": 1, "log.topics.0": 1, "log.blockNumber": 1}),
+            index("log_block_number", doc! {"log.blockNumber": 1}),
+        ];
+        self.collection::<StoredLog>().create_indexes(indexes, None).await?;
+        Ok(())
+    }
+
+    /// Creates the indexes of the transfers collection, used by `alchemy_getAssetTransfers`: the
+    /// transfers are queried by block range, optionally restricted to a sender, a recipient or
+    /// some tokens. Creating an index which already exists is a no-op.
+    pub async fn create_transfer_indexes(&self) -> DatabaseResult<()> {
+        let index = |name: &str, keys: Document| {
+            IndexModel::builder().keys(keys).options(IndexOptions::builder().name(name.to_string()).build()).build()
+        };
+        let indexes = [
+            index(
+                "transfer_from_block_number",
+                doc! {"transfer.from": 1, "transfer.blockNumber": 1, "transfer.logIndex": 1},
+            ),
+            index(
+                "transfer_to_block_number",
+                doc! {"transfer.to": 1, "transfer.blockNumber": 1, "transfer.logIndex": 1},
+            ),
+            index(
+                "transfer_address_block_number",
+                doc! {"transfer.address": 1, "transfer.blockNumber": 1, "transfer.logIndex": 1},
+            ),
+            index("transfer_block_number", doc! {"transfer.blockNumber": 1, "transfer.logIndex": 1}),
+        ];
+        self.collection::<StoredTransfer>().create_indexes(indexes, None).await?;
+        Ok(())
+    }
+
+    /// Creates the indexes of the internal transactions collection, used by
+    /// `kakarot_getInternalTransactions` and `ots_getInternalOperations`: the internal
+    /// transactions are queried by sender or recipient and block range, or by transaction hash.
+    /// Creating an index which already exists is a no-op.
+    pub async fn create_internal_transaction_indexes(&self) -> DatabaseResult<()> {
+        let index = |name: &str, keys: Document| {
+            IndexModel::builder().keys(keys).options(IndexOptions::builder().name(name.to_string()).build()).build()
+        };
+        let indexes = [
+            index(
+                "internal_tx_from_block_number",
+                doc! {"internal_tx.from": 1, "internal_tx.blockNumber": 1, "internal_tx.index": 1},
+            ),
+            index(
+                "internal_tx_to_block_number",
+                doc! {"internal_tx.to": 1, "internal_tx.blockNumber": 1, "internal_tx.index": 1},
+            ),
+            index("internal_tx_transaction_hash", doc! {"internal_tx.transactionHash": 1}),
+        ];
+        self.collection::<StoredInternalTransaction>().create_indexes(indexes, None).await?;
+        Ok(())
+    }
+
+    /// Creates the indexes of the transactions and pending transactions collections, used to look
+    /// up the transaction sent by an address with a given nonce. Creating an index which already
+    /// exists is a no-op.
+    pub async fn create_transaction_indexes(&self) -> DatabaseResult<()> {
+        let index = || {
+            IndexModel::builder()
+                .keys(doc! {"tx.from": 1, "tx.nonce": 1})
+                .options(IndexOptions::builder().name("tx_from_nonce".to_string()).build())
+                .build()
+        };
+        self.collection::<StoredTransaction>().create_index(index(), None).await?;
+        self.collection::<StoredPendingTransaction>().create_index(index(), None).await?;
+        Ok(())
+    }
+
     /// Count the number of documents in a collection matching the filter
+    #[tracing::instrument(
+        name = "db_query",
+        skip_all,
+        fields(db.system = "mongodb", db.collection = T::collection_name(), db.operation = "count_documents")
+    )]
     pub async fn count<T>(&self, filter: impl Into<Option<Document>>) -> DatabaseResult<u64>
     where
         T: CollectionName,
@@ -139,6 +356,16 @@ impl From<MongoDatabase> for Database {
     }
 }
 
+/// Returns true if the only errors of the bulk write are duplicate key errors.
+fn is_duplicate_key_error(err: &mongodb::error::Error) -> bool {
+    const DUPLICATE_KEY_ERROR_CODE: i32 = 11000;
+    matches!(
+        &*err.kind,
+        ErrorKind::BulkWrite(BulkWriteFailure { write_errors: Some(errors), write_concern_error: None, .. })
+            if errors.iter().all(|error| error.code == DUPLICATE_KEY_ERROR_CODE)
+    )
+}
+
 /// Trait for associating a type with its collection name
 pub trait CollectionName {
     /// Returns the name of the collection associated with the type
@@ -152,6 +379,13 @@ impl CollectionName for StoredHeader {
     }
 }
 
+/// Implement [`CollectionName`] for [`StoredHeaderLogsBloom`]
+impl CollectionName for StoredHeaderLogsBloom {
+    fn collection_name() -> &'static str {
+        "headers"
+    }
+}
+
 /// Implement [`CollectionName`] for [`StoredTransaction`]
 impl CollectionName for StoredTransaction {
     fn collection_name() -> &'static str {
@@ -186,3 +420,38 @@ impl CollectionName for StoredLog {
         "logs"
     }
 }
+
+/// Implement [`CollectionName`] for [`StoredRemovedLog`]
+impl CollectionName for StoredRemovedLog {
+    fn collection_name() -> &'static str {
+        "logs_removed"
+    }
+}
+
+/// Implement [`CollectionName`] for [`StoredTransfer`]
+impl CollectionName for StoredTransfer {
+    fn collection_name() -> &'static str {
+        "transfers"
+    }
+}
+
+/// Implement [`CollectionName`] for [`StoredInternalTransaction`]
+impl CollectionName for StoredInternalTransaction {
+    fn collection_name() -> &'static str {
+        "internal_transactions"
+    }
+}
+
+/// Implement [`CollectionName`] for [`StoredTrace`]
+impl CollectionName for StoredTrace {
+    fn collection_name() -> &'static str {
+        "traces"
+    }
+}
+
+/// Implement [`CollectionName`] for [`StoredIndexerCheckpoint`]
+impl CollectionName for StoredIndexerCheckpoint {
+    fn collection_name() -> &'static str {
+        "indexer_checkpoint"
+    }
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// The progress of the built-in indexer as stored in the database
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct StoredIndexerCheckpoint {
+    /// Number of the last Starknet block which was fully indexed
+    pub last_indexed_block: u64,
+}
@@ -1,3 +1,4 @@
+use reth_primitives::{Bloom, U64};
 use reth_rpc_types::Header;
 use serde::{Deserialize, Serialize};
 #[cfg(any(test, feature = "arbitrary", feature = "testing"))]
@@ -14,6 +15,21 @@ pub struct StoredHeader {
     pub header: Header,
 }
 
+/// The number and logs bloom of a header as stored in the database.
+/// Used to pre-screen the blocks which can contain logs matching a filter.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct StoredHeaderLogsBloom {
+    #[serde(deserialize_with = "crate::eth_provider::database::types::serde::deserialize_intermediate")]
+    pub header: HeaderLogsBloom,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderLogsBloom {
+    pub number: U64,
+    pub logs_bloom: Bloom,
+}
+
 #[cfg(any(test, feature = "arbitrary", feature = "testing"))]
 impl<'a> StoredHeader {
     pub fn arbitrary_with_optional_fields(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::internal_transaction::InternalTransaction;
+
+/// An internal transaction as stored in the database
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredInternalTransaction {
+    #[serde(deserialize_with = "crate::eth_provider::database::types::serde::deserialize_intermediate")]
+    pub internal_tx: InternalTransaction,
+}
+
+impl From<StoredInternalTransaction> for InternalTransaction {
+    fn from(internal_tx: StoredInternalTransaction) -> Self {
+        internal_tx.internal_tx
+    }
+}
+
+impl From<InternalTransaction> for StoredInternalTransaction {
+    fn from(internal_tx: InternalTransaction) -> Self {
+        Self { internal_tx }
+    }
+}
@@ -10,6 +10,19 @@ pub struct StoredLog {
     pub log: Log,
 }
 
+/// A log removed from the chain by a reorg, as stored in the database
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredRemovedLog {
+    #[serde(deserialize_with = "crate::eth_provider::database::types::serde::deserialize_intermediate")]
+    pub log: Log,
+}
+
+impl From<StoredRemovedLog> for Log {
+    fn from(log: StoredRemovedLog) -> Self {
+        log.log
+    }
+}
+
 impl From<StoredLog> for Log {
     fn from(log: StoredLog) -> Self {
         log.log
@@ -1,5 +1,9 @@
+pub mod checkpoint;
 pub mod header;
+pub mod internal_transaction;
 pub mod log;
 pub mod receipt;
 pub mod serde;
+pub mod trace;
 pub mod transaction;
+pub mod transfer;
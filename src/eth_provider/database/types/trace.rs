@@ -0,0 +1,18 @@
+use mongodb::bson::DateTime;
+use reth_primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// A transaction trace as cached in the database
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct StoredTrace {
+    /// Hash of the traced transaction
+    pub hash: B256,
+    /// Number of the block of the traced transaction, from which the trace is pruned
+    pub block_number: u64,
+    /// Kind of the trace: `parity`, or `geth:` followed by the tracing options
+    pub kind: String,
+    /// The trace, serialized as JSON
+    pub trace: String,
+    /// Time at which the trace was cached, from which its expiration is computed
+    pub created_at: DateTime,
+}
@@ -69,17 +69,34 @@ pub struct StoredPendingTransaction {
     pub tx: Transaction,
     /// Number of retries
     pub retries: u64,
+    /// Block number at the last submission of the transaction to Starknet
+    #[serde(default)]
+    pub submitted_block: u64,
+    /// Block number at which the transaction was marked as failed, after reaching the maximum
+    /// number of retries without being included in a block
+    #[serde(default)]
+    pub failed_block: Option<u64>,
+    /// Whether the transaction was accepted by the RPC but its submission to Starknet didn't
+    /// complete yet, e.g. because the RPC stopped while deploying the account of its sender
+    #[serde(default)]
+    pub unsubmitted: bool,
 }
 
 impl StoredPendingTransaction {
     pub fn new(tx: Transaction, retries: u64) -> Self {
-        Self { tx, retries }
+        Self { tx, retries, submitted_block: 0, failed_block: None, unsubmitted: false }
+    }
+}
+
+impl From<StoredPendingTransaction> for Transaction {
+    fn from(tx: StoredPendingTransaction) -> Self {
+        tx.tx
     }
 }
 
 impl From<Transaction> for StoredPendingTransaction {
     fn from(tx: Transaction) -> Self {
-        Self { tx, retries: 0 }
+        Self::new(tx, 0)
     }
 }
 
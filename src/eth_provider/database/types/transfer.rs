@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::transfer::TokenTransfer;
+
+/// A token transfer as stored in the database
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredTransfer {
+    #[serde(deserialize_with = "crate::eth_provider::database::types::serde::deserialize_intermediate")]
+    pub transfer: TokenTransfer,
+}
+
+impl From<StoredTransfer> for TokenTransfer {
+    fn from(transfer: StoredTransfer) -> Self {
+        transfer.transfer
+    }
+}
+
+impl From<TokenTransfer> for StoredTransfer {
+    fn from(transfer: TokenTransfer) -> Self {
+        Self { transfer }
+    }
+}
@@ -1,10 +1,14 @@
-use jsonrpsee::types::ErrorObject;
-use reth_primitives::Bytes;
+use reth_primitives::{Address, Bytes, B256, U256, U64};
+use starknet::providers::jsonrpc::HttpTransportError;
 use starknet_crypto::FieldElement;
 use thiserror::Error;
 
+use super::starknet::transport::{is_upstream_unavailable, FailoverTransportError};
+use super::utils::try_from_u8_iterator;
+
 /// List of JSON-RPC error codes from ETH rpc spec.
 /// https://github.com/ethereum/EIPs/blob/master/EIPS/eip-1474.md
+/// The errors are converted to JSON-RPC errors in [`crate::eth_rpc::error`].
 #[derive(Debug, Copy, PartialEq, Eq, Clone)]
 pub enum EthRpcErrorCode {
     /// Custom geth error code, <https://github.com/vapory-legacy/wiki/blob/master/JSON-RPC-Error-Codes-Improvement-Proposal.md>
@@ -24,21 +28,6 @@ pub enum EthRpcErrorCode {
     JsonRpcVersionUnsupported = -32006,
 }
 
-impl From<EthApiError> for EthRpcErrorCode {
-    fn from(error: EthApiError) -> Self {
-        match error {
-            EthApiError::UnknownBlock | EthApiError::UnknownBlockNumber => EthRpcErrorCode::ResourceNotFound,
-            EthApiError::InvalidBlockRange
-            | EthApiError::Signature(_)
-            | EthApiError::EthereumDataFormat(_)
-            | EthApiError::CalldataExceededLimit(_, _) => EthRpcErrorCode::InvalidParams,
-            EthApiError::Transaction(err) => err.into(),
-            EthApiError::Unsupported(_) => EthRpcErrorCode::InternalError,
-            EthApiError::Kakarot(err) => err.into(),
-        }
-    }
-}
-
 /// Error that can occur when interacting with the ETH Api.
 #[derive(Error)]
 pub enum EthApiError {
@@ -51,12 +40,37 @@ pub enum EthApiError {
     /// When an invalid block range is provided
     #[error("invalid block range")]
     InvalidBlockRange,
+    /// When the requested block range exceeds the maximum allowed range
+    #[error("query exceeds max block range {0}")]
+    BlockRangeLimitExceeded(u64),
+    /// When the logs matching a filter exceed the maximum number of logs of a response. The
+    /// block range of the logs which fit in a response is suggested.
+    #[error("query returned more than {limit} results, try with this block range [{from:#x}, {to:#x}]")]
+    LogsLimitExceeded { limit: u64, from: u64, to: u64 },
+    /// When the response exceeds the maximum size of a response, in bytes
+    #[error("response exceeds the maximum size of {0} bytes, use the paginated method")]
+    ResponseTooLarge(usize),
+    /// When a client exceeds its rate limit
+    #[error("rate limit exceeded")]
+    RateLimitExceeded,
+    /// When the reward percentiles are not monotonically increasing or out of the [0, 100] range
+    #[error("invalid reward percentiles")]
+    InvalidRewardPercentiles,
+    /// When a filter is not found or expired
+    #[error("filter not found: {0}")]
+    FilterNotFound(U64),
+    /// When a transaction is not found
+    #[error("transaction not found: {0}")]
+    TransactionNotFound(B256),
     /// Error related to transaction
     #[error("transaction error: {0}")]
     Transaction(#[from] TransactionError),
     /// Error related to signing
     #[error("signature error: {0}")]
     Signature(#[from] SignatureError),
+    /// When the node is not ready to serve requests
+    #[error("node not ready: {0}")]
+    NotReady(String),
     /// Unsupported feature
     #[error("unsupported: {0}")]
     Unsupported(&'static str),
@@ -69,11 +83,35 @@ pub enum EthApiError {
     /// Error related to transaction calldata being too large.
     #[error("calldata exceeded limit of {0}: {1}")]
     CalldataExceededLimit(u64, u64),
+    /// Too many token addresses in a token balances request
+    #[error("too many token addresses, maximum is {0}")]
+    TokenAddressesLimitExceeded(usize),
+    /// Too many calls in a call bundle
+    #[error("too many calls in bundle, maximum is {0}")]
+    CallBundleLimitExceeded(usize),
+    /// Tracing exceeded its timeout
+    #[error("tracing timed out")]
+    TracingTimeout,
+    /// State override setting both the state and the state diff of an account
+    #[error("account {0} has both 'state' and 'stateDiff'")]
+    InvalidStateOverride(Address),
+    /// Invalid parameters of a request
+    #[error("invalid params: {0}")]
+    InvalidParams(String),
+    /// When the call exceeds the timeout of its method
+    #[error("request timed out")]
+    RequestTimeout,
+    /// When the namespace of the method was disabled by an operator
+    #[error("namespace {0} is disabled")]
+    NamespaceDisabled(String),
 }
 
 impl std::fmt::Debug for EthApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::Kakarot(KakarotError::ProviderError(err)) if is_upstream_unavailable(err) => {
+                write!(f, "{}", FailoverTransportError::<HttpTransportError>::Unavailable)
+            }
             Self::Kakarot(KakarotError::ProviderError(err)) => {
                 write!(f, "starknet provider error: {:?}", err)
             }
@@ -82,14 +120,6 @@ impl std::fmt::Debug for EthApiError {
     }
 }
 
-/// Constructs a JSON-RPC error object, consisting of `code` and `message`.
-impl From<EthApiError> for ErrorObject<'static> {
-    fn from(value: EthApiError) -> Self {
-        let msg = format!("{:?}", value);
-        ErrorObject::owned(EthRpcErrorCode::from(value) as i32, msg, None::<()>)
-    }
-}
-
 /// Error related to the Kakarot eth provider
 /// which utilizes the starknet provider and
 /// a database internally.
@@ -110,6 +140,18 @@ pub enum KakarotError {
     /// Error related to a starknet call.
     #[error(transparent)]
     CallError(#[from] cainome::cairo_serde::Error),
+    /// Error related to the fetching of a starknet state proof.
+    #[error("starknet proof error: {0}")]
+    ProofError(String),
+    /// Error related to a call to the dev API of Katana.
+    #[error("katana dev api error: {0}")]
+    DevApiError(String),
+    /// Error related to a call forwarded to the Starknet provider.
+    #[error("starknet passthrough error: {0}")]
+    PassthroughError(String),
+    /// Error related to the indexing of the Starknet blocks.
+    #[error("indexer error: {0}")]
+    IndexerError(String),
 }
 
 impl From<KakarotError> for EthApiError {
@@ -118,15 +160,6 @@ impl From<KakarotError> for EthApiError {
     }
 }
 
-impl From<KakarotError> for EthRpcErrorCode {
-    fn from(value: KakarotError) -> Self {
-        match value {
-            KakarotError::ExecutionError(_) => EthRpcErrorCode::ExecutionError,
-            _ => EthRpcErrorCode::InternalError,
-        }
-    }
-}
-
 /// Error related to EVM execution.
 #[derive(Debug, Error)]
 pub enum EvmError {
@@ -134,9 +167,9 @@ pub enum EvmError {
     ValidationError,
     #[error("state modification error")]
     StateModificationError,
-    #[error("unknown opcode")]
+    #[error("invalid opcode")]
     UnknownOpcode,
-    #[error("invalid jump dest")]
+    #[error("invalid jump destination")]
     InvalidJumpDest,
     #[error("invalid caller")]
     NotKakarotEoaCaller,
@@ -162,6 +195,9 @@ pub enum EvmError {
     AddressCollision,
     #[error("out of gas")]
     OutOfGas,
+    /// The execution reverted, with the given revert data.
+    #[error("execution reverted")]
+    Revert(Bytes),
     #[error("{0}")]
     Other(String),
 }
@@ -172,16 +208,78 @@ impl From<EvmError> for KakarotError {
     }
 }
 
+impl EvmError {
+    /// Returns the decoded reason of a reverted execution, if its revert data is an
+    /// `Error(string)` or a `Panic(uint256)` payload. Custom errors are only returned as data.
+    pub fn revert_reason(&self) -> Option<String> {
+        match self {
+            Self::Revert(data) => decode_revert_reason(data),
+            _ => None,
+        }
+    }
+}
+
+/// Selector of the `Error(string)` revert payload.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the `Panic(uint256)` revert payload.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decodes the reason of an `Error(string)` or a `Panic(uint256)` revert payload.
+fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    // Reads the 32 bytes word at the offset as a usize
+    let word = |offset: usize| -> Option<usize> {
+        let word = data.get(offset..offset.checked_add(32)?)?;
+        let (high, low) = word.split_at(24);
+        if high.iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        usize::try_from(u64::from_be_bytes(low.try_into().ok()?)).ok()
+    };
+
+    if data.starts_with(&ERROR_SELECTOR) {
+        // The payload holds the offset of the string, its length and its padded bytes
+        let offset = word(4)?.checked_add(4)?;
+        let length = word(offset)?;
+        let start = offset.checked_add(32)?;
+        let reason = data.get(start..start.checked_add(length)?)?;
+        return String::from_utf8(reason.to_vec()).ok();
+    }
+    if data.starts_with(&PANIC_SELECTOR) {
+        let code = word(4)?;
+        return Some(format!("panic: {} (0x{code:02x})", panic_reason(code)));
+    }
+    None
+}
+
+/// Returns the description of the Solidity panic code.
+/// <https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require>
+const fn panic_reason(code: usize) -> &'static str {
+    match code {
+        0x00 => "generic panic",
+        0x01 => "assert(false)",
+        0x11 => "arithmetic underflow or overflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "enum overflow",
+        0x22 => "invalid encoded storage byte array accessed",
+        0x31 => "out-of-bounds array access; popping on an empty array",
+        0x32 => "out-of-bounds access of an array or bytesN",
+        0x41 => "out of memory",
+        0x51 => "uninitialized function",
+        _ => "unknown panic code",
+    }
+}
+
+/// Converts the return data of a failed Kakarot execution into an error. The return data holds
+/// one byte per felt: either the revert data of the EVM execution, or the message of an error
+/// raised by Kakarot itself, prefixed by `Kakarot: ` or `Precompile: `.
 impl From<Vec<FieldElement>> for EvmError {
     fn from(value: Vec<FieldElement>) -> Self {
-        let bytes = value.into_iter().filter_map(|x| u8::try_from(x).ok()).collect::<Vec<_>>();
-        let maybe_revert_reason = String::from_utf8(bytes.clone());
-        if maybe_revert_reason.is_err() {
-            return EvmError::Other(format!("{}", Bytes::from(bytes)));
-        }
+        let bytes = try_from_u8_iterator::<_, Vec<_>>(value);
+        let Ok(message) = std::str::from_utf8(&bytes) else {
+            return EvmError::Revert(bytes.into());
+        };
 
-        let revert_reason = maybe_revert_reason.unwrap(); // safe unwrap
-        let trimmed = revert_reason.trim_start_matches("Kakarot: ").trim_start_matches("Precompile: ");
+        let trimmed = message.trim_start_matches("Kakarot: ").trim_start_matches("Precompile: ");
         match trimmed {
             "eth validation failed" => EvmError::ValidationError,
             "StateModificationError" => EvmError::StateModificationError,
@@ -203,7 +301,9 @@ impl From<Vec<FieldElement>> for EvmError {
             "transfer amount exceeds balance" => EvmError::BalanceError,
             "AddressCollision" => EvmError::AddressCollision,
             s if s.contains("outOfGas") => EvmError::OutOfGas,
-            _ => EvmError::Other(format!("{}", Bytes::from(bytes))),
+            // Other errors raised by Kakarot, which aren't revert data
+            s if s.len() < message.len() => EvmError::Other(s.to_string()),
+            _ => EvmError::Revert(bytes.into()),
         }
     }
 }
@@ -224,16 +324,33 @@ pub enum TransactionError {
     /// Thrown if the tracing fails
     #[error("tracing error: {0}")]
     Tracing(Box<dyn std::error::Error + Send + Sync>),
-}
-
-impl From<TransactionError> for EthRpcErrorCode {
-    fn from(error: TransactionError) -> Self {
-        match error {
-            TransactionError::InvalidChainId => EthRpcErrorCode::InvalidInput,
-            TransactionError::GasOverflow => EthRpcErrorCode::TransactionRejected,
-            TransactionError::ExpectedFullTransactions | TransactionError::Tracing(_) => EthRpcErrorCode::InternalError,
-        }
-    }
+    /// Thrown when the max fee per gas is lower than the base fee.
+    #[error("max fee per gas less than block base fee: maxFeePerGas: {0}, baseFee: {1}")]
+    FeeCapTooLow(u128, u128),
+    /// Thrown when the max priority fee per gas is higher than the max fee per gas.
+    #[error("max priority fee per gas higher than max fee per gas: maxPriorityFeePerGas: {0}, maxFeePerGas: {1}")]
+    TipAboveFeeCap(u128, u128),
+    /// Thrown when the nonce of a transaction is lower than the nonce of its sender.
+    #[error("nonce too low: address {0}, tx: {1} state: {2}")]
+    NonceTooLow(Address, u64, u64),
+    /// Thrown when a transaction is already pending.
+    #[error("already known")]
+    AlreadyKnown,
+    /// Thrown when a transaction replacing a pending transaction doesn't bump its fees enough.
+    #[error("replacement transaction underpriced")]
+    ReplacementUnderpriced,
+    /// Thrown when an EIP-4844 blob transaction is sent, as Kakarot doesn't support blobs.
+    #[error("blob transactions unsupported on Kakarot")]
+    BlobTransactionUnsupported,
+    /// Thrown when the balance of the sender doesn't cover the cost of the transaction.
+    #[error("insufficient funds for gas * price + value: have {0} want {1}")]
+    InsufficientFunds(U256, U256),
+    /// Thrown when the account of the sender of a transaction couldn't be deployed.
+    #[error("failed to deploy the account of {0}: {1}")]
+    AccountDeploymentFailed(Address, String),
+    /// Thrown when the balances of all the relayers are below the minimum balance.
+    #[error("no relayer has enough funds")]
+    RelayersOutOfFunds,
 }
 
 /// Error related to signature.
@@ -251,6 +368,9 @@ pub enum SignatureError {
     /// Thrown when parity is invalid.
     #[error("invalid parity")]
     InvalidParity,
+    /// Thrown when the account isn't one of the signers of the RPC.
+    #[error("unknown account {0}")]
+    UnknownAccount(Address),
 }
 
 /// Error related to Ethereum data format.
@@ -273,16 +393,75 @@ pub enum EthereumDataFormatError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_primitives::U256;
+
+    /// Returns the felts of the bytes, as returned by Kakarot.
+    fn to_felts(bytes: &[u8]) -> Vec<FieldElement> {
+        bytes.iter().map(|byte| FieldElement::from(*byte)).collect()
+    }
+
+    #[test]
+    fn test_evm_error_from_revert_data() {
+        // Given
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend_from_slice(&U256::from(0x20).to_be_bytes::<32>());
+        data.extend_from_slice(&U256::from(4).to_be_bytes::<32>());
+        data.extend_from_slice(b"fail");
+        data.resize(4 + 3 * 32, 0);
+
+        // When
+        let err = EvmError::from(to_felts(&data));
+
+        // Then
+        assert!(matches!(&err, EvmError::Revert(revert) if revert.as_ref() == data.as_slice()));
+        assert_eq!(err.revert_reason(), Some("fail".to_string()));
+    }
 
     #[test]
-    fn test_assure_source_error_visible_in_kakarot_error() {
-        let err = KakarotError::ProviderError(starknet::providers::ProviderError::StarknetError(
-            starknet::core::types::StarknetError::UnexpectedError("test".to_string()),
+    fn test_evm_error_from_panic_data() {
+        // Given
+        let mut data = PANIC_SELECTOR.to_vec();
+        data.extend_from_slice(&U256::from(0x11).to_be_bytes::<32>());
+
+        // When
+        let err = EvmError::from(to_felts(&data));
+
+        // Then
+        assert_eq!(err.revert_reason(), Some("panic: arithmetic underflow or overflow (0x11)".to_string()));
+    }
+
+    #[test]
+    fn test_evm_error_from_custom_error_data() {
+        // Given
+        let data = [0xde, 0xad, 0xbe, 0xef];
+
+        // When
+        let err = EvmError::from(to_felts(&data));
+
+        // Then
+        assert!(matches!(&err, EvmError::Revert(revert) if revert.as_ref() == data.as_slice()));
+        assert_eq!(err.revert_reason(), None);
+    }
+
+    #[test]
+    fn test_evm_error_from_kakarot_error() {
+        assert!(matches!(EvmError::from(to_felts(b"Kakarot: StackOverflow")), EvmError::StackOverflow));
+        assert!(matches!(
+            EvmError::from(to_felts(b"Kakarot: value exceeds balance")),
+            EvmError::Other(message) if message == "value exceeds balance"
         ));
+    }
 
-        let eth_err: EthApiError = err.into();
-        let json_err: ErrorObject<'static> = eth_err.into();
+    #[test]
+    fn test_malformed_revert_reason() {
+        // Given
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend_from_slice(&U256::from(0x20).to_be_bytes::<32>());
+        data.extend_from_slice(&U256::from(64).to_be_bytes::<32>());
+        data.extend_from_slice(b"fail");
 
-        assert_eq!(json_err.message(), "starknet provider error: StarknetError(UnexpectedError(\"test\"))");
+        // Then
+        assert_eq!(decode_revert_reason(&data), None);
+        assert_eq!(decode_revert_reason(&ERROR_SELECTOR), None);
     }
 }
@@ -0,0 +1,148 @@
+use std::fmt;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use reth_primitives::U256;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::ResourcePrice;
+
+lazy_static! {
+    // Policy converting the Starknet gas price into the reported base fee: `oracle`, `passthrough`
+    // or `multiplier:<basis points>`.
+    pub static ref BASE_FEE_POLICY: BaseFeePolicy = BaseFeePolicy::from_str(
+        &std::env::var("BASE_FEE_POLICY").unwrap_or_else(|_| "oracle".to_string())
+    ).expect("failing to parse BASE_FEE_POLICY");
+}
+
+/// Number of basis points of a multiplier of 1.
+const BASIS_POINTS: u128 = 10_000;
+
+/// Policy converting the Starknet L1 gas price of a block into the EVM base fee reported in the
+/// blocks and by `eth_gasPrice`, which lets the operators add a markup to the Starknet price. The
+/// reported base fee is never below the base fee enforced by the Kakarot contract, so that the
+/// transactions priced at the reported base fee are accepted.
+/// The L1 data gas price isn't served by the version 0.6 of the Starknet JSON-RPC, and doesn't
+/// take part in the conversion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BaseFeePolicy {
+    /// The base fee set in the Kakarot contract, e.g. by a price oracle.
+    #[default]
+    Oracle,
+    /// The L1 gas price in wei.
+    Passthrough,
+    /// The L1 gas price in wei, multiplied by a number of basis points, e.g. 12000 for a markup of
+    /// 20%.
+    Multiplier { basis_points: u64 },
+}
+
+impl BaseFeePolicy {
+    /// Converts the L1 gas price of a Starknet block into the base fee of the block. Returns None
+    /// for the [`BaseFeePolicy::Oracle`] policy, whose base fee is read from the Kakarot contract.
+    pub fn convert_l1_gas_price(&self, l1_gas_price: &ResourcePrice) -> Option<u128> {
+        let price_in_wei: u128 = U256::from_be_bytes(l1_gas_price.price_in_wei.to_bytes_be()).saturating_to();
+        match self {
+            Self::Oracle => None,
+            Self::Passthrough => Some(price_in_wei),
+            Self::Multiplier { basis_points } => {
+                Some(price_in_wei.saturating_mul(u128::from(*basis_points)) / BASIS_POINTS)
+            }
+        }
+    }
+
+    /// Returns the base fee reported for a block with the given L1 gas price, whose base fee in the
+    /// Kakarot contract is `contract_base_fee`: the converted L1 gas price, raised to the base fee
+    /// of the contract if it is lower.
+    pub fn base_fee(&self, l1_gas_price: &ResourcePrice, contract_base_fee: u128) -> u128 {
+        self.convert_l1_gas_price(l1_gas_price).map_or(contract_base_fee, |base_fee| base_fee.max(contract_base_fee))
+    }
+}
+
+impl fmt::Display for BaseFeePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Oracle => write!(f, "oracle"),
+            Self::Passthrough => write!(f, "passthrough"),
+            Self::Multiplier { basis_points } => write!(f, "multiplier:{basis_points}"),
+        }
+    }
+}
+
+impl FromStr for BaseFeePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "oracle" => Ok(Self::Oracle),
+            "passthrough" => Ok(Self::Passthrough),
+            policy => policy
+                .strip_prefix("multiplier:")
+                .and_then(|basis_points| basis_points.parse().ok())
+                .map(|basis_points| Self::Multiplier { basis_points })
+                .ok_or_else(|| {
+                    format!("invalid base fee policy {s}, expected oracle, passthrough or multiplier:<bps>")
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::transaction::validate_transaction_fees;
+    use reth_primitives::{Signature, Transaction, TransactionSigned, TxEip1559};
+    use starknet::core::types::FieldElement;
+
+    #[test]
+    fn test_convert_l1_gas_price() {
+        // Given
+        let l1_gas_price =
+            ResourcePrice { price_in_wei: FieldElement::from(1_000_000_000u64), price_in_fri: FieldElement::ONE };
+
+        // When
+        let oracle = BaseFeePolicy::Oracle.convert_l1_gas_price(&l1_gas_price);
+        let passthrough = BaseFeePolicy::Passthrough.convert_l1_gas_price(&l1_gas_price);
+        let multiplier = BaseFeePolicy::Multiplier { basis_points: 12_000 }.convert_l1_gas_price(&l1_gas_price);
+
+        // Then
+        assert_eq!(oracle, None);
+        assert_eq!(passthrough, Some(1_000_000_000));
+        assert_eq!(multiplier, Some(1_200_000_000));
+    }
+
+    #[test]
+    fn test_base_fee_never_below_contract_base_fee() {
+        // Given
+        let l1_gas_price =
+            ResourcePrice { price_in_wei: FieldElement::from(1_000_000_000u64), price_in_fri: FieldElement::ONE };
+        let contract_base_fee = 2_000_000_000;
+        let policy = BaseFeePolicy::Passthrough;
+
+        // When
+        let reported = policy.base_fee(&l1_gas_price, contract_base_fee);
+        let transaction = Transaction::Eip1559(TxEip1559 {
+            max_fee_per_gas: reported,
+            max_priority_fee_per_gas: 0,
+            ..Default::default()
+        });
+        let transaction = TransactionSigned::from_transaction_and_signature(transaction, Signature::default());
+
+        // Then
+        assert_eq!(reported, contract_base_fee);
+        assert_eq!(
+            BaseFeePolicy::Multiplier { basis_points: 30_000 }.base_fee(&l1_gas_price, contract_base_fee),
+            3_000_000_000
+        );
+        assert!(validate_transaction_fees(&transaction, contract_base_fee).is_ok());
+    }
+
+    #[test]
+    fn test_base_fee_policy_round_trip() {
+        for policy in [BaseFeePolicy::Oracle, BaseFeePolicy::Passthrough, BaseFeePolicy::Multiplier { basis_points: 1 }]
+        {
+            assert_eq!(policy.to_string().parse::<BaseFeePolicy>(), Ok(policy));
+        }
+        assert!("multiplier:1.5".parse::<BaseFeePolicy>().is_err());
+        assert!("markup".parse::<BaseFeePolicy>().is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use starknet::core::types::{BlockId, BlockStatus, MaybePendingBlockWithTxHashes, StarknetError};
+use starknet::providers::ProviderError;
+use tokio::sync::Mutex;
+
+use super::error::{EthApiError, KakarotError};
+use super::provider::EthProviderResult;
+use super::utils::last_at_most;
+
+/// Duration during which the latest block accepted on L1 is reused before being searched again.
+/// Starknet blocks are accepted on L1 by batches, every few hours.
+const FINALITY_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Tracks the latest block accepted on L1, which is the block of the `safe` and `finalized` tags.
+/// As the L1 acceptance of the blocks is monotonic, the block is binary searched from the last
+/// known one, and the concurrent calls wait for a single search.
+#[derive(Debug, Default)]
+pub struct FinalityTracker {
+    state: Mutex<FinalityState>,
+}
+
+#[derive(Debug, Default)]
+struct FinalityState {
+    /// Latest block known to be accepted on L1.
+    finalized: Option<u64>,
+    /// Time of the last search.
+    refreshed_at: Option<Instant>,
+}
+
+impl FinalityTracker {
+    /// Returns the number of the latest block accepted on L1, up to the given latest block, or
+    /// None if no block is accepted on L1 yet, which is always the case on a devnet.
+    pub async fn finalized_block_number<SP>(
+        &self,
+        starknet_provider: &SP,
+        latest: u64,
+    ) -> EthProviderResult<Option<u64>>
+    where
+        SP: starknet::providers::Provider + Sync,
+    {
+        let mut state = self.state.lock().await;
+        if state.refreshed_at.is_some_and(|refreshed_at| refreshed_at.elapsed() < FINALITY_REFRESH_INTERVAL) {
+            return Ok(state.finalized.map(|finalized| finalized.min(latest)));
+        }
+
+        // The blocks accepted on L1 come first: the accepted blocks are mapped to 0 and the
+        // others to 1, and the last block mapped to 0 is searched
+        let low = state.finalized.unwrap_or_default();
+        let finalized = last_at_most(low, latest, 0, |block_number| async move {
+            Ok::<_, EthApiError>(u64::from(!is_accepted_on_l1(starknet_provider, block_number).await?))
+        })
+        .await?;
+
+        state.finalized = finalized.or(state.finalized);
+        state.refreshed_at = Some(Instant::now());
+        Ok(state.finalized.map(|finalized| finalized.min(latest)))
+    }
+}
+
+/// Returns true if the Starknet block is accepted on L1. The pending and unknown blocks aren't.
+async fn is_accepted_on_l1<SP>(starknet_provider: &SP, block_number: u64) -> EthProviderResult<bool>
+where
+    SP: starknet::providers::Provider + Sync,
+{
+    match starknet_provider.get_block_with_tx_hashes(BlockId::Number(block_number)).await {
+        Ok(MaybePendingBlockWithTxHashes::Block(block)) => Ok(block.status == BlockStatus::AcceptedOnL1),
+        Ok(MaybePendingBlockWithTxHashes::PendingBlock(_))
+        | Err(ProviderError::StarknetError(StarknetError::BlockNotFound)) => Ok(false),
+        Err(err) => Err(KakarotError::from(err).into()),
+    }
+}
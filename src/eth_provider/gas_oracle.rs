@@ -0,0 +1,140 @@
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    // Number of recent blocks sampled by the gas price oracle.
+    pub static ref GAS_PRICE_ORACLE_BLOCKS: u64 = u64::from_str(
+        &std::env::var("GAS_PRICE_ORACLE_BLOCKS").unwrap_or_else(|_| "20".to_string())
+    ).expect("failing to parse GAS_PRICE_ORACLE_BLOCKS");
+    // Percentile (between 0 and 100) of the sampled tips suggested by the gas price oracle.
+    pub static ref GAS_PRICE_ORACLE_PERCENTILE: f64 = {
+        let percentile = f64::from_str(
+            &std::env::var("GAS_PRICE_ORACLE_PERCENTILE").unwrap_or_else(|_| "60".to_string())
+        ).expect("failing to parse GAS_PRICE_ORACLE_PERCENTILE");
+        assert!((0. ..=100.).contains(&percentile), "GAS_PRICE_ORACLE_PERCENTILE must be between 0 and 100");
+        percentile
+    };
+}
+
+/// Number of lowest tips sampled in each block. Sampling the lowest tips rather than all of them
+/// prevents a few transactions paying large tips from driving the suggestion up.
+pub const GAS_PRICE_ORACLE_SAMPLES_PER_BLOCK: usize = 3;
+
+/// Configuration of the gas price oracle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasPriceOracleConfig {
+    /// Number of recent blocks sampled.
+    pub blocks: u64,
+    /// Percentile of the sampled tips which is suggested.
+    pub percentile: f64,
+}
+
+impl Default for GasPriceOracleConfig {
+    fn default() -> Self {
+        Self { blocks: *GAS_PRICE_ORACLE_BLOCKS, percentile: *GAS_PRICE_ORACLE_PERCENTILE }
+    }
+}
+
+/// Gas price oracle, suggesting the priority fee of the transactions from the tips paid in the
+/// recent blocks. The suggestion is cached until a new block is produced.
+#[derive(Debug, Default)]
+pub struct GasPriceOracle {
+    config: GasPriceOracleConfig,
+    /// Latest block number along with the tip suggested at this block.
+    last_suggestion: Mutex<Option<(u64, u128)>>,
+}
+
+impl GasPriceOracle {
+    /// Create a new [`GasPriceOracle`].
+    pub fn new(config: GasPriceOracleConfig) -> Self {
+        Self { config, last_suggestion: Mutex::new(None) }
+    }
+
+    /// Returns the configuration of the oracle.
+    pub const fn config(&self) -> &GasPriceOracleConfig {
+        &self.config
+    }
+
+    /// Returns the tip suggested at the given block, if it was already computed.
+    pub fn cached_tip(&self, block_number: u64) -> Option<u128> {
+        let last_suggestion = self.last_suggestion.lock().expect("Failed to lock gas price oracle");
+        last_suggestion.filter(|(number, _)| *number == block_number).map(|(_, tip)| tip)
+    }
+
+    /// Returns the last suggested tip, at any block.
+    pub fn last_tip(&self) -> Option<u128> {
+        self.last_suggestion.lock().expect("Failed to lock gas price oracle").map(|(_, tip)| tip)
+    }
+
+    /// Caches the tip suggested at the given block.
+    pub fn cache_tip(&self, block_number: u64, tip: u128) {
+        *self.last_suggestion.lock().expect("Failed to lock gas price oracle") = Some((block_number, tip));
+    }
+
+    /// Suggests a tip from the tips paid in each of the sampled blocks. Returns None if the sampled
+    /// blocks hold no transaction.
+    pub fn suggest_tip(&self, tips_per_block: Vec<Vec<u128>>) -> Option<u128> {
+        let mut samples = tips_per_block
+            .into_iter()
+            .flat_map(|mut tips| {
+                tips.sort_unstable();
+                tips.into_iter().take(GAS_PRICE_ORACLE_SAMPLES_PER_BLOCK)
+            })
+            .collect::<Vec<_>>();
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+
+        let index = ((samples.len() - 1) as f64 * self.config.percentile / 100.).round() as usize;
+        Some(samples[index.min(samples.len() - 1)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_tip() {
+        // Given
+        let oracle = GasPriceOracle::new(GasPriceOracleConfig { blocks: 20, percentile: 60. });
+        let tips_per_block = vec![vec![100, 1, 2, 3], vec![], vec![4, 5], vec![1_000, 6]];
+
+        // When
+        let tip = oracle.suggest_tip(tips_per_block);
+
+        // Then
+        // The samples are [1, 2, 3, 4, 5, 6, 1000], the 100 tip isn't sampled
+        assert_eq!(tip, Some(5));
+        assert_eq!(oracle.suggest_tip(vec![vec![], vec![]]), None);
+    }
+
+    #[test]
+    fn test_suggest_tip_percentiles() {
+        // Given
+        let tips_per_block = vec![vec![10], vec![20], vec![30]];
+        let oracle = |percentile| GasPriceOracle::new(GasPriceOracleConfig { blocks: 3, percentile });
+
+        // Then
+        assert_eq!(oracle(0.).suggest_tip(tips_per_block.clone()), Some(10));
+        assert_eq!(oracle(50.).suggest_tip(tips_per_block.clone()), Some(20));
+        assert_eq!(oracle(100.).suggest_tip(tips_per_block), Some(30));
+    }
+
+    #[test]
+    fn test_cached_tip() {
+        // Given
+        let oracle = GasPriceOracle::new(GasPriceOracleConfig { blocks: 20, percentile: 60. });
+
+        // When
+        oracle.cache_tip(10, 7);
+
+        // Then
+        assert_eq!(oracle.cached_tip(10), Some(7));
+        assert_eq!(oracle.cached_tip(11), None);
+        assert_eq!(oracle.last_tip(), Some(7));
+    }
+}
@@ -0,0 +1,711 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use alloy_rlp::{Decodable, Encodable};
+use futures::future::try_join_all;
+use lazy_static::lazy_static;
+use mongodb::bson::{doc, Document};
+use reth_primitives::constants::{EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH};
+use reth_primitives::proofs::{calculate_receipt_root, calculate_transaction_root};
+use reth_primitives::{
+    Address, Bloom, Receipt, ReceiptWithBloom, TransactionSigned, TransactionSignedEcRecovered, TxType, B256, B64, U256,
+};
+use reth_rpc_types::{Header, Log, ReceiptEnvelope, TransactionReceipt};
+use reth_rpc_types_compat::transaction::from_recovered;
+use serde::Serialize;
+use starknet::core::types::{
+    BlockId, BlockWithTxs, InvokeTransaction, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
+    MaybePendingTransactionReceipt, Transaction, TransactionReceipt as StarknetTransactionReceipt,
+};
+use starknet_crypto::FieldElement;
+use tokio::time::{sleep, Duration};
+
+use super::cache::ResponseCache;
+use super::constant::{BLOCK_NUMBER_HEX_STRING_LEN, DEFAULT_BLOCK_GAS_LIMIT, U64_HEX_STRING_LEN};
+use super::database::types::{
+    checkpoint::StoredIndexerCheckpoint, header::StoredHeader, internal_transaction::StoredInternalTransaction,
+    log::StoredLog, receipt::StoredTransactionReceipt, transaction::StoredTransaction, transfer::StoredTransfer,
+};
+use super::database::{CollectionName, Database};
+use super::error::KakarotError;
+use super::fee_policy::BASE_FEE_POLICY;
+use super::provider::EthProviderResult;
+use super::starknet::kakarot_core::{core::KakarotCoreReader, KAKAROT_ADDRESS};
+use super::utils::{format_hex, into_filter};
+use crate::eth_rpc::shutdown::ShutdownSignal;
+use crate::into_via_wrapper;
+use crate::models::felt::Felt252Wrapper;
+use crate::models::internal_transaction::InternalTransaction;
+use crate::models::log::{starknet_event_to_log, TRANSACTION_EXECUTED};
+use crate::models::transfer::TokenTransfer;
+
+lazy_static! {
+    // Interval between two polls of the Starknet chain by the indexer (in seconds)
+    pub static ref INDEXER_POLL_INTERVAL: u64 = u64::from_str(
+        &std::env::var("INDEXER_POLL_INTERVAL").unwrap_or_else(|_| "2".to_string())
+    ).expect("failing to parse INDEXER_POLL_INTERVAL");
+    // Starknet block from which the indexer starts when the database holds no checkpoint
+    pub static ref INDEXER_STARTING_BLOCK: u64 = u64::from_str(
+        &std::env::var("INDEXER_STARTING_BLOCK").unwrap_or_else(|_| "0".to_string())
+    ).expect("failing to parse INDEXER_STARTING_BLOCK");
+    // Maximum number of blocks the indexer walks back to find the last block which wasn't replaced by a reorg
+    pub static ref INDEXER_MAX_REORG_DEPTH: u64 = u64::from_str(
+        &std::env::var("INDEXER_MAX_REORG_DEPTH").unwrap_or_else(|_| "64".to_string())
+    ).expect("failing to parse INDEXER_MAX_REORG_DEPTH");
+}
+
+/// An Ethereum transaction extracted from a Starknet block, along with its execution result.
+#[derive(Debug)]
+struct IndexedTransaction {
+    transaction: TransactionSigned,
+    signer: Address,
+    status: bool,
+    gas_used: u128,
+    logs: Vec<reth_primitives::Log>,
+}
+
+/// Indexer converting the Starknet blocks into Ethereum blocks, transactions, receipts and logs,
+/// which are written to the database read by the provider.
+#[derive(Debug, Clone)]
+pub struct Indexer<SP: starknet::providers::Provider> {
+    database: Database,
+    starknet_provider: SP,
+    /// Cache of the responses of the provider, whose responses for the reorged blocks are dropped.
+    cache: Option<Arc<ResponseCache>>,
+}
+
+impl<SP> Indexer<SP>
+where
+    SP: starknet::providers::Provider + Send + Sync,
+{
+    pub const fn new(database: Database, starknet_provider: SP) -> Self {
+        Self { database, starknet_provider, cache: None }
+    }
+
+    /// Invalidates the cached responses of the blocks rolled back after a reorg.
+    pub fn with_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Indexes the Starknet blocks which were produced since the last checkpoint, writing the
+    /// checkpoint after each block. Returns the number of indexed blocks.
+    pub async fn index_new_blocks(&self) -> EthProviderResult<u64> {
+        self.rollback_reorged_blocks().await?;
+
+        let tip = self.starknet_provider.block_number().await.map_err(KakarotError::from)?;
+        let checkpoint = self.database.get_one::<StoredIndexerCheckpoint>(None, None).await?;
+        let start = checkpoint.map_or(*INDEXER_STARTING_BLOCK, |checkpoint| checkpoint.last_indexed_block + 1);
+
+        let mut indexed = 0;
+        for block_number in start..=tip {
+            if !self.index_block(block_number).await? {
+                break;
+            }
+            self.database
+                .update_one(StoredIndexerCheckpoint { last_indexed_block: block_number }, doc! {}, true)
+                .await?;
+            indexed += 1;
+        }
+
+        Ok(indexed)
+    }
+
+    /// Checks that the last indexed block is still part of the Starknet chain. Otherwise, walks
+    /// back to the last block which wasn't replaced by a reorg, rolls back the blocks after it,
+    /// invalidates their cached responses and moves the checkpoint to it. The walk is bounded by
+    /// [`INDEXER_MAX_REORG_DEPTH`]: a deeper reorg, or a gap in the indexed headers, is reported
+    /// as an error without rolling back any block.
+    pub async fn rollback_reorged_blocks(&self) -> EthProviderResult<()> {
+        let Some(checkpoint) = self.database.get_one::<StoredIndexerCheckpoint>(None, None).await? else {
+            return Ok(());
+        };
+
+        let mut block_number = checkpoint.last_indexed_block;
+        let deepest_block = block_number.saturating_sub(*INDEXER_MAX_REORG_DEPTH);
+        let common_ancestor = loop {
+            if self.is_canonical(block_number).await? {
+                break Some(block_number);
+            }
+            if block_number <= *INDEXER_STARTING_BLOCK {
+                break None;
+            }
+            if block_number <= deepest_block {
+                return Err(KakarotError::IndexerError(format!(
+                    "no block of the last {} indexed blocks is part of the Starknet chain",
+                    *INDEXER_MAX_REORG_DEPTH
+                ))
+                .into());
+            }
+            block_number -= 1;
+        };
+        if common_ancestor == Some(checkpoint.last_indexed_block) {
+            return Ok(());
+        }
+
+        let rollback_start = common_ancestor.map_or(block_number, |ancestor| ancestor + 1);
+        tracing::warn!("Reorg detected, rolling back the indexed blocks from {}", rollback_start);
+        self.database.rollback_from(rollback_start).await?;
+        if let Some(cache) = &self.cache {
+            cache.invalidate_from(rollback_start).await;
+        }
+        match common_ancestor {
+            Some(ancestor) => {
+                self.database
+                    .update_one(StoredIndexerCheckpoint { last_indexed_block: ancestor }, doc! {}, true)
+                    .await?
+            }
+            None => self.database.delete_one::<StoredIndexerCheckpoint>(doc! {}).await?,
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the indexed header of the block has the hash of the Starknet block at the
+    /// same height.
+    async fn is_canonical(&self, block_number: u64) -> EthProviderResult<bool> {
+        let filter = into_filter("header.number", &block_number, BLOCK_NUMBER_HEX_STRING_LEN);
+        let Some(stored) = self.database.get_one::<StoredHeader>(filter, None).await? else {
+            return Ok(false);
+        };
+
+        let block_hash = match self
+            .starknet_provider
+            .get_block_with_tx_hashes(BlockId::Number(block_number))
+            .await
+            .map_err(KakarotError::from)?
+        {
+            MaybePendingBlockWithTxHashes::Block(block) => B256::from(block.block_hash.to_bytes_be()),
+            MaybePendingBlockWithTxHashes::PendingBlock(_) => return Ok(false),
+        };
+
+        Ok(stored.header.hash == Some(block_hash))
+    }
+
+    /// Indexes a single Starknet block. All the writes are upserts, which makes it safe to index
+    /// a block again after an interruption. Returns false if the block isn't accepted yet.
+    pub async fn index_block(&self, block_number: u64) -> EthProviderResult<bool> {
+        let block = match self
+            .starknet_provider
+            .get_block_with_txs(BlockId::Number(block_number))
+            .await
+            .map_err(KakarotError::from)?
+        {
+            MaybePendingBlockWithTxs::Block(block) => block,
+            MaybePendingBlockWithTxs::PendingBlock(_) => return Ok(false),
+        };
+        let block_hash = B256::from(block.block_hash.to_bytes_be());
+
+        let transactions = try_join_all(block.transactions.iter().map(|tx| self.to_indexed_transaction(tx))).await?;
+
+        let mut cumulative_gas_used = 0u128;
+        let mut log_index = 0u64;
+        let mut logs_bloom = Bloom::ZERO;
+        let mut signed_transactions = Vec::new();
+        let mut receipts = Vec::new();
+        let mut rpc_transactions = Vec::new();
+        let mut rpc_receipts = Vec::new();
+
+        for (index, indexed) in transactions.into_iter().flatten().enumerate() {
+            let IndexedTransaction { transaction, signer, status, gas_used, logs } = indexed;
+            cumulative_gas_used = cumulative_gas_used.saturating_add(gas_used);
+
+            let receipt = Receipt {
+                tx_type: transaction.tx_type(),
+                success: status,
+                cumulative_gas_used: cumulative_gas_used as u64,
+                logs,
+            }
+            .with_bloom();
+            logs_bloom.accrue_bloom(&receipt.bloom);
+
+            let position = TransactionPosition {
+                block_hash,
+                block_number,
+                block_timestamp: block.timestamp,
+                transaction_index: index as u64,
+                log_index,
+            };
+            let (rpc_transaction, rpc_receipt) =
+                to_rpc_transaction_and_receipt(&transaction, signer, &receipt, gas_used, &position);
+            log_index += receipt.receipt.logs.len() as u64;
+
+            rpc_transactions.push(rpc_transaction);
+            rpc_receipts.push(rpc_receipt);
+            signed_transactions.push(transaction);
+            receipts.push(receipt);
+        }
+
+        let header = to_header(&block, block_hash, cumulative_gas_used, logs_bloom, &signed_transactions, &receipts);
+        let header = self.with_kakarot_fields(header).await;
+
+        // The parent of the block is the last indexed block, unless the indexing starts at it
+        let filter = into_filter("header.number", &block_number.saturating_sub(1), BLOCK_NUMBER_HEX_STRING_LEN);
+        let parent = match block_number {
+            0 => None,
+            _ => self.database.get_one::<StoredHeader>(filter, None).await?,
+        };
+        verify_parent_hash(&header, parent.as_ref().map(|parent| &parent.header))?;
+
+        write_block(&self.database, header, rpc_transactions, rpc_receipts).await?;
+
+        Ok(true)
+    }
+
+    /// Extracts the Ethereum transaction and its execution result from a Starknet transaction.
+    /// Returns None if the transaction isn't a Kakarot transaction or failed on Starknet.
+    async fn to_indexed_transaction(&self, transaction: &Transaction) -> EthProviderResult<Option<IndexedTransaction>> {
+        let Some((transaction_hash, transaction)) = kakarot_transaction(transaction) else {
+            return Ok(None);
+        };
+        let Some(signer) = transaction.recover_signer() else {
+            tracing::warn!("Failed to recover the signer of the Ethereum transaction of {:#x}", transaction_hash);
+            return Ok(None);
+        };
+
+        let events =
+            match self.starknet_provider.get_transaction_receipt(transaction_hash).await.map_err(KakarotError::from)? {
+                MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(receipt)) => receipt.events,
+                _ => return Ok(None),
+            };
+
+        // The execution result of the Ethereum transaction is held by the last two elements of
+        // the data of the `transaction_executed` event: the status and the gas used
+        let Some(executed) = events.iter().find(|event| event.keys.first() == Some(&*TRANSACTION_EXECUTED)) else {
+            return Ok(None);
+        };
+        let [.., status, gas_used] = executed.data.as_slice() else {
+            return Ok(None);
+        };
+        let status = *status != FieldElement::ZERO;
+        let gas_used = u128::try_from(U256::from(Felt252Wrapper::from(*gas_used))).unwrap_or(u128::MAX);
+
+        let logs = events.iter().filter_map(starknet_event_to_log).collect();
+
+        Ok(Some(IndexedTransaction { transaction, signer, status, gas_used, logs }))
+    }
+
+    /// Fills the fields of the header read from the Kakarot contract at the given block: the
+    /// coinbase, the base fee, which the base fee converted from the Starknet gas price is raised
+    /// to if it is lower, and the block gas limit.
+    async fn with_kakarot_fields(&self, mut header: Header) -> Header {
+        let block_id = BlockId::Number(header.number.unwrap_or_default());
+        let kakarot_contract = KakarotCoreReader::new(*KAKAROT_ADDRESS, &self.starknet_provider);
+
+        if let Ok(coinbase) = kakarot_contract.get_coinbase().block_id(block_id).call().await {
+            header.miner = Address::from_slice(&coinbase.coinbase.to_bytes_be()[12..]);
+        }
+        if let Ok(base_fee) = kakarot_contract.get_base_fee().block_id(block_id).call().await {
+            if let Ok(contract_base_fee) = u128::try_from(base_fee.base_fee) {
+                header.base_fee_per_gas =
+                    Some(header.base_fee_per_gas.map_or(contract_base_fee, |base_fee| base_fee.max(contract_base_fee)));
+            }
+        }
+        if let Ok(gas_limit) = kakarot_contract.get_block_gas_limit().block_id(block_id).call().await {
+            header.gas_limit = u128::try_from(gas_limit.block_gas_limit).unwrap_or(DEFAULT_BLOCK_GAS_LIMIT);
+        }
+
+        header
+    }
+}
+
+/// Position of a transaction in its block.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TransactionPosition {
+    pub(crate) block_hash: B256,
+    pub(crate) block_number: u64,
+    pub(crate) block_timestamp: u64,
+    pub(crate) transaction_index: u64,
+    /// Index in the block of the first log of the transaction.
+    pub(crate) log_index: u64,
+}
+
+/// Converts an Ethereum transaction and its receipt into their RPC representations.
+pub(crate) fn to_rpc_transaction_and_receipt(
+    transaction: &TransactionSigned,
+    signer: Address,
+    receipt: &ReceiptWithBloom,
+    gas_used: u128,
+    position: &TransactionPosition,
+) -> (reth_rpc_types::Transaction, TransactionReceipt) {
+    let TransactionPosition { block_hash, block_number, block_timestamp, transaction_index, log_index } = *position;
+
+    let rpc_logs = receipt
+        .receipt
+        .logs
+        .iter()
+        .zip(log_index..)
+        .map(|(log, log_index)| Log {
+            inner: log.clone(),
+            block_hash: Some(block_hash),
+            block_number: Some(block_number),
+            block_timestamp: Some(block_timestamp),
+            transaction_hash: Some(transaction.hash),
+            transaction_index: Some(transaction_index),
+            log_index: Some(log_index),
+            removed: false,
+        })
+        .collect::<Vec<_>>();
+
+    let mut rpc_transaction =
+        from_recovered(TransactionSignedEcRecovered::from_signed_transaction(transaction.clone(), signer));
+    rpc_transaction.block_hash = Some(block_hash);
+    rpc_transaction.block_number = Some(block_number);
+    rpc_transaction.transaction_index = Some(transaction_index);
+
+    let receipt_with_bloom = reth_rpc_types::ReceiptWithBloom {
+        receipt: reth_rpc_types::Receipt {
+            status: receipt.receipt.success,
+            cumulative_gas_used: receipt.receipt.cumulative_gas_used.into(),
+            logs: rpc_logs,
+        },
+        logs_bloom: receipt.bloom,
+    };
+    let rpc_receipt = TransactionReceipt {
+        transaction_hash: transaction.hash,
+        transaction_index: Some(transaction_index),
+        block_hash: Some(block_hash),
+        block_number: Some(block_number),
+        gas_used,
+        effective_gas_price: rpc_transaction.gas_price.unwrap_or_default(),
+        blob_gas_used: None,
+        blob_gas_price: None,
+        from: signer,
+        to: transaction.to(),
+        contract_address: transaction.to().is_none().then(|| signer.create(transaction.nonce())),
+        state_root: None,
+        inner: match transaction.tx_type() {
+            TxType::Legacy => ReceiptEnvelope::Legacy(receipt_with_bloom),
+            TxType::Eip2930 => ReceiptEnvelope::Eip2930(receipt_with_bloom),
+            TxType::Eip1559 => ReceiptEnvelope::Eip1559(receipt_with_bloom),
+            TxType::Eip4844 => ReceiptEnvelope::Eip4844(receipt_with_bloom),
+        },
+    };
+
+    (rpc_transaction, rpc_receipt)
+}
+
+/// Writes an Ethereum block to the database: its transactions, receipts, logs and the token
+/// transfers decoded from its logs, then its header. The header is written last, as the provider considers a block as indexed once its
+/// header is in the database. All the writes are upserts, which makes it safe to write a block
+/// again after an interruption.
+pub(crate) async fn write_block(
+    database: &Database,
+    header: Header,
+    transactions: Vec<reth_rpc_types::Transaction>,
+    receipts: Vec<TransactionReceipt>,
+) -> EthProviderResult<()> {
+    for transaction in transactions {
+        upsert(database, StoredTransaction::from(transaction), "tx", "hash", &["blockNumber", "transactionIndex"])
+            .await?;
+    }
+    for receipt in receipts {
+        for log in receipt.inner.logs() {
+            let filter = doc! {
+                "log.transactionHash": format!("{:#x}", receipt.transaction_hash),
+                "log.logIndex": format!("{:#x}", log.log_index.unwrap_or_default()),
+            };
+            let document = to_padded_document(&StoredLog::from(log.clone()), "log", &["blockNumber"])?;
+            database.upsert_document::<StoredLog>(document, filter).await?;
+
+            // The log index is padded as well, since the transfers are paginated by log index
+            if let Some(transfer) = TokenTransfer::from_log(log) {
+                let filter = doc! {
+                    "transfer.transactionHash": format!("{:#x}", transfer.transaction_hash),
+                    "transfer.logIndex": format_hex(transfer.log_index.to::<u64>(), U64_HEX_STRING_LEN),
+                };
+                let document =
+                    to_padded_document(&StoredTransfer::from(transfer), "transfer", &["blockNumber", "logIndex"])?;
+                database.upsert_document::<StoredTransfer>(document, filter).await?;
+            }
+        }
+        upsert(database, StoredTransactionReceipt { receipt }, "receipt", "transactionHash", &["blockNumber"]).await?;
+    }
+    upsert(database, StoredHeader { header }, "header", "number", &["number"]).await?;
+    Ok(())
+}
+
+/// Writes the internal transactions of a block to the database. The writes are upserts of the
+/// internal transactions identified by their block number and index, which makes it safe to write
+/// the internal transactions of a block again.
+pub(crate) async fn write_internal_transactions(
+    database: &Database,
+    internal_transactions: Vec<InternalTransaction>,
+) -> EthProviderResult<()> {
+    for internal_tx in internal_transactions {
+        let filter = doc! {
+            "internal_tx.blockNumber": format_hex(internal_tx.block_number.to::<u64>(), U64_HEX_STRING_LEN),
+            "internal_tx.index": format_hex(internal_tx.index.to::<u64>(), U64_HEX_STRING_LEN),
+        };
+        let document = to_padded_document(
+            &StoredInternalTransaction::from(internal_tx),
+            "internal_tx",
+            &["blockNumber", "transactionIndex", "index"],
+        )?;
+        database.upsert_document::<StoredInternalTransaction>(document, filter).await?;
+    }
+    Ok(())
+}
+
+/// Upserts a document, identified by the value at `key` in `prefix`, after padding its
+/// number fields.
+async fn upsert<T>(database: &Database, doc: T, prefix: &str, key: &str, numbers: &[&str]) -> EthProviderResult<()>
+where
+    T: Serialize + CollectionName,
+{
+    let document = to_padded_document(&doc, prefix, numbers)?;
+    let value = document.get_document(prefix).ok().and_then(|inner| inner.get(key).cloned()).unwrap_or_default();
+    let key = format!("{prefix}.{key}");
+    database.upsert_document::<T>(document, doc! {key: value}).await?;
+    Ok(())
+}
+
+/// Checks that the parent hash of the header links it to the header of its parent, when the parent
+/// is indexed and sealed. A broken link means the headers were converted or indexed inconsistently, which is
+/// reported before the header is written. A reorg between the indexing of the two blocks is
+/// reported as well, and rolled back by the next poll.
+fn verify_parent_hash(header: &Header, parent: Option<&Header>) -> Result<(), KakarotError> {
+    // The pending block is stored with a zero hash
+    let Some(parent) = parent.filter(|parent| !parent.hash.unwrap_or_default().is_zero()) else {
+        return Ok(());
+    };
+    if parent.hash == Some(header.parent_hash) {
+        return Ok(());
+    }
+    Err(KakarotError::IndexerError(format!(
+        "broken hash chain at block {}: the parent hash {} doesn't match the hash {} of the indexed block {}",
+        header.number.unwrap_or_default(),
+        header.parent_hash,
+        parent.hash.unwrap_or_default(),
+        parent.number.unwrap_or_default()
+    )))
+}
+
+/// Builds the Ethereum header of a Starknet block.
+fn to_header(
+    block: &BlockWithTxs,
+    block_hash: B256,
+    gas_used: u128,
+    logs_bloom: Bloom,
+    transactions: &[TransactionSigned],
+    receipts: &[ReceiptWithBloom],
+) -> Header {
+    Header {
+        hash: Some(block_hash),
+        parent_hash: B256::from(block.parent_hash.to_bytes_be()),
+        uncles_hash: EMPTY_OMMER_ROOT_HASH,
+        miner: Address::ZERO,
+        state_root: B256::from(block.new_root.to_bytes_be()),
+        transactions_root: calculate_transaction_root(transactions),
+        receipts_root: calculate_receipt_root(receipts),
+        logs_bloom,
+        difficulty: U256::ZERO,
+        number: Some(block.block_number),
+        gas_limit: DEFAULT_BLOCK_GAS_LIMIT,
+        gas_used,
+        timestamp: block.timestamp,
+        total_difficulty: Some(U256::ZERO),
+        extra_data: Default::default(),
+        mix_hash: Some(B256::ZERO),
+        nonce: Some(B64::ZERO),
+        // Raised to the base fee of the Kakarot contract if it is lower, with the Kakarot fields
+        base_fee_per_gas: BASE_FEE_POLICY.convert_l1_gas_price(&block.l1_gas_price),
+        withdrawals_root: Some(EMPTY_ROOT_HASH),
+        // The Cancun fields are set, as expected by the clients, but no blob gas is ever used
+        blob_gas_used: Some(0),
+        excess_blob_gas: Some(0),
+        parent_beacon_block_root: Some(B256::ZERO),
+    }
+}
+
+/// Serializes a document and pads the given number fields of its `prefix` sub-document to
+/// [`U64_HEX_STRING_LEN`] characters, which is the format expected by the filters of the provider.
+fn to_padded_document<T: Serialize>(doc: &T, prefix: &str, numbers: &[&str]) -> Result<Document, KakarotError> {
+    let mut document = mongodb::bson::to_document(doc).map_err(mongodb::error::Error::custom)?;
+    if let Ok(inner) = document.get_document_mut(prefix) {
+        for number in numbers {
+            if let Ok(value) = inner.get_str(number) {
+                let padded = format!("0x{:0>width$}", value.trim_start_matches("0x"), width = U64_HEX_STRING_LEN);
+                inner.insert(*number, padded);
+            }
+        }
+    }
+    Ok(document)
+}
+
+/// Splits the payload of a RLP list into its raw encoded items.
+fn rlp_list_items(mut payload: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut items = Vec::new();
+    while !payload.is_empty() {
+        let mut buf = payload;
+        let header = alloy_rlp::Header::decode(&mut buf).ok()?;
+        let length = payload.len() - buf.len() + header.payload_length;
+        items.push(payload.get(..length)?);
+        payload = &payload[length..];
+    }
+    Some(items)
+}
+
+/// Returns the hash of the Starknet transaction and the Ethereum transaction it wraps, if the
+/// Starknet transaction is a Kakarot transaction.
+pub(crate) fn kakarot_transaction(transaction: &Transaction) -> Option<(FieldElement, TransactionSigned)> {
+    let Transaction::Invoke(InvokeTransaction::V1(invoke)) = transaction else {
+        return None;
+    };
+    // The second calldata element is the address of the called contract
+    if invoke.calldata.get(1) != Some(&*KAKAROT_ADDRESS) {
+        return None;
+    }
+
+    let Some(transaction) = to_ethereum_transaction(&invoke.calldata, &invoke.signature) else {
+        tracing::warn!("Failed to decode the Ethereum transaction of {:#x}", invoke.transaction_hash);
+        return None;
+    };
+    Some((invoke.transaction_hash, transaction))
+}
+
+/// Decodes the Ethereum transaction sent to Kakarot from the calldata and the signature of the
+/// Starknet transaction. This is the inverse of
+/// [`to_starknet_transaction`](super::starknet::kakarot_core::to_starknet_transaction).
+fn to_ethereum_transaction(calldata: &[FieldElement], signature: &[FieldElement]) -> Option<TransactionSigned> {
+    // Only transactions holding a single call are sent to Kakarot
+    if calldata.first() != Some(&FieldElement::ONE) {
+        return None;
+    }
+    let [r_low, r_high, s_low, s_high, v] = signature else {
+        return None;
+    };
+    let to_u256 = |low: &FieldElement, high: &FieldElement| {
+        U256::from(into_via_wrapper!(*low)) + (U256::from(into_via_wrapper!(*high)) << 128)
+    };
+    let r: U256 = to_u256(r_low, r_high);
+    let s: U256 = to_u256(s_low, s_high);
+    let v: u64 = (*v).try_into().ok()?;
+
+    // Each felt following the call array holds a byte of the unsigned transaction
+    let data = calldata.get(6..)?.iter().map(|felt| u8::try_from(*felt).ok()).collect::<Option<Vec<_>>>()?;
+
+    // Typed transactions are prefixed by their type, legacy transactions are a RLP list
+    let (tx_type, payload) = match *data.first()? {
+        tx_type @ 0..=0x7f => (Some(tx_type), &data[1..]),
+        _ => (None, data.as_slice()),
+    };
+    let mut buf = payload;
+    let header = alloy_rlp::Header::decode(&mut buf).ok()?;
+    if !header.list {
+        return None;
+    }
+    let mut items = rlp_list_items(buf.get(..header.payload_length)?)?;
+    // The unsigned legacy transactions end with the EIP-155 fields (chain id, 0, 0), which are
+    // replaced by the signature
+    if tx_type.is_none() {
+        items.truncate(6);
+    }
+
+    let mut fields = items.concat();
+    v.encode(&mut fields);
+    r.encode(&mut fields);
+    s.encode(&mut fields);
+
+    let mut encoded = Vec::with_capacity(fields.len() + 10);
+    encoded.extend(tx_type);
+    alloy_rlp::Header { list: true, payload_length: fields.len() }.encode(&mut encoded);
+    encoded.extend_from_slice(&fields);
+
+    TransactionSigned::decode(&mut encoded.as_slice()).ok()
+}
+
+/// Runs the indexer, polling the Starknet chain for new blocks every [`INDEXER_POLL_INTERVAL`]
+/// seconds, until the shutdown signal is received. The indexing resumes from the last checkpoint
+/// written to the database.
+pub async fn start_indexer_service<SP>(indexer: Indexer<SP>, mut shutdown: ShutdownSignal)
+where
+    SP: starknet::providers::Provider + Send + Sync,
+{
+    loop {
+        match indexer.index_new_blocks().await {
+            Ok(0) => {}
+            Ok(indexed) => tracing::info!("Indexed {} blocks", indexed),
+            Err(err) => tracing::error!("Error while indexing blocks: {:?}", err),
+        }
+
+        tokio::select! {
+            _ = sleep(Duration::from_secs(*INDEXER_POLL_INTERVAL)) => {}
+            _ = shutdown.recv() => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth_provider::starknet::kakarot_core::to_starknet_transaction;
+    use reth_primitives::{sign_message, TransactionKind, TxEip1559, TxLegacy};
+    use starknet::core::types::BroadcastedInvokeTransaction;
+
+    fn assert_transaction_round_trip(transaction: reth_primitives::Transaction) {
+        // Given
+        let signature = sign_message(B256::with_last_byte(1), transaction.signature_hash()).unwrap();
+        let transaction = TransactionSigned::from_transaction_and_signature(transaction, signature);
+        let signer = transaction.recover_signer().unwrap();
+        let BroadcastedInvokeTransaction::V1(starknet_transaction) =
+            to_starknet_transaction(&transaction, 1, signer, 0).unwrap()
+        else {
+            panic!("Expected an invoke transaction v1");
+        };
+
+        // When
+        let decoded = to_ethereum_transaction(&starknet_transaction.calldata, &starknet_transaction.signature);
+
+        // Then
+        assert_eq!(decoded, Some(transaction));
+    }
+
+    #[test]
+    fn test_to_ethereum_transaction_legacy() {
+        assert_transaction_round_trip(reth_primitives::Transaction::Legacy(TxLegacy {
+            chain_id: Some(1),
+            nonce: 1,
+            gas_price: 10,
+            gas_limit: 21000,
+            to: TransactionKind::Call(Address::with_last_byte(1)),
+            value: U256::from(1000),
+            input: vec![1, 2, 3].into(),
+        }));
+    }
+
+    #[test]
+    fn test_to_ethereum_transaction_eip1559() {
+        assert_transaction_round_trip(reth_primitives::Transaction::Eip1559(TxEip1559 {
+            chain_id: 1,
+            nonce: 2,
+            gas_limit: 21000,
+            to: TransactionKind::Create,
+            value: U256::ZERO,
+            input: vec![0x60, 0x80].into(),
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: 1,
+            access_list: Default::default(),
+        }));
+    }
+
+    #[test]
+    fn test_verify_parent_hash() {
+        // Given
+        let parent = Header { hash: Some(B256::with_last_byte(1)), number: Some(1), ..Default::default() };
+        let pending = Header { hash: Some(B256::ZERO), number: Some(1), ..Default::default() };
+        let child = |parent_hash| Header { parent_hash, number: Some(2), ..Default::default() };
+
+        // When
+        let linked = verify_parent_hash(&child(B256::with_last_byte(1)), Some(&parent));
+        let broken = verify_parent_hash(&child(B256::with_last_byte(2)), Some(&parent));
+        let unindexed_parent = verify_parent_hash(&child(B256::with_last_byte(2)), None);
+        let pending_parent = verify_parent_hash(&child(B256::with_last_byte(2)), Some(&pending));
+
+        // Then
+        assert!(linked.is_ok());
+        assert!(matches!(broken, Err(KakarotError::IndexerError(_))));
+        assert!(unindexed_parent.is_ok());
+        assert!(pending_parent.is_ok());
+    }
+}
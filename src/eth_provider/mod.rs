@@ -1,8 +1,16 @@
+pub mod cache;
+pub mod cache_backend;
 pub mod constant;
 pub mod contracts;
 pub mod database;
 pub mod error;
+pub mod fee_policy;
+pub mod finality;
+pub mod gas_oracle;
+pub mod indexer;
 pub mod pending_pool;
 pub mod provider;
+pub mod pruning;
+pub mod single_flight;
 pub mod starknet;
 pub mod utils;
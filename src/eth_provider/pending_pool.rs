@@ -1,4 +1,5 @@
 use crate::eth_provider::provider::EthDataProvider;
+use crate::eth_rpc::shutdown::ShutdownSignal;
 use lazy_static::lazy_static;
 use std::str::FromStr;
 use std::time::Instant;
@@ -12,10 +13,22 @@ lazy_static! {
     ).expect("failing to parse RETRY_TX_INTERVAL");
 }
 
-pub async fn start_retry_service<SP>(eth_provider: EthDataProvider<SP>)
+/// Resubmits the pending transactions whose submission was interrupted at startup, then retries
+/// the stuck transactions every [`RETRY_TX_INTERVAL`] seconds, until the shutdown signal is
+/// received. A round of retries which is in progress is completed before returning.
+pub async fn start_retry_service<SP>(eth_provider: EthDataProvider<SP>, mut shutdown: ShutdownSignal)
 where
     SP: starknet::providers::Provider + Send + Sync,
 {
+    // The pending transactions accepted before the RPC stopped are resubmitted first
+    match eth_provider.recover_pending_transactions().await {
+        Ok(recovered) if !recovered.is_empty() => {
+            tracing::info!("Resubmitted {} pending transactions", recovered.len())
+        }
+        Ok(_) => {}
+        Err(err) => tracing::error!("Error while recovering pending transactions: {:?}", err),
+    }
+
     // Initialize last print time
     let mut last_print_time = Instant::now();
 
@@ -38,6 +51,9 @@ where
         }
 
         // pause
-        sleep(Duration::from_secs(*RETRY_TX_INTERVAL as u64)).await;
+        tokio::select! {
+            _ = sleep(Duration::from_secs(*RETRY_TX_INTERVAL as u64)) => {}
+            _ = shutdown.recv() => return,
+        }
     }
 }
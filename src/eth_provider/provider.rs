@@ -1,35 +1,65 @@
-use alloy_rlp::{Decodable, Encodable};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
 use async_trait::async_trait;
 use auto_impl::auto_impl;
 use cainome::cairo_serde::CairoArrayLegacy;
 use eyre::Result;
+use futures::future::OptionFuture;
+use futures::{StreamExt, TryStreamExt};
 use itertools::Itertools;
 use mongodb::bson::doc;
 use reth_primitives::constants::EMPTY_ROOT_HASH;
 use reth_primitives::serde_helper::{JsonStorageKey, U64HexOrNumber};
 use reth_primitives::{
-    Address, BlockId, BlockNumberOrTag, Bytes, TransactionSigned, TransactionSignedEcRecovered, B256, U256, U64,
+    keccak256, Address, BlockId, BlockNumberOrTag, Bytes, TransactionSigned, TransactionSignedEcRecovered, B256,
+    KECCAK_EMPTY, U256, U64,
 };
+use reth_rpc_types::request::TransactionInput;
+use reth_rpc_types::trace::parity::{AccountDiff, Delta, StateDiff};
 use reth_rpc_types::{
-    Block, BlockHashOrNumber, BlockTransactions, FeeHistory, Filter, FilterChanges, Header, Index, RichBlock,
-    TransactionReceipt, TransactionRequest, ValueOrArray,
+    Block, BlockHashOrNumber, BlockTransactions, EIP1186AccountProofResponse, EIP1186StorageProof, FeeHistory, Filter,
+    FilterChanges, Header, Index, Log, RichBlock, TransactionReceipt, TransactionRequest,
 };
 use reth_rpc_types::{SyncInfo, SyncStatus};
 use reth_rpc_types_compat::transaction::from_recovered;
-use starknet::core::types::SyncStatusType;
+use starknet::core::types::{
+    InvokeTransactionResult, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, MaybePendingStateUpdate,
+    MaybePendingTransactionReceipt, PriceUnit, SyncStatusType, TransactionReceipt as StarknetTransactionReceipt,
+};
 use starknet::core::utils::get_storage_var_address;
 use starknet_crypto::FieldElement;
 
+use super::cache::ResponseCache;
 use super::constant::{
-    ADDRESS_HEX_STRING_LEN, BLOCK_NUMBER_HEX_STRING_LEN, CALL_REQUEST_GAS_LIMIT, HASH_HEX_STRING_LEN,
-    LOGS_TOPICS_HEX_STRING_LEN, TRANSACTION_MAX_RETRIES, U64_HEX_STRING_LEN,
+    ADDRESS_HEX_STRING_LEN, BLOB_BASE_FEE, BLOCK_NUMBER_HEX_STRING_LEN, BLOCK_TRANSACTIONS_QUERY_CHUNK_SIZE,
+    BLOCK_TRANSACTIONS_QUERY_CONCURRENCY, CALL_REQUEST_GAS_LIMIT, EOA_EMPTY_CODE, ESTIMATE_GAS_ERROR_RATIO,
+    ESTIMATE_GAS_MARGIN_PERCENT, FAILED_TRANSACTION_RETENTION_BLOCKS, FEE_BREAKDOWN_QUERY_CONCURRENCY,
+    HASH_HEX_STRING_LEN, L1_MESSAGES_QUERY_CONCURRENCY, LOGS_QUERY_CHUNK_SIZE, LOGS_QUERY_CONCURRENCY,
+    LOGS_TOPICS_HEX_STRING_LEN, MAX_L1_MESSAGES_BLOCK_RANGE, MAX_LOGS_BLOCK_RANGE, MAX_LOGS_PER_RESPONSE,
+    STARKNET_PROOF_PROVIDER_URL, STATE_DIFF_QUERY_CONCURRENCY, SYNCING_BLOCK_LAG_THRESHOLD, TRANSACTION_MAX_RETRIES,
+    TRANSACTION_STUCK_BLOCKS, U64_HEX_STRING_LEN,
 };
+use super::contracts::nft::EthereumNft;
 use super::database::types::{
-    header::StoredHeader, log::StoredLog, receipt::StoredTransactionReceipt, transaction::StoredPendingTransaction,
-    transaction::StoredTransaction, transaction::StoredTransactionHash,
+    header::{StoredHeader, StoredHeaderLogsBloom},
+    internal_transaction::StoredInternalTransaction,
+    log::{StoredLog, StoredRemovedLog},
+    receipt::StoredTransactionReceipt,
+    transaction::StoredPendingTransaction,
+    transaction::StoredTransaction,
+    transaction::StoredTransactionHash,
+    transfer::StoredTransfer,
 };
 use super::database::{CollectionName, Database};
 use super::error::{EthApiError, EthereumDataFormatError, EvmError, KakarotError, SignatureError, TransactionError};
+use super::fee_policy::{BaseFeePolicy, BASE_FEE_POLICY};
+use super::finality::FinalityTracker;
+use super::gas_oracle::GasPriceOracle;
+use super::indexer::kakarot_transaction;
+use super::single_flight::InFlightRequests;
+use super::starknet::bytecode::{bytecode_info, fetch_bytecode};
+use super::starknet::deployer::EoaDeployer;
 use super::starknet::kakarot_core::{
     self,
     account_contract::AccountContractReader,
@@ -37,12 +67,30 @@ use super::starknet::kakarot_core::{
     core::{CallInput, Uint256},
     starknet_address, to_starknet_transaction, KAKAROT_ADDRESS,
 };
+use super::starknet::proof::get_starknet_proof;
+use super::starknet::state_diff::{delta, KakarotStateUpdate};
 use super::starknet::{ERC20Reader, STARKNET_NATIVE_TOKEN};
-use super::utils::{contract_not_found, entrypoint_not_found, into_filter, split_u256, try_from_u8_iterator};
+use super::utils::{
+    contract_not_found, entrypoint_not_found, filter_addresses_and_topics, into_filter, last_at_most, log_matches,
+    logs_bloom_matches, reward_percentiles, sender_and_nonce_filter, split_u256, try_from_u8_iterator,
+    with_transaction_hashes,
+};
 use crate::eth_provider::utils::format_hex;
-use crate::models::block::{EthBlockId, EthBlockNumberOrTag};
+use crate::models::account::{AccountType, EthAccount};
+use crate::models::block::{EthBlock, EthBlockNumberOrTag};
+use crate::models::fee::{BlockFeeBreakdown, FeeUnit, TransactionFeeBreakdown};
 use crate::models::felt::Felt252Wrapper;
-use crate::models::transaction::rpc_to_ec_recovered_transaction;
+use crate::models::internal_transaction::InternalTransaction;
+use crate::models::l1_message::L1Message;
+use crate::models::nft::{nft_balances, NftBalance};
+use crate::models::otterscan::SearchDirection;
+use crate::models::pagination::{Cursor, Page};
+use crate::models::receipt::normalize_block_receipts;
+use crate::models::transaction::{
+    decode_raw_transaction, rpc_to_ec_recovered_transaction, validate_replacement_fees, validate_transaction_balance,
+    validate_transaction_fees,
+};
+use crate::models::transfer::{TokenTransfer, TransferCategory, TransferFilter};
 use crate::{into_via_try_wrapper, into_via_wrapper};
 
 pub type EthProviderResult<T> = Result<T, EthApiError>;
@@ -99,12 +147,65 @@ pub trait EthereumProvider {
         index: JsonStorageKey,
         block_id: Option<BlockId>,
     ) -> EthProviderResult<B256>;
+    /// Returns the account and storage values of the address, along with their Starknet state proofs.
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<JsonStorageKey>,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<EIP1186AccountProofResponse>;
+    /// Returns the balance, nonce, code hash and storage root of the address, read concurrently
+    /// from the same block.
+    async fn account(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<EthAccount>;
     /// Returns the nonce for the address at the given block.
     async fn transaction_count(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<U256>;
     /// Returns the code for the address at the given block.
     async fn get_code(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<Bytes>;
     /// Returns the logs for the given filter.
     async fn get_logs(&self, filter: Filter) -> EthProviderResult<FilterChanges>;
+    /// Returns a page of the logs for the given filter, starting at the block of the cursor. Each
+    /// page holds the logs of whole blocks, so that the next page starts at the block of its cursor.
+    async fn logs_page(&self, filter: Filter, cursor: Option<Cursor>) -> EthProviderResult<Page<Log>>;
+    /// Returns the logs matching the filter which were emitted in the given blocks, before these
+    /// blocks were removed from the chain by a reorg. The logs are flagged as removed.
+    async fn removed_logs(&self, filter: Filter, block_hashes: Vec<B256>) -> EthProviderResult<Vec<Log>>;
+    /// Returns a page of at most `limit` token transfers matching the filter, starting at the
+    /// transfer of the cursor, sorted by block number and log index.
+    async fn token_transfers(
+        &self,
+        filter: TransferFilter,
+        cursor: Option<Cursor>,
+        limit: u64,
+    ) -> EthProviderResult<Page<TokenTransfer>>;
+    /// Returns a page of at most `limit` stored internal transactions sent or received by the
+    /// address in the block range, starting at the internal transaction of the cursor.
+    async fn internal_transactions(
+        &self,
+        address: Address,
+        from_block: u64,
+        to_block: u64,
+        cursor: Option<Cursor>,
+        limit: u64,
+    ) -> EthProviderResult<Page<InternalTransaction>>;
+    /// Returns the stored internal transactions of the transaction.
+    async fn transaction_internal_transactions(&self, hash: B256) -> EthProviderResult<Vec<InternalTransaction>>;
+    /// Returns the ERC721 and ERC1155 tokens held by the owner at the given block, derived from
+    /// the indexed transfers, optionally restricted to some token contracts.
+    async fn nft_balances(
+        &self,
+        owner: Address,
+        contract_addresses: Vec<Address>,
+        block_number: u64,
+    ) -> EthProviderResult<Vec<NftBalance>>;
+    /// Returns the metadata URI of the ERC721 or ERC1155 token at the given block, or None if
+    /// the contract doesn't implement the metadata extension of its standard.
+    async fn token_uri(
+        &self,
+        contract_address: Address,
+        token_id: U256,
+        category: TransferCategory,
+        block_number: u64,
+    ) -> EthProviderResult<Option<String>>;
     /// Returns the result of a call.
     async fn call(&self, request: TransactionRequest, block_id: Option<BlockId>) -> EthProviderResult<Bytes>;
     /// Returns the result of a estimate gas.
@@ -118,8 +219,18 @@ pub trait EthereumProvider {
     ) -> EthProviderResult<FeeHistory>;
     /// Send a raw transaction to the network and returns the transactions hash.
     async fn send_raw_transaction(&self, transaction: Bytes) -> EthProviderResult<B256>;
-    /// Returns the current gas price.
+    /// Send a transaction on behalf of the signer, without checking the signature of the transaction.
+    /// The transaction is only accepted by a Starknet node which doesn't validate the transactions,
+    /// such as Katana in dev mode.
+    async fn send_impersonated_transaction(
+        &self,
+        transaction: TransactionSigned,
+        signer: Address,
+    ) -> EthProviderResult<B256>;
+    /// Returns the current gas price, which is the base fee increased by the suggested priority fee.
     async fn gas_price(&self) -> EthProviderResult<U256>;
+    /// Returns the priority fee suggested by the gas price oracle from the tips paid in the recent blocks.
+    async fn max_priority_fee_per_gas(&self) -> EthProviderResult<U256>;
     /// Returns the block receipts for a block.
     async fn block_receipts(&self, block_id: Option<BlockId>) -> EthProviderResult<Option<Vec<TransactionReceipt>>>;
     /// Returns the transactions for a block.
@@ -127,6 +238,60 @@ pub trait EthereumProvider {
         &self,
         block_id: Option<BlockId>,
     ) -> EthProviderResult<Option<Vec<reth_rpc_types::Transaction>>>;
+    /// Returns the transactions that were sent but are not yet included in a block.
+    async fn pending_transactions(&self) -> EthProviderResult<Vec<reth_rpc_types::Transaction>>;
+    /// Adds the transactions to the pending transactions, from which they are resubmitted by the retry
+    /// service. Transactions which are already pending or included in a block are skipped. Returns the
+    /// number of transactions added.
+    async fn load_pending_transactions(
+        &self,
+        transactions: Vec<reth_rpc_types::Transaction>,
+    ) -> EthProviderResult<usize>;
+    /// Removes all the cached responses and returns their number.
+    fn flush_caches(&self) -> usize;
+    /// Returns the number of the latest block mined at or before the timestamp, or None if the
+    /// timestamp precedes the first indexed block.
+    async fn block_number_by_timestamp(&self, timestamp: u64) -> EthProviderResult<Option<u64>>;
+    /// Returns the EVM address of the Kakarot account deployed at the Starknet address, or None if
+    /// no Kakarot account is deployed at this address.
+    async fn evm_address(&self, address: FieldElement, block_id: Option<BlockId>)
+        -> EthProviderResult<Option<Address>>;
+    /// Returns the type of the Kakarot account of the address: undeployed, EOA or contract.
+    async fn account_type(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<AccountType>;
+    /// Returns the messages sent from L1 to the Kakarot contract and handled in the block range.
+    async fn l1_messages(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> EthProviderResult<Vec<L1Message>>;
+    /// Returns the EVM gas used and fee of each Kakarot transaction of the block, along with the
+    /// fee paid on Starknet for it. Returns None if the block isn't indexed.
+    async fn block_fee_breakdown(&self, block_id: BlockNumberOrTag) -> EthProviderResult<Option<BlockFeeBreakdown>>;
+    /// Returns the balance, nonce, code and storage changes of the Kakarot accounts in the block,
+    /// derived from the Starknet state update of the block. Returns None if the block isn't indexed.
+    async fn state_diff(&self, block_id: BlockNumberOrTag) -> EthProviderResult<Option<StateDiff>>;
+    /// Returns the transaction sent by the sender with the given nonce.
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U256,
+    ) -> EthProviderResult<Option<reth_rpc_types::Transaction>>;
+    /// Returns the revert data of the transaction, by replaying it on top of the state of its parent
+    /// block. Returns None if the transaction isn't included in a block or doesn't revert when replayed.
+    async fn revert_reason(&self, hash: B256) -> EthProviderResult<Option<Bytes>>;
+    /// Returns the receipt of the transaction which deployed the contract at the given address.
+    async fn contract_creation_receipt(&self, address: Address) -> EthProviderResult<Option<TransactionReceipt>>;
+    /// Returns the transactions sent from or to the address in the blocks before or after the given
+    /// block, sorted from the most recent to the oldest. At least `page_size` transactions are returned
+    /// if available, along with all the other transactions of the address in the last block reached.
+    /// Also returns whether there are more transactions of the address in the direction of the search.
+    async fn search_transactions(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+        direction: SearchDirection,
+    ) -> EthProviderResult<(Vec<reth_rpc_types::Transaction>, bool)>;
 }
 
 /// Structure that implements the EthereumProvider trait.
@@ -137,6 +302,18 @@ pub struct EthDataProvider<SP: starknet::providers::Provider> {
     database: Database,
     starknet_provider: SP,
     chain_id: u64,
+    cache: Arc<ResponseCache>,
+    /// Requests shared by the identical concurrent calls.
+    in_flight: Arc<InFlightRequests>,
+    /// Latest block accepted on L1, served for the `safe` and `finalized` tags.
+    finality: Arc<FinalityTracker>,
+    gas_price_oracle: Arc<GasPriceOracle>,
+    /// Deployer of the accounts of the senders of the first transactions, if configured.
+    eoa_deployer: Option<Arc<EoaDeployer>>,
+    /// Indexed block at the start of the current sync, if the database is lagging behind Starknet.
+    sync_starting_block: Arc<Mutex<Option<u64>>>,
+    /// HTTP client of the requests to the proof provider.
+    http_client: reqwest_011::Client,
 }
 
 impl<SP> EthDataProvider<SP>
@@ -147,6 +324,19 @@ where
     pub fn database(&self) -> &Database {
         &self.database
     }
+
+    /// Returns the cache of the responses, shared with the indexer which invalidates the responses
+    /// of the reorged blocks.
+    pub fn cache(&self) -> Arc<ResponseCache> {
+        self.cache.clone()
+    }
+
+    /// Sets the HTTP client of the requests to the proof provider, so that they share the pool of
+    /// connections of the Starknet transports.
+    pub fn with_http_client(mut self, http_client: reqwest_011::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
 }
 
 #[async_trait]
@@ -164,29 +354,37 @@ where
     }
 
     async fn block_number(&self) -> EthProviderResult<U64> {
-        let sort = doc! { "header.number": -1 };
-        let block_number = match self.database.get_one::<StoredHeader>(None, sort).await? {
+        let block_number = match self.indexed_block_number().await? {
             // In case the database is empty, use the starknet provider
-            None => U64::from(self.starknet_provider.block_number().await.map_err(KakarotError::from)?),
-            Some(header) => {
-                let number = header.header.number.ok_or(EthApiError::UnknownBlockNumber)?;
-                let is_pending_block = header.header.hash.unwrap_or_default().is_zero();
-                U64::from(if is_pending_block { number - 1 } else { number })
-            }
+            None => self.starknet_provider.block_number().await.map_err(KakarotError::from)?,
+            Some(number) => number,
         };
-        Ok(block_number)
+        Ok(U64::from(block_number))
     }
 
     async fn syncing(&self) -> EthProviderResult<SyncStatus> {
-        Ok(match self.starknet_provider.syncing().await.map_err(KakarotError::from)? {
-            SyncStatusType::NotSyncing => SyncStatus::None,
-            SyncStatusType::Syncing(data) => SyncStatus::Info(SyncInfo {
-                starting_block: U256::from(data.starting_block_num),
-                current_block: U256::from(data.current_block_num),
-                highest_block: U256::from(data.highest_block_num),
-                ..Default::default()
-            }),
-        })
+        // The highest block is the Starknet tip, or the highest block known by the
+        // Starknet provider if it is syncing itself
+        let highest_block = match self.starknet_provider.syncing().await.map_err(KakarotError::from)? {
+            SyncStatusType::Syncing(data) => data.highest_block_num,
+            SyncStatusType::NotSyncing => self.starknet_provider.block_number().await.map_err(KakarotError::from)?,
+        };
+        let current_block = self.indexed_block_number().await?.unwrap_or_default();
+
+        let mut starting_block = self.sync_starting_block.lock().expect("Failed to lock sync starting block");
+        if highest_block <= current_block + SYNCING_BLOCK_LAG_THRESHOLD {
+            *starting_block = None;
+            return Ok(SyncStatus::None);
+        }
+
+        // The starting block is the indexed block at the moment the lag was first detected
+        let starting_block = *starting_block.get_or_insert(current_block);
+        Ok(SyncStatus::Info(SyncInfo {
+            starting_block: U256::from(starting_block),
+            current_block: U256::from(current_block),
+            highest_block: U256::from(highest_block),
+            ..Default::default()
+        }))
     }
 
     async fn chain_id(&self) -> EthProviderResult<Option<U64>> {
@@ -202,6 +400,9 @@ where
         number_or_tag: BlockNumberOrTag,
         full: bool,
     ) -> EthProviderResult<Option<RichBlock>> {
+        if number_or_tag == BlockNumberOrTag::Pending {
+            return self.pending_block(full).await;
+        }
         let block_number = self.tag_into_block_number(number_or_tag).await?;
         Ok(self.block(block_number.into(), full).await?)
     }
@@ -242,7 +443,9 @@ where
                     "pipeline": [
                         {
                             "$match": {
-                                "tx.hash": format_hex(hash, HASH_HEX_STRING_LEN)
+                                "tx.hash": format_hex(hash, HASH_HEX_STRING_LEN),
+                                // Failed transactions were dropped
+                                "failed_block": null
                             }
                         }
                     ]
@@ -293,14 +496,10 @@ where
     }
 
     async fn transaction_receipt(&self, hash: B256) -> EthProviderResult<Option<TransactionReceipt>> {
-        Ok(self
-            .database
-            .get_one::<StoredTransactionReceipt>(
-                into_filter("receipt.transactionHash", &hash, HASH_HEX_STRING_LEN),
-                None,
-            )
-            .await?
-            .map(Into::into))
+        if let Some(receipt) = self.cache.receipts.get(&hash).await {
+            return Ok(Some(receipt));
+        }
+        self.in_flight.receipts.run(hash, || self.fetch_transaction_receipt(hash)).await
     }
 
     async fn balance(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<U256> {
@@ -351,6 +550,83 @@ where
         Ok(storage.into())
     }
 
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<JsonStorageKey>,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<EIP1186AccountProofResponse> {
+        let url = STARKNET_PROOF_PROVIDER_URL.as_ref().ok_or(EthApiError::Unsupported("eth_getProof"))?;
+
+        // Pin the latest block so that the values and the proofs are read from the same state
+        let block_id = match block_id {
+            None | Some(BlockId::Number(BlockNumberOrTag::Latest)) => {
+                BlockId::Number(BlockNumberOrTag::Number(self.block_number().await?.to()))
+            }
+            Some(block_id) => block_id,
+        };
+        let starknet_block_id = self.to_starknet_block_id(Some(block_id)).await?;
+
+        // Each EVM storage slot is stored in two consecutive Starknet storage slots (low and high)
+        let storage_addresses = keys
+            .iter()
+            .flat_map(|key| {
+                let keys = split_u256::<FieldElement>(key.0);
+                let low = get_storage_var_address("Account_storage", &keys).expect("Storage var name is not ASCII");
+                [low, low + FieldElement::ONE]
+            })
+            .collect::<Vec<_>>();
+        let proof = get_starknet_proof(
+            &self.http_client,
+            url,
+            starknet_block_id,
+            starknet_address(address),
+            &storage_addresses,
+        )
+        .await?;
+
+        let mut storage_proof = Vec::with_capacity(keys.len());
+        for (i, key) in keys.into_iter().enumerate() {
+            let value = self.storage_at(address, key, Some(block_id)).await?;
+            let mut nodes = proof.encode_storage_proof(2 * i);
+            nodes.extend(proof.encode_storage_proof(2 * i + 1));
+            storage_proof.push(EIP1186StorageProof { key, value: U256::from_be_bytes(value.0), proof: nodes });
+        }
+
+        let code = self.get_code(address, Some(block_id)).await?;
+        let storage_hash =
+            proof.contract_data.as_ref().map_or(EMPTY_ROOT_HASH, |data| B256::from_slice(&data.root.to_bytes_be()));
+
+        Ok(EIP1186AccountProofResponse {
+            address,
+            balance: self.balance(address, Some(block_id)).await?,
+            code_hash: keccak256(code),
+            nonce: self.transaction_count(address, Some(block_id)).await?.to(),
+            storage_hash,
+            account_proof: proof.encode_contract_proof(),
+            storage_proof,
+        })
+    }
+
+    async fn account(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<EthAccount> {
+        // Pin the latest block so that the fields are read from the same state
+        let block_id = match block_id {
+            None | Some(BlockId::Number(BlockNumberOrTag::Latest)) => {
+                BlockId::Number(BlockNumberOrTag::Number(self.block_number().await?.to()))
+            }
+            Some(block_id) => block_id,
+        };
+
+        let (balance, nonce, code_hash, storage_root) = futures::try_join!(
+            self.balance(address, Some(block_id)),
+            self.transaction_count(address, Some(block_id)),
+            self.code_hash(address, block_id),
+            self.storage_root(address, block_id),
+        )?;
+
+        Ok(EthAccount { code_hash, storage_root, balance, nonce: nonce.to() })
+    }
+
     async fn transaction_count(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<U256> {
         let starknet_block_id = self.to_starknet_block_id(block_id).await?;
 
@@ -368,75 +644,261 @@ where
         // but the protocol nonce is still incremented.
         let protocol_nonce = self.starknet_provider.get_nonce(starknet_block_id, address).await.unwrap_or_default();
         let nonce = nonce.max(protocol_nonce);
+        let nonce: U256 = into_via_wrapper!(nonce);
 
-        Ok(into_via_wrapper!(nonce))
+        if block_id == Some(BlockId::Number(BlockNumberOrTag::Pending)) {
+            return self.pending_nonce(address, nonce).await;
+        }
+        Ok(nonce)
     }
 
     async fn get_code(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<Bytes> {
         let starknet_block_id = self.to_starknet_block_id(block_id).await?;
 
-        let address = starknet_address(address);
-        let account_contract = AccountContractReader::new(address, &self.starknet_provider);
-        let bytecode = account_contract.bytecode().block_id(starknet_block_id).call().await;
-
-        if contract_not_found(&bytecode) || entrypoint_not_found(&bytecode) {
-            return Ok(Bytes::default());
+        // The code at a sealed block can't change, only cache the code for these blocks
+        let cache_key = match starknet_block_id {
+            starknet::core::types::BlockId::Number(number) => Some((address, number)),
+            _ => None,
+        };
+        if let Some(key) = &cache_key {
+            if let Some(code) = self.cache.code.get(key).await {
+                return Ok(code);
+            }
         }
 
-        let bytecode = bytecode.map_err(KakarotError::from)?.bytecode.0;
-        Ok(Bytes::from(try_from_u8_iterator::<_, Vec<u8>>(bytecode)))
+        let mut code = self.account_code(address, starknet_block_id).await?.unwrap_or_default();
+        // The EOAs have no code in the EVM, whatever their Starknet account holds
+        if *EOA_EMPTY_CODE && !code.is_empty() && self.is_sender(address).await? {
+            code = Bytes::default();
+        }
+        if let Some(key) = cache_key {
+            self.cache.code.insert(key, code.clone()).await;
+        }
+        Ok(code)
     }
 
     async fn get_logs(&self, filter: Filter) -> EthProviderResult<FilterChanges> {
-        let current_block = self.block_number().await?.try_into().map_err(|_| EthApiError::UnknownBlockNumber)?;
-        let from = filter.get_from_block().unwrap_or_default();
-        let to = filter.get_to_block().unwrap_or(current_block);
+        let Some((from, to)) = self.logs_block_range(&filter).await? else {
+            return Ok(FilterChanges::Empty);
+        };
+
+        let max_block_range = *MAX_LOGS_BLOCK_RANGE;
+        if max_block_range != 0 && to - from + 1 > max_block_range {
+            return Err(EthApiError::BlockRangeLimitExceeded(max_block_range));
+        }
+
+        let max_logs = *MAX_LOGS_PER_RESPONSE;
+        let (logs, next_block) = self.logs_in_range(&filter, from, to, max_logs).await?;
+        if let Some(next_block) = next_block {
+            return Err(EthApiError::LogsLimitExceeded { limit: max_logs, from, to: next_block - 1 });
+        }
+
+        Ok(FilterChanges::Logs(logs))
+    }
 
-        let (from, to) = match (from, to) {
-            (from, _) if from > current_block => return Ok(FilterChanges::Empty),
-            (from, to) if to > current_block => (from, current_block),
-            (from, to) if to < from => return Ok(FilterChanges::Empty),
-            _ => (from, to),
+    async fn logs_page(&self, filter: Filter, cursor: Option<Cursor>) -> EthProviderResult<Page<Log>> {
+        let Some((from, to)) = self.logs_block_range(&filter).await? else {
+            return Ok(Page { items: Vec::new(), next_cursor: None });
         };
+        let from = cursor.map_or(from, |cursor| cursor.block_number.max(from));
+        if from > to {
+            return Ok(Page { items: Vec::new(), next_cursor: None });
+        }
 
-        // Convert the topics to a vector of B256
-        let topics = filter
-            .topics
-            .into_iter()
-            .filter_map(|t| t.to_value_or_array())
-            .flat_map(|t| match t {
-                ValueOrArray::Value(topic) => vec![topic],
-                ValueOrArray::Array(topics) => topics,
-            })
-            .collect::<Vec<_>>();
+        // A page covers at most the maximum block range
+        let max_block_range = *MAX_LOGS_BLOCK_RANGE;
+        let page_to = if max_block_range == 0 { to } else { to.min(from.saturating_add(max_block_range - 1)) };
+
+        let (logs, next_block) = self.logs_in_range(&filter, from, page_to, *MAX_LOGS_PER_RESPONSE).await?;
+        let next_block = next_block.or(Some(page_to + 1)).filter(|next_block| *next_block <= to);
+
+        Ok(Page { items: logs, next_cursor: next_block.map(|block_number| Cursor::new(block_number, 0)) })
+    }
+
+    async fn token_transfers(
+        &self,
+        filter: TransferFilter,
+        cursor: Option<Cursor>,
+        limit: u64,
+    ) -> EthProviderResult<Page<TokenTransfer>> {
+        let padded = |number: u64| format_hex(number, U64_HEX_STRING_LEN);
+        let address = |address: Address| format_hex(address, ADDRESS_HEX_STRING_LEN);
 
-        // Create the database filter. We filter by block number using $gte and $lte,
-        // and by topics using $expr and $eq. The topics query will:
-        // 1. Slice the topics array to the same length as the filter topics
-        // 2. Match on values for which the sliced topics equal the filter topics
         let mut database_filter = doc! {
-            "log.blockNumber": {"$gte": format_hex(from, BLOCK_NUMBER_HEX_STRING_LEN), "$lte": format_hex(to, BLOCK_NUMBER_HEX_STRING_LEN)},
-            "$expr": {
-                "$eq": [
-                  { "$slice": ["$log.topics", topics.len() as i32] },
-                  topics.into_iter().map(|t| format_hex(t, LOGS_TOPICS_HEX_STRING_LEN)).collect::<Vec<_>>()
-                ]
-              }
+            "transfer.blockNumber": {"$gte": padded(filter.from_block), "$lte": padded(filter.to_block)}
         };
+        if let Some(from) = filter.from_address {
+            database_filter.insert("transfer.from", address(from));
+        }
+        if let Some(to) = filter.to_address {
+            database_filter.insert("transfer.to", address(to));
+        }
+        if !filter.contract_addresses.is_empty() {
+            let addresses = filter.contract_addresses.into_iter().map(address).collect::<Vec<_>>();
+            database_filter.insert("transfer.address", doc! {"$in": addresses});
+        }
+        if !filter.categories.is_empty() {
+            let categories = filter.categories.iter().map(|category| category.as_str()).collect::<Vec<_>>();
+            database_filter.insert("transfer.category", doc! {"$in": categories});
+        }
+        if filter.exclude_zero_value {
+            database_filter.insert("transfer.value", doc! {"$ne": "0x0"});
+        }
 
-        // Add the address filter if any
-        let addresses = filter.address.to_value_or_array().map(|a| match a {
-            ValueOrArray::Value(address) => vec![address],
-            ValueOrArray::Array(addresses) => addresses,
-        });
-        addresses.map(|adds| {
-            database_filter.insert(
-                "log.address",
-                doc! {"$in": adds.into_iter().map(|a| format_hex(a, ADDRESS_HEX_STRING_LEN)).collect::<Vec<_>>()},
-            )
-        });
+        let mut conditions = Vec::new();
+        // The ERC721 transfers hold the token id, and the ERC1155 transfers the ids of their tokens
+        if let Some(token_id) = filter.token_id {
+            let token_id = format!("{token_id:#x}");
+            conditions
+                .push(doc! {"$or": [{"transfer.tokenId": &token_id}, {"transfer.erc1155Metadata.tokenId": &token_id}]});
+        }
+        // The page starts at the transfer of the cursor, in the direction of the order
+        let (operator, order) = if filter.descending { ("$lt", -1) } else { ("$gt", 1) };
+        if let Some(cursor) = cursor {
+            let inclusive_operator = if filter.descending { "$lte" } else { "$gte" };
+            conditions.push(doc! {"$or": [
+                {"transfer.blockNumber": {operator: padded(cursor.block_number)}},
+                {
+                    "transfer.blockNumber": padded(cursor.block_number),
+                    "transfer.logIndex": {inclusive_operator: padded(cursor.index)}
+                },
+            ]});
+        }
+        if !conditions.is_empty() {
+            database_filter.insert("$and", conditions);
+        }
+
+        // One more transfer is queried, which is the start of the next page
+        let sort = doc! {"transfer.blockNumber": order, "transfer.logIndex": order};
+        let limit = limit.max(1);
+        let mut transfers: Vec<TokenTransfer> = self
+            .database
+            .get_sorted::<StoredTransfer>(database_filter, sort, i64::try_from(limit + 1).unwrap_or(i64::MAX))
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let next_cursor = if transfers.len() as u64 > limit { transfers.pop().map(|t| t.cursor()) } else { None };
+        Ok(Page { items: transfers, next_cursor })
+    }
+
+    async fn internal_transactions(
+        &self,
+        address: Address,
+        from_block: u64,
+        to_block: u64,
+        cursor: Option<Cursor>,
+        limit: u64,
+    ) -> EthProviderResult<Page<InternalTransaction>> {
+        let padded = |number: u64| format_hex(number, U64_HEX_STRING_LEN);
+        let address = format_hex(address, ADDRESS_HEX_STRING_LEN);
+        let from_block = cursor.map_or(from_block, |cursor| cursor.block_number.max(from_block));
+
+        let mut filter = doc! {
+            "internal_tx.blockNumber": {"$gte": padded(from_block), "$lte": padded(to_block)},
+        };
+        let mut conditions = vec![doc! {"$or": [{"internal_tx.from": &address}, {"internal_tx.to": &address}]}];
+        // The page starts at the internal transaction of the cursor
+        if let Some(cursor) = cursor {
+            conditions.push(doc! {"$or": [
+                {"internal_tx.blockNumber": {"$gt": padded(cursor.block_number)}},
+                {
+                    "internal_tx.blockNumber": padded(cursor.block_number),
+                    "internal_tx.index": {"$gte": padded(cursor.index)}
+                },
+            ]});
+        }
+        filter.insert("$and", conditions);
+
+        // One more internal transaction is queried, which is the start of the next page
+        let sort = doc! {"internal_tx.blockNumber": 1, "internal_tx.index": 1};
+        let limit = limit.max(1);
+        let mut internal_transactions: Vec<InternalTransaction> = self
+            .database
+            .get_sorted::<StoredInternalTransaction>(filter, sort, i64::try_from(limit + 1).unwrap_or(i64::MAX))
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let next_cursor = if internal_transactions.len() as u64 > limit {
+            internal_transactions.pop().map(|internal_tx| internal_tx.cursor())
+        } else {
+            None
+        };
+        Ok(Page { items: internal_transactions, next_cursor })
+    }
+
+    async fn transaction_internal_transactions(&self, hash: B256) -> EthProviderResult<Vec<InternalTransaction>> {
+        let filter = into_filter("internal_tx.transactionHash", &hash, HASH_HEX_STRING_LEN);
+        let mut internal_transactions: Vec<InternalTransaction> =
+            self.database.get_and_map_to::<InternalTransaction, StoredInternalTransaction>(filter, None).await?;
+        internal_transactions.sort_by_key(|internal_tx| internal_tx.index);
+        Ok(internal_transactions)
+    }
 
-        Ok(FilterChanges::Logs(self.database.get_and_map_to::<_, StoredLog>(database_filter, None).await?))
+    async fn nft_balances(
+        &self,
+        owner: Address,
+        contract_addresses: Vec<Address>,
+        block_number: u64,
+    ) -> EthProviderResult<Vec<NftBalance>> {
+        let owner_hex = format_hex(owner, ADDRESS_HEX_STRING_LEN);
+        let mut filter = doc! {
+            "$or": [{"transfer.from": &owner_hex}, {"transfer.to": &owner_hex}],
+            "transfer.category": {"$in": [TransferCategory::Erc721.as_str(), TransferCategory::Erc1155.as_str()]},
+            "transfer.blockNumber": {"$lte": format_hex(block_number, U64_HEX_STRING_LEN)},
+        };
+        if !contract_addresses.is_empty() {
+            let addresses = contract_addresses
+                .iter()
+                .map(|address| format_hex(address, ADDRESS_HEX_STRING_LEN))
+                .collect::<Vec<_>>();
+            filter.insert("transfer.address", doc! {"$in": addresses});
+        }
+
+        // The balances are replayed from the transfers of the owner in the order of the chain
+        let sort = doc! {"transfer.blockNumber": 1, "transfer.logIndex": 1};
+        let transfers = self.database.get_sorted::<StoredTransfer>(filter, sort, None).await?;
+        Ok(nft_balances(owner, transfers.into_iter().map(Into::into)))
+    }
+
+    async fn token_uri(
+        &self,
+        contract_address: Address,
+        token_id: U256,
+        category: TransferCategory,
+        block_number: u64,
+    ) -> EthProviderResult<Option<String>> {
+        let key = (contract_address, token_id, block_number);
+        if let Some(uri) = self.cache.token_uris.get(&key) {
+            return Ok(Some(uri));
+        }
+
+        let block_id = BlockId::Number(BlockNumberOrTag::Number(block_number));
+        let uri = EthereumNft::new(contract_address, self).token_uri(token_id, category, block_id).await?;
+        if let Some(uri) = &uri {
+            self.cache.token_uris.insert(key, uri.clone());
+        }
+        Ok(uri)
+    }
+
+    async fn removed_logs(&self, filter: Filter, block_hashes: Vec<B256>) -> EthProviderResult<Vec<Log>> {
+        if block_hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let hashes = block_hashes.iter().map(|hash| format_hex(hash, HASH_HEX_STRING_LEN)).collect::<Vec<_>>();
+        let logs: Vec<Log> =
+            self.database.get_and_map_to::<_, StoredRemovedLog>(doc! {"log.blockHash": {"$in": hashes}}, None).await?;
+
+        let (addresses, topics) = filter_addresses_and_topics(&filter);
+        Ok(logs
+            .into_iter()
+            .filter(|log| log_matches(&log.inner, &addresses, &topics))
+            .map(|log| Log { removed: true, ..log })
+            .collect())
     }
 
     async fn call(&self, request: TransactionRequest, block_id: Option<BlockId>) -> EthProviderResult<Bytes> {
@@ -445,28 +907,67 @@ where
     }
 
     async fn estimate_gas(&self, request: TransactionRequest, block_id: Option<BlockId>) -> EthProviderResult<U256> {
+        // The block and the call input are resolved once, so that all the executions run against the
+        // same state and the nonce of the sender isn't fetched again for each of them.
         // Set a high gas limit to make sure the transaction will not fail due to gas.
-        let request = TransactionRequest { gas: Some(u64::MAX as u128), ..request };
+        let starknet_block_id = self.to_starknet_block_id(block_id).await?;
+        let call_input = self
+            .prepare_call_input(TransactionRequest { gas: Some(u64::MAX as u128), ..request.clone() }, block_id)
+            .await?;
+        let required_gas = self.estimate_gas_helper(call_input.clone(), starknet_block_id).await?;
+
+        // The gas required by Kakarot is a lower bound of the gas limit: the refunds
+        // and the 63/64 rule can make the execution fail with a gas limit equal to the gas used.
+        let mut lowest = required_gas;
+        if self.execute_with_gas_limit(&call_input, lowest, starknet_block_id).await.is_ok() {
+            return Ok(U256::from(lowest));
+        }
+
+        // The upper bound is the required gas with a margin, which covers most executions. Otherwise
+        // it is the gas limit of the request, or the gas limit of the calls, whose execution is
+        // expected to succeed, otherwise its error is returned.
+        let margin = required_gas.saturating_mul(ESTIMATE_GAS_MARGIN_PERCENT) / 100;
+        let limit = request.gas.unwrap_or_else(|| CALL_REQUEST_GAS_LIMIT.max(required_gas.saturating_mul(2)));
+        let mut highest = required_gas.saturating_add(margin).min(limit);
+        if self.execute_with_gas_limit(&call_input, highest, starknet_block_id).await.is_ok() {
+            lowest += 1;
+        } else {
+            lowest = highest + 1;
+            highest = limit;
+            self.execute_with_gas_limit(&call_input, highest, starknet_block_id).await?;
+        }
 
-        let gas_used = self.estimate_gas_helper(request, block_id).await?;
+        // Binary search the lowest gas limit for which the execution succeeds,
+        // stopping once the estimate is within the error ratio.
+        while lowest < highest && (highest - lowest) as f64 / highest as f64 > ESTIMATE_GAS_ERROR_RATIO {
+            let mid = lowest + (highest - lowest) / 2;
+            if self.execute_with_gas_limit(&call_input, mid, starknet_block_id).await.is_ok() {
+                highest = mid;
+            } else {
+                lowest = mid + 1;
+            }
+        }
 
-        // Increase the gas used by 20% to make sure the transaction will not fail due to gas.
-        // This is a temporary solution until we have a proper gas estimation.
-        // Does not apply to Hive feature otherwise end2end tests will fail.
-        let gas_used = if !cfg!(feature = "hive") { gas_used * 120 / 100 } else { gas_used };
-        Ok(U256::from(gas_used))
+        Ok(U256::from(highest))
     }
 
     async fn fee_history(
         &self,
         block_count: U64HexOrNumber,
         newest_block: BlockNumberOrTag,
-        _reward_percentiles: Option<Vec<f64>>,
+        reward_percentiles: Option<Vec<f64>>,
     ) -> EthProviderResult<FeeHistory> {
         if block_count.to() == 0 {
             return Ok(FeeHistory::default());
         }
 
+        // Percentiles must be in the [0, 100] range and monotonically increasing
+        if let Some(percentiles) = &reward_percentiles {
+            if percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) || percentiles.windows(2).any(|w| w[0] > w[1]) {
+                return Err(EthApiError::InvalidRewardPercentiles);
+            }
+        }
+
         let end_block = self.tag_into_block_number(newest_block).await?;
         let end_block = end_block.to::<u64>();
         let end_block_plus = end_block.saturating_add(1);
@@ -476,12 +977,26 @@ where
 
         // TODO: check if we should use a projection since we only need the gasLimit and gasUsed.
         // This means we need to introduce a new type for the StoredHeader.
-        let header_filter = doc! {"$and": [ { "header.number": { "$gte": format_hex(start_block, BLOCK_NUMBER_HEX_STRING_LEN) } }, { "header.number": { "$lte": format_hex(end_block, BLOCK_NUMBER_HEX_STRING_LEN) } } ] };
-        let blocks: Vec<StoredHeader> = self.database.get(header_filter, None).await?;
+        // The block following the range is also fetched in order to get the next base fee.
+        let header_filter = doc! {"$and": [ { "header.number": { "$gte": format_hex(start_block, BLOCK_NUMBER_HEX_STRING_LEN) } }, { "header.number": { "$lte": format_hex(end_block_plus, BLOCK_NUMBER_HEX_STRING_LEN) } } ] };
+        let mut blocks: Vec<StoredHeader> = self.database.get(header_filter, None).await?;
+        blocks.sort_unstable_by_key(|header| header.header.number);
+
+        let next_block = match blocks.last() {
+            Some(header) if header.header.number == Some(end_block_plus) => blocks.pop(),
+            _ => None,
+        };
 
         if blocks.is_empty() {
             return Err(EthApiError::UnknownBlock);
         }
+        // The fetched headers fill the block timestamps searched by `block_number_by_timestamp`,
+        // except for the pending block whose timestamp isn't final
+        for header in blocks.iter().filter(|header| header.header.hash.is_some_and(|hash| !hash.is_zero())) {
+            if let Some(number) = header.header.number {
+                self.cache.block_timestamps.insert(number, header.header.timestamp);
+            }
+        }
 
         let gas_used_ratio = blocks
             .iter()
@@ -497,118 +1012,137 @@ where
 
         let mut base_fee_per_gas =
             blocks.iter().map(|header| header.header.base_fee_per_gas.unwrap_or_default()).collect::<Vec<_>>();
-        // TODO(EIP1559): Remove this when proper base fee computation: if gas_ratio > 50%, increase base_fee_per_gas
-        base_fee_per_gas.extend_from_within((base_fee_per_gas.len() - 1)..);
+
+        // The base fee is derived from the Starknet gas price and doesn't follow EIP-1559. The
+        // next base fee is the base fee of the following block if it exists, or the current base fee.
+        let next_base_fee = match next_block {
+            Some(header) => header.header.base_fee_per_gas.unwrap_or_default(),
+            None => self.reported_base_fee().await?,
+        };
+        base_fee_per_gas.push(next_base_fee);
+
+        let reward = match reward_percentiles {
+            Some(percentiles) => {
+                let receipt_filter = doc! {"$and": [ { "receipt.blockNumber": { "$gte": format_hex(start_block, BLOCK_NUMBER_HEX_STRING_LEN) } }, { "receipt.blockNumber": { "$lte": format_hex(end_block, BLOCK_NUMBER_HEX_STRING_LEN) } } ] };
+                let receipts: Vec<StoredTransactionReceipt> = self.database.get(receipt_filter, None).await?;
+                let mut receipts_per_block = receipts.into_iter().into_group_map_by(|r| r.receipt.block_number);
+
+                Some(
+                    blocks
+                        .iter()
+                        .map(|header| {
+                            let base_fee = header.header.base_fee_per_gas.unwrap_or_default();
+                            let tips = receipts_per_block
+                                .remove(&header.header.number)
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|r| (r.receipt.effective_gas_price.saturating_sub(base_fee), r.receipt.gas_used))
+                                .collect();
+                            reward_percentiles(tips, header.header.gas_used, &percentiles)
+                        })
+                        .collect(),
+                )
+            }
+            None => None,
+        };
+
+        // No blob gas is ever used, the blob base fee stays at its minimum
+        let base_fee_per_blob_gas = vec![BLOB_BASE_FEE; base_fee_per_gas.len()];
+        let blob_gas_used_ratio = vec![0.; gas_used_ratio.len()];
 
         Ok(FeeHistory {
             base_fee_per_gas,
             gas_used_ratio,
+            base_fee_per_blob_gas,
+            blob_gas_used_ratio,
             oldest_block: start_block,
-            reward: Some(vec![]),
-            ..Default::default()
+            reward,
         })
     }
 
     async fn send_raw_transaction(&self, transaction: Bytes) -> EthProviderResult<B256> {
-        // Get the chain ID
-        let chain_id =
-            self.chain_id().await?.unwrap_or_default().try_into().map_err(|_| TransactionError::InvalidChainId)?;
-
         // Decode the transaction data
-        let transaction_signed = TransactionSigned::decode(&mut transaction.0.as_ref())
-            .map_err(|_| EthApiError::EthereumDataFormat(EthereumDataFormatError::TransactionConversionError))?;
+        let transaction_signed = decode_raw_transaction(&transaction)?;
 
         // Recover the signer from the transaction
         let signer = transaction_signed.recover_signer().ok_or(SignatureError::RecoveryError)?;
 
-        // Determine the maximum fee
-        let max_fee = if cfg!(feature = "hive") {
-            u64::MAX
-        } else {
-            // TODO(Kakarot Fee Mechanism): When we no longer need to use the Starknet fees, remove this line.
-            // We need to get the balance (in Kakarot/Starknet native Token) of the signer to compute the Starknet maximum `max_fee`.
-            // We used to set max_fee = u64::MAX, but it'll fail if the signer doesn't have enough balance to pay the fees.
-            let eth_fees_per_gas =
-                transaction_signed.effective_gas_price(Some(transaction_signed.max_fee_per_gas() as u64)) as u64;
-            let eth_fees = eth_fees_per_gas.saturating_mul(transaction_signed.gas_limit());
-            let balance = self.balance(signer, None).await?;
-            let max_fee: u64 = balance.try_into().unwrap_or(u64::MAX);
-            let max_fee = (max_fee as u128 * 80 / 100) as u64;
-            max_fee.saturating_sub(eth_fees)
-        };
-
-        // Deploy EVM transaction signer if Hive feature is enabled
-        #[cfg(feature = "hive")]
-        self.deploy_evm_transaction_signer(signer).await?;
+        // Reject the transactions which are already pending or whose nonce was already used
+        self.validate_new_transaction(signer, &transaction_signed).await?;
 
-        // Convert the transaction to a Starknet transaction
-        let transaction = to_starknet_transaction(&transaction_signed, chain_id, signer, max_fee)?;
+        self.submit_transaction(transaction_signed, signer).await
+    }
 
-        // Add the transaction to the Starknet provider
-        let res = self.starknet_provider.add_invoke_transaction(transaction).await.map_err(KakarotError::from)?;
+    async fn send_impersonated_transaction(
+        &self,
+        transaction: TransactionSigned,
+        signer: Address,
+    ) -> EthProviderResult<B256> {
+        self.validate_new_transaction(signer, &transaction).await?;
+        self.submit_transaction(transaction, signer).await
+    }
 
-        // Serialize transaction document
-        let transaction =
-            from_recovered(TransactionSignedEcRecovered::from_signed_transaction(transaction_signed.clone(), signer));
+    async fn gas_price(&self) -> EthProviderResult<U256> {
+        let base_fee = self.reported_base_fee().await?;
+        let tip = self.max_priority_fee_per_gas().await?;
+        Ok(U256::from(base_fee).saturating_add(tip))
+    }
 
-        // Update pending transactions collection
-        let filter = into_filter("tx.hash", &transaction.hash, HASH_HEX_STRING_LEN);
+    async fn max_priority_fee_per_gas(&self) -> EthProviderResult<U256> {
+        let latest_block = self.block_number().await?.to::<u64>();
+        if let Some(tip) = self.gas_price_oracle.cached_tip(latest_block) {
+            return Ok(U256::from(tip));
+        }
 
-        if let Some(pending_transaction) =
-            self.database.get_one::<StoredPendingTransaction>(filter.clone(), None).await?
-        {
-            self.database
-                .update_one::<StoredPendingTransaction>(
-                    StoredPendingTransaction::new(transaction, pending_transaction.retries + 1),
-                    filter,
-                    true,
-                )
-                .await?;
-        } else {
-            self.database.update_one::<StoredPendingTransaction>(transaction.into(), filter, true).await?;
+        let blocks = self.gas_price_oracle.config().blocks;
+        if blocks == 0 {
+            return Ok(U256::ZERO);
         }
+        let start_block = latest_block.saturating_add(1).saturating_sub(blocks);
+        let start_block = format_hex(start_block, BLOCK_NUMBER_HEX_STRING_LEN);
+        let end_block = format_hex(latest_block, BLOCK_NUMBER_HEX_STRING_LEN);
 
-        // Return transaction hash if testing feature is enabled, otherwise log and return Ethereum hash
-        if cfg!(feature = "testing") {
-            return Ok(B256::from_slice(&res.transaction_hash.to_bytes_be()[..]));
-        } else {
-            let hash = transaction_signed.hash();
-            tracing::info!(
-                "Fired a transaction: Starknet Hash: {:?} --- Ethereum Hash: {:?}",
-                res.transaction_hash,
-                hash
-            );
+        let header_filter = doc! {"header.number": {"$gte": start_block.as_str(), "$lte": end_block.as_str()}};
+        let headers: Vec<StoredHeader> = self.database.get(header_filter, None).await?;
+        let base_fees = headers
+            .into_iter()
+            .filter_map(|header| Some((header.header.number?, header.header.base_fee_per_gas.unwrap_or_default())))
+            .collect::<HashMap<_, _>>();
 
-            Ok(hash)
-        }
-    }
+        let receipt_filter = doc! {"receipt.blockNumber": {"$gte": start_block.as_str(), "$lte": end_block.as_str()}};
+        let receipts: Vec<StoredTransactionReceipt> = self.database.get(receipt_filter, None).await?;
+        let tips_per_block = receipts
+            .into_iter()
+            .filter_map(|r| {
+                let base_fee = base_fees.get(&r.receipt.block_number?)?;
+                Some((r.receipt.block_number, r.receipt.effective_gas_price.saturating_sub(*base_fee)))
+            })
+            .into_group_map()
+            .into_values()
+            .collect();
 
-    async fn gas_price(&self) -> EthProviderResult<U256> {
-        let kakarot_contract = KakarotCoreReader::new(*KAKAROT_ADDRESS, &self.starknet_provider);
-        let gas_price = kakarot_contract.get_base_fee().call().await.map_err(KakarotError::from)?.base_fee;
-        Ok(into_via_wrapper!(gas_price))
+        // Blocks without transactions keep the last suggestion, which defaults to no tip
+        let tip = self
+            .gas_price_oracle
+            .suggest_tip(tips_per_block)
+            .unwrap_or_else(|| self.gas_price_oracle.last_tip().unwrap_or_default());
+        self.gas_price_oracle.cache_tip(latest_block, tip);
+
+        Ok(U256::from(tip))
     }
 
     async fn block_receipts(&self, block_id: Option<BlockId>) -> EthProviderResult<Option<Vec<TransactionReceipt>>> {
-        match block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)) {
+        let receipts = match block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)) {
             BlockId::Number(maybe_number) => {
                 let block_number = self.tag_into_block_number(maybe_number).await?;
-                if !self.block_exists(block_number.into()).await? {
-                    return Ok(None);
-                }
-
-                let filter = into_filter("receipt.blockNumber", &block_number, BLOCK_NUMBER_HEX_STRING_LEN);
-                let tx: Vec<StoredTransactionReceipt> = self.database.get(filter, None).await?;
-                Ok(Some(tx.into_iter().map(Into::into).collect()))
-            }
-            BlockId::Hash(hash) => {
-                if !self.block_exists(hash.block_hash.into()).await? {
-                    return Ok(None);
-                }
-                let filter = into_filter("receipt.blockHash", &hash.block_hash, HASH_HEX_STRING_LEN);
-                Ok(Some(self.database.get_and_map_to::<_, StoredTransactionReceipt>(filter, None).await?))
+                self.receipts_of_block(block_number.to::<u64>()).await?
             }
-        }
+            BlockId::Hash(hash) => match self.canonical_block_number(hash.block_hash).await? {
+                Some(block_number) => self.receipts_of_block(block_number).await?,
+                None => None,
+            },
+        };
+        Ok(receipts.map(|(receipts, _)| Arc::unwrap_or_clone(receipts)))
     }
 
     async fn block_transactions(
@@ -628,32 +1162,768 @@ where
             _ => Err(TransactionError::ExpectedFullTransactions.into()),
         }
     }
-}
-
-impl<SP> EthDataProvider<SP>
-where
-    SP: starknet::providers::Provider + Send + Sync,
-{
-    pub async fn new(database: Database, starknet_provider: SP) -> Result<Self> {
-        // We take the chain_id modulo u32::MAX to ensure compatibility with tooling
-        // see: https://github.com/ethereum/EIPs/issues/2294
-        // Note: Metamask is breaking for a chain_id = u64::MAX - 1
-        let chain_id = (FieldElement::from(u32::MAX) & starknet_provider.chain_id().await?).try_into().unwrap(); // safe unwrap
-        Ok(Self { database, starknet_provider, chain_id })
-    }
 
-    #[cfg(feature = "testing")]
-    pub fn starknet_provider(&self) -> &SP {
-        &self.starknet_provider
+    async fn pending_transactions(&self) -> EthProviderResult<Vec<reth_rpc_types::Transaction>> {
+        Ok(self.database.get_and_map_to::<_, StoredPendingTransaction>(doc! {"failed_block": null}, None).await?)
     }
 
-    /// Prepare the call input for an estimate gas or call from a transaction request.
-    async fn prepare_call_input(
+    async fn load_pending_transactions(
         &self,
-        request: TransactionRequest,
-        block_id: Option<BlockId>,
-    ) -> EthProviderResult<CallInput> {
-        // unwrap option
+        transactions: Vec<reth_rpc_types::Transaction>,
+    ) -> EthProviderResult<usize> {
+        // Malformed transactions are rejected before any of the transactions is loaded
+        for transaction in &transactions {
+            rpc_to_ec_recovered_transaction(transaction.clone())?;
+        }
+
+        let mut loaded = 0;
+        for transaction in transactions {
+            let filter = into_filter("tx.hash", &transaction.hash, HASH_HEX_STRING_LEN);
+            if self.database.get_one::<StoredPendingTransaction>(filter.clone(), None).await?.is_some()
+                || self.database.get_one::<StoredTransaction>(filter.clone(), None).await?.is_some()
+            {
+                continue;
+            }
+
+            // The transaction is stored as submitted at the genesis block, so that the retry service
+            // resubmits it on its next round
+            self.database.update_one::<StoredPendingTransaction>(transaction.into(), filter, true).await?;
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    fn flush_caches(&self) -> usize {
+        self.cache.clear()
+    }
+
+    async fn block_number_by_timestamp(&self, timestamp: u64) -> EthProviderResult<Option<u64>> {
+        let (Some(earliest), Some(latest)) =
+            (self.earliest_indexed_block_number().await?, self.indexed_block_number().await?)
+        else {
+            return Ok(None);
+        };
+        // The timestamps don't decrease with the block number
+        last_at_most(earliest, latest, timestamp, |block_number| self.block_timestamp(block_number)).await
+    }
+
+    async fn evm_address(
+        &self,
+        address: FieldElement,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<Option<Address>> {
+        let starknet_block_id = self.to_starknet_block_id(block_id).await?;
+
+        let account_contract = AccountContractReader::new(address, &self.starknet_provider);
+        let evm_address = account_contract.get_evm_address().block_id(starknet_block_id).call().await;
+
+        if contract_not_found(&evm_address) || entrypoint_not_found(&evm_address) {
+            return Ok(None);
+        }
+        let evm_address = evm_address.map_err(KakarotError::from)?.address;
+        let Ok(evm_address) = Address::try_from(Felt252Wrapper::from(evm_address)) else {
+            return Ok(None);
+        };
+
+        // Contracts which aren't Kakarot accounts can expose the same entrypoint, the address must
+        // be the one derived from the EVM address
+        Ok((starknet_address(evm_address) == address).then_some(evm_address))
+    }
+
+    async fn account_type(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<AccountType> {
+        let starknet_block_id = self.to_starknet_block_id(block_id).await?;
+
+        let Some(code) = self.account_code(address, starknet_block_id).await? else {
+            return Ok(AccountType::Undeployed);
+        };
+        // Only the EOAs can send transactions
+        if code.is_empty() || self.is_sender(address).await? {
+            return Ok(AccountType::Eoa);
+        }
+        Ok(AccountType::Contract)
+    }
+
+    async fn l1_messages(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> EthProviderResult<Vec<L1Message>> {
+        let from = self.tag_into_block_number(from_block).await?.to::<u64>();
+        let to = self.tag_into_block_number(to_block).await?.to::<u64>().min(self.block_number().await?.to());
+        if to < from {
+            return Ok(Vec::new());
+        }
+        if to - from >= MAX_L1_MESSAGES_BLOCK_RANGE {
+            return Err(EthApiError::BlockRangeLimitExceeded(MAX_L1_MESSAGES_BLOCK_RANGE));
+        }
+
+        // The L1 handler transactions aren't indexed, they are read from the Starknet blocks
+        let blocks = futures::stream::iter(from..=to)
+            .map(|block_number| async move {
+                self.starknet_provider
+                    .get_block_with_txs(starknet::core::types::BlockId::Number(block_number))
+                    .await
+                    .map_err(KakarotError::from)
+            })
+            .buffered(L1_MESSAGES_QUERY_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(blocks
+            .into_iter()
+            .filter_map(|block| match block {
+                MaybePendingBlockWithTxs::Block(block) => Some(block),
+                MaybePendingBlockWithTxs::PendingBlock(_) => None,
+            })
+            .flat_map(|block| {
+                let (block_hash, block_number) = (block.block_hash, block.block_number);
+                block.transactions.into_iter().filter_map(move |transaction| match transaction {
+                    starknet::core::types::Transaction::L1Handler(transaction)
+                        if transaction.contract_address == *KAKAROT_ADDRESS =>
+                    {
+                        L1Message::from_l1_handler(&transaction, block_hash, block_number)
+                    }
+                    _ => None,
+                })
+            })
+            .collect())
+    }
+
+    async fn block_fee_breakdown(&self, block_id: BlockNumberOrTag) -> EthProviderResult<Option<BlockFeeBreakdown>> {
+        let block_number = self.tag_into_block_number(block_id).await?.to::<u64>();
+        let Some(header) = self.header(block_number.into()).await? else {
+            return Ok(None);
+        };
+        let Some(receipts) = self.block_receipts(Some(BlockId::Number(block_number.into()))).await? else {
+            return Ok(None);
+        };
+        let receipts =
+            receipts.into_iter().map(|receipt| (receipt.transaction_hash, receipt)).collect::<HashMap<_, _>>();
+
+        // The Starknet fees aren't indexed, they are read from the Starknet block and receipts
+        let block = match self
+            .starknet_provider
+            .get_block_with_txs(starknet::core::types::BlockId::Number(block_number))
+            .await
+            .map_err(KakarotError::from)?
+        {
+            MaybePendingBlockWithTxs::Block(block) => block,
+            MaybePendingBlockWithTxs::PendingBlock(_) => return Ok(None),
+        };
+
+        let transactions = futures::stream::iter(block.transactions.iter().filter_map(kakarot_transaction))
+            .map(|(starknet_hash, transaction)| async move {
+                let fee = match self
+                    .starknet_provider
+                    .get_transaction_receipt(starknet_hash)
+                    .await
+                    .map_err(KakarotError::from)?
+                {
+                    MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(receipt)) => {
+                        Some(receipt.actual_fee)
+                    }
+                    _ => None,
+                };
+                EthProviderResult::Ok((starknet_hash, transaction.hash(), fee))
+            })
+            .buffered(FEE_BREAKDOWN_QUERY_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        // The transactions which failed on Starknet aren't indexed and don't have an EVM receipt
+        let transactions = transactions
+            .into_iter()
+            .filter_map(|(starknet_hash, hash, fee)| {
+                let receipt = receipts.get(&hash)?;
+                let fee = fee?;
+                let unit = match fee.unit {
+                    PriceUnit::Wei => FeeUnit::Wei,
+                    PriceUnit::Fri => FeeUnit::Fri,
+                };
+                Some(TransactionFeeBreakdown::new(
+                    hash,
+                    B256::from(starknet_hash.to_bytes_be()),
+                    U256::from(receipt.gas_used),
+                    U256::from(receipt.effective_gas_price),
+                    U256::from_be_bytes(fee.amount.to_bytes_be()),
+                    unit,
+                ))
+            })
+            .collect();
+
+        let header = header.header;
+        Ok(Some(BlockFeeBreakdown::new(
+            header.hash.unwrap_or_default(),
+            block_number,
+            header.base_fee_per_gas.map(U256::from),
+            U256::from_be_bytes(block.l1_gas_price.price_in_wei.to_bytes_be()),
+            U256::from_be_bytes(block.l1_gas_price.price_in_fri.to_bytes_be()),
+            transactions,
+        )))
+    }
+
+    async fn state_diff(&self, block_id: BlockNumberOrTag) -> EthProviderResult<Option<StateDiff>> {
+        let block_number = self.tag_into_block_number(block_id).await?.to::<u64>();
+        let Some(transactions) = self.block_transactions(Some(BlockId::Number(block_number.into()))).await? else {
+            return Ok(None);
+        };
+
+        // The state diffs aren't indexed, they are read from the Starknet state update of the block
+        let state_update = match self
+            .starknet_provider
+            .get_state_update(starknet::core::types::BlockId::Number(block_number))
+            .await
+            .map_err(KakarotError::from)?
+        {
+            MaybePendingStateUpdate::Update(update) => KakarotStateUpdate::from(update.state_diff),
+            MaybePendingStateUpdate::PendingUpdate(_) => return Ok(None),
+        };
+
+        // The recipients of the value transfers only change in the storage of the native token,
+        // under hashed keys, so the senders and recipients of the transactions are added directly
+        let mut accounts = transactions
+            .iter()
+            .flat_map(|transaction| [Some(transaction.from), transaction.to])
+            .flatten()
+            .map(|address| (starknet_address(address), address))
+            .collect::<BTreeMap<_, _>>();
+        let contracts = state_update.contracts().filter(|address| !accounts.contains_key(address)).collect::<Vec<_>>();
+        let block_id = BlockId::Number(block_number.into());
+        let kakarot_accounts = futures::stream::iter(contracts)
+            .map(|address| async move {
+                EthProviderResult::Ok(self.evm_address(address, Some(block_id)).await?.map(|evm| (address, evm)))
+            })
+            .buffered(STATE_DIFF_QUERY_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+        accounts.extend(kakarot_accounts.into_iter().flatten());
+
+        let state_update = &state_update;
+        let diffs = futures::stream::iter(accounts)
+            .map(|(starknet_address, address)| async move {
+                let diff = self.account_diff(state_update, starknet_address, address, block_number).await?;
+                EthProviderResult::Ok((address, diff))
+            })
+            .buffered(STATE_DIFF_QUERY_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(Some(StateDiff(
+            diffs
+                .into_iter()
+                .filter(|(_, diff)| {
+                    !matches!(
+                        (&diff.balance, &diff.nonce, &diff.code),
+                        (Delta::Unchanged, Delta::Unchanged, Delta::Unchanged)
+                    ) || !diff.storage.is_empty()
+                })
+                .collect(),
+        )))
+    }
+
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U256,
+    ) -> EthProviderResult<Option<reth_rpc_types::Transaction>> {
+        let filter = sender_and_nonce_filter(sender, nonce.saturating_to());
+        Ok(self.database.get_one::<StoredTransaction>(filter, None).await?.map(Into::into))
+    }
+
+    async fn revert_reason(&self, hash: B256) -> EthProviderResult<Option<Bytes>> {
+        let Some(transaction) = self.transaction_by_hash(hash).await? else {
+            return Ok(None);
+        };
+        let Some(block_number) = transaction.block_number else {
+            return Ok(None);
+        };
+
+        let request = TransactionRequest {
+            from: Some(transaction.from),
+            to: transaction.to,
+            gas: Some(transaction.gas),
+            value: Some(transaction.value),
+            input: TransactionInput { input: Some(transaction.input), data: None },
+            ..Default::default()
+        };
+        let block_id = BlockId::Number(BlockNumberOrTag::Number(block_number.saturating_sub(1)));
+
+        let (return_data, success) = self.eth_call_output(request, Some(block_id)).await?;
+        if success {
+            return Ok(None);
+        }
+        Ok(Some(try_from_u8_iterator::<_, Vec<_>>(return_data.0).into()))
+    }
+
+    async fn contract_creation_receipt(&self, address: Address) -> EthProviderResult<Option<TransactionReceipt>> {
+        let filter = into_filter("receipt.contractAddress", &address, ADDRESS_HEX_STRING_LEN);
+        Ok(self.database.get_one::<StoredTransactionReceipt>(filter, None).await?.map(Into::into))
+    }
+
+    async fn search_transactions(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+        direction: SearchDirection,
+    ) -> EthProviderResult<(Vec<reth_rpc_types::Transaction>, bool)> {
+        let address = format_hex(address, ADDRESS_HEX_STRING_LEN);
+        let address_filter = || doc! {"$or": [{"tx.from": &address}, {"tx.to": &address}]};
+        let (operator, order) = match direction {
+            SearchDirection::Before => ("$lt", -1),
+            SearchDirection::After => ("$gt", 1),
+        };
+        // Returns the filter on the transactions of the address in the blocks beyond the given block
+        let beyond = |block_number: Option<u64>| {
+            let mut filter = address_filter();
+            if let Some(block_number) = block_number {
+                filter.insert("tx.blockNumber", doc! {operator: format_hex(block_number, BLOCK_NUMBER_HEX_STRING_LEN)});
+            }
+            filter
+        };
+        // Searching before the block 0 starts from the latest block
+        let start = (direction == SearchDirection::After || block_number != 0).then_some(block_number);
+
+        let sort = doc! {"tx.blockNumber": order, "tx.transactionIndex": order};
+        let limit = i64::try_from(page_size.max(1)).unwrap_or(i64::MAX);
+        let mut transactions: Vec<reth_rpc_types::Transaction> = self
+            .database
+            .get_sorted::<StoredTransaction>(beyond(start), sort, limit)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        // Complete the page with the remaining transactions of the last block, so that the next
+        // page can start from the block following the last block.
+        let mut has_more = false;
+        if transactions.len() >= page_size {
+            if let Some(last_block) = transactions.last().and_then(|tx| tx.block_number) {
+                let mut filter = address_filter();
+                filter.insert("tx.blockNumber", format_hex(last_block, BLOCK_NUMBER_HEX_STRING_LEN));
+                let remaining = self
+                    .database
+                    .get_and_map_to::<reth_rpc_types::Transaction, StoredTransaction>(filter, None)
+                    .await?;
+                for tx in remaining {
+                    if transactions.iter().all(|known| known.hash != tx.hash) {
+                        transactions.push(tx);
+                    }
+                }
+                has_more = self.database.count::<StoredTransaction>(beyond(Some(last_block))).await? > 0;
+            }
+        }
+
+        transactions.sort_by(|a, b| (b.block_number, b.transaction_index).cmp(&(a.block_number, a.transaction_index)));
+        Ok((transactions, has_more))
+    }
+}
+
+impl<SP> EthDataProvider<SP>
+where
+    SP: starknet::providers::Provider + Send + Sync,
+{
+    pub async fn new(database: Database, starknet_provider: SP) -> Result<Self> {
+        // We take the chain_id modulo u32::MAX to ensure compatibility with tooling
+        // see: https://github.com/ethereum/EIPs/issues/2294
+        // Note: Metamask is breaking for a chain_id = u64::MAX - 1
+        let chain_id = (FieldElement::from(u32::MAX) & starknet_provider.chain_id().await?).try_into().unwrap(); // safe unwrap
+        Ok(Self {
+            database,
+            starknet_provider,
+            chain_id,
+            cache: Arc::new(ResponseCache::from_env(chain_id).await?),
+            in_flight: Arc::new(InFlightRequests::default()),
+            finality: Arc::new(FinalityTracker::default()),
+            gas_price_oracle: Arc::new(GasPriceOracle::default()),
+            eoa_deployer: EoaDeployer::from_env()?.map(Arc::new),
+            sync_starting_block: Arc::new(Mutex::new(None)),
+            http_client: reqwest_011::Client::new(),
+        })
+    }
+
+    /// Returns the range of blocks of the filter, bounded by the latest block, or None if no block
+    /// of the range was mined yet.
+    async fn logs_block_range(&self, filter: &Filter) -> EthProviderResult<Option<(u64, u64)>> {
+        let current_block = self.block_number().await?.try_into().map_err(|_| EthApiError::UnknownBlockNumber)?;
+        let from = filter.get_from_block().unwrap_or_default();
+        let to = filter.get_to_block().unwrap_or(current_block);
+
+        Ok(match (from, to) {
+            (from, _) if from > current_block => None,
+            (from, to) if to > current_block => Some((from, current_block)),
+            (from, to) if to < from => None,
+            range => Some(range),
+        })
+    }
+
+    /// Returns the logs matching the filter in the blocks from `from` to `to`, sorted by block
+    /// number and log index. Once more than `max_logs` logs are found, the following blocks aren't
+    /// queried and only the logs of the blocks fitting in the limit are returned, along with the
+    /// number of the first block whose logs weren't returned. The logs of a single block are
+    /// always returned together, even beyond the limit. Setting `max_logs` to 0 disables the limit.
+    async fn logs_in_range(
+        &self,
+        filter: &Filter,
+        from: u64,
+        to: u64,
+        max_logs: u64,
+    ) -> EthProviderResult<(Vec<Log>, Option<u64>)> {
+        // The topics at each position of the filter, an empty vector matching any topic
+        let (addresses, topics_by_position) = filter_addresses_and_topics(filter);
+
+        // Create the database filter, matching each position of the filter against the topic at the
+        // same position of the log. The filter on the first topic, the address and the block number
+        // is served by the index of the logs collection.
+        let mut database_filter = doc! {};
+        for (position, topics) in topics_by_position.iter().enumerate().filter(|(_, topics)| !topics.is_empty()) {
+            database_filter.insert(
+                format!("log.topics.{position}"),
+                doc! {"$in": topics.iter().map(|t| format_hex(t, LOGS_TOPICS_HEX_STRING_LEN)).collect::<Vec<_>>()},
+            );
+        }
+
+        // Add the address filter if any
+        if !addresses.is_empty() {
+            database_filter.insert(
+                "log.address",
+                doc! {"$in": addresses.iter().map(|a| format_hex(a, ADDRESS_HEX_STRING_LEN)).collect::<Vec<_>>()},
+            );
+        }
+
+        // Filter the blocks by block number, in ascending order. When filtering on addresses or
+        // topics, only keep the blocks for which the logs bloom indicates that matching logs could
+        // be present.
+        let block_filters = if addresses.is_empty() && topics_by_position.iter().all(Vec::is_empty) {
+            (from..=to)
+                .step_by(LOGS_QUERY_CHUNK_SIZE)
+                .map(|start| {
+                    let end = to.min(start + LOGS_QUERY_CHUNK_SIZE as u64 - 1);
+                    doc! {"$gte": format_hex(start, BLOCK_NUMBER_HEX_STRING_LEN), "$lte": format_hex(end, BLOCK_NUMBER_HEX_STRING_LEN)}
+                })
+                .collect::<Vec<_>>()
+        } else {
+            let blooms = self
+                .database
+                .get::<StoredHeaderLogsBloom>(
+                    doc! {"header.number": {"$gte": format_hex(from, BLOCK_NUMBER_HEX_STRING_LEN), "$lte": format_hex(to, BLOCK_NUMBER_HEX_STRING_LEN)}},
+                    doc! {"header.number": 1, "header.logsBloom": 1},
+                )
+                .await?;
+            blooms
+                .into_iter()
+                .filter(|stored| logs_bloom_matches(&stored.header.logs_bloom, &addresses, &topics_by_position))
+                .map(|stored| stored.header.number)
+                .sorted_unstable()
+                .map(|number| format_hex(number, BLOCK_NUMBER_HEX_STRING_LEN))
+                .chunks(LOGS_QUERY_CHUNK_SIZE)
+                .into_iter()
+                .map(|numbers| doc! {"$in": numbers.collect::<Vec<_>>()})
+                .collect::<Vec<_>>()
+        };
+
+        // Query the chunks of blocks in order with a bounded concurrency, until the limit is exceeded.
+        // Each chunk loads the first logs of its blocks up to one log beyond the limit, so that a
+        // dense range doesn't load all its logs in memory, and no chunk is queried once the limit is
+        // exceeded. The log index isn't padded, hence the logs are sorted again once loaded.
+        let limit = (max_logs != 0).then(|| i64::try_from(max_logs.saturating_add(1)).unwrap_or(i64::MAX));
+        let mut chunks = futures::stream::iter(block_filters)
+            .map(|block_filter| {
+                let mut database_filter = database_filter.clone();
+                database_filter.insert("log.blockNumber", block_filter);
+                self.database.get_sorted::<StoredLog>(
+                    database_filter,
+                    doc! {"log.blockNumber": 1, "log.logIndex": 1},
+                    limit,
+                )
+            })
+            .buffered(LOGS_QUERY_CONCURRENCY);
+        let mut logs: Vec<Log> = Vec::new();
+        while let Some(chunk) = chunks.try_next().await? {
+            logs.extend(chunk.into_iter().map(Log::from));
+            if max_logs != 0 && logs.len() as u64 > max_logs {
+                break;
+            }
+        }
+        drop(chunks);
+        logs.sort_by_key(|log| (log.block_number, log.log_index));
+
+        if max_logs == 0 || logs.len() as u64 <= max_logs {
+            return Ok((logs, None));
+        }
+
+        // Only keep the blocks fitting in the limit, whose logs are all loaded since the logs are
+        // queried in the order of the blocks
+        let first_block = logs[0].block_number.unwrap_or_default();
+        let next_block = logs[max_logs as usize].block_number.unwrap_or_default();
+        if next_block > first_block {
+            logs.retain(|log| log.block_number.unwrap_or_default() < next_block);
+            return Ok((logs, Some(next_block)));
+        }
+
+        // The first block exceeds the limit alone, its logs are returned together
+        let mut database_filter = database_filter;
+        database_filter.insert("log.blockNumber", format_hex(first_block, BLOCK_NUMBER_HEX_STRING_LEN));
+        let mut logs = self.database.get_and_map_to::<Log, StoredLog>(database_filter, None).await?;
+        logs.sort_by_key(|log| log.log_index);
+
+        Ok((logs, Some(first_block + 1)))
+    }
+
+    /// Relays the transaction to Starknet and stores it in the pending transactions. Also used to
+    /// resubmit the pending transactions, which skips the checks of the new transactions.
+    async fn submit_transaction(
+        &self,
+        transaction_signed: TransactionSigned,
+        signer: Address,
+    ) -> EthProviderResult<B256> {
+        // Get the chain ID
+        let chain_id =
+            self.chain_id().await?.unwrap_or_default().try_into().map_err(|_| TransactionError::InvalidChainId)?;
+
+        // Validate the transaction fees against the current base fee
+        let base_fee = self.base_fee().await?;
+        validate_transaction_fees(&transaction_signed, base_fee)?;
+
+        // Check if the transaction replaces a pending transaction with the same nonce
+        let replaced = self.replaced_transaction(signer, &transaction_signed).await?;
+
+        // Persist the transaction before relaying it, so that it is recovered if the RPC stops
+        // before its submission to Starknet completes, e.g. while deploying the account of its sender
+        let transaction =
+            from_recovered(TransactionSignedEcRecovered::from_signed_transaction(transaction_signed.clone(), signer));
+        let filter = into_filter("tx.hash", &transaction.hash, HASH_HEX_STRING_LEN);
+        let pending = self.database.get_one::<StoredPendingTransaction>(filter.clone(), None).await?;
+        if pending.is_none() {
+            let pending_transaction = StoredPendingTransaction {
+                submitted_block: self.block_number().await?.to(),
+                unsubmitted: true,
+                ..StoredPendingTransaction::new(transaction.clone(), 0)
+            };
+            self.database.update_one::<StoredPendingTransaction>(pending_transaction, filter.clone(), true).await?;
+        }
+
+        let res = match self.relay_transaction(&transaction_signed, signer, chain_id, base_fee).await {
+            Ok(res) => res,
+            Err(err) => {
+                // A new transaction which couldn't be relayed is rejected, it isn't recovered
+                if pending.is_none() {
+                    self.database.delete_one::<StoredPendingTransaction>(filter).await?;
+                }
+                return Err(err);
+            }
+        };
+
+        // Evict the replaced transaction from the pending transactions collection, so that it isn't retried
+        if let Some(replaced) = replaced {
+            self.database
+                .delete_one::<StoredPendingTransaction>(into_filter("tx.hash", &replaced, HASH_HEX_STRING_LEN))
+                .await?;
+        }
+
+        // Update pending transactions collection
+        let retries = pending.map_or(0, |pending_transaction| pending_transaction.retries + 1);
+        let pending_transaction = StoredPendingTransaction {
+            submitted_block: self.block_number().await?.to(),
+            ..StoredPendingTransaction::new(transaction, retries)
+        };
+        self.database.update_one::<StoredPendingTransaction>(pending_transaction, filter, true).await?;
+
+        // Return transaction hash if testing feature is enabled, otherwise log and return Ethereum hash
+        if cfg!(feature = "testing") {
+            return Ok(B256::from_slice(&res.transaction_hash.to_bytes_be()[..]));
+        } else {
+            let hash = transaction_signed.hash();
+            tracing::info!(
+                "Fired a transaction: Starknet Hash: {:?} --- Ethereum Hash: {:?}",
+                res.transaction_hash,
+                hash
+            );
+
+            Ok(hash)
+        }
+    }
+
+    /// Deploys the account of the signer if needed, then wraps the transaction into a Starknet
+    /// transaction and adds it to the Starknet provider.
+    async fn relay_transaction(
+        &self,
+        transaction_signed: &TransactionSigned,
+        signer: Address,
+        chain_id: u64,
+        base_fee: u128,
+    ) -> EthProviderResult<InvokeTransactionResult> {
+        // Determine the maximum fee
+        let max_fee = if cfg!(feature = "hive") {
+            u64::MAX
+        } else {
+            // TODO(Kakarot Fee Mechanism): When we no longer need to use the Starknet fees, remove this line.
+            // We need to get the balance (in Kakarot/Starknet native Token) of the signer to compute the Starknet maximum `max_fee`.
+            // We used to set max_fee = u64::MAX, but it'll fail if the signer doesn't have enough balance to pay the fees.
+            let eth_fees_per_gas = transaction_signed.effective_gas_price(Some(base_fee as u64)) as u64;
+            let eth_fees = eth_fees_per_gas.saturating_mul(transaction_signed.gas_limit());
+            let balance = self.balance(signer, None).await?;
+            // The senders which can't pay for their transaction are rejected before the deployment
+            // of their account, which is paid by the relayers
+            validate_transaction_balance(transaction_signed, balance)?;
+            let max_fee: u64 = balance.try_into().unwrap_or(u64::MAX);
+            let max_fee = (max_fee as u128 * 80 / 100) as u64;
+            max_fee.saturating_sub(eth_fees)
+        };
+
+        // Deploy EVM transaction signer if Hive feature is enabled
+        #[cfg(feature = "hive")]
+        self.deploy_evm_transaction_signer(signer).await?;
+
+        // Deploy the account of the signer before relaying its first transaction
+        self.deploy_signer_account(signer).await?;
+
+        // Convert the transaction to a Starknet transaction
+        let transaction = to_starknet_transaction(transaction_signed, chain_id, signer, max_fee)?;
+
+        // Add the transaction to the Starknet provider
+        Ok(self.starknet_provider.add_invoke_transaction(transaction).await.map_err(KakarotError::from)?)
+    }
+
+    /// Reads the receipt of the transaction from the database, caching it once its block is sealed.
+    async fn fetch_transaction_receipt(&self, hash: B256) -> EthProviderResult<Option<TransactionReceipt>> {
+        let receipt: Option<TransactionReceipt> = self
+            .database
+            .get_one::<StoredTransactionReceipt>(
+                into_filter("receipt.transactionHash", &hash, HASH_HEX_STRING_LEN),
+                None,
+            )
+            .await?
+            .map(Into::into);
+
+        // The cumulative gas used depends on the other receipts of the block, which are fetched
+        // once for all the transactions of the block
+        let Some(block_number) = receipt.as_ref().and_then(|receipt| receipt.block_number) else {
+            return Ok(receipt);
+        };
+        let Some((receipts, complete)) = self.receipts_of_block(block_number).await? else {
+            return Ok(receipt);
+        };
+        let receipt = receipts.iter().find(|receipt| receipt.transaction_hash == hash).cloned().or(receipt);
+
+        // Only cache the receipts of transactions included in a sealed block, once all the
+        // receipts of the block are written
+        if let Some(receipt) = &receipt {
+            if complete && receipt.block_hash.is_some_and(|hash| !hash.is_zero()) {
+                self.cache.receipts.insert(hash, receipt.clone()).await;
+            }
+        }
+        Ok(receipt)
+    }
+
+    /// Returns the receipts of the block with the given number along with whether they are
+    /// complete, or None if the block isn't indexed.
+    async fn receipts_of_block(
+        &self,
+        block_number: u64,
+    ) -> EthProviderResult<Option<(Arc<Vec<TransactionReceipt>>, bool)>> {
+        if let Some(receipts) = self.cache.block_receipts.get(&block_number) {
+            return Ok(Some((receipts, true)));
+        }
+        self.in_flight.block_receipts.run(block_number, || self.fetch_receipts_of_block(block_number)).await
+    }
+
+    /// Fetches the header and the receipts of the block concurrently. The complete receipts of a
+    /// sealed block are cached, both for the block and for each of its transactions.
+    async fn fetch_receipts_of_block(
+        &self,
+        block_number: u64,
+    ) -> EthProviderResult<Option<(Arc<Vec<TransactionReceipt>>, bool)>> {
+        let filter = into_filter("receipt.blockNumber", &block_number, BLOCK_NUMBER_HEX_STRING_LEN);
+        let receipts = async {
+            Ok::<_, EthApiError>(
+                self.database.get_and_map_to::<TransactionReceipt, StoredTransactionReceipt>(filter, None).await?,
+            )
+        };
+        let (header, mut receipts) = futures::try_join!(self.header(block_number.into()), receipts)?;
+        let Some(header) = header.map(|header| header.header) else {
+            return Ok(None);
+        };
+        normalize_block_receipts(&mut receipts);
+
+        // The header is written after the receipts of the block, which may still be partially
+        // written when they are queried concurrently: they are complete if they add up to the gas
+        // used by the block. The pending block has a zero hash.
+        let gas_used = receipts.iter().fold(0u128, |gas_used, receipt| gas_used.saturating_add(receipt.gas_used));
+        let complete = gas_used == header.gas_used;
+        let receipts = Arc::new(receipts);
+        if complete && header.hash.is_some_and(|hash| !hash.is_zero()) {
+            futures::future::join_all(
+                receipts.iter().map(|receipt| self.cache.receipts.insert(receipt.transaction_hash, receipt.clone())),
+            )
+            .await;
+            self.cache.block_receipts.insert(block_number, receipts.clone());
+        }
+        Ok(Some((receipts, complete)))
+    }
+
+    /// Returns the current base fee, read from the Kakarot contract.
+    async fn base_fee(&self) -> EthProviderResult<u128> {
+        self.in_flight.base_fee.run((), || self.fetch_base_fee()).await
+    }
+
+    /// Returns the base fee reported by `eth_gasPrice` and `eth_feeHistory`, converted from the L1
+    /// gas price of the pending Starknet block by the [`BASE_FEE_POLICY`]. The transactions are
+    /// validated against the base fee of the Kakarot contract, which is enforced on chain, and
+    /// which the reported base fee is never below.
+    async fn reported_base_fee(&self) -> EthProviderResult<u128> {
+        if *BASE_FEE_POLICY == BaseFeePolicy::Oracle {
+            return self.base_fee().await;
+        }
+        let pending_block = async {
+            self.starknet_provider
+                .get_block_with_tx_hashes(starknet::core::types::BlockId::Tag(starknet::core::types::BlockTag::Pending))
+                .await
+                .map_err(|err| EthApiError::from(KakarotError::from(err)))
+        };
+        let (block, contract_base_fee) = futures::try_join!(pending_block, self.base_fee())?;
+        let l1_gas_price = match &block {
+            MaybePendingBlockWithTxHashes::Block(block) => &block.l1_gas_price,
+            MaybePendingBlockWithTxHashes::PendingBlock(block) => &block.l1_gas_price,
+        };
+        Ok(BASE_FEE_POLICY.base_fee(l1_gas_price, contract_base_fee))
+    }
+
+    /// Reads the current base fee from the Kakarot contract.
+    async fn fetch_base_fee(&self) -> EthProviderResult<u128> {
+        let kakarot_contract = KakarotCoreReader::new(*KAKAROT_ADDRESS, &self.starknet_provider);
+        let base_fee = kakarot_contract.get_base_fee().call().await.map_err(KakarotError::from)?.base_fee;
+        let base_fee: U256 = into_via_wrapper!(base_fee);
+        Ok(base_fee.saturating_to())
+    }
+
+    /// Deploys the Kakarot account of the signer if it isn't deployed yet. Does nothing if no
+    /// deployer account is configured, in which case the accounts are deployed by the users.
+    async fn deploy_signer_account(&self, signer: Address) -> EthProviderResult<()> {
+        let Some(deployer) = &self.eoa_deployer else {
+            return Ok(());
+        };
+        // A deployed account can't be removed, the deployed accounts don't need to be checked again
+        if self.cache.deployed_accounts.get(&signer).is_some() {
+            return Ok(());
+        }
+        deployer.deploy_if_missing(&self.starknet_provider, signer).await?;
+        self.cache.deployed_accounts.insert(signer, ());
+        Ok(())
+    }
+
+    #[cfg(feature = "testing")]
+    pub fn starknet_provider(&self) -> &SP {
+        &self.starknet_provider
+    }
+
+    /// Prepare the call input for an estimate gas or call from a transaction request.
+    async fn prepare_call_input(
+        &self,
+        request: TransactionRequest,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<CallInput> {
+        // unwrap option
         let to: kakarot_core::core::Option = {
             match request.to {
                 Some(to) => kakarot_core::core::Option { is_some: FieldElement::ONE, value: into_via_wrapper!(to) },
@@ -674,7 +1944,7 @@ where
         let gas_price = {
             let gas_price = match request.gas_price {
                 Some(gas_price) => U256::from(gas_price),
-                None => self.gas_price().await?,
+                None => U256::from(self.base_fee().await?),
             };
             into_via_try_wrapper!(gas_price)?
         };
@@ -703,9 +1973,30 @@ where
         request: TransactionRequest,
         block_id: Option<BlockId>,
     ) -> EthProviderResult<CairoArrayLegacy<FieldElement>> {
+        let (return_data, success) = self.eth_call_output(request, block_id).await?;
+        if !success {
+            return Err(KakarotError::from(EvmError::from(return_data.0)).into());
+        }
+        Ok(return_data)
+    }
+
+    /// Calls the eth_call entrypoint of Kakarot and returns the return data along with the success of the call.
+    async fn eth_call_output(
+        &self,
+        request: TransactionRequest,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<(CairoArrayLegacy<FieldElement>, bool)> {
         let starknet_block_id = self.to_starknet_block_id(block_id).await?;
         let call_input = self.prepare_call_input(request, block_id).await?;
+        self.kakarot_eth_call(call_input, starknet_block_id).await
+    }
 
+    /// Calls the eth_call entrypoint of Kakarot with the prepared input at the Starknet block.
+    async fn kakarot_eth_call(
+        &self,
+        call_input: CallInput,
+        starknet_block_id: starknet::core::types::BlockId,
+    ) -> EthProviderResult<(CairoArrayLegacy<FieldElement>, bool)> {
         let kakarot_contract = KakarotCoreReader::new(*KAKAROT_ADDRESS, &self.starknet_provider);
         let call_output = kakarot_contract
             .eth_call(
@@ -725,22 +2016,30 @@ where
             .await
             .map_err(KakarotError::from)?;
 
-        let return_data = call_output.return_data;
-        if call_output.success == FieldElement::ZERO {
+        Ok((call_output.return_data, call_output.success != FieldElement::ZERO))
+    }
+
+    /// Executes the request in Kakarot with the given gas limit, returning an error if the execution fails.
+    async fn execute_with_gas_limit(
+        &self,
+        call_input: &CallInput,
+        gas_limit: u128,
+        starknet_block_id: starknet::core::types::BlockId,
+    ) -> EthProviderResult<()> {
+        let call_input = CallInput { gas_limit: into_via_try_wrapper!(gas_limit)?, ..call_input.clone() };
+        let (return_data, success) = self.kakarot_eth_call(call_input, starknet_block_id).await?;
+        if !success {
             return Err(KakarotError::from(EvmError::from(return_data.0)).into());
         }
-        Ok(return_data)
+        Ok(())
     }
 
-    /// Estimate the gas used in Kakarot for the given request.
+    /// Estimate the gas used in Kakarot for the given call input at the Starknet block.
     async fn estimate_gas_helper(
         &self,
-        request: TransactionRequest,
-        block_id: Option<BlockId>,
+        call_input: CallInput,
+        starknet_block_id: starknet::core::types::BlockId,
     ) -> EthProviderResult<u128> {
-        let starknet_block_id = self.to_starknet_block_id(block_id).await?;
-        let call_input = self.prepare_call_input(request, block_id).await?;
-
         let kakarot_contract = KakarotCoreReader::new(*KAKAROT_ADDRESS, &self.starknet_provider);
         let estimate_gas_output = kakarot_contract
             .eth_estimate_gas(
@@ -768,6 +2067,37 @@ where
         Ok(required_gas)
     }
 
+    /// Returns the number of the latest block indexed in the database,
+    /// excluding the pending block, or None if the database is empty.
+    async fn indexed_block_number(&self) -> EthProviderResult<Option<u64>> {
+        let sort = doc! { "header.number": -1 };
+        let Some(header) = self.database.get_one::<StoredHeader>(None, sort).await? else {
+            return Ok(None);
+        };
+
+        let number = header.header.number.ok_or(EthApiError::UnknownBlockNumber)?;
+        let is_pending_block = header.header.hash.unwrap_or_default().is_zero();
+        Ok(Some(if is_pending_block { number.saturating_sub(1) } else { number }))
+    }
+
+    /// Returns the number of the earliest block indexed in the database, or None if the database
+    /// is empty.
+    async fn earliest_indexed_block_number(&self) -> EthProviderResult<Option<u64>> {
+        let sort = doc! { "header.number": 1 };
+        let header = self.database.get_one::<StoredHeader>(None, sort).await?;
+        Ok(header.and_then(|header| header.header.number))
+    }
+
+    /// Returns the timestamp of the sealed block, from the cached block timestamps if known.
+    async fn block_timestamp(&self, block_number: u64) -> EthProviderResult<u64> {
+        if let Some(timestamp) = self.cache.block_timestamps.get(&block_number) {
+            return Ok(timestamp);
+        }
+        let header = self.header(block_number.into()).await?.ok_or(EthApiError::UnknownBlock)?;
+        self.cache.block_timestamps.insert(block_number, header.header.timestamp);
+        Ok(header.header.timestamp)
+    }
+
     /// Check if a block exists in the database.
     async fn block_exists(&self, block_id: BlockHashOrNumber) -> EthProviderResult<bool> {
         Ok(self.header(block_id).await?.is_some())
@@ -798,28 +2128,79 @@ where
             BlockHashOrNumber::Hash(hash) => into_filter("tx.blockHash", &hash, HASH_HEX_STRING_LEN),
             BlockHashOrNumber::Number(number) => into_filter("tx.blockNumber", &number, BLOCK_NUMBER_HEX_STRING_LEN),
         };
+        let hashes =
+            self.database.get_and_map_to::<_, StoredTransactionHash>(transactions_filter, doc! {"tx.hash": 1}).await?;
         let block_transactions = if full {
-            BlockTransactions::Full(
-                self.database.get_and_map_to::<_, StoredTransaction>(transactions_filter, None).await?,
-            )
+            BlockTransactions::Full(self.transactions_by_hashes(&hashes).await?)
         } else {
-            BlockTransactions::Hashes(
-                self.database
-                    .get_and_map_to::<_, StoredTransactionHash>(transactions_filter, doc! {"tx.hash": 1})
-                    .await?,
-            )
+            BlockTransactions::Hashes(hashes)
         };
 
         Ok(block_transactions)
     }
 
+    /// Returns the full transactions with the given hashes, sorted by their index in the block.
+    /// The transactions are fetched by chunks, with a bounded number of concurrent queries, so that
+    /// the assembly of a busy block isn't bound by a single query.
+    async fn transactions_by_hashes(&self, hashes: &[B256]) -> EthProviderResult<Vec<reth_rpc_types::Transaction>> {
+        let chunks = futures::stream::iter(hashes.chunks(BLOCK_TRANSACTIONS_QUERY_CHUNK_SIZE))
+            .map(|chunk| async move {
+                let hashes = chunk.iter().map(|hash| format_hex(*hash, HASH_HEX_STRING_LEN)).collect::<Vec<_>>();
+                self.database
+                    .get_and_map_to::<reth_rpc_types::Transaction, StoredTransaction>(
+                        doc! {"tx.hash": {"$in": hashes}},
+                        None,
+                    )
+                    .await
+            })
+            .buffer_unordered(BLOCK_TRANSACTIONS_QUERY_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut transactions = chunks.into_iter().flatten().collect::<Vec<_>>();
+        transactions.sort_unstable_by_key(|tx| tx.transaction_index);
+        Ok(transactions)
+    }
+
     /// Get a block from the database based on a block hash or number.
     /// If full is true, the block will contain the full transactions, otherwise just the hashes
+    /// Sealed blocks are cached, since they can't change once produced.
     async fn block(&self, block_id: BlockHashOrNumber, full: bool) -> EthProviderResult<Option<RichBlock>> {
-        let header = match self.header(block_id).await? {
+        if let Some(block) = self.cache.blocks.get(&(block_id, full)).await {
+            return Ok(Some(block));
+        }
+        // The hashes of the transactions of a sealed block are read from its cached full transactions
+        if !full {
+            if let Some(block) = self.cache.blocks.get(&(block_id, true)).await {
+                let block = with_transaction_hashes(block);
+                self.cache.blocks.insert((block_id, false), block.clone()).await;
+                return Ok(Some(block));
+            }
+        }
+        self.in_flight.blocks.run((block_id, full), || self.fetch_block(block_id, full)).await
+    }
+
+    /// Assembles the block from the database, caching it if it is sealed.
+    async fn fetch_block(&self, block_id: BlockHashOrNumber, full: bool) -> EthProviderResult<Option<RichBlock>> {
+        // The header and the transaction hashes of a sealed block are reused from its cached block
+        // without the full transactions, only the full transactions are left to fetch
+        let cached_block = if full { self.cache.blocks.get(&(block_id, false)).await } else { None };
+        if let Some(block) = cached_block {
+            if let BlockTransactions::Hashes(hashes) = &block.inner.transactions {
+                let transactions = BlockTransactions::Full(self.transactions_by_hashes(hashes).await?);
+                let block: RichBlock = Block { transactions, ..block.inner }.into();
+                self.cache.blocks.insert((block_id, true), block.clone()).await;
+                return Ok(Some(block));
+            }
+        }
+
+        let (header, transactions) = futures::try_join!(self.header(block_id), self.transactions(block_id, full))?;
+        let header = match header {
             Some(h) => h.header,
             None => return Ok(None),
         };
+        // The pending block has a zero hash
+        let is_sealed = header.hash.is_some_and(|hash| !hash.is_zero());
 
         // The withdrawals are not supported, hence the withdrawals_root should always be empty.
         if let Some(withdrawals_root) = header.withdrawals_root {
@@ -828,22 +2209,221 @@ where
             }
         }
 
-        // This is how reth computes the block size.
-        // `https://github.com/paradigmxyz/reth/blob/v0.2.0-beta.5/crates/rpc/rpc-types-compat/src/block.rs#L66`
-        let size = reth_primitives::Header::try_from(header.clone())
-            .map_err(|_| EthereumDataFormatError::PrimitiveError)?
-            .length();
-        Ok(Some(
-            Block {
-                header,
-                transactions: self.transactions(block_id, full).await?,
-                uncles: Default::default(),
-                size: Some(U256::from(size)),
-                withdrawals: Some(Default::default()),
-                other: Default::default(),
+        let block = EthBlock::new(header, transactions);
+        let size = block.size()?;
+        let block = block.into_rich_block(Some(size));
+
+        if is_sealed {
+            self.cache.blocks.insert((block_id, full), block.clone()).await;
+            if full {
+                self.cache.blocks.insert((block_id, false), with_transaction_hashes(block.clone())).await;
             }
-            .into(),
-        ))
+        }
+        Ok(Some(block))
+    }
+
+    /// Assembles the pending block from the Starknet pending block indexed in the database, or from
+    /// the latest block if there is none, followed by the transactions of the pending pool which
+    /// aren't included in a block yet.
+    async fn pending_block(&self, full: bool) -> EthProviderResult<Option<RichBlock>> {
+        let latest_block_number = self.block_number().await?.to::<u64>();
+        let pending_block_number = latest_block_number.saturating_add(1);
+
+        let (mut header, mut transactions) = match self.header(pending_block_number.into()).await? {
+            Some(pending) => match self.transactions(pending_block_number.into(), true).await? {
+                BlockTransactions::Full(transactions) => (pending.header, transactions),
+                _ => return Err(TransactionError::ExpectedFullTransactions.into()),
+            },
+            None => {
+                let Some(latest) = self.header(latest_block_number.into()).await? else {
+                    return Ok(None);
+                };
+                let timestamp = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default();
+                let header = Header {
+                    number: Some(pending_block_number),
+                    parent_hash: latest.header.hash.unwrap_or_default(),
+                    timestamp: timestamp.max(latest.header.timestamp),
+                    gas_used: Default::default(),
+                    logs_bloom: Default::default(),
+                    ..latest.header
+                };
+                (header, Vec::new())
+            }
+        };
+        // The hash of the pending block is unknown
+        header.hash = None;
+
+        // Append the transactions of the pending pool which aren't included in a block
+        let pool = self.pending_transactions().await?;
+        let hashes = pool.iter().map(|tx| format_hex(tx.hash, HASH_HEX_STRING_LEN)).collect::<Vec<_>>();
+        let included = self
+            .database
+            .get_and_map_to::<B256, StoredTransactionHash>(doc! {"tx.hash": {"$in": hashes}}, doc! {"tx.hash": 1})
+            .await?;
+        let mut pool = pool
+            .into_iter()
+            .filter(|tx| !included.contains(&tx.hash))
+            .filter(|tx| !transactions.iter().any(|pending| pending.hash == tx.hash))
+            .collect::<Vec<_>>();
+        pool.sort_by_key(|tx| (tx.from, tx.nonce));
+
+        for tx in pool {
+            transactions.push(reth_rpc_types::Transaction {
+                block_hash: None,
+                block_number: Some(pending_block_number),
+                transaction_index: Some(transactions.len() as u64),
+                ..tx
+            });
+        }
+
+        let transactions = if full {
+            BlockTransactions::Full(transactions)
+        } else {
+            BlockTransactions::Hashes(transactions.into_iter().map(|tx| tx.hash).collect())
+        };
+
+        let block = EthBlock::new(header, transactions);
+        let size = block.size().ok();
+        Ok(Some(block.into_rich_block(size)))
+    }
+
+    /// Returns the hash of the pending transaction of the signer with the same nonce as the
+    /// transaction, if any. Returns an error if the transaction doesn't bump the fees of the
+    /// pending transaction enough to replace it.
+    async fn replaced_transaction(
+        &self,
+        signer: Address,
+        transaction: &TransactionSigned,
+    ) -> EthProviderResult<Option<B256>> {
+        let mut filter = sender_and_nonce_filter(signer, transaction.nonce());
+        filter.insert("failed_block", None::<i64>);
+        // A transaction with the same hash is a resubmission, not a replacement
+        filter.insert("tx.hash", doc! {"$ne": format_hex(transaction.hash(), HASH_HEX_STRING_LEN)});
+        let Some(replaced) = self
+            .database
+            .get_one::<StoredPendingTransaction>(filter, None)
+            .await?
+            .map(reth_rpc_types::Transaction::from)
+        else {
+            return Ok(None);
+        };
+
+        validate_replacement_fees(transaction, &replaced)?;
+        Ok(Some(replaced.hash))
+    }
+
+    /// Rejects a new transaction if it is already pending, or if its nonce is lower than the nonce
+    /// of its sender. A transaction with the same nonce as a pending transaction of its sender is
+    /// a replacement, validated when submitted.
+    async fn validate_new_transaction(
+        &self,
+        signer: Address,
+        transaction: &TransactionSigned,
+    ) -> EthProviderResult<()> {
+        let mut filter = into_filter("tx.from", &signer, ADDRESS_HEX_STRING_LEN);
+        filter.insert("failed_block", None::<i64>);
+        let pending_transactions =
+            self.database.get_and_map_to::<reth_rpc_types::Transaction, StoredPendingTransaction>(filter, None).await?;
+
+        if pending_transactions.iter().any(|tx| tx.hash == transaction.hash()) {
+            return Err(TransactionError::AlreadyKnown.into());
+        }
+        if pending_transactions.iter().any(|tx| tx.nonce == transaction.nonce()) {
+            return Ok(());
+        }
+
+        let state_nonce: u64 = self.transaction_count(signer, None).await?.try_into().unwrap_or(u64::MAX);
+        if transaction.nonce() < state_nonce {
+            return Err(TransactionError::NonceTooLow(signer, transaction.nonce(), state_nonce).into());
+        }
+        Ok(())
+    }
+
+    /// Returns the nonce of the address once its transactions from the pending pool which follow
+    /// the given nonce without gap are executed.
+    async fn pending_nonce(&self, address: Address, nonce: U256) -> EthProviderResult<U256> {
+        let mut filter = into_filter("tx.from", &address, ADDRESS_HEX_STRING_LEN);
+        filter.insert("failed_block", None::<i64>);
+        let pending_nonces = self
+            .database
+            .get_and_map_to::<reth_rpc_types::Transaction, StoredPendingTransaction>(filter, None)
+            .await?
+            .into_iter()
+            .map(|tx| tx.nonce)
+            .collect::<HashSet<_>>();
+
+        let mut nonce: u64 = nonce.saturating_to();
+        while pending_nonces.contains(&nonce) {
+            nonce += 1;
+        }
+        Ok(U256::from(nonce))
+    }
+
+    /// Returns the changes of the Kakarot account which the state update flags, reading the values
+    /// prior to the block and after it. The values prior to the block are left out for the accounts
+    /// deployed in the block.
+    async fn account_diff(
+        &self,
+        state_update: &KakarotStateUpdate,
+        starknet_address: FieldElement,
+        address: Address,
+        block_number: u64,
+    ) -> EthProviderResult<AccountDiff> {
+        let block_id = Some(BlockId::Number(block_number.into()));
+        let parent_number = block_number.checked_sub(1).filter(|_| !state_update.is_deployed(starknet_address));
+        let parent_id = parent_number.map(|number| BlockId::Number(number.into()));
+
+        let balance = if state_update.balance_changed(starknet_address) {
+            let from = OptionFuture::from(parent_id.map(|parent_id| self.balance(address, Some(parent_id)))).await;
+            delta(from.transpose()?, self.balance(address, block_id).await?)
+        } else {
+            Delta::Unchanged
+        };
+
+        let nonce = if state_update.nonce_changed(starknet_address) {
+            let from =
+                OptionFuture::from(parent_id.map(|parent_id| self.transaction_count(address, Some(parent_id)))).await;
+            let to = self.transaction_count(address, block_id).await?;
+            delta(
+                from.transpose()?.map(|nonce| U64::from(nonce.saturating_to::<u64>())),
+                U64::from(to.saturating_to::<u64>()),
+            )
+        } else {
+            Delta::Unchanged
+        };
+
+        let code = if state_update.code_changed(starknet_address) {
+            let from = OptionFuture::from(parent_id.map(|parent_id| self.get_code(address, Some(parent_id)))).await;
+            delta(from.transpose()?, self.get_code(address, block_id).await?)
+        } else {
+            Delta::Unchanged
+        };
+
+        // The EVM storage slots are stored under hashed storage addresses, which can't be reverted
+        // to the slots: the changes are keyed by the Starknet storage address of the words
+        let storage = futures::stream::iter(state_update.storage_changes(starknet_address))
+            .map(|(key, value)| async move {
+                let from = match parent_number {
+                    Some(number) => Some(
+                        self.starknet_provider
+                            .get_storage_at(starknet_address, key, starknet::core::types::BlockId::Number(number))
+                            .await
+                            .map_err(KakarotError::from)?,
+                    ),
+                    None => None,
+                };
+                let to_b256 = |value: FieldElement| B256::from(value.to_bytes_be());
+                EthProviderResult::Ok((to_b256(key), delta(from.map(to_b256), to_b256(value))))
+            })
+            .buffered(STATE_DIFF_QUERY_CONCURRENCY)
+            .try_filter(|(_, delta)| futures::future::ready(!matches!(delta, Delta::Unchanged)))
+            .try_collect::<BTreeMap<_, _>>()
+            .await?;
+
+        Ok(AccountDiff { balance, nonce, code, storage })
     }
 
     /// Convert the given block id into a Starknet block id
@@ -853,7 +2433,10 @@ where
     ) -> EthProviderResult<starknet::core::types::BlockId> {
         match block_id.into() {
             Some(BlockId::Hash(hash)) => {
-                Ok(EthBlockId::new(BlockId::Hash(hash)).try_into().map_err(EthereumDataFormatError::from)?)
+                // Translate the hash into the number of the indexed block, so that unknown
+                // hashes return an error instead of being forwarded to Starknet.
+                let number = self.canonical_block_number(hash.block_hash).await?.ok_or(EthApiError::UnknownBlock)?;
+                Ok(starknet::core::types::BlockId::Number(number))
             }
             Some(BlockId::Number(number_or_tag)) => {
                 // There is a need to separate the BlockNumberOrTag case into three subcases
@@ -863,14 +2446,22 @@ where
                 // 3. The block number is not found, then we return an error
                 match number_or_tag {
                     BlockNumberOrTag::Number(number) => {
+                        if self.cache.block_hash(number).is_some() {
+                            return Ok(starknet::core::types::BlockId::Number(number));
+                        }
                         let header = self.header(number.into()).await?.ok_or(EthApiError::UnknownBlockNumber)?;
                         // If the block hash is zero, then the block corresponds to a Starknet pending block
-                        if header.header.hash.ok_or(EthApiError::UnknownBlock)?.is_zero() {
+                        let hash = header.header.hash.ok_or(EthApiError::UnknownBlock)?;
+                        if hash.is_zero() {
                             Ok(starknet::core::types::BlockId::Tag(starknet::core::types::BlockTag::Pending))
                         } else {
+                            self.cache.insert_block_hash(number, hash);
                             Ok(starknet::core::types::BlockId::Number(number))
                         }
                     }
+                    BlockNumberOrTag::Finalized | BlockNumberOrTag::Safe => {
+                        Ok(starknet::core::types::BlockId::Number(self.finalized_block_number().await?))
+                    }
                     _ => Ok(EthBlockNumberOrTag::from(number_or_tag).into()),
                 }
             }
@@ -878,7 +2469,102 @@ where
         }
     }
 
+    /// Returns the number of the indexed block with the given hash, from the cached translations
+    /// of the canonical blocks if known.
+    async fn canonical_block_number(&self, hash: B256) -> EthProviderResult<Option<u64>> {
+        if let Some(number) = self.cache.block_number(&hash) {
+            return Ok(Some(number));
+        }
+        let Some(number) = self.header(hash.into()).await?.and_then(|header| header.header.number) else {
+            return Ok(None);
+        };
+        // The pending block is stored with a zero hash
+        if !hash.is_zero() {
+            self.cache.insert_block_hash(number, hash);
+        }
+        Ok(Some(number))
+    }
+
+    /// Returns the number of the latest block accepted on L1. Returns an error if no block is
+    /// accepted on L1 yet, which is always the case on a devnet.
+    async fn finalized_block_number(&self) -> EthProviderResult<u64> {
+        let latest = self.block_number().await?.to::<u64>();
+        self.finality.finalized_block_number(&self.starknet_provider, latest).await?.ok_or(EthApiError::UnknownBlock)
+    }
+
+    /// Returns the code hash of the account. The code hash stored by the account is used when
+    /// possible, so that the bytecode isn't fetched.
+    async fn code_hash(&self, address: Address, block_id: BlockId) -> EthProviderResult<B256> {
+        let starknet_block_id = self.to_starknet_block_id(Some(block_id)).await?;
+        let info = bytecode_info(&self.starknet_provider, starknet_address(address), starknet_block_id).await?;
+        match info.map(|info| info.code_hash) {
+            None | Some(Some(KECCAK_EMPTY)) => Ok(KECCAK_EMPTY),
+            // The EOAs have no code in the EVM, whatever their Starknet account holds
+            Some(Some(_)) if *EOA_EMPTY_CODE && self.is_sender(address).await? => Ok(KECCAK_EMPTY),
+            Some(Some(code_hash)) => Ok(code_hash),
+            // The accounts deployed before the code hash was stored
+            Some(None) => Ok(keccak256(self.get_code(address, Some(block_id)).await?)),
+        }
+    }
+
+    /// Returns the root of the storage tree of the Starknet contract of the account, read from the
+    /// proof provider. Returns the empty root if no proof provider is configured or if the contract
+    /// isn't deployed.
+    async fn storage_root(&self, address: Address, block_id: BlockId) -> EthProviderResult<B256> {
+        let Some(url) = STARKNET_PROOF_PROVIDER_URL.as_ref() else {
+            return Ok(EMPTY_ROOT_HASH);
+        };
+        let starknet_block_id = self.to_starknet_block_id(Some(block_id)).await?;
+        let proof =
+            get_starknet_proof(&self.http_client, url, starknet_block_id, starknet_address(address), &[]).await?;
+        Ok(proof.contract_data.as_ref().map_or(EMPTY_ROOT_HASH, |data| B256::from_slice(&data.root.to_bytes_be())))
+    }
+
     /// Converts the given [`BlockNumberOrTag`] into a block number.
+    /// Returns the EVM bytecode held by the Kakarot account of the address, or None if the account
+    /// isn't deployed.
+    async fn account_code(
+        &self,
+        address: Address,
+        starknet_block_id: starknet::core::types::BlockId,
+    ) -> EthProviderResult<Option<Bytes>> {
+        let address = starknet_address(address);
+        let Some(info) = bytecode_info(&self.starknet_provider, address, starknet_block_id).await? else {
+            return Ok(None);
+        };
+        if let Some(code) = info.code_hash.and_then(|code_hash| self.cache.code_by_hash.get(&code_hash)) {
+            return Ok(Some(code));
+        }
+
+        // The chunks of the bytecode are read concurrently, the entrypoint is only used when the
+        // account doesn't store its code hash or when the chunks don't match it
+        if let Some(code) = fetch_bytecode(&self.starknet_provider, address, starknet_block_id, info).await? {
+            if let Some(code_hash) = info.code_hash {
+                self.cache.code_by_hash.insert(code_hash, code.clone());
+            }
+            return Ok(Some(code));
+        }
+
+        let account_contract = AccountContractReader::new(address, &self.starknet_provider);
+        let bytecode = account_contract.bytecode().block_id(starknet_block_id).call().await;
+
+        if contract_not_found(&bytecode) {
+            return Ok(None);
+        }
+        if entrypoint_not_found(&bytecode) {
+            return Ok(Some(Bytes::default()));
+        }
+
+        let bytecode = bytecode.map_err(KakarotError::from)?.bytecode.0;
+        Ok(Some(Bytes::from(try_from_u8_iterator::<_, Vec<u8>>(bytecode))))
+    }
+
+    /// Returns true if the address sent at least one of the indexed transactions.
+    async fn is_sender(&self, address: Address) -> EthProviderResult<bool> {
+        let filter = into_filter("tx.from", &address, ADDRESS_HEX_STRING_LEN);
+        Ok(self.database.get_one::<StoredTransaction>(filter, None).await?.is_some())
+    }
+
     async fn tag_into_block_number(&self, tag: BlockNumberOrTag) -> EthProviderResult<U64> {
         match tag {
             // Converts the tag representing the earliest block into block number 0.
@@ -886,9 +2572,9 @@ where
             // Converts the tag containing a specific block number into a `U64`.
             BlockNumberOrTag::Number(number) => Ok(U64::from(number)),
             // Returns `self.block_number()` which is the block number of the latest finalized block.
-            BlockNumberOrTag::Latest | BlockNumberOrTag::Finalized | BlockNumberOrTag::Safe => {
-                self.block_number().await
-            }
+            BlockNumberOrTag::Latest => self.block_number().await,
+            // The Starknet blocks are final once accepted on L1.
+            BlockNumberOrTag::Finalized | BlockNumberOrTag::Safe => Ok(U64::from(self.finalized_block_number().await?)),
             // Adds 1 to the block number of the latest finalized block.
             BlockNumberOrTag::Pending => Ok(self.block_number().await?.saturating_add(U64::from(1))),
         }
@@ -951,53 +2637,124 @@ impl<SP> EthDataProvider<SP>
 where
     SP: starknet::providers::Provider + Send + Sync,
 {
+    /// Resubmits the pending transactions which are stuck, i.e. which weren't included in a block
+    /// [`TRANSACTION_STUCK_BLOCKS`] blocks after their submission. Transactions which are still stuck
+    /// after [`TRANSACTION_MAX_RETRIES`] retries are marked as failed, and are no longer returned as
+    /// pending transactions.
     pub async fn retry_transactions(&self) -> EthProviderResult<Vec<B256>> {
         // Initialize an empty vector to store the hashes of retried transactions
         let mut transactions_retried = Vec::new();
+        let block_number: u64 = self.block_number().await?.to();
 
         // Iterate over pending transactions fetched from the database
         for tx in self.database.get::<StoredPendingTransaction>(None, None).await? {
-            // Check if the number of retries exceeds the maximum allowed retries
-            // or if the transaction already exists in the database of finalized transactions
-            if tx.retries + 1 > TRANSACTION_MAX_RETRIES
-                || self
-                    .database
-                    .get_one::<StoredTransaction>(into_filter("tx.hash", &tx.tx.hash, HASH_HEX_STRING_LEN), None)
-                    .await?
-                    .is_some()
-            {
+            let filter = into_filter("tx.hash", &tx.tx.hash, HASH_HEX_STRING_LEN);
+
+            // Check if the transaction already exists in the database of finalized transactions
+            // or if the retention of the failed transaction is over
+            let is_expired = tx.failed_block.is_some_and(|failed_block| {
+                block_number.saturating_sub(failed_block) > FAILED_TRANSACTION_RETENTION_BLOCKS
+            });
+            if is_expired || self.database.get_one::<StoredTransaction>(filter.clone(), None).await?.is_some() {
                 // Delete the pending transaction from the database
-                self.database
-                    .delete_one::<StoredPendingTransaction>(into_filter("tx.hash", &tx.tx.hash, HASH_HEX_STRING_LEN))
-                    .await?;
+                self.database.delete_one::<StoredPendingTransaction>(filter).await?;
 
                 // Continue to the next iteration of the loop
                 continue;
             }
 
+            // Wait for the transaction to be included, unless it is stuck
+            if tx.failed_block.is_some() || block_number.saturating_sub(tx.submitted_block) < TRANSACTION_STUCK_BLOCKS {
+                continue;
+            }
+
+            // Check if the number of retries exceeds the maximum allowed retries
+            if tx.retries + 1 > TRANSACTION_MAX_RETRIES {
+                tracing::warn!("Transaction {:?} failed after {} retries", tx.tx.hash, tx.retries);
+                self.database
+                    .update_one::<StoredPendingTransaction>(
+                        StoredPendingTransaction { failed_block: Some(block_number), ..tx },
+                        filter,
+                        false,
+                    )
+                    .await?;
+                continue;
+            }
+
             // Generate primitive transaction, handle error if any
             let transaction = match rpc_to_ec_recovered_transaction(tx.tx.clone()) {
                 Ok(transaction) => transaction,
                 Err(_) => {
                     // Delete the pending transaction from the database due conversion error
                     // Malformed transaction
-                    self.database
-                        .delete_one::<StoredPendingTransaction>(into_filter(
-                            "tx.hash",
-                            &tx.tx.hash,
-                            HASH_HEX_STRING_LEN,
-                        ))
-                        .await?;
+                    self.database.delete_one::<StoredPendingTransaction>(filter).await?;
                     // Continue to the next iteration of the loop
                     continue;
                 }
             };
 
-            // Create a signed transaction and send it
-            transactions_retried.push(self.send_raw_transaction(transaction.into_signed().envelope_encoded()).await?);
+            // Resubmit the signed transaction
+            let signer = transaction.signer();
+            match self.submit_transaction(transaction.into_signed(), signer).await {
+                Ok(hash) => transactions_retried.push(hash),
+                Err(err) => {
+                    // Count the failed submission as a retry, so that the transaction eventually fails
+                    tracing::warn!("Failed to resubmit transaction {:?}: {:?}", tx.tx.hash, err);
+                    self.database
+                        .update_one::<StoredPendingTransaction>(
+                            StoredPendingTransaction { retries: tx.retries + 1, submitted_block: block_number, ..tx },
+                            filter,
+                            false,
+                        )
+                        .await?;
+                }
+            }
         }
 
         // Return the hashes of retried transactions
         Ok(transactions_retried)
     }
+
+    /// Resubmits at startup the pending transactions whose submission to Starknet was interrupted,
+    /// so that the transactions accepted before a crash or a restart aren't dropped. The
+    /// transactions which were included in a block or whose nonce was used meanwhile are removed
+    /// instead. The transactions which were submitted are left to [`Self::retry_transactions`].
+    pub async fn recover_pending_transactions(&self) -> EthProviderResult<Vec<B256>> {
+        let mut transactions_recovered = Vec::new();
+
+        let filter = doc! {"failed_block": None::<i64>, "unsubmitted": true};
+        let mut pending_transactions = self.database.get::<StoredPendingTransaction>(filter, None).await?;
+        // The transactions of a sender are resubmitted in the order of their nonces
+        pending_transactions.sort_by_key(|tx| (tx.tx.from, tx.tx.nonce));
+
+        for tx in pending_transactions {
+            let filter = into_filter("tx.hash", &tx.tx.hash, HASH_HEX_STRING_LEN);
+            if self.database.get_one::<StoredTransaction>(filter.clone(), None).await?.is_some() {
+                self.database.delete_one::<StoredPendingTransaction>(filter).await?;
+                continue;
+            }
+
+            let Ok(transaction) = rpc_to_ec_recovered_transaction(tx.tx.clone()) else {
+                self.database.delete_one::<StoredPendingTransaction>(filter).await?;
+                continue;
+            };
+            let signer = transaction.signer();
+            let nonce = self.transaction_count(signer, None).await?;
+            if U256::from(transaction.nonce()) < nonce {
+                tracing::info!("Dropping pending transaction {:?}, its nonce was already used", tx.tx.hash);
+                self.database.delete_one::<StoredPendingTransaction>(filter).await?;
+                continue;
+            }
+
+            match self.submit_transaction(transaction.into_signed(), signer).await {
+                Ok(hash) => transactions_recovered.push(hash),
+                Err(err) => {
+                    // The transaction is left to the retry service
+                    tracing::warn!("Failed to resubmit pending transaction {:?}: {:?}", tx.tx.hash, err);
+                }
+            }
+        }
+
+        Ok(transactions_recovered)
+    }
 }
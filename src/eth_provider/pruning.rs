@@ -0,0 +1,192 @@
+//! Pruning of the old records of the database, which otherwise grows with the chain. The
+//! retention policies keep the logs and the cached traces of the most recent blocks, while the
+//! headers, transactions and receipts are kept forever. The policies are applied periodically by
+//! a background service, or once with `kakarot-rpc prune`.
+
+use std::str::FromStr;
+
+use eyre::{eyre, Result};
+use lazy_static::lazy_static;
+use mongodb::bson::doc;
+use tokio::time::{sleep, Duration};
+
+use super::constant::BLOCK_NUMBER_HEX_STRING_LEN;
+use super::database::types::{
+    header::StoredHeader,
+    log::{StoredLog, StoredRemovedLog},
+    trace::StoredTrace,
+};
+use super::database::Database;
+use super::utils::format_hex;
+use crate::eth_rpc::shutdown::ShutdownSignal;
+
+lazy_static! {
+    // Number of most recent blocks whose logs are kept by the pruning. Setting it to 0 keeps all the logs.
+    pub static ref PRUNE_LOGS_BLOCKS: u64 = u64::from_str(
+        &std::env::var("PRUNE_LOGS_BLOCKS").unwrap_or_else(|_| "0".to_string())
+    ).expect("failing to parse PRUNE_LOGS_BLOCKS");
+    // Number of most recent blocks whose cached traces are kept by the pruning. Setting it to 0 keeps all the traces.
+    pub static ref PRUNE_TRACES_BLOCKS: u64 = u64::from_str(
+        &std::env::var("PRUNE_TRACES_BLOCKS").unwrap_or_else(|_| "0".to_string())
+    ).expect("failing to parse PRUNE_TRACES_BLOCKS");
+    // Interval between two prunings of the database (in seconds). Setting it to 0 disables the background pruning.
+    pub static ref PRUNE_INTERVAL: u64 = u64::from_str(
+        &std::env::var("PRUNE_INTERVAL").unwrap_or_else(|_| "0".to_string())
+    ).expect("failing to parse PRUNE_INTERVAL");
+}
+
+/// Retention policies of the pruning, as the number of most recent blocks whose records are
+/// kept. None keeps all the records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningConfig {
+    pub logs: Option<u64>,
+    pub traces: Option<u64>,
+}
+
+impl PruningConfig {
+    /// Reads the retention policies from the `PRUNE_LOGS_BLOCKS` and `PRUNE_TRACES_BLOCKS`
+    /// environment variables.
+    pub fn from_env() -> Self {
+        Self { logs: retention(*PRUNE_LOGS_BLOCKS), traces: retention(*PRUNE_TRACES_BLOCKS) }
+    }
+
+    /// Reads the configuration of a one-shot pruning from the arguments of the command, e.g.
+    /// `prune --logs 10000 --traces 1000`. Like the flags, the `prune` command can be given at any
+    /// position, e.g. `--config kakarot.toml prune`. The policies which aren't given are read from
+    /// the environment. Returns None if the command isn't `prune`. The other flags are ignored.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let args = args.into_iter().collect::<Vec<_>>();
+        if !args.iter().any(|arg| arg == "prune") {
+            return Ok(None);
+        }
+
+        let mut config = Self::from_env();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            if !["--logs", "--traces"].contains(&flag.as_str()) {
+                continue;
+            }
+            let value = value.or_else(|| args.next()).ok_or_else(|| eyre!("Missing value for {flag}"))?;
+            let blocks = retention(value.parse().map_err(|_| eyre!("Invalid value {value} for {flag}"))?);
+            match flag.as_str() {
+                "--logs" => config.logs = blocks,
+                _ => config.traces = blocks,
+            }
+        }
+        Ok(Some(config))
+    }
+
+    /// Returns true if at least one of the policies prunes records.
+    pub const fn is_enabled(&self) -> bool {
+        self.logs.is_some() || self.traces.is_some()
+    }
+}
+
+/// Converts a number of blocks to keep into a retention policy, 0 keeping all the records.
+const fn retention(blocks: u64) -> Option<u64> {
+    if blocks == 0 {
+        None
+    } else {
+        Some(blocks)
+    }
+}
+
+/// Returns the first block whose records are kept, when keeping the given number of blocks up to
+/// the latest block.
+const fn first_kept_block(latest: u64, blocks: u64) -> u64 {
+    latest.saturating_add(1).saturating_sub(blocks)
+}
+
+/// Result of a pruning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningSummary {
+    /// Number of logs deleted.
+    pub logs: u64,
+    /// Number of logs removed by a reorg deleted.
+    pub removed_logs: u64,
+    /// Number of cached traces deleted.
+    pub traces: u64,
+}
+
+/// Applies the retention policies to the database, relative to the latest block stored in it.
+/// The logs removed by the reorgs are pruned with the same retention as the logs.
+pub async fn prune_database(database: &Database, config: &PruningConfig) -> Result<PruningSummary> {
+    let latest = database
+        .get_one::<StoredHeader>(None, doc! {"header.number": -1})
+        .await?
+        .and_then(|stored| stored.header.number);
+    let Some(latest) = latest else {
+        return Ok(PruningSummary::default());
+    };
+
+    let mut summary = PruningSummary::default();
+    if let Some(blocks) = config.logs {
+        let first_kept = format_hex(first_kept_block(latest, blocks), BLOCK_NUMBER_HEX_STRING_LEN);
+        let filter = doc! {"log.blockNumber": {"$lt": first_kept}};
+        summary.logs = database.collection::<StoredLog>().delete_many(filter.clone(), None).await?.deleted_count;
+        summary.removed_logs = database.collection::<StoredRemovedLog>().delete_many(filter, None).await?.deleted_count;
+    }
+    if let Some(blocks) = config.traces {
+        let first_kept = i64::try_from(first_kept_block(latest, blocks)).unwrap_or(i64::MAX);
+        let filter = doc! {"block_number": {"$lt": first_kept}};
+        summary.traces = database.collection::<StoredTrace>().delete_many(filter, None).await?.deleted_count;
+    }
+
+    if summary != PruningSummary::default() {
+        tracing::info!(
+            "Pruned {} logs, {} removed logs and {} traces up to block {latest}",
+            summary.logs,
+            summary.removed_logs,
+            summary.traces
+        );
+    }
+    Ok(summary)
+}
+
+/// Prunes the database every [`PRUNE_INTERVAL`] seconds, until the shutdown signal is received.
+pub async fn start_pruning_service(database: Database, config: PruningConfig, mut shutdown: ShutdownSignal) {
+    loop {
+        if let Err(err) = prune_database(&database, &config).await {
+            tracing::error!("Error while pruning the database: {err:?}");
+        }
+
+        tokio::select! {
+            _ = sleep(Duration::from_secs(*PRUNE_INTERVAL)) => {}
+            _ = shutdown.recv() => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pruning_config_from_args() {
+        // Given
+        let args = ["prune", "--logs=10000", "--traces", "0", "--config", "kakarot.toml"].map(String::from);
+
+        // When
+        let config = PruningConfig::from_args(args).unwrap();
+
+        // Then
+        assert_eq!(config, Some(PruningConfig { logs: Some(10000), traces: None }));
+        assert_eq!(PruningConfig::from_args(["import".to_string()]).unwrap(), None);
+        // The command can follow the flags
+        let args = ["--config", "kakarot.toml", "--traces=10", "prune"].map(String::from);
+        assert_eq!(PruningConfig::from_args(args).unwrap(), Some(PruningConfig { logs: None, traces: Some(10) }));
+        assert!(PruningConfig::from_args(["prune", "--logs"].map(String::from)).is_err());
+        assert!(PruningConfig::from_args(["prune", "--logs", "all"].map(String::from)).is_err());
+    }
+
+    #[test]
+    fn test_first_kept_block() {
+        assert_eq!(first_kept_block(100, 10), 91);
+        assert_eq!(first_kept_block(100, 1), 100);
+        assert_eq!(first_kept_block(5, 10), 0);
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use reth_primitives::B256;
+use reth_rpc_types::{BlockHashOrNumber, RichBlock, TransactionReceipt};
+use tokio::sync::OnceCell;
+
+/// Coalesces the concurrent calls with the same key: the first call runs the request, and the
+/// calls made while it is in flight wait for its result instead of running their own. Failed
+/// requests aren't shared, a waiting call runs its own request once the first one fails.
+pub struct SingleFlight<K, V> {
+    in_flight: Mutex<HashMap<K, Arc<OnceCell<V>>>>,
+}
+
+impl<K, V> SingleFlight<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    /// Runs the request unless a request with the same key is in flight, in which case its
+    /// result is returned.
+    pub async fn run<F, Fut, E>(&self, key: K, request: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        let cell = self.lock().entry(key.clone()).or_default().clone();
+        let result = cell.get_or_try_init(request).await.cloned();
+
+        // The following calls run a new request, unless another one already replaced this one
+        let mut in_flight = self.lock();
+        if in_flight.get(&key).is_some_and(|current| Arc::ptr_eq(current, &cell)) {
+            in_flight.remove(&key);
+        }
+        result
+    }
+
+    /// Returns the number of requests in flight.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no request is in flight.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<K, Arc<OnceCell<V>>>> {
+        self.in_flight.lock().expect("Failed to lock single flight")
+    }
+}
+
+impl<K, V> Default for SingleFlight<K, V> {
+    fn default() -> Self {
+        Self { in_flight: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl<K, V> std::fmt::Debug for SingleFlight<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SingleFlight").finish_non_exhaustive()
+    }
+}
+
+/// Requests of the provider which are coalesced when they are made concurrently, as explorers
+/// issue bursts of identical calls.
+#[derive(Debug, Default)]
+pub struct InFlightRequests {
+    /// Blocks by hash or number, with full transactions or hashes only.
+    pub blocks: SingleFlight<(BlockHashOrNumber, bool), Option<RichBlock>>,
+    /// Receipts by transaction hash.
+    pub receipts: SingleFlight<B256, Option<TransactionReceipt>>,
+    /// Receipts of a block by number, shared by the receipts of its transactions, along with
+    /// whether they are complete.
+    pub block_receipts: SingleFlight<u64, Option<(Arc<Vec<TransactionReceipt>>, bool)>>,
+    /// Base fee read from the Kakarot contract.
+    pub base_fee: SingleFlight<(), u128>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_single_flight_coalesces_concurrent_calls() {
+        // Given
+        let single_flight = SingleFlight::<u64, u64>::default();
+        let calls = AtomicUsize::new(0);
+        let request = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok::<_, ()>(42)
+        };
+
+        // When
+        let results = futures::future::join_all((0..10).map(|_| single_flight.run(1, request))).await;
+
+        // Then
+        assert!(results.into_iter().all(|result| result == Ok(42)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(single_flight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_does_not_share_errors() {
+        // Given
+        let single_flight = SingleFlight::<u64, u64>::default();
+        let calls = AtomicUsize::new(0);
+        let request = || async {
+            // Only the first request fails
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if call == 0 {
+                Err(())
+            } else {
+                Ok(42)
+            }
+        };
+
+        // When
+        let (first, second) = tokio::join!(single_flight.run(1, request), single_flight.run(1, request));
+
+        // Then
+        assert_eq!(first, Err(()));
+        assert_eq!(second, Ok(42));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_runs_sequential_calls() {
+        // Given
+        let single_flight = SingleFlight::<u64, u64>::default();
+        let calls = AtomicUsize::new(0);
+        let request = || async { Ok::<_, ()>(calls.fetch_add(1, Ordering::SeqCst) as u64) };
+
+        // When
+        let first = single_flight.run(1, request).await;
+        let second = single_flight.run(1, request).await;
+
+        // Then
+        assert_eq!((first, second), (Ok(0), Ok(1)));
+    }
+}
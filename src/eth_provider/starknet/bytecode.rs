@@ -0,0 +1,147 @@
+//! Reads of the EVM bytecode of the Kakarot accounts. The bytecode is stored by the account in
+//! chunks of 31 bytes, at the storage addresses starting from 0, which are read concurrently
+//! instead of through the `bytecode` entrypoint which reads them one by one. The reassembled
+//! bytecode is checked against the code hash stored by the account.
+
+use futures::{StreamExt, TryStreamExt};
+use reth_primitives::{keccak256, Bytes, B256, KECCAK_EMPTY, U256};
+use starknet::core::types::{BlockId, StarknetError};
+use starknet::core::utils::get_storage_var_address;
+use starknet::providers::{Provider, ProviderError};
+use starknet_crypto::FieldElement;
+
+use crate::eth_provider::error::KakarotError;
+use crate::eth_provider::provider::EthProviderResult;
+use crate::models::felt::Felt252Wrapper;
+
+/// Number of bytes of the bytecode stored in each storage slot.
+pub const BYTECODE_CHUNK_SIZE: usize = 31;
+/// Maximum number of concurrent storage reads when fetching a bytecode.
+pub const BYTECODE_QUERY_CONCURRENCY: usize = 16;
+/// Maximum size of a deployed bytecode (EIP-170).
+pub const MAX_BYTECODE_SIZE: usize = 0x6000;
+
+/// Code hash and length of the bytecode of a Kakarot account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BytecodeInfo {
+    /// Keccak hash of the bytecode, or None if the account doesn't store it.
+    pub code_hash: Option<B256>,
+    /// Length of the bytecode in bytes.
+    pub len: usize,
+}
+
+/// Reads the code hash and the length of the bytecode of the account. Returns None if no
+/// contract is deployed at the address.
+pub async fn bytecode_info<SP>(
+    starknet_provider: &SP,
+    address: FieldElement,
+    block_id: BlockId,
+) -> EthProviderResult<Option<BytecodeInfo>>
+where
+    SP: Provider + Sync,
+{
+    let code_hash_address = get_storage_var_address("Account_code_hash", &[]).expect("Storage var name is not ASCII");
+    let len_address = get_storage_var_address("Account_bytecode_len", &[]).expect("Storage var name is not ASCII");
+
+    let (low, high, len) = match futures::try_join!(
+        starknet_provider.get_storage_at(address, code_hash_address, block_id),
+        starknet_provider.get_storage_at(address, code_hash_address + FieldElement::ONE, block_id),
+        starknet_provider.get_storage_at(address, len_address, block_id),
+    ) {
+        Ok(values) => values,
+        Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => return Ok(None),
+        Err(err) => return Err(KakarotError::from(err).into()),
+    };
+
+    let low = U256::from(Felt252Wrapper::from(low));
+    let high = U256::from(Felt252Wrapper::from(high));
+    let code_hash = B256::from(low + (high << 128));
+    // The accounts deployed before the code hash was stored have a zero code hash
+    let code_hash = (code_hash != B256::ZERO).then_some(code_hash);
+    let len = usize::try_from(U256::from(Felt252Wrapper::from(len))).unwrap_or(usize::MAX);
+
+    Ok(Some(BytecodeInfo { code_hash, len }))
+}
+
+/// Reads the bytecode of the account from its storage, with concurrent reads of the chunks.
+/// Returns None if the account doesn't store its code hash, if the length exceeds the maximum
+/// size of a bytecode or if the reassembled bytecode doesn't match the code hash.
+pub async fn fetch_bytecode<SP>(
+    starknet_provider: &SP,
+    address: FieldElement,
+    block_id: BlockId,
+    info: BytecodeInfo,
+) -> EthProviderResult<Option<Bytes>>
+where
+    SP: Provider + Sync,
+{
+    let Some(code_hash) = info.code_hash else {
+        return Ok(None);
+    };
+    if code_hash == KECCAK_EMPTY {
+        return Ok(Some(Bytes::default()));
+    }
+    if info.len > MAX_BYTECODE_SIZE {
+        return Ok(None);
+    }
+
+    let chunks = futures::stream::iter(0..info.len.div_ceil(BYTECODE_CHUNK_SIZE))
+        .map(|index| starknet_provider.get_storage_at(address, FieldElement::from(index), block_id))
+        .buffered(BYTECODE_QUERY_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await
+        .map_err(KakarotError::from)?;
+
+    let bytecode = reassemble_bytecode(&chunks, info.len);
+    if keccak256(&bytecode) != code_hash {
+        tracing::warn!("Bytecode of account {address:#x} doesn't match its code hash {code_hash}");
+        return Ok(None);
+    }
+    Ok(Some(bytecode))
+}
+
+/// Reassembles the bytecode of the given length from its chunks. Each chunk holds 31 bytes,
+/// except the last one which holds the remaining bytes.
+pub fn reassemble_bytecode(chunks: &[FieldElement], len: usize) -> Bytes {
+    let mut bytecode = Vec::with_capacity(len);
+    for chunk in chunks {
+        let remaining = (len - bytecode.len()).min(BYTECODE_CHUNK_SIZE);
+        let bytes = chunk.to_bytes_be();
+        bytecode.extend_from_slice(&bytes[bytes.len() - remaining..]);
+    }
+    bytecode.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reassemble_bytecode() {
+        // Given
+        let bytecode = (0..70u8).collect::<Vec<_>>();
+        let chunks = bytecode
+            .chunks(BYTECODE_CHUNK_SIZE)
+            .map(|chunk| FieldElement::from_byte_slice_be(chunk).unwrap())
+            .collect::<Vec<_>>();
+
+        // When
+        let reassembled = reassemble_bytecode(&chunks, bytecode.len());
+
+        // Then
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(reassembled, Bytes::from(bytecode));
+    }
+
+    #[test]
+    fn test_reassemble_bytecode_with_leading_zeros() {
+        // Given
+        let bytecode = vec![0u8, 0, 1];
+
+        // When
+        let reassembled = reassemble_bytecode(&[FieldElement::ONE], bytecode.len());
+
+        // Then
+        assert_eq!(reassembled, Bytes::from(bytecode));
+    }
+}
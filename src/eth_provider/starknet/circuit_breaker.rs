@@ -0,0 +1,210 @@
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+
+/// State of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CircuitState {
+    /// The requests are sent to the upstream.
+    Closed,
+    /// The upstream failed repeatedly, the requests fail fast without being sent.
+    Open,
+    /// The upstream was open long enough, a single request is sent to probe its recovery.
+    HalfOpen,
+}
+
+/// Configuration of the circuit breakers of the Starknet providers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Number of consecutive failures after which the circuit opens.
+    pub failure_threshold: u32,
+    /// Duration during which the circuit stays open before probing the upstream.
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self { failure_threshold: 5, open_duration: Duration::from_secs(30) }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Create a new `CircuitBreakerConfig` from the `STARKNET_CIRCUIT_BREAKER_THRESHOLD` and
+    /// `STARKNET_CIRCUIT_BREAKER_OPEN_DURATION` (in seconds) environment variables.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        let var = |name: &str| -> Result<Option<u64>> {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| u64::from_str(value.trim()).map_err(|err| eyre!("Invalid {name} {value}: {err}")))
+                .transpose()
+        };
+
+        if let Some(threshold) = var("STARKNET_CIRCUIT_BREAKER_THRESHOLD")? {
+            // A threshold of 0 would keep the circuit open, a single failure opens it instead
+            config.failure_threshold = u32::try_from(threshold)?.max(1);
+        }
+        if let Some(open_duration) = var("STARKNET_CIRCUIT_BREAKER_OPEN_DURATION")? {
+            config.open_duration = Duration::from_secs(open_duration);
+        }
+        Ok(config)
+    }
+}
+
+/// Circuit breaker of an upstream provider. The circuit opens after a number of consecutive
+/// failures, during which the requests fail fast instead of piling up on a degraded upstream.
+/// Once open for long enough, the circuit half opens and lets a single request probe the
+/// upstream: the circuit closes if it succeeds and opens again otherwise.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<CircuitBreakerInner>,
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreakerInner {
+    consecutive_failures: u32,
+    /// Instant at which the circuit opened, None if it is closed.
+    opened_at: Option<Instant>,
+    /// Instant at which the probe of the half open circuit started, if any.
+    probe_started_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Create a new closed [`CircuitBreaker`].
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self { config, inner: Mutex::new(CircuitBreakerInner::default()) }
+    }
+
+    /// Returns the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        let inner = self.inner.lock().expect("Failed to lock circuit breaker");
+        self.state_at(&inner, Instant::now())
+    }
+
+    fn state_at(&self, inner: &CircuitBreakerInner, now: Instant) -> CircuitState {
+        match inner.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if now < opened_at + self.config.open_duration => CircuitState::Open,
+            // A probe which never completed, e.g. because its request was dropped, is abandoned
+            // after the open duration so that the circuit doesn't stay open forever
+            Some(_) if inner.probe_started_at.map_or(false, |started| now < started + self.config.open_duration) => {
+                CircuitState::Open
+            }
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Returns true if a request can be sent to the upstream. A half open circuit lets a single
+    /// probe request through.
+    pub fn try_acquire(&self) -> bool {
+        let mut inner = self.inner.lock().expect("Failed to lock circuit breaker");
+        let now = Instant::now();
+        match self.state_at(&inner, now) {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                inner.probe_started_at = Some(now);
+                true
+            }
+        }
+    }
+
+    /// Records a successful request, which closes the circuit.
+    pub fn record_success(&self) {
+        *self.inner.lock().expect("Failed to lock circuit breaker") = CircuitBreakerInner::default();
+    }
+
+    /// Records a failed request, which opens the circuit if the failure threshold is reached or
+    /// if the request was probing the upstream.
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().expect("Failed to lock circuit breaker");
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        if inner.probe_started_at.is_some() || inner.consecutive_failures >= self.config.failure_threshold {
+            inner.opened_at = Some(Instant::now());
+            inner.probe_started_at = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker_opens_after_threshold() {
+        // Given
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig { failure_threshold: 3, ..Default::default() });
+
+        // When
+        breaker.record_failure();
+        breaker.record_failure();
+        let before_threshold = breaker.state();
+        breaker.record_failure();
+
+        // Then
+        assert_eq!(before_threshold, CircuitState::Closed);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.try_acquire());
+    }
+
+    #[test]
+    fn test_circuit_breaker_success_resets_failures() {
+        // Given
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig { failure_threshold: 2, ..Default::default() });
+
+        // When
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+
+        // Then
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire());
+    }
+
+    #[test]
+    fn test_circuit_breaker_half_open_probe() {
+        // Given
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig { failure_threshold: 1, open_duration: Duration::ZERO });
+        breaker.record_failure();
+
+        // When
+        let probe = breaker.try_acquire();
+        let state_during_probe = breaker.state();
+
+        // Then
+        assert!(probe);
+        // The open duration is zero, so the abandoned probe check can't keep the circuit open
+        assert_eq!(state_during_probe, CircuitState::HalfOpen);
+        breaker.record_failure();
+        assert!(breaker.inner.lock().unwrap().opened_at.is_some());
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_breaker_single_probe() {
+        // Given
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            open_duration: Duration::from_millis(50),
+        });
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(60));
+
+        // When
+        let probe = breaker.try_acquire();
+        let second = breaker.try_acquire();
+
+        // Then
+        // Only the first request probes the half open circuit
+        assert!(probe);
+        assert!(!second);
+    }
+}
@@ -0,0 +1,124 @@
+use std::time::{Duration, Instant};
+
+use eyre::Result;
+use reth_primitives::Address;
+use starknet::accounts::Call;
+use starknet::core::types::{
+    BlockId, BlockTag, ExecutionResult, MaybePendingTransactionReceipt, PendingTransactionReceipt, StarknetError,
+    TransactionReceipt,
+};
+use starknet::macros::selector;
+use starknet::providers::{Provider, ProviderError};
+use starknet_crypto::FieldElement;
+
+use super::kakarot_core::{starknet_address, KAKAROT_ADDRESS};
+use super::relayer::RelayerPool;
+use crate::eth_provider::constant::{EOA_DEPLOYMENT_POLL_INTERVAL_MS, EOA_DEPLOYMENT_TIMEOUT};
+use crate::eth_provider::error::{KakarotError, TransactionError};
+use crate::eth_provider::provider::EthProviderResult;
+use crate::into_via_wrapper;
+use crate::models::felt::Felt252Wrapper;
+
+/// Deploys the Kakarot accounts of the senders of the first transactions, so that the users don't
+/// have to deploy their accounts through a separate tool. The deployments are paid by the relayers
+/// of the pool.
+#[derive(Debug)]
+pub struct EoaDeployer {
+    relayers: RelayerPool,
+}
+
+impl EoaDeployer {
+    /// Create a new [`EoaDeployer`] paying the deployments with the relayers of the pool.
+    pub const fn new(relayers: RelayerPool) -> Self {
+        Self { relayers }
+    }
+
+    /// Create a new [`EoaDeployer`] from the relayers set in the environment. Returns None if no
+    /// relayer is set, in which case the accounts aren't deployed by the RPC.
+    pub fn from_env() -> Result<Option<Self>> {
+        Ok(RelayerPool::from_env()?.map(Self::new))
+    }
+
+    /// Deploys the Kakarot account of the EVM address if it isn't deployed yet, and waits for the
+    /// deployment to be executed. Returns true if the account was deployed.
+    pub async fn deploy_if_missing<P>(&self, provider: &P, evm_address: Address) -> EthProviderResult<bool>
+    where
+        P: Provider + Send + Sync,
+    {
+        let starknet_address = starknet_address(evm_address);
+        if is_deployed(provider, starknet_address).await? {
+            return Ok(false);
+        }
+
+        let relayer = self.relayers.select(provider).await?;
+        let call = Call {
+            to: *KAKAROT_ADDRESS,
+            selector: selector!("deploy_externally_owned_account"),
+            calldata: vec![into_via_wrapper!(evm_address)],
+        };
+        let result = match relayer.execute(provider, vec![call]).await {
+            Ok(transaction_hash) => {
+                tracing::info!(
+                    "Deploying the account of {:?} at {:#x} with relayer {:#x}: Starknet Hash: {:#x}",
+                    evm_address,
+                    starknet_address,
+                    relayer.address(),
+                    transaction_hash
+                );
+                wait_for_execution(provider, transaction_hash).await
+            }
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(()) => Ok(true),
+            // The account may have been deployed by a concurrent transaction of the same sender
+            Err(_) if is_deployed(provider, starknet_address).await? => Ok(false),
+            Err(reason) => Err(TransactionError::AccountDeploymentFailed(evm_address, reason).into()),
+        }
+    }
+}
+
+/// Returns true if a contract is deployed at the Starknet address, including in the pending block.
+async fn is_deployed<P: Provider + Send + Sync>(provider: &P, address: FieldElement) -> EthProviderResult<bool> {
+    match provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), address).await {
+        Ok(_) => Ok(true),
+        Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(false),
+        Err(err) => Err(KakarotError::from(err).into()),
+    }
+}
+
+/// Waits for the Starknet transaction to be executed, in the pending block or in a sealed block.
+/// Returns the reason of the failure if the transaction reverted or wasn't executed in time.
+async fn wait_for_execution<P: Provider + Send + Sync>(
+    provider: &P,
+    transaction_hash: FieldElement,
+) -> std::result::Result<(), String> {
+    let deadline = Instant::now() + Duration::from_secs(EOA_DEPLOYMENT_TIMEOUT);
+    loop {
+        let execution_result = match provider.get_transaction_receipt(transaction_hash).await {
+            Ok(MaybePendingTransactionReceipt::Receipt(TransactionReceipt::Invoke(receipt))) => {
+                receipt.execution_result
+            }
+            Ok(MaybePendingTransactionReceipt::PendingReceipt(PendingTransactionReceipt::Invoke(receipt))) => {
+                receipt.execution_result
+            }
+            Ok(_) => return Err(format!("unexpected receipt for transaction {transaction_hash:#x}")),
+            Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {
+                if Instant::now() >= deadline {
+                    return Err(format!(
+                        "transaction {transaction_hash:#x} not executed after {EOA_DEPLOYMENT_TIMEOUT}s"
+                    ));
+                }
+                tokio::time::sleep(Duration::from_millis(EOA_DEPLOYMENT_POLL_INTERVAL_MS)).await;
+                continue;
+            }
+            Err(err) => return Err(err.to_string()),
+        };
+
+        return match execution_result {
+            ExecutionResult::Succeeded => Ok(()),
+            ExecutionResult::Reverted { reason } => Err(reason),
+        };
+    }
+}
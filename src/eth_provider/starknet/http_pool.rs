@@ -0,0 +1,262 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use starknet::providers::jsonrpc::{JsonRpcMethod, JsonRpcResponse, JsonRpcTransport};
+use tokio::sync::Semaphore;
+use url::Url;
+
+use crate::prometheus_handler::{register, Gauge, GaugeVec, Opts, PrometheusError, Registry, I64};
+
+/// Configuration of the pool of HTTP connections shared by the requests to the Starknet providers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpPoolConfig {
+    /// Maximum number of concurrent requests to a provider, each holding a connection.
+    pub max_connections: usize,
+    /// Maximum number of idle connections kept open to a provider.
+    pub max_idle_connections: usize,
+    /// Duration after which an idle connection is closed.
+    pub idle_timeout: Duration,
+    /// Interval of the TCP keep-alive probes of the connections.
+    pub tcp_keepalive: Duration,
+    /// Whether the providers are reached over HTTP/2, multiplexing the requests on the
+    /// connections. The providers must support HTTP/2 without negotiation.
+    pub http2: bool,
+}
+
+impl Default for HttpPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 64,
+            max_idle_connections: 32,
+            idle_timeout: Duration::from_secs(90),
+            tcp_keepalive: Duration::from_secs(60),
+            http2: false,
+        }
+    }
+}
+
+impl HttpPoolConfig {
+    /// Create a new `HttpPoolConfig` from the `STARKNET_HTTP_MAX_CONNECTIONS`,
+    /// `STARKNET_HTTP_MAX_IDLE_CONNECTIONS`, `STARKNET_HTTP_IDLE_TIMEOUT`,
+    /// `STARKNET_HTTP_KEEPALIVE` and `STARKNET_HTTP2` environment variables.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let number = |name: &str| -> Result<Option<u64>> {
+            var(name)
+                .map(|value| u64::from_str(value.trim()).map_err(|err| eyre!("Invalid {name} {value}: {err}")))
+                .transpose()
+        };
+
+        if let Some(max_connections) = number("STARKNET_HTTP_MAX_CONNECTIONS")? {
+            config.max_connections = usize::try_from(max_connections)?.max(1);
+        }
+        if let Some(max_idle_connections) = number("STARKNET_HTTP_MAX_IDLE_CONNECTIONS")? {
+            config.max_idle_connections = usize::try_from(max_idle_connections)?;
+        }
+        if let Some(idle_timeout) = number("STARKNET_HTTP_IDLE_TIMEOUT")? {
+            config.idle_timeout = Duration::from_secs(idle_timeout);
+        }
+        if let Some(tcp_keepalive) = number("STARKNET_HTTP_KEEPALIVE")? {
+            config.tcp_keepalive = Duration::from_secs(tcp_keepalive);
+        }
+        if let Some(http2) = var("STARKNET_HTTP2") {
+            config.http2 =
+                bool::from_str(http2.trim()).map_err(|err| eyre!("Invalid STARKNET_HTTP2 {http2}: {err}"))?;
+        }
+        Ok(config)
+    }
+
+    /// Builds the HTTP client shared by the transports of all the providers, whose pool keeps the
+    /// connections open between the requests.
+    pub fn build_client(&self) -> Result<reqwest_011::Client> {
+        let mut builder = reqwest_011::Client::builder()
+            .pool_max_idle_per_host(self.max_idle_connections)
+            .pool_idle_timeout(self.idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive)
+            .tcp_nodelay(true);
+        if self.http2 {
+            builder = builder
+                .http2_prior_knowledge()
+                .http2_keep_alive_interval(self.tcp_keepalive)
+                .http2_keep_alive_while_idle(true)
+                .http2_adaptive_window(true);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// Metrics on the usage of the pool of HTTP connections of each provider.
+#[derive(Debug, Clone)]
+pub struct HttpPoolMetrics {
+    /// Maximum number of concurrent requests to a provider.
+    max_connections: GaugeVec<I64>,
+    /// Number of requests in flight to a provider.
+    connections_in_use: GaugeVec<I64>,
+    /// Number of requests waiting for a connection to a provider.
+    connections_waiting: GaugeVec<I64>,
+}
+
+impl HttpPoolMetrics {
+    /// Create an instance of metrics
+    pub fn new(metrics_registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            max_connections: register(
+                GaugeVec::new(
+                    Opts::new("starknet_http_max_connections", "Maximum number of connections to a Starknet provider"),
+                    &["provider"],
+                )?,
+                metrics_registry,
+            )?,
+            connections_in_use: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "starknet_http_connections_in_use",
+                        "Number of requests in flight to a Starknet provider",
+                    ),
+                    &["provider"],
+                )?,
+                metrics_registry,
+            )?,
+            connections_waiting: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "starknet_http_connections_waiting",
+                        "Number of requests waiting for a connection to a Starknet provider",
+                    ),
+                    &["provider"],
+                )?,
+                metrics_registry,
+            )?,
+        })
+    }
+}
+
+/// A JSON-RPC transport wrapper which bounds the number of concurrent requests to a provider, and
+/// reports the usage of its connections.
+#[derive(Debug)]
+pub struct PooledTransport<T> {
+    transport: T,
+    permits: Arc<Semaphore>,
+    in_use: Gauge<I64>,
+    waiting: Gauge<I64>,
+}
+
+impl<T> PooledTransport<T> {
+    /// Create a new [`PooledTransport`] to the provider at the given URL. Only the host of the URL
+    /// labels the metrics, since the path of the URL may hold an API key.
+    pub fn new(transport: T, url: &Url, max_connections: usize, metrics: &HttpPoolMetrics) -> Self {
+        let provider = url.host_str().unwrap_or_default();
+        let max_connections = max_connections.max(1);
+        metrics.max_connections.with_label_values(&[provider]).set(max_connections as i64);
+        Self {
+            transport,
+            permits: Arc::new(Semaphore::new(max_connections)),
+            in_use: metrics.connections_in_use.with_label_values(&[provider]),
+            waiting: metrics.connections_waiting.with_label_values(&[provider]),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> JsonRpcTransport for PooledTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync,
+{
+    type Error = T::Error;
+
+    async fn send_request<P, R>(&self, method: JsonRpcMethod, params: P) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let waiting = GaugeGuard::new(&self.waiting);
+        // The semaphore is never closed
+        let _permit = self.permits.acquire().await;
+        drop(waiting);
+
+        let _in_use = GaugeGuard::new(&self.in_use);
+        self.transport.send_request(method, params).await
+    }
+}
+
+/// Increments a gauge until dropped, including when the request is cancelled, e.g. on a timeout.
+struct GaugeGuard<'a>(&'a Gauge<I64>);
+
+impl<'a> GaugeGuard<'a> {
+    fn new(gauge: &'a Gauge<I64>) -> Self {
+        gauge.inc();
+        Self(gauge)
+    }
+}
+
+impl Drop for GaugeGuard<'_> {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("mock transport error")]
+    struct MockError;
+
+    /// A transport which records the maximum number of concurrent requests it served.
+    #[derive(Debug, Default)]
+    struct MockTransport {
+        concurrent: AtomicUsize,
+        max_concurrent: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl JsonRpcTransport for MockTransport {
+        type Error = MockError;
+
+        async fn send_request<P, R>(&self, _method: JsonRpcMethod, _params: P) -> Result<JsonRpcResponse<R>, MockError>
+        where
+            P: Serialize + Send + Sync,
+            R: DeserializeOwned,
+        {
+            let concurrent = self.concurrent.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_concurrent.fetch_max(concurrent, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.concurrent.fetch_sub(1, Ordering::SeqCst);
+            Ok(serde_json::from_value(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": 1})).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pooled_transport_limits_concurrent_requests() {
+        // Given
+        let metrics = HttpPoolMetrics::new(&Registry::new()).unwrap();
+        let url = Url::parse("http://localhost:5050/rpc").unwrap();
+        let transport = PooledTransport::new(MockTransport::default(), &url, 2, &metrics);
+
+        // When
+        let requests =
+            (0..6).map(|_| transport.send_request::<_, u64>(JsonRpcMethod::BlockNumber, serde_json::json!([])));
+        let responses = futures::future::join_all(requests).await;
+
+        // Then
+        assert!(responses.iter().all(Result::is_ok));
+        assert_eq!(transport.transport.max_concurrent.load(Ordering::SeqCst), 2);
+        assert_eq!(metrics.max_connections.with_label_values(&["localhost"]).get(), 2);
+        assert_eq!(transport.in_use.get(), 0);
+        assert_eq!(transport.waiting.get(), 0);
+    }
+
+    #[test]
+    fn test_http_pool_build_client() {
+        for http2 in [false, true] {
+            assert!(HttpPoolConfig { http2, ..Default::default() }.build_client().is_ok());
+        }
+    }
+}
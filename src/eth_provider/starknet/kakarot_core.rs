@@ -34,7 +34,7 @@ pub mod core {
     use super::*;
     abigen_legacy!(KakarotCore, "./.kakarot/artifacts/kakarot.json");
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct CallInput {
         pub(crate) nonce: FieldElement,
         pub(crate) from: FieldElement,
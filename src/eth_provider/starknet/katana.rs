@@ -0,0 +1,91 @@
+use reth_primitives::U256;
+use serde_json::{json, Value};
+use starknet::core::utils::get_storage_var_address;
+use starknet_crypto::FieldElement;
+use url::Url;
+
+use super::STARKNET_NATIVE_TOKEN;
+use crate::eth_provider::error::KakarotError;
+use crate::eth_provider::utils::split_u256;
+
+/// Client of the dev API of Katana, used to mine blocks, set the timestamp of the next block and
+/// write the storage of the contracts.
+#[derive(Debug, Clone)]
+pub struct KatanaDevClient {
+    url: Url,
+    client: reqwest_011::Client,
+}
+
+impl KatanaDevClient {
+    /// Create a new `KatanaDevClient` sending the requests to Katana over the shared HTTP client.
+    pub fn new(url: Url, client: reqwest_011::Client) -> Self {
+        Self { url, client }
+    }
+
+    /// Mines a new block.
+    pub async fn generate_block(&self) -> Result<(), KakarotError> {
+        self.request("dev_generateBlock", json!([])).await.map(|_| ())
+    }
+
+    /// Sets the timestamp of the next block.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), KakarotError> {
+        self.request("dev_setNextBlockTimestamp", json!([timestamp])).await.map(|_| ())
+    }
+
+    /// Increases the timestamp of the next block by the given number of seconds.
+    pub async fn increase_next_block_timestamp(&self, seconds: u64) -> Result<(), KakarotError> {
+        self.request("dev_increaseNextBlockTimestamp", json!([seconds])).await.map(|_| ())
+    }
+
+    /// Writes the value at the given storage key of the contract.
+    pub async fn set_storage_at(
+        &self,
+        contract_address: FieldElement,
+        key: FieldElement,
+        value: FieldElement,
+    ) -> Result<(), KakarotError> {
+        self.request("dev_setStorageAt", json!([contract_address, key, value])).await.map(|_| ())
+    }
+
+    /// Sets the native token balance of the Starknet address, which is the balance of its
+    /// Kakarot account.
+    pub async fn set_native_token_balance(
+        &self,
+        starknet_address: FieldElement,
+        balance: U256,
+    ) -> Result<(), KakarotError> {
+        let key = get_storage_var_address("ERC20_balances", &[starknet_address])
+            .map_err(|err| KakarotError::DevApiError(err.to_string()))?;
+        let [low, high] = split_u256::<FieldElement>(balance);
+        self.set_storage_at(*STARKNET_NATIVE_TOKEN, key, low).await?;
+        self.set_storage_at(*STARKNET_NATIVE_TOKEN, key + FieldElement::ONE, high).await
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, KakarotError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = self
+            .client
+            .post(self.url.clone())
+            .header("Content-Type", "application/json")
+            .body(request.to_string())
+            .send()
+            .await
+            .map_err(|err| KakarotError::DevApiError(err.to_string()))?
+            .text()
+            .await
+            .map_err(|err| KakarotError::DevApiError(err.to_string()))?;
+
+        let mut response: Value =
+            serde_json::from_str(&response).map_err(|err| KakarotError::DevApiError(err.to_string()))?;
+        if let Some(error) = response.get("error") {
+            return Err(KakarotError::DevApiError(error.to_string()));
+        }
+        Ok(response["result"].take())
+    }
+}
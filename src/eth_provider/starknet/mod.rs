@@ -1,5 +1,14 @@
 #![allow(non_snake_case, clippy::derive_partial_eq_without_eq)]
+pub mod bytecode;
+pub mod circuit_breaker;
+pub mod deployer;
+pub mod http_pool;
 pub mod kakarot_core;
+pub mod katana;
+pub mod proof;
+pub mod relayer;
+pub mod state_diff;
+pub mod transport;
 
 use cainome::rs::abigen_legacy;
 use lazy_static::lazy_static;
@@ -8,6 +17,11 @@ use starknet_crypto::FieldElement;
 abigen_legacy!(ERC20, "./.kakarot/artifacts/fixtures/ERC20.json");
 
 lazy_static! {
-    pub static ref STARKNET_NATIVE_TOKEN: FieldElement =
-        FieldElement::from_hex_be("0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7").unwrap();
+    // Address of the Starknet token used to pay the fees, whose balance is the Ethereum balance
+    pub static ref STARKNET_NATIVE_TOKEN: FieldElement = FieldElement::from_hex_be(
+        &std::env::var("STARKNET_NATIVE_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+            .unwrap_or_else(|| "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7".to_string())
+    ).expect("failing to parse STARKNET_NATIVE_TOKEN");
 }
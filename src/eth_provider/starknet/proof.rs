@@ -0,0 +1,201 @@
+use reth_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use starknet::core::types::{BlockId, BlockTag};
+use starknet_crypto::FieldElement;
+use url::Url;
+
+use crate::eth_provider::error::KakarotError;
+
+/// Tag of an encoded binary node.
+const BINARY_NODE_TAG: u8 = 0;
+/// Tag of an encoded edge node.
+const EDGE_NODE_TAG: u8 = 1;
+/// Tag of the encoded global state commitments.
+const STATE_COMMITMENTS_TAG: u8 = 2;
+/// Tag of the encoded contract leaf.
+const CONTRACT_LEAF_TAG: u8 = 3;
+
+/// Path of an edge node in a Starknet Merkle-Patricia tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgePath {
+    pub value: FieldElement,
+    pub len: u8,
+}
+
+/// Node of a Starknet Merkle-Patricia tree, as returned by `pathfinder_getProof`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofNode {
+    Binary { left: FieldElement, right: FieldElement },
+    Edge { child: FieldElement, path: EdgePath },
+}
+
+impl ProofNode {
+    /// Encodes the node as `0x00 || left || right` for binary nodes and
+    /// `0x01 || child || path || len` for edge nodes.
+    pub fn encode(&self) -> Bytes {
+        let mut out = Vec::with_capacity(66);
+        match self {
+            Self::Binary { left, right } => {
+                out.push(BINARY_NODE_TAG);
+                out.extend_from_slice(&left.to_bytes_be());
+                out.extend_from_slice(&right.to_bytes_be());
+            }
+            Self::Edge { child, path } => {
+                out.push(EDGE_NODE_TAG);
+                out.extend_from_slice(&child.to_bytes_be());
+                out.extend_from_slice(&path.value.to_bytes_be());
+                out.push(path.len);
+            }
+        }
+        out.into()
+    }
+}
+
+/// Data of a deployed contract, as returned by `pathfinder_getProof`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractData {
+    pub class_hash: FieldElement,
+    pub nonce: FieldElement,
+    pub root: FieldElement,
+    pub contract_state_hash_version: FieldElement,
+    pub storage_proofs: Vec<Vec<ProofNode>>,
+}
+
+/// Starknet state proof of a contract and some of its storage keys, as returned by `pathfinder_getProof`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StarknetProof {
+    pub state_commitment: Option<FieldElement>,
+    pub class_commitment: Option<FieldElement>,
+    pub contract_proof: Vec<ProofNode>,
+    pub contract_data: Option<ContractData>,
+}
+
+impl StarknetProof {
+    /// Encodes the proof of the contract in the global state, from the state commitments to the
+    /// contract leaf:
+    /// - `0x02 || state_commitment || class_commitment`
+    /// - the encoded nodes of the contracts tree, from the root
+    /// - `0x03 || class_hash || nonce || storage_root || contract_state_hash_version`, if the
+    ///   contract is deployed
+    pub fn encode_contract_proof(&self) -> Vec<Bytes> {
+        let mut header = vec![STATE_COMMITMENTS_TAG];
+        header.extend_from_slice(&self.state_commitment.unwrap_or_default().to_bytes_be());
+        header.extend_from_slice(&self.class_commitment.unwrap_or_default().to_bytes_be());
+
+        let leaf = self.contract_data.as_ref().map(|data| {
+            let mut leaf = vec![CONTRACT_LEAF_TAG];
+            for felt in [data.class_hash, data.nonce, data.root, data.contract_state_hash_version] {
+                leaf.extend_from_slice(&felt.to_bytes_be());
+            }
+            Bytes::from(leaf)
+        });
+
+        std::iter::once(header.into()).chain(self.contract_proof.iter().map(ProofNode::encode)).chain(leaf).collect()
+    }
+
+    /// Returns the encoded nodes of the storage proof at the given index.
+    pub fn encode_storage_proof(&self, index: usize) -> Vec<Bytes> {
+        self.contract_data
+            .as_ref()
+            .and_then(|data| data.storage_proofs.get(index))
+            .map(|nodes| nodes.iter().map(ProofNode::encode).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Serializes the block id following the Starknet JSON-RPC specification.
+fn block_id_to_value(block_id: BlockId) -> Value {
+    match block_id {
+        BlockId::Hash(hash) => json!({ "block_hash": hash }),
+        BlockId::Number(number) => json!({ "block_number": number }),
+        BlockId::Tag(BlockTag::Latest) => json!("latest"),
+        BlockId::Tag(BlockTag::Pending) => json!("pending"),
+    }
+}
+
+/// Fetches the state proof of the contract and of the given storage keys from the
+/// `pathfinder_getProof` endpoint of the node at the given url, over the shared HTTP client.
+pub async fn get_starknet_proof(
+    client: &reqwest_011::Client,
+    url: &Url,
+    block_id: BlockId,
+    contract_address: FieldElement,
+    keys: &[FieldElement],
+) -> Result<StarknetProof, KakarotError> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "pathfinder_getProof",
+        "params": {
+            "block_id": block_id_to_value(block_id),
+            "contract_address": contract_address,
+            "keys": keys,
+        },
+    });
+
+    let response = client
+        .post(url.clone())
+        .header("Content-Type", "application/json")
+        .body(request.to_string())
+        .send()
+        .await
+        .map_err(|err| KakarotError::ProofError(err.to_string()))?
+        .text()
+        .await
+        .map_err(|err| KakarotError::ProofError(err.to_string()))?;
+
+    let mut response: Value =
+        serde_json::from_str(&response).map_err(|err| KakarotError::ProofError(err.to_string()))?;
+    if let Some(error) = response.get("error") {
+        return Err(KakarotError::ProofError(error.to_string()));
+    }
+    serde_json::from_value(response["result"].take()).map_err(|err| KakarotError::ProofError(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_and_encode_proof() {
+        // Given
+        let proof: StarknetProof = serde_json::from_value(json!({
+            "state_commitment": "0x1",
+            "class_commitment": "0x2",
+            "contract_proof": [
+                { "binary": { "left": "0x3", "right": "0x4" } },
+                { "edge": { "child": "0x5", "path": { "value": "0x6", "len": 250 } } }
+            ],
+            "contract_data": {
+                "class_hash": "0x7",
+                "nonce": "0x8",
+                "root": "0x9",
+                "contract_state_hash_version": "0x0",
+                "storage_proofs": [[{ "binary": { "left": "0xa", "right": "0xb" } }]]
+            }
+        }))
+        .expect("Failed to deserialize proof");
+
+        // When
+        let contract_proof = proof.encode_contract_proof();
+        let storage_proof = proof.encode_storage_proof(0);
+
+        // Then
+        assert_eq!(contract_proof.len(), 4);
+        assert_eq!(contract_proof[0].len(), 65);
+        assert_eq!(contract_proof[0][0], STATE_COMMITMENTS_TAG);
+        assert_eq!(contract_proof[0][32], 1);
+        assert_eq!(contract_proof[0][64], 2);
+        assert_eq!(contract_proof[1][0], BINARY_NODE_TAG);
+        assert_eq!(contract_proof[2].len(), 66);
+        assert_eq!(contract_proof[2][0], EDGE_NODE_TAG);
+        assert_eq!(contract_proof[2][65], 250);
+        assert_eq!(contract_proof[3].len(), 129);
+        assert_eq!(contract_proof[3][0], CONTRACT_LEAF_TAG);
+        assert_eq!(storage_proof.len(), 1);
+        assert_eq!(storage_proof[0][32], 0xa);
+        assert!(proof.encode_storage_proof(1).is_empty());
+    }
+}
@@ -0,0 +1,251 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use eyre::{eyre, Result};
+use reth_primitives::U256;
+use starknet::accounts::{Account, Call, ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::types::{BlockId, BlockTag};
+use starknet::providers::Provider;
+use starknet::signers::{LocalWallet, SigningKey};
+use starknet_crypto::FieldElement;
+use tokio::sync::Mutex;
+
+use super::{ERC20Reader, STARKNET_NATIVE_TOKEN};
+use crate::eth_provider::constant::RELAYER_BALANCE_REFRESH_INTERVAL;
+use crate::eth_provider::error::{KakarotError, TransactionError};
+use crate::eth_provider::provider::EthProviderResult;
+use crate::into_via_wrapper;
+use crate::models::felt::Felt252Wrapper;
+
+/// Funded Starknet account submitting the transactions paid by the RPC, i.e. the deployments of
+/// the accounts of the new senders.
+#[derive(Debug)]
+pub struct Relayer {
+    address: FieldElement,
+    signer: LocalWallet,
+    /// Next nonce of the account, fetched before its first transaction. Holding the lock
+    /// serializes the transactions of the account.
+    nonce: Mutex<Option<FieldElement>>,
+    /// Last balance read, along with the instant it was read.
+    balance: std::sync::Mutex<Option<(U256, Instant)>>,
+}
+
+impl Relayer {
+    /// Create a new [`Relayer`].
+    pub fn new(address: FieldElement, private_key: FieldElement) -> Self {
+        Self {
+            address,
+            signer: LocalWallet::from_signing_key(SigningKey::from_secret_scalar(private_key)),
+            nonce: Mutex::new(None),
+            balance: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Returns the Starknet address of the relayer.
+    pub const fn address(&self) -> FieldElement {
+        self.address
+    }
+
+    /// Returns the balance of the relayer, which is read again once it is older than
+    /// [`RELAYER_BALANCE_REFRESH_INTERVAL`] seconds.
+    pub async fn balance<P: Provider + Send + Sync>(&self, provider: &P) -> EthProviderResult<U256> {
+        let cached = *self.balance.lock().expect("Failed to lock relayer balance");
+        if let Some((balance, read_at)) = cached {
+            if read_at.elapsed() < Duration::from_secs(RELAYER_BALANCE_REFRESH_INTERVAL) {
+                return Ok(balance);
+            }
+        }
+
+        let native_token = ERC20Reader::new(*STARKNET_NATIVE_TOKEN, provider);
+        let balance = native_token
+            .balanceOf(&self.address)
+            .block_id(BlockId::Tag(BlockTag::Pending))
+            .call()
+            .await
+            .map_err(KakarotError::from)?
+            .balance;
+        let low: U256 = into_via_wrapper!(balance.low);
+        let high: U256 = into_via_wrapper!(balance.high);
+        let balance = low + (high << 128);
+
+        *self.balance.lock().expect("Failed to lock relayer balance") = Some((balance, Instant::now()));
+        Ok(balance)
+    }
+
+    /// Sends the calls from the relayer and returns the hash of the Starknet transaction. The fees
+    /// are estimated by the Starknet provider.
+    pub async fn execute<P: Provider + Send + Sync>(
+        &self,
+        provider: &P,
+        calls: Vec<Call>,
+    ) -> std::result::Result<FieldElement, String> {
+        let mut nonce = self.nonce.lock().await;
+        let current_nonce = match *nonce {
+            Some(nonce) => nonce,
+            None => provider
+                .get_nonce(BlockId::Tag(BlockTag::Pending), self.address)
+                .await
+                .map_err(|err| err.to_string())?,
+        };
+        let chain_id = provider.chain_id().await.map_err(|err| err.to_string())?;
+        let account =
+            SingleOwnerAccount::new(provider, self.signer.clone(), self.address, chain_id, ExecutionEncoding::New);
+
+        match account.execute(calls).nonce(current_nonce).send().await {
+            Ok(res) => {
+                *nonce = Some(current_nonce + FieldElement::ONE);
+                Ok(res.transaction_hash)
+            }
+            Err(err) => {
+                // The nonce is fetched again for the next transaction, in case it was consumed
+                *nonce = None;
+                Err(err.to_string())
+            }
+        }
+    }
+}
+
+/// Pool of funded Starknet relayer accounts, between which the transactions paid by the RPC are
+/// spread in a round robin fashion. Each relayer manages its own nonce, so that the relayers send
+/// their transactions concurrently. Relayers whose balance drops below the minimum balance are
+/// reported and skipped, and the transactions are refused once all the relayers run dry.
+///
+/// The pool only pays for the deployments of [`super::deployer::EoaDeployer`]: the EVM
+/// transactions are invoked from the Starknet accounts of their senders, which validate their EVM
+/// signatures, so their fees can't be paid by another account.
+#[derive(Debug)]
+pub struct RelayerPool {
+    relayers: Vec<Relayer>,
+    /// Index of the relayer to use for the next transaction.
+    next: AtomicUsize,
+    /// Minimum balance of a relayer to be selected.
+    min_balance: U256,
+}
+
+impl RelayerPool {
+    /// Create a new [`RelayerPool`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no relayer is provided.
+    pub fn new(relayers: Vec<Relayer>, min_balance: U256) -> Self {
+        assert!(!relayers.is_empty(), "at least one relayer is required");
+        Self { relayers, next: AtomicUsize::new(0), min_balance }
+    }
+
+    /// Create a new [`RelayerPool`] from the `RELAYER_ACCOUNT_ADDRESSES` and `RELAYER_PRIVATE_KEYS`
+    /// environment variables, which are comma separated lists of the same length, and from the
+    /// `RELAYER_MIN_BALANCE` environment variable. Returns None if no relayer is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let (addresses, private_keys) = match (var("RELAYER_ACCOUNT_ADDRESSES"), var("RELAYER_PRIVATE_KEYS")) {
+            (Some(addresses), Some(private_keys)) => (addresses, private_keys),
+            (None, None) => return Ok(None),
+            _ => return Err(eyre!("RELAYER_ACCOUNT_ADDRESSES and RELAYER_PRIVATE_KEYS must be set together")),
+        };
+        let min_balance = match var("RELAYER_MIN_BALANCE") {
+            Some(min_balance) => U256::from_str(min_balance.trim())
+                .map_err(|err| eyre!("Invalid RELAYER_MIN_BALANCE {min_balance}: {err}"))?,
+            None => U256::ZERO,
+        };
+        Ok(Some(Self::new(parse_relayers(&addresses, &private_keys)?, min_balance)))
+    }
+
+    /// Returns the number of relayers of the pool.
+    pub fn len(&self) -> usize {
+        self.relayers.len()
+    }
+
+    /// Returns true if the pool has no relayer.
+    pub fn is_empty(&self) -> bool {
+        self.relayers.is_empty()
+    }
+
+    /// Returns the order in which the relayers should be tried for the next transaction, starting
+    /// from the next relayer in the round robin.
+    fn selection_order(&self) -> impl Iterator<Item = usize> {
+        let len = self.relayers.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % len;
+        (0..len).map(move |i| (start + i) % len)
+    }
+
+    /// Returns the next relayer holding at least the minimum balance. Fails if all the relayers
+    /// run dry.
+    pub async fn select<P: Provider + Send + Sync>(&self, provider: &P) -> EthProviderResult<&Relayer> {
+        for index in self.selection_order() {
+            let relayer = &self.relayers[index];
+            let balance = relayer.balance(provider).await?;
+            if balance >= self.min_balance {
+                return Ok(relayer);
+            }
+            tracing::warn!(
+                "Relayer {:#x} has a balance of {} below the minimum balance {}, skipping it",
+                relayer.address,
+                balance,
+                self.min_balance
+            );
+        }
+        Err(TransactionError::RelayersOutOfFunds.into())
+    }
+}
+
+/// Parses the comma separated addresses and private keys of the relayers.
+fn parse_relayers(addresses: &str, private_keys: &str) -> Result<Vec<Relayer>> {
+    let parse = |values: &str, name: &str| {
+        values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| FieldElement::from_hex_be(value).map_err(|err| eyre!("Invalid {name}: {err}")))
+            .collect::<Result<Vec<_>>>()
+    };
+    let addresses = parse(addresses, "RELAYER_ACCOUNT_ADDRESSES")?;
+    let private_keys = parse(private_keys, "RELAYER_PRIVATE_KEYS")?;
+    if addresses.len() != private_keys.len() {
+        return Err(eyre!(
+            "Expected as many RELAYER_PRIVATE_KEYS as RELAYER_ACCOUNT_ADDRESSES, got {} and {}",
+            private_keys.len(),
+            addresses.len()
+        ));
+    }
+    Ok(addresses
+        .into_iter()
+        .zip(private_keys)
+        .map(|(address, private_key)| Relayer::new(address, private_key))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_relayers() {
+        // When
+        let relayers = parse_relayers("0x1, 0x2", "0xa,0xb").unwrap();
+
+        // Then
+        assert_eq!(
+            relayers.iter().map(Relayer::address).collect::<Vec<_>>(),
+            vec![FieldElement::ONE, FieldElement::TWO]
+        );
+        assert!(parse_relayers("0x1,0x2", "0xa").is_err());
+        assert!(parse_relayers("0x1", "not a key").is_err());
+    }
+
+    #[test]
+    fn test_selection_order() {
+        // Given
+        let pool = RelayerPool::new(parse_relayers("0x1,0x2,0x3", "0xa,0xb,0xc").unwrap(), U256::ZERO);
+
+        // When
+        let first = pool.selection_order().collect::<Vec<_>>();
+        let second = pool.selection_order().collect::<Vec<_>>();
+
+        // Then
+        assert_eq!(first, vec![0, 1, 2]);
+        assert_eq!(second, vec![1, 2, 0]);
+        assert_eq!(pool.len(), 3);
+    }
+}
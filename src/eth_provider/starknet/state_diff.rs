@@ -0,0 +1,191 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use lazy_static::lazy_static;
+use reth_rpc_types::trace::parity::{ChangedType, Delta};
+use starknet::core::types::StateDiff;
+use starknet::core::utils::get_storage_var_address;
+use starknet_crypto::FieldElement;
+
+use super::bytecode::{BYTECODE_CHUNK_SIZE, MAX_BYTECODE_SIZE};
+use super::kakarot_core::KAKAROT_ADDRESS;
+use super::STARKNET_NATIVE_TOKEN;
+
+lazy_static! {
+    static ref ACCOUNT_NONCE_ADDRESS: FieldElement = storage_var_address("Account_nonce");
+    static ref ACCOUNT_CODE_HASH_ADDRESS: FieldElement = storage_var_address("Account_code_hash");
+    /// Storage addresses of the variables of the Kakarot accounts which don't hold the EVM storage.
+    static ref ACCOUNT_VARIABLE_ADDRESSES: HashSet<FieldElement> = [
+        *ACCOUNT_NONCE_ADDRESS,
+        *ACCOUNT_CODE_HASH_ADDRESS,
+        *ACCOUNT_CODE_HASH_ADDRESS + FieldElement::ONE,
+        storage_var_address("Account_bytecode_len"),
+        storage_var_address("Account_evm_address"),
+        storage_var_address("Account_implementation"),
+        storage_var_address("Account_cairo1_helpers_class_hash"),
+        storage_var_address("Ownable_owner"),
+    ]
+    .into();
+    /// The bytecode chunks are stored at the first storage addresses of the account.
+    static ref BYTECODE_CHUNKS_END: FieldElement = FieldElement::from(MAX_BYTECODE_SIZE.div_ceil(BYTECODE_CHUNK_SIZE));
+}
+
+fn storage_var_address(name: &str) -> FieldElement {
+    get_storage_var_address(name, &[]).expect("Storage var name is not ASCII")
+}
+
+/// The changes of a Starknet state update which concern the Kakarot accounts. The state update
+/// only holds the values after the block, keyed by Starknet address and storage address.
+#[derive(Debug, Default)]
+pub struct KakarotStateUpdate {
+    contracts: BTreeSet<FieldElement>,
+    storage: HashMap<FieldElement, HashMap<FieldElement, FieldElement>>,
+    nonces: HashSet<FieldElement>,
+    deployed: HashSet<FieldElement>,
+}
+
+impl From<StateDiff> for KakarotStateUpdate {
+    fn from(state_diff: StateDiff) -> Self {
+        let storage = state_diff
+            .storage_diffs
+            .into_iter()
+            .map(|diff| {
+                (diff.address, diff.storage_entries.into_iter().map(|entry| (entry.key, entry.value)).collect())
+            })
+            .collect::<HashMap<_, HashMap<_, _>>>();
+        let nonces = state_diff.nonces.into_iter().map(|nonce| nonce.contract_address).collect::<HashSet<_>>();
+        let deployed =
+            state_diff.deployed_contracts.into_iter().map(|contract| contract.address).collect::<HashSet<_>>();
+
+        let contracts = storage
+            .keys()
+            .chain(&nonces)
+            .chain(&deployed)
+            .copied()
+            .chain(state_diff.replaced_classes.into_iter().map(|class| class.contract_address))
+            .filter(|address| *address != *KAKAROT_ADDRESS && *address != *STARKNET_NATIVE_TOKEN)
+            .collect();
+
+        Self { contracts, storage, nonces, deployed }
+    }
+}
+
+impl KakarotStateUpdate {
+    /// Returns the Starknet addresses of the contracts changed by the block, apart from the Kakarot
+    /// contract and the native token. The contracts which aren't Kakarot accounts must be filtered
+    /// out by the caller.
+    pub fn contracts(&self) -> impl Iterator<Item = FieldElement> + '_ {
+        self.contracts.iter().copied()
+    }
+
+    /// Returns whether the account was deployed in the block.
+    pub fn is_deployed(&self, address: FieldElement) -> bool {
+        self.deployed.contains(&address)
+    }
+
+    /// Returns whether the native token balance of the account changed.
+    pub fn balance_changed(&self, address: FieldElement) -> bool {
+        let low = get_storage_var_address("ERC20_balances", &[address]).expect("Storage var name is not ASCII");
+        self.storage_changed(*STARKNET_NATIVE_TOKEN, low)
+            || self.storage_changed(*STARKNET_NATIVE_TOKEN, low + FieldElement::ONE)
+    }
+
+    /// Returns whether the nonce of the account changed, which is the Starknet nonce for the EOAs
+    /// and a storage variable for the contracts.
+    pub fn nonce_changed(&self, address: FieldElement) -> bool {
+        self.nonces.contains(&address) || self.storage_changed(address, *ACCOUNT_NONCE_ADDRESS)
+    }
+
+    /// Returns whether the code of the account changed.
+    pub fn code_changed(&self, address: FieldElement) -> bool {
+        self.is_deployed(address)
+            || self.storage_changed(address, *ACCOUNT_CODE_HASH_ADDRESS)
+            || self.storage_changed(address, *ACCOUNT_CODE_HASH_ADDRESS + FieldElement::ONE)
+    }
+
+    /// Returns the changed words of the EVM storage of the account, sorted by storage address,
+    /// along with their value after the block. The other variables and the bytecode chunks of the
+    /// account are left out.
+    pub fn storage_changes(&self, address: FieldElement) -> Vec<(FieldElement, FieldElement)> {
+        let mut changes = self
+            .storage
+            .get(&address)
+            .into_iter()
+            .flatten()
+            .filter(|(key, _)| **key >= *BYTECODE_CHUNKS_END && !ACCOUNT_VARIABLE_ADDRESSES.contains(key))
+            .map(|(key, value)| (*key, *value))
+            .collect::<Vec<_>>();
+        changes.sort_unstable_by_key(|(key, _)| *key);
+        changes
+    }
+
+    fn storage_changed(&self, address: FieldElement, key: FieldElement) -> bool {
+        self.storage.get(&address).is_some_and(|storage| storage.contains_key(&key))
+    }
+}
+
+/// Returns the change from the value prior to the block, if any, to the value after the block.
+pub fn delta<T: PartialEq>(from: Option<T>, to: T) -> Delta<T> {
+    match from {
+        None => Delta::Added(to),
+        Some(from) if from == to => Delta::Unchanged,
+        Some(from) => Delta::Changed(ChangedType { from, to }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::types::{ContractStorageDiffItem, DeployedContractItem, NonceUpdate, StorageEntry};
+
+    #[test]
+    fn test_kakarot_state_update() {
+        // Given
+        let eoa = FieldElement::from(0x1234u32);
+        let contract = FieldElement::from(0x5678u32);
+        let slot = get_storage_var_address("Account_storage", &[FieldElement::ONE, FieldElement::ZERO]).unwrap();
+        let balance = get_storage_var_address("ERC20_balances", &[eoa]).unwrap();
+        let entry = |key, value| StorageEntry { key, value };
+        let state_diff = StateDiff {
+            storage_diffs: vec![
+                ContractStorageDiffItem {
+                    address: *STARKNET_NATIVE_TOKEN,
+                    storage_entries: vec![entry(balance, FieldElement::TWO)],
+                },
+                ContractStorageDiffItem {
+                    address: contract,
+                    storage_entries: vec![
+                        entry(FieldElement::ZERO, FieldElement::ONE),
+                        entry(*ACCOUNT_CODE_HASH_ADDRESS, FieldElement::ONE),
+                        entry(slot, FieldElement::from(3u8)),
+                    ],
+                },
+            ],
+            deprecated_declared_classes: vec![],
+            declared_classes: vec![],
+            deployed_contracts: vec![DeployedContractItem { address: contract, class_hash: FieldElement::ONE }],
+            replaced_classes: vec![],
+            nonces: vec![NonceUpdate { contract_address: eoa, nonce: FieldElement::ONE }],
+        };
+
+        // When
+        let state_update = KakarotStateUpdate::from(state_diff);
+
+        // Then
+        assert_eq!(state_update.contracts().collect::<Vec<_>>(), vec![eoa, contract]);
+        assert!(state_update.balance_changed(eoa));
+        assert!(!state_update.balance_changed(contract));
+        assert!(state_update.nonce_changed(eoa));
+        assert!(!state_update.nonce_changed(contract));
+        assert!(state_update.code_changed(contract));
+        assert!(state_update.is_deployed(contract));
+        assert_eq!(state_update.storage_changes(contract), vec![(slot, FieldElement::from(3u8))]);
+        assert!(state_update.storage_changes(eoa).is_empty());
+    }
+
+    #[test]
+    fn test_delta() {
+        assert_eq!(delta(None, 1), Delta::Added(1));
+        assert_eq!(delta(Some(1), 1), Delta::Unchanged);
+        assert_eq!(delta(Some(1), 2), Delta::Changed(ChangedType { from: 1, to: 2 }));
+    }
+}
@@ -0,0 +1,788 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use starknet::providers::jsonrpc::{
+    HttpTransportError, JsonRpcClientError, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport,
+};
+use starknet::providers::ProviderError;
+use tracing::Instrument;
+
+use super::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use crate::eth_rpc::middleware::timeout::request_deadline;
+
+use crate::prometheus_handler::{
+    register, CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, U64,
+};
+
+/// Timeout of a request to a single provider.
+const PROVIDER_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// JSON-RPC error codes returned by the providers when the requests are rate limited.
+const RATE_LIMITED_ERROR_CODES: [i64; 2] = [429, -32005];
+
+/// Histogram time buckets in microseconds.
+const HISTOGRAM_BUCKETS: [f64; 11] =
+    [5.0, 25.0, 100.0, 500.0, 1_000.0, 2_500.0, 10_000.0, 25_000.0, 100_000.0, 1_000_000.0, 10_000_000.0];
+
+/// Metrics on the calls made to the underlying Starknet JSON-RPC provider.
+#[derive(Debug, Clone)]
+pub struct StarknetMetrics {
+    /// Histogram over Starknet call execution times.
+    calls_time: HistogramVec,
+    /// Number of calls started.
+    calls_started: CounterVec<U64>,
+    /// Number of calls completed.
+    calls_finished: CounterVec<U64>,
+    /// Number of calls retried after a transient error.
+    calls_retried: CounterVec<U64>,
+}
+
+impl StarknetMetrics {
+    /// Create an instance of metrics
+    pub fn new(metrics_registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            calls_time: register(
+                HistogramVec::new(
+                    HistogramOpts::new("starknet_rpc_calls_time", "Total time [μs] of Starknet RPC calls")
+                        .buckets(HISTOGRAM_BUCKETS.to_vec()),
+                    &["method"],
+                )?,
+                metrics_registry,
+            )?,
+            calls_started: register(
+                CounterVec::new(
+                    Opts::new("starknet_rpc_calls_started", "Number of sent Starknet RPC calls"),
+                    &["method"],
+                )?,
+                metrics_registry,
+            )?,
+            calls_finished: register(
+                CounterVec::new(
+                    Opts::new("starknet_rpc_calls_finished", "Number of completed Starknet RPC calls"),
+                    &["method", "is_error"],
+                )?,
+                metrics_registry,
+            )?,
+            calls_retried: register(
+                CounterVec::new(
+                    Opts::new("starknet_rpc_calls_retried", "Number of retried Starknet RPC calls"),
+                    &["method"],
+                )?,
+                metrics_registry,
+            )?,
+        })
+    }
+}
+
+/// A JSON-RPC transport wrapper which records metrics on every call
+/// forwarded to the inner transport.
+#[derive(Debug)]
+pub struct MetricsTransport<T> {
+    transport: T,
+    metrics: StarknetMetrics,
+}
+
+impl<T> MetricsTransport<T> {
+    /// Create a new [`MetricsTransport`].
+    pub const fn new(transport: T, metrics: StarknetMetrics) -> Self {
+        Self { transport, metrics }
+    }
+}
+
+#[async_trait]
+impl<T> JsonRpcTransport for MetricsTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync,
+{
+    type Error = T::Error;
+
+    async fn send_request<P, R>(&self, method: JsonRpcMethod, params: P) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let method_name = method_name(method);
+        self.metrics.calls_started.with_label_values(&[&method_name]).inc();
+
+        // The span is nested in the one of the RPC call, reporting the latency of each Starknet call
+        let span = tracing::info_span!(
+            "starknet_call",
+            otel.name = %method_name,
+            otel.kind = "client",
+            rpc.system = "jsonrpc",
+            rpc.method = %method_name,
+        );
+        let now = Instant::now();
+        let res = self.transport.send_request(method, params).instrument(span).await;
+        let micros = now.elapsed().as_micros();
+
+        let is_error = !matches!(res, Ok(JsonRpcResponse::Success { .. }));
+        self.metrics.calls_time.with_label_values(&[&method_name]).observe(micros as _);
+        self.metrics.calls_finished.with_label_values(&[&method_name, if is_error { "true" } else { "false" }]).inc();
+
+        res
+    }
+}
+
+/// Returns the JSON-RPC name of the method, e.g. starknet_call.
+fn method_name(method: JsonRpcMethod) -> String {
+    serde_json::to_value(method).ok().and_then(|value| value.as_str().map(ToString::to_string)).unwrap_or_default()
+}
+
+/// Returns true if the method adds a transaction.
+const fn adds_transaction(method: JsonRpcMethod) -> bool {
+    matches!(
+        method,
+        JsonRpcMethod::AddInvokeTransaction
+            | JsonRpcMethod::AddDeclareTransaction
+            | JsonRpcMethod::AddDeployAccountTransaction
+    )
+}
+
+/// Classification of the errors of a transport between the transient errors, after which the
+/// request can be retried, and the permanent ones.
+pub trait RetryableError {
+    /// Returns true if the request can be retried after this error.
+    fn is_retryable(&self) -> bool;
+}
+
+impl RetryableError for HttpTransportError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Reqwest(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.status().map_or(false, |status| status.as_u16() == 429 || status.is_server_error())
+            }
+            // The body of 429 and 5xx responses returned by proxies usually isn't JSON
+            Self::Json(_) => true,
+        }
+    }
+}
+
+/// Retry policy of the [`RetryTransport`]. The delay before each retry grows exponentially from
+/// the initial backoff up to the maximum backoff, and is randomized to spread the retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries of a request. Setting it to 0 disables the retries.
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Maximum delay between two retries.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_retries: 3, initial_backoff: Duration::from_millis(100), max_backoff: Duration::from_secs(2) }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a new `RetryPolicy` from the `STARKNET_RETRY_MAX_RETRIES`,
+    /// `STARKNET_RETRY_INITIAL_BACKOFF_MS` and `STARKNET_RETRY_MAX_BACKOFF_MS` environment variables.
+    pub fn from_env() -> Result<Self> {
+        let mut policy = Self::default();
+        let var = |name: &str| -> Result<Option<u64>> {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| u64::from_str(value.trim()).map_err(|err| eyre!("Invalid {name} {value}: {err}")))
+                .transpose()
+        };
+
+        if let Some(max_retries) = var("STARKNET_RETRY_MAX_RETRIES")? {
+            policy.max_retries = u32::try_from(max_retries)?;
+        }
+        if let Some(initial_backoff) = var("STARKNET_RETRY_INITIAL_BACKOFF_MS")? {
+            policy.initial_backoff = Duration::from_millis(initial_backoff);
+        }
+        if let Some(max_backoff) = var("STARKNET_RETRY_MAX_BACKOFF_MS")? {
+            policy.max_backoff = Duration::from_millis(max_backoff);
+        }
+        Ok(policy)
+    }
+
+    /// Returns the delay before the given retry, starting at 1. The delay is drawn between half
+    /// and the whole of the exponential backoff.
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self.initial_backoff.saturating_mul(1 << retry.saturating_sub(1).min(31)).min(self.max_backoff);
+        let jitter = (RandomState::new().build_hasher().finish() % 1_000) as f64 / 1_000.;
+        backoff / 2 + (backoff / 2).mul_f64(jitter)
+    }
+}
+
+/// A JSON-RPC transport which retries the requests failing with a transient error, such as a
+/// timeout, a connection error or a rate limited response. Requests adding transactions are only
+/// retried when rate limited, since a timed out request may have reached the provider.
+#[derive(Debug)]
+pub struct RetryTransport<T> {
+    transport: T,
+    policy: RetryPolicy,
+    metrics: Option<StarknetMetrics>,
+}
+
+impl<T> RetryTransport<T> {
+    /// Create a new [`RetryTransport`].
+    pub const fn new(transport: T, policy: RetryPolicy) -> Self {
+        Self { transport, policy, metrics: None }
+    }
+
+    /// Counts the retries in the given metrics.
+    pub fn with_metrics(mut self, metrics: StarknetMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+}
+
+#[async_trait]
+impl<T> JsonRpcTransport for RetryTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync,
+    T::Error: RetryableError,
+{
+    type Error = T::Error;
+
+    async fn send_request<P, R>(&self, method: JsonRpcMethod, params: P) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let adds_transaction = adds_transaction(method);
+
+        let mut retry = 0;
+        loop {
+            let res = self.transport.send_request(method, &params).await;
+            let is_retryable = match &res {
+                Ok(JsonRpcResponse::Success { .. }) => false,
+                Ok(JsonRpcResponse::Error { error, .. }) => RATE_LIMITED_ERROR_CODES.contains(&error.code),
+                Err(err) => !adds_transaction && err.is_retryable(),
+            };
+            if !is_retryable || retry >= self.policy.max_retries {
+                return res;
+            }
+
+            retry += 1;
+            let backoff = self.policy.backoff(retry);
+            // The RPC call would be cancelled before the retry is answered
+            if request_deadline().is_some_and(|deadline| Instant::now() + backoff >= deadline) {
+                return res;
+            }
+            let method_name = method_name(method);
+            tracing::debug!("Starknet call {} failed, retry {} in {:?}", method_name, retry, backoff);
+            if let Some(metrics) = &self.metrics {
+                metrics.calls_retried.with_label_values(&[&method_name]).inc();
+            }
+            tokio::time::sleep(backoff).await;
+        }
+    }
+}
+
+/// Error returned by the [`FailoverTransport`].
+#[derive(Debug, thiserror::Error)]
+pub enum FailoverTransportError<E> {
+    /// The last error returned by the underlying transports.
+    #[error(transparent)]
+    Transport(E),
+    /// The request to the provider timed out.
+    #[error("request timed out")]
+    Timeout,
+    /// The circuits of all the providers are open.
+    #[error("upstream unavailable: all the Starknet providers are failing")]
+    Unavailable,
+    /// The request or the response of a broadcast couldn't be converted.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Returns true if the error was returned because all the Starknet providers are failing.
+pub fn is_upstream_unavailable(err: &ProviderError) -> bool {
+    match err {
+        ProviderError::Other(err) => matches!(
+            err.as_any().downcast_ref::<JsonRpcClientError<FailoverTransportError<HttpTransportError>>>(),
+            Some(JsonRpcClientError::TransportError(FailoverTransportError::Unavailable))
+        ),
+        _ => false,
+    }
+}
+
+impl<E: RetryableError> RetryableError for FailoverTransportError<E> {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(err) => err.is_retryable(),
+            Self::Timeout => true,
+            // Retrying would pile up requests on the failing providers
+            Self::Unavailable | Self::Json(_) => false,
+        }
+    }
+}
+
+/// A JSON-RPC transport which load balances the requests between multiple
+/// transports in a round robin fashion. When a transport fails or times out,
+/// the request is retried on the next transport. Each transport has a circuit
+/// breaker, which skips it once it failed repeatedly: when the circuits of all
+/// the transports are open, the requests fail fast.
+///
+/// When broadcasting is enabled, the requests adding transactions are sent to all the healthy
+/// transports at once, and the first acceptance is returned without waiting for the others.
+#[derive(Debug)]
+pub struct FailoverTransport<T> {
+    transports: Arc<Vec<T>>,
+    /// Index of the transport to use for the next request.
+    next: AtomicUsize,
+    /// Health of each transport.
+    health: ProviderHealth,
+    /// Whether the transactions are broadcast to all the transports.
+    broadcast: bool,
+}
+
+/// Health of the transports of a [`FailoverTransport`], shared with the components reporting it.
+#[derive(Debug, Clone)]
+pub struct ProviderHealth {
+    /// Circuit breaker of each transport.
+    breakers: Arc<Vec<CircuitBreaker>>,
+}
+
+impl ProviderHealth {
+    /// Returns the number of transports which are currently healthy, i.e. whose circuit is closed.
+    pub fn healthy_count(&self) -> usize {
+        self.breakers.iter().filter(|breaker| breaker.state() == CircuitState::Closed).count()
+    }
+
+    /// Returns the state of the circuit of each transport, in the order of the transports.
+    pub fn states(&self) -> Vec<CircuitState> {
+        self.breakers.iter().map(CircuitBreaker::state).collect()
+    }
+
+    /// Records the success or the failure of a request to the transport at the given index.
+    fn record(&self, index: usize, healthy: bool) {
+        let breaker = &self.breakers[index];
+        if healthy {
+            breaker.record_success();
+        } else {
+            breaker.record_failure();
+        }
+    }
+}
+
+impl<T> FailoverTransport<T> {
+    /// Create a new [`FailoverTransport`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no transport is provided.
+    pub fn new(transports: Vec<T>, config: CircuitBreakerConfig) -> Self {
+        assert!(!transports.is_empty(), "at least one transport is required");
+        let breakers = transports.iter().map(|_| CircuitBreaker::new(config)).collect();
+        let health = ProviderHealth { breakers: Arc::new(breakers) };
+        Self { transports: Arc::new(transports), next: AtomicUsize::new(0), health, broadcast: false }
+    }
+
+    /// Broadcasts the transactions to all the transports if `broadcast` is true.
+    #[must_use]
+    pub const fn with_broadcast(mut self, broadcast: bool) -> Self {
+        self.broadcast = broadcast;
+        self
+    }
+
+    /// Returns a handle on the health of the transports.
+    pub fn health(&self) -> ProviderHealth {
+        self.health.clone()
+    }
+
+    /// Returns the order in which the transports should be tried for the next request:
+    /// healthy transports first, starting from the next transport in the round robin.
+    fn transports_order(&self) -> Vec<usize> {
+        let len = self.transports.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % len;
+
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) =
+            (0..len).map(|i| (start + i) % len).partition(|&i| self.health.breakers[i].state() == CircuitState::Closed);
+        healthy.into_iter().chain(unhealthy).collect()
+    }
+}
+
+impl<T> FailoverTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync + 'static,
+    T::Error: Send + 'static,
+{
+    /// Sends the request to all the transports whose circuit isn't open, and returns the first
+    /// successful response. The requests to the other transports keep running in the background,
+    /// so that the transaction reaches every provider. If no transport accepted the request, the
+    /// first error response (e.g. a rejected transaction) is returned, or else the last error.
+    async fn broadcast<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, FailoverTransportError<T::Error>>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let params = serde_json::to_value(params)?;
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        for index in 0..self.transports.len() {
+            if !self.health.breakers[index].try_acquire() {
+                continue;
+            }
+            let (transports, health, params, sender) =
+                (self.transports.clone(), self.health.clone(), params.clone(), sender.clone());
+            tokio::spawn(
+                async move {
+                    let response = tokio::time::timeout(
+                        PROVIDER_REQUEST_TIMEOUT,
+                        transports[index].send_request::<_, serde_json::Value>(method, params),
+                    )
+                    .await;
+                    let response = match response {
+                        Ok(Ok(response)) => Ok(response),
+                        Ok(Err(err)) => {
+                            tracing::warn!("Starknet provider {} failed to receive the broadcast: {}", index, err);
+                            Err(FailoverTransportError::Transport(err))
+                        }
+                        Err(_) => {
+                            tracing::warn!("Starknet provider {} timed out receiving the broadcast", index);
+                            Err(FailoverTransportError::Timeout)
+                        }
+                    };
+                    health.record(index, response.is_ok());
+                    // The receiver is dropped once a provider accepted the request
+                    let _ = sender.send(response);
+                }
+                .in_current_span(),
+            );
+        }
+        drop(sender);
+
+        let mut rejection = None;
+        let mut last_error = FailoverTransportError::Unavailable;
+        while let Some(response) = receiver.recv().await {
+            match response {
+                Ok(JsonRpcResponse::Success { id, result }) => {
+                    return Ok(JsonRpcResponse::Success { id, result: serde_json::from_value(result)? });
+                }
+                Ok(JsonRpcResponse::Error { id, error }) => {
+                    rejection = rejection.or(Some(JsonRpcResponse::Error { id, error }));
+                }
+                Err(err) => last_error = err,
+            }
+        }
+
+        rejection.ok_or(last_error)
+    }
+}
+
+#[async_trait]
+impl<T> JsonRpcTransport for FailoverTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync + 'static,
+    T::Error: Send + 'static,
+{
+    type Error = FailoverTransportError<T::Error>;
+
+    async fn send_request<P, R>(&self, method: JsonRpcMethod, params: P) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        if self.broadcast && adds_transaction(method) {
+            return self.broadcast(method, params).await;
+        }
+
+        let mut last_error = FailoverTransportError::Unavailable;
+
+        for index in self.transports_order() {
+            // Transports whose circuit is open are skipped, a half open circuit lets a probe through
+            if !self.health.breakers[index].try_acquire() {
+                continue;
+            }
+            // Errors returned by the provider itself (e.g. a reverted call) are part of
+            // the response and don't trigger a failover.
+            match tokio::time::timeout(PROVIDER_REQUEST_TIMEOUT, self.transports[index].send_request(method, &params))
+                .await
+            {
+                Ok(Ok(response)) => {
+                    self.health.record(index, true);
+                    return Ok(response);
+                }
+                Ok(Err(err)) => {
+                    tracing::warn!("Starknet provider {} failed, trying the next provider: {}", index, err);
+                    last_error = FailoverTransportError::Transport(err);
+                }
+                Err(_) => {
+                    tracing::warn!("Starknet provider {} timed out, trying the next provider", index);
+                    last_error = FailoverTransportError::Timeout;
+                }
+            }
+            self.health.record(index, false);
+        }
+
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("mock transport error")]
+    struct MockError;
+
+    impl RetryableError for MockError {
+        fn is_retryable(&self) -> bool {
+            true
+        }
+    }
+
+    /// A transport which either fails or returns the given block number.
+    #[derive(Debug)]
+    struct MockTransport {
+        block_number: u64,
+        fail: AtomicBool,
+    }
+
+    impl MockTransport {
+        fn new(block_number: u64, fail: bool) -> Self {
+            Self { block_number, fail: AtomicBool::new(fail) }
+        }
+    }
+
+    #[async_trait]
+    impl JsonRpcTransport for MockTransport {
+        type Error = MockError;
+
+        async fn send_request<P, R>(&self, _method: JsonRpcMethod, _params: P) -> Result<JsonRpcResponse<R>, MockError>
+        where
+            P: Serialize + Send + Sync,
+            R: DeserializeOwned,
+        {
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(MockError);
+            }
+            Ok(serde_json::from_value(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": self.block_number}))
+                .unwrap())
+        }
+    }
+
+    /// A transport which fails the given number of times before returning the block number 1.
+    /// The failures are either transport errors or rate limited responses.
+    #[derive(Debug)]
+    struct FlakyTransport {
+        failures: AtomicUsize,
+        rate_limited: bool,
+        calls: AtomicUsize,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: usize, rate_limited: bool) -> Self {
+            Self { failures: AtomicUsize::new(failures), rate_limited, calls: AtomicUsize::new(0) }
+        }
+    }
+
+    #[async_trait]
+    impl JsonRpcTransport for FlakyTransport {
+        type Error = MockError;
+
+        async fn send_request<P, R>(&self, _method: JsonRpcMethod, _params: P) -> Result<JsonRpcResponse<R>, MockError>
+        where
+            P: Serialize + Send + Sync,
+            R: DeserializeOwned,
+        {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            let failing =
+                self.failures.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |f| f.checked_sub(1)).is_ok();
+            let response = match (failing, self.rate_limited) {
+                (false, _) => serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": 1}),
+                (true, true) => {
+                    serde_json::json!({"jsonrpc": "2.0", "id": 1, "error": {"code": 429, "message": "Too Many Requests"}})
+                }
+                (true, false) => return Err(MockError),
+            };
+            Ok(serde_json::from_value(response).unwrap())
+        }
+    }
+
+    const OPEN_ON_FAILURE: CircuitBreakerConfig =
+        CircuitBreakerConfig { failure_threshold: 1, open_duration: Duration::from_secs(30) };
+
+    const NO_BACKOFF: RetryPolicy =
+        RetryPolicy { max_retries: 3, initial_backoff: Duration::ZERO, max_backoff: Duration::ZERO };
+
+    async fn block_number<T: JsonRpcTransport + Send + Sync>(transport: &T) -> Result<u64, T::Error> {
+        match transport.send_request::<_, u64>(JsonRpcMethod::BlockNumber, ()).await? {
+            JsonRpcResponse::Success { result, .. } => Ok(result),
+            JsonRpcResponse::Error { .. } => panic!("Unexpected error response"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_transport_round_robin() {
+        // Given
+        let transport =
+            FailoverTransport::new(vec![MockTransport::new(1, false), MockTransport::new(2, false)], OPEN_ON_FAILURE);
+
+        // When
+        let first = block_number(&transport).await.unwrap();
+        let second = block_number(&transport).await.unwrap();
+        let third = block_number(&transport).await.unwrap();
+
+        // Then
+        assert_eq!((first, second, third), (1, 2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_failover_transport_skips_failing_transport() {
+        // Given
+        let transport =
+            FailoverTransport::new(vec![MockTransport::new(1, true), MockTransport::new(2, false)], OPEN_ON_FAILURE);
+
+        // When
+        let results = [
+            block_number(&transport).await.unwrap(),
+            block_number(&transport).await.unwrap(),
+            block_number(&transport).await.unwrap(),
+        ];
+
+        // Then
+        // The first transport failed and is skipped until its circuit half opens
+        assert_eq!(results, [2, 2, 2]);
+        assert_eq!(transport.health.breakers[0].state(), CircuitState::Open);
+        assert_eq!(transport.health().healthy_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failover_transport_all_failing() {
+        // Given
+        let transport =
+            FailoverTransport::new(vec![MockTransport::new(1, true), MockTransport::new(2, true)], OPEN_ON_FAILURE);
+
+        // When
+        let result = block_number(&transport).await;
+
+        // Then
+        assert!(matches!(result, Err(FailoverTransportError::Transport(MockError))));
+    }
+
+    #[tokio::test]
+    async fn test_failover_transport_fails_fast() {
+        // Given
+        let transport =
+            FailoverTransport::new(vec![MockTransport::new(1, true), MockTransport::new(2, true)], OPEN_ON_FAILURE);
+        let _ = block_number(&transport).await;
+
+        // When
+        // Both circuits are open, the transports are healthy again but aren't tried
+        transport.transports.iter().for_each(|transport| transport.fail.store(false, Ordering::Relaxed));
+        let result = block_number(&transport).await;
+
+        // Then
+        assert!(matches!(result, Err(FailoverTransportError::Unavailable)));
+        assert!(!FailoverTransportError::<MockError>::Unavailable.is_retryable());
+        assert_eq!(transport.health().healthy_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failover_transport_broadcast() {
+        // Given
+        let transport =
+            FailoverTransport::new(vec![MockTransport::new(1, true), MockTransport::new(2, false)], OPEN_ON_FAILURE)
+                .with_broadcast(true);
+
+        // When
+        let result = transport.send_request::<_, u64>(JsonRpcMethod::AddInvokeTransaction, ()).await.unwrap();
+
+        // Then
+        // The transaction is accepted by the healthy transport, whatever the round robin order
+        assert!(matches!(result, JsonRpcResponse::Success { result: 2, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_failover_transport_broadcast_all_failing() {
+        // Given
+        let transport =
+            FailoverTransport::new(vec![MockTransport::new(1, true), MockTransport::new(2, true)], OPEN_ON_FAILURE)
+                .with_broadcast(true);
+
+        // When
+        let result = transport.send_request::<_, u64>(JsonRpcMethod::AddInvokeTransaction, ()).await;
+
+        // Then
+        assert!(matches!(result, Err(FailoverTransportError::Transport(MockError))));
+        assert_eq!(transport.health().healthy_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transport_transient_errors() {
+        // Given
+        let transport = RetryTransport::new(FlakyTransport::new(2, false), NO_BACKOFF);
+
+        // When
+        let result = block_number(&transport).await;
+
+        // Then
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(transport.transport.calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transport_rate_limited() {
+        // Given
+        let transport = RetryTransport::new(FlakyTransport::new(1, true), NO_BACKOFF);
+
+        // When
+        let result = block_number(&transport).await;
+
+        // Then
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(transport.transport.calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transport_max_retries() {
+        // Given
+        let transport = RetryTransport::new(FlakyTransport::new(10, false), NO_BACKOFF);
+
+        // When
+        let result = block_number(&transport).await;
+
+        // Then
+        assert!(matches!(result, Err(MockError)));
+        assert_eq!(transport.transport.calls.load(Ordering::Relaxed), NO_BACKOFF.max_retries as usize + 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transport_add_transaction() {
+        // Given
+        let transport = RetryTransport::new(FlakyTransport::new(1, false), NO_BACKOFF);
+
+        // When
+        let result = transport.send_request::<_, u64>(JsonRpcMethod::AddInvokeTransaction, ()).await;
+
+        // Then
+        // A transaction which may have reached the provider isn't sent twice
+        assert!(matches!(result, Err(MockError)));
+        assert_eq!(transport.transport.calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        // Given
+        let policy = RetryPolicy::default();
+
+        // Then
+        assert!((Duration::from_millis(50)..=Duration::from_millis(100)).contains(&policy.backoff(1)));
+        assert!((Duration::from_millis(200)..=Duration::from_millis(400)).contains(&policy.backoff(3)));
+        assert!((Duration::from_secs(1)..=Duration::from_secs(2)).contains(&policy.backoff(10)));
+        assert!(policy.backoff(u32::MAX) <= policy.max_backoff);
+    }
+}
@@ -1,13 +1,17 @@
 use std::fmt::LowerHex;
+use std::future::Future;
 
 use cainome::cairo_serde::Error;
 use mongodb::bson::{doc, Document};
-use reth_primitives::{U128, U256};
+use reth_primitives::{Address, Bloom, BloomInput, Log, B256, U128, U256};
+use reth_rpc_types::{BlockTransactions, Filter, RichBlock, ValueOrArray};
 use starknet::{
     core::types::{ContractErrorData, StarknetError},
     providers::ProviderError,
 };
 
+use crate::eth_provider::constant::{ADDRESS_HEX_STRING_LEN, U64_HEX_STRING_LEN};
+
 /// Converts an iterator of `TryInto<u8>` into a `FromIterator<u8>`.
 #[inline]
 pub(crate) fn try_from_u8_iterator<I: TryInto<u8>, T: FromIterator<u8>>(it: impl IntoIterator<Item = I>) -> T {
@@ -33,6 +37,48 @@ where
     doc! {key: format_hex(value, width)}
 }
 
+/// Returns the filter matching the transaction sent by the sender with the given nonce, served by
+/// the sender and nonce index of the transactions collections. The nonce can be stored with or
+/// without padding.
+pub(crate) fn sender_and_nonce_filter(sender: Address, nonce: u64) -> Document {
+    let mut filter = into_filter("tx.from", &sender, ADDRESS_HEX_STRING_LEN);
+    filter.insert("tx.nonce", doc! {"$in": [format_hex(nonce, U64_HEX_STRING_LEN), format!("{nonce:#x}")]});
+    filter
+}
+
+/// Replaces the full transactions of the block with their hashes.
+pub(crate) fn with_transaction_hashes(mut block: RichBlock) -> RichBlock {
+    if let BlockTransactions::Full(transactions) = &block.inner.transactions {
+        block.inner.transactions = BlockTransactions::Hashes(transactions.iter().map(|tx| tx.hash).collect());
+    }
+    block
+}
+
+/// Binary searches the last index between `low` and `high` whose value is at most the target,
+/// for values which don't decrease with the index. Returns None if the value at `low` is already
+/// above the target. Only O(log(high - low)) values are looked up.
+pub(crate) async fn last_at_most<F, Fut, E>(low: u64, high: u64, target: u64, mut value: F) -> Result<Option<u64>, E>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<u64, E>>,
+{
+    if value(low).await? > target {
+        return Ok(None);
+    }
+
+    // The value at `low` is always at most the target
+    let (mut low, mut high) = (low, high.max(low));
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if value(mid).await? <= target {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Ok(Some(low))
+}
+
 /// Splits a U256 value into two generic values implementing the From<u128> trait
 #[inline]
 pub fn split_u256<T: From<u128>>(value: impl Into<U256>) -> [T; 2] {
@@ -66,11 +112,74 @@ pub(crate) fn entrypoint_not_found<T>(err: &Result<T, Error>) -> bool {
     }
 }
 
+/// Computes the rewards at the given percentiles for a block. Each transaction is represented
+/// by its tip and the gas it used, which is used to weight the tip.
+/// Taken from Reth's `calculate_reward_percentiles_for_block`:
+/// <https://github.com/paradigmxyz/reth/blob/v0.2.0-beta.6/crates/rpc/rpc/src/eth/api/fees.rs>
+pub(crate) fn reward_percentiles(mut tips: Vec<(u128, u128)>, gas_used: u128, percentiles: &[f64]) -> Vec<u128> {
+    if tips.is_empty() {
+        return vec![0; percentiles.len()];
+    }
+    tips.sort_unstable_by_key(|(tip, _)| *tip);
+
+    let mut index = 0;
+    let mut cumulative_gas_used = tips[0].1;
+    percentiles
+        .iter()
+        .map(|percentile| {
+            let threshold = (gas_used as f64 * percentile / 100.) as u128;
+            while cumulative_gas_used < threshold && index < tips.len() - 1 {
+                index += 1;
+                cumulative_gas_used += tips[index].1;
+            }
+            tips[index].0
+        })
+        .collect()
+}
+
+/// Checks if a logs bloom can contain logs emitted by one of the addresses and matching the topics.
+/// Empty addresses or topics at a position match any value.
+pub(crate) fn logs_bloom_matches(bloom: &Bloom, addresses: &[Address], topics: &[Vec<B256>]) -> bool {
+    let contains = |input: &[u8]| bloom.contains_input(BloomInput::Raw(input));
+    let address_matches = addresses.is_empty() || addresses.iter().any(|address| contains(address.as_slice()));
+    address_matches
+        && topics.iter().all(|topics| topics.is_empty() || topics.iter().any(|topic| contains(topic.as_slice())))
+}
+
+/// Returns the addresses and the topics at each position of a logs filter. Empty addresses or
+/// topics at a position match any value.
+pub(crate) fn filter_addresses_and_topics(filter: &Filter) -> (Vec<Address>, Vec<Vec<B256>>) {
+    let addresses = match filter.address.to_value_or_array() {
+        Some(ValueOrArray::Value(address)) => vec![address],
+        Some(ValueOrArray::Array(addresses)) => addresses,
+        None => Vec::new(),
+    };
+    let topics = filter
+        .topics
+        .iter()
+        .map(|t| match t.to_value_or_array() {
+            Some(ValueOrArray::Value(topic)) => vec![topic],
+            Some(ValueOrArray::Array(topics)) => topics,
+            None => Vec::new(),
+        })
+        .collect();
+    (addresses, topics)
+}
+
+/// Checks if a log was emitted by one of the addresses and matches the topics.
+/// Empty addresses or topics at a position match any value.
+pub(crate) fn log_matches(log: &Log, addresses: &[Address], topics: &[Vec<B256>]) -> bool {
+    let address_matches = addresses.is_empty() || addresses.contains(&log.address);
+    address_matches
+        && topics.iter().enumerate().all(|(position, topics)| {
+            topics.is_empty() || log.topics().get(position).is_some_and(|topic| topics.contains(topic))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use proptest::prelude::*;
-    use reth_primitives::B256;
     use std::str::FromStr;
 
     #[test]
@@ -90,6 +199,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sender_and_nonce_filter() {
+        // Given
+        let sender = Address::from_str("0x000000000000000000000000000000000000dead").unwrap();
+
+        // When
+        let filter = sender_and_nonce_filter(sender, 0x2a);
+
+        // Then
+        assert_eq!(
+            filter,
+            doc! {
+                "tx.from": "0x000000000000000000000000000000000000dead",
+                "tx.nonce": {"$in": ["0x000000000000002a", "0x2a"]}
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_last_at_most() {
+        // Given
+        let timestamps = [10u64, 20, 20, 30, 40];
+        let lookup = |index: u64| std::future::ready(Ok::<_, ()>(timestamps[index as usize]));
+
+        // Then
+        assert_eq!(last_at_most(0, 4, 5, lookup).await, Ok(None));
+        assert_eq!(last_at_most(0, 4, 10, lookup).await, Ok(Some(0)));
+        assert_eq!(last_at_most(0, 4, 25, lookup).await, Ok(Some(2)));
+        assert_eq!(last_at_most(0, 4, 40, lookup).await, Ok(Some(4)));
+        assert_eq!(last_at_most(0, 4, 100, lookup).await, Ok(Some(4)));
+        assert_eq!(last_at_most(1, 1, 20, lookup).await, Ok(Some(1)));
+    }
+
     #[test]
     fn test_split_u256() {
         // Define a property-based test using Proptest
@@ -104,4 +246,51 @@ mod tests {
             assert_eq!(U256::from_str(&combined_hex).unwrap(), value);
         });
     }
+
+    #[test]
+    fn test_reward_percentiles() {
+        // Tips of 1, 2 and 3 using respectively 10, 30 and 60 gas
+        let tips = vec![(3, 60), (1, 10), (2, 30)];
+
+        assert_eq!(reward_percentiles(tips, 100, &[0., 10., 25., 50., 100.]), vec![1, 1, 2, 3, 3]);
+        assert_eq!(reward_percentiles(vec![], 0, &[10., 50.]), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_logs_bloom_matches() {
+        // Given
+        let address = Address::from([1u8; 20]);
+        let topic = B256::from([2u8; 32]);
+        let mut bloom = Bloom::default();
+        bloom.accrue(BloomInput::Raw(address.as_slice()));
+        bloom.accrue(BloomInput::Raw(topic.as_slice()));
+
+        let other_address = Address::from([3u8; 20]);
+        let other_topic = B256::from([4u8; 32]);
+
+        // When & Then
+        assert!(logs_bloom_matches(&bloom, &[], &[]));
+        assert!(logs_bloom_matches(&bloom, &[other_address, address], &[vec![], vec![topic]]));
+        assert!(!logs_bloom_matches(&bloom, &[other_address], &[]));
+        assert!(!logs_bloom_matches(&bloom, &[address], &[vec![topic], vec![other_topic]]));
+        assert!(!logs_bloom_matches(&Bloom::default(), &[address], &[]));
+    }
+
+    #[test]
+    fn test_log_matches() {
+        // Given
+        let address = Address::from([1u8; 20]);
+        let topic = B256::from([2u8; 32]);
+        let log = Log::new_unchecked(address, vec![topic], Default::default());
+
+        let other_address = Address::from([3u8; 20]);
+        let other_topic = B256::from([4u8; 32]);
+
+        // When & Then
+        assert!(log_matches(&log, &[], &[]));
+        assert!(log_matches(&log, &[other_address, address], &[vec![other_topic, topic]]));
+        assert!(!log_matches(&log, &[other_address], &[]));
+        assert!(!log_matches(&log, &[address], &[vec![other_topic]]));
+        assert!(!log_matches(&log, &[address], &[vec![], vec![topic]]));
+    }
 }
@@ -0,0 +1,36 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use reth_rpc_types::Transaction;
+
+use crate::models::admin::ProviderStatus;
+
+/// Admin API, used by the operators to manage the node at runtime.
+/// Only served by the authenticated server.
+#[rpc(server, namespace = "admin")]
+#[async_trait]
+pub trait AdminApi {
+    /// Returns the state of the circuit breaker of each Starknet provider.
+    #[method(name = "providerHealth")]
+    fn provider_health(&self) -> Result<Vec<ProviderStatus>>;
+
+    /// Removes all the cached responses and returns their number.
+    #[method(name = "flushCaches")]
+    fn flush_caches(&self) -> Result<usize>;
+
+    /// Returns the pending transactions.
+    #[method(name = "dumpMempool")]
+    async fn dump_mempool(&self) -> Result<Vec<Transaction>>;
+
+    /// Adds the transactions to the pending transactions, which are then resubmitted by the
+    /// retry service. Returns the number of transactions added.
+    #[method(name = "loadMempool")]
+    async fn load_mempool(&self, transactions: Vec<Transaction>) -> Result<usize>;
+
+    /// Replaces the log filter, using the `RUST_LOG` syntax (e.g. `info,kakarot_rpc=debug`).
+    #[method(name = "setLogLevel")]
+    fn set_log_level(&self, filter: String) -> Result<bool>;
+
+    /// Enables or disables a namespace, and returns the disabled namespaces.
+    #[method(name = "setNamespaceEnabled")]
+    fn set_namespace_enabled(&self, namespace: String, enabled: bool) -> Result<Vec<String>>;
+}
@@ -1,11 +1,27 @@
 use crate::models::balance::TokenBalances;
+use crate::models::nft::{NftOwnershipHistoryRequest, NftsRequest, OwnedNfts};
+use crate::models::token::TokenMetadata;
+use crate::models::transfer::{AssetTransfers, AssetTransfersRequest};
 use jsonrpsee::core::RpcResult as Result;
 use jsonrpsee::proc_macros::rpc;
 use reth_primitives::Address;
 
-#[rpc(server, namespace = "alchemy")]
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "alchemy"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "alchemy"))]
 #[async_trait]
 pub trait AlchemyApi {
     #[method(name = "getTokenBalances")]
     async fn token_balances(&self, address: Address, contract_addresses: Vec<Address>) -> Result<TokenBalances>;
+
+    #[method(name = "getTokenMetadata")]
+    async fn token_metadata(&self, contract_address: Address) -> Result<TokenMetadata>;
+
+    #[method(name = "getAssetTransfers")]
+    async fn asset_transfers(&self, request: AssetTransfersRequest) -> Result<AssetTransfers>;
+
+    #[method(name = "getNFTs")]
+    async fn nfts(&self, request: NftsRequest) -> Result<OwnedNfts>;
+
+    #[method(name = "getNFTOwnershipHistory")]
+    async fn nft_ownership_history(&self, request: NftOwnershipHistoryRequest) -> Result<AssetTransfers>;
 }
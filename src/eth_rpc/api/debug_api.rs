@@ -2,10 +2,13 @@ use jsonrpsee::core::RpcResult as Result;
 use jsonrpsee::proc_macros::rpc;
 use reth_primitives::{Bytes, B256};
 use reth_rpc_types::{
-    trace::geth::{GethDebugTracingOptions, TraceResult},
-    BlockId, BlockNumberOrTag,
+    trace::geth::{GethDebugTracingOptions, GethTrace, TraceResult},
+    BlockId, BlockNumberOrTag, BlockOverrides,
 };
 
+use crate::models::call_bundle::BundleCall;
+use crate::models::pagination::{Cursor, Page};
+
 /// Debug API
 /// Taken from Reth's DebugApi trait:
 /// <https://github.com/paradigmxyz/reth/blob/5d6ac4c815c562677d7ae6ad6b422b55ef4ed8e2/crates/rpc/rpc-api/src/debug.rs#L14>
@@ -49,4 +52,37 @@ pub trait DebugApi {
         block_hash: B256,
         opts: Option<GethDebugTracingOptions>,
     ) -> Result<Option<Vec<TraceResult>>>;
+
+    /// Returns a page of the Geth debug traces of the block, along with the cursor of the next
+    /// page. The traces of a page stay below the maximum response size, so that the traces of
+    /// large blocks can be fetched one page at a time. The first page starts at the first
+    /// transaction of the block, and each following page at the cursor returned by the previous
+    /// page.
+    #[method(name = "traceBlockPage")]
+    async fn trace_block_page(
+        &self,
+        block_id: BlockId,
+        opts: Option<GethDebugTracingOptions>,
+        cursor: Option<Cursor>,
+    ) -> Result<Option<Page<TraceResult>>>;
+
+    /// Returns the Geth debug trace for the given transaction hash.
+    #[method(name = "traceTransaction")]
+    async fn trace_transaction(
+        &self,
+        transaction_hash: B256,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> Result<GethTrace>;
+
+    /// Returns the Geth debug traces of a bundle of calls, simulated in sequence on top of the
+    /// state of the given block. Each call is executed on the state left by the previous ones,
+    /// once its state overrides are applied.
+    #[method(name = "traceCallMany")]
+    async fn trace_call_many(
+        &self,
+        calls: Vec<BundleCall>,
+        block_id: Option<BlockId>,
+        block_overrides: Option<Box<BlockOverrides>>,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> Result<Vec<GethTrace>>;
 }
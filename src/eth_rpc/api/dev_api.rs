@@ -0,0 +1,36 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use reth_primitives::serde_helper::U64HexOrNumber;
+use reth_primitives::{Address, U256, U64};
+
+/// Dev API, compatible with the cheat methods of Anvil and Hardhat.
+/// Only served when running against Katana.
+#[rpc(server)]
+#[async_trait]
+pub trait DevApi {
+    /// Mines a new block, with the given timestamp if provided.
+    #[method(name = "evm_mine")]
+    async fn evm_mine(&self, timestamp: Option<U64HexOrNumber>) -> Result<String>;
+
+    /// Sets the timestamp of the next block.
+    #[method(name = "evm_setNextBlockTimestamp")]
+    async fn evm_set_next_block_timestamp(&self, timestamp: U64HexOrNumber) -> Result<()>;
+
+    /// Increases the timestamp of the next block by the given number of seconds. Returns the
+    /// total time increase.
+    #[method(name = "evm_increaseTime")]
+    async fn evm_increase_time(&self, seconds: U64HexOrNumber) -> Result<U64>;
+
+    /// Sets the balance of the account.
+    #[method(name = "anvil_setBalance")]
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> Result<()>;
+
+    /// Allows `eth_sendTransaction` to send transactions on behalf of the account, without
+    /// signing them. Requires Katana to run with the transaction validation disabled.
+    #[method(name = "anvil_impersonateAccount")]
+    async fn anvil_impersonate_account(&self, address: Address) -> Result<()>;
+
+    /// Stops impersonating the account.
+    #[method(name = "anvil_stopImpersonatingAccount")]
+    async fn anvil_stop_impersonating_account(&self, address: Address) -> Result<()>;
+}
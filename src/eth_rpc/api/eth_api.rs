@@ -3,10 +3,15 @@ use jsonrpsee::proc_macros::rpc;
 use reth_primitives::serde_helper::{JsonStorageKey, U64HexOrNumber};
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64};
 use reth_rpc_types::{
-    AccessListWithGasUsed, EIP1186AccountProofResponse, FeeHistory, Filter, FilterChanges, Index, RichBlock,
-    SyncStatus, Transaction as EthTransaction, TransactionReceipt, TransactionRequest, Work,
+    state::StateOverride, AccessListWithGasUsed, BlockOverrides, EIP1186AccountProofResponse, FeeHistory, Filter,
+    FilterChanges, Index, RichBlock, SyncStatus, Transaction as EthTransaction, TransactionReceipt, TransactionRequest,
+    Work,
 };
 
+use crate::models::account::EthAccount;
+use crate::models::call_bundle::{BundleCall, BundleCallResult};
+use crate::models::receipt::TransactionReceiptWithRevertReason;
+
 /// Ethereum JSON-RPC API Trait
 /// Mostly based on <https://github.com/paradigmxyz/reth/blob/559124ac5a0b25030250203babcd8a94693df648/crates/rpc/rpc-api/src/eth.rs#L15>
 /// With some small modifications
@@ -84,9 +89,10 @@ pub trait EthApi {
         index: Index,
     ) -> Result<Option<EthTransaction>>;
 
-    /// Returns the receipt of a transaction by transaction hash.
+    /// Returns the receipt of a transaction by transaction hash, along with the revert data if the
+    /// transaction reverted.
     #[method(name = "getTransactionReceipt")]
-    async fn transaction_receipt(&self, hash: B256) -> Result<Option<TransactionReceipt>>;
+    async fn transaction_receipt(&self, hash: B256) -> Result<Option<TransactionReceiptWithRevertReason>>;
 
     /// Returns the balance of the account of given address.
     #[method(name = "getBalance")]
@@ -109,8 +115,27 @@ pub trait EthApi {
     async fn get_logs(&self, filter: Filter) -> Result<FilterChanges>;
 
     /// Executes a new message call immediately without creating a transaction on the block chain.
+    /// The optional state and block overrides are applied before the execution.
     #[method(name = "call")]
-    async fn call(&self, request: TransactionRequest, block_id: Option<BlockId>) -> Result<Bytes>;
+    async fn call(
+        &self,
+        request: TransactionRequest,
+        block_id: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> Result<Bytes>;
+
+    /// Simulates a bundle of calls in sequence on top of the state of the given block, each call
+    /// being executed on the state left by the previous ones. The state overrides of a call are
+    /// applied right before it, and the optional block overrides apply to the whole bundle.
+    /// Returns the output, failure reason and gas used of each call.
+    #[method(name = "callMany")]
+    async fn call_many(
+        &self,
+        calls: Vec<BundleCall>,
+        block_id: Option<BlockId>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> Result<Vec<BundleCallResult>>;
 
     /// Generates an access list for a transaction.
     ///
@@ -134,9 +159,14 @@ pub trait EthApi {
     ) -> Result<AccessListWithGasUsed>;
 
     /// Generates and returns an estimate of how much gas is necessary to allow the transaction to
-    /// complete.
+    /// complete. The optional state overrides are applied before the estimation.
     #[method(name = "estimateGas")]
-    async fn estimate_gas(&self, request: TransactionRequest, block_id: Option<BlockId>) -> Result<U256>;
+    async fn estimate_gas(
+        &self,
+        request: TransactionRequest,
+        block_id: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+    ) -> Result<U256>;
 
     /// Returns the current price per gas in wei.
     #[method(name = "gasPrice")]
@@ -209,16 +239,26 @@ pub trait EthApi {
     #[method(name = "signTypedData")]
     async fn sign_typed_data(&self, address: Address, data: serde_json::Value) -> Result<Bytes>;
 
+    /// Signs data via [EIP-712](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md),
+    /// as implemented by MetaMask's `eth_signTypedData_v4`, with support for arrays and nested
+    /// structs.
+    #[method(name = "signTypedData_v4")]
+    async fn sign_typed_data_v4(&self, address: Address, data: serde_json::Value) -> Result<Bytes>;
+
     /// Returns the account and storage values of the specified account including the Merkle-proof.
     /// This call can be used to verify that the data you are pulling from is not tampered with.
     #[method(name = "getProof")]
     async fn get_proof(
         &self,
         address: Address,
-        keys: Vec<B256>,
+        keys: Vec<JsonStorageKey>,
         block_id: Option<BlockId>,
     ) -> Result<EIP1186AccountProofResponse>;
 
+    /// Returns the balance, nonce, code hash and storage root of the account in a single call.
+    #[method(name = "getAccount")]
+    async fn get_account(&self, address: Address, block_id: Option<BlockId>) -> Result<EthAccount>;
+
     /// Creates a filter object, based on filter options, to notify when the state changes (logs).
     #[method(name = "newFilter")]
     async fn new_filter(&self, filter: Filter) -> Result<U64>;
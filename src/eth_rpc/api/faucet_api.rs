@@ -0,0 +1,14 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use reth_primitives::{Address, B256};
+
+/// Faucet API
+/// Drips of test ETH from a funded account, served by the testnets.
+#[rpc(server, namespace = "kakarot")]
+#[async_trait]
+pub trait FaucetApi {
+    /// Sends the amount of the faucet to the address and returns the hash of the transaction.
+    /// The requests are limited per address and per client IP.
+    #[method(name = "requestFunds")]
+    async fn request_funds(&self, address: Address) -> Result<B256>;
+}
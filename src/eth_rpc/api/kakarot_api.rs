@@ -0,0 +1,76 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use reth_primitives::serde_helper::U64HexOrNumber;
+use reth_primitives::{Address, BlockId, BlockNumberOrTag};
+use reth_rpc_types::trace::parity::StateDiff;
+use reth_rpc_types::{Filter, Log, RichBlock};
+use starknet_crypto::FieldElement;
+
+use crate::models::account::AccountType;
+use crate::models::fee::BlockFeeBreakdown;
+use crate::models::internal_transaction::InternalTransaction;
+use crate::models::l1_message::L1Message;
+use crate::models::pagination::{Cursor, Page};
+
+/// Kakarot API
+/// Convenience methods which aren't part of the Ethereum JSON-RPC specification.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "kakarot"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "kakarot"))]
+#[async_trait]
+pub trait KakarotApi {
+    /// Returns the latest block mined at or before the timestamp (in seconds), or null if the
+    /// timestamp precedes the first block. The block can be full or just the hashes of the
+    /// transactions.
+    #[method(name = "getBlockByTimestamp")]
+    async fn get_block_by_timestamp(&self, timestamp: U64HexOrNumber, full: bool) -> Result<Option<RichBlock>>;
+
+    /// Returns the messages sent from L1 and handled by the Kakarot contract in the block range,
+    /// which allows tracking the deposits of the bridges.
+    #[method(name = "getL1Messages")]
+    async fn get_l1_messages(&self, from_block: BlockNumberOrTag, to_block: BlockNumberOrTag)
+        -> Result<Vec<L1Message>>;
+
+    /// Returns the Starknet address of the Kakarot account of the EVM address. The address is
+    /// derived from the EVM address, whether or not the account is deployed.
+    #[method(name = "getStarknetAddress")]
+    async fn get_starknet_address(&self, address: Address) -> Result<FieldElement>;
+
+    /// Returns the EVM address of the Kakarot account deployed at the Starknet address, or null
+    /// if no Kakarot account is deployed at this address.
+    #[method(name = "getEvmAddress")]
+    async fn get_evm_address(&self, address: FieldElement, block_id: Option<BlockId>) -> Result<Option<Address>>;
+
+    /// Returns the type of the Kakarot account of the address: "undeployed", "eoa" or "contract".
+    #[method(name = "getAccountType")]
+    async fn get_account_type(&self, address: Address, block_id: Option<BlockId>) -> Result<AccountType>;
+
+    /// Returns the EVM gas used and fee of each transaction of the block, alongside the fee
+    /// actually paid on Starknet and the implied conversion rate, or null if the block isn't
+    /// indexed.
+    #[method(name = "getBlockFeeBreakdown")]
+    async fn get_block_fee_breakdown(&self, block: BlockNumberOrTag) -> Result<Option<BlockFeeBreakdown>>;
+
+    /// Returns a page of the logs matching the filter, along with the cursor of the next page.
+    /// Unlike `eth_getLogs`, the logs of any block range can be queried, one page at a time: the
+    /// first page starts at the start of the range, and each following page at the cursor
+    /// returned by the previous page.
+    #[method(name = "getLogsPage")]
+    async fn get_logs_page(&self, filter: Filter, cursor: Option<Cursor>) -> Result<Page<Log>>;
+
+    /// Returns the balance, nonce, code and storage changes of the Kakarot accounts in the block,
+    /// derived from the Starknet state update of the block, or null if the block isn't indexed.
+    #[method(name = "getStateDiff")]
+    async fn get_state_diff(&self, block: BlockNumberOrTag) -> Result<Option<StateDiff>>;
+
+    /// Returns a page of the internal transactions sent or received by the address in the block
+    /// range, along with the cursor of the next page. The internal transactions are extracted
+    /// from the traces of the blocks by the trace backfill.
+    #[method(name = "getInternalTransactions")]
+    async fn get_internal_transactions(
+        &self,
+        address: Address,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+        cursor: Option<Cursor>,
+    ) -> Result<Page<InternalTransaction>>;
+}
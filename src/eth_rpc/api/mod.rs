@@ -1,6 +1,14 @@
+pub mod admin_api;
 pub mod alchemy_api;
 pub mod debug_api;
+pub mod dev_api;
 pub mod eth_api;
+pub mod faucet_api;
+pub mod kakarot_api;
 pub mod net_api;
+pub mod ots_api;
+pub mod personal_api;
+pub mod pubsub_api;
 pub mod trace_api;
+pub mod txpool_api;
 pub mod web3_api;
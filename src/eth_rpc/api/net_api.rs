@@ -11,11 +11,11 @@ pub trait NetApi {
     #[method(name = "version")]
     async fn version(&self) -> Result<U64>;
 
-    /// Returns number of peers connected to node.
+    /// Returns the number of healthy Starknet providers, which stand for the peers of the node.
     #[method(name = "peerCount")]
     fn peer_count(&self) -> Result<PeerCount>;
 
-    /// Returns true if client is actively listening for network connections.
+    /// Returns true if the server is accepting new connections, i.e. isn't shutting down.
     /// Otherwise false.
     #[method(name = "listening")]
     fn listening(&self) -> Result<bool>;
@@ -24,4 +24,9 @@ pub trait NetApi {
     /// Otherwise throw an EthApiError.
     #[method(name = "health")]
     async fn health(&self) -> Result<bool>;
+
+    /// Returns true if Kakarot RPC is healthy and the block number advanced recently.
+    /// Otherwise throw an EthApiError.
+    #[method(name = "ready")]
+    async fn ready(&self) -> Result<bool>;
 }
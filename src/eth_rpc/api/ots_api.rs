@@ -0,0 +1,60 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use reth_primitives::{Address, B256, U256};
+
+use crate::models::internal_transaction::InternalOperation;
+use crate::models::otterscan::{BlockDetails, ContractCreator, TransactionsWithReceipts};
+
+/// Otterscan API
+/// Based on the Otterscan extension namespace:
+/// <https://github.com/otterscan/otterscan/blob/develop/docs/custom-jsonrpc.md>
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "ots"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "ots"))]
+#[async_trait]
+pub trait OtterscanApi {
+    /// Returns the version of the Otterscan API implemented by the node.
+    #[method(name = "getApiLevel")]
+    async fn get_api_level(&self) -> Result<u64>;
+
+    /// Returns the block without its transactions, along with its issuance and the sum of the
+    /// fees paid by its transactions.
+    #[method(name = "getBlockDetails")]
+    async fn get_block_details(&self, block_number: u64) -> Result<Option<BlockDetails>>;
+
+    /// Same as `ots_getBlockDetails`, for a block given by its hash.
+    #[method(name = "getBlockDetailsByHash")]
+    async fn get_block_details_by_hash(&self, block_hash: B256) -> Result<Option<BlockDetails>>;
+
+    /// Returns a page of the transactions sent from or to the address before the block, from
+    /// the most recent to the oldest. A block number of 0 starts the search at the latest block.
+    #[method(name = "searchTransactionsBefore")]
+    async fn search_transactions_before(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> Result<TransactionsWithReceipts>;
+
+    /// Returns a page of the transactions sent from or to the address after the block, from
+    /// the most recent to the oldest. A block number of 0 starts the search at the genesis block.
+    #[method(name = "searchTransactionsAfter")]
+    async fn search_transactions_after(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> Result<TransactionsWithReceipts>;
+
+    /// Returns the hash of the transaction sent by the sender with the given nonce.
+    #[method(name = "getTransactionBySenderAndNonce")]
+    async fn get_transaction_by_sender_and_nonce(&self, sender: Address, nonce: U256) -> Result<Option<B256>>;
+
+    /// Returns the hash of the transaction which deployed the contract, along with its sender.
+    #[method(name = "getContractCreator")]
+    async fn get_contract_creator(&self, address: Address) -> Result<Option<ContractCreator>>;
+
+    /// Returns the value transfers, contract deployments and self destructs made by the
+    /// contracts called by the transaction.
+    #[method(name = "getInternalOperations")]
+    async fn get_internal_operations(&self, transaction_hash: B256) -> Result<Vec<InternalOperation>>;
+}
@@ -0,0 +1,29 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use reth_primitives::{Address, Bytes, B256};
+use reth_rpc_types::TransactionRequest;
+
+/// Personal API
+/// Management of the accounts of an on-disk keystore, as geth's personal namespace.
+#[rpc(server, namespace = "personal")]
+#[async_trait]
+pub trait PersonalApi {
+    /// Generates a new account, whose keystore is encrypted with the passphrase, and returns its
+    /// address.
+    #[method(name = "newAccount")]
+    async fn new_account(&self, passphrase: String) -> Result<Address>;
+
+    /// Imports the unencrypted private key into the keystore, encrypted with the passphrase, and
+    /// returns the address of the account.
+    #[method(name = "importRawKey")]
+    async fn import_raw_key(&self, private_key: Bytes, passphrase: String) -> Result<Address>;
+
+    /// Returns the addresses of the accounts of the keystore.
+    #[method(name = "listAccounts")]
+    async fn list_accounts(&self) -> Result<Vec<Address>>;
+
+    /// Signs the transaction of the `from` account with the key decrypted with the passphrase,
+    /// and sends it. The key isn't kept unlocked.
+    #[method(name = "sendTransaction")]
+    async fn send_transaction(&self, request: TransactionRequest, passphrase: String) -> Result<B256>;
+}
@@ -0,0 +1,18 @@
+use jsonrpsee::core::SubscriptionResult;
+use jsonrpsee::proc_macros::rpc;
+use reth_rpc_types::pubsub::{Params, SubscriptionKind};
+
+/// Ethereum pub-sub API.
+/// Taken from Reth's EthPubSubApi trait:
+/// <https://github.com/paradigmxyz/reth/blob/v0.2.0-beta.6/crates/rpc/rpc-api/src/eth_pubsub.rs>
+#[rpc(server, namespace = "eth")]
+#[async_trait]
+pub trait EthPubSubApi {
+    /// Create an ethereum subscription for the given params
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = reth_rpc_types::pubsub::SubscriptionResult
+    )]
+    async fn subscribe(&self, kind: SubscriptionKind, params: Option<Params>) -> SubscriptionResult;
+}
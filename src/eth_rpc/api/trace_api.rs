@@ -1,8 +1,13 @@
+use std::collections::HashSet;
+
 use jsonrpsee::core::RpcResult as Result;
 use jsonrpsee::proc_macros::rpc;
-use reth_rpc_types::trace::parity::LocalizedTransactionTrace;
+use reth_primitives::B256;
+use reth_rpc_types::trace::parity::{LocalizedTransactionTrace, TraceResults, TraceType};
 use reth_rpc_types::BlockId;
 
+use crate::models::pagination::{Cursor, Page};
+
 /// Trace API
 #[rpc(server, namespace = "trace")]
 #[async_trait]
@@ -10,4 +15,26 @@ pub trait TraceApi {
     /// Returns the parity traces for the given block.
     #[method(name = "block")]
     async fn trace_block(&self, block_id: BlockId) -> Result<Option<Vec<LocalizedTransactionTrace>>>;
+
+    /// Returns a page of the parity traces of the block, along with the cursor of the next page.
+    /// The traces of a page stay below the maximum response size, so that the traces of large
+    /// blocks can be fetched one page at a time.
+    #[method(name = "blockPage")]
+    async fn trace_block_page(
+        &self,
+        block_id: BlockId,
+        cursor: Option<Cursor>,
+    ) -> Result<Option<Page<LocalizedTransactionTrace>>>;
+
+    /// Returns the parity traces for the given transaction.
+    #[method(name = "transaction")]
+    async fn trace_transaction(&self, transaction_hash: B256) -> Result<Option<Vec<LocalizedTransactionTrace>>>;
+
+    /// Replays the given transaction and returns the requested trace types.
+    #[method(name = "replayTransaction")]
+    async fn trace_replay_transaction(
+        &self,
+        transaction_hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<TraceResults>;
 }
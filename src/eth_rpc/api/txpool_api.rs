@@ -0,0 +1,29 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use reth_primitives::Address;
+use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolStatus};
+
+/// Txpool API
+/// Taken from Reth's TxPoolApi trait:
+/// <https://github.com/paradigmxyz/reth/blob/v0.2.0-beta.6/crates/rpc/rpc-api/src/txpool.rs>
+#[rpc(server, namespace = "txpool")]
+#[async_trait]
+pub trait TxPoolApi {
+    /// Returns the number of transactions currently pending for inclusion in the next block(s), as
+    /// well as the ones that are being scheduled for future execution only.
+    /// Ref: [Here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_status)
+    #[method(name = "status")]
+    async fn txpool_status(&self) -> Result<TxpoolStatus>;
+
+    /// Retrieves the transactions contained within the txpool, returning pending
+    /// transactions of this address, grouped by nonce.
+    /// Ref: [Here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_contentFrom)
+    #[method(name = "contentFrom")]
+    async fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom>;
+
+    /// Returns the details of all transactions currently pending for inclusion in the next
+    /// block(s), grouped by sender and nonce.
+    /// Ref: [Here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content)
+    #[method(name = "content")]
+    async fn txpool_content(&self) -> Result<TxpoolContent>;
+}
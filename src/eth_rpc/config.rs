@@ -1,19 +1,26 @@
+use std::collections::HashSet;
+
 use eyre::{eyre, Result};
 
+use super::middleware::cors::CorsConfig;
+use super::rpc::KakarotRpcModule;
+
 #[derive(Debug)]
 pub struct RPCConfig {
     pub socket_addr: String,
+    /// CORS policy and virtual hosts of the server.
+    pub cors: CorsConfig,
 }
 
 impl RPCConfig {
-    pub const fn new(socket_addr: String) -> Self {
-        Self { socket_addr }
+    pub fn new(socket_addr: String) -> Self {
+        Self { socket_addr, cors: CorsConfig::default() }
     }
 
     pub fn from_env() -> Result<Self> {
         let socket_addr = std::env::var("KAKAROT_RPC_URL")
             .map_err(|_| eyre!("Missing mandatory environment variable: KAKAROT_RPC_URL"))?;
-        Ok(Self::new(socket_addr))
+        Ok(Self { socket_addr, cors: CorsConfig::from_env()? })
     }
 
     pub fn from_port(port: u16) -> Result<Self> {
@@ -43,3 +50,96 @@ impl RPCConfig {
         config
     }
 }
+
+/// APIs served by each transport, as geth's `--http.api`. A transport serves all the APIs when
+/// they aren't listed. The APIs which aren't enabled, e.g. `dev` without the `--dev` flag, aren't
+/// served even when listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RpcApiConfig {
+    /// APIs of the public server, over HTTP and websocket.
+    pub http: Option<HashSet<KakarotRpcModule>>,
+    /// APIs of the authenticated server.
+    pub authrpc: Option<HashSet<KakarotRpcModule>>,
+    /// APIs of the IPC server.
+    pub ipc: Option<HashSet<KakarotRpcModule>>,
+}
+
+impl RpcApiConfig {
+    /// Create a new `RpcApiConfig` from the `RPC_HTTP_API`, `RPC_AUTHRPC_API` and `RPC_IPC_API`
+    /// environment variables, each a comma separated list of APIs.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        for (name, apis) in [
+            ("RPC_HTTP_API", &mut config.http),
+            ("RPC_AUTHRPC_API", &mut config.authrpc),
+            ("RPC_IPC_API", &mut config.ipc),
+        ] {
+            if let Some(value) = std::env::var(name).ok().filter(|value| !value.trim().is_empty()) {
+                *apis = Some(parse_apis(&value).map_err(|err| eyre!("Invalid {name}: {err}"))?);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Overrides the configuration with the `--http.api`, `--authrpc.api` and `--ipc.api` command
+    /// line flags, given either as `--flag value` or `--flag=value`.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let apis = match flag.as_str() {
+                "--http.api" => &mut self.http,
+                "--authrpc.api" => &mut self.authrpc,
+                "--ipc.api" => &mut self.ipc,
+                _ => continue,
+            };
+            let value = value.or_else(|| args.next()).ok_or_else(|| eyre!("Missing value for {flag}"))?;
+            *apis = Some(parse_apis(&value).map_err(|err| eyre!("Invalid {flag}: {err}"))?);
+        }
+        Ok(self)
+    }
+}
+
+/// Parses a comma separated list of APIs.
+fn parse_apis(value: &str) -> Result<HashSet<KakarotRpcModule>> {
+    let mut modules = HashSet::new();
+    for api in value.split(',').map(str::trim).filter(|api| !api.is_empty()) {
+        let api_modules = KakarotRpcModule::from_api(api).ok_or_else(|| {
+            let mut apis = KakarotRpcModule::ALL.map(|module| module.api()).to_vec();
+            apis.dedup();
+            eyre!("unknown API {api}, expected one of {}", apis.join(", "))
+        })?;
+        modules.extend(api_modules);
+    }
+    Ok(modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_api_config_with_args() {
+        // Given
+        let args = ["--http.api", "eth, net,web3", "--ipc.api=debug"].map(ToString::to_string);
+
+        // When
+        let config = RpcApiConfig::default().with_args(args).unwrap();
+
+        // Then
+        let http = [KakarotRpcModule::Eth, KakarotRpcModule::EthPubSub, KakarotRpcModule::Net, KakarotRpcModule::Web3];
+        assert_eq!(config.http, Some(HashSet::from(http)));
+        assert_eq!(config.ipc, Some(HashSet::from([KakarotRpcModule::Debug])));
+        assert_eq!(config.authrpc, None);
+    }
+
+    #[test]
+    fn test_rpc_api_config_unknown_api() {
+        let err = RpcApiConfig::default().with_args(["--http.api=eth,shh".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("unknown API shh"));
+        assert!(RpcApiConfig::default().with_args(["--authrpc.api".to_string()]).is_err());
+    }
+}
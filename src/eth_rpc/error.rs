@@ -0,0 +1,292 @@
+//! Conversion of the errors of the Ethereum provider into JSON-RPC errors.
+//!
+//! The codes and messages follow geth, as tools and client libraries pattern match on them:
+//! reverted executions are returned with the code 3 and the revert data, and the transactions
+//! rejected by the pool with the code -32000 and the messages of the geth transaction pool.
+
+use jsonrpsee::types::ErrorObject;
+use starknet::core::types::StarknetError;
+use starknet::providers::ProviderError;
+
+use crate::eth_provider::error::{EthApiError, EthRpcErrorCode, EvmError, KakarotError, TransactionError};
+use crate::eth_provider::starknet::transport::is_upstream_unavailable;
+
+/// Constructs a JSON-RPC error object, consisting of `code`, `message` and, for reverted
+/// executions, the revert data as `data`.
+impl From<EthApiError> for ErrorObject<'static> {
+    fn from(value: EthApiError) -> Self {
+        ErrorObject::owned(error_code(&value) as i32, error_message(&value), error_data(&value))
+    }
+}
+
+/// Returns the JSON-RPC error code of the error.
+pub fn error_code(error: &EthApiError) -> EthRpcErrorCode {
+    match error {
+        EthApiError::UnknownBlock | EthApiError::UnknownBlockNumber | EthApiError::TransactionNotFound(_) => {
+            EthRpcErrorCode::ResourceNotFound
+        }
+        EthApiError::InvalidBlockRange
+        | EthApiError::InvalidRewardPercentiles
+        | EthApiError::Signature(_)
+        | EthApiError::EthereumDataFormat(_)
+        | EthApiError::CalldataExceededLimit(_, _)
+        | EthApiError::InvalidStateOverride(_)
+        | EthApiError::TokenAddressesLimitExceeded(_)
+        | EthApiError::CallBundleLimitExceeded(_)
+        | EthApiError::InvalidParams(_) => EthRpcErrorCode::InvalidParams,
+        EthApiError::BlockRangeLimitExceeded(_)
+        | EthApiError::LogsLimitExceeded { .. }
+        | EthApiError::ResponseTooLarge(_)
+        | EthApiError::RateLimitExceeded
+        | EthApiError::TracingTimeout => EthRpcErrorCode::RequestLimitExceeded,
+        EthApiError::FilterNotFound(_) => EthRpcErrorCode::InvalidInput,
+        EthApiError::Transaction(err) => transaction_error_code(err),
+        EthApiError::Unsupported(_) => EthRpcErrorCode::MethodNotSupported,
+        EthApiError::NotReady(_) | EthApiError::RequestTimeout => EthRpcErrorCode::ResourceUnavailable,
+        EthApiError::NamespaceDisabled(_) => EthRpcErrorCode::MethodNotSupported,
+        EthApiError::Kakarot(err) => kakarot_error_code(err),
+    }
+}
+
+fn transaction_error_code(error: &TransactionError) -> EthRpcErrorCode {
+    match error {
+        TransactionError::InvalidChainId
+        | TransactionError::FeeCapTooLow(_, _)
+        | TransactionError::TipAboveFeeCap(_, _)
+        | TransactionError::ReplacementUnderpriced
+        | TransactionError::NonceTooLow(_, _, _)
+        | TransactionError::InsufficientFunds(_, _)
+        | TransactionError::AlreadyKnown => EthRpcErrorCode::InvalidInput,
+        TransactionError::GasOverflow
+        | TransactionError::BlobTransactionUnsupported
+        | TransactionError::AccountDeploymentFailed(_, _) => EthRpcErrorCode::TransactionRejected,
+        TransactionError::RelayersOutOfFunds => EthRpcErrorCode::ResourceUnavailable,
+        TransactionError::ExpectedFullTransactions | TransactionError::Tracing(_) => EthRpcErrorCode::InternalError,
+    }
+}
+
+fn kakarot_error_code(error: &KakarotError) -> EthRpcErrorCode {
+    match error {
+        KakarotError::ExecutionError(EvmError::Revert(_)) => EthRpcErrorCode::ExecutionError,
+        // As in geth, the executions halted by the EVM are failed calls rather than reverts
+        KakarotError::ExecutionError(_) => EthRpcErrorCode::InvalidInput,
+        KakarotError::ProviderError(err) if is_upstream_unavailable(err) => EthRpcErrorCode::ResourceUnavailable,
+        KakarotError::ProviderError(ProviderError::StarknetError(err)) => starknet_error_code(err),
+        _ => EthRpcErrorCode::InternalError,
+    }
+}
+
+fn starknet_error_code(error: &StarknetError) -> EthRpcErrorCode {
+    match error {
+        StarknetError::InvalidTransactionNonce
+        | StarknetError::InsufficientMaxFee
+        | StarknetError::InsufficientAccountBalance
+        | StarknetError::DuplicateTx
+        | StarknetError::ValidationFailure(_) => EthRpcErrorCode::InvalidInput,
+        StarknetError::ContractNotFound | StarknetError::BlockNotFound | StarknetError::TransactionHashNotFound => {
+            EthRpcErrorCode::ResourceNotFound
+        }
+        StarknetError::TooManyKeysInFilter
+        | StarknetError::PageSizeTooBig
+        | StarknetError::InvalidContinuationToken => EthRpcErrorCode::InvalidParams,
+        _ => EthRpcErrorCode::InternalError,
+    }
+}
+
+/// Returns the JSON-RPC error message of the error.
+pub fn error_message(error: &EthApiError) -> String {
+    match error {
+        // The messages of the rejected transactions are the ones of the geth transaction pool
+        EthApiError::Transaction(err) => err.to_string(),
+        EthApiError::Kakarot(KakarotError::ExecutionError(err @ EvmError::Revert(_))) => match err.revert_reason() {
+            Some(reason) => format!("execution reverted: {reason}"),
+            None => "execution reverted".to_string(),
+        },
+        EthApiError::Kakarot(KakarotError::ExecutionError(err)) => err.to_string(),
+        EthApiError::Kakarot(KakarotError::ProviderError(ProviderError::StarknetError(err))) => {
+            starknet_error_message(err).map_or_else(|| format!("{error:?}"), ToString::to_string)
+        }
+        _ => format!("{error:?}"),
+    }
+}
+
+/// Returns the geth message matching the Starknet error, if any.
+const fn starknet_error_message(error: &StarknetError) -> Option<&'static str> {
+    match error {
+        StarknetError::InvalidTransactionNonce => Some("invalid transaction nonce"),
+        StarknetError::InsufficientMaxFee => Some("transaction underpriced"),
+        StarknetError::InsufficientAccountBalance => Some("insufficient funds for gas * price + value"),
+        StarknetError::DuplicateTx => Some("already known"),
+        _ => None,
+    }
+}
+
+/// Returns the data of the JSON-RPC error: the hex encoded revert data of a reverted execution.
+fn error_data(error: &EthApiError) -> Option<String> {
+    match error {
+        EthApiError::Kakarot(KakarotError::ExecutionError(EvmError::Revert(data))) => Some(data.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth_provider::starknet::transport::FailoverTransportError;
+    use reth_primitives::{Address, Bytes};
+    use starknet::providers::jsonrpc::HttpTransportError;
+
+    /// Returns the `Error(string)` revert payload of the reason.
+    fn revert_payload(reason: &str) -> Bytes {
+        let mut payload = vec![0x08, 0xc3, 0x79, 0xa0];
+        let mut word = [0u8; 32];
+        word[31] = 0x20;
+        payload.extend_from_slice(&word);
+        word[31] = reason.len() as u8;
+        payload.extend_from_slice(&word);
+        let mut reason = reason.as_bytes().to_vec();
+        reason.resize(reason.len().div_ceil(32) * 32, 0);
+        payload.extend_from_slice(&reason);
+        payload.into()
+    }
+
+    #[test]
+    fn test_assure_source_error_visible_in_kakarot_error() {
+        let err = KakarotError::ProviderError(ProviderError::StarknetError(StarknetError::UnexpectedError(
+            "test".to_string(),
+        )));
+
+        let eth_err: EthApiError = err.into();
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        assert_eq!(json_err.code(), EthRpcErrorCode::InternalError as i32);
+        assert_eq!(json_err.message(), "starknet provider error: StarknetError(UnexpectedError(\"test\"))");
+    }
+
+    #[test]
+    fn test_revert_error() {
+        // Given
+        let payload = revert_payload("Ownable: caller is not the owner");
+        let eth_err: EthApiError = KakarotError::from(EvmError::Revert(payload.clone())).into();
+
+        // When
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        // Then
+        assert_eq!(json_err.code(), EthRpcErrorCode::ExecutionError as i32);
+        assert_eq!(json_err.message(), "execution reverted: Ownable: caller is not the owner");
+        assert_eq!(json_err.data().map(|data| data.get().to_string()), Some(format!("\"{payload}\"")));
+    }
+
+    #[test]
+    fn test_revert_error_without_reason() {
+        // Given
+        let payload = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        let eth_err: EthApiError = KakarotError::from(EvmError::Revert(payload)).into();
+
+        // When
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        // Then
+        assert_eq!(json_err.code(), EthRpcErrorCode::ExecutionError as i32);
+        assert_eq!(json_err.message(), "execution reverted");
+        assert_eq!(json_err.data().map(|data| data.get().to_string()), Some("\"0xdeadbeef\"".to_string()));
+    }
+
+    #[test]
+    fn test_panic_error() {
+        // Given
+        let mut payload = vec![0x4e, 0x48, 0x7b, 0x71];
+        payload.extend_from_slice(&[0u8; 31]);
+        payload.push(0x12);
+        let eth_err: EthApiError = KakarotError::from(EvmError::Revert(payload.into())).into();
+
+        // When
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        // Then
+        assert_eq!(json_err.code(), EthRpcErrorCode::ExecutionError as i32);
+        assert_eq!(json_err.message(), "execution reverted: panic: division or modulo by zero (0x12)");
+    }
+
+    #[test]
+    fn test_evm_halt_error() {
+        let eth_err: EthApiError = KakarotError::from(EvmError::OutOfGas).into();
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        assert_eq!(json_err.code(), EthRpcErrorCode::InvalidInput as i32);
+        assert_eq!(json_err.message(), "out of gas");
+        assert!(json_err.data().is_none());
+    }
+
+    #[test]
+    fn test_nonce_errors() {
+        let nonce_too_low: ErrorObject<'static> =
+            EthApiError::from(TransactionError::NonceTooLow(Address::ZERO, 1, 2)).into();
+        let already_known: ErrorObject<'static> = EthApiError::from(TransactionError::AlreadyKnown).into();
+
+        assert_eq!(nonce_too_low.code(), EthRpcErrorCode::InvalidInput as i32);
+        assert_eq!(
+            nonce_too_low.message(),
+            "nonce too low: address 0x0000000000000000000000000000000000000000, tx: 1 state: 2"
+        );
+        assert_eq!(already_known.code(), EthRpcErrorCode::InvalidInput as i32);
+        assert_eq!(already_known.message(), "already known");
+    }
+
+    #[test]
+    fn test_starknet_transaction_errors() {
+        let duplicate: EthApiError =
+            KakarotError::ProviderError(ProviderError::StarknetError(StarknetError::DuplicateTx)).into();
+        let json_err: ErrorObject<'static> = duplicate.into();
+
+        assert_eq!(json_err.code(), EthRpcErrorCode::InvalidInput as i32);
+        assert_eq!(json_err.message(), "already known");
+    }
+
+    #[test]
+    fn test_unsupported_error() {
+        let eth_err = EthApiError::Unsupported("eth_coinbase");
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        assert_eq!(json_err.code(), EthRpcErrorCode::MethodNotSupported as i32);
+        assert_eq!(json_err.message(), "unsupported: eth_coinbase");
+    }
+
+    #[test]
+    fn test_blob_transaction_error() {
+        let eth_err: EthApiError = TransactionError::BlobTransactionUnsupported.into();
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        assert_eq!(json_err.code(), EthRpcErrorCode::TransactionRejected as i32);
+        assert_eq!(json_err.message(), "blob transactions unsupported on Kakarot");
+    }
+
+    #[test]
+    fn test_account_deployment_error() {
+        let eth_err: EthApiError =
+            TransactionError::AccountDeploymentFailed(Address::ZERO, "reverted".to_string()).into();
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        assert_eq!(json_err.code(), EthRpcErrorCode::TransactionRejected as i32);
+        assert_eq!(
+            json_err.message(),
+            "failed to deploy the account of 0x0000000000000000000000000000000000000000: reverted"
+        );
+    }
+
+    #[test]
+    fn test_upstream_unavailable_error() {
+        let err = KakarotError::ProviderError(ProviderError::Other(Box::new(
+            starknet::providers::jsonrpc::JsonRpcClientError::TransportError(
+                FailoverTransportError::<HttpTransportError>::Unavailable,
+            ),
+        )));
+
+        let eth_err: EthApiError = err.into();
+        let json_err: ErrorObject<'static> = eth_err.into();
+
+        assert_eq!(json_err.code(), EthRpcErrorCode::ResourceUnavailable as i32);
+        assert_eq!(json_err.message(), "upstream unavailable: all the Starknet providers are failing");
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use eyre::{eyre, Result};
+use reth_primitives::{Address, B256, U256};
+use reth_rpc_types::TransactionRequest;
+use tokio::sync::Mutex;
+
+use crate::eth_provider::error::EthApiError;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
+use crate::eth_rpc::servers::eth_rpc::request_transaction;
+use crate::eth_rpc::signer::Signers;
+use crate::prometheus_handler::{register, Counter, CounterVec, Opts, PrometheusError, Registry, F64, U64};
+
+/// Number of tracked addresses or IPs above which the ones which didn't request funds recently
+/// are forgotten.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Configuration of the faucet, dripping test ETH from a funded account.
+#[derive(Clone, PartialEq, Eq)]
+pub struct FaucetConfig {
+    /// Private key of the funded account.
+    pub private_key: B256,
+    /// Amount sent by each drip, in wei.
+    pub amount: U256,
+    /// Minimum duration between two drips to the same address, in seconds. 0 disables the limit.
+    pub address_interval: u64,
+    /// Minimum duration between two drips requested by the same client IP, in seconds. 0
+    /// disables the limit.
+    pub ip_interval: u64,
+}
+
+impl FaucetConfig {
+    /// Create a new [`FaucetConfig`] from the `FAUCET_PRIVATE_KEY`, `FAUCET_AMOUNT`,
+    /// `FAUCET_ADDRESS_INTERVAL` and `FAUCET_IP_INTERVAL` environment variables. Returns an
+    /// error if the private key isn't set.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let parse_interval = |name: &str, default: u64| -> Result<u64> {
+            var(name).map_or(Ok(default), |interval| {
+                u64::from_str(interval.trim()).map_err(|err| eyre!("Invalid {name} {interval}: {err}"))
+            })
+        };
+
+        let private_key = var("FAUCET_PRIVATE_KEY")
+            .ok_or_else(|| eyre!("Missing FAUCET_PRIVATE_KEY, required by the faucet"))?
+            .trim()
+            .parse::<B256>()
+            .map_err(|_| eyre!("Invalid FAUCET_PRIVATE_KEY"))?;
+        let amount = var("FAUCET_AMOUNT").map_or(Ok(U256::from(10).pow(U256::from(18))), |amount| {
+            U256::from_str(amount.trim()).map_err(|err| eyre!("Invalid FAUCET_AMOUNT {amount}: {err}"))
+        })?;
+
+        Ok(Self {
+            private_key,
+            amount,
+            address_interval: parse_interval("FAUCET_ADDRESS_INTERVAL", 86_400)?,
+            ip_interval: parse_interval("FAUCET_IP_INTERVAL", 3_600)?,
+        })
+    }
+}
+
+impl std::fmt::Debug for FaucetConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The private key is never printed
+        f.debug_struct("FaucetConfig")
+            .field("amount", &self.amount)
+            .field("address_interval", &self.address_interval)
+            .field("ip_interval", &self.ip_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Metrics on the drips of the faucet.
+#[derive(Debug, Clone)]
+pub struct FaucetMetrics {
+    /// Number of requests of funds, per outcome.
+    requests: CounterVec<U64>,
+    /// Total amount sent, in ETH.
+    sent: Counter<F64>,
+}
+
+impl FaucetMetrics {
+    /// Create an instance of metrics
+    pub fn new(metrics_registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            requests: register(
+                CounterVec::new(
+                    Opts::new("faucet_requests", "Number of requests of funds to the faucet"),
+                    &["status"],
+                )?,
+                metrics_registry,
+            )?,
+            sent: register(
+                Counter::new("faucet_sent_eth", "Total amount of ETH sent by the faucet")?,
+                metrics_registry,
+            )?,
+        })
+    }
+}
+
+/// Times of the last drips to each key, allowing a drip per key at most once per interval.
+#[derive(Debug)]
+struct DripLimiter<K> {
+    interval: Duration,
+    last_drips: HashMap<K, Instant>,
+}
+
+impl<K: Hash + Eq> DripLimiter<K> {
+    /// Returns the limiter for the interval in seconds, or None if the limit is disabled.
+    fn new(interval: u64) -> Option<Self> {
+        (interval > 0).then(|| Self { interval: Duration::from_secs(interval), last_drips: HashMap::new() })
+    }
+
+    /// Returns true if the key is allowed a drip.
+    fn allowed(&self, key: &K) -> bool {
+        self.last_drips.get(key).map_or(true, |last_drip| last_drip.elapsed() >= self.interval)
+    }
+
+    /// Records a drip to the key.
+    fn record(&mut self, key: K) {
+        if self.last_drips.len() > MAX_TRACKED_KEYS {
+            let interval = self.interval;
+            self.last_drips.retain(|_, last_drip| last_drip.elapsed() < interval);
+        }
+        self.last_drips.insert(key, Instant::now());
+    }
+}
+
+/// State of the faucet, updated by one drip at a time.
+#[derive(Debug)]
+struct FaucetState {
+    address_limiter: Option<DripLimiter<Address>>,
+    ip_limiter: Option<DripLimiter<IpAddr>>,
+    /// Nonce of the last drip, so that each drip gets its own nonce even before the previous ones
+    /// are indexed.
+    last_nonce: Option<u64>,
+}
+
+impl FaucetState {
+    /// Returns true if the address and the client IP are allowed a drip. When the IP limit is
+    /// enabled, the requests of unknown client IPs are rejected, since they couldn't be limited.
+    fn check(&self, address: Address, ip: Option<IpAddr>) -> bool {
+        let ip_allowed = self.ip_limiter.as_ref().map_or(true, |limiter| ip.is_some_and(|ip| limiter.allowed(&ip)));
+        ip_allowed && self.address_limiter.as_ref().map_or(true, |limiter| limiter.allowed(&address))
+    }
+
+    /// Records a drip to the address requested by the client IP.
+    fn record(&mut self, address: Address, ip: Option<IpAddr>) {
+        if let Some(limiter) = &mut self.address_limiter {
+            limiter.record(address);
+        }
+        if let (Some(limiter), Some(ip)) = (&mut self.ip_limiter, ip) {
+            limiter.record(ip);
+        }
+    }
+}
+
+/// Faucet sending a fixed amount of test ETH from a funded account, at most once per interval
+/// for each address and each client IP.
+pub struct Faucet {
+    signers: Signers,
+    account: Address,
+    amount: U256,
+    /// The drips are sent one at a time, so that the quotas are only consumed by the drips which
+    /// were sent.
+    state: Mutex<FaucetState>,
+    metrics: Option<FaucetMetrics>,
+}
+
+impl Faucet {
+    /// Create a new [`Faucet`] from its configuration.
+    pub fn new(config: &FaucetConfig) -> Result<Self> {
+        let signers = Signers::new([config.private_key])?;
+        let account = signers.addresses()[0];
+
+        Ok(Self {
+            signers,
+            account,
+            amount: config.amount,
+            state: Mutex::new(FaucetState {
+                address_limiter: DripLimiter::new(config.address_interval),
+                ip_limiter: DripLimiter::new(config.ip_interval),
+                last_nonce: None,
+            }),
+            metrics: None,
+        })
+    }
+
+    /// Records the drips in the metrics.
+    pub fn with_metrics(mut self, metrics: FaucetMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Returns the address of the funded account.
+    pub const fn account(&self) -> Address {
+        self.account
+    }
+
+    /// Sends the amount of the faucet to the address, and returns the hash of the transaction.
+    /// Requests exceeding the limits of the address or of the client IP are rejected. The limits
+    /// are only consumed once the transaction is sent.
+    pub async fn request_funds<P: EthereumProvider>(
+        &self,
+        eth_provider: &P,
+        address: Address,
+        ip: Option<IpAddr>,
+    ) -> EthProviderResult<B256> {
+        let mut state = self.state.lock().await;
+        if !state.check(address, ip) {
+            self.record("rate_limited");
+            return Err(EthApiError::RateLimitExceeded);
+        }
+
+        match self.send(eth_provider, address, &mut state.last_nonce).await {
+            Ok(hash) => {
+                state.record(address, ip);
+                self.record("sent");
+                if let Some(metrics) = &self.metrics {
+                    metrics.sent.inc_by(f64::from(self.amount) / 1e18);
+                }
+                Ok(hash)
+            }
+            Err(err) => {
+                self.record("failed");
+                Err(err)
+            }
+        }
+    }
+
+    async fn send<P: EthereumProvider>(
+        &self,
+        eth_provider: &P,
+        address: Address,
+        last_nonce: &mut Option<u64>,
+    ) -> EthProviderResult<B256> {
+        let nonce: u64 = eth_provider.transaction_count(self.account, None).await?.try_into().unwrap_or(u64::MAX);
+        let nonce = last_nonce.map_or(nonce, |last_nonce| nonce.max(last_nonce + 1));
+
+        let request = TransactionRequest {
+            from: Some(self.account),
+            to: Some(address),
+            value: Some(self.amount),
+            nonce: Some(nonce),
+            ..Default::default()
+        };
+        let transaction = request_transaction(eth_provider, self.account, request).await?;
+        let transaction = self.signers.sign_transaction(&self.account, transaction.transaction)?;
+        let hash = eth_provider.send_raw_transaction(transaction.envelope_encoded()).await?;
+
+        *last_nonce = Some(nonce);
+        Ok(hash)
+    }
+
+    fn record(&self, status: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.requests.with_label_values(&[status]).inc();
+        }
+    }
+}
+
+impl std::fmt::Debug for Faucet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Faucet").field("account", &self.account).field("amount", &self.amount).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_faucet_rate_limits() {
+        // Given
+        let config = FaucetConfig {
+            private_key: B256::with_last_byte(1),
+            amount: U256::from(1),
+            address_interval: 3_600,
+            ip_interval: 3_600,
+        };
+        let faucet = Faucet::new(&config).unwrap();
+        let mut state = faucet.state.lock().await;
+        let ip = IpAddr::from([10, 0, 0, 1]);
+
+        // When & Then
+        assert!(state.check(Address::with_last_byte(1), Some(ip)));
+        // The quotas are only consumed by the drips which were sent
+        assert!(state.check(Address::with_last_byte(1), Some(ip)));
+        state.record(Address::with_last_byte(1), Some(ip));
+        // The address and the IP already received funds
+        assert!(!state.check(Address::with_last_byte(1), Some(IpAddr::from([10, 0, 0, 2]))));
+        assert!(!state.check(Address::with_last_byte(2), Some(ip)));
+        assert!(state.check(Address::with_last_byte(2), Some(IpAddr::from([10, 0, 0, 2]))));
+        // The requests of unknown client IPs are rejected
+        assert!(!state.check(Address::with_last_byte(2), None));
+    }
+
+    #[tokio::test]
+    async fn test_faucet_disabled_rate_limits() {
+        let config = FaucetConfig {
+            private_key: B256::with_last_byte(1),
+            amount: U256::from(1),
+            address_interval: 0,
+            ip_interval: 0,
+        };
+        let faucet = Faucet::new(&config).unwrap();
+        let mut state = faucet.state.lock().await;
+        for _ in 0..10 {
+            assert!(state.check(Address::ZERO, None));
+            state.record(Address::ZERO, None);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reth_primitives::{BlockId, BlockNumberOrTag, B256, U64};
+use reth_rpc_types::{Filter, FilterChanges, Header};
+
+use crate::eth_provider::constant::REORG_TRACKING_DEPTH;
+use crate::eth_provider::error::EthApiError;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
+
+/// Duration after which a filter which wasn't polled is removed.
+pub const FILTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The kind of an installed filter.
+#[derive(Debug)]
+enum FilterKind {
+    /// Logs matching the filter.
+    Logs(Box<Filter>),
+    /// Hashes of the new blocks.
+    Blocks,
+    /// Hashes of the transactions entering the pending pool, along with
+    /// the hashes already returned to the caller.
+    PendingTransactions(HashSet<B256>),
+}
+
+/// A filter installed by a caller, which is polled through `eth_getFilterChanges`.
+#[derive(Debug)]
+struct ActiveFilter {
+    kind: FilterKind,
+    /// Blocks returned to the caller.
+    chain: ChainTracker,
+    /// Instant of the last poll, used to expire the filter.
+    last_poll: Instant,
+}
+
+/// Keeps track of the filters installed through the `eth_newFilter`, `eth_newBlockFilter`
+/// and `eth_newPendingTransactionFilter` methods. Filters which aren't polled during
+/// [`FILTER_TIMEOUT`] are removed.
+#[derive(Debug, Default)]
+pub struct FilterManager {
+    next_id: AtomicU64,
+    filters: Mutex<HashMap<U64, Arc<tokio::sync::Mutex<ActiveFilter>>>>,
+}
+
+impl FilterManager {
+    /// Installs a logs filter, returning its id.
+    pub async fn new_logs_filter<P: EthereumProvider>(&self, provider: &P, filter: Filter) -> EthProviderResult<U64> {
+        self.install(provider, FilterKind::Logs(Box::new(filter))).await
+    }
+
+    /// Installs a new blocks filter, returning its id.
+    pub async fn new_block_filter<P: EthereumProvider>(&self, provider: &P) -> EthProviderResult<U64> {
+        self.install(provider, FilterKind::Blocks).await
+    }
+
+    /// Installs a pending transactions filter, returning its id.
+    pub async fn new_pending_transaction_filter<P: EthereumProvider>(&self, provider: &P) -> EthProviderResult<U64> {
+        // The transactions already in the pending pool are not part of the changes
+        let seen = provider.pending_transactions().await?.into_iter().map(|tx| tx.hash).collect();
+        self.install(provider, FilterKind::PendingTransactions(seen)).await
+    }
+
+    /// Removes the filter, returning true if the filter existed.
+    pub fn uninstall(&self, id: U64) -> bool {
+        self.filters.lock().expect("Failed to lock filters").remove(&id).is_some()
+    }
+
+    /// Returns the changes of the filter since its last poll.
+    pub async fn filter_changes<P: EthereumProvider>(&self, provider: &P, id: U64) -> EthProviderResult<FilterChanges> {
+        let filter = self.get(id)?;
+        let mut filter = filter.lock().await;
+        filter.last_poll = Instant::now();
+
+        let ActiveFilter { kind, chain, .. } = &mut *filter;
+        let changes = match kind {
+            FilterKind::Logs(logs_filter) => {
+                let update = chain.poll(provider).await?;
+                // The logs of the blocks removed by a reorg are returned first, flagged as removed
+                let mut logs = provider.removed_logs((**logs_filter).clone(), update.removed).await?;
+
+                if let Some((from, to)) = update.added {
+                    // Only return the logs in the range requested by the filter
+                    let from = logs_filter.get_from_block().map_or(from, |start| start.max(from));
+                    let to = logs_filter.get_to_block().map_or(to, |end| end.min(to));
+                    if from <= to {
+                        if let FilterChanges::Logs(added) =
+                            provider.get_logs((**logs_filter).clone().from_block(from).to_block(to)).await?
+                        {
+                            logs.extend(added);
+                        }
+                    }
+                }
+                chain.advance(&update);
+                FilterChanges::Logs(logs)
+            }
+            FilterKind::Blocks => {
+                let update = chain.poll(provider).await?;
+                chain.advance(&update);
+                FilterChanges::Hashes(update.headers.into_iter().filter_map(|header| header.hash).collect())
+            }
+            FilterKind::PendingTransactions(seen) => {
+                let pending = provider.pending_transactions().await?;
+                // Forget about the transactions which left the pending pool
+                seen.retain(|hash| pending.iter().any(|tx| tx.hash == *hash));
+                FilterChanges::Hashes(pending.into_iter().map(|tx| tx.hash).filter(|hash| seen.insert(*hash)).collect())
+            }
+        };
+
+        Ok(changes)
+    }
+
+    /// Returns all the logs matching a logs filter.
+    pub async fn filter_logs<P: EthereumProvider>(&self, provider: &P, id: U64) -> EthProviderResult<FilterChanges> {
+        let filter = self.get(id)?;
+        let mut filter = filter.lock().await;
+        filter.last_poll = Instant::now();
+
+        match &filter.kind {
+            FilterKind::Logs(logs_filter) => provider.get_logs((**logs_filter).clone()).await,
+            _ => Err(EthApiError::FilterNotFound(id)),
+        }
+    }
+
+    /// Inserts a new filter, starting at the current block.
+    async fn install<P: EthereumProvider>(&self, provider: &P, kind: FilterKind) -> EthProviderResult<U64> {
+        let chain = ChainTracker::new(provider.block_number().await?.to::<u64>());
+        let id = U64::from(self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let filter = ActiveFilter { kind, chain, last_poll: Instant::now() };
+
+        let mut filters = self.filters.lock().expect("Failed to lock filters");
+        Self::remove_expired(&mut filters);
+        filters.insert(id, Arc::new(tokio::sync::Mutex::new(filter)));
+
+        Ok(id)
+    }
+
+    /// Returns the filter for the id.
+    fn get(&self, id: U64) -> EthProviderResult<Arc<tokio::sync::Mutex<ActiveFilter>>> {
+        let mut filters = self.filters.lock().expect("Failed to lock filters");
+        Self::remove_expired(&mut filters);
+        filters.get(&id).cloned().ok_or(EthApiError::FilterNotFound(id))
+    }
+
+    /// Removes the filters which weren't polled during [`FILTER_TIMEOUT`].
+    /// Filters currently being polled are kept.
+    fn remove_expired(filters: &mut HashMap<U64, Arc<tokio::sync::Mutex<ActiveFilter>>>) {
+        filters
+            .retain(|_, filter| filter.try_lock().map_or(true, |filter| filter.last_poll.elapsed() < FILTER_TIMEOUT));
+    }
+}
+
+/// The changes of the chain since the last poll of a [`ChainTracker`].
+#[derive(Debug)]
+pub(crate) struct ChainUpdate {
+    /// Hashes of the blocks returned to the caller which were removed from the chain by a reorg,
+    /// from the most recent one.
+    pub(crate) removed: Vec<B256>,
+    /// Inclusive range of the blocks added to the chain, if any.
+    pub(crate) added: Option<(u64, u64)>,
+    /// Headers of the blocks added to the chain, in order.
+    pub(crate) headers: Vec<Header>,
+    /// Last block returned to the caller once the update is applied.
+    last_block: u64,
+}
+
+/// Tracks the blocks returned to a caller, in order to detect the ones which were removed from
+/// the chain by a reorg. Only the last [`REORG_TRACKING_DEPTH`] blocks are tracked.
+#[derive(Debug)]
+pub(crate) struct ChainTracker {
+    /// Last block returned to the caller.
+    last_block: u64,
+    /// Numbers and hashes of the last blocks returned to the caller.
+    blocks: VecDeque<(u64, B256)>,
+}
+
+impl ChainTracker {
+    /// Creates a tracker starting after the given block.
+    pub(crate) const fn new(last_block: u64) -> Self {
+        Self { last_block, blocks: VecDeque::new() }
+    }
+
+    /// Returns the blocks removed from and added to the chain since the last poll. The tracker is
+    /// left untouched until the update is applied with [`ChainTracker::advance`], so that a poll
+    /// failing halfway, or whose update couldn't be delivered, is retried from the same block.
+    pub(crate) async fn poll<P: EthereumProvider>(&self, provider: &P) -> EthProviderResult<ChainUpdate> {
+        // Walk back the tracked blocks until one which is still part of the chain
+        let mut removed = Vec::new();
+        let mut last_block = self.last_block;
+        for &(number, hash) in self.blocks.iter().rev() {
+            let header = provider.header(&BlockId::Number(BlockNumberOrTag::Number(number))).await?;
+            if header.and_then(|header| header.hash) == Some(hash) {
+                break;
+            }
+            removed.push(hash);
+            last_block = number.saturating_sub(1);
+        }
+
+        let latest = provider.block_number().await?.to::<u64>();
+        let mut headers = Vec::new();
+        for number in last_block + 1..=latest {
+            // Stop at the first missing header, the next poll resuming from its block
+            let Some(header) = provider.header(&BlockId::Number(BlockNumberOrTag::Number(number))).await? else {
+                break;
+            };
+            headers.push(header);
+        }
+
+        let added = (!headers.is_empty()).then(|| (last_block + 1, last_block + headers.len() as u64));
+        Ok(ChainUpdate { removed, added, headers, last_block: added.map_or(last_block, |(_, to)| to) })
+    }
+
+    /// Applies an update returned by [`ChainTracker::poll`], once delivered to the caller.
+    pub(crate) fn advance(&mut self, update: &ChainUpdate) {
+        self.blocks.truncate(self.blocks.len().saturating_sub(update.removed.len()));
+        if let Some((from, _)) = update.added {
+            for (number, header) in (from..).zip(&update.headers) {
+                if let Some(hash) = header.hash {
+                    self.blocks.push_back((number, hash));
+                }
+            }
+        }
+        while self.blocks.len() > REORG_TRACKING_DEPTH {
+            self.blocks.pop_front();
+        }
+        self.last_block = update.last_block;
+    }
+}
@@ -0,0 +1,148 @@
+//! GraphQL endpoint implementing the Ethereum GraphQL schema of EIP-1767, backed by the same
+//! provider as the JSON-RPC methods. Only the queries are served: the transactions are sent
+//! with `eth_sendRawTransaction`, so that they're subject to the authentication of the RPC.
+pub mod scalars;
+pub mod types;
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use async_graphql::{BatchRequest, EmptyMutation, EmptySubscription, Schema};
+use futures::future::{BoxFuture, Either};
+
+use crate::eth_provider::provider::EthereumProvider;
+use types::Query;
+
+/// Path on which the GraphQL queries are served.
+pub const GRAPHQL_PATH: &str = "/graphql";
+
+/// Maximum number of blocks of a `blocks` query.
+pub const MAX_BLOCK_RANGE: u64 = 100;
+
+/// Maximum depth of the queries, which bounds the nested fetches, e.g. of the parent blocks.
+const MAX_QUERY_DEPTH: usize = 16;
+
+/// Maximum complexity of the queries, i.e. their number of fields.
+const MAX_QUERY_COMPLEXITY: usize = 1024;
+
+/// Maximum size of the body of a query, in bytes.
+const MAX_REQUEST_BODY_SIZE: u64 = 1024 * 1024;
+
+/// Ethereum provider backing the schema.
+pub type GraphQlProvider = Arc<dyn EthereumProvider + Send + Sync>;
+
+/// Schema of the GraphQL endpoint.
+pub type GraphQlSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Returns the schema of the GraphQL endpoint backed by the provider.
+pub fn graphql_schema<P: EthereumProvider + Send + Sync + 'static>(eth_provider: P) -> GraphQlSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data::<GraphQlProvider>(Arc::new(eth_provider))
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+/// HTTP middleware layer serving the GraphQL queries posted to `/graphql`. The other requests are
+/// forwarded to the JSON-RPC server.
+#[derive(Clone)]
+pub struct GraphQlLayer {
+    schema: GraphQlSchema,
+}
+
+impl std::fmt::Debug for GraphQlLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("GraphQlLayer")
+    }
+}
+
+impl GraphQlLayer {
+    pub const fn new(schema: GraphQlSchema) -> Self {
+        Self { schema }
+    }
+}
+
+impl<S> tower::Layer<S> for GraphQlLayer {
+    type Service = GraphQl<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        GraphQl { service, schema: self.schema.clone() }
+    }
+}
+
+#[derive(Clone)]
+pub struct GraphQl<S> {
+    service: S,
+    schema: GraphQlSchema,
+}
+
+impl<S> std::fmt::Debug for GraphQl<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("GraphQl")
+    }
+}
+
+impl<S> tower::Service<http::Request<hyper_014::Body>> for GraphQl<S>
+where
+    S: tower::Service<http::Request<hyper_014::Body>, Response = http::Response<hyper_014::Body>>,
+    S::Error: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<BoxFuture<'static, Result<S::Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<hyper_014::Body>) -> Self::Future {
+        if req.uri().path() != GRAPHQL_PATH {
+            return Either::Right(self.service.call(req));
+        }
+        let schema = self.schema.clone();
+        Either::Left(Box::pin(async move { Ok(graphql_response(&schema, req).await) }))
+    }
+}
+
+/// Executes the query, or the batch of queries, of the request.
+async fn graphql_response(
+    schema: &GraphQlSchema,
+    req: http::Request<hyper_014::Body>,
+) -> http::Response<hyper_014::Body> {
+    if req.method() != http::Method::POST {
+        return error_response(http::StatusCode::METHOD_NOT_ALLOWED, "queries must be posted");
+    }
+    let content_length = req
+        .headers()
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if !content_length.is_some_and(|length| length <= MAX_REQUEST_BODY_SIZE) {
+        return error_response(http::StatusCode::PAYLOAD_TOO_LARGE, "missing or too large content length");
+    }
+
+    let body = match hyper_014::body::to_bytes(req.into_body()).await {
+        Ok(body) => body,
+        Err(err) => return error_response(http::StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let request = match serde_json::from_slice::<BatchRequest>(&body) {
+        Ok(request) => request,
+        Err(err) => return error_response(http::StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let response = schema.execute_batch(request).await;
+    json_response(http::StatusCode::OK, &serde_json::to_string(&response).unwrap_or_default())
+}
+
+/// Returns a GraphQL error response.
+fn error_response(status: http::StatusCode, message: &str) -> http::Response<hyper_014::Body> {
+    json_response(status, &serde_json::json!({ "errors": [{ "message": message }] }).to_string())
+}
+
+fn json_response(status: http::StatusCode, body: &str) -> http::Response<hyper_014::Body> {
+    http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(hyper_014::Body::from(body.to_string()))
+        .expect("Failed to build GraphQL response")
+}
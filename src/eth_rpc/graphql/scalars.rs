@@ -0,0 +1,143 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+use reth_primitives::U256;
+
+/// Parses a string value.
+fn parse_str<T>(value: &Value) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    match value {
+        Value::String(string) => string.parse().map_err(|err: T::Err| err.to_string()),
+        _ => Err(format!("expected a string, got {value}")),
+    }
+}
+
+/// 32 bytes, encoded as a 0x prefixed hex string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bytes32(pub reth_primitives::B256);
+
+#[Scalar(name = "Bytes32")]
+impl ScalarType for Bytes32 {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        parse_str(&value).map(Self).map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+/// Ethereum address, encoded as a 0x prefixed hex string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address(pub reth_primitives::Address);
+
+#[Scalar(name = "Address")]
+impl ScalarType for Address {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        parse_str(&value).map(Self).map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(format!("{:#x}", self.0))
+    }
+}
+
+/// Arbitrary length binary data, encoded as a 0x prefixed hex string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bytes(pub reth_primitives::Bytes);
+
+#[Scalar(name = "Bytes")]
+impl ScalarType for Bytes {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        parse_str(&value).map(Self).map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+/// Large integer, encoded as a 0x prefixed hex string. Decimal strings are accepted as input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BigInt(pub U256);
+
+impl From<u128> for BigInt {
+    fn from(value: u128) -> Self {
+        Self(U256::from(value))
+    }
+}
+
+#[Scalar(name = "BigInt")]
+impl ScalarType for BigInt {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        parse_str(&value).map(Self).map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(format!("0x{:x}", self.0))
+    }
+}
+
+/// 64 bits integer, encoded as a number. Hex and decimal strings are accepted as input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Long(pub u64);
+
+impl Long {
+    /// Returns the value as a Long, saturating at `u64::MAX`.
+    pub fn saturating(value: u128) -> Self {
+        Self(u64::try_from(value).unwrap_or(u64::MAX))
+    }
+}
+
+#[Scalar(name = "Long")]
+impl ScalarType for Long {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        let long = match &value {
+            Value::Number(number) => number.as_u64().ok_or_else(|| format!("expected a 64 bits integer, got {number}")),
+            Value::String(string) => match string.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16).map_err(|err| err.to_string()),
+                None => string.parse().map_err(|err: std::num::ParseIntError| err.to_string()),
+            },
+            _ => Err(format!("expected a number or a string, got {value}")),
+        };
+        long.map(Self).map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Number(self.0.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long() {
+        assert_eq!(Long::parse(Value::Number(16.into())).unwrap(), Long(16));
+        assert_eq!(Long::parse(Value::String("0x10".to_string())).unwrap(), Long(16));
+        assert_eq!(Long::parse(Value::String("16".to_string())).unwrap(), Long(16));
+        assert!(Long::parse(Value::Boolean(true)).is_err());
+        assert_eq!(Long::saturating(u128::MAX), Long(u64::MAX));
+        assert_eq!(Long(16).to_value(), Value::Number(16.into()));
+    }
+
+    #[test]
+    fn test_big_int() {
+        assert_eq!(BigInt::parse(Value::String("0x10".to_string())).unwrap(), BigInt(U256::from(16)));
+        assert_eq!(BigInt::parse(Value::String("16".to_string())).unwrap(), BigInt(U256::from(16)));
+        assert_eq!(BigInt(U256::from(16)).to_value(), Value::String("0x10".to_string()));
+    }
+
+    #[test]
+    fn test_bytes32() {
+        let hash = format!("0x{}", "ab".repeat(32));
+        let parsed = Bytes32::parse(Value::String(hash.clone())).unwrap();
+        assert_eq!(parsed.to_value(), Value::String(hash));
+        assert!(Bytes32::parse(Value::String("0x12".to_string())).is_err());
+    }
+}
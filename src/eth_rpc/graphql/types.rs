@@ -0,0 +1,497 @@
+use std::sync::Arc;
+
+use async_graphql::{Context, InputObject, Object, Result};
+use reth_primitives::{BlockId, BlockNumberOrTag};
+use reth_rpc_types::serde_helpers::JsonStorageKey;
+use reth_rpc_types::{BlockTransactions, FilterChanges, TransactionReceipt};
+use serde_json::json;
+use tokio::sync::OnceCell;
+
+use super::scalars::{Address, BigInt, Bytes, Bytes32, Long};
+use super::{GraphQlProvider, MAX_BLOCK_RANGE};
+
+/// Returns the Ethereum provider of the schema.
+fn provider<'a>(ctx: &Context<'a>) -> &'a GraphQlProvider {
+    ctx.data_unchecked::<GraphQlProvider>()
+}
+
+/// Returns the logs matching the filter.
+async fn logs(ctx: &Context<'_>, filter: serde_json::Value) -> Result<Vec<Log>> {
+    let filter = serde_json::from_value(filter)?;
+    Ok(match provider(ctx).get_logs(filter).await? {
+        FilterChanges::Logs(logs) => logs.into_iter().map(Log::from).collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// Filter of the logs of a range of blocks.
+#[derive(Debug, InputObject)]
+pub struct FilterCriteria {
+    /// First block of the range, the latest block if unset.
+    pub from_block: Option<Long>,
+    /// Last block of the range, the latest block if unset.
+    pub to_block: Option<Long>,
+    /// Addresses of the contracts which emitted the logs, any contract if empty.
+    pub addresses: Option<Vec<Address>>,
+    /// Alternatives of each topic, any topic at a position if empty.
+    pub topics: Option<Vec<Vec<Bytes32>>>,
+}
+
+/// Filter of the logs of a block.
+#[derive(Debug, InputObject)]
+pub struct BlockFilterCriteria {
+    /// Addresses of the contracts which emitted the logs, any contract if empty.
+    pub addresses: Option<Vec<Address>>,
+    /// Alternatives of each topic, any topic at a position if empty.
+    pub topics: Option<Vec<Vec<Bytes32>>>,
+}
+
+/// Returns the addresses and topics of the criteria in the format of the JSON-RPC filters.
+fn filter_fields(addresses: Option<Vec<Address>>, topics: Option<Vec<Vec<Bytes32>>>) -> serde_json::Value {
+    let addresses = addresses.unwrap_or_default().into_iter().map(|address| address.0).collect::<Vec<_>>();
+    let topics = topics
+        .unwrap_or_default()
+        .into_iter()
+        .map(|topics| match topics.len() {
+            0 => serde_json::Value::Null,
+            _ => json!(topics.into_iter().map(|topic| topic.0).collect::<Vec<_>>()),
+        })
+        .collect::<Vec<_>>();
+    json!({ "address": addresses, "topics": topics })
+}
+
+/// Root of the queries.
+#[derive(Debug, Default)]
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Block of the given number or hash, the latest block if neither is set.
+    async fn block(&self, ctx: &Context<'_>, number: Option<Long>, hash: Option<Bytes32>) -> Result<Option<Block>> {
+        let block = match (number, hash) {
+            (Some(_), Some(_)) => return Err("only one of number or hash must be specified".into()),
+            (_, Some(hash)) => provider(ctx).block_by_hash(hash.0, true).await?,
+            (number, None) => {
+                let number = number.map_or(BlockNumberOrTag::Latest, |number| BlockNumberOrTag::Number(number.0));
+                provider(ctx).block_by_number(number, true).await?
+            }
+        };
+        Ok(block.map(|block| Block::from(block.inner)))
+    }
+
+    /// Blocks of the range, up to the latest block if `to` is unset.
+    async fn blocks(&self, ctx: &Context<'_>, from: Long, to: Option<Long>) -> Result<Vec<Block>> {
+        let to = match to {
+            Some(to) => to.0,
+            None => provider(ctx).block_number().await?.to(),
+        };
+        if to.saturating_sub(from.0) >= MAX_BLOCK_RANGE {
+            return Err(format!("block range exceeds {MAX_BLOCK_RANGE} blocks").into());
+        }
+
+        let mut blocks = Vec::new();
+        for number in from.0..=to {
+            match provider(ctx).block_by_number(BlockNumberOrTag::Number(number), true).await? {
+                Some(block) => blocks.push(Block::from(block.inner)),
+                None => break,
+            }
+        }
+        Ok(blocks)
+    }
+
+    /// Transaction of the given hash.
+    async fn transaction(&self, ctx: &Context<'_>, hash: Bytes32) -> Result<Option<Transaction>> {
+        Ok(provider(ctx).transaction_by_hash(hash.0).await?.map(Transaction::from))
+    }
+
+    /// Logs matching the filter.
+    async fn logs(&self, ctx: &Context<'_>, filter: FilterCriteria) -> Result<Vec<Log>> {
+        let block =
+            |number: Option<Long>| number.map_or_else(|| json!("latest"), |number| json!(format!("0x{:x}", number.0)));
+        let mut fields = filter_fields(filter.addresses, filter.topics);
+        fields["fromBlock"] = block(filter.from_block);
+        fields["toBlock"] = block(filter.to_block);
+        logs(ctx, fields).await
+    }
+
+    /// Suggested gas price.
+    async fn gas_price(&self, ctx: &Context<'_>) -> Result<BigInt> {
+        Ok(BigInt(provider(ctx).gas_price().await?))
+    }
+
+    /// Suggested priority fee per gas.
+    async fn max_priority_fee_per_gas(&self, ctx: &Context<'_>) -> Result<BigInt> {
+        Ok(BigInt(provider(ctx).max_priority_fee_per_gas().await?))
+    }
+
+    /// Chain id used to sign the transactions.
+    #[graphql(name = "chainID")]
+    async fn chain_id(&self, ctx: &Context<'_>) -> Result<BigInt> {
+        let chain_id = provider(ctx).chain_id().await?.unwrap_or_default();
+        Ok(BigInt(chain_id.to()))
+    }
+}
+
+/// Account at a block.
+#[derive(Debug)]
+pub struct Account {
+    address: reth_primitives::Address,
+    /// Block at which the state of the account is read, the latest block if None.
+    block_id: Option<BlockId>,
+}
+
+impl Account {
+    fn new(address: reth_primitives::Address, block: Option<Long>) -> Self {
+        Self { address, block_id: block.map(|block| BlockId::Number(BlockNumberOrTag::Number(block.0))) }
+    }
+}
+
+#[Object]
+impl Account {
+    async fn address(&self) -> Address {
+        Address(self.address)
+    }
+
+    async fn balance(&self, ctx: &Context<'_>) -> Result<BigInt> {
+        Ok(BigInt(provider(ctx).balance(self.address, self.block_id).await?))
+    }
+
+    async fn transaction_count(&self, ctx: &Context<'_>) -> Result<Long> {
+        Ok(Long(provider(ctx).transaction_count(self.address, self.block_id).await?.to()))
+    }
+
+    async fn code(&self, ctx: &Context<'_>) -> Result<Bytes> {
+        Ok(Bytes(provider(ctx).get_code(self.address, self.block_id).await?))
+    }
+
+    /// Value of the storage slot.
+    async fn storage(&self, ctx: &Context<'_>, slot: Bytes32) -> Result<Bytes32> {
+        Ok(Bytes32(provider(ctx).storage_at(self.address, JsonStorageKey(slot.0), self.block_id).await?))
+    }
+}
+
+/// Block, along with its full transactions.
+#[derive(Debug)]
+pub struct Block {
+    block: reth_rpc_types::Block,
+}
+
+impl From<reth_rpc_types::Block> for Block {
+    fn from(block: reth_rpc_types::Block) -> Self {
+        Self { block }
+    }
+}
+
+impl Block {
+    fn block_number(&self) -> u64 {
+        self.block.header.number.unwrap_or_default()
+    }
+
+    fn full_transactions(&self) -> &[reth_rpc_types::Transaction] {
+        match &self.block.transactions {
+            BlockTransactions::Full(transactions) => transactions,
+            _ => &[],
+        }
+    }
+}
+
+#[Object]
+impl Block {
+    async fn number(&self) -> Long {
+        Long(self.block_number())
+    }
+
+    async fn hash(&self) -> Bytes32 {
+        Bytes32(self.block.header.hash.unwrap_or_default())
+    }
+
+    /// Parent block, None for the genesis block.
+    async fn parent(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        if self.block_number() == 0 {
+            return Ok(None);
+        }
+        let parent = provider(ctx).block_by_hash(self.block.header.parent_hash, true).await?;
+        Ok(parent.map(|block| Block::from(block.inner)))
+    }
+
+    async fn nonce(&self) -> Bytes {
+        Bytes(self.block.header.nonce.unwrap_or_default().0.to_vec().into())
+    }
+
+    async fn transactions_root(&self) -> Bytes32 {
+        Bytes32(self.block.header.transactions_root)
+    }
+
+    async fn state_root(&self) -> Bytes32 {
+        Bytes32(self.block.header.state_root)
+    }
+
+    async fn receipts_root(&self) -> Bytes32 {
+        Bytes32(self.block.header.receipts_root)
+    }
+
+    /// Account which received the fees of the block, at the given block or at this block.
+    async fn miner(&self, block: Option<Long>) -> Account {
+        Account::new(self.block.header.miner, Some(block.unwrap_or(Long(self.block_number()))))
+    }
+
+    async fn extra_data(&self) -> Bytes {
+        Bytes(self.block.header.extra_data.clone())
+    }
+
+    async fn gas_limit(&self) -> Long {
+        Long::saturating(self.block.header.gas_limit)
+    }
+
+    async fn gas_used(&self) -> Long {
+        Long::saturating(self.block.header.gas_used)
+    }
+
+    async fn base_fee_per_gas(&self) -> Option<BigInt> {
+        self.block.header.base_fee_per_gas.map(BigInt::from)
+    }
+
+    async fn timestamp(&self) -> Long {
+        Long(self.block.header.timestamp)
+    }
+
+    async fn logs_bloom(&self) -> Bytes {
+        Bytes(self.block.header.logs_bloom.0.to_vec().into())
+    }
+
+    async fn mix_hash(&self) -> Bytes32 {
+        Bytes32(self.block.header.mix_hash.unwrap_or_default())
+    }
+
+    async fn difficulty(&self) -> BigInt {
+        BigInt(self.block.header.difficulty)
+    }
+
+    async fn total_difficulty(&self) -> BigInt {
+        BigInt(self.block.header.total_difficulty.unwrap_or_default())
+    }
+
+    async fn ommer_count(&self) -> Long {
+        Long(self.block.uncles.len() as u64)
+    }
+
+    async fn ommer_hash(&self) -> Bytes32 {
+        Bytes32(self.block.header.uncles_hash)
+    }
+
+    async fn transaction_count(&self) -> Long {
+        Long(self.full_transactions().len() as u64)
+    }
+
+    async fn transactions(&self) -> Vec<Transaction> {
+        self.full_transactions().iter().cloned().map(Transaction::from).collect()
+    }
+
+    async fn transaction_at(&self, index: Long) -> Option<Transaction> {
+        let index = usize::try_from(index.0).ok()?;
+        self.full_transactions().get(index).cloned().map(Transaction::from)
+    }
+
+    /// Logs of the block matching the filter.
+    async fn logs(&self, ctx: &Context<'_>, filter: BlockFilterCriteria) -> Result<Vec<Log>> {
+        let mut fields = filter_fields(filter.addresses, filter.topics);
+        fields["blockHash"] = json!(self.block.header.hash.unwrap_or_default());
+        logs(ctx, fields).await
+    }
+
+    /// Account at this block.
+    async fn account(&self, address: Address) -> Account {
+        Account::new(address.0, Some(Long(self.block_number())))
+    }
+}
+
+/// Transaction, whose receipt is fetched once by the fields which need it.
+#[derive(Debug)]
+pub struct Transaction {
+    transaction: reth_rpc_types::Transaction,
+    receipt: Arc<OnceCell<Option<TransactionReceipt>>>,
+}
+
+impl From<reth_rpc_types::Transaction> for Transaction {
+    fn from(transaction: reth_rpc_types::Transaction) -> Self {
+        Self { transaction, receipt: Arc::default() }
+    }
+}
+
+impl Transaction {
+    /// Returns the receipt of the transaction, None if the transaction is pending.
+    async fn receipt(&self, ctx: &Context<'_>) -> Result<Option<&TransactionReceipt>> {
+        let receipt = self
+            .receipt
+            .get_or_try_init(|| async { provider(ctx).transaction_receipt(self.transaction.hash).await })
+            .await?;
+        Ok(receipt.as_ref())
+    }
+
+    /// Returns the given block or the block of the transaction, if it was included.
+    fn block_or_inclusion(&self, block: Option<Long>) -> Option<Long> {
+        block.or(self.transaction.block_number.map(Long))
+    }
+}
+
+#[Object]
+impl Transaction {
+    async fn hash(&self) -> Bytes32 {
+        Bytes32(self.transaction.hash)
+    }
+
+    async fn nonce(&self) -> Long {
+        Long(self.transaction.nonce)
+    }
+
+    /// Index of the transaction in its block, None if the transaction is pending.
+    async fn index(&self) -> Option<Long> {
+        self.transaction.transaction_index.map(Long)
+    }
+
+    #[graphql(name = "from")]
+    async fn sender(&self, block: Option<Long>) -> Account {
+        Account::new(self.transaction.from, self.block_or_inclusion(block))
+    }
+
+    /// Recipient of the transaction, None for a contract creation.
+    async fn to(&self, block: Option<Long>) -> Option<Account> {
+        self.transaction.to.map(|to| Account::new(to, self.block_or_inclusion(block)))
+    }
+
+    async fn value(&self) -> BigInt {
+        BigInt(self.transaction.value)
+    }
+
+    async fn gas_price(&self) -> BigInt {
+        BigInt::from(self.transaction.gas_price.unwrap_or_default())
+    }
+
+    async fn max_fee_per_gas(&self) -> Option<BigInt> {
+        self.transaction.max_fee_per_gas.map(BigInt::from)
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> Option<BigInt> {
+        self.transaction.max_priority_fee_per_gas.map(BigInt::from)
+    }
+
+    async fn gas(&self) -> Long {
+        Long::saturating(self.transaction.gas)
+    }
+
+    async fn input_data(&self) -> Bytes {
+        Bytes(self.transaction.input.clone())
+    }
+
+    /// Block of the transaction, None if the transaction is pending.
+    async fn block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        let Some(hash) = self.transaction.block_hash else { return Ok(None) };
+        Ok(provider(ctx).block_by_hash(hash, true).await?.map(|block| Block::from(block.inner)))
+    }
+
+    /// Status of the transaction, 1 if it succeeded and 0 if it reverted.
+    async fn status(&self, ctx: &Context<'_>) -> Result<Option<Long>> {
+        Ok(self.receipt(ctx).await?.map(|receipt| Long(receipt.inner.status().into())))
+    }
+
+    async fn gas_used(&self, ctx: &Context<'_>) -> Result<Option<Long>> {
+        Ok(self.receipt(ctx).await?.map(|receipt| Long::saturating(receipt.gas_used)))
+    }
+
+    async fn cumulative_gas_used(&self, ctx: &Context<'_>) -> Result<Option<Long>> {
+        Ok(self.receipt(ctx).await?.map(|receipt| Long::saturating(receipt.inner.cumulative_gas_used())))
+    }
+
+    async fn effective_gas_price(&self, ctx: &Context<'_>) -> Result<Option<BigInt>> {
+        Ok(self.receipt(ctx).await?.map(|receipt| BigInt::from(receipt.effective_gas_price)))
+    }
+
+    /// Contract created by the transaction, if any.
+    async fn created_contract(&self, ctx: &Context<'_>, block: Option<Long>) -> Result<Option<Account>> {
+        let block = self.block_or_inclusion(block);
+        let receipt = self.receipt(ctx).await?;
+        Ok(receipt.and_then(|receipt| receipt.contract_address).map(|address| Account::new(address, block)))
+    }
+
+    /// Logs emitted by the transaction, None if the transaction is pending.
+    async fn logs(&self, ctx: &Context<'_>) -> Result<Option<Vec<Log>>> {
+        let receipt = self.receipt(ctx).await?;
+        Ok(receipt.map(|receipt| receipt.inner.logs().iter().cloned().map(Log::from).collect()))
+    }
+
+    async fn r(&self) -> BigInt {
+        BigInt(self.transaction.signature.as_ref().map(|signature| signature.r).unwrap_or_default())
+    }
+
+    async fn s(&self) -> BigInt {
+        BigInt(self.transaction.signature.as_ref().map(|signature| signature.s).unwrap_or_default())
+    }
+
+    async fn v(&self) -> BigInt {
+        BigInt(self.transaction.signature.as_ref().map(|signature| signature.v).unwrap_or_default())
+    }
+
+    #[graphql(name = "type")]
+    async fn transaction_type(&self) -> Option<Long> {
+        self.transaction.transaction_type.map(|transaction_type| Long(transaction_type.into()))
+    }
+}
+
+/// Log emitted by a transaction.
+#[derive(Debug)]
+pub struct Log {
+    log: reth_rpc_types::Log,
+}
+
+impl From<reth_rpc_types::Log> for Log {
+    fn from(log: reth_rpc_types::Log) -> Self {
+        Self { log }
+    }
+}
+
+#[Object]
+impl Log {
+    /// Index of the log in its block.
+    async fn index(&self) -> Long {
+        Long(self.log.log_index.unwrap_or_default())
+    }
+
+    /// Contract which emitted the log, at the given block or at the block of the log.
+    async fn account(&self, block: Option<Long>) -> Account {
+        Account::new(self.log.inner.address, block.or(self.log.block_number.map(Long)))
+    }
+
+    async fn topics(&self) -> Vec<Bytes32> {
+        self.log.inner.data.topics().iter().copied().map(Bytes32).collect()
+    }
+
+    async fn data(&self) -> Bytes {
+        Bytes(self.log.inner.data.data.clone())
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>) -> Result<Option<Transaction>> {
+        let Some(hash) = self.log.transaction_hash else { return Ok(None) };
+        Ok(provider(ctx).transaction_by_hash(hash).await?.map(Transaction::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+
+    #[test]
+    fn test_filter_fields() {
+        // Given
+        let address = Address(reth_primitives::Address::with_last_byte(1));
+        let topic = Bytes32(B256::with_last_byte(2));
+
+        // When
+        let fields = filter_fields(Some(vec![address]), Some(vec![vec![], vec![topic]]));
+        let filter = serde_json::from_value::<reth_rpc_types::Filter>(fields.clone());
+
+        // Then
+        assert_eq!(fields, json!({ "address": [address.0], "topics": [null, [topic.0]] }));
+        assert!(filter.is_ok());
+    }
+}
@@ -0,0 +1,306 @@
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+
+use jsonrpsee::server::{stop_channel, ServerHandle, StopHandle};
+use jsonrpsee::types::{ErrorCode, ErrorObject, ErrorObjectOwned};
+use jsonrpsee::{Methods, RpcModule};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc;
+
+use super::middleware::namespaces::DisabledNamespaces;
+use super::RpcError;
+use crate::eth_provider::error::EthApiError;
+
+/// Maximum size of the requests buffered by a connection, beyond which the connection is closed.
+const MAX_BUFFERED_REQUESTS_SIZE: usize = 10 * 1024 * 1024;
+
+/// Capacity of the queue of the messages written to a connection, and of the buffer of the
+/// notifications of each subscription.
+const MESSAGE_BUFFER_SIZE: usize = 1024;
+
+/// Returns the path of the IPC socket given by the `--ipcpath` command line flag, as
+/// `--ipcpath path` or `--ipcpath=path`, or by the `RPC_IPC_PATH` environment variable.
+///
+/// # Errors
+///
+/// Will return `Err` if the `--ipcpath` flag is missing its value.
+pub fn ipc_path(args: impl IntoIterator<Item = String>) -> eyre::Result<Option<PathBuf>> {
+    let mut args = args.into_iter();
+    let mut path = std::env::var("RPC_IPC_PATH").ok().filter(|path| !path.trim().is_empty());
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix("--ipcpath=") {
+            path = Some(value.to_string());
+        } else if arg == "--ipcpath" {
+            path = Some(args.next().ok_or_else(|| eyre::eyre!("Missing value for --ipcpath"))?);
+        }
+    }
+    Ok(path.map(|path| PathBuf::from(path.trim())))
+}
+
+/// Serves the methods of the module over the Unix domain socket at the given path, as the IPC
+/// transport of geth. The requests and the responses are JSON values written back to back on
+/// the socket. Like geth, the local clients which can open the socket aren't authenticated, so
+/// that the module is expected to hold the protected methods as well. The calls to the disabled
+/// namespaces are rejected.
+///
+/// # Errors
+///
+/// Will return `Err` if the socket can't be bound, e.g. because its directory doesn't exist.
+pub async fn run_ipc_server(
+    kakarot_rpc_module: RpcModule<()>,
+    path: impl AsRef<Path>,
+    disabled_namespaces: DisabledNamespaces,
+) -> Result<ServerHandle, RpcError> {
+    let path = path.as_ref().to_path_buf();
+    // The socket of a previous run is left behind if it wasn't stopped gracefully
+    if std::fs::metadata(&path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        std::fs::remove_file(&path)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    let methods = Methods::from(kakarot_rpc_module);
+    let (stop_handle, handle) = stop_channel();
+
+    tokio::spawn(async move {
+        loop {
+            let stream = tokio::select! {
+                conn = listener.accept() => match conn {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        tracing::warn!(%err, "Failed to accept IPC connection");
+                        continue;
+                    }
+                },
+                () = stop_handle.clone().shutdown() => break,
+            };
+            tokio::spawn(serve_connection(stream, methods.clone(), disabled_namespaces.clone(), stop_handle.clone()));
+        }
+        let _ = std::fs::remove_file(&path);
+    });
+
+    Ok(handle)
+}
+
+/// Serves the requests of the connection until it's closed or the server is stopped.
+async fn serve_connection(
+    stream: UnixStream,
+    methods: Methods,
+    disabled_namespaces: DisabledNamespaces,
+    stop_handle: StopHandle,
+) {
+    let (mut reader, mut writer) = stream.into_split();
+    let (sender, mut receiver) = mpsc::channel::<String>(MESSAGE_BUFFER_SIZE);
+    let write_task = tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            if writer.write_all(message.as_bytes()).await.is_err() || writer.write_all(b"\n").await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut buffer = Vec::new();
+    let mut chunk = vec![0; 8192];
+    loop {
+        let read = tokio::select! {
+            read = reader.read(&mut chunk) => read,
+            () = stop_handle.clone().shutdown() => break,
+        };
+        match read {
+            Ok(0) | Err(_) => break,
+            Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+        }
+
+        let requests = match split_values(&buffer) {
+            Ok((requests, consumed)) => {
+                buffer.drain(..consumed);
+                requests
+            }
+            Err(_) => {
+                // The stream can't be split into requests past invalid JSON
+                let _ = sender.send(error_response(Value::Null, ErrorCode::ParseError.into())).await;
+                break;
+            }
+        };
+        if buffer.len() > MAX_BUFFERED_REQUESTS_SIZE {
+            let _ = sender.send(error_response(Value::Null, ErrorCode::OversizedRequest.into())).await;
+            break;
+        }
+
+        for request in requests {
+            let (methods, disabled_namespaces, sender, stop_handle) =
+                (methods.clone(), disabled_namespaces.clone(), sender.clone(), stop_handle.clone());
+            tokio::spawn(async move {
+                handle_request(&methods, &disabled_namespaces, request, &sender).await;
+                // The in-flight requests are drained before the server is considered stopped
+                drop(stop_handle);
+            });
+        }
+    }
+
+    // The pending messages are written before the connection is closed
+    drop(sender);
+    let _ = write_task.await;
+}
+
+/// Splits the complete JSON values at the start of the buffer. Returns the values, along with the
+/// number of bytes they span, or an error if the buffer doesn't start with JSON values.
+fn split_values(buffer: &[u8]) -> Result<(Vec<Value>, usize), serde_json::Error> {
+    let mut values = Vec::new();
+    let mut stream = serde_json::Deserializer::from_slice(buffer).into_iter::<Value>();
+    let mut consumed = 0;
+    loop {
+        match stream.next() {
+            Some(Ok(value)) => {
+                values.push(value);
+                consumed = stream.byte_offset();
+            }
+            // The last value is incomplete
+            Some(Err(err)) if err.is_eof() => break,
+            Some(Err(err)) => return Err(err),
+            None => {
+                consumed = stream.byte_offset();
+                break;
+            }
+        }
+    }
+    Ok((values, consumed))
+}
+
+/// Answers the request, or the batch of requests, then forwards the notifications of the
+/// subscriptions it opened.
+async fn handle_request(
+    methods: &Methods,
+    disabled_namespaces: &DisabledNamespaces,
+    request: Value,
+    sender: &mpsc::Sender<String>,
+) {
+    let mut subscriptions = Vec::new();
+    let response = match request {
+        Value::Array(requests) if !requests.is_empty() => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                let (response, notifications) = call(methods, disabled_namespaces, request).await;
+                responses.push(response);
+                subscriptions.extend(notifications);
+            }
+            format!("[{}]", responses.join(","))
+        }
+        request => {
+            let (response, notifications) = call(methods, disabled_namespaces, request).await;
+            subscriptions.extend(notifications);
+            response
+        }
+    };
+    if sender.send(response).await.is_err() {
+        return;
+    }
+
+    // The notifications are sent after the response holding the id of the subscription
+    for mut notifications in subscriptions {
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            while let Some(notification) = notifications.recv().await {
+                if sender.send(notification).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Calls the method of the request. Returns the response, along with the receiver of the
+/// notifications if the method opened a subscription.
+async fn call(
+    methods: &Methods,
+    disabled_namespaces: &DisabledNamespaces,
+    request: Value,
+) -> (String, Option<mpsc::Receiver<String>>) {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+    if let Some(namespace) = disabled_namespaces.disabled_namespace(method) {
+        let err = EthApiError::NamespaceDisabled(namespace.to_string());
+        return (error_response(id, err.into()), None);
+    }
+
+    match methods.raw_json_request(&request.to_string(), MESSAGE_BUFFER_SIZE).await {
+        Ok((response, notifications)) => (response, Some(notifications)),
+        Err(_) => (error_response(id, ErrorObject::from(ErrorCode::InvalidRequest)), None),
+    }
+}
+
+/// Returns a JSON-RPC error response.
+fn error_response(id: Value, error: ErrorObjectOwned) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_values() {
+        // Given
+        let buffer = br#"{"id":1} [{"id":2}]
+{"id":"#;
+
+        // When
+        let (values, consumed) = split_values(buffer).unwrap();
+
+        // Then
+        assert_eq!(values, vec![json!({ "id": 1 }), json!([{ "id": 2 }])]);
+        assert_eq!(&buffer[consumed..], b"\n{\"id\":");
+        assert!(split_values(b"{\"id\":1} }").is_err());
+    }
+
+    #[test]
+    fn test_ipc_path() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        let path = Some(PathBuf::from("/tmp/kakarot.ipc"));
+        assert_eq!(ipc_path(args(&["--ipcpath", "/tmp/kakarot.ipc"])).unwrap(), path);
+        assert_eq!(ipc_path(args(&["--dev", "--ipcpath=/tmp/kakarot.ipc"])).unwrap(), path);
+        assert!(ipc_path(args(&["--ipcpath"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ipc_server() {
+        // Given
+        let mut module = RpcModule::new(());
+        module.register_method("web3_clientVersion", |_, _| "kakarot").unwrap();
+        module.register_method("debug_traceBlockByNumber", |_, _| "trace").unwrap();
+        let disabled_namespaces = DisabledNamespaces::default();
+        disabled_namespaces.set_enabled("debug", false);
+        let path = std::env::temp_dir().join(format!("kakarot-{}.ipc", std::process::id()));
+        let handle = run_ipc_server(module, &path, disabled_namespaces).await.unwrap();
+
+        // When
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        let requests = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "web3_clientVersion", "params": [] }),
+            json!([{ "jsonrpc": "2.0", "id": 2, "method": "debug_traceBlockByNumber", "params": [] }]),
+        ];
+        for request in requests {
+            stream.write_all(request.to_string().as_bytes()).await.unwrap();
+        }
+        let mut responses = Vec::new();
+        let mut buffer = Vec::new();
+        while responses.len() < 2 {
+            let mut chunk = [0; 1024];
+            let read = stream.read(&mut chunk).await.unwrap();
+            buffer.extend_from_slice(&chunk[..read]);
+            let (values, consumed) = split_values(&buffer).unwrap();
+            buffer.drain(..consumed);
+            responses.extend(values);
+        }
+        responses.sort_by_key(Value::is_array);
+
+        // Then
+        assert_eq!(responses[0]["result"], "kakarot");
+        assert_eq!(responses[1][0]["id"], 2);
+        assert!(responses[1][0]["error"]["message"].as_str().unwrap().contains("debug"));
+
+        handle.stop().unwrap();
+        handle.stopped().await;
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+
+use ethers::core::rand::thread_rng;
+use ethers::signers::{LocalWallet, Signer};
+use eyre::{eyre, Result};
+use reth_primitives::{Address, B256};
+
+/// Directory of the encrypted keystores (v3) of the accounts managed by the `personal`
+/// namespace, each encrypted with its own passphrase. The keystore of an account is named after
+/// its address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keystore {
+    dir: PathBuf,
+}
+
+impl Keystore {
+    /// Create a new [`Keystore`] in the directory, which is created with the first account.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Create a new [`Keystore`] in the `SIGNER_KEYSTORE_DIR` directory. Returns None if it
+    /// isn't set.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("SIGNER_KEYSTORE_DIR").ok().filter(|dir| !dir.trim().is_empty()).map(Self::new)
+    }
+
+    /// Returns the directory of the keystores.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Generates a new account, encrypted with the passphrase, and returns its address.
+    pub fn new_account(&self, passphrase: &str) -> Result<Address> {
+        let wallet = LocalWallet::new(&mut thread_rng());
+        self.import_raw_key(B256::from_slice(wallet.signer().to_bytes().as_slice()), passphrase)
+    }
+
+    /// Imports the private key as an account encrypted with the passphrase, and returns its
+    /// address.
+    pub fn import_raw_key(&self, private_key: B256, passphrase: &str) -> Result<Address> {
+        let wallet =
+            LocalWallet::from_bytes(private_key.as_slice()).map_err(|err| eyre!("Invalid private key: {err}"))?;
+        let address = Address::from_slice(wallet.address().as_bytes());
+        if self.path(&address).exists() {
+            return Err(eyre!("Account {address} already exists"));
+        }
+
+        std::fs::create_dir_all(&self.dir).map_err(|err| eyre!("Failed to create {}: {err}", self.dir.display()))?;
+        let name = file_name(&address);
+        LocalWallet::encrypt_keystore(&self.dir, &mut thread_rng(), private_key, passphrase, Some(&name))
+            .map_err(|err| eyre!("Failed to write the keystore of {address}: {err}"))?;
+        Ok(address)
+    }
+
+    /// Returns the addresses of the accounts of the keystore, in ascending order.
+    pub fn accounts(&self) -> Result<Vec<Address>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(eyre!("Failed to read {}: {err}", self.dir.display())),
+        };
+
+        let mut accounts =
+            entries.filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<Address>().ok()).collect::<Vec<_>>();
+        accounts.sort_unstable();
+        Ok(accounts)
+    }
+
+    /// Decrypts the private key of the account with the passphrase.
+    pub fn unlock(&self, address: &Address, passphrase: &str) -> Result<B256> {
+        let path = self.path(address);
+        if !path.exists() {
+            return Err(eyre!("Unknown account {address}"));
+        }
+        let wallet = LocalWallet::decrypt_keystore(&path, passphrase)
+            .map_err(|_| eyre!("Could not decrypt key with given password"))?;
+        Ok(B256::from_slice(wallet.signer().to_bytes().as_slice()))
+    }
+
+    fn path(&self, address: &Address) -> PathBuf {
+        self.dir.join(file_name(address))
+    }
+}
+
+/// Name of the keystore of the account.
+fn file_name(address: &Address) -> String {
+    format!("{address:#x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystore_accounts() {
+        // Given
+        let dir = std::env::temp_dir().join(format!("kakarot-keystore-{}", std::process::id()));
+        let keystore = Keystore::new(&dir);
+        let private_key = B256::with_last_byte(1);
+
+        // When
+        let imported = keystore.import_raw_key(private_key, "passphrase").unwrap();
+        let created = keystore.new_account("other passphrase").unwrap();
+
+        // Then
+        let mut expected = vec![imported, created];
+        expected.sort_unstable();
+        assert_eq!(keystore.accounts().unwrap(), expected);
+        assert_eq!(keystore.unlock(&imported, "passphrase").unwrap(), private_key);
+        assert!(keystore.unlock(&imported, "wrong passphrase").is_err());
+        assert!(keystore.import_raw_key(private_key, "passphrase").is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use eyre::{eyre, Result};
+use futures::future::{ready, Either, Ready};
+use jsonrpsee::RpcModule;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+
+use crate::eth_provider::error::EthRpcErrorCode;
+
+/// Methods which are only served by the authenticated server.
+const PROTECTED_METHODS: [&str; 9] = [
+    "eth_sendRawTransaction",
+    "eth_sendTransaction",
+    "eth_sign",
+    "eth_signTransaction",
+    "eth_signTypedData",
+    "eth_signTypedData_v4",
+    "starknet_addInvokeTransaction",
+    "starknet_addDeclareTransaction",
+    "starknet_addDeployAccountTransaction",
+];
+/// Namespaces which are only served by the authenticated server.
+/// The cheat methods of the dev API (`anvil_`, `evm_`) write the state and impersonate accounts.
+const PROTECTED_NAMESPACES: [&str; 6] = ["admin_", "anvil_", "debug_", "evm_", "personal_", "trace_"];
+/// Maximum difference (in seconds) between the issued at claim of a JWT and the current time.
+const JWT_IAT_LEEWAY: u64 = 60;
+
+/// Configuration of the authenticated RPC server, which serves the protected methods.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthConfig {
+    /// Address of the authenticated server.
+    pub socket_addr: String,
+    /// API keys accepted as bearer tokens.
+    pub api_keys: Vec<String>,
+    /// Secret used to verify HS256 JWTs passed as bearer tokens.
+    pub jwt_secret: Option<Vec<u8>>,
+}
+
+impl AuthConfig {
+    /// Create a new `AuthConfig` from the `RPC_AUTH_API_KEYS` (comma separated) and
+    /// `RPC_AUTH_JWT_SECRET` (hex encoded) environment variables. The authenticated
+    /// server listens on `KAKAROT_AUTH_RPC_URL`. Returns None if neither API keys
+    /// nor a JWT secret are set, in which case all the methods stay public.
+    pub fn from_env() -> Result<Option<Self>> {
+        let api_keys = std::env::var("RPC_AUTH_API_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        let jwt_secret = std::env::var("RPC_AUTH_JWT_SECRET")
+            .ok()
+            .filter(|secret| !secret.trim().is_empty())
+            .map(|secret| hex::decode(secret.trim().trim_start_matches("0x")))
+            .transpose()
+            .map_err(|err| eyre!("Invalid RPC_AUTH_JWT_SECRET: {err}"))?;
+
+        if api_keys.is_empty() && jwt_secret.is_none() {
+            return Ok(None);
+        }
+
+        let socket_addr = std::env::var("KAKAROT_AUTH_RPC_URL").unwrap_or_else(|_| "127.0.0.1:8551".to_string());
+        Ok(Some(Self { socket_addr, api_keys, jwt_secret }))
+    }
+
+    /// Returns the layer rejecting the requests without valid credentials.
+    pub fn layer(&self) -> AuthLayer {
+        AuthLayer { config: Arc::new(self.clone()) }
+    }
+
+    /// Returns true if the bearer token is one of the API keys or a valid JWT.
+    fn is_authorized(&self, token: &str) -> bool {
+        self.api_keys.iter().any(|key| key == token)
+            || self.jwt_secret.as_ref().is_some_and(|secret| is_valid_jwt(token, secret))
+    }
+}
+
+/// Claims of the JWTs, following the Engine API authentication.
+#[derive(Debug, Deserialize)]
+struct Claims {
+    /// Issued at, in seconds since the Unix epoch.
+    iat: u64,
+}
+
+/// Returns true if the token is a HS256 JWT signed with the secret and issued recently.
+fn is_valid_jwt(token: &str, secret: &[u8]) -> bool {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_exp = false;
+    validation.required_spec_claims.clear();
+
+    let Ok(token) = jsonwebtoken::decode::<Claims>(token, &DecodingKey::from_secret(secret), &validation) else {
+        return false;
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    now.abs_diff(token.claims.iat) <= JWT_IAT_LEEWAY
+}
+
+/// Returns true if the method is only served by the authenticated server.
+pub fn is_protected_method(method: &str) -> bool {
+    PROTECTED_METHODS.contains(&method) || PROTECTED_NAMESPACES.iter().any(|namespace| method.starts_with(namespace))
+}
+
+/// Removes the protected methods from the module.
+pub fn remove_protected_methods(module: &mut RpcModule<()>) {
+    let protected = module.method_names().filter(|method| is_protected_method(method)).collect::<Vec<_>>();
+    for method in protected {
+        module.remove_method(method);
+    }
+}
+
+/// HTTP middleware layer rejecting the requests without an `Authorization: Bearer <token>`
+/// header, where the token is either an API key or a JWT signed with the secret.
+#[derive(Debug, Clone)]
+pub struct AuthLayer {
+    config: Arc<AuthConfig>,
+}
+
+impl<S> tower::Layer<S> for AuthLayer {
+    type Service = Auth<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        Auth { service, config: self.config.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Auth<S> {
+    service: S,
+    config: Arc<AuthConfig>,
+}
+
+impl<S, B, RB> tower::Service<http::Request<B>> for Auth<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<RB>>,
+    RB: From<String>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<S::Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let token = req
+            .headers()
+            .get(http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        if !token.is_some_and(|token| self.config.is_authorized(token.trim())) {
+            return Either::Left(ready(Ok(unauthorized_response())));
+        }
+        Either::Right(self.service.call(req))
+    }
+}
+
+/// Returns a JSON-RPC error response for a request without valid credentials.
+fn unauthorized_response<RB: From<String>>() -> http::Response<RB> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": EthRpcErrorCode::InvalidRequest as i32, "message": "unauthorized" }
+    });
+
+    http::Response::builder()
+        .status(http::StatusCode::UNAUTHORIZED)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(RB::from(body.to_string()))
+        .expect("Failed to build unauthorized response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header};
+
+    fn jwt(secret: &[u8], iat: u64) -> String {
+        jsonwebtoken::encode(&Header::default(), &serde_json::json!({ "iat": iat }), &EncodingKey::from_secret(secret))
+            .unwrap()
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    #[test]
+    fn test_is_protected_method() {
+        assert!(is_protected_method("eth_sendRawTransaction"));
+        assert!(is_protected_method("eth_sign"));
+        assert!(is_protected_method("debug_traceTransaction"));
+        assert!(is_protected_method("trace_block"));
+        assert!(is_protected_method("admin_flushCaches"));
+        assert!(is_protected_method("personal_newAccount"));
+        assert!(is_protected_method("anvil_impersonateAccount"));
+        assert!(is_protected_method("evm_mine"));
+        assert!(!is_protected_method("eth_call"));
+        assert!(!is_protected_method("eth_getLogs"));
+    }
+
+    #[test]
+    fn test_api_key_authorization() {
+        let config = AuthConfig { api_keys: vec!["key".to_string()], ..Default::default() };
+
+        assert!(config.is_authorized("key"));
+        assert!(!config.is_authorized("other"));
+        // No JWT secret configured
+        assert!(!config.is_authorized(&jwt(b"secret", now())));
+    }
+
+    #[test]
+    fn test_jwt_authorization() {
+        let config = AuthConfig { jwt_secret: Some(b"secret".to_vec()), ..Default::default() };
+
+        assert!(config.is_authorized(&jwt(b"secret", now())));
+        // Wrong secret
+        assert!(!config.is_authorized(&jwt(b"other", now())));
+        // Stale token
+        assert!(!config.is_authorized(&jwt(b"secret", now() - 2 * JWT_IAT_LEEWAY)));
+        assert!(!config.is_authorized("not a jwt"));
+    }
+}
@@ -0,0 +1,301 @@
+use std::str::FromStr;
+use std::task::{Context, Poll};
+
+use eyre::{eyre, Result};
+use futures::future::{BoxFuture, Either};
+use futures::StreamExt;
+use serde_json::value::RawValue;
+use tower::ServiceExt;
+
+use crate::eth_provider::constant::RPC_MAX_RESPONSE_SIZE;
+use crate::prometheus_handler::{register, Histogram, HistogramOpts, PrometheusError, Registry};
+
+/// Maximum size of the body of a batch split by the middleware, which is the default maximum size
+/// of the requests of the server. Larger batches are left to the server, which rejects them.
+const MAX_BATCH_BODY_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Histogram buckets of the number of calls in a batch.
+const BATCH_SIZE_BUCKETS: [f64; 10] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1_000.0];
+
+/// JSON-RPC error code of a batch whose responses exceed the maximum response size.
+const BATCH_RESPONSE_TOO_LARGE_CODE: i32 = -32011;
+
+/// Handling of the batch requests: their maximum number of calls, and the number of calls of a
+/// batch which are executed concurrently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchConfig {
+    /// Maximum number of calls in a batch, 0 disabling the batch requests.
+    pub max_batch_size: u32,
+    /// Maximum number of calls of a batch executed at once.
+    pub concurrency: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self { max_batch_size: 1000, concurrency: 8 }
+    }
+}
+
+impl BatchConfig {
+    /// Create a new `BatchConfig` from the `RPC_MAX_BATCH_SIZE` and `RPC_BATCH_CONCURRENCY`
+    /// environment variables.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        if let Some(max_batch_size) = var("RPC_MAX_BATCH_SIZE") {
+            config.max_batch_size = u32::from_str(max_batch_size.trim())
+                .map_err(|err| eyre!("Invalid RPC_MAX_BATCH_SIZE {max_batch_size}: {err}"))?;
+        }
+        if let Some(concurrency) = var("RPC_BATCH_CONCURRENCY") {
+            config.concurrency = usize::from_str(concurrency.trim())
+                .map_err(|err| eyre!("Invalid RPC_BATCH_CONCURRENCY {concurrency}: {err}"))?
+                .max(1);
+        }
+        Ok(config)
+    }
+
+    /// Returns the layer splitting the batches and recording their size, if the batch requests
+    /// are enabled.
+    pub fn layer(&self, registry: &Registry) -> Result<Option<BatchLayer>, PrometheusError> {
+        if self.max_batch_size == 0 {
+            return Ok(None);
+        }
+        let batch_size = register(
+            Histogram::with_opts(
+                HistogramOpts::new("eth_rpc_batch_size", "Number of calls of the RPC batch requests")
+                    .buckets(BATCH_SIZE_BUCKETS.to_vec()),
+            )?,
+            registry,
+        )?;
+        Ok(Some(BatchLayer { config: *self, batch_size }))
+    }
+}
+
+/// HTTP middleware layer recording the size of the batch requests, whose calls are forwarded to
+/// the server one by one with a bounded concurrency. The responses are gathered in a single batch
+/// response, in the order of the calls.
+#[derive(Debug, Clone)]
+pub struct BatchLayer {
+    config: BatchConfig,
+    batch_size: Histogram,
+}
+
+impl<S> tower::Layer<S> for BatchLayer {
+    type Service = Batch<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        Batch { service, config: self.config, batch_size: self.batch_size.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Batch<S> {
+    service: S,
+    config: BatchConfig,
+    batch_size: Histogram,
+}
+
+impl<S> tower::Service<http::Request<hyper_014::Body>> for Batch<S>
+where
+    S: tower::Service<http::Request<hyper_014::Body>, Response = http::Response<hyper_014::Body>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send,
+    S::Error: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<BoxFuture<'static, Result<S::Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<hyper_014::Body>) -> Self::Future {
+        let content_length = req
+            .headers()
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        if req.method() != http::Method::POST || !content_length.is_some_and(|length| length <= MAX_BATCH_BODY_SIZE) {
+            return Either::Right(self.service.call(req));
+        }
+
+        // The service which was polled ready handles the request, its clone the next ones
+        let service = self.service.clone();
+        let service = std::mem::replace(&mut self.service, service);
+        let config = self.config;
+        let batch_size = self.batch_size.clone();
+        Either::Left(Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let body = match hyper_014::body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(_) => return service.oneshot(http::Request::from_parts(parts, hyper_014::Body::empty())).await,
+            };
+
+            // Single calls, empty batches, invalid and too large batches are left to the server
+            let calls = match serde_json::from_slice::<Vec<&RawValue>>(&body) {
+                Ok(calls) if !calls.is_empty() => calls,
+                _ => return service.oneshot(http::Request::from_parts(parts, body.into())).await,
+            };
+            batch_size.observe(calls.len() as f64);
+            if calls.len() > config.max_batch_size as usize {
+                return service.oneshot(http::Request::from_parts(parts, body.into())).await;
+            }
+
+            let requests = calls.iter().map(|call| {
+                let mut request = http::Request::new(hyper_014::Body::from(call.get().to_string()));
+                *request.method_mut() = parts.method.clone();
+                *request.uri_mut() = parts.uri.clone();
+                *request.version_mut() = parts.version;
+                *request.headers_mut() = parts.headers.clone();
+                request.headers_mut().insert(http::header::CONTENT_LENGTH, call.get().len().into());
+                service.clone().oneshot(request)
+            });
+            let responses = futures::stream::iter(requests).buffered(config.concurrency).collect::<Vec<_>>().await;
+
+            // The notifications aren't answered
+            let mut bodies = Vec::with_capacity(responses.len());
+            for response in responses {
+                let body = hyper_014::body::to_bytes(response?.into_body()).await.unwrap_or_default();
+                if !body.is_empty() {
+                    bodies.push(body);
+                }
+            }
+            Ok(batch_response(&bodies))
+        }))
+    }
+}
+
+/// Gathers the responses of the calls of a batch in a batch response. A batch of notifications is
+/// answered with an empty body.
+fn batch_response(bodies: &[hyper_014::body::Bytes]) -> http::Response<hyper_014::Body> {
+    let size = bodies.iter().map(|body| body.len() + 1).sum::<usize>() + 1;
+    let body = if bodies.is_empty() {
+        String::new()
+    } else if size > *RPC_MAX_RESPONSE_SIZE as usize {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": {
+                "code": BATCH_RESPONSE_TOO_LARGE_CODE,
+                "message": "The batch response was too large",
+                "data": format!("Exceeded max limit of {}", *RPC_MAX_RESPONSE_SIZE),
+            }
+        })
+        .to_string()
+    } else {
+        let mut body = Vec::with_capacity(size);
+        body.push(b'[');
+        for (index, response) in bodies.iter().enumerate() {
+            if index > 0 {
+                body.push(b',');
+            }
+            body.extend_from_slice(response);
+        }
+        body.push(b']');
+        String::from_utf8_lossy(&body).into_owned()
+    };
+
+    http::Response::builder()
+        .status(http::StatusCode::OK)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(hyper_014::Body::from(body))
+        .expect("Failed to build the batch response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Service answering each call with its id, and recording the maximum number of concurrent calls.
+    #[derive(Debug, Clone, Default)]
+    struct EchoService {
+        concurrent: Arc<AtomicUsize>,
+        max_concurrent: Arc<AtomicUsize>,
+    }
+
+    impl tower::Service<http::Request<hyper_014::Body>> for EchoService {
+        type Response = http::Response<hyper_014::Body>;
+        type Error = Infallible;
+        type Future = BoxFuture<'static, Result<Self::Response, Infallible>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: http::Request<hyper_014::Body>) -> Self::Future {
+            let (concurrent, max_concurrent) = (self.concurrent.clone(), self.max_concurrent.clone());
+            Box::pin(async move {
+                let count = concurrent.fetch_add(1, Ordering::SeqCst) + 1;
+                max_concurrent.fetch_max(count, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                concurrent.fetch_sub(1, Ordering::SeqCst);
+
+                let body = hyper_014::body::to_bytes(req.into_body()).await.unwrap();
+                let call: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let body = match call.get("id") {
+                    Some(id) => serde_json::json!({"jsonrpc": "2.0", "id": id, "result": id}).to_string(),
+                    None => String::new(),
+                };
+                Ok(http::Response::new(hyper_014::Body::from(body)))
+            })
+        }
+    }
+
+    fn post(body: String) -> http::Request<hyper_014::Body> {
+        http::Request::post("/")
+            .header(http::header::CONTENT_LENGTH, body.len())
+            .body(hyper_014::Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_batch_calls_bounded_concurrency() {
+        // Given
+        let registry = Registry::new();
+        let config = BatchConfig { max_batch_size: 100, concurrency: 3 };
+        let layer = config.layer(&registry).unwrap().unwrap();
+        let inner = EchoService::default();
+        let service = tower::Layer::layer(&layer, inner.clone());
+        let calls = (0..10)
+            .map(|id| serde_json::json!({"jsonrpc": "2.0", "id": id, "method": "eth_chainId"}))
+            .chain(std::iter::once(serde_json::json!({"jsonrpc": "2.0", "method": "eth_chainId"})))
+            .collect::<Vec<_>>();
+
+        // When
+        let response = service.oneshot(post(serde_json::to_string(&calls).unwrap())).await.unwrap();
+        let body = hyper_014::body::to_bytes(response.into_body()).await.unwrap();
+        let responses: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+
+        // Then
+        let ids = responses.iter().map(|response| response["id"].as_u64().unwrap()).collect::<Vec<_>>();
+        assert_eq!(ids, (0..10).collect::<Vec<_>>());
+        assert_eq!(inner.max_concurrent.load(Ordering::SeqCst), 3);
+        assert_eq!(layer.batch_size.get_sample_count(), 1);
+        assert_eq!(layer.batch_size.get_sample_sum(), 11.);
+    }
+
+    #[tokio::test]
+    async fn test_single_call_and_disabled_batches() {
+        // Given
+        let layer = BatchConfig::default().layer(&Registry::new()).unwrap().unwrap();
+        let service = tower::Layer::layer(&layer, EchoService::default());
+        let call = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"}).to_string();
+
+        // When
+        let response = service.oneshot(post(call)).await.unwrap();
+        let body = hyper_014::body::to_bytes(response.into_body()).await.unwrap();
+
+        // Then
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap()["result"], 1);
+        assert_eq!(layer.batch_size.get_sample_count(), 0);
+        assert!(BatchConfig { max_batch_size: 0, ..Default::default() }.layer(&Registry::new()).unwrap().is_none());
+    }
+}
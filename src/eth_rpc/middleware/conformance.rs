@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use eyre::Result;
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request, MethodResponse};
+use pin_project_lite::pin_project;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+use crate::eth_rpc::openrpc::{OpenRpcSpec, SchemaViolation};
+
+/// Validation of the responses of the RPC against an OpenRPC document, e.g. the assembled
+/// specification of the Ethereum execution APIs. The violations are logged as warnings, so that
+/// the regressions of the shape of the responses can be caught while debugging. The validation
+/// parses every response, which makes it unsuitable for production.
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceConfig {
+    /// Methods and schemas against which the responses are validated.
+    pub spec: Option<Arc<OpenRpcSpec>>,
+}
+
+impl ConformanceConfig {
+    /// Create a new `ConformanceConfig` from the `RPC_OPENRPC_SPEC` environment variable, which holds
+    /// the path of the OpenRPC document. The responses aren't validated if it's unset.
+    pub fn from_env() -> Result<Self> {
+        let Some(path) = std::env::var("RPC_OPENRPC_SPEC").ok().filter(|path| !path.trim().is_empty()) else {
+            return Ok(Self::default());
+        };
+        Ok(Self { spec: Some(Arc::new(OpenRpcSpec::from_file(path.trim())?)) })
+    }
+
+    /// Returns the layer validating the responses, if a document is loaded.
+    pub fn layer(&self) -> Option<ConformanceLayer> {
+        self.spec.clone().map(|spec| ConformanceLayer { spec })
+    }
+}
+
+/// Result of a successful JSON-RPC response.
+#[derive(Deserialize)]
+struct SuccessResponse<'a> {
+    #[serde(borrow)]
+    result: &'a RawValue,
+}
+
+/// Returns the violations of the schema of the method by the result of the response, if it's a
+/// successful response of a method of the document.
+fn response_violations(spec: &OpenRpcSpec, method: &str, response: &MethodResponse) -> Option<Vec<SchemaViolation>> {
+    if !response.is_success() || response.is_subscription {
+        return None;
+    }
+    let response = serde_json::from_str::<SuccessResponse<'_>>(&response.result).ok()?;
+    let result = serde_json::from_str(response.result.get()).ok()?;
+    spec.validate_result(method, &result)
+}
+
+/// RPC middleware layer validating the responses.
+#[derive(Debug, Clone)]
+pub struct ConformanceLayer {
+    spec: Arc<OpenRpcSpec>,
+}
+
+impl<S> tower::Layer<S> for ConformanceLayer {
+    type Service = Conformance<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        Conformance { service, spec: self.spec.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Conformance<S> {
+    service: S,
+    spec: Arc<OpenRpcSpec>,
+}
+
+impl<'a, S> RpcServiceT<'a> for Conformance<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<'a, S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        ResponseFuture { method: req.method.clone(), fut: self.service.call(req), spec: self.spec.clone() }
+    }
+}
+
+pin_project! {
+    /// Response future validating the response once the call completes.
+    pub struct ResponseFuture<'a, F> {
+        #[pin]
+        fut: F,
+        spec: Arc<OpenRpcSpec>,
+        method: Cow<'a, str>,
+    }
+}
+
+impl<'a, F> std::fmt::Debug for ResponseFuture<'a, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ResponseFuture")
+    }
+}
+
+impl<'a, F: Future<Output = MethodResponse>> Future for ResponseFuture<'a, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let res = this.fut.poll(cx);
+        if let Poll::Ready(rp) = &res {
+            for violation in response_violations(this.spec, this.method, rp).into_iter().flatten() {
+                tracing::warn!(target: "rpc_conformance", method = %this.method, %violation, "response violates the spec");
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::{ErrorObject, Id, ResponsePayload};
+    use serde_json::json;
+
+    #[test]
+    fn test_response_violations() {
+        // Given
+        let spec = OpenRpcSpec::from_value(json!({
+            "methods": [{ "name": "eth_blockNumber", "params": [], "result": { "schema": { "type": "string" } } }]
+        }))
+        .unwrap();
+        let response = |result: serde_json::Value| {
+            MethodResponse::response(Id::Number(1), ResponsePayload::result(result), usize::MAX)
+        };
+        let error = MethodResponse::error(Id::Number(1), ErrorObject::owned(-32000, "error", None::<()>));
+
+        // When
+        let valid = response_violations(&spec, "eth_blockNumber", &response(json!("0x1")));
+        let invalid = response_violations(&spec, "eth_blockNumber", &response(json!(1)));
+        let unknown = response_violations(&spec, "eth_unknown", &response(json!(1)));
+        let failed = response_violations(&spec, "eth_blockNumber", &error);
+
+        // Then
+        assert_eq!(valid, Some(vec![]));
+        assert_eq!(invalid.map(|violations| violations.len()), Some(1));
+        assert_eq!(unknown, None);
+        assert_eq!(failed, None);
+    }
+}
@@ -0,0 +1,277 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use eyre::{eyre, Result};
+use futures::future::{ready, Either, Ready};
+use http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{Any, CorsLayer};
+
+use crate::eth_provider::error::EthRpcErrorCode;
+
+/// Value allowing any origin, method, header or host.
+const WILDCARD: &str = "*";
+
+/// CORS policy and virtual hosts of the RPC server, similar to geth's `--http.corsdomain` and
+/// `--http.vhosts`. Each list holding `*` allows any value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsConfig {
+    /// Origins allowed to send cross-origin requests.
+    pub allowed_origins: Vec<String>,
+    /// Methods allowed in cross-origin requests.
+    pub allowed_methods: Vec<String>,
+    /// Headers allowed in cross-origin requests.
+    pub allowed_headers: Vec<String>,
+    /// Values of the `Host` header accepted by the server, without the port.
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        let any = vec![WILDCARD.to_string()];
+        Self {
+            allowed_origins: any.clone(),
+            allowed_methods: any.clone(),
+            allowed_headers: any.clone(),
+            allowed_hosts: any,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Create a new `CorsConfig` from the `RPC_CORS_ALLOWED_ORIGINS`, `RPC_CORS_ALLOWED_METHODS`,
+    /// `RPC_CORS_ALLOWED_HEADERS` and `RPC_ALLOWED_HOSTS` environment variables, which are comma
+    /// separated lists. Unset variables allow any value.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        for (name, list) in [
+            ("RPC_CORS_ALLOWED_ORIGINS", &mut config.allowed_origins),
+            ("RPC_CORS_ALLOWED_METHODS", &mut config.allowed_methods),
+            ("RPC_CORS_ALLOWED_HEADERS", &mut config.allowed_headers),
+            ("RPC_ALLOWED_HOSTS", &mut config.allowed_hosts),
+        ] {
+            if let Ok(value) = std::env::var(name) {
+                *list = parse_list(&value);
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Overrides the configuration with the `--http.corsdomain`, `--http.corsmethods`,
+    /// `--http.corsheaders` and `--http.vhosts` command line flags, given either as
+    /// `--flag value` or `--flag=value`.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let list = match flag.as_str() {
+                "--http.corsdomain" => &mut self.allowed_origins,
+                "--http.corsmethods" => &mut self.allowed_methods,
+                "--http.corsheaders" => &mut self.allowed_headers,
+                "--http.vhosts" => &mut self.allowed_hosts,
+                _ => continue,
+            };
+            let value = value.or_else(|| args.next()).ok_or_else(|| eyre!("Missing value for {flag}"))?;
+            *list = parse_list(&value);
+        }
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Checks that the origins, methods and headers are valid HTTP values.
+    fn validate(&self) -> Result<()> {
+        self.cors_layer().map(|_| ())
+    }
+
+    /// Returns the layer adding the CORS headers to the responses.
+    pub fn cors_layer(&self) -> Result<CorsLayer> {
+        let mut layer = CorsLayer::new();
+
+        layer = if is_wildcard(&self.allowed_origins) {
+            layer.allow_origin(Any)
+        } else {
+            let origins = self
+                .allowed_origins
+                .iter()
+                .map(|origin| HeaderValue::from_str(origin).map_err(|err| eyre!("Invalid CORS origin {origin}: {err}")))
+                .collect::<Result<Vec<_>>>()?;
+            layer.allow_origin(origins)
+        };
+
+        layer = if is_wildcard(&self.allowed_methods) {
+            layer.allow_methods(Any)
+        } else {
+            let methods = self
+                .allowed_methods
+                .iter()
+                .map(|method| {
+                    Method::from_bytes(method.to_uppercase().as_bytes())
+                        .map_err(|err| eyre!("Invalid CORS method {method}: {err}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            layer.allow_methods(methods)
+        };
+
+        layer = if is_wildcard(&self.allowed_headers) {
+            layer.allow_headers(Any)
+        } else {
+            let headers = self
+                .allowed_headers
+                .iter()
+                .map(|header| {
+                    HeaderName::from_bytes(header.as_bytes())
+                        .map_err(|err| eyre!("Invalid CORS header {header}: {err}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            layer.allow_headers(headers)
+        };
+
+        Ok(layer)
+    }
+
+    /// Returns the layer rejecting the requests to a host which isn't allowed, if the hosts are restricted.
+    pub fn host_filter_layer(&self) -> Option<HostFilterLayer> {
+        if is_wildcard(&self.allowed_hosts) {
+            return None;
+        }
+        let hosts = self.allowed_hosts.iter().map(|host| host.to_lowercase()).collect();
+        Some(HostFilterLayer { hosts: Arc::new(hosts) })
+    }
+}
+
+/// Parses a comma separated list, ignoring the empty entries.
+fn parse_list(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|entry| !entry.is_empty()).map(ToString::to_string).collect()
+}
+
+/// Returns true if the list allows any value.
+fn is_wildcard(list: &[String]) -> bool {
+    list.iter().any(|value| value == WILDCARD)
+}
+
+/// HTTP middleware layer rejecting the requests whose `Host` header isn't one of the allowed hosts.
+#[derive(Debug, Clone)]
+pub struct HostFilterLayer {
+    hosts: Arc<Vec<String>>,
+}
+
+impl<S> tower::Layer<S> for HostFilterLayer {
+    type Service = HostFilter<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        HostFilter { service, hosts: self.hosts.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HostFilter<S> {
+    service: S,
+    hosts: Arc<Vec<String>>,
+}
+
+impl<S> HostFilter<S> {
+    /// Returns true if the host, with or without its port, is allowed.
+    fn is_allowed(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        let without_port = match host.rsplit_once(':') {
+            // Don't split IPv6 addresses without a port, e.g. [::1]
+            Some((name, port)) if !port.contains(']') => name,
+            _ => host.as_str(),
+        };
+        self.hosts.iter().any(|allowed| *allowed == host || allowed == without_port)
+    }
+}
+
+impl<S, B, RB> tower::Service<http::Request<B>> for HostFilter<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<RB>>,
+    RB: From<String>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<S::Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        // HTTP/2 requests carry the host in the URI authority instead of the Host header
+        let host = req
+            .headers()
+            .get(http::header::HOST)
+            .and_then(|value| value.to_str().ok())
+            .or_else(|| req.uri().authority().map(http::uri::Authority::as_str));
+
+        if !host.is_some_and(|host| self.is_allowed(host)) {
+            return Either::Left(ready(Ok(forbidden_host_response())));
+        }
+        Either::Right(self.service.call(req))
+    }
+}
+
+/// Returns a JSON-RPC error response for a request to a host which isn't allowed.
+fn forbidden_host_response<RB: From<String>>() -> http::Response<RB> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": EthRpcErrorCode::InvalidRequest as i32, "message": "invalid host specified" }
+    });
+
+    http::Response::builder()
+        .status(http::StatusCode::FORBIDDEN)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(RB::from(body.to_string()))
+        .expect("Failed to build forbidden host response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_with_args() {
+        // Given
+        let config = CorsConfig::default();
+
+        // When
+        let config = config
+            .with_args(args(&[
+                "--index",
+                "--http.corsdomain",
+                "https://a.xyz, https://b.xyz",
+                "--http.vhosts=localhost",
+            ]))
+            .unwrap();
+
+        // Then
+        assert_eq!(config.allowed_origins, vec!["https://a.xyz", "https://b.xyz"]);
+        assert_eq!(config.allowed_hosts, vec!["localhost"]);
+        assert_eq!(config.allowed_methods, vec![WILDCARD]);
+        assert!(CorsConfig::default().with_args(args(&["--http.vhosts"])).is_err());
+        assert!(CorsConfig::default().with_args(args(&["--http.corsheaders", "invalid header"])).is_err());
+    }
+
+    #[test]
+    fn test_host_filter() {
+        // Given
+        let config =
+            CorsConfig { allowed_hosts: vec!["LocalHost".to_string(), "[::1]".to_string()], ..Default::default() };
+        let filter = HostFilter { service: (), hosts: config.host_filter_layer().unwrap().hosts };
+
+        // Then
+        assert!(filter.is_allowed("localhost"));
+        assert!(filter.is_allowed("localhost:3030"));
+        assert!(filter.is_allowed("[::1]"));
+        assert!(filter.is_allowed("[::1]:3030"));
+        assert!(!filter.is_allowed("rpc.kakarot.org"));
+        assert!(CorsConfig::default().host_filter_layer().is_none());
+    }
+}
@@ -0,0 +1,283 @@
+use std::borrow::Cow;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use eyre::{eyre, Result};
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request, MethodResponse};
+use pin_project_lite::pin_project;
+
+/// Maximum number of characters of the logged params, beyond which they're truncated.
+const MAX_LOGGED_PARAMS_LEN: usize = 1024;
+
+/// Value logged in place of the params of the redacted methods.
+const REDACTED: &str = "<redacted>";
+
+/// Methods whose params are always redacted: the raw transactions, and the `personal_` namespace
+/// whose params hold private keys and passphrases.
+const DEFAULT_REDACTED_METHODS: [&str; 2] = ["eth_sendRawTransaction", "personal_"];
+
+/// Logging of the RPC calls. Each logged call reports its method, the size of its params, its
+/// latency and its error code if it failed. The successful and the failed calls are sampled
+/// separately, so that the errors can all be logged while only a fraction of the traffic is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLoggingConfig {
+    /// Fraction of the successful calls which are logged, between 0 and 1.
+    pub sample_rate: f64,
+    /// Fraction of the failed calls which are logged, between 0 and 1.
+    pub error_sample_rate: f64,
+    /// Logs the params of the calls, along with their size.
+    pub log_params: bool,
+    /// Methods whose params are never logged, e.g. because they hold raw transactions. A name
+    /// ending with `_`, such as `personal_`, redacts all the methods of the namespace.
+    pub redacted_methods: Vec<String>,
+}
+
+impl Default for RequestLoggingConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 0.,
+            error_sample_rate: 1.,
+            log_params: false,
+            redacted_methods: DEFAULT_REDACTED_METHODS.map(ToString::to_string).to_vec(),
+        }
+    }
+}
+
+impl RequestLoggingConfig {
+    /// Create a new `RequestLoggingConfig` from the `RPC_LOG_SAMPLE_RATE`, `RPC_LOG_ERROR_SAMPLE_RATE`,
+    /// `RPC_LOG_PARAMS` and `RPC_LOG_REDACTED_METHODS` environment variables. The latter is a comma
+    /// separated list of methods, which are redacted in addition to the default ones.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let parse_rate = |name: &str, value: String| -> Result<f64> {
+            let rate = f64::from_str(value.trim()).map_err(|err| eyre!("Invalid {name} {value}: {err}"))?;
+            if !(0. ..=1.).contains(&rate) {
+                return Err(eyre!("Invalid {name} {value}: expected a rate between 0 and 1"));
+            }
+            Ok(rate)
+        };
+
+        if let Some(rate) = var("RPC_LOG_SAMPLE_RATE") {
+            config.sample_rate = parse_rate("RPC_LOG_SAMPLE_RATE", rate)?;
+        }
+        if let Some(rate) = var("RPC_LOG_ERROR_SAMPLE_RATE") {
+            config.error_sample_rate = parse_rate("RPC_LOG_ERROR_SAMPLE_RATE", rate)?;
+        }
+        if let Some(log_params) = var("RPC_LOG_PARAMS") {
+            config.log_params =
+                bool::from_str(log_params.trim()).map_err(|err| eyre!("Invalid RPC_LOG_PARAMS {log_params}: {err}"))?;
+        }
+        if let Some(methods) = var("RPC_LOG_REDACTED_METHODS") {
+            for method in methods.split(',').map(str::trim).filter(|method| !method.is_empty()) {
+                if !config.redacted_methods.iter().any(|redacted| redacted == method) {
+                    config.redacted_methods.push(method.to_string());
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Returns the layer logging the RPC calls, if any call is logged.
+    pub fn layer(&self) -> Option<RequestLoggingLayer> {
+        if self.sample_rate <= 0. && self.error_sample_rate <= 0. {
+            return None;
+        }
+        Some(RequestLoggingLayer {
+            inner: Arc::new(RequestLogger {
+                successes: Sampler::new(self.sample_rate),
+                errors: Sampler::new(self.error_sample_rate),
+                log_params: self.log_params,
+                redacted_methods: self.redacted_methods.clone(),
+            }),
+        })
+    }
+}
+
+/// Deterministic sampler, selecting evenly spread calls at the given rate.
+#[derive(Debug)]
+struct Sampler {
+    rate: f64,
+    count: AtomicU64,
+}
+
+impl Sampler {
+    fn new(rate: f64) -> Self {
+        Self { rate, count: AtomicU64::new(0) }
+    }
+
+    /// Returns true if the next call is sampled.
+    fn sample(&self) -> bool {
+        if self.rate >= 1. {
+            return true;
+        }
+        if self.rate <= 0. {
+            return false;
+        }
+        // The call is sampled when the expected number of sampled calls reaches a new integer
+        let count = self.count.fetch_add(1, Ordering::Relaxed) as f64;
+        ((count + 1.) * self.rate).floor() > (count * self.rate).floor()
+    }
+}
+
+#[derive(Debug)]
+struct RequestLogger {
+    successes: Sampler,
+    errors: Sampler,
+    log_params: bool,
+    redacted_methods: Vec<String>,
+}
+
+impl RequestLogger {
+    /// Returns the params to log for the call, if any.
+    fn params(&self, method: &str, params: Option<&str>) -> Option<String> {
+        if !self.log_params {
+            return None;
+        }
+        let is_redacted = |redacted: &String| {
+            redacted == method || (redacted.ends_with('_') && method.starts_with(redacted.as_str()))
+        };
+        if self.redacted_methods.iter().any(is_redacted) {
+            return Some(REDACTED.to_string());
+        }
+        let params = params?;
+        Some(match params.char_indices().nth(MAX_LOGGED_PARAMS_LEN) {
+            Some((index, _)) => format!("{}...", &params[..index]),
+            None => params.to_string(),
+        })
+    }
+}
+
+/// RPC middleware layer logging the calls.
+#[derive(Debug, Clone)]
+pub struct RequestLoggingLayer {
+    inner: Arc<RequestLogger>,
+}
+
+impl<S> tower::Layer<S> for RequestLoggingLayer {
+    type Service = RequestLogging<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RequestLogging { service, logger: self.inner.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestLogging<S> {
+    service: S,
+    logger: Arc<RequestLogger>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RequestLogging<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<'a, S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let raw_params = req.params.as_ref().map(|params| params.get());
+        let params_size = raw_params.map_or(0, str::len);
+        let params = self.logger.params(req.method_name(), raw_params);
+
+        ResponseFuture {
+            method: req.method.clone(),
+            fut: self.service.call(req),
+            logger: self.logger.clone(),
+            params_size,
+            params,
+            now: Instant::now(),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future logging the call once it completes.
+    pub struct ResponseFuture<'a, F> {
+        #[pin]
+        fut: F,
+        logger: Arc<RequestLogger>,
+        method: Cow<'a, str>,
+        params_size: usize,
+        params: Option<String>,
+        now: Instant,
+    }
+}
+
+impl<'a, F> std::fmt::Debug for ResponseFuture<'a, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ResponseFuture")
+    }
+}
+
+impl<'a, F: Future<Output = MethodResponse>> Future for ResponseFuture<'a, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let res = this.fut.poll(cx);
+        if let Poll::Ready(rp) = &res {
+            let error_code = rp.as_error_code();
+            let sampler = if error_code.is_some() { &this.logger.errors } else { &this.logger.successes };
+            if sampler.sample() {
+                tracing::info!(
+                    target: "rpc_requests",
+                    method = %this.method,
+                    params_size = *this.params_size,
+                    latency_ms = this.now.elapsed().as_millis() as u64,
+                    error_code,
+                    params = this.params.as_deref(),
+                    "rpc call"
+                );
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampler() {
+        // Given
+        let sampler = Sampler::new(0.25);
+
+        // When
+        let sampled = (0..100).filter(|_| sampler.sample()).count();
+
+        // Then
+        assert_eq!(sampled, 25);
+        assert!((0..10).all(|_| Sampler::new(1.).sample()));
+        assert!((0..10).all(|_| !Sampler::new(0.).sample()));
+    }
+
+    #[test]
+    fn test_params_redaction() {
+        // Given
+        let config = RequestLoggingConfig { log_params: true, ..Default::default() };
+        let layer = config.layer().expect("Missing logging layer");
+        let long_params = format!("[\"0x{}\"]", "ab".repeat(MAX_LOGGED_PARAMS_LEN));
+
+        // When
+        let raw_transaction = layer.inner.params("eth_sendRawTransaction", Some("[\"0x02f8...\"]"));
+        let import_key = layer.inner.params("personal_importRawKey", Some("[\"0xac09...\", \"passphrase\"]"));
+        let call = layer.inner.params("eth_call", Some("[{}, \"latest\"]"));
+        let truncated = layer.inner.params("eth_call", Some(&long_params)).unwrap();
+
+        // Then
+        assert_eq!(raw_transaction.as_deref(), Some(REDACTED));
+        assert_eq!(import_key.as_deref(), Some(REDACTED));
+        assert_eq!(call.as_deref(), Some("[{}, \"latest\"]"));
+        assert_eq!(truncated.len(), MAX_LOGGED_PARAMS_LEN + 3);
+        assert!(RequestLoggingConfig { sample_rate: 0., error_sample_rate: 0., ..Default::default() }
+            .layer()
+            .is_none());
+    }
+}
@@ -18,7 +18,24 @@
 
 //! JSON-RPC specific middleware.
 
+/// Authentication middleware.
+pub mod auth;
+/// Batch requests middleware.
+pub mod batch;
+/// OpenRPC conformance middleware.
+pub mod conformance;
+/// CORS and virtual hosts middleware.
+pub mod cors;
+/// Request/response logging middleware.
+pub mod logging;
 /// Grafana metrics middleware.
 pub mod metrics;
+/// Runtime toggling of namespaces middleware.
+pub mod namespaces;
 /// Rate limit middleware.
+pub mod rate_limit;
+/// Tracing spans middleware.
+pub mod spans;
+/// Request timeout middleware.
+pub mod timeout;
 pub use metrics::*;
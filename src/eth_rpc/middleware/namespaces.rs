@@ -0,0 +1,117 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
+
+use futures::future::{ready, Either, Ready};
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request, MethodResponse};
+
+use crate::eth_provider::error::EthApiError;
+
+/// Namespaces which can be disabled at runtime, because their methods are expensive to serve.
+pub const TOGGLEABLE_NAMESPACES: [&str; 5] = ["alchemy", "debug", "ots", "trace", "txpool"];
+
+/// Namespaces disabled at runtime, shared between the servers and the admin namespace.
+#[derive(Debug, Clone, Default)]
+pub struct DisabledNamespaces {
+    namespaces: Arc<RwLock<BTreeSet<String>>>,
+}
+
+impl DisabledNamespaces {
+    /// Enables or disables the namespace. Returns false if the namespace can't be toggled.
+    pub fn set_enabled(&self, namespace: &str, enabled: bool) -> bool {
+        if !TOGGLEABLE_NAMESPACES.contains(&namespace) {
+            return false;
+        }
+
+        let mut namespaces = self.namespaces.write().expect("Failed to lock disabled namespaces");
+        if enabled {
+            namespaces.remove(namespace);
+        } else {
+            namespaces.insert(namespace.to_string());
+        }
+        true
+    }
+
+    /// Returns the disabled namespaces, sorted alphabetically.
+    pub fn list(&self) -> Vec<String> {
+        self.namespaces.read().expect("Failed to lock disabled namespaces").iter().cloned().collect()
+    }
+
+    /// Returns the namespace of the method if it is disabled.
+    pub(crate) fn disabled_namespace<'m>(&self, method: &'m str) -> Option<&'m str> {
+        let (namespace, _) = method.split_once('_')?;
+        self.namespaces.read().expect("Failed to lock disabled namespaces").contains(namespace).then_some(namespace)
+    }
+
+    /// Returns the layer rejecting the calls to the disabled namespaces.
+    pub fn layer(&self) -> NamespaceFilterLayer {
+        NamespaceFilterLayer { disabled: self.clone() }
+    }
+}
+
+/// RPC middleware layer rejecting the calls to the methods of the disabled namespaces.
+#[derive(Debug, Clone)]
+pub struct NamespaceFilterLayer {
+    disabled: DisabledNamespaces,
+}
+
+impl<S> tower::Layer<S> for NamespaceFilterLayer {
+    type Service = NamespaceFilter<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        NamespaceFilter { service, disabled: self.disabled.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NamespaceFilter<S> {
+    service: S,
+    disabled: DisabledNamespaces,
+}
+
+impl<'a, S> RpcServiceT<'a> for NamespaceFilter<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = Either<Ready<MethodResponse>, S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if let Some(namespace) = self.disabled.disabled_namespace(req.method_name()) {
+            let err = EthApiError::NamespaceDisabled(namespace.to_string());
+            return Either::Left(ready(MethodResponse::error(req.id, err)));
+        }
+        Either::Right(self.service.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_namespaces() {
+        // Given
+        let disabled = DisabledNamespaces::default();
+
+        // When
+        assert!(disabled.set_enabled("trace", false));
+        assert!(disabled.set_enabled("debug", false));
+        assert!(disabled.set_enabled("debug", true));
+
+        // Then
+        assert_eq!(disabled.list(), vec!["trace".to_string()]);
+        assert_eq!(disabled.disabled_namespace("trace_block"), Some("trace"));
+        assert_eq!(disabled.disabled_namespace("debug_traceTransaction"), None);
+        assert_eq!(disabled.disabled_namespace("eth_call"), None);
+    }
+
+    #[test]
+    fn test_untoggleable_namespaces() {
+        // Given
+        let disabled = DisabledNamespaces::default();
+
+        // Then
+        assert!(!disabled.set_enabled("eth", false));
+        assert!(!disabled.set_enabled("admin", false));
+        assert!(disabled.list().is_empty());
+    }
+}
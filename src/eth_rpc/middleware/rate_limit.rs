@@ -0,0 +1,452 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroU32;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use eyre::{eyre, Result};
+use futures::future::{ready, Either, Ready};
+use governor::{DefaultDirectRateLimiter, DefaultKeyedRateLimiter, Quota, RateLimiter};
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request, MethodResponse};
+use tokio::task::futures::TaskLocalFuture;
+
+use crate::eth_provider::error::{EthApiError, EthRpcErrorCode};
+
+/// Number of tracked client IPs above which the IPs which didn't send requests recently are forgotten.
+const MAX_TRACKED_IPS: usize = 10_000;
+
+tokio::task_local! {
+    /// IP of the client of the HTTP request being served by the current task.
+    static CLIENT_IP: Option<IpAddr>;
+}
+
+/// Returns the IP of the client of the HTTP request being served by the current task, if known.
+pub fn request_client_ip() -> Option<IpAddr> {
+    CLIENT_IP.try_with(|ip| *ip).ok().flatten()
+}
+
+/// Rate limits of the RPC server, in requests per second. A limit of 0 disables it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// IPs of the reverse proxies whose forwarding headers are trusted to hold the client IP.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Limit for each client IP, across all methods.
+    pub per_ip: u32,
+    /// Limit for each method which doesn't have a specific limit, across all clients.
+    pub per_method: u32,
+    /// Specific limits for some methods, across all clients.
+    pub methods: HashMap<String, u32>,
+}
+
+impl RateLimitConfig {
+    /// Create a new `RateLimitConfig` from the `RPC_RATE_LIMIT_PER_IP`, `RPC_RATE_LIMIT_PER_METHOD`,
+    /// `RPC_RATE_LIMIT_METHODS` and `RPC_TRUSTED_PROXIES` environment variables. `RPC_RATE_LIMIT_METHODS`
+    /// is a comma separated list of `method=limit`, e.g. `eth_getLogs=10,eth_call=50`, and
+    /// `RPC_TRUSTED_PROXIES` a comma separated list of IPs.
+    pub fn from_env() -> Result<Self> {
+        let parse_limit = |name: &str| -> Result<u32> {
+            std::env::var(name).ok().filter(|limit| !limit.trim().is_empty()).map_or(Ok(0), |limit| {
+                u32::from_str(limit.trim()).map_err(|err| eyre!("Invalid {name} {limit}: {err}"))
+            })
+        };
+
+        Ok(Self {
+            per_ip: parse_limit("RPC_RATE_LIMIT_PER_IP")?,
+            per_method: parse_limit("RPC_RATE_LIMIT_PER_METHOD")?,
+            methods: std::env::var("RPC_RATE_LIMIT_METHODS")
+                .map_or(Ok(HashMap::new()), |methods| Self::parse_method_limits(&methods))?,
+            trusted_proxies: std::env::var("RPC_TRUSTED_PROXIES")
+                .map_or(Ok(Vec::new()), |proxies| Self::parse_trusted_proxies(&proxies))?,
+        })
+    }
+
+    /// Parses a comma separated list of IPs.
+    fn parse_trusted_proxies(proxies: &str) -> Result<Vec<IpAddr>> {
+        proxies
+            .split(',')
+            .map(str::trim)
+            .filter(|proxy| !proxy.is_empty())
+            .map(|proxy| IpAddr::from_str(proxy).map_err(|err| eyre!("Invalid trusted proxy {proxy}: {err}")))
+            .collect()
+    }
+
+    /// Parses a comma separated list of `method=limit`.
+    fn parse_method_limits(methods: &str) -> Result<HashMap<String, u32>> {
+        methods
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (method, limit) = entry
+                    .split_once('=')
+                    .ok_or_else(|| eyre!("Invalid method rate limit {entry}, expected method=limit"))?;
+                let limit =
+                    u32::from_str(limit.trim()).map_err(|err| eyre!("Invalid rate limit for {method}: {err}"))?;
+                Ok((method.trim().to_string(), limit))
+            })
+            .collect()
+    }
+
+    /// Returns the layer limiting the requests per client IP, if enabled.
+    pub fn ip_layer(&self) -> Option<IpRateLimitLayer> {
+        quota(self.per_ip).map(|quota| IpRateLimitLayer {
+            limiter: Arc::new(RateLimiter::keyed(quota)),
+            trusted_proxies: self.trusted_proxies.clone().into(),
+        })
+    }
+
+    /// Returns the layer exposing the IP of the client to the RPC methods.
+    pub fn client_ip_layer(&self) -> ClientIpLayer {
+        ClientIpLayer { trusted_proxies: self.trusted_proxies.clone().into() }
+    }
+
+    /// Returns the layer limiting the requests per method, if enabled.
+    pub fn method_layer(&self) -> Option<MethodRateLimitLayer> {
+        let methods = self
+            .methods
+            .iter()
+            .map(|(method, limit)| (method.clone(), quota(*limit).map(RateLimiter::direct)))
+            .collect::<HashMap<_, _>>();
+        let default = quota(self.per_method).map(RateLimiter::keyed);
+
+        if methods.values().all(Option::is_none) && default.is_none() {
+            return None;
+        }
+        Some(MethodRateLimitLayer { limiter: Arc::new(MethodRateLimiter { default, methods }) })
+    }
+}
+
+/// Returns the quota for a limit in requests per second, or None if the limit is disabled.
+fn quota(limit: u32) -> Option<Quota> {
+    NonZeroU32::new(limit).map(Quota::per_second)
+}
+
+/// Rate limiters for the RPC methods.
+struct MethodRateLimiter {
+    /// Limiter for the methods without a specific limit.
+    default: Option<DefaultKeyedRateLimiter<String>>,
+    /// Limiters for the methods with a specific limit. None means that the method isn't limited.
+    methods: HashMap<String, Option<DefaultDirectRateLimiter>>,
+}
+
+impl MethodRateLimiter {
+    /// Returns true if the call to the method is allowed.
+    fn check(&self, method: &str) -> bool {
+        match self.methods.get(method) {
+            Some(Some(limiter)) => limiter.check().is_ok(),
+            Some(None) => true,
+            None => self.default.as_ref().map_or(true, |limiter| limiter.check_key(&method.to_string()).is_ok()),
+        }
+    }
+}
+
+impl std::fmt::Debug for MethodRateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MethodRateLimiter").field("methods", &self.methods.keys()).finish_non_exhaustive()
+    }
+}
+
+/// RPC middleware layer limiting the rate of calls to each method.
+#[derive(Debug, Clone)]
+pub struct MethodRateLimitLayer {
+    limiter: Arc<MethodRateLimiter>,
+}
+
+impl<S> tower::Layer<S> for MethodRateLimitLayer {
+    type Service = MethodRateLimit<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        MethodRateLimit { service, limiter: self.limiter.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodRateLimit<S> {
+    service: S,
+    limiter: Arc<MethodRateLimiter>,
+}
+
+impl<'a, S> RpcServiceT<'a> for MethodRateLimit<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = Either<Ready<MethodResponse>, S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if !self.limiter.check(req.method_name()) {
+            return Either::Left(ready(MethodResponse::error(req.id, EthApiError::RateLimitExceeded)));
+        }
+        Either::Right(self.service.call(req))
+    }
+}
+
+/// HTTP middleware layer limiting the rate of requests of each client IP.
+/// The client IP is the peer address of the connection, or the one read from the forwarding headers
+/// if the peer is a trusted proxy, see [`client_ip`].
+#[derive(Clone)]
+pub struct IpRateLimitLayer {
+    limiter: Arc<DefaultKeyedRateLimiter<IpAddr>>,
+    trusted_proxies: Arc<[IpAddr]>,
+}
+
+impl std::fmt::Debug for IpRateLimitLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IpRateLimitLayer").finish_non_exhaustive()
+    }
+}
+
+impl<S> tower::Layer<S> for IpRateLimitLayer {
+    type Service = IpRateLimit<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        IpRateLimit { service, limiter: self.limiter.clone(), trusted_proxies: self.trusted_proxies.clone() }
+    }
+}
+
+#[derive(Clone)]
+pub struct IpRateLimit<S> {
+    service: S,
+    limiter: Arc<DefaultKeyedRateLimiter<IpAddr>>,
+    trusted_proxies: Arc<[IpAddr]>,
+}
+
+impl<S: std::fmt::Debug> std::fmt::Debug for IpRateLimit<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IpRateLimit").field("service", &self.service).finish_non_exhaustive()
+    }
+}
+
+impl<S, B, RB> tower::Service<http::Request<B>> for IpRateLimit<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<RB>>,
+    RB: From<String>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<S::Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        // The requests of unknown clients share the same quota
+        let ip = client_ip(&req, &self.trusted_proxies).unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        if self.limiter.len() > MAX_TRACKED_IPS {
+            self.limiter.retain_recent();
+        }
+        if self.limiter.check_key(&ip).is_err() {
+            return Either::Left(ready(Ok(rate_limit_response())));
+        }
+        Either::Right(self.service.call(req))
+    }
+}
+
+/// HTTP middleware layer exposing the IP of the client to the RPC methods, through
+/// [`request_client_ip`]. The IP is resolved as for [`IpRateLimitLayer`].
+#[derive(Debug, Clone, Default)]
+pub struct ClientIpLayer {
+    trusted_proxies: Arc<[IpAddr]>,
+}
+
+impl<S> tower::Layer<S> for ClientIpLayer {
+    type Service = ClientIp<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        ClientIp { service, trusted_proxies: self.trusted_proxies.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientIp<S> {
+    service: S,
+    trusted_proxies: Arc<[IpAddr]>,
+}
+
+impl<S, B> tower::Service<http::Request<B>> for ClientIp<S>
+where
+    S: tower::Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<Option<IpAddr>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let ip = client_ip(&req, &self.trusted_proxies);
+        CLIENT_IP.scope(ip, self.service.call(req))
+    }
+}
+
+/// Service inserting the peer address of its connection in the extensions of the requests,
+/// from which [`client_ip`] reads it. A service is built for each accepted connection.
+#[derive(Debug, Clone)]
+pub struct PeerAddr<S> {
+    service: S,
+    peer_addr: SocketAddr,
+}
+
+impl<S> PeerAddr<S> {
+    pub const fn new(service: S, peer_addr: SocketAddr) -> Self {
+        Self { service, peer_addr }
+    }
+}
+
+impl<S, B> tower::Service<http::Request<B>> for PeerAddr<S>
+where
+    S: tower::Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        req.extensions_mut().insert(self.peer_addr);
+        self.service.call(req)
+    }
+}
+
+/// Returns the IP of the client of the request, which is the peer address of its connection.
+/// If the peer is a trusted proxy, the client IP is read from the headers set by the proxies
+/// instead: the last address of `X-Forwarded-For` which isn't a trusted proxy, or `X-Real-IP`.
+/// The headers sent by other peers are ignored, since they can be forged by the clients.
+fn client_ip<B>(req: &http::Request<B>, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let peer_ip = req.extensions().get::<SocketAddr>().map(SocketAddr::ip)?;
+    if !trusted_proxies.contains(&peer_ip) {
+        return Some(peer_ip);
+    }
+
+    let headers = req.headers();
+    // Each proxy appends the address of its peer to X-Forwarded-For
+    let forwarded_for = headers.get("x-forwarded-for").and_then(|value| value.to_str().ok()).and_then(|value| {
+        value.rsplit(',').map_while(|ip| IpAddr::from_str(ip.trim()).ok()).find(|ip| !trusted_proxies.contains(ip))
+    });
+    let real_ip = || {
+        headers.get("x-real-ip").and_then(|value| value.to_str().ok()).and_then(|ip| IpAddr::from_str(ip.trim()).ok())
+    };
+
+    forwarded_for.or_else(real_ip).or(Some(peer_ip))
+}
+
+/// Returns a JSON-RPC error response for a client which exceeded its rate limit.
+fn rate_limit_response<RB: From<String>>() -> http::Response<RB> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": EthRpcErrorCode::RequestLimitExceeded as i32, "message": EthApiError::RateLimitExceeded.to_string() }
+    });
+
+    http::Response::builder()
+        .status(http::StatusCode::TOO_MANY_REQUESTS)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(RB::from(body.to_string()))
+        .expect("Failed to build rate limit response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_method_limits() {
+        // Given
+        let methods = "eth_getLogs=10, eth_chainId = 0,";
+
+        // When
+        let limits = RateLimitConfig::parse_method_limits(methods).unwrap();
+
+        // Then
+        assert_eq!(limits, HashMap::from([("eth_getLogs".to_string(), 10), ("eth_chainId".to_string(), 0)]));
+        assert!(RateLimitConfig::parse_method_limits("eth_getLogs").is_err());
+        assert!(RateLimitConfig::parse_method_limits("eth_getLogs=ten").is_err());
+    }
+
+    #[test]
+    fn test_method_rate_limiter() {
+        // Given
+        let config = RateLimitConfig {
+            per_method: 2,
+            methods: HashMap::from([("eth_getLogs".to_string(), 1), ("eth_chainId".to_string(), 0)]),
+            ..Default::default()
+        };
+        let layer = config.method_layer().unwrap();
+
+        // When & Then
+        assert!(layer.limiter.check("eth_getLogs"));
+        assert!(!layer.limiter.check("eth_getLogs"));
+        // The default limit is applied per method
+        assert!(layer.limiter.check("eth_call"));
+        assert!(layer.limiter.check("eth_call"));
+        assert!(!layer.limiter.check("eth_call"));
+        assert!(layer.limiter.check("eth_blockNumber"));
+        // A limit of 0 disables the limit for the method
+        for _ in 0..10 {
+            assert!(layer.limiter.check("eth_chainId"));
+        }
+    }
+
+    #[test]
+    fn test_disabled_rate_limits() {
+        let config = RateLimitConfig::default();
+        assert!(config.ip_layer().is_none());
+        assert!(config.method_layer().is_none());
+    }
+
+    #[test]
+    fn test_parse_trusted_proxies() {
+        assert_eq!(
+            RateLimitConfig::parse_trusted_proxies("10.0.0.1, ::1,").unwrap(),
+            vec![IpAddr::from([10, 0, 0, 1]), IpAddr::from(std::net::Ipv6Addr::LOCALHOST)]
+        );
+        assert!(RateLimitConfig::parse_trusted_proxies("localhost").is_err());
+    }
+
+    #[test]
+    fn test_client_ip() {
+        // Given
+        let proxy = IpAddr::from([192, 168, 0, 1]);
+        let peer = IpAddr::from([192, 168, 0, 2]);
+        let request = |peer_ip: Option<IpAddr>, headers: &[(&str, &str)]| {
+            let mut req = http::Request::new(());
+            if let Some(ip) = peer_ip {
+                req.extensions_mut().insert(SocketAddr::new(ip, 1234));
+            }
+            for (name, value) in headers {
+                req.headers_mut().insert(*name, value.parse().unwrap());
+            }
+            req
+        };
+        let forwarded = [("x-forwarded-for", "10.0.0.1, 10.0.0.3, 192.168.0.1"), ("x-real-ip", "10.0.0.2")];
+
+        // When & Then
+        assert_eq!(client_ip(&request(None, &forwarded), &[proxy]), None);
+        // The headers sent by an untrusted peer are ignored
+        assert_eq!(client_ip(&request(Some(peer), &forwarded), &[proxy]), Some(peer));
+        assert_eq!(client_ip(&request(Some(peer), &forwarded), &[]), Some(peer));
+        // The last address of X-Forwarded-For which isn't a trusted proxy is the client
+        assert_eq!(client_ip(&request(Some(proxy), &forwarded), &[proxy]), Some(IpAddr::from([10, 0, 0, 3])));
+        assert_eq!(client_ip(&request(Some(proxy), &forwarded[1..]), &[proxy]), Some(IpAddr::from([10, 0, 0, 2])));
+        assert_eq!(client_ip(&request(Some(proxy), &[]), &[proxy]), Some(proxy));
+    }
+
+    #[tokio::test]
+    async fn test_request_client_ip() {
+        // Given
+        let ip = IpAddr::from([10, 0, 0, 1]);
+
+        // When
+        let scoped = CLIENT_IP.scope(Some(ip), async { request_client_ip() }).await;
+
+        // Then
+        assert_eq!(scoped, Some(ip));
+        assert_eq!(request_client_ip(), None);
+    }
+}
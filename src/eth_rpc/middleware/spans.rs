@@ -0,0 +1,39 @@
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request};
+use tracing::instrument::Instrumented;
+use tracing::Instrument;
+
+/// RPC middleware layer opening a span for each call. The spans of the Starknet calls and of the
+/// database queries made by the call are nested in it, so that the call can be traced end-to-end.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcSpanLayer;
+
+impl<S> tower::Layer<S> for RpcSpanLayer {
+    type Service = RpcSpan<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcSpan { service }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcSpan<S> {
+    service: S,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcSpan<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = Instrumented<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let span = tracing::info_span!(
+            "rpc_call",
+            otel.name = %req.method_name(),
+            otel.kind = "server",
+            rpc.system = "jsonrpc",
+            rpc.method = %req.method_name(),
+        );
+        self.service.call(req).instrument(span)
+    }
+}
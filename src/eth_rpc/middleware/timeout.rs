@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use eyre::{eyre, Result};
+use futures::future::Either;
+use jsonrpsee::types::{Id, Request};
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, MethodResponse};
+use pin_project_lite::pin_project;
+use tokio::task::futures::TaskLocalFuture;
+use tokio::time::Timeout;
+
+use crate::eth_provider::error::EthApiError;
+
+tokio::task_local! {
+    /// Deadline of the RPC call being served by the current task.
+    static REQUEST_DEADLINE: Instant;
+}
+
+/// Returns the deadline of the RPC call being served by the current task, if any. Work which
+/// can't complete before the deadline, such as a retry after a long backoff, should be skipped.
+pub fn request_deadline() -> Option<Instant> {
+    REQUEST_DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// Timeouts of the RPC calls, in seconds. A timeout of 0 disables it. Once its timeout is
+/// reached, a call is cancelled: its future is dropped, which aborts the requests to the
+/// Starknet providers in flight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeoutConfig {
+    /// Timeout of the methods which don't have a specific timeout.
+    pub default: u64,
+    /// Specific timeouts for some methods.
+    pub methods: HashMap<String, u64>,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        // The transactions aren't cancelled between the deployment of the account of their sender
+        // and their relaying, and the tracing of the blocks is bounded by `TRACE_BLOCK_TIMEOUT`
+        let methods = [
+            "eth_sendRawTransaction",
+            "eth_sendTransaction",
+            "personal_sendTransaction",
+            "kakarot_requestFunds",
+            "debug_traceBlockByNumber",
+            "debug_traceBlockByHash",
+            "debug_traceBlockPage",
+        ]
+        .into_iter()
+        .map(|method| (method.to_string(), 0))
+        .collect();
+        Self { default: 30, methods }
+    }
+}
+
+impl TimeoutConfig {
+    /// Create a new `TimeoutConfig` from the `RPC_TIMEOUT` and `RPC_TIMEOUT_METHODS` environment
+    /// variables. The latter is a comma separated list of `method=timeout`, e.g.
+    /// `eth_getLogs=10,debug_traceBlockByNumber=120`, merged into the default method timeouts so
+    /// that the relaying methods stay exempted unless they are set explicitly.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        if let Some(timeout) = var("RPC_TIMEOUT") {
+            config.default =
+                u64::from_str(timeout.trim()).map_err(|err| eyre!("Invalid RPC_TIMEOUT {timeout}: {err}"))?;
+        }
+        if let Some(methods) = var("RPC_TIMEOUT_METHODS") {
+            config = config.with_method_timeouts(&methods)?;
+        }
+        Ok(config)
+    }
+
+    /// Merges the comma separated list of `method=timeout` into the method timeouts.
+    fn with_method_timeouts(mut self, methods: &str) -> Result<Self> {
+        self.methods.extend(Self::parse_method_timeouts(methods)?);
+        Ok(self)
+    }
+
+    /// Parses a comma separated list of `method=timeout`.
+    fn parse_method_timeouts(methods: &str) -> Result<HashMap<String, u64>> {
+        methods
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (method, timeout) = entry
+                    .split_once('=')
+                    .ok_or_else(|| eyre!("Invalid method timeout {entry}, expected method=timeout"))?;
+                let timeout =
+                    u64::from_str(timeout.trim()).map_err(|err| eyre!("Invalid timeout for {method}: {err}"))?;
+                Ok((method.trim().to_string(), timeout))
+            })
+            .collect()
+    }
+
+    /// Returns the timeout of the method, or None if the method isn't timed out.
+    fn timeout(&self, method: &str) -> Option<Duration> {
+        let timeout = self.methods.get(method).copied().unwrap_or(self.default);
+        (timeout > 0).then(|| Duration::from_secs(timeout))
+    }
+
+    /// Returns the layer cancelling the calls exceeding their timeout, if any method is timed out.
+    pub fn layer(&self) -> Option<TimeoutLayer> {
+        if self.default == 0 && self.methods.values().all(|timeout| *timeout == 0) {
+            return None;
+        }
+        Some(TimeoutLayer { config: Arc::new(self.clone()) })
+    }
+}
+
+/// RPC middleware layer cancelling the calls which exceed the timeout of their method.
+#[derive(Debug, Clone)]
+pub struct TimeoutLayer {
+    config: Arc<TimeoutConfig>,
+}
+
+impl<S> tower::Layer<S> for TimeoutLayer {
+    type Service = RequestTimeout<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RequestTimeout { service, config: self.config.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestTimeout<S> {
+    service: S,
+    config: Arc<TimeoutConfig>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RequestTimeout<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = Either<S::Future, ResponseFuture<'a, S::Future>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let Some(timeout) = self.config.timeout(req.method_name()) else {
+            return Either::Left(self.service.call(req));
+        };
+
+        // The deadline is made available to the futures of the call, down to the Starknet transport
+        let deadline = Instant::now() + timeout;
+        let id = req.id.clone();
+        let fut = REQUEST_DEADLINE.scope(deadline, self.service.call(req));
+        Either::Right(ResponseFuture { fut: tokio::time::timeout_at(deadline.into(), fut), id: Some(id) })
+    }
+}
+
+pin_project! {
+    /// Response future answering with a timeout error once the deadline of the call is reached.
+    pub struct ResponseFuture<'a, F> {
+        #[pin]
+        fut: Timeout<TaskLocalFuture<Instant, F>>,
+        id: Option<Id<'a>>,
+    }
+}
+
+impl<'a, F> std::fmt::Debug for ResponseFuture<'a, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ResponseFuture")
+    }
+}
+
+impl<'a, F: Future<Output = MethodResponse>> Future for ResponseFuture<'a, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        match this.fut.poll(cx) {
+            Poll::Ready(Ok(rp)) => Poll::Ready(rp),
+            Poll::Ready(Err(_)) => {
+                let id = this.id.take().expect("Polled the response future after completion");
+                Poll::Ready(MethodResponse::error(id, EthApiError::RequestTimeout))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_method_timeouts() {
+        // Given
+        let methods = "eth_getLogs=10, debug_traceBlockByNumber = 0,";
+
+        // When
+        let timeouts = TimeoutConfig::parse_method_timeouts(methods).unwrap();
+
+        // Then
+        assert_eq!(
+            timeouts,
+            HashMap::from([("eth_getLogs".to_string(), 10), ("debug_traceBlockByNumber".to_string(), 0)])
+        );
+        assert!(TimeoutConfig::parse_method_timeouts("eth_getLogs").is_err());
+        assert!(TimeoutConfig::parse_method_timeouts("eth_getLogs=ten").is_err());
+    }
+
+    #[test]
+    fn test_with_method_timeouts() {
+        // When
+        let config = TimeoutConfig::default().with_method_timeouts("eth_getLogs=10,debug_traceBlockByHash=60").unwrap();
+
+        // Then
+        assert_eq!(config.timeout("eth_getLogs"), Some(Duration::from_secs(10)));
+        assert_eq!(config.timeout("debug_traceBlockByHash"), Some(Duration::from_secs(60)));
+        assert_eq!(config.timeout("eth_sendRawTransaction"), None);
+        assert_eq!(config.timeout("personal_sendTransaction"), None);
+    }
+
+    #[test]
+    fn test_method_timeout() {
+        // Given
+        let config = TimeoutConfig {
+            default: 30,
+            methods: HashMap::from([("eth_getLogs".to_string(), 10), ("eth_sendRawTransaction".to_string(), 0)]),
+        };
+
+        // Then
+        assert_eq!(config.timeout("eth_getLogs"), Some(Duration::from_secs(10)));
+        assert_eq!(config.timeout("eth_call"), Some(Duration::from_secs(30)));
+        assert_eq!(config.timeout("eth_sendRawTransaction"), None);
+        assert!(TimeoutConfig { default: 0, methods: HashMap::new() }.layer().is_none());
+        assert_eq!(TimeoutConfig::default().timeout("eth_sendTransaction"), None);
+        assert_eq!(TimeoutConfig::default().timeout("personal_sendTransaction"), None);
+        assert_eq!(TimeoutConfig::default().timeout("kakarot_requestFunds"), None);
+    }
+
+    #[tokio::test]
+    async fn test_request_deadline() {
+        // Given
+        let deadline = Instant::now() + Duration::from_secs(1);
+
+        // When
+        let scoped = REQUEST_DEADLINE.scope(deadline, async { request_deadline() }).await;
+
+        // Then
+        assert_eq!(scoped, Some(deadline));
+        assert_eq!(request_deadline(), None);
+    }
+}
@@ -1,25 +1,48 @@
 // //! Kakarot RPC module for Ethereum.
 // //! It is an adapter layer to interact with Kakarot ZK-EVM.
 use std::net::{AddrParseError, Ipv4Addr, SocketAddr};
+use std::time::Duration;
 
 use config::RPCConfig;
 pub mod api;
 pub mod config;
+pub mod error;
+pub mod faucet;
+pub mod filters;
+pub mod graphql;
+pub mod ipc;
+pub mod keystore;
 pub mod middleware;
+pub mod openrpc;
 pub mod rpc;
 pub mod servers;
+pub mod shutdown;
+pub mod signer;
 
+use crate::eth_provider::constant::RPC_MAX_RESPONSE_SIZE;
+use crate::eth_rpc::graphql::{GraphQlLayer, GraphQlSchema};
+use crate::eth_rpc::middleware::auth::AuthConfig;
+use crate::eth_rpc::middleware::batch::BatchConfig;
+use crate::eth_rpc::middleware::conformance::ConformanceConfig;
+use crate::eth_rpc::middleware::logging::RequestLoggingConfig;
 use crate::eth_rpc::middleware::metrics::RpcMetrics;
+use crate::eth_rpc::middleware::namespaces::DisabledNamespaces;
+use crate::eth_rpc::middleware::rate_limit::{PeerAddr, RateLimitConfig};
+use crate::eth_rpc::middleware::spans::RpcSpanLayer;
+use crate::eth_rpc::middleware::timeout::TimeoutConfig;
 use crate::eth_rpc::middleware::MetricsLayer;
 use crate::prometheus_handler::init_prometheus;
 use eyre::Result;
+use futures::future::Either;
 use jsonrpsee::server::middleware::http::{InvalidPath, ProxyGetRequestLayer};
-use jsonrpsee::server::{RpcServiceBuilder, ServerBuilder, ServerHandle};
-use jsonrpsee::RpcModule;
+use jsonrpsee::server::{stop_channel, BatchRequestConfig, RpcServiceBuilder, ServerBuilder, ServerHandle};
+use jsonrpsee::{Methods, RpcModule};
 use prometheus::Registry;
 use thiserror::Error;
+use tokio::net::TcpListener;
 
-use tower_http::cors::{Any, CorsLayer};
+/// Delay before accepting connections again after a failure.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(50);
 
 #[derive(Error, Debug)]
 pub enum RpcError {
@@ -38,19 +61,40 @@ pub enum RpcError {
 /// # Errors
 ///
 /// Will return `Err` if an error occurs when running the `ServerBuilder` start fails.
+/// The metrics of the server are registered in the given registry, which is served
+/// by the prometheus exporter. The calls to the disabled namespaces are rejected. Each call
+/// opens a span if `spans` is true, e.g. when the spans are exported to a collector. The GraphQL
+/// queries are served on `/graphql` if a schema is given.
 pub async fn run_server(
     kakarot_rpc_module: RpcModule<()>,
     rpc_config: RPCConfig,
+    registry: Registry,
+    disabled_namespaces: DisabledNamespaces,
+    spans: bool,
+    graphql: Option<GraphQlSchema>,
 ) -> Result<(SocketAddr, ServerHandle), RpcError> {
-    let RPCConfig { socket_addr } = rpc_config;
+    let RPCConfig { socket_addr, cors } = rpc_config;
 
-    let cors = CorsLayer::new().allow_methods(Any).allow_origin(Any).allow_headers(Any);
+    let rate_limit_config = RateLimitConfig::from_env().expect("Failed to load rate limit config");
+    let request_logging_config = RequestLoggingConfig::from_env().expect("Failed to load request logging config");
+    let timeout_config = TimeoutConfig::from_env().expect("Failed to load timeout config");
+    let conformance_config = ConformanceConfig::from_env().expect("Failed to load the OpenRPC spec");
+    let batch_config = BatchConfig::from_env().expect("Failed to load batch config");
 
-    let http_middleware =
-        tower::ServiceBuilder::new().layer(ProxyGetRequestLayer::new("/health", "net_health")?).layer(cors);
+    // Liveness and readiness probes, served as GET requests
+    // The IP of the client is exposed to the methods limiting their calls per client, such as the faucet
+    // The GraphQL queries are answered after the CORS, host and rate limit checks
+    // The calls of a batch are executed with a bounded concurrency, within the scope of the client IP
+    let http_middleware = tower::ServiceBuilder::new()
+        .option_layer(rate_limit_config.ip_layer())
+        .layer(rate_limit_config.client_ip_layer())
+        .option_layer(cors.host_filter_layer())
+        .layer(ProxyGetRequestLayer::new("/health", "net_health")?)
+        .layer(ProxyGetRequestLayer::new("/ready", "net_ready")?)
+        .layer(cors.cors_layer().expect("Failed to build the CORS layer"))
+        .option_layer(graphql.map(GraphQlLayer::new))
+        .option_layer(batch_config.layer(&registry)?);
 
-    // Creating the prometheus registry to register the metrics
-    let registry = Registry::new();
     // register the metrics
     let metrics = RpcMetrics::new(Some(&registry))?.map(|m| MetricsLayer::new(m, "http"));
     tokio::spawn(async move {
@@ -67,13 +111,108 @@ pub async fn run_server(
     // add the metrics as a middleware to the RPC so that every new RPC call fires prometheus metrics
     // upon start, finish etc. we don't need to manually handle each method, it should automatically
     // work for any new method.
-    let rpc_middleware = RpcServiceBuilder::new().option_layer(metrics);
+    // Calls exceeding the rate limit of their method are rejected with the "limit exceeded" error code.
+    // The calls are logged first, so that the rate limited calls are logged as well.
+    // Calls exceeding the timeout of their method are cancelled and answered with a timeout error.
+    // The span of each call wraps all the other layers, so that it covers the whole call.
+    // The responses are validated against the OpenRPC spec, if any, for debugging purposes.
+    let rpc_middleware = RpcServiceBuilder::new()
+        .option_layer(spans.then_some(RpcSpanLayer))
+        .option_layer(request_logging_config.layer())
+        .option_layer(conformance_config.layer())
+        .option_layer(metrics)
+        .option_layer(rate_limit_config.method_layer())
+        .layer(disabled_namespaces.layer())
+        .option_layer(timeout_config.layer());
+
+    // Batches exceeding the maximum size are rejected with the "too big batch" error code.
+    // Setting the maximum size to 0 disables batch requests.
+    let batch_request_config = match batch_config.max_batch_size {
+        0 => BatchRequestConfig::Disabled,
+        max_batch_size => BatchRequestConfig::Limit(max_batch_size),
+    };
+
+    // Responses exceeding the maximum size are replaced by the "response too big" error
+    let service_builder = ServerBuilder::default()
+        .max_connections(get_env_or_default("RPC_MAX_CONNECTIONS", "100").parse().unwrap())
+        .max_response_body_size(*RPC_MAX_RESPONSE_SIZE)
+        .max_subscriptions_per_connection(
+            get_env_or_default("RPC_MAX_SUBSCRIPTIONS_PER_CONNECTION", "1024").parse().unwrap(),
+        )
+        .set_batch_request_config(batch_request_config)
+        .set_http_middleware(http_middleware)
+        .set_rpc_middleware(rpc_middleware)
+        .to_service_builder();
+
+    // The connections are accepted here rather than by the server, so that the peer address of
+    // each connection is known to the middlewares identifying the clients by IP
+    let listener = TcpListener::bind(socket_addr.parse::<SocketAddr>()?).await?;
+    let addr = listener.local_addr()?;
+    let methods = Methods::from(kakarot_rpc_module);
+    let (stop_handle, handle) = stop_channel();
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, peer_addr) = tokio::select! {
+                conn = listener.accept() => match conn {
+                    Ok(conn) => conn,
+                    Err(err) => {
+                        // Persistent errors, such as running out of file descriptors, would
+                        // otherwise spin the loop
+                        tracing::warn!(%err, "Failed to accept connection");
+                        tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                        continue;
+                    }
+                },
+                () = stop_handle.clone().shutdown() => break,
+            };
+
+            let service = PeerAddr::new(service_builder.clone().build(methods.clone(), stop_handle.clone()), peer_addr);
+            let stopped = stop_handle.clone().shutdown();
+            tokio::spawn(async move {
+                // The connection is shut down gracefully when the server stops: the calls in flight
+                // are answered, but the idle keep-alive connections don't hold the server open
+                let conn = hyper_014::server::conn::Http::new().serve_connection(stream, service).with_upgrades();
+                tokio::pin!(conn, stopped);
+                let res = match futures::future::select(conn, stopped).await {
+                    Either::Left((res, _)) => res,
+                    Either::Right(((), mut conn)) => {
+                        conn.as_mut().graceful_shutdown();
+                        conn.await
+                    }
+                };
+                if let Err(err) = res {
+                    tracing::debug!(%err, %peer_addr, "Failed to serve connection");
+                }
+            });
+        }
+    });
+
+    Ok((addr, handle))
+}
+
+/// Runs the authenticated server, which serves all the methods to the
+/// requests with valid credentials, except the ones of the disabled namespaces.
+///
+/// # Errors
+///
+/// Will return `Err` if an error occurs when running the `ServerBuilder` start fails.
+pub async fn run_auth_server(
+    kakarot_rpc_module: RpcModule<()>,
+    auth_config: AuthConfig,
+    disabled_namespaces: DisabledNamespaces,
+) -> Result<(SocketAddr, ServerHandle), RpcError> {
+    let http_middleware = tower::ServiceBuilder::new().layer(auth_config.layer());
+    let timeout_config = TimeoutConfig::from_env().expect("Failed to load timeout config");
+    let rpc_middleware =
+        RpcServiceBuilder::new().layer(disabled_namespaces.layer()).option_layer(timeout_config.layer());
 
     let server = ServerBuilder::default()
         .max_connections(get_env_or_default("RPC_MAX_CONNECTIONS", "100").parse().unwrap())
+        .max_response_body_size(*RPC_MAX_RESPONSE_SIZE)
         .set_http_middleware(http_middleware)
         .set_rpc_middleware(rpc_middleware)
-        .build(socket_addr.parse::<SocketAddr>()?)
+        .build(auth_config.socket_addr.parse::<SocketAddr>()?)
         .await?;
 
     let addr = server.local_addr()?;
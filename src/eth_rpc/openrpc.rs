@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use eyre::{eyre, Result};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Prefix of the references to the schemas of the components of the document.
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// Maximum number of nested schemas followed, which bounds the recursive references.
+const MAX_SCHEMA_DEPTH: usize = 64;
+
+/// Param of a method of an OpenRPC document.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MethodParam {
+    pub name: String,
+    #[serde(default)]
+    pub required: bool,
+    pub schema: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct MethodResult {
+    schema: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct Method {
+    name: String,
+    #[serde(default)]
+    params: Vec<MethodParam>,
+    result: Option<MethodResult>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Components {
+    #[serde(default)]
+    schemas: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Document {
+    methods: Vec<Method>,
+    #[serde(default)]
+    components: Components,
+}
+
+/// Violation of the schema of a method by a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// Path of the violating value, e.g. `$.transactions[0].hash`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Methods of an OpenRPC document, such as the assembled specification of the Ethereum execution
+/// APIs, against which the responses of the RPC are validated.
+///
+/// The subset of JSON Schema used by the specification is supported: references to the schemas
+/// of the components, `type`, `enum`, `pattern`, `properties`, `required`, `additionalProperties`,
+/// `items`, `allOf`, `anyOf` and `oneOf`. The alternatives of `oneOf` overlap in the specification,
+/// e.g. between the transaction types, so that `oneOf` is checked as `anyOf`.
+#[derive(Debug, Clone)]
+pub struct OpenRpcSpec {
+    params: HashMap<String, Vec<MethodParam>>,
+    results: HashMap<String, Value>,
+    schemas: Map<String, Value>,
+    patterns: HashMap<String, Regex>,
+}
+
+impl OpenRpcSpec {
+    /// Loads the OpenRPC document at the given path.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let document = std::fs::read_to_string(path)
+            .map_err(|err| eyre!("Failed to read the OpenRPC spec {}: {err}", path.display()))?;
+        Self::from_value(serde_json::from_str(&document)?)
+    }
+
+    /// Loads the given OpenRPC document.
+    pub fn from_value(document: Value) -> Result<Self> {
+        let mut patterns = HashMap::new();
+        collect_patterns(&document, &mut patterns)?;
+
+        let Document { methods, components } = serde_json::from_value(document)?;
+        let mut params = HashMap::with_capacity(methods.len());
+        let mut results = HashMap::with_capacity(methods.len());
+        for method in methods {
+            if let Some(result) = method.result {
+                results.insert(method.name.clone(), result.schema);
+            }
+            params.insert(method.name, method.params);
+        }
+
+        Ok(Self { params, results, schemas: components.schemas, patterns })
+    }
+
+    /// Returns the names of the methods of the document.
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.params.keys().map(String::as_str)
+    }
+
+    /// Returns the params of the method, if it is part of the document.
+    pub fn params(&self, method: &str) -> Option<&[MethodParam]> {
+        self.params.get(method).map(Vec::as_slice)
+    }
+
+    /// Validates the result of a call to the method against the schema of its result. Returns None
+    /// if the method isn't part of the document.
+    pub fn validate_result(&self, method: &str, result: &Value) -> Option<Vec<SchemaViolation>> {
+        let schema = self.results.get(method)?;
+        let mut violations = Vec::new();
+        self.validate(schema, result, "$", 0, &mut violations);
+        Some(violations)
+    }
+
+    fn validate(&self, schema: &Value, value: &Value, path: &str, depth: usize, violations: &mut Vec<SchemaViolation>) {
+        let Some(schema) = schema.as_object() else { return };
+        let violation = |message: String| SchemaViolation { path: path.to_string(), message };
+        if depth > MAX_SCHEMA_DEPTH {
+            violations.push(violation("schema nested too deeply".to_string()));
+            return;
+        }
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match reference.strip_prefix(SCHEMA_REF_PREFIX).and_then(|name| self.schemas.get(name)) {
+                Some(referenced) => self.validate(referenced, value, path, depth + 1, violations),
+                None => violations.push(violation(format!("unresolved reference {reference}"))),
+            }
+            return;
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let matches = match expected {
+                Value::String(expected) => is_type(value, expected),
+                Value::Array(expected) => {
+                    expected.iter().filter_map(Value::as_str).any(|expected| is_type(value, expected))
+                }
+                _ => true,
+            };
+            if !matches {
+                violations.push(violation(format!("expected type {expected}, got {value}")));
+                return;
+            }
+        }
+
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if !values.contains(value) {
+                violations.push(violation(format!("{value} isn't one of {}", Value::Array(values.clone()))));
+            }
+        }
+
+        if let (Some(pattern), Some(string)) = (schema.get("pattern").and_then(Value::as_str), value.as_str()) {
+            if self.patterns.get(pattern).is_some_and(|regex| !regex.is_match(string)) {
+                violations.push(violation(format!("\"{string}\" doesn't match the pattern {pattern}")));
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            self.validate_object(schema, object, path, depth, violations);
+        }
+
+        if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+            for (index, item) in array.iter().enumerate() {
+                self.validate(items, item, &format!("{path}[{index}]"), depth + 1, violations);
+            }
+        }
+
+        for all_of in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+            self.validate(all_of, value, path, depth + 1, violations);
+        }
+
+        for keyword in ["anyOf", "oneOf"] {
+            let Some(alternatives) = schema.get(keyword).and_then(Value::as_array) else { continue };
+            let matches = alternatives.iter().any(|alternative| {
+                let mut alternative_violations = Vec::new();
+                self.validate(alternative, value, path, depth + 1, &mut alternative_violations);
+                alternative_violations.is_empty()
+            });
+            if !matches {
+                violations
+                    .push(violation(format!("doesn't match any of the {} schemas of {keyword}", alternatives.len())));
+            }
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema: &Map<String, Value>,
+        object: &Map<String, Value>,
+        path: &str,
+        depth: usize,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+
+        for required in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+            let Some(required) = required.as_str() else { continue };
+            if !object.contains_key(required) {
+                violations.push(SchemaViolation {
+                    path: path.to_string(),
+                    message: format!("missing required field {required}"),
+                });
+            }
+        }
+
+        for (key, value) in object {
+            let field_path = format!("{path}.{key}");
+            match (properties.and_then(|properties| properties.get(key)), schema.get("additionalProperties")) {
+                (Some(property), _) => self.validate(property, value, &field_path, depth + 1, violations),
+                (None, Some(Value::Bool(false))) => {
+                    violations.push(SchemaViolation { path: field_path, message: "unexpected field".to_string() })
+                }
+                (None, Some(additional)) => self.validate(additional, value, &field_path, depth + 1, violations),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+/// Returns whether the value is of the given JSON Schema type.
+fn is_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// Compiles the patterns of the schemas of the document, so that they're compiled once.
+fn collect_patterns(value: &Value, patterns: &mut HashMap<String, Regex>) -> Result<()> {
+    match value {
+        Value::Object(object) => {
+            if let Some(pattern) = object.get("pattern").and_then(Value::as_str) {
+                if !patterns.contains_key(pattern) {
+                    let regex = Regex::new(pattern).map_err(|err| eyre!("Invalid pattern {pattern}: {err}"))?;
+                    patterns.insert(pattern.to_string(), regex);
+                }
+            }
+            object.values().try_for_each(|value| collect_patterns(value, patterns))
+        }
+        Value::Array(array) => array.iter().try_for_each(|value| collect_patterns(value, patterns)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> OpenRpcSpec {
+        OpenRpcSpec::from_value(json!({
+            "openrpc": "1.2.4",
+            "methods": [
+                {
+                    "name": "eth_getBlockByNumber",
+                    "params": [
+                        { "name": "Block", "required": true, "schema": { "$ref": "#/components/schemas/uint" } },
+                        { "name": "Hydrated transactions", "required": true, "schema": { "type": "boolean" } }
+                    ],
+                    "result": {
+                        "name": "Block information",
+                        "schema": {
+                            "oneOf": [
+                                { "$ref": "#/components/schemas/notFound" },
+                                { "$ref": "#/components/schemas/Block" }
+                            ]
+                        }
+                    }
+                }
+            ],
+            "components": {
+                "schemas": {
+                    "notFound": { "type": "null" },
+                    "uint": { "type": "string", "pattern": "^0x([1-9a-f]+[0-9a-f]*|0)$" },
+                    "hash32": { "type": "string", "pattern": "^0x[0-9a-f]{64}$" },
+                    "Block": {
+                        "type": "object",
+                        "required": ["hash", "number"],
+                        "additionalProperties": false,
+                        "properties": {
+                            "hash": { "$ref": "#/components/schemas/hash32" },
+                            "number": { "$ref": "#/components/schemas/uint" },
+                            "transactions": { "type": "array", "items": { "$ref": "#/components/schemas/hash32" } }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_result() {
+        // Given
+        let spec = spec();
+        let hash = format!("0x{}", "a".repeat(64));
+
+        // When
+        let valid = spec.validate_result("eth_getBlockByNumber", &json!({ "hash": hash, "number": "0x1" }));
+        let not_found = spec.validate_result("eth_getBlockByNumber", &Value::Null);
+        let unknown = spec.validate_result("eth_unknown", &Value::Null);
+
+        // Then
+        assert_eq!(valid, Some(vec![]));
+        assert_eq!(not_found, Some(vec![]));
+        assert_eq!(unknown, None);
+        assert_eq!(spec.params("eth_getBlockByNumber").unwrap()[1].name, "Hydrated transactions");
+        assert_eq!(spec.methods().collect::<Vec<_>>(), vec!["eth_getBlockByNumber"]);
+    }
+
+    #[test]
+    fn test_validate_violations() {
+        // Given
+        let spec = spec();
+        let block = json!({ "hash": "0x1", "number": "0x01", "transactions": [1], "extra": true });
+        let schema = json!({ "$ref": "#/components/schemas/Block" });
+
+        // When
+        let mut violations = Vec::new();
+        spec.validate(&schema, &block, "$", 0, &mut violations);
+        let result = spec.validate_result("eth_getBlockByNumber", &block).unwrap();
+
+        // Then
+        // The fields are visited in the order of the map of the object, which depends on the features of serde_json
+        let mut messages = violations.iter().map(ToString::to_string).collect::<Vec<_>>();
+        messages.sort();
+        assert_eq!(
+            messages,
+            vec![
+                "$.extra: unexpected field",
+                "$.hash: \"0x1\" doesn't match the pattern ^0x[0-9a-f]{64}$",
+                "$.number: \"0x01\" doesn't match the pattern ^0x([1-9a-f]+[0-9a-f]*|0)$",
+                "$.transactions[0]: expected type \"string\", got 1",
+            ]
+        );
+        assert_eq!(
+            result,
+            vec![SchemaViolation {
+                path: "$".to_string(),
+                message: "doesn't match any of the 2 schemas of oneOf".to_string()
+            }]
+        );
+    }
+}
@@ -1,33 +1,113 @@
-use std::collections::HashMap;
-use std::marker::PhantomData;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use jsonrpsee::server::RegisterMethodError;
 use jsonrpsee::{Methods, RpcModule};
 
 use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_provider::starknet::katana::KatanaDevClient;
+use crate::eth_provider::starknet::transport::ProviderHealth;
+use crate::eth_rpc::api::admin_api::AdminApiServer;
 use crate::eth_rpc::api::alchemy_api::AlchemyApiServer;
 use crate::eth_rpc::api::debug_api::DebugApiServer;
+use crate::eth_rpc::api::dev_api::DevApiServer;
 use crate::eth_rpc::api::eth_api::EthApiServer;
+use crate::eth_rpc::api::faucet_api::FaucetApiServer;
+use crate::eth_rpc::api::kakarot_api::KakarotApiServer;
 use crate::eth_rpc::api::net_api::NetApiServer;
+use crate::eth_rpc::api::ots_api::OtterscanApiServer;
+use crate::eth_rpc::api::personal_api::PersonalApiServer;
+use crate::eth_rpc::api::pubsub_api::EthPubSubApiServer;
 use crate::eth_rpc::api::trace_api::TraceApiServer;
+use crate::eth_rpc::api::txpool_api::TxPoolApiServer;
 use crate::eth_rpc::api::web3_api::Web3ApiServer;
+use crate::eth_rpc::faucet::Faucet;
+use crate::eth_rpc::keystore::Keystore;
+use crate::eth_rpc::middleware::namespaces::DisabledNamespaces;
+use crate::eth_rpc::servers::admin_rpc::{AdminRpc, LogFilterHandle};
 use crate::eth_rpc::servers::alchemy_rpc::AlchemyRpc;
 use crate::eth_rpc::servers::debug_rpc::DebugRpc;
+use crate::eth_rpc::servers::dev_rpc::{DevRpc, ImpersonatedAccounts};
 use crate::eth_rpc::servers::eth_rpc::KakarotEthRpc;
+use crate::eth_rpc::servers::faucet_rpc::FaucetRpc;
+use crate::eth_rpc::servers::kakarot_rpc::KakarotRpc;
 use crate::eth_rpc::servers::net_rpc::NetRpc;
+use crate::eth_rpc::servers::ots_rpc::OtterscanRpc;
+use crate::eth_rpc::servers::personal_rpc::PersonalRpc;
+use crate::eth_rpc::servers::pubsub_rpc::EthPubSubRpc;
+use crate::eth_rpc::servers::starknet_rpc::StarknetRpc;
 use crate::eth_rpc::servers::trace_rpc::TraceRpc;
+use crate::eth_rpc::servers::txpool_rpc::TxpoolRpc;
 use crate::eth_rpc::servers::web3_rpc::Web3Rpc;
+use crate::eth_rpc::signer::Signers;
+use crate::tracing::cache::TraceCache;
 
 /// Represents RPC modules that are supported by reth
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum KakarotRpcModule {
     Eth,
+    EthPubSub,
     Alchemy,
     Web3,
     Net,
     Debug,
     Trace,
+    Txpool,
+    Otterscan,
+    Kakarot,
+    Admin,
+    Dev,
+    Personal,
+    Faucet,
+    Starknet,
+}
+
+impl KakarotRpcModule {
+    /// All the modules.
+    pub const ALL: [Self; 15] = [
+        Self::Eth,
+        Self::EthPubSub,
+        Self::Alchemy,
+        Self::Web3,
+        Self::Net,
+        Self::Debug,
+        Self::Trace,
+        Self::Txpool,
+        Self::Otterscan,
+        Self::Kakarot,
+        Self::Admin,
+        Self::Dev,
+        Self::Personal,
+        Self::Faucet,
+        Self::Starknet,
+    ];
+
+    /// Returns the name of the API of the module, as listed in the `--http.api` flag.
+    pub const fn api(&self) -> &'static str {
+        match self {
+            Self::Eth | Self::EthPubSub => "eth",
+            Self::Alchemy => "alchemy",
+            Self::Web3 => "web3",
+            Self::Net => "net",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+            Self::Txpool => "txpool",
+            Self::Otterscan => "ots",
+            Self::Kakarot => "kakarot",
+            Self::Admin => "admin",
+            Self::Dev => "dev",
+            Self::Personal => "personal",
+            Self::Faucet => "faucet",
+            Self::Starknet => "starknet",
+        }
+    }
+
+    /// Returns the modules of the API with the given name, or None if the API is unknown.
+    pub fn from_api(api: &str) -> Option<Vec<Self>> {
+        let modules = Self::ALL.into_iter().filter(|module| module.api() == api).collect::<Vec<_>>();
+        (!modules.is_empty()).then_some(modules)
+    }
 }
 
 #[derive(Debug)]
@@ -36,7 +116,11 @@ where
     P: EthereumProvider + Send + Sync,
 {
     modules: HashMap<KakarotRpcModule, Methods>,
-    _phantom: PhantomData<P>,
+    eth_provider: Arc<P>,
+    /// The accounts impersonated through the dev module, if served.
+    impersonated_accounts: Option<ImpersonatedAccounts>,
+    /// The accounts whose transactions and messages are signed by the eth module.
+    signers: Option<Arc<Signers>>,
 }
 
 impl<P> KakarotRpcModuleBuilder<P>
@@ -46,29 +130,136 @@ where
     pub fn new(eth_provider: P) -> Self {
         let eth_provider = Arc::new(eth_provider);
         let eth_rpc_module = KakarotEthRpc::new(eth_provider.clone()).into_rpc();
+        let eth_pubsub_rpc_module = EthPubSubRpc::new(eth_provider.clone()).into_rpc();
         let alchemy_rpc_module = AlchemyRpc::new(eth_provider.clone()).into_rpc();
         let web3_rpc_module = Web3Rpc::default().into_rpc();
         let net_rpc_module = NetRpc::new(eth_provider.clone()).into_rpc();
         let debug_rpc_module = DebugRpc::new(eth_provider.clone()).into_rpc();
-        let trace_rpc_module = TraceRpc::new(eth_provider).into_rpc();
+        let trace_rpc_module = TraceRpc::new(eth_provider.clone()).into_rpc();
+        let txpool_rpc_module = TxpoolRpc::new(eth_provider.clone()).into_rpc();
+        let otterscan_rpc_module = OtterscanRpc::new(eth_provider.clone()).into_rpc();
+        let kakarot_rpc_module = KakarotRpc::new(eth_provider.clone()).into_rpc();
 
         let mut modules = HashMap::new();
 
         modules.insert(KakarotRpcModule::Eth, eth_rpc_module.into());
+        modules.insert(KakarotRpcModule::EthPubSub, eth_pubsub_rpc_module.into());
         modules.insert(KakarotRpcModule::Alchemy, alchemy_rpc_module.into());
         modules.insert(KakarotRpcModule::Web3, web3_rpc_module.into());
         modules.insert(KakarotRpcModule::Net, net_rpc_module.into());
         modules.insert(KakarotRpcModule::Debug, debug_rpc_module.into());
         modules.insert(KakarotRpcModule::Trace, trace_rpc_module.into());
+        modules.insert(KakarotRpcModule::Txpool, txpool_rpc_module.into());
+        modules.insert(KakarotRpcModule::Otterscan, otterscan_rpc_module.into());
+        modules.insert(KakarotRpcModule::Kakarot, kakarot_rpc_module.into());
+
+        Self { modules, eth_provider, impersonated_accounts: None, signers: None }
+    }
+
+    /// Replaces the net module with one reporting the number of healthy Starknet providers
+    /// as `net_peerCount` and the server status as `net_listening`.
+    pub fn with_net_status(
+        mut self,
+        provider_health: Option<ProviderHealth>,
+        listening: Option<Arc<AtomicBool>>,
+    ) -> Self {
+        let net_rpc_module = NetRpc::new(self.eth_provider.clone()).with_status(provider_health, listening).into_rpc();
+        self.modules.insert(KakarotRpcModule::Net, net_rpc_module.into());
+        self
+    }
+
+    /// Adds the admin module. It must only be served by the authenticated server.
+    pub fn with_admin(
+        mut self,
+        provider_health: Option<ProviderHealth>,
+        log_filter: Option<LogFilterHandle>,
+        disabled_namespaces: DisabledNamespaces,
+    ) -> Self {
+        let admin_rpc_module =
+            AdminRpc::new(self.eth_provider.clone(), provider_health, log_filter, disabled_namespaces).into_rpc();
+        self.modules.insert(KakarotRpcModule::Admin, admin_rpc_module.into());
+        self
+    }
+
+    /// Replaces the debug and trace modules with ones caching the transaction traces.
+    pub fn with_trace_cache(mut self, trace_cache: TraceCache) -> Self {
+        let debug_rpc_module =
+            DebugRpc::new(self.eth_provider.clone()).with_trace_cache(trace_cache.clone()).into_rpc();
+        self.modules.insert(KakarotRpcModule::Debug, debug_rpc_module.into());
+        let trace_rpc_module = TraceRpc::new(self.eth_provider.clone()).with_trace_cache(trace_cache).into_rpc();
+        self.modules.insert(KakarotRpcModule::Trace, trace_rpc_module.into());
+        self
+    }
 
-        Self { modules, _phantom: PhantomData }
+    /// Adds the Anvil compatible dev module, backed by the dev API of Katana. The eth module is
+    /// replaced by one sending the transactions of the impersonated accounts.
+    pub fn with_dev(mut self, katana: KatanaDevClient) -> Self {
+        let impersonated_accounts = ImpersonatedAccounts::default();
+        self.impersonated_accounts = Some(impersonated_accounts.clone());
+        self.replace_eth_module();
+        let dev_rpc_module = DevRpc::new(katana, impersonated_accounts).into_rpc();
+        self.modules.insert(KakarotRpcModule::Dev, dev_rpc_module.into());
+        self
+    }
+
+    /// Replaces the eth module with one signing the transactions and messages of the accounts of
+    /// the signers, which are returned by `eth_accounts`.
+    pub fn with_signers(mut self, signers: Signers) -> Self {
+        self.signers = Some(Arc::new(signers));
+        self.replace_eth_module();
+        self
+    }
+
+    /// Adds the personal module, managing the accounts of the keystore.
+    pub fn with_personal(mut self, keystore: Keystore) -> Self {
+        let personal_rpc_module = PersonalRpc::new(self.eth_provider.clone(), keystore).into_rpc();
+        self.modules.insert(KakarotRpcModule::Personal, personal_rpc_module.into());
+        self
+    }
+
+    /// Adds the faucet module, serving `kakarot_requestFunds`.
+    pub fn with_faucet(mut self, faucet: Arc<Faucet>) -> Self {
+        let faucet_rpc_module = FaucetRpc::new(self.eth_provider.clone(), faucet).into_rpc();
+        self.modules.insert(KakarotRpcModule::Faucet, faucet_rpc_module.into());
+        self
+    }
+
+    /// Adds the starknet module, proxying the `starknet_` methods to the Starknet provider.
+    pub fn with_starknet_passthrough(mut self, starknet_rpc: StarknetRpc) -> Self {
+        self.modules.insert(KakarotRpcModule::Starknet, starknet_rpc.into_rpc().into());
+        self
+    }
+
+    /// Replaces the eth module with one sending the transactions of the impersonated accounts and
+    /// of the signers.
+    fn replace_eth_module(&mut self) {
+        let mut eth_rpc = KakarotEthRpc::new(self.eth_provider.clone());
+        if let Some(impersonated_accounts) = &self.impersonated_accounts {
+            eth_rpc = eth_rpc.with_impersonated_accounts(impersonated_accounts.clone());
+        }
+        if let Some(signers) = &self.signers {
+            eth_rpc = eth_rpc.with_signers(signers.clone());
+        }
+        self.modules.insert(KakarotRpcModule::Eth, eth_rpc.into_rpc().into());
     }
 
     pub fn rpc_module(&self) -> Result<RpcModule<()>, RegisterMethodError> {
+        self.rpc_module_with_apis(None)
+    }
+
+    /// Returns the module serving the methods of the given modules, or of all the modules if None.
+    /// The modules which weren't added to the builder aren't served, even if they're given.
+    pub fn rpc_module_with_apis(
+        &self,
+        apis: Option<&HashSet<KakarotRpcModule>>,
+    ) -> Result<RpcModule<()>, RegisterMethodError> {
         let mut rpc_module = RpcModule::new(());
 
-        for methods in self.modules.values().cloned() {
-            rpc_module.merge(methods)?;
+        for (module, methods) in &self.modules {
+            if apis.is_some_and(|apis| !apis.contains(module)) {
+                continue;
+            }
+            rpc_module.merge(methods.clone())?;
         }
 
         Ok(rpc_module)
@@ -0,0 +1,83 @@
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use reth_rpc_types::Transaction;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::eth_provider::error::EthApiError;
+use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_provider::starknet::transport::ProviderHealth;
+use crate::eth_rpc::api::admin_api::AdminApiServer;
+use crate::eth_rpc::middleware::namespaces::{DisabledNamespaces, TOGGLEABLE_NAMESPACES};
+use crate::models::admin::ProviderStatus;
+
+/// Handle replacing the log filter of the subscriber at runtime.
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// The RPC module for implementing the Admin api
+#[derive(Debug)]
+pub struct AdminRpc<P: EthereumProvider> {
+    eth_provider: P,
+    /// Health of the Starknet providers, if they are JSON-RPC providers.
+    provider_health: Option<ProviderHealth>,
+    /// Handle on the log filter, if it can be reloaded.
+    log_filter: Option<LogFilterHandle>,
+    /// Namespaces disabled by the operators.
+    disabled_namespaces: DisabledNamespaces,
+}
+
+impl<P: EthereumProvider> AdminRpc<P> {
+    pub const fn new(
+        eth_provider: P,
+        provider_health: Option<ProviderHealth>,
+        log_filter: Option<LogFilterHandle>,
+        disabled_namespaces: DisabledNamespaces,
+    ) -> Self {
+        Self { eth_provider, provider_health, log_filter, disabled_namespaces }
+    }
+}
+
+#[async_trait]
+impl<P: EthereumProvider + Send + Sync + 'static> AdminApiServer for AdminRpc<P> {
+    fn provider_health(&self) -> Result<Vec<ProviderStatus>> {
+        let states = self.provider_health.as_ref().map(ProviderHealth::states).unwrap_or_default();
+        Ok(states.into_iter().enumerate().map(|(index, state)| ProviderStatus { index, state }).collect())
+    }
+
+    fn flush_caches(&self) -> Result<usize> {
+        let flushed = self.eth_provider.flush_caches();
+        tracing::info!("Flushed {flushed} cached responses");
+        Ok(flushed)
+    }
+
+    async fn dump_mempool(&self) -> Result<Vec<Transaction>> {
+        Ok(self.eth_provider.pending_transactions().await?)
+    }
+
+    async fn load_mempool(&self, transactions: Vec<Transaction>) -> Result<usize> {
+        let loaded = self.eth_provider.load_pending_transactions(transactions).await?;
+        tracing::info!("Loaded {loaded} pending transactions");
+        Ok(loaded)
+    }
+
+    fn set_log_level(&self, filter: String) -> Result<bool> {
+        let log_filter = self.log_filter.as_ref().ok_or(EthApiError::Unsupported("log filter reload"))?;
+        let new_filter = EnvFilter::try_new(&filter)
+            .map_err(|err| EthApiError::InvalidParams(format!("invalid log filter {filter}: {err}")))?;
+        log_filter
+            .reload(new_filter)
+            .map_err(|err| EthApiError::InvalidParams(format!("failed to reload the log filter: {err}")))?;
+        tracing::info!("Log filter set to {filter}");
+        Ok(true)
+    }
+
+    fn set_namespace_enabled(&self, namespace: String, enabled: bool) -> Result<Vec<String>> {
+        if !self.disabled_namespaces.set_enabled(&namespace, enabled) {
+            return Err(EthApiError::InvalidParams(format!(
+                "namespace {namespace} can't be toggled, expected one of {}",
+                TOGGLEABLE_NAMESPACES.join(", ")
+            ))
+            .into());
+        }
+        tracing::info!("Namespace {namespace} {}", if enabled { "enabled" } else { "disabled" });
+        Ok(self.disabled_namespaces.list())
+    }
+}
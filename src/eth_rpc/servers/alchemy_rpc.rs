@@ -1,10 +1,26 @@
-use futures::future::join_all;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use futures::StreamExt;
+use itertools::Itertools;
 use jsonrpsee::core::{async_trait, RpcResult as Result};
-use reth_primitives::{Address, BlockId, BlockNumberOrTag};
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, U64};
 
-use crate::eth_provider::contracts::erc20::EthereumErc20;
+use crate::eth_provider::constant::{
+    MAX_ASSET_TRANSFERS, MAX_NFTS_PAGE_SIZE, MAX_TOKEN_BALANCES_ADDRESSES, TOKEN_BALANCES_CONCURRENCY,
+};
+use crate::eth_provider::contracts::erc20::{balance_of_calldata, decode_balance, EthereumErc20};
+use crate::eth_provider::contracts::multicall::Multicall;
+use crate::eth_provider::error::EthApiError;
 use crate::eth_rpc::api::alchemy_api::AlchemyApiServer;
-use crate::models::balance::FutureTokenBalance;
+use crate::models::balance::{FutureTokenBalance, TokenBalance};
+use crate::models::nft::{NftOwnershipHistoryRequest, NftsRequest, OwnedNft, OwnedNfts};
+use crate::models::pagination::{Cursor, Page};
+use crate::models::token::TokenMetadata;
+use crate::models::transfer::{
+    AssetTransfer, AssetTransfers, AssetTransfersRequest, TokenTransfer, TransferCategory, TransferFilter,
+    TransferOrder,
+};
 use crate::{eth_provider::provider::EthereumProvider, models::balance::TokenBalances};
 
 /// The RPC module for the Ethereum protocol required by Kakarot.
@@ -23,7 +39,36 @@ impl<P: EthereumProvider> AlchemyRpc<P> {
 impl<P: EthereumProvider + Send + Sync + 'static> AlchemyApiServer for AlchemyRpc<P> {
     #[tracing::instrument(skip_all, ret, fields(address = %address, token_addresses = ?token_addresses))]
     async fn token_balances(&self, address: Address, token_addresses: Vec<Address>) -> Result<TokenBalances> {
-        let block_id = BlockId::Number(BlockNumberOrTag::Latest);
+        if token_addresses.len() > MAX_TOKEN_BALANCES_ADDRESSES {
+            return Err(EthApiError::TokenAddressesLimitExceeded(MAX_TOKEN_BALANCES_ADDRESSES).into());
+        }
+
+        // Read all the balances at the same block, even if a new block is produced in the meantime
+        let block_number = self.eth_provider.block_number().await?;
+        let block_id = BlockId::Number(BlockNumberOrTag::Number(block_number.to()));
+
+        // Read all the balances in batches through the Multicall3 contract
+        let calls = token_addresses.iter().map(|token_addr| (*token_addr, balance_of_calldata(address))).collect();
+        if let Ok(outputs) = Multicall::new(&self.eth_provider).aggregate(calls, block_id).await {
+            let token_balances = token_addresses
+                .into_iter()
+                .zip(outputs)
+                .map(|(token_address, output)| match output.map(|output| decode_balance(&output)) {
+                    Some(Ok(balance)) => TokenBalance { token_address, token_balance: Some(balance), error: None },
+                    Some(Err(error)) => {
+                        TokenBalance { token_address, token_balance: None, error: Some(error.to_string()) }
+                    }
+                    None => TokenBalance {
+                        token_address,
+                        token_balance: None,
+                        error: Some("execution reverted".to_string()),
+                    },
+                })
+                .collect();
+            return Ok(TokenBalances { address, token_balances });
+        }
+
+        // Fall back to one call per token if the Multicall3 contract isn't available
         let handles = token_addresses.into_iter().map(|token_addr| {
             let token = EthereumErc20::new(token_addr, &self.eth_provider);
             let balance = token.balance_of(address, block_id);
@@ -31,8 +76,138 @@ impl<P: EthereumProvider + Send + Sync + 'static> AlchemyApiServer for AlchemyRp
             FutureTokenBalance::new(Box::pin(balance), token_addr)
         });
 
-        let token_balances = join_all(handles).await;
+        let token_balances = futures::stream::iter(handles).buffered(TOKEN_BALANCES_CONCURRENCY).collect().await;
 
         Ok(TokenBalances { address, token_balances })
     }
+
+    #[tracing::instrument(skip(self), ret)]
+    async fn token_metadata(&self, contract_address: Address) -> Result<TokenMetadata> {
+        let block_id = BlockId::Number(BlockNumberOrTag::Latest);
+        let token = EthereumErc20::new(contract_address, &self.eth_provider);
+        Ok(token.metadata(block_id).await?)
+    }
+
+    #[tracing::instrument(skip(self), ret)]
+    async fn asset_transfers(&self, request: AssetTransfersRequest) -> Result<AssetTransfers> {
+        let categories = request
+            .category
+            .iter()
+            .map(|category| TransferCategory::from_str(category))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(EthApiError::InvalidParams)?;
+        if categories.is_empty() {
+            return Err(EthApiError::InvalidParams("category must not be empty".to_string()).into());
+        }
+        let limit = page_limit(request.max_count, MAX_ASSET_TRANSFERS, "maxCount")?;
+
+        let latest = self.eth_provider.block_number().await?.to();
+        let block_number = |tag: Option<BlockNumberOrTag>, default: u64| match tag {
+            None => default,
+            Some(BlockNumberOrTag::Number(number)) => number,
+            Some(BlockNumberOrTag::Earliest) => 0,
+            Some(_) => latest,
+        };
+        let filter = TransferFilter {
+            from_block: block_number(request.from_block, 0),
+            to_block: block_number(request.to_block, latest),
+            from_address: request.from_address,
+            to_address: request.to_address,
+            contract_addresses: request.contract_addresses,
+            categories,
+            token_id: None,
+            descending: request.order == TransferOrder::Desc,
+            exclude_zero_value: request.exclude_zero_value,
+        };
+        let page = self.eth_provider.token_transfers(filter, request.page_key, limit).await?;
+
+        Ok(self.asset_transfers_page(page, request.with_metadata).await)
+    }
+
+    #[tracing::instrument(skip(self), fields(owner = %request.owner))]
+    async fn nfts(&self, request: NftsRequest) -> Result<OwnedNfts> {
+        let page_size = page_limit(request.page_size, MAX_NFTS_PAGE_SIZE, "pageSize")?;
+
+        // The key of a page pins the block of the first page, so that the pages don't overlap
+        let (block_number, offset) = match request.page_key {
+            Some(page_key) => (page_key.block_number, page_key.index),
+            None => (self.eth_provider.block_number().await?.to(), 0),
+        };
+        let balances = self.eth_provider.nft_balances(request.owner, request.contract_addresses, block_number).await?;
+        let total_count = balances.len() as u64;
+        let next_offset = offset.saturating_add(page_size);
+        let page_key = (next_offset < total_count).then(|| Cursor::new(block_number, next_offset));
+
+        let with_metadata = request.with_metadata;
+        let page = balances.into_iter().skip(usize::try_from(offset).unwrap_or(usize::MAX)).take(page_size as usize);
+        let owned_nfts = futures::stream::iter(page)
+            .map(|balance| async move {
+                let token_uri = if with_metadata {
+                    let (address, token_id, category) = (balance.contract_address, balance.token_id, balance.category);
+                    self.eth_provider.token_uri(address, token_id, category, block_number).await.ok().flatten()
+                } else {
+                    None
+                };
+                OwnedNft::new(balance, token_uri)
+            })
+            .buffered(TOKEN_BALANCES_CONCURRENCY)
+            .collect()
+            .await;
+
+        Ok(OwnedNfts { owned_nfts, page_key, total_count: U64::from(total_count) })
+    }
+
+    #[tracing::instrument(skip(self), ret)]
+    async fn nft_ownership_history(&self, request: NftOwnershipHistoryRequest) -> Result<AssetTransfers> {
+        let limit = page_limit(request.max_count, MAX_ASSET_TRANSFERS, "maxCount")?;
+        let filter = TransferFilter {
+            from_block: 0,
+            to_block: self.eth_provider.block_number().await?.to(),
+            contract_addresses: vec![request.contract_address],
+            categories: vec![TransferCategory::Erc721, TransferCategory::Erc1155],
+            token_id: Some(request.token_id),
+            descending: request.order == TransferOrder::Desc,
+            ..Default::default()
+        };
+        let page = self.eth_provider.token_transfers(filter, request.page_key, limit).await?;
+
+        Ok(self.asset_transfers_page(page, true).await)
+    }
+}
+
+impl<P: EthereumProvider + Send + Sync + 'static> AlchemyRpc<P> {
+    /// Converts a page of token transfers to the format of `alchemy_getAssetTransfers`, reading
+    /// the symbol and the decimals of each token once.
+    async fn asset_transfers_page(&self, page: Page<TokenTransfer>, with_metadata: bool) -> AssetTransfers {
+        let block_id = BlockId::Number(BlockNumberOrTag::Latest);
+        let tokens = page.items.iter().map(|transfer| transfer.address).unique().collect::<Vec<_>>();
+        let metadata: HashMap<Address, TokenMetadata> = futures::stream::iter(tokens)
+            .map(|token| async move {
+                let metadata = EthereumErc20::new(token, &self.eth_provider).metadata(block_id).await;
+                (token, metadata.unwrap_or_default())
+            })
+            .buffer_unordered(TOKEN_BALANCES_CONCURRENCY)
+            .collect()
+            .await;
+
+        let transfers = page
+            .items
+            .into_iter()
+            .map(|transfer| {
+                let token = metadata.get(&transfer.address).cloned().unwrap_or_default();
+                AssetTransfer::new(transfer, token.symbol, token.decimals, with_metadata)
+            })
+            .collect();
+
+        AssetTransfers { transfers, page_key: page.next_cursor }
+    }
+}
+
+/// Returns the size of a page, which defaults to and can't exceed `max`.
+fn page_limit(size: Option<U64>, max: u64, name: &str) -> std::result::Result<u64, EthApiError> {
+    let size = size.map_or(max, |size| size.to());
+    if size == 0 || size > max {
+        return Err(EthApiError::InvalidParams(format!("{name} must be between 1 and {max}")));
+    }
+    Ok(size)
 }
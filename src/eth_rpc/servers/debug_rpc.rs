@@ -1,25 +1,97 @@
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use alloy_rlp::Encodable;
 use jsonrpsee::core::{async_trait, RpcResult as Result};
 use reth_primitives::{Block, Bytes, Header, Log, Receipt, ReceiptWithBloom, TransactionSigned, B256};
-use reth_rpc_types::trace::geth::{GethDebugTracingOptions, TraceResult};
-use reth_rpc_types::{BlockId, BlockNumberOrTag};
+use reth_rpc_types::trace::geth::{GethDebugTracingOptions, GethTrace, TraceResult};
+use reth_rpc_types::{BlockId, BlockNumberOrTag, BlockOverrides};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
+use crate::eth_provider::constant::{
+    MAX_CALL_BUNDLE_SIZE, RPC_MAX_RESPONSE_SIZE, TRACE_BLOCK_MAX_CONCURRENCY, TRACE_BLOCK_TIMEOUT,
+};
 use crate::eth_provider::error::{EthApiError, EthereumDataFormatError, SignatureError};
 use crate::eth_rpc::api::debug_api::DebugApiServer;
-use crate::tracing::builder::TracerBuilder;
+use crate::models::call_bundle::BundleCall;
+use crate::models::pagination::{Cursor, Page};
+use crate::tracing::builder::{Pinned, TracerBuilder};
+use crate::tracing::cache::{geth_trace_kind, TraceCache};
+use crate::tracing::{Tracer, TracerResult};
 use crate::{eth_provider::provider::EthereumProvider, models::transaction::rpc_to_primitive_transaction};
 
 /// The RPC module for the implementing Net api
 #[derive(Debug)]
 pub struct DebugRpc<P: EthereumProvider> {
     eth_provider: P,
+    /// Bounds the number of blocks traced concurrently.
+    block_tracing_permits: Semaphore,
+    /// Cache of the transaction traces.
+    trace_cache: Option<TraceCache>,
 }
 
 impl<P: EthereumProvider> DebugRpc<P> {
-    pub const fn new(eth_provider: P) -> Self {
-        Self { eth_provider }
+    pub fn new(eth_provider: P) -> Self {
+        Self {
+            eth_provider,
+            block_tracing_permits: Semaphore::new((*TRACE_BLOCK_MAX_CONCURRENCY).max(1)),
+            trace_cache: None,
+        }
+    }
+
+    /// Caches the traces returned by `debug_traceTransaction`.
+    pub fn with_trace_cache(mut self, trace_cache: TraceCache) -> Self {
+        self.trace_cache = Some(trace_cache);
+        self
+    }
+}
+
+impl<P: EthereumProvider + Send + Sync> DebugRpc<P> {
+    /// Replays all the transactions of the block and returns their Geth debug traces.
+    /// At most [`TRACE_BLOCK_MAX_CONCURRENCY`] blocks are traced concurrently, and the
+    /// tracing fails if it lasts more than [`TRACE_BLOCK_TIMEOUT`] seconds or if the traces
+    /// exceed [`RPC_MAX_RESPONSE_SIZE`] bytes.
+    async fn trace_block(
+        &self,
+        block_id: BlockId,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> Result<Option<Vec<TraceResult>>> {
+        let Some((_permit, tracer)) = self.block_tracer(block_id).await? else {
+            return Ok(None);
+        };
+        Ok(tracer.debug_block(opts.unwrap_or_default())?)
+    }
+
+    /// Returns the tracer of the block, bounded by the tracing timeout and the maximum response
+    /// size, along with the block tracing permit to hold until the tracing ends.
+    async fn block_tracer(&self, block_id: BlockId) -> Result<Option<(SemaphorePermit<'_>, Tracer<Arc<&P>>)>> {
+        let (permit, builder) = self.block_tracer_builder(block_id).await?;
+        let tracer = builder.with_max_response_size(*RPC_MAX_RESPONSE_SIZE as usize).build()?;
+        Ok(tracer.map(|tracer| (permit, tracer)))
+    }
+
+    /// Acquires a block tracing permit and returns the tracer builder pinned to the block. The
+    /// wait for the permit and the fetching of the block count towards the tracing timeout, so
+    /// that a slow provider can't hold the permit past the deadline.
+    async fn block_tracer_builder(
+        &self,
+        block_id: BlockId,
+    ) -> TracerResult<(SemaphorePermit<'_>, TracerBuilder<Arc<&P>, Pinned>)> {
+        let deadline = (*TRACE_BLOCK_TIMEOUT > 0).then(|| Instant::now() + Duration::from_secs(*TRACE_BLOCK_TIMEOUT));
+        let (permit, builder) = before_deadline(deadline, async {
+            let permit = self.block_tracing_permits.acquire().await.expect("Block tracing semaphore closed");
+            let provider = Arc::new(&self.eth_provider);
+            let builder = TracerBuilder::new(provider).await?.with_block_id(block_id).await?;
+            Ok((permit, builder))
+        })
+        .await?;
+
+        let builder = match deadline {
+            Some(deadline) => builder.with_deadline(deadline),
+            None => builder,
+        };
+        Ok((permit, builder))
     }
 }
 
@@ -152,15 +224,7 @@ impl<P: EthereumProvider + Send + Sync + 'static> DebugApiServer for DebugRpc<P>
         block_number: BlockNumberOrTag,
         opts: Option<GethDebugTracingOptions>,
     ) -> Result<Option<Vec<TraceResult>>> {
-        let provider = Arc::new(&self.eth_provider);
-        let maybe_tracer =
-            TracerBuilder::new(provider).await?.with_block_id(BlockId::Number(block_number)).await?.build()?;
-        if maybe_tracer.is_none() {
-            return Ok(None);
-        }
-        let tracer = maybe_tracer.unwrap();
-        let traces = tracer.debug_block(opts.unwrap_or_default())?;
-        Ok(traces)
+        self.trace_block(BlockId::Number(block_number), opts).await
     }
 
     /// Returns the Geth debug trace for the given block hash.
@@ -169,14 +233,119 @@ impl<P: EthereumProvider + Send + Sync + 'static> DebugApiServer for DebugRpc<P>
         block_hash: B256,
         opts: Option<GethDebugTracingOptions>,
     ) -> Result<Option<Vec<TraceResult>>> {
-        let provider = Arc::new(&self.eth_provider);
-        let maybe_tracer =
-            TracerBuilder::new(provider).await?.with_block_id(BlockId::Hash(block_hash.into())).await?.build()?;
-        if maybe_tracer.is_none() {
+        self.trace_block(BlockId::Hash(block_hash.into()), opts).await
+    }
+
+    /// Returns a page of the Geth debug traces of the block, starting at the transaction of the
+    /// cursor, if any.
+    async fn trace_block_page(
+        &self,
+        block_id: BlockId,
+        opts: Option<GethDebugTracingOptions>,
+        cursor: Option<Cursor>,
+    ) -> Result<Option<Page<TraceResult>>> {
+        // The next pages trace the block of the cursor, even if the block id is a tag
+        let (block_id, first_transaction) = match cursor {
+            Some(cursor) => (BlockId::Number(cursor.block_number.into()), cursor.index),
+            None => (block_id, 0),
+        };
+        let Some((_permit, tracer)) = self.block_tracer(block_id).await? else {
             return Ok(None);
+        };
+        let block_number = tracer.block_number();
+        let (traces, next_transaction) = tracer
+            .debug_block_page(opts.unwrap_or_default(), usize::try_from(first_transaction).unwrap_or(usize::MAX))?;
+
+        Ok(Some(Page {
+            items: traces,
+            next_cursor: next_transaction.map(|index| Cursor::new(block_number, index as u64)),
+        }))
+    }
+
+    /// Returns the Geth debug trace for the given transaction hash.
+    async fn trace_transaction(
+        &self,
+        transaction_hash: B256,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> Result<GethTrace> {
+        let opts = opts.unwrap_or_default();
+        let kind = geth_trace_kind(&opts);
+        if let Some(cache) = &self.trace_cache {
+            if let Some(trace) = cache.get(transaction_hash, &kind).await {
+                return Ok(trace);
+            }
+        }
+
+        let provider = Arc::new(&self.eth_provider);
+        let tracer = TracerBuilder::new(provider)
+            .await?
+            .with_transaction_hash(transaction_hash)
+            .await?
+            .build()?
+            .ok_or(EthApiError::UnknownBlock)?;
+        let trace = tracer
+            .debug_transaction(transaction_hash, opts)?
+            .ok_or(EthApiError::TransactionNotFound(transaction_hash))?;
+        if let Some(cache) = &self.trace_cache {
+            let transaction = self.eth_provider.transaction_by_hash(transaction_hash).await?;
+            if let Some(block_number) = transaction.and_then(|transaction| transaction.block_number) {
+                cache.insert(transaction_hash, block_number, &kind, &trace).await;
+            }
+        }
+        Ok(trace)
+    }
+
+    /// Returns the Geth debug traces of a bundle of calls simulated on top of the given block.
+    /// The simulation counts as the tracing of a block for the concurrency and timeout limits.
+    async fn trace_call_many(
+        &self,
+        calls: Vec<BundleCall>,
+        block_id: Option<BlockId>,
+        block_overrides: Option<Box<BlockOverrides>>,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> Result<Vec<GethTrace>> {
+        if calls.len() > MAX_CALL_BUNDLE_SIZE {
+            return Err(EthApiError::CallBundleLimitExceeded(MAX_CALL_BUNDLE_SIZE).into());
+        }
+        let (_permit, builder) =
+            self.block_tracer_builder(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest))).await?;
+        let tracer = builder.build()?.ok_or(EthApiError::UnknownBlock)?;
+        Ok(tracer.debug_call_many(calls, block_overrides, opts.unwrap_or_default())?)
+    }
+}
+
+/// Awaits the future until the deadline, if any, failing with a tracing timeout past it.
+async fn before_deadline<T>(
+    deadline: Option<Instant>,
+    future: impl Future<Output = TracerResult<T>>,
+) -> TracerResult<T> {
+    match deadline {
+        Some(deadline) => {
+            tokio::time::timeout_at(deadline.into(), future).await.map_err(|_| EthApiError::TracingTimeout)?
         }
-        let tracer = maybe_tracer.unwrap();
-        let traces = tracer.debug_block(opts.unwrap_or_default())?;
-        Ok(traces)
+        None => future.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_before_deadline() {
+        // Given
+        let permits = Semaphore::new(1);
+        let _permit = permits.acquire().await.unwrap();
+        let deadline = Some(Instant::now() + Duration::from_millis(10));
+
+        // When
+        let ready = before_deadline(deadline, async { Ok(1) }).await;
+        let waiting = before_deadline(deadline, async { Ok(permits.acquire().await.unwrap()) }).await;
+        let unbounded = before_deadline(None, async { Ok(2) }).await;
+
+        // Then
+        assert!(matches!(waiting, Err(EthApiError::TracingTimeout)));
+        assert_eq!(ready.unwrap(), 1);
+        assert_eq!(unbounded.unwrap(), 2);
     }
 }
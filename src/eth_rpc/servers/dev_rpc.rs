@@ -0,0 +1,67 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use reth_primitives::serde_helper::U64HexOrNumber;
+use reth_primitives::{Address, U256, U64};
+
+use crate::eth_provider::error::EthApiError;
+use crate::eth_provider::starknet::kakarot_core::starknet_address;
+use crate::eth_provider::starknet::katana::KatanaDevClient;
+use crate::eth_rpc::api::dev_api::DevApiServer;
+
+/// The accounts on behalf of which `eth_sendTransaction` sends transactions.
+pub type ImpersonatedAccounts = Arc<RwLock<HashSet<Address>>>;
+
+/// The RPC module for implementing the Anvil compatible dev API. The methods are translated
+/// into calls to the dev API of Katana, which the Starknet provider must be.
+#[derive(Debug)]
+pub struct DevRpc {
+    katana: KatanaDevClient,
+    impersonated_accounts: ImpersonatedAccounts,
+    /// Total time increase of the `evm_increaseTime` calls.
+    time_increase: AtomicU64,
+}
+
+impl DevRpc {
+    pub fn new(katana: KatanaDevClient, impersonated_accounts: ImpersonatedAccounts) -> Self {
+        Self { katana, impersonated_accounts, time_increase: AtomicU64::new(0) }
+    }
+}
+
+#[async_trait]
+impl DevApiServer for DevRpc {
+    async fn evm_mine(&self, timestamp: Option<U64HexOrNumber>) -> Result<String> {
+        if let Some(timestamp) = timestamp {
+            self.katana.set_next_block_timestamp(timestamp.to()).await.map_err(EthApiError::from)?;
+        }
+        self.katana.generate_block().await.map_err(EthApiError::from)?;
+        Ok("0x0".to_string())
+    }
+
+    async fn evm_set_next_block_timestamp(&self, timestamp: U64HexOrNumber) -> Result<()> {
+        Ok(self.katana.set_next_block_timestamp(timestamp.to()).await.map_err(EthApiError::from)?)
+    }
+
+    async fn evm_increase_time(&self, seconds: U64HexOrNumber) -> Result<U64> {
+        let seconds = seconds.to();
+        self.katana.increase_next_block_timestamp(seconds).await.map_err(EthApiError::from)?;
+        let total = self.time_increase.fetch_add(seconds, Ordering::Relaxed).saturating_add(seconds);
+        Ok(U64::from(total))
+    }
+
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> Result<()> {
+        Ok(self.katana.set_native_token_balance(starknet_address(address), balance).await.map_err(EthApiError::from)?)
+    }
+
+    async fn anvil_impersonate_account(&self, address: Address) -> Result<()> {
+        self.impersonated_accounts.write().expect("Failed to lock impersonated accounts").insert(address);
+        Ok(())
+    }
+
+    async fn anvil_stop_impersonating_account(&self, address: Address) -> Result<()> {
+        self.impersonated_accounts.write().expect("Failed to lock impersonated accounts").remove(&address);
+        Ok(())
+    }
+}
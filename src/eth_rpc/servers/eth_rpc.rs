@@ -1,18 +1,30 @@
 #![allow(clippy::blocks_in_conditions)]
 
+use std::sync::Arc;
+
 use jsonrpsee::core::{async_trait, RpcResult as Result};
 use reth_primitives::serde_helper::{JsonStorageKey, U64HexOrNumber};
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64};
+use reth_primitives::{
+    AccessList, AccessListItem, Address, BlockId, BlockNumberOrTag, Bytes, Signature, TransactionKind,
+    TransactionSigned, TxEip1559, TxLegacy, B256, B64, U256, U64,
+};
 use reth_rpc_types::{
-    AccessListWithGasUsed, EIP1186AccountProofResponse, FeeHistory, Filter, FilterChanges, Index, RichBlock,
-    SyncStatus, Transaction, TransactionReceipt, TransactionRequest, Work,
+    state::StateOverride, AccessListWithGasUsed, BlockOverrides, EIP1186AccountProofResponse, FeeHistory, Filter,
+    FilterChanges, Index, RichBlock, SyncStatus, Transaction, TransactionReceipt, TransactionRequest, Work,
 };
 use serde_json::Value;
 
-use crate::eth_provider::constant::MAX_PRIORITY_FEE_PER_GAS;
+use crate::eth_provider::constant::{BLOB_BASE_FEE, MAX_CALL_BUNDLE_SIZE};
 use crate::eth_provider::error::EthApiError;
-use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
 use crate::eth_rpc::api::eth_api::EthApiServer;
+use crate::eth_rpc::filters::FilterManager;
+use crate::eth_rpc::servers::dev_rpc::ImpersonatedAccounts;
+use crate::eth_rpc::signer::Signers;
+use crate::models::account::EthAccount;
+use crate::models::call_bundle::{BundleCall, BundleCallResult};
+use crate::models::receipt::TransactionReceiptWithRevertReason;
+use crate::tracing::builder::TracerBuilder;
 
 /// The RPC module for the Ethereum protocol required by Kakarot.
 #[derive(Debug)]
@@ -21,15 +33,103 @@ where
     P: EthereumProvider,
 {
     eth_provider: P,
+    filters: FilterManager,
+    /// The accounts on behalf of which `eth_sendTransaction` sends transactions, set by the dev API.
+    impersonated_accounts: Option<ImpersonatedAccounts>,
+    /// The accounts whose transactions and messages are signed by the RPC.
+    signers: Option<Arc<Signers>>,
 }
 
 impl<P> KakarotEthRpc<P>
 where
     P: EthereumProvider,
 {
-    pub const fn new(eth_provider: P) -> Self {
-        Self { eth_provider }
+    pub fn new(eth_provider: P) -> Self {
+        Self { eth_provider, filters: FilterManager::default(), impersonated_accounts: None, signers: None }
+    }
+
+    /// Allows `eth_sendTransaction` to send the transactions of the impersonated accounts.
+    pub fn with_impersonated_accounts(mut self, impersonated_accounts: ImpersonatedAccounts) -> Self {
+        self.impersonated_accounts = Some(impersonated_accounts);
+        self
+    }
+
+    /// Signs the transactions and messages of the accounts of the signers, which are returned by
+    /// `eth_accounts`.
+    pub fn with_signers(mut self, signers: Arc<Signers>) -> Self {
+        self.signers = Some(signers);
+        self
     }
+
+    /// Returns the signers owning the account, if any.
+    fn signers_of(&self, address: &Address) -> Option<&Signers> {
+        self.signers.as_deref().filter(|signers| signers.contains(address))
+    }
+
+    fn is_impersonated(&self, address: &Address) -> bool {
+        self.impersonated_accounts
+            .as_ref()
+            .is_some_and(|accounts| accounts.read().expect("Failed to lock impersonated accounts").contains(address))
+    }
+}
+
+/// Builds the transaction of the account from the request, filling the nonce, gas limit and
+/// fees if they are missing. The transaction is left unsigned.
+pub(crate) async fn request_transaction<P: EthereumProvider>(
+    eth_provider: &P,
+    from: Address,
+    request: TransactionRequest,
+) -> EthProviderResult<TransactionSigned> {
+    let chain_id = eth_provider.chain_id().await?.unwrap_or_default().to::<u64>();
+    let nonce = match request.nonce {
+        Some(nonce) => nonce,
+        None => eth_provider.transaction_count(from, None).await?.try_into().unwrap_or(u64::MAX),
+    };
+    let gas_limit = match request.gas {
+        Some(gas) => gas.try_into().unwrap_or(u64::MAX),
+        None => eth_provider.estimate_gas(request.clone(), None).await?.try_into().unwrap_or(u64::MAX),
+    };
+    let to = request.to.map_or(TransactionKind::Create, TransactionKind::Call);
+    let value = request.value.unwrap_or_default();
+    let input = request.input.into_input().unwrap_or_default();
+
+    let transaction = match request.gas_price {
+        Some(gas_price) => reth_primitives::Transaction::Legacy(TxLegacy {
+            chain_id: Some(chain_id),
+            nonce,
+            gas_price,
+            gas_limit,
+            to,
+            value,
+            input,
+        }),
+        None => {
+            let max_fee_per_gas = match request.max_fee_per_gas {
+                Some(max_fee_per_gas) => max_fee_per_gas,
+                None => eth_provider.gas_price().await?.try_into().unwrap_or(u128::MAX),
+            };
+            let access_list = request
+                .access_list
+                .unwrap_or_default()
+                .0
+                .into_iter()
+                .map(|item| AccessListItem { address: item.address, storage_keys: item.storage_keys })
+                .collect();
+            reth_primitives::Transaction::Eip1559(TxEip1559 {
+                chain_id,
+                nonce,
+                gas_limit,
+                max_fee_per_gas,
+                max_priority_fee_per_gas: request.max_priority_fee_per_gas.unwrap_or_default(),
+                to,
+                value,
+                access_list: AccessList(access_list),
+                input,
+            })
+        }
+    };
+
+    Ok(TransactionSigned::from_transaction_and_signature(transaction, Signature::default()))
 }
 
 #[async_trait]
@@ -53,7 +153,7 @@ where
 
     #[tracing::instrument(skip_all, ret, err)]
     async fn accounts(&self) -> Result<Vec<Address>> {
-        Ok(Vec::new())
+        Ok(self.signers.as_ref().map(|signers| signers.addresses()).unwrap_or_default())
     }
 
     #[tracing::instrument(skip_all, ret, err)]
@@ -129,8 +229,14 @@ where
     }
 
     #[tracing::instrument(skip_all, ret, err, fields(hash = %hash))]
-    async fn transaction_receipt(&self, hash: B256) -> Result<Option<TransactionReceipt>> {
-        Ok(self.eth_provider.transaction_receipt(hash).await?)
+    async fn transaction_receipt(&self, hash: B256) -> Result<Option<TransactionReceiptWithRevertReason>> {
+        let Some(receipt) = self.eth_provider.transaction_receipt(hash).await? else {
+            return Ok(None);
+        };
+        // The revert reason is best effort, the receipt is returned even if the replay fails
+        let revert_reason =
+            if receipt.inner.status() { None } else { self.eth_provider.revert_reason(hash).await.ok().flatten() };
+        Ok(Some(TransactionReceiptWithRevertReason { receipt, revert_reason }))
     }
 
     #[tracing::instrument(skip_all, ret, err, fields(address = %address, block_id = ?block_id))]
@@ -159,21 +265,86 @@ where
     }
 
     #[tracing::instrument(skip_all, ret, err, fields(request = ?request, block_id = ?block_id))]
-    async fn call(&self, request: TransactionRequest, block_id: Option<BlockId>) -> Result<Bytes> {
-        Ok(self.eth_provider.call(request, block_id).await?)
+    async fn call(
+        &self,
+        request: TransactionRequest,
+        block_id: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> Result<Bytes> {
+        // Kakarot can't apply state or block overrides, the request is then executed locally
+        let state_overrides = state_overrides.unwrap_or_default();
+        if state_overrides.is_empty() && block_overrides.is_none() {
+            return Ok(self.eth_provider.call(request, block_id).await?);
+        }
+
+        let provider = Arc::new(&self.eth_provider);
+        let tracer = TracerBuilder::new(provider)
+            .await?
+            .with_block_id(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)))
+            .await?
+            .build()?
+            .ok_or(EthApiError::UnknownBlock)?;
+        Ok(tracer.call(request, state_overrides, block_overrides)?)
+    }
+
+    #[tracing::instrument(skip_all, ret, err, fields(calls = calls.len(), block_id = ?block_id))]
+    async fn call_many(
+        &self,
+        calls: Vec<BundleCall>,
+        block_id: Option<BlockId>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> Result<Vec<BundleCallResult>> {
+        if calls.len() > MAX_CALL_BUNDLE_SIZE {
+            return Err(EthApiError::CallBundleLimitExceeded(MAX_CALL_BUNDLE_SIZE).into());
+        }
+
+        let provider = Arc::new(&self.eth_provider);
+        let tracer = TracerBuilder::new(provider)
+            .await?
+            .with_block_id(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)))
+            .await?
+            .build()?
+            .ok_or(EthApiError::UnknownBlock)?;
+        Ok(tracer.call_many(calls, block_overrides)?)
     }
 
+    #[tracing::instrument(skip_all, ret, err, fields(request = ?request, block_id = ?block_id))]
     async fn create_access_list(
         &self,
-        _request: TransactionRequest,
-        _block_id: Option<BlockId>,
+        request: TransactionRequest,
+        block_id: Option<BlockId>,
     ) -> Result<AccessListWithGasUsed> {
-        Err(EthApiError::Unsupported("eth_createAccessList").into())
+        let provider = Arc::new(&self.eth_provider);
+        let tracer = TracerBuilder::new(provider)
+            .await?
+            .with_block_id(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)))
+            .await?
+            .build()?
+            .ok_or(EthApiError::UnknownBlock)?;
+        Ok(tracer.create_access_list(request)?)
     }
 
     #[tracing::instrument(skip_all, ret, fields(request = ?request, block_id = ?block_id))]
-    async fn estimate_gas(&self, request: TransactionRequest, block_id: Option<BlockId>) -> Result<U256> {
-        Ok(self.eth_provider.estimate_gas(request, block_id).await?)
+    async fn estimate_gas(
+        &self,
+        request: TransactionRequest,
+        block_id: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+    ) -> Result<U256> {
+        // Kakarot can't apply state overrides, the request is then executed locally
+        let Some(state_overrides) = state_overrides.filter(|overrides| !overrides.is_empty()) else {
+            return Ok(self.eth_provider.estimate_gas(request, block_id).await?);
+        };
+
+        let provider = Arc::new(&self.eth_provider);
+        let tracer = TracerBuilder::new(provider)
+            .await?
+            .with_block_id(block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)))
+            .await?
+            .build()?
+            .ok_or(EthApiError::UnknownBlock)?;
+        Ok(tracer.estimate_gas(request, state_overrides)?)
     }
 
     #[tracing::instrument(skip_all, ret, err)]
@@ -193,11 +364,11 @@ where
 
     #[tracing::instrument(skip_all, ret, err)]
     async fn max_priority_fee_per_gas(&self) -> Result<U256> {
-        Ok(U256::from(*MAX_PRIORITY_FEE_PER_GAS))
+        Ok(self.eth_provider.max_priority_fee_per_gas().await?)
     }
 
     async fn blob_base_fee(&self) -> Result<U256> {
-        Err(EthApiError::Unsupported("eth_blobBaseFee").into())
+        Ok(U256::from(BLOB_BASE_FEE))
     }
 
     async fn mining(&self) -> Result<bool> {
@@ -223,8 +394,20 @@ where
         Err(EthApiError::Unsupported("eth_submitWork").into())
     }
 
-    async fn send_transaction(&self, _request: TransactionRequest) -> Result<B256> {
-        Err(EthApiError::Unsupported("eth_sendTransaction").into())
+    async fn send_transaction(&self, request: TransactionRequest) -> Result<B256> {
+        // Only the transactions of the signers and of the accounts impersonated through the dev
+        // API can be sent
+        let from = request.from.ok_or(EthApiError::Unsupported("eth_sendTransaction"))?;
+        if let Some(signers) = self.signers_of(&from) {
+            let transaction = request_transaction(&self.eth_provider, from, request).await?;
+            let transaction = signers.sign_transaction(&from, transaction.transaction).map_err(EthApiError::from)?;
+            return Ok(self.eth_provider.send_raw_transaction(transaction.envelope_encoded()).await?);
+        }
+        if !self.is_impersonated(&from) {
+            return Err(EthApiError::Unsupported("eth_sendTransaction").into());
+        }
+        let transaction = request_transaction(&self.eth_provider, from, request).await?;
+        Ok(self.eth_provider.send_impersonated_transaction(transaction, from).await?)
     }
 
     #[tracing::instrument(skip_all, ret, err, fields(bytes = %bytes))]
@@ -232,49 +415,76 @@ where
         Ok(self.eth_provider.send_raw_transaction(bytes).await?)
     }
 
-    async fn sign(&self, _address: Address, _message: Bytes) -> Result<Bytes> {
-        Err(EthApiError::Unsupported("eth_sign").into())
+    #[tracing::instrument(skip(self, message), err)]
+    async fn sign(&self, address: Address, message: Bytes) -> Result<Bytes> {
+        let signers = self.signers_of(&address).ok_or(EthApiError::Unsupported("eth_sign"))?;
+        Ok(signers.sign_message(&address, &message).map_err(EthApiError::from)?)
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn sign_transaction(&self, request: TransactionRequest) -> Result<Bytes> {
+        let from = request.from.ok_or(EthApiError::Unsupported("eth_signTransaction"))?;
+        let signers = self.signers_of(&from).ok_or(EthApiError::Unsupported("eth_signTransaction"))?;
+        let transaction = request_transaction(&self.eth_provider, from, request).await?;
+        let transaction = signers.sign_transaction(&from, transaction.transaction).map_err(EthApiError::from)?;
+        Ok(transaction.envelope_encoded())
     }
 
-    async fn sign_transaction(&self, _transaction: TransactionRequest) -> Result<Bytes> {
-        Err(EthApiError::Unsupported("eth_signTransaction").into())
+    #[tracing::instrument(skip(self, data), err)]
+    async fn sign_typed_data(&self, address: Address, data: Value) -> Result<Bytes> {
+        let signers = self.signers_of(&address).ok_or(EthApiError::Unsupported("eth_signTypedData"))?;
+        let data = serde_json::from_value(data).map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+        Ok(signers.sign_typed_data(&address, &data)?)
     }
 
-    async fn sign_typed_data(&self, _address: Address, _data: Value) -> Result<Bytes> {
-        Err(EthApiError::Unsupported("eth_signTypedData").into())
+    #[tracing::instrument(skip(self, data), err)]
+    async fn sign_typed_data_v4(&self, address: Address, data: Value) -> Result<Bytes> {
+        self.sign_typed_data(address, data).await
     }
 
+    #[tracing::instrument(skip_all, ret, err, fields(address = %address, keys = ?keys, block_id = ?block_id))]
     async fn get_proof(
         &self,
-        _address: Address,
-        _keys: Vec<B256>,
-        _block_id: Option<BlockId>,
+        address: Address,
+        keys: Vec<JsonStorageKey>,
+        block_id: Option<BlockId>,
     ) -> Result<EIP1186AccountProofResponse> {
-        Err(EthApiError::Unsupported("eth_getProof").into())
+        Ok(self.eth_provider.get_proof(address, keys, block_id).await?)
     }
 
-    async fn new_filter(&self, _filter: Filter) -> Result<U64> {
-        Err(EthApiError::Unsupported("eth_newFilter").into())
+    #[tracing::instrument(skip_all, ret, err, fields(address = %address, block_id = ?block_id))]
+    async fn get_account(&self, address: Address, block_id: Option<BlockId>) -> Result<EthAccount> {
+        Ok(self.eth_provider.account(address, block_id).await?)
+    }
+
+    #[tracing::instrument(skip_all, ret, err, fields(filter = ?filter))]
+    async fn new_filter(&self, filter: Filter) -> Result<U64> {
+        Ok(self.filters.new_logs_filter(&self.eth_provider, filter).await?)
     }
 
+    #[tracing::instrument(skip_all, ret, err)]
     async fn new_block_filter(&self) -> Result<U64> {
-        Err(EthApiError::Unsupported("eth_newBlockFilter").into())
+        Ok(self.filters.new_block_filter(&self.eth_provider).await?)
     }
 
+    #[tracing::instrument(skip_all, ret, err)]
     async fn new_pending_transaction_filter(&self) -> Result<U64> {
-        Err(EthApiError::Unsupported("eth_newPendingTransactionFilter").into())
+        Ok(self.filters.new_pending_transaction_filter(&self.eth_provider).await?)
     }
 
-    async fn uninstall_filter(&self, _id: U64) -> Result<bool> {
-        Err(EthApiError::Unsupported("eth_uninstallFilter").into())
+    #[tracing::instrument(skip_all, ret, err, fields(id = %id))]
+    async fn uninstall_filter(&self, id: U64) -> Result<bool> {
+        Ok(self.filters.uninstall(id))
     }
 
-    async fn get_filter_changes(&self, _id: U64) -> Result<FilterChanges> {
-        Err(EthApiError::Unsupported("eth_getFilterChanges").into())
+    #[tracing::instrument(skip_all, ret, err, fields(id = %id))]
+    async fn get_filter_changes(&self, id: U64) -> Result<FilterChanges> {
+        Ok(self.filters.filter_changes(&self.eth_provider, id).await?)
     }
 
-    async fn get_filter_logs(&self, _id: U64) -> Result<FilterChanges> {
-        Err(EthApiError::Unsupported("eth_getFilterLogs").into())
+    #[tracing::instrument(skip_all, ret, err, fields(id = %id))]
+    async fn get_filter_logs(&self, id: U64) -> Result<FilterChanges> {
+        Ok(self.filters.filter_logs(&self.eth_provider, id).await?)
     }
 
     async fn block_receipts(&self, block_id: Option<BlockId>) -> Result<Option<Vec<TransactionReceipt>>> {
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use reth_primitives::{Address, B256};
+
+use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_rpc::api::faucet_api::FaucetApiServer;
+use crate::eth_rpc::faucet::Faucet;
+use crate::eth_rpc::middleware::rate_limit::request_client_ip;
+
+/// The RPC module for the Faucet API.
+#[derive(Debug)]
+pub struct FaucetRpc<P: EthereumProvider> {
+    eth_provider: P,
+    faucet: Arc<Faucet>,
+}
+
+impl<P: EthereumProvider> FaucetRpc<P> {
+    pub const fn new(eth_provider: P, faucet: Arc<Faucet>) -> Self {
+        Self { eth_provider, faucet }
+    }
+}
+
+#[async_trait]
+impl<P: EthereumProvider + Send + Sync + 'static> FaucetApiServer for FaucetRpc<P> {
+    #[tracing::instrument(skip(self), ret, err)]
+    async fn request_funds(&self, address: Address) -> Result<B256> {
+        Ok(self.faucet.request_funds(&self.eth_provider, address, request_client_ip()).await?)
+    }
+}
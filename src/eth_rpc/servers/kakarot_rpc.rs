@@ -0,0 +1,104 @@
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use reth_primitives::serde_helper::U64HexOrNumber;
+use reth_primitives::{Address, BlockId, BlockNumberOrTag};
+use reth_rpc_types::trace::parity::StateDiff;
+use reth_rpc_types::{Filter, Log, RichBlock};
+use starknet_crypto::FieldElement;
+
+use crate::eth_provider::constant::MAX_INTERNAL_TRANSACTIONS_PER_PAGE;
+use crate::eth_provider::error::EthApiError;
+use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_provider::starknet::kakarot_core::starknet_address;
+use crate::eth_rpc::api::kakarot_api::KakarotApiServer;
+use crate::models::account::AccountType;
+use crate::models::fee::BlockFeeBreakdown;
+use crate::models::internal_transaction::InternalTransaction;
+use crate::models::l1_message::L1Message;
+use crate::models::pagination::{Cursor, Page};
+
+/// The RPC module for the Kakarot API.
+#[derive(Debug)]
+pub struct KakarotRpc<P: EthereumProvider> {
+    eth_provider: P,
+}
+
+impl<P: EthereumProvider> KakarotRpc<P> {
+    pub const fn new(eth_provider: P) -> Self {
+        Self { eth_provider }
+    }
+}
+
+#[async_trait]
+impl<P: EthereumProvider + Send + Sync + 'static> KakarotApiServer for KakarotRpc<P> {
+    #[tracing::instrument(skip(self), err)]
+    async fn get_block_by_timestamp(&self, timestamp: U64HexOrNumber, full: bool) -> Result<Option<RichBlock>> {
+        let Some(block_number) = self.eth_provider.block_number_by_timestamp(timestamp.to()).await? else {
+            return Ok(None);
+        };
+        Ok(self.eth_provider.block_by_number(BlockNumberOrTag::Number(block_number), full).await?)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn get_l1_messages(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+    ) -> Result<Vec<L1Message>> {
+        Ok(self.eth_provider.l1_messages(from_block, to_block).await?)
+    }
+
+    #[tracing::instrument(skip(self), ret, err)]
+    async fn get_starknet_address(&self, address: Address) -> Result<FieldElement> {
+        Ok(starknet_address(address))
+    }
+
+    #[tracing::instrument(skip(self), ret, err)]
+    async fn get_evm_address(&self, address: FieldElement, block_id: Option<BlockId>) -> Result<Option<Address>> {
+        Ok(self.eth_provider.evm_address(address, block_id).await?)
+    }
+
+    #[tracing::instrument(skip(self), ret, err)]
+    async fn get_account_type(&self, address: Address, block_id: Option<BlockId>) -> Result<AccountType> {
+        Ok(self.eth_provider.account_type(address, block_id).await?)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn get_block_fee_breakdown(&self, block: BlockNumberOrTag) -> Result<Option<BlockFeeBreakdown>> {
+        Ok(self.eth_provider.block_fee_breakdown(block).await?)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn get_logs_page(&self, filter: Filter, cursor: Option<Cursor>) -> Result<Page<Log>> {
+        Ok(self.eth_provider.logs_page(filter, cursor).await?)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn get_state_diff(&self, block: BlockNumberOrTag) -> Result<Option<StateDiff>> {
+        Ok(self.eth_provider.state_diff(block).await?)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn get_internal_transactions(
+        &self,
+        address: Address,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+        cursor: Option<Cursor>,
+    ) -> Result<Page<InternalTransaction>> {
+        let latest = self.eth_provider.block_number().await?.to();
+        let block_number = |tag: BlockNumberOrTag| match tag {
+            BlockNumberOrTag::Number(number) => number,
+            BlockNumberOrTag::Earliest => 0,
+            _ => latest,
+        };
+        let (from_block, to_block) = (block_number(from_block), block_number(to_block));
+        if from_block > to_block {
+            return Err(EthApiError::InvalidBlockRange.into());
+        }
+
+        Ok(self
+            .eth_provider
+            .internal_transactions(address, from_block, to_block, cursor, MAX_INTERNAL_TRANSACTIONS_PER_PAGE)
+            .await?)
+    }
+}
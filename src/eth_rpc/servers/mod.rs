@@ -1,6 +1,15 @@
+pub mod admin_rpc;
 pub mod alchemy_rpc;
 pub mod debug_rpc;
+pub mod dev_rpc;
 pub mod eth_rpc;
+pub mod faucet_rpc;
+pub mod kakarot_rpc;
 pub mod net_rpc;
+pub mod ots_rpc;
+pub mod personal_rpc;
+pub mod pubsub_rpc;
+pub mod starknet_rpc;
 pub mod trace_rpc;
+pub mod txpool_rpc;
 pub mod web3_rpc;
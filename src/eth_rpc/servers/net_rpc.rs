@@ -1,4 +1,11 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::eth_provider::constant::READINESS_MAX_BLOCK_AGE;
+use crate::eth_provider::error::EthApiError;
 use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_provider::starknet::transport::ProviderHealth;
 use jsonrpsee::core::{async_trait, RpcResult as Result};
 use reth_primitives::U64;
 use reth_rpc_types::PeerCount;
@@ -9,11 +16,24 @@ use crate::eth_rpc::api::net_api::NetApiServer;
 #[derive(Debug)]
 pub struct NetRpc<P: EthereumProvider> {
     eth_provider: P,
+    /// Last block number observed by the readiness probe, along with the instant it was first observed.
+    last_block: Mutex<Option<(U64, Instant)>>,
+    /// Health of the Starknet providers, reported as the peers of the node.
+    provider_health: Option<ProviderHealth>,
+    /// True while the server accepts new connections.
+    listening: Option<Arc<AtomicBool>>,
 }
 
 impl<P: EthereumProvider> NetRpc<P> {
     pub const fn new(eth_provider: P) -> Self {
-        Self { eth_provider }
+        Self { eth_provider, last_block: Mutex::new(None), provider_health: None, listening: None }
+    }
+
+    /// Reports the healthy Starknet providers as peers and the server status as the listening status.
+    pub fn with_status(mut self, provider_health: Option<ProviderHealth>, listening: Option<Arc<AtomicBool>>) -> Self {
+        self.provider_health = provider_health;
+        self.listening = listening;
+        self
     }
 }
 
@@ -24,18 +44,53 @@ impl<P: EthereumProvider + Send + Sync + 'static> NetApiServer for NetRpc<P> {
     }
 
     fn peer_count(&self) -> Result<PeerCount> {
-        // Kakarot RPC currently does not have peers connected to node
-        Ok(PeerCount::Number(0))
+        // Kakarot RPC doesn't have peers, the Starknet providers which are healthy are reported instead
+        let healthy = self.provider_health.as_ref().map_or(0, ProviderHealth::healthy_count);
+        Ok(PeerCount::Number(healthy.try_into().unwrap_or_default()))
     }
 
     fn listening(&self) -> Result<bool> {
-        // Kakarot RPC currently does not support peer-to-peer connections
-        Ok(false)
+        // The server stops listening once the shutdown starts
+        Ok(self.listening.as_ref().map_or(true, |listening| listening.load(Ordering::Relaxed)))
     }
 
     async fn health(&self) -> Result<bool> {
-        // Calls starknet block_number method to check if it resolves
+        // Reads the latest block number from the database to check if it is connected
         let _ = self.eth_provider.block_number().await?;
+        // Calls starknet syncing method to check if the provider is reachable
+        let _ = self.eth_provider.syncing().await?;
+
+        Ok(true)
+    }
+
+    async fn ready(&self) -> Result<bool> {
+        self.health().await?;
+
+        // The staleness check is opt-in, since the devnets only produce blocks on demand
+        let max_block_age = Duration::from_secs(*READINESS_MAX_BLOCK_AGE);
+        if max_block_age.is_zero() {
+            return Ok(true);
+        }
+
+        let block_number = self.eth_provider.block_number().await?;
+
+        let mut last_block = self.last_block.lock().expect("Failed to lock last block");
+        let block_age = match *last_block {
+            Some((number, observed_at)) if number == block_number => observed_at.elapsed(),
+            _ => {
+                *last_block = Some((block_number, Instant::now()));
+                Duration::ZERO
+            }
+        };
+
+        if block_age > max_block_age {
+            return Err(EthApiError::NotReady(format!(
+                "no new block since {}s, latest block is {}",
+                block_age.as_secs(),
+                block_number
+            ))
+            .into());
+        }
 
         Ok(true)
     }
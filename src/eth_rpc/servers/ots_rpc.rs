@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, B256, U256};
+use reth_revm::tracing::TracingInspectorConfig;
+use reth_rpc_types::RichBlock;
+
+use crate::eth_provider::constant::OTS_MAX_PAGE_SIZE;
+use crate::eth_provider::error::EthApiError;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
+use crate::eth_rpc::api::ots_api::OtterscanApiServer;
+use crate::models::internal_transaction::{internal_transactions, InternalOperation};
+use crate::models::otterscan::{
+    BlockDetails, BlockIssuance, ContractCreator, OtsTransactionReceipt, SearchDirection, TransactionsWithReceipts,
+};
+use crate::tracing::builder::TracerBuilder;
+
+/// Version of the Otterscan API implemented by the node.
+const API_LEVEL: u64 = 8;
+
+/// The RPC module for the Otterscan API.
+/// Kakarot only indexes the top level calls, so the searches only return the
+/// transactions sent from or to an address, and the contract creators are only
+/// known for the contracts deployed by a transaction. The internal operations
+/// are read from the stored internal transactions, or extracted from the
+/// traces of the transaction.
+#[derive(Debug)]
+pub struct OtterscanRpc<P: EthereumProvider> {
+    eth_provider: P,
+}
+
+impl<P: EthereumProvider> OtterscanRpc<P> {
+    pub const fn new(eth_provider: P) -> Self {
+        Self { eth_provider }
+    }
+}
+
+impl<P: EthereumProvider + Send + Sync> OtterscanRpc<P> {
+    /// Returns the details of the block, computing the fees paid by its transactions.
+    async fn block_details(&self, block: RichBlock) -> EthProviderResult<BlockDetails> {
+        let block_hash = block.header.hash.unwrap_or_default();
+        let receipts = self.eth_provider.block_receipts(Some(BlockId::Hash(block_hash.into()))).await?;
+        let total_fees = receipts
+            .unwrap_or_default()
+            .into_iter()
+            .map(|receipt| U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price))
+            .sum();
+
+        Ok(BlockDetails { block: block.into(), issuance: BlockIssuance::default(), total_fees })
+    }
+
+    /// Returns a page of the transactions of the address along with their receipts. The page
+    /// holds at most [`OTS_MAX_PAGE_SIZE`] transactions, besides the ones completing its last block.
+    async fn search_transactions(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+        direction: SearchDirection,
+    ) -> EthProviderResult<TransactionsWithReceipts> {
+        let page_size = page_size.min(*OTS_MAX_PAGE_SIZE);
+        let (txs, has_more) =
+            self.eth_provider.search_transactions(address, block_number, page_size, direction).await?;
+
+        let mut timestamps = HashMap::new();
+        let mut receipts = Vec::with_capacity(txs.len());
+        for tx in &txs {
+            let receipt = self
+                .eth_provider
+                .transaction_receipt(tx.hash)
+                .await?
+                .ok_or(EthApiError::TransactionNotFound(tx.hash))?;
+
+            let block_number = receipt.block_number.unwrap_or_default();
+            let timestamp = match timestamps.get(&block_number) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let header = self
+                        .eth_provider
+                        .header(&BlockId::Number(BlockNumberOrTag::Number(block_number)))
+                        .await?
+                        .ok_or(EthApiError::UnknownBlock)?;
+                    timestamps.insert(block_number, header.timestamp);
+                    header.timestamp
+                }
+            };
+
+            receipts.push(OtsTransactionReceipt { receipt, timestamp });
+        }
+
+        // The first page holds the most recent transactions, the last page holds the oldest ones
+        let (first_page, last_page) = match direction {
+            SearchDirection::Before => (block_number == 0, !has_more),
+            SearchDirection::After => (!has_more, block_number == 0),
+        };
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page })
+    }
+}
+
+#[async_trait]
+impl<P: EthereumProvider + Send + Sync + 'static> OtterscanApiServer for OtterscanRpc<P> {
+    async fn get_api_level(&self) -> Result<u64> {
+        Ok(API_LEVEL)
+    }
+
+    #[tracing::instrument(skip(self), ret, err)]
+    async fn get_block_details(&self, block_number: u64) -> Result<Option<BlockDetails>> {
+        let Some(block) = self.eth_provider.block_by_number(BlockNumberOrTag::Number(block_number), false).await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(self.block_details(block).await?))
+    }
+
+    #[tracing::instrument(skip(self), ret, err)]
+    async fn get_block_details_by_hash(&self, block_hash: B256) -> Result<Option<BlockDetails>> {
+        let Some(block) = self.eth_provider.block_by_hash(block_hash, false).await? else {
+            return Ok(None);
+        };
+        Ok(Some(self.block_details(block).await?))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn search_transactions_before(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> Result<TransactionsWithReceipts> {
+        Ok(self.search_transactions(address, block_number, page_size, SearchDirection::Before).await?)
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn search_transactions_after(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> Result<TransactionsWithReceipts> {
+        Ok(self.search_transactions(address, block_number, page_size, SearchDirection::After).await?)
+    }
+
+    #[tracing::instrument(skip(self), ret, err)]
+    async fn get_transaction_by_sender_and_nonce(&self, sender: Address, nonce: U256) -> Result<Option<B256>> {
+        Ok(self.eth_provider.transaction_by_sender_and_nonce(sender, nonce).await?.map(|tx| tx.hash))
+    }
+
+    #[tracing::instrument(skip(self), ret, err)]
+    async fn get_contract_creator(&self, address: Address) -> Result<Option<ContractCreator>> {
+        let receipt = self.eth_provider.contract_creation_receipt(address).await?;
+        Ok(receipt.map(|receipt| ContractCreator { hash: receipt.transaction_hash, creator: receipt.from }))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn get_internal_operations(&self, transaction_hash: B256) -> Result<Vec<InternalOperation>> {
+        let stored = self.eth_provider.transaction_internal_transactions(transaction_hash).await?;
+        if !stored.is_empty() {
+            return Ok(stored.into_iter().map(Into::into).collect());
+        }
+
+        // The internal transactions of the transactions which weren't backfilled are extracted
+        // from the traces of the transaction
+        let provider = Arc::new(&self.eth_provider);
+        let tracer = TracerBuilder::new(provider)
+            .await?
+            .with_transaction_hash(transaction_hash)
+            .await?
+            .build()?
+            .ok_or(EthApiError::UnknownBlock)?;
+        let traces = tracer.trace_transaction(transaction_hash, TracingInspectorConfig::default_parity())?;
+
+        Ok(internal_transactions(&traces.unwrap_or_default()).into_iter().map(Into::into).collect())
+    }
+}
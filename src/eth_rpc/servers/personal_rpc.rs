@@ -0,0 +1,72 @@
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use reth_primitives::{Address, Bytes, B256};
+use reth_rpc_types::TransactionRequest;
+
+use crate::eth_provider::error::EthApiError;
+use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_rpc::api::personal_api::PersonalApiServer;
+use crate::eth_rpc::keystore::Keystore;
+use crate::eth_rpc::servers::eth_rpc::request_transaction;
+use crate::eth_rpc::signer::Signers;
+
+/// The RPC module for the Personal API.
+#[derive(Debug)]
+pub struct PersonalRpc<P: EthereumProvider> {
+    eth_provider: P,
+    keystore: Keystore,
+}
+
+impl<P: EthereumProvider> PersonalRpc<P> {
+    pub const fn new(eth_provider: P, keystore: Keystore) -> Self {
+        Self { eth_provider, keystore }
+    }
+}
+
+#[async_trait]
+impl<P: EthereumProvider + Send + Sync + 'static> PersonalApiServer for PersonalRpc<P> {
+    #[tracing::instrument(skip_all, ret, err)]
+    async fn new_account(&self, passphrase: String) -> Result<Address> {
+        let keystore = self.keystore.clone();
+        // The encryption of the keystore is CPU intensive
+        let address = tokio::task::spawn_blocking(move || keystore.new_account(&passphrase))
+            .await
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+        Ok(address)
+    }
+
+    #[tracing::instrument(skip_all, ret, err)]
+    async fn import_raw_key(&self, private_key: Bytes, passphrase: String) -> Result<Address> {
+        if private_key.len() != 32 {
+            return Err(EthApiError::InvalidParams("private key must be 32 bytes".to_string()).into());
+        }
+        let private_key = B256::from_slice(&private_key);
+        let keystore = self.keystore.clone();
+        let address = tokio::task::spawn_blocking(move || keystore.import_raw_key(private_key, &passphrase))
+            .await
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+        Ok(address)
+    }
+
+    #[tracing::instrument(skip_all, ret, err)]
+    async fn list_accounts(&self) -> Result<Vec<Address>> {
+        Ok(self.keystore.accounts().map_err(|err| EthApiError::InvalidParams(err.to_string()))?)
+    }
+
+    #[tracing::instrument(skip_all, ret, err)]
+    async fn send_transaction(&self, request: TransactionRequest, passphrase: String) -> Result<B256> {
+        let from = request.from.ok_or_else(|| EthApiError::InvalidParams("missing from address".to_string()))?;
+
+        let keystore = self.keystore.clone();
+        let private_key = tokio::task::spawn_blocking(move || keystore.unlock(&from, &passphrase))
+            .await
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+        let signers = Signers::new([private_key]).map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        let transaction = request_transaction(&self.eth_provider, from, request).await?;
+        let transaction = signers.sign_transaction(&from, transaction.transaction).map_err(EthApiError::from)?;
+        Ok(self.eth_provider.send_raw_transaction(transaction.envelope_encoded()).await?)
+    }
+}
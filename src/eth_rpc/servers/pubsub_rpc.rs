@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use jsonrpsee::core::{async_trait, SubscriptionResult};
+use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink};
+use reth_primitives::B256;
+use reth_rpc_types::pubsub::{Params, SubscriptionKind, SubscriptionResult as SubscriptionItem};
+use reth_rpc_types::{Filter, FilterChanges};
+
+use crate::eth_provider::constant::SUBSCRIPTION_POLL_INTERVAL_MS;
+use crate::eth_provider::error::EthApiError;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
+use crate::eth_rpc::api::pubsub_api::EthPubSubApiServer;
+use crate::eth_rpc::filters::ChainTracker;
+
+/// The RPC module for the Ethereum pub-sub API.
+/// Kakarot doesn't have access to a stream of new blocks, so the
+/// subscriptions are served by polling the provider.
+#[derive(Debug)]
+pub struct EthPubSubRpc<P: EthereumProvider> {
+    eth_provider: P,
+}
+
+impl<P: EthereumProvider> EthPubSubRpc<P> {
+    pub const fn new(eth_provider: P) -> Self {
+        Self { eth_provider }
+    }
+}
+
+#[async_trait]
+impl<P: EthereumProvider + Clone + Send + Sync + 'static> EthPubSubApiServer for EthPubSubRpc<P> {
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: SubscriptionKind,
+        params: Option<Params>,
+    ) -> SubscriptionResult {
+        let subscription = match (kind, params) {
+            (SubscriptionKind::NewHeads, _) => Subscription::NewHeads,
+            (SubscriptionKind::Logs, Some(Params::Logs(filter))) => Subscription::Logs(*filter),
+            (SubscriptionKind::Logs, _) => Subscription::Logs(Filter::default()),
+            (SubscriptionKind::NewPendingTransactions, Some(Params::Bool(full))) => {
+                Subscription::NewPendingTransactions { full }
+            }
+            (SubscriptionKind::NewPendingTransactions, _) => Subscription::NewPendingTransactions { full: false },
+            (SubscriptionKind::Syncing, _) => {
+                pending.reject(EthApiError::Unsupported("eth_subscribe(syncing)")).await;
+                return Ok(());
+            }
+        };
+
+        let sink = pending.accept().await?;
+        let eth_provider = self.eth_provider.clone();
+        let chain = ChainTracker::new(eth_provider.block_number().await?.to::<u64>());
+
+        tokio::spawn(async move {
+            SubscriptionPoller { eth_provider, subscription, chain, seen_hashes: HashSet::new() }.run(sink).await;
+        });
+
+        Ok(())
+    }
+}
+
+/// The supported subscriptions.
+#[derive(Debug)]
+enum Subscription {
+    /// New block headers.
+    NewHeads,
+    /// Logs matching the filter, emitted when included in new blocks, and emitted again flagged as
+    /// removed when their block is removed from the chain by a reorg.
+    Logs(Filter),
+    /// Transactions entering the pending pool, either full or as hashes.
+    NewPendingTransactions { full: bool },
+}
+
+/// Polls the provider for a single subscription and pipes the
+/// new items into the subscription sink.
+#[derive(Debug)]
+struct SubscriptionPoller<P: EthereumProvider> {
+    eth_provider: P,
+    subscription: Subscription,
+    /// Blocks processed by the poller.
+    chain: ChainTracker,
+    /// Pending transactions already sent to the subscriber.
+    seen_hashes: HashSet<B256>,
+}
+
+impl<P: EthereumProvider + Send + Sync> SubscriptionPoller<P> {
+    /// Runs the poller until the subscriber disconnects.
+    async fn run(mut self, sink: SubscriptionSink) {
+        let mut interval = tokio::time::interval(Duration::from_millis(SUBSCRIPTION_POLL_INTERVAL_MS));
+
+        loop {
+            tokio::select! {
+                _ = sink.closed() => break,
+                _ = interval.tick() => {
+                    let items = match self.poll().await {
+                        Ok(items) => items,
+                        Err(err) => {
+                            tracing::error!("Error while polling subscription {:?}: {:?}", self.subscription, err);
+                            continue;
+                        }
+                    };
+
+                    for item in items {
+                        let Ok(message) = SubscriptionMessage::from_json(&item) else {
+                            tracing::error!("Failed to serialize subscription item");
+                            continue;
+                        };
+                        if sink.send(message).await.is_err() {
+                            // The subscriber disconnected
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the items produced since the last poll.
+    async fn poll(&mut self) -> EthProviderResult<Vec<SubscriptionItem>> {
+        match &self.subscription {
+            Subscription::NewHeads => {
+                let update = self.chain.poll(&self.eth_provider).await?;
+                self.chain.advance(&update);
+                Ok(update.headers.into_iter().map(|header| SubscriptionItem::Header(Box::new(header.into()))).collect())
+            }
+            Subscription::Logs(filter) => {
+                let filter = filter.clone();
+                let update = self.chain.poll(&self.eth_provider).await?;
+
+                // The logs of the blocks removed by a reorg are sent first, flagged as removed
+                let mut logs = self.eth_provider.removed_logs(filter.clone(), update.removed).await?;
+                if let Some((from, to)) = update.added {
+                    if let FilterChanges::Logs(added) =
+                        self.eth_provider.get_logs(filter.from_block(from).to_block(to)).await?
+                    {
+                        logs.extend(added);
+                    }
+                }
+                self.chain.advance(&update);
+                Ok(logs.into_iter().map(|log| SubscriptionItem::Log(Box::new(log))).collect())
+            }
+            Subscription::NewPendingTransactions { full } => {
+                let full = *full;
+                let pending = self.eth_provider.pending_transactions().await?;
+
+                // Forget about the transactions which left the pending pool
+                self.seen_hashes.retain(|hash| pending.iter().any(|tx| tx.hash == *hash));
+
+                Ok(pending
+                    .into_iter()
+                    .filter(|tx| self.seen_hashes.insert(tx.hash))
+                    .map(|tx| {
+                        if full {
+                            SubscriptionItem::FullTransaction(Box::new(tx))
+                        } else {
+                            SubscriptionItem::TransactionHash(tx.hash)
+                        }
+                    })
+                    .collect())
+            }
+        }
+    }
+}
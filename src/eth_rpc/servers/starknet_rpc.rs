@@ -0,0 +1,189 @@
+use eyre::eyre;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned, Params};
+use jsonrpsee::RpcModule;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::eth_provider::error::{EthApiError, KakarotError};
+
+/// Methods of the Starknet JSON-RPC specification, forwarded by default.
+/// <https://github.com/starkware-libs/starknet-specs/tree/master/api>
+pub const STARKNET_METHODS: [&str; 29] = [
+    "starknet_specVersion",
+    "starknet_getBlockWithTxHashes",
+    "starknet_getBlockWithTxs",
+    "starknet_getBlockWithReceipts",
+    "starknet_getStateUpdate",
+    "starknet_getStorageAt",
+    "starknet_getTransactionStatus",
+    "starknet_getTransactionByHash",
+    "starknet_getTransactionByBlockIdAndIndex",
+    "starknet_getTransactionReceipt",
+    "starknet_getClass",
+    "starknet_getClassHashAt",
+    "starknet_getClassAt",
+    "starknet_getBlockTransactionCount",
+    "starknet_call",
+    "starknet_estimateFee",
+    "starknet_estimateMessageFee",
+    "starknet_blockNumber",
+    "starknet_blockHashAndNumber",
+    "starknet_chainId",
+    "starknet_syncing",
+    "starknet_getEvents",
+    "starknet_getNonce",
+    "starknet_addInvokeTransaction",
+    "starknet_addDeclareTransaction",
+    "starknet_addDeployAccountTransaction",
+    "starknet_traceTransaction",
+    "starknet_simulateTransactions",
+    "starknet_traceBlockTransactions",
+];
+
+/// The RPC module proxying the `starknet_` methods to the Starknet JSON-RPC provider, so that both
+/// ecosystems are served from the same URL. The requests and the responses, including the errors,
+/// are forwarded unchanged.
+#[derive(Debug, Clone)]
+pub struct StarknetRpc {
+    url: Url,
+    client: reqwest_011::Client,
+    methods: Vec<String>,
+}
+
+impl StarknetRpc {
+    pub fn new(url: Url, client: reqwest_011::Client, methods: Vec<String>) -> Self {
+        Self { url, client, methods }
+    }
+
+    /// Creates a new `StarknetRpc` forwarding the methods to the provider at the given URL. The
+    /// forwarded methods can be set with the comma separated `STARKNET_PASSTHROUGH_METHODS`
+    /// environment variable, e.g. to forward methods which are newer than the specification.
+    /// The requests are sent over the HTTP client shared with the Starknet transports.
+    pub fn from_env(url: Url, client: reqwest_011::Client) -> Result<Self, eyre::Error> {
+        let methods = passthrough_methods(std::env::var("STARKNET_PASSTHROUGH_METHODS").ok().as_deref())?;
+        Ok(Self::new(url, client, methods))
+    }
+
+    /// Returns the module serving the forwarded methods.
+    pub fn into_rpc(self) -> RpcModule<Self> {
+        // The method names must outlive the module, which is built once at startup
+        let methods = self
+            .methods
+            .iter()
+            .map(|method| -> &'static str { Box::leak(method.clone().into_boxed_str()) })
+            .collect::<Vec<_>>();
+        let mut module = RpcModule::new(self);
+        for method in methods {
+            module
+                .register_async_method(method, move |params, starknet_rpc| async move {
+                    starknet_rpc.forward(method, params).await
+                })
+                .expect("Failed to register a Starknet passthrough method");
+        }
+        module
+    }
+
+    /// Forwards the call to the Starknet provider and returns its result or its error.
+    async fn forward(&self, method: &str, params: Params<'static>) -> Result<Value, ErrorObjectOwned> {
+        // The methods without parameters can be called without the params field
+        let params = match params.parse::<Value>().map_err(|err| EthApiError::InvalidParams(err.to_string()))? {
+            Value::Null => json!([]),
+            params => params,
+        };
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = self
+            .client
+            .post(self.url.clone())
+            .header("Content-Type", "application/json")
+            .body(request.to_string())
+            .send()
+            .await
+            .map_err(|err| passthrough_error(err.to_string()))?
+            .text()
+            .await
+            .map_err(|err| passthrough_error(err.to_string()))?;
+        let response: Value = serde_json::from_str(&response).map_err(|err| passthrough_error(err.to_string()))?;
+
+        match response {
+            Value::Object(mut response) => match response.remove("error") {
+                Some(error) => Err(starknet_error(error)),
+                None => Ok(response.remove("result").unwrap_or_default()),
+            },
+            response => Err(passthrough_error(format!("invalid response {response}"))),
+        }
+    }
+}
+
+/// Returns the forwarded methods from the comma separated list, or the methods of the Starknet
+/// specification if there is none.
+fn passthrough_methods(methods: Option<&str>) -> Result<Vec<String>, eyre::Error> {
+    let Some(methods) = methods.filter(|methods| !methods.trim().is_empty()) else {
+        return Ok(STARKNET_METHODS.iter().map(ToString::to_string).collect());
+    };
+
+    methods
+        .split(',')
+        .map(str::trim)
+        .filter(|method| !method.is_empty())
+        .map(|method| {
+            if method.starts_with("starknet_") {
+                Ok(method.to_string())
+            } else {
+                Err(eyre!("invalid STARKNET_PASSTHROUGH_METHODS: {method} isn't a starknet_ method"))
+            }
+        })
+        .collect()
+}
+
+fn passthrough_error(err: String) -> ErrorObjectOwned {
+    EthApiError::Kakarot(KakarotError::PassthroughError(err)).into()
+}
+
+/// Converts the error object returned by the Starknet provider, keeping its code, message and data.
+fn starknet_error(error: Value) -> ErrorObjectOwned {
+    let code = error.get("code").and_then(Value::as_i64).and_then(|code| i32::try_from(code).ok());
+    let message = error.get("message").and_then(Value::as_str);
+    match (code, message) {
+        (Some(code), Some(message)) => ErrorObject::owned(code, message, error.get("data").cloned()),
+        _ => passthrough_error(format!("invalid error {error}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passthrough_methods() {
+        // Given
+        let methods = Some(" starknet_getBlockWithTxs, starknet_getMessagesStatus,");
+
+        // When
+        let methods = passthrough_methods(methods).unwrap();
+
+        // Then
+        assert_eq!(methods, vec!["starknet_getBlockWithTxs", "starknet_getMessagesStatus"]);
+        assert_eq!(passthrough_methods(None).unwrap().len(), STARKNET_METHODS.len());
+        assert!(passthrough_methods(Some("starknet_call,eth_call")).is_err());
+    }
+
+    #[test]
+    fn test_starknet_error() {
+        // Given
+        let error = json!({"code": 20, "message": "Contract not found"});
+
+        // When
+        let error = starknet_error(error);
+
+        // Then
+        assert_eq!(error.code(), 20);
+        assert_eq!(error.message(), "Contract not found");
+        assert!(error.data().is_none());
+    }
+}
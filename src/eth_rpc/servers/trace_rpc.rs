@@ -1,36 +1,128 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use crate::eth_provider::constant::RPC_MAX_RESPONSE_SIZE;
+use crate::eth_provider::error::EthApiError;
 use crate::eth_provider::provider::EthereumProvider;
 use crate::eth_rpc::api::trace_api::TraceApiServer;
+use crate::models::pagination::{Cursor, Page};
 use crate::tracing::builder::TracerBuilder;
+use crate::tracing::cache::{TraceCache, PARITY_TRACE_KIND};
+use crate::tracing::Tracer;
 use jsonrpsee::core::{async_trait, RpcResult as Result};
+use reth_primitives::B256;
 use reth_revm::tracing::TracingInspectorConfig;
-use reth_rpc_types::trace::parity::LocalizedTransactionTrace;
+use reth_rpc_types::trace::parity::{LocalizedTransactionTrace, TraceResults, TraceType};
 use reth_rpc_types::BlockId;
 
 /// The RPC module for implementing the Trace api
 #[derive(Debug)]
 pub struct TraceRpc<P: EthereumProvider> {
     eth_provider: P,
+    /// Cache of the transaction traces.
+    trace_cache: Option<TraceCache>,
 }
 
 impl<P: EthereumProvider> TraceRpc<P> {
     pub const fn new(eth_provider: P) -> Self {
-        Self { eth_provider }
+        Self { eth_provider, trace_cache: None }
+    }
+
+    /// Caches the traces returned by `trace_transaction`.
+    pub fn with_trace_cache(mut self, trace_cache: TraceCache) -> Self {
+        self.trace_cache = Some(trace_cache);
+        self
+    }
+}
+
+impl<P: EthereumProvider + Send + Sync> TraceRpc<P> {
+    /// Returns the tracer of the block, bounded by the maximum response size.
+    async fn block_tracer(&self, block_id: BlockId) -> Result<Option<Tracer<Arc<&P>>>> {
+        let provider = Arc::new(&self.eth_provider);
+        let builder = TracerBuilder::new(provider).await?.with_block_id(block_id).await?;
+        Ok(builder.with_max_response_size(*RPC_MAX_RESPONSE_SIZE as usize).build()?)
     }
 }
 
 #[async_trait]
 impl<P: EthereumProvider + Send + Sync + 'static> TraceApiServer for TraceRpc<P> {
     /// Returns the parity traces for the given block.
+    /// Fails if the traces exceed [`RPC_MAX_RESPONSE_SIZE`] bytes.
     async fn trace_block(&self, block_id: BlockId) -> Result<Option<Vec<LocalizedTransactionTrace>>> {
-        let provider = Arc::new(&self.eth_provider);
-        let maybe_tracer = TracerBuilder::new(provider).await?.with_block_id(block_id).await?.build()?;
-        if maybe_tracer.is_none() {
+        let Some(tracer) = self.block_tracer(block_id).await? else {
             return Ok(None);
-        }
-        let tracer = maybe_tracer.unwrap();
+        };
         let traces = tracer.trace_block(TracingInspectorConfig::default_parity())?;
         Ok(traces)
     }
+
+    /// Returns a page of the parity traces of the block, starting at the transaction of the
+    /// cursor, if any.
+    async fn trace_block_page(
+        &self,
+        block_id: BlockId,
+        cursor: Option<Cursor>,
+    ) -> Result<Option<Page<LocalizedTransactionTrace>>> {
+        // The next pages trace the block of the cursor, even if the block id is a tag
+        let (block_id, first_transaction) = match cursor {
+            Some(cursor) => (BlockId::Number(cursor.block_number.into()), cursor.index),
+            None => (block_id, 0),
+        };
+        let Some(tracer) = self.block_tracer(block_id).await? else {
+            return Ok(None);
+        };
+        let block_number = tracer.block_number();
+        let (traces, next_transaction) = tracer.trace_block_page(
+            TracingInspectorConfig::default_parity(),
+            usize::try_from(first_transaction).unwrap_or(usize::MAX),
+        )?;
+
+        Ok(Some(Page {
+            items: traces,
+            next_cursor: next_transaction.map(|index| Cursor::new(block_number, index as u64)),
+        }))
+    }
+
+    /// Returns the parity traces for the given transaction.
+    async fn trace_transaction(&self, transaction_hash: B256) -> Result<Option<Vec<LocalizedTransactionTrace>>> {
+        if let Some(cache) = &self.trace_cache {
+            if let Some(traces) = cache.get(transaction_hash, PARITY_TRACE_KIND).await {
+                return Ok(Some(traces));
+            }
+        }
+
+        let provider = Arc::new(&self.eth_provider);
+        let tracer = TracerBuilder::new(provider)
+            .await?
+            .with_transaction_hash(transaction_hash)
+            .await?
+            .build()?
+            .ok_or(EthApiError::UnknownBlock)?;
+        let traces = tracer.trace_transaction(transaction_hash, TracingInspectorConfig::default_parity())?;
+        if let (Some(cache), Some(traces)) = (&self.trace_cache, &traces) {
+            if let Some(block_number) = traces.first().and_then(|trace| trace.block_number) {
+                cache.insert(transaction_hash, block_number, PARITY_TRACE_KIND, traces).await;
+            }
+        }
+        Ok(traces)
+    }
+
+    /// Replays the given transaction and returns the requested trace types.
+    async fn trace_replay_transaction(
+        &self,
+        transaction_hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<TraceResults> {
+        let provider = Arc::new(&self.eth_provider);
+        let tracer = TracerBuilder::new(provider)
+            .await?
+            .with_transaction_hash(transaction_hash)
+            .await?
+            .build()?
+            .ok_or(EthApiError::UnknownBlock)?;
+        let trace_results = tracer
+            .replay_transaction(transaction_hash, trace_types)?
+            .ok_or(EthApiError::TransactionNotFound(transaction_hash))?;
+        Ok(trace_results)
+    }
 }
@@ -0,0 +1,47 @@
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use reth_primitives::Address;
+use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolStatus};
+
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
+use crate::eth_rpc::api::txpool_api::TxPoolApiServer;
+
+/// The RPC module for implementing the Txpool api
+/// The pool is backed by the pending transactions collection, in which transactions
+/// sent through `eth_sendRawTransaction` are stored until they are included in a
+/// Starknet block. Transactions are forwarded to Starknet right away, meaning Kakarot
+/// has no notion of queued transactions: all the transactions are reported as pending.
+#[derive(Debug)]
+pub struct TxpoolRpc<P: EthereumProvider> {
+    eth_provider: P,
+}
+
+impl<P: EthereumProvider> TxpoolRpc<P> {
+    pub const fn new(eth_provider: P) -> Self {
+        Self { eth_provider }
+    }
+
+    /// Returns the content of the pool, grouped by sender and nonce.
+    async fn content(&self) -> EthProviderResult<TxpoolContent> {
+        let mut content = TxpoolContent::default();
+        for tx in self.eth_provider.pending_transactions().await? {
+            content.pending.entry(tx.from).or_default().insert(tx.nonce.to_string(), tx);
+        }
+        Ok(content)
+    }
+}
+
+#[async_trait]
+impl<P: EthereumProvider + Send + Sync + 'static> TxPoolApiServer for TxpoolRpc<P> {
+    async fn txpool_status(&self) -> Result<TxpoolStatus> {
+        let pending = self.eth_provider.pending_transactions().await?.len() as u64;
+        Ok(TxpoolStatus { pending, queued: 0 })
+    }
+
+    async fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom> {
+        Ok(self.content().await?.remove_from(&from))
+    }
+
+    async fn txpool_content(&self) -> Result<TxpoolContent> {
+        Ok(self.content().await?)
+    }
+}
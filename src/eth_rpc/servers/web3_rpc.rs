@@ -3,6 +3,18 @@ use reth_primitives::{keccak256, Bytes, B256};
 
 use crate::eth_rpc::api::web3_api::Web3ApiServer;
 
+/// Client version reported by `web3_clientVersion`, following the geth format
+/// `<name>/v<version>-<commit>/<os>-<arch>`, which some client libraries parse on connect.
+pub fn client_version() -> String {
+    format!(
+        "kakarot-rpc/v{}-{}/{}-{}",
+        env!("CARGO_PKG_VERSION"),
+        env!("KAKAROT_RPC_GIT_HASH"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
 /// The RPC module for the implementing Web3 Api { i.e rpc endpoints prefixed with web3_ }
 #[derive(Default, Debug)]
 pub struct Web3Rpc {}
@@ -16,10 +28,48 @@ impl Web3Rpc {
 #[async_trait]
 impl Web3ApiServer for Web3Rpc {
     fn client_version(&self) -> Result<String> {
-        Ok(format!("kakarot_{}", env!("CARGO_PKG_VERSION")))
+        Ok(client_version())
     }
 
     fn sha3(&self, input: Bytes) -> Result<B256> {
         Ok(keccak256(input))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_client_version() {
+        // When
+        let version = client_version();
+
+        // Then
+        let parts = version.split('/').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0], "kakarot-rpc");
+        assert!(parts[1].starts_with(&format!("v{}-", env!("CARGO_PKG_VERSION"))));
+    }
+
+    #[test]
+    fn test_sha3() {
+        // Given
+        let rpc = Web3Rpc::new();
+
+        // When
+        let empty = rpc.sha3(Bytes::default()).unwrap();
+        let hello = rpc.sha3(Bytes::from_static(b"hello")).unwrap();
+
+        // Then
+        assert_eq!(
+            empty,
+            B256::from_str("0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470").unwrap()
+        );
+        assert_eq!(
+            hello,
+            B256::from_str("0x1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8").unwrap()
+        );
+    }
+}
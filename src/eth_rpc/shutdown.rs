@@ -0,0 +1,167 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::join_all;
+use jsonrpsee::server::ServerHandle;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use super::get_env_or_default;
+
+/// Signal given to the background services, which resolves once the shutdown of the RPC starts.
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal(watch::Receiver<bool>);
+
+impl ShutdownSignal {
+    /// Waits for the shutdown to start.
+    pub async fn recv(&mut self) {
+        // An error means the coordinator was dropped, which is also a shutdown
+        let _ = self.0.wait_for(|shutdown| *shutdown).await;
+    }
+}
+
+/// Coordinates the shutdown of the RPC, which goes through the following steps:
+/// 1. The servers stop accepting new connections.
+/// 2. The in-flight requests of the open connections are drained.
+/// 3. The background services (retry of the pending transactions, indexer) finish their current
+///    iteration, so that their writes to the mempool and the database are complete.
+///
+/// Steps 2 and 3 are each bounded by the shutdown timeout.
+#[derive(Debug)]
+pub struct ShutdownCoordinator {
+    sender: watch::Sender<bool>,
+    /// True until the servers stop accepting new connections.
+    listening: Arc<AtomicBool>,
+    timeout: Duration,
+    servers: Vec<ServerHandle>,
+    services: Vec<JoinHandle<()>>,
+}
+
+impl ShutdownCoordinator {
+    pub fn new(timeout: Duration) -> Self {
+        let (sender, _) = watch::channel(false);
+        Self { sender, listening: Arc::new(AtomicBool::new(true)), timeout, servers: Vec::new(), services: Vec::new() }
+    }
+
+    /// Reads the shutdown timeout (in seconds) from the `RPC_SHUTDOWN_TIMEOUT` environment variable.
+    pub fn from_env() -> Self {
+        let timeout =
+            get_env_or_default("RPC_SHUTDOWN_TIMEOUT", "30").parse().expect("failing to parse RPC_SHUTDOWN_TIMEOUT");
+        Self::new(Duration::from_secs(timeout))
+    }
+
+    /// Returns a signal which resolves when the shutdown starts.
+    pub fn signal(&self) -> ShutdownSignal {
+        ShutdownSignal(self.sender.subscribe())
+    }
+
+    /// Returns a flag which is true until the servers stop accepting new connections.
+    pub fn listening(&self) -> Arc<AtomicBool> {
+        self.listening.clone()
+    }
+
+    /// Registers a server to stop and drain on shutdown.
+    pub fn register_server(&mut self, handle: ServerHandle) {
+        self.servers.push(handle);
+    }
+
+    /// Spawns a background service, which is awaited on shutdown. The service is expected to
+    /// return once the signal returned by [`ShutdownCoordinator::signal`] resolves.
+    pub fn spawn_service<F>(&mut self, service: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.services.push(tokio::spawn(service));
+    }
+
+    /// Waits for SIGTERM or SIGINT, then shuts the RPC down.
+    pub async fn run_until_signal(self) {
+        wait_for_signal().await;
+        tracing::info!("Shutdown signal received, draining the in-flight requests");
+        self.shutdown().await;
+    }
+
+    /// Stops the servers, drains the in-flight requests and waits for the background services.
+    pub async fn shutdown(mut self) {
+        self.listening.store(false, Ordering::Relaxed);
+        for server in &self.servers {
+            // The server is already stopped if this fails
+            let _ = server.stop();
+        }
+        // The handle of a stopped server only resolves once its connections are closed, which
+        // happens when their in-flight calls are answered.
+        let stopped = join_all(self.servers.into_iter().map(ServerHandle::stopped));
+        if tokio::time::timeout(self.timeout, stopped).await.is_err() {
+            tracing::warn!("Timed out while draining the in-flight requests");
+        }
+
+        let _ = self.sender.send(true);
+        let services = join_all(self.services.iter_mut());
+        if tokio::time::timeout(self.timeout, services).await.is_err() {
+            tracing::warn!("Timed out while waiting for the background services to stop");
+            self.services.iter().for_each(JoinHandle::abort);
+        }
+        tracing::info!("Shutdown complete");
+    }
+}
+
+/// Waits for SIGTERM or SIGINT.
+async fn wait_for_signal() {
+    let interrupt = async { tokio::signal::ctrl_c().await.expect("Failed to listen for SIGINT") };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_services() {
+        // Given
+        let mut coordinator = ShutdownCoordinator::new(Duration::from_secs(5));
+        let flushed = Arc::new(AtomicBool::new(false));
+        let mut signal = coordinator.signal();
+        let service_flushed = flushed.clone();
+        let listening = coordinator.listening();
+        coordinator.spawn_service(async move {
+            signal.recv().await;
+            service_flushed.store(true, Ordering::SeqCst);
+        });
+
+        // When
+        coordinator.shutdown().await;
+
+        // Then
+        assert!(flushed.load(Ordering::SeqCst));
+        assert!(!listening.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_timeout() {
+        // Given
+        let mut coordinator = ShutdownCoordinator::new(Duration::from_millis(100));
+        coordinator.spawn_service(std::future::pending());
+
+        // When
+        let shutdown = tokio::time::timeout(Duration::from_secs(5), coordinator.shutdown()).await;
+
+        // Then
+        assert!(shutdown.is_ok());
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use alloy_primitives::eip191_hash_message;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip712::{Eip712, TypedData};
+use eyre::{eyre, Result};
+use reth_primitives::{sign_message, Address, Bytes, Signature, Transaction, TransactionSigned, B256};
+
+use crate::eth_provider::error::{EthApiError, SignatureError};
+
+/// Accounts owned by the operator of the RPC, whose transactions and messages are signed by
+/// `eth_sendTransaction`, `eth_signTransaction` and `eth_sign`, as the unlocked accounts of geth.
+#[derive(Clone, Default)]
+pub struct Signers {
+    private_keys: BTreeMap<Address, B256>,
+}
+
+impl Signers {
+    /// Create a new [`Signers`] from the private keys of the accounts.
+    pub fn new(private_keys: impl IntoIterator<Item = B256>) -> Result<Self> {
+        let private_keys = private_keys
+            .into_iter()
+            .map(|private_key| {
+                let wallet = LocalWallet::from_bytes(private_key.as_slice())
+                    .map_err(|err| eyre!("Invalid signer private key: {err}"))?;
+                Ok((Address::from_slice(wallet.address().as_bytes()), private_key))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { private_keys })
+    }
+
+    /// Create a new [`Signers`] from the `SIGNER_PRIVATE_KEYS` environment variable, a comma
+    /// separated list of private keys, and from the keystores of the `SIGNER_KEYSTORE_DIR`
+    /// directory decrypted with the `SIGNER_KEYSTORE_PASSWORD` password. The keystores encrypted
+    /// with another passphrase stay locked, they are only used by `personal_sendTransaction`.
+    /// Returns None if no signer is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        let mut private_keys = Vec::new();
+        if let Some(keys) = var("SIGNER_PRIVATE_KEYS") {
+            for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+                private_keys.push(key.parse::<B256>().map_err(|_| eyre!("Invalid SIGNER_PRIVATE_KEYS {key}"))?);
+            }
+        }
+        if let (Some(dir), Some(password)) = (var("SIGNER_KEYSTORE_DIR"), var("SIGNER_KEYSTORE_PASSWORD")) {
+            private_keys.extend(read_keystores(dir.as_ref(), &password)?);
+        }
+
+        if private_keys.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(private_keys)?))
+    }
+
+    /// Returns the addresses of the accounts, in ascending order.
+    pub fn addresses(&self) -> Vec<Address> {
+        self.private_keys.keys().copied().collect()
+    }
+
+    /// Returns true if the account of the address is owned by the operator.
+    pub fn contains(&self, address: &Address) -> bool {
+        self.private_keys.contains_key(address)
+    }
+
+    /// Signs the transaction with the key of the account.
+    pub fn sign_transaction(
+        &self,
+        address: &Address,
+        transaction: Transaction,
+    ) -> Result<TransactionSigned, SignatureError> {
+        let signature = self.sign_hash(address, transaction.signature_hash())?;
+        Ok(TransactionSigned::from_transaction_and_signature(transaction, signature))
+    }
+
+    /// Signs the message prefixed as specified by EIP-191 with the key of the account, and
+    /// returns the 65 bytes of the signature, as `eth_sign`.
+    pub fn sign_message(&self, address: &Address, message: &[u8]) -> Result<Bytes, SignatureError> {
+        let signature = self.sign_hash(address, eip191_hash_message(message))?;
+        Ok(signature_bytes(&signature))
+    }
+
+    /// Signs the hash of the typed data, as specified by EIP-712, with the key of the account,
+    /// and returns the 65 bytes of the signature, as `eth_signTypedData_v4`.
+    pub fn sign_typed_data(&self, address: &Address, data: &TypedData) -> Result<Bytes, EthApiError> {
+        let hash =
+            data.encode_eip712().map_err(|err| EthApiError::InvalidParams(format!("invalid typed data: {err}")))?;
+        let signature = self.sign_hash(address, B256::from(hash))?;
+        Ok(signature_bytes(&signature))
+    }
+
+    fn sign_hash(&self, address: &Address, hash: B256) -> Result<Signature, SignatureError> {
+        let private_key = self.private_keys.get(address).ok_or(SignatureError::UnknownAccount(*address))?;
+        sign_message(*private_key, hash).map_err(|_| SignatureError::SignError)
+    }
+}
+
+/// Encodes the signature as the 32 bytes of r, the 32 bytes of s and the recovery id v (27 or 28).
+fn signature_bytes(signature: &Signature) -> Bytes {
+    let mut bytes = Vec::with_capacity(65);
+    bytes.extend_from_slice(&signature.r.to_be_bytes::<32>());
+    bytes.extend_from_slice(&signature.s.to_be_bytes::<32>());
+    bytes.push(27 + u8::from(signature.odd_y_parity));
+    bytes.into()
+}
+
+impl std::fmt::Debug for Signers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The private keys are never printed
+        f.debug_struct("Signers").field("addresses", &self.addresses()).finish_non_exhaustive()
+    }
+}
+
+/// Decrypts the keystores of the directory with the password, skipping the ones encrypted with
+/// another passphrase.
+fn read_keystores(dir: &Path, password: &str) -> Result<Vec<B256>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(eyre!("Failed to read {}: {err}", dir.display())),
+    };
+
+    let mut private_keys = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        match LocalWallet::decrypt_keystore(&path, password) {
+            Ok(wallet) => private_keys.push(B256::from_slice(wallet.signer().to_bytes().as_slice())),
+            Err(err) => tracing::debug!("Keystore {} stays locked: {err}", path.display()),
+        }
+    }
+    Ok(private_keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::{TransactionKind, TxLegacy, U256};
+
+    use super::*;
+
+    // Private key of the first account of the "test test ... junk" mnemonic
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    #[test]
+    fn test_signers_sign_transaction() {
+        // Given
+        let signers = Signers::new([PRIVATE_KEY.parse().unwrap()]).unwrap();
+        let address: Address = ADDRESS.parse().unwrap();
+        let transaction = Transaction::Legacy(TxLegacy {
+            chain_id: Some(1),
+            nonce: 0,
+            gas_price: 1,
+            gas_limit: 21_000,
+            to: TransactionKind::Call(Address::ZERO),
+            value: U256::from(1),
+            input: Bytes::default(),
+        });
+
+        // When
+        let signed = signers.sign_transaction(&address, transaction).unwrap();
+
+        // Then
+        assert_eq!(signers.addresses(), vec![address]);
+        assert_eq!(signed.recover_signer(), Some(address));
+    }
+
+    #[test]
+    fn test_signers_sign_message() {
+        // Given
+        let signers = Signers::new([PRIVATE_KEY.parse().unwrap()]).unwrap();
+        let address: Address = ADDRESS.parse().unwrap();
+
+        // When
+        let signature = signers.sign_message(&address, b"hello").unwrap();
+
+        // Then
+        assert_eq!(signature.len(), 65);
+        assert!(signature[64] == 27 || signature[64] == 28);
+    }
+
+    #[test]
+    fn test_signers_sign_typed_data() {
+        // Given
+        // Mail example of EIP-712, signed with the private key of "cow"
+        let private_key = reth_primitives::keccak256("cow");
+        let signers = Signers::new([private_key]).unwrap();
+        let address: Address = "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".parse().unwrap();
+        let data: TypedData = serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Person": [{"name": "name", "type": "string"}, {"name": "wallet", "type": "address"}],
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "Person"},
+                    {"name": "contents", "type": "string"}
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                "contents": "Hello, Bob!"
+            }
+        }))
+        .unwrap();
+
+        // When
+        let signature = signers.sign_typed_data(&address, &data).unwrap();
+
+        // Then
+        // Signature given by EIP-712
+        assert_eq!(
+            signature,
+            "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c"
+                .parse::<Bytes>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_signers_unknown_account() {
+        // Given
+        let signers = Signers::default();
+
+        // When
+        let result = signers.sign_message(&Address::ZERO, b"hello");
+
+        // Then
+        assert!(matches!(result, Err(SignatureError::UnknownAccount(address)) if address == Address::ZERO));
+    }
+}
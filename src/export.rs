@@ -0,0 +1,319 @@
+//! Export of the Ethereum blocks served by the RPC, run with `kakarot-rpc export`. The blocks
+//! are fetched concurrently and written in order, either as RLP, which can be imported by the
+//! Ethereum clients, or as JSON lines. An interrupted export is resumed from its progress file.
+
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use alloy_rlp::Encodable;
+use eyre::{eyre, Result};
+use futures::{StreamExt, TryStreamExt};
+use reth_primitives::{Block, BlockId, BlockNumberOrTag, Receipt, ReceiptWithBloom, TxType};
+use reth_rpc_types::TransactionReceipt;
+use serde::{Deserialize, Serialize};
+
+use crate::eth_provider::provider::EthereumProvider;
+
+/// Default number of blocks fetched concurrently.
+const DEFAULT_EXPORT_CONCURRENCY: usize = 8;
+
+/// Format of the exported blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// The RLP encoded blocks, followed in a separate file by the RLP list of their receipts.
+    Rlp,
+    /// One JSON object per line, holding a block with its transactions and its receipts.
+    Jsonl,
+}
+
+impl FromStr for ExportFormat {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "rlp" => Ok(Self::Rlp),
+            "jsonl" | "json" => Ok(Self::Jsonl),
+            _ => Err(eyre!("Invalid export format {s}, expected rlp or jsonl")),
+        }
+    }
+}
+
+/// Configuration of the export, read from the arguments of `kakarot-rpc export`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportConfig {
+    /// First block exported.
+    pub from: u64,
+    /// Last block exported, the latest block if not set.
+    pub to: Option<u64>,
+    pub format: ExportFormat,
+    /// File the blocks are written to.
+    pub output: PathBuf,
+    /// Number of blocks fetched concurrently.
+    pub concurrency: usize,
+}
+
+impl ExportConfig {
+    /// Reads the configuration of the export from the arguments of the command, e.g.
+    /// `export --from 0 --to 1000 --format rlp --output blocks.rlp`. Returns None if the
+    /// command isn't `export`. The other flags, such as `--config`, are ignored.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut args = args.into_iter().peekable();
+        if args.next_if(|arg| arg == "export").is_none() {
+            return Ok(None);
+        }
+
+        let mut from = 0;
+        let mut to = None;
+        let mut format = ExportFormat::Jsonl;
+        let mut output = None;
+        let mut concurrency = DEFAULT_EXPORT_CONCURRENCY;
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            if !["--from", "--to", "--format", "--output", "--concurrency"].contains(&flag.as_str()) {
+                continue;
+            }
+            let value = value.or_else(|| args.next()).ok_or_else(|| eyre!("Missing value for {flag}"))?;
+            let number = || u64::from_str(value.trim()).map_err(|err| eyre!("Invalid {flag} {value}: {err}"));
+            match flag.as_str() {
+                "--from" => from = number()?,
+                "--to" => to = Some(number()?),
+                "--format" => format = value.parse()?,
+                "--output" => output = Some(PathBuf::from(&value)),
+                _ => concurrency = number()?.max(1) as usize,
+            }
+        }
+
+        let output = output.ok_or_else(|| eyre!("Missing --output for the export"))?;
+        if to.is_some_and(|to| to < from) {
+            return Err(eyre!("Invalid block range, --to is lower than --from"));
+        }
+        Ok(Some(Self { from, to, format, output, concurrency }))
+    }
+
+    /// Returns the file the receipts are written to, for the RLP format.
+    fn receipts_output(&self) -> Option<PathBuf> {
+        (self.format == ExportFormat::Rlp).then(|| with_suffix(&self.output, ".receipts"))
+    }
+
+    /// Returns the file the progress of the export is written to.
+    fn progress_output(&self) -> PathBuf {
+        with_suffix(&self.output, ".progress")
+    }
+}
+
+/// Returns the path with the suffix appended to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    path.into()
+}
+
+/// Progress of an export, written after each block. The files are truncated to the recorded
+/// lengths when the export is resumed, so that a partially written block is overwritten.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportProgress {
+    next_block: u64,
+    blocks_length: u64,
+    receipts_length: u64,
+}
+
+impl ExportProgress {
+    fn read(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write(&self, path: &Path) -> Result<()> {
+        // The progress is replaced atomically, so that it can't be read partially written
+        let tmp = with_suffix(path, ".tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+/// A block with its transactions and its receipts, as written in the JSON lines format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedBlock {
+    pub block: reth_rpc_types::Block,
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+impl ExportedBlock {
+    /// Encodes the block in the given format. For the RLP format, the RLP list of the receipts is
+    /// returned separately.
+    fn encode(self, format: ExportFormat) -> Result<(Vec<u8>, Vec<u8>)> {
+        match format {
+            ExportFormat::Jsonl => {
+                let mut line = serde_json::to_vec(&self)?;
+                line.push(b'\n');
+                Ok((line, Vec::new()))
+            }
+            ExportFormat::Rlp => {
+                let receipts = self.receipts.iter().map(to_primitive_receipt).collect::<Result<Vec<_>>>()?;
+                let block = Block::try_from(self.block).map_err(|err| eyre!("Failed to convert block: {err:?}"))?;
+
+                let (mut encoded_block, mut encoded_receipts) = (Vec::new(), Vec::new());
+                block.encode(&mut encoded_block);
+                receipts.encode(&mut encoded_receipts);
+                Ok((encoded_block, encoded_receipts))
+            }
+        }
+    }
+}
+
+/// Converts an RPC receipt into its consensus representation.
+fn to_primitive_receipt(receipt: &TransactionReceipt) -> Result<ReceiptWithBloom> {
+    let inner = receipt
+        .inner
+        .as_receipt_with_bloom()
+        .ok_or_else(|| eyre!("Unsupported receipt of transaction {}", receipt.transaction_hash))?;
+    let tx_type = TxType::try_from(Into::<u8>::into(receipt.transaction_type()))
+        .map_err(|_| eyre!("Unsupported type of transaction {}", receipt.transaction_hash))?;
+
+    Ok(Receipt {
+        tx_type,
+        success: inner.receipt.status,
+        cumulative_gas_used: inner.receipt.cumulative_gas_used as u64,
+        logs: inner.receipt.logs.iter().map(|log| log.inner.clone()).collect(),
+    }
+    .with_bloom())
+}
+
+/// Fetches a block with its transactions and its receipts.
+async fn fetch_block<P: EthereumProvider>(provider: &P, number: u64) -> Result<ExportedBlock> {
+    let block = provider
+        .block_by_number(BlockNumberOrTag::Number(number), true)
+        .await?
+        .ok_or_else(|| eyre!("Block {number} not found"))?;
+    let receipts =
+        provider.block_receipts(Some(BlockId::Number(BlockNumberOrTag::Number(number)))).await?.unwrap_or_default();
+    Ok(ExportedBlock { block: block.inner, receipts })
+}
+
+/// Opens the file for appending, truncated to the given length.
+fn open_truncated(path: &Path, length: u64) -> Result<BufWriter<File>> {
+    let mut file = OpenOptions::new().create(true).write(true).truncate(false).open(path)?;
+    file.set_len(length)?;
+    file.seek(SeekFrom::End(0))?;
+    Ok(BufWriter::new(file))
+}
+
+/// Exports the blocks of the range. If the progress file of the output exists, the export is
+/// resumed from the block following the last block written.
+/// Returns the number of blocks exported.
+pub async fn export_blocks<P: EthereumProvider>(provider: &P, config: &ExportConfig) -> Result<u64> {
+    let to = match config.to {
+        Some(to) => to,
+        None => provider.block_number().await?.to(),
+    };
+
+    let progress_output = config.progress_output();
+    let mut progress = match ExportProgress::read(&progress_output)? {
+        Some(progress) => {
+            tracing::info!("Resuming the export from block {}", progress.next_block);
+            progress
+        }
+        None => ExportProgress { next_block: config.from, ..Default::default() },
+    };
+
+    let mut blocks = open_truncated(&config.output, progress.blocks_length)?;
+    let mut receipts =
+        config.receipts_output().map(|path| open_truncated(&path, progress.receipts_length)).transpose()?;
+
+    // The blocks are fetched concurrently, but written in order
+    let mut stream = futures::stream::iter(progress.next_block..=to)
+        .map(|number| async move { fetch_block(provider, number).await.map(|block| (number, block)) })
+        .buffered(config.concurrency);
+
+    let mut exported = 0;
+    while let Some((number, block)) = stream.try_next().await? {
+        let (encoded_block, encoded_receipts) = block.encode(config.format)?;
+        blocks.write_all(&encoded_block)?;
+        blocks.flush()?;
+        if let Some(receipts) = receipts.as_mut() {
+            receipts.write_all(&encoded_receipts)?;
+            receipts.flush()?;
+        }
+
+        progress.next_block = number + 1;
+        progress.blocks_length += encoded_block.len() as u64;
+        progress.receipts_length += encoded_receipts.len() as u64;
+        progress.write(&progress_output)?;
+
+        exported += 1;
+        if exported % 1000 == 0 {
+            tracing::info!("Exported {exported} blocks, up to block {number}");
+        }
+    }
+
+    tracing::info!("Exported {exported} blocks to {}", config.output.display());
+    Ok(exported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_export_config_from_args() {
+        // Given
+        let args = args("export --config kakarot.toml --from=10 --to 20 --format rlp --output blocks.rlp");
+
+        // When
+        let config = ExportConfig::from_args(args).unwrap();
+
+        // Then
+        assert_eq!(
+            config,
+            Some(ExportConfig {
+                from: 10,
+                to: Some(20),
+                format: ExportFormat::Rlp,
+                output: PathBuf::from("blocks.rlp"),
+                concurrency: DEFAULT_EXPORT_CONCURRENCY,
+            })
+        );
+        assert_eq!(config.as_ref().unwrap().receipts_output(), Some(PathBuf::from("blocks.rlp.receipts")));
+        assert_eq!(config.unwrap().progress_output(), PathBuf::from("blocks.rlp.progress"));
+    }
+
+    #[test]
+    fn test_export_config_invalid_args() {
+        assert_eq!(ExportConfig::from_args(args("--index --config kakarot.toml")).unwrap(), None);
+        assert!(ExportConfig::from_args(args("export --from 10")).is_err());
+        assert!(ExportConfig::from_args(args("export --from 10 --to 5 --output blocks.jsonl")).is_err());
+        assert!(ExportConfig::from_args(args("export --format csv --output blocks.csv")).is_err());
+    }
+
+    #[test]
+    fn test_export_progress_round_trip() {
+        // Given
+        let path = std::env::temp_dir().join(format!("kakarot-export-{}.progress", std::process::id()));
+        let progress = ExportProgress { next_block: 42, blocks_length: 1024, receipts_length: 512 };
+
+        // When
+        progress.write(&path).unwrap();
+        let read = ExportProgress::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Then
+        assert_eq!(read, Some(progress));
+        assert_eq!(ExportProgress::read(&path).unwrap(), None);
+    }
+}
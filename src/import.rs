@@ -0,0 +1,342 @@
+//! Import of the Ethereum blocks written by `kakarot-rpc export`, run with `kakarot-rpc import`.
+//! The blocks are written to the database read by the provider, which bootstraps a new replica
+//! without indexing the Starknet chain again.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use alloy_rlp::Decodable;
+use eyre::{eyre, Result};
+use mongodb::bson::doc;
+use reth_primitives::{Block, ReceiptWithBloom, B256, B64, U256};
+use reth_rpc_types::{BlockTransactions, Header};
+
+use crate::eth_provider::constant::BLOCK_NUMBER_HEX_STRING_LEN;
+use crate::eth_provider::database::types::{checkpoint::StoredIndexerCheckpoint, header::StoredHeader};
+use crate::eth_provider::database::Database;
+use crate::eth_provider::indexer::{to_rpc_transaction_and_receipt, write_block, TransactionPosition};
+use crate::eth_provider::utils::into_filter;
+use crate::export::{ExportFormat, ExportedBlock};
+
+/// Configuration of the import, read from the arguments of `kakarot-rpc import`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportConfig {
+    pub format: ExportFormat,
+    /// File the blocks are read from. For the RLP format, the receipts are read from
+    /// `<input>.receipts`.
+    pub input: PathBuf,
+}
+
+impl ImportConfig {
+    /// Reads the configuration of the import from the arguments of the command, e.g.
+    /// `import --format rlp --input blocks.rlp`. Returns None if the command isn't `import`.
+    /// The other flags, such as `--config`, are ignored.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut args = args.into_iter().peekable();
+        if args.next_if(|arg| arg == "import").is_none() {
+            return Ok(None);
+        }
+
+        let mut format = ExportFormat::Jsonl;
+        let mut input = None;
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            if !["--format", "--input"].contains(&flag.as_str()) {
+                continue;
+            }
+            let value = value.or_else(|| args.next()).ok_or_else(|| eyre!("Missing value for {flag}"))?;
+            match flag.as_str() {
+                "--format" => format = value.parse()?,
+                _ => input = Some(PathBuf::from(value)),
+            }
+        }
+
+        let input = input.ok_or_else(|| eyre!("Missing --input for the import"))?;
+        Ok(Some(Self { format, input }))
+    }
+}
+
+/// Result of an import.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Number of blocks written to the database.
+    pub imported: u64,
+    /// Number of blocks skipped, as they were already in the database.
+    pub skipped: u64,
+}
+
+/// Reads the blocks of a JSON lines file.
+fn read_jsonl(config: &ImportConfig) -> Result<Vec<ExportedBlock>> {
+    BufReader::new(File::open(&config.input)?)
+        .lines()
+        .filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+/// Reads the blocks of an RLP file and their receipts. The RLP encoding of the blocks doesn't
+/// hold their hashes, which are read from the parent hashes of the following blocks: the last
+/// block of the file can't be imported, and is left to the following import or to the indexer.
+fn read_rlp(config: &ImportConfig) -> Result<Vec<ExportedBlock>> {
+    let blocks_file = std::fs::read(&config.input)?;
+    let mut receipts_path = config.input.clone().into_os_string();
+    receipts_path.push(".receipts");
+    let receipts_file = std::fs::read(PathBuf::from(receipts_path))?;
+
+    let (mut blocks_buf, mut receipts_buf) = (blocks_file.as_slice(), receipts_file.as_slice());
+    let mut blocks = Vec::new();
+    while !blocks_buf.is_empty() {
+        let block = Block::decode(&mut blocks_buf)?;
+        let receipts = Vec::<ReceiptWithBloom>::decode(&mut receipts_buf)?;
+        blocks.push((block, receipts));
+    }
+
+    let hashes = blocks.iter().skip(1).map(|(block, _)| block.parent_hash).collect::<Vec<_>>();
+    if blocks.len() > hashes.len() {
+        tracing::warn!("The last block of {} is skipped, as its hash is unknown", config.input.display());
+    }
+    blocks.into_iter().zip(hashes).map(|((block, receipts), hash)| from_rlp_block(block, receipts, hash)).collect()
+}
+
+/// Converts a block decoded from an RLP file and its receipts into their RPC representations.
+fn from_rlp_block(block: Block, receipts: Vec<ReceiptWithBloom>, hash: B256) -> Result<ExportedBlock> {
+    let Block { header, body, .. } = block;
+    if body.len() != receipts.len() {
+        return Err(eyre!("Block {} has {} transactions but {} receipts", header.number, body.len(), receipts.len()));
+    }
+
+    let mut rpc_transactions = Vec::with_capacity(body.len());
+    let mut rpc_receipts = Vec::with_capacity(receipts.len());
+    let mut previous_cumulative_gas_used = 0;
+    let mut log_index = 0;
+    for (index, (transaction, receipt)) in body.iter().zip(&receipts).enumerate() {
+        let signer = transaction
+            .recover_signer()
+            .ok_or_else(|| eyre!("Failed to recover the signer of transaction {}", transaction.hash))?;
+        let gas_used = receipt.receipt.cumulative_gas_used.saturating_sub(previous_cumulative_gas_used);
+        previous_cumulative_gas_used = receipt.receipt.cumulative_gas_used;
+
+        let position = TransactionPosition {
+            block_hash: hash,
+            block_number: header.number,
+            block_timestamp: header.timestamp,
+            transaction_index: index as u64,
+            log_index,
+        };
+        let (rpc_transaction, rpc_receipt) =
+            to_rpc_transaction_and_receipt(transaction, signer, receipt, gas_used.into(), &position);
+        log_index += receipt.receipt.logs.len() as u64;
+
+        rpc_transactions.push(rpc_transaction);
+        rpc_receipts.push(rpc_receipt);
+    }
+
+    let header = Header {
+        hash: Some(hash),
+        parent_hash: header.parent_hash,
+        uncles_hash: header.ommers_hash,
+        miner: header.beneficiary,
+        state_root: header.state_root,
+        transactions_root: header.transactions_root,
+        receipts_root: header.receipts_root,
+        logs_bloom: header.logs_bloom,
+        difficulty: header.difficulty,
+        number: Some(header.number),
+        gas_limit: header.gas_limit.into(),
+        gas_used: header.gas_used.into(),
+        timestamp: header.timestamp,
+        total_difficulty: Some(U256::ZERO),
+        extra_data: header.extra_data,
+        mix_hash: Some(header.mix_hash),
+        nonce: Some(B64::from(header.nonce.to_be_bytes())),
+        base_fee_per_gas: header.base_fee_per_gas.map(Into::into),
+        withdrawals_root: header.withdrawals_root,
+        blob_gas_used: header.blob_gas_used.map(Into::into),
+        excess_blob_gas: header.excess_blob_gas.map(Into::into),
+        parent_beacon_block_root: header.parent_beacon_block_root,
+    };
+    let block = reth_rpc_types::Block {
+        header,
+        uncles: Vec::new(),
+        transactions: BlockTransactions::Full(rpc_transactions),
+        size: None,
+        withdrawals: None,
+        other: Default::default(),
+    };
+    Ok(ExportedBlock { block, receipts: rpc_receipts })
+}
+
+/// Returns the number and the hash of the header, which must hold both.
+fn number_and_hash(header: &Header) -> Result<(u64, B256)> {
+    match (header.number, header.hash) {
+        (Some(number), Some(hash)) => Ok((number, hash)),
+        _ => Err(eyre!("Missing number or hash in an imported header")),
+    }
+}
+
+/// Checks that the block with the given number and parent hash follows the previous block of
+/// the chain, if it is known.
+fn check_continuity(previous: Option<(u64, B256)>, number: u64, parent_hash: B256) -> Result<()> {
+    let Some((previous_number, previous_hash)) = previous else {
+        return Ok(());
+    };
+    if previous_number.checked_add(1) != Some(number) {
+        return Err(eyre!("Block {number} doesn't follow block {previous_number}"));
+    }
+    if parent_hash != previous_hash {
+        return Err(eyre!(
+            "Parent hash {parent_hash} of block {number} doesn't match the hash {previous_hash} of block {previous_number}"
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the hash of the block stored in the database at the given height, if any.
+async fn stored_hash(database: &Database, number: u64) -> Result<Option<B256>> {
+    let filter = into_filter("header.number", &number, BLOCK_NUMBER_HEX_STRING_LEN);
+    Ok(database.get_one::<StoredHeader>(filter, None).await?.and_then(|stored| stored.header.hash))
+}
+
+/// Imports the blocks of the file in the database. The blocks must form a chain, which must
+/// extend the block before the first one if it is in the database. The blocks already in the
+/// database are skipped, but a block conflicting with the database aborts the import. Once
+/// done, the checkpoint of the built-in indexer is moved to the last imported block.
+pub async fn import_blocks(database: &Database, config: &ImportConfig) -> Result<ImportSummary> {
+    let blocks = match config.format {
+        ExportFormat::Jsonl => read_jsonl(config)?,
+        ExportFormat::Rlp => read_rlp(config)?,
+    };
+
+    let mut summary = ImportSummary::default();
+    let mut previous = None;
+    for ExportedBlock { block, receipts } in blocks {
+        let (number, hash) = number_and_hash(&block.header)?;
+        if previous.is_none() && number > 0 {
+            previous = stored_hash(database, number - 1).await?.map(|parent_hash| (number - 1, parent_hash));
+        }
+        check_continuity(previous, number, block.header.parent_hash)?;
+        previous = Some((number, hash));
+
+        match stored_hash(database, number).await? {
+            Some(stored) if stored == hash => {
+                summary.skipped += 1;
+                continue;
+            }
+            Some(stored) => {
+                return Err(eyre!("Block {number} with hash {hash} conflicts with the stored block {stored}"));
+            }
+            None => {}
+        }
+
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            return Err(eyre!("Block {number} doesn't hold its full transactions"));
+        };
+        if transactions.len() != receipts.len() {
+            return Err(eyre!(
+                "Block {number} has {} transactions but {} receipts",
+                transactions.len(),
+                receipts.len()
+            ));
+        }
+        write_block(database, block.header, transactions, receipts).await?;
+
+        summary.imported += 1;
+        if summary.imported % 1000 == 0 {
+            tracing::info!("Imported {} blocks, up to block {number}", summary.imported);
+        }
+    }
+
+    // The built-in indexer resumes after the imported blocks
+    if let Some((last, _)) = previous {
+        let checkpoint = database.get_one::<StoredIndexerCheckpoint>(None, None).await?;
+        if checkpoint.map_or(true, |checkpoint| checkpoint.last_indexed_block < last) {
+            database.update_one(StoredIndexerCheckpoint { last_indexed_block: last }, doc! {}, true).await?;
+        }
+    }
+
+    tracing::info!(
+        "Imported {} blocks from {}, skipped {} blocks already stored",
+        summary.imported,
+        config.input.display(),
+        summary.skipped
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        sign_message, Address, Bytes, Receipt, TransactionKind, TransactionSigned, TxEip1559, TxType,
+    };
+
+    #[test]
+    fn test_import_config_from_args() {
+        // Given
+        let args = ["import", "--format=rlp", "--input", "blocks.rlp", "--config", "kakarot.toml"].map(String::from);
+
+        // When
+        let config = ImportConfig::from_args(args).unwrap();
+
+        // Then
+        assert_eq!(config, Some(ImportConfig { format: ExportFormat::Rlp, input: PathBuf::from("blocks.rlp") }));
+        assert_eq!(ImportConfig::from_args(["export".to_string()]).unwrap(), None);
+        assert!(ImportConfig::from_args(["import".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_check_continuity() {
+        // Given
+        let parent = B256::with_last_byte(1);
+
+        // Then
+        assert!(check_continuity(None, 5, B256::ZERO).is_ok());
+        assert!(check_continuity(Some((4, parent)), 5, parent).is_ok());
+        assert!(check_continuity(Some((4, parent)), 5, B256::ZERO).is_err());
+        assert!(check_continuity(Some((3, parent)), 5, parent).is_err());
+    }
+
+    #[test]
+    fn test_from_rlp_block() {
+        // Given
+        let transaction = reth_primitives::Transaction::Eip1559(TxEip1559 {
+            chain_id: 1,
+            nonce: 0,
+            gas_limit: 21000,
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: 1,
+            to: TransactionKind::Call(Address::with_last_byte(1)),
+            value: U256::from(1),
+            access_list: Default::default(),
+            input: Bytes::default(),
+        });
+        let signature = sign_message(B256::with_last_byte(1), transaction.signature_hash()).unwrap();
+        let transaction = TransactionSigned::from_transaction_and_signature(transaction, signature);
+        let signer = transaction.recover_signer().unwrap();
+        let receipts = [21000, 42000]
+            .map(|cumulative_gas_used| {
+                Receipt { tx_type: TxType::Eip1559, success: true, cumulative_gas_used, logs: vec![] }.with_bloom()
+            })
+            .to_vec();
+        let mut block = Block { body: vec![transaction.clone(), transaction], ..Default::default() };
+        block.header.number = 7;
+        let hash = B256::with_last_byte(7);
+
+        // When
+        let ExportedBlock { block, receipts } = from_rlp_block(block, receipts, hash).unwrap();
+
+        // Then
+        assert_eq!(block.header.hash, Some(hash));
+        assert_eq!(block.header.number, Some(7));
+        assert_eq!(receipts.iter().map(|receipt| receipt.gas_used).collect::<Vec<_>>(), vec![21000, 21000]);
+        assert!(receipts.iter().all(|receipt| receipt.from == signer && receipt.block_hash == Some(hash)));
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            panic!("Expected full transactions");
+        };
+        assert_eq!(transactions.iter().map(|tx| tx.transaction_index).collect::<Vec<_>>(), vec![Some(0), Some(1)]);
+    }
+}
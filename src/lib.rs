@@ -1,8 +1,15 @@
+pub mod bench;
+pub mod chain_spec;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod config;
 pub mod eth_provider;
 pub mod eth_rpc;
+pub mod export;
+pub mod import;
 pub mod models;
 pub mod prometheus_handler;
+pub mod telemetry;
 #[cfg(feature = "testing")]
 pub mod test_utils;
 pub mod tracing;
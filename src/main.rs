@@ -3,40 +3,154 @@ use std::sync::Arc;
 
 use dotenvy::dotenv;
 use eyre::Result;
-use kakarot_rpc::config::{JsonRpcClientBuilder, KakarotRpcConfig, Network, SequencerGatewayProviderBuilder};
+use jsonrpsee::RpcModule;
+use kakarot_rpc::bench::{run_bench, BenchConfig};
+use kakarot_rpc::chain_spec::ChainSpec;
+use kakarot_rpc::config::{Config, JsonRpcClientBuilder, KakarotRpcConfig, Network, SequencerGatewayProviderBuilder};
+use kakarot_rpc::eth_provider::constant::{STARKNET_BROADCAST_TRANSACTIONS, TRACE_BACKFILL_INTERVAL};
 use kakarot_rpc::eth_provider::database::Database;
+use kakarot_rpc::eth_provider::indexer::{start_indexer_service, Indexer};
 use kakarot_rpc::eth_provider::pending_pool::start_retry_service;
 use kakarot_rpc::eth_provider::provider::EthDataProvider;
-use kakarot_rpc::eth_rpc::config::RPCConfig;
+use kakarot_rpc::eth_provider::pruning::{prune_database, start_pruning_service, PruningConfig, PRUNE_INTERVAL};
+use kakarot_rpc::eth_provider::starknet::circuit_breaker::CircuitBreakerConfig;
+use kakarot_rpc::eth_provider::starknet::http_pool::{HttpPoolConfig, HttpPoolMetrics, PooledTransport};
+use kakarot_rpc::eth_provider::starknet::katana::KatanaDevClient;
+use kakarot_rpc::eth_provider::starknet::transport::{
+    FailoverTransport, MetricsTransport, ProviderHealth, RetryPolicy, RetryTransport, StarknetMetrics,
+};
+use kakarot_rpc::eth_rpc::config::{RPCConfig, RpcApiConfig};
+use kakarot_rpc::eth_rpc::faucet::{Faucet, FaucetConfig, FaucetMetrics};
+use kakarot_rpc::eth_rpc::graphql::{graphql_schema, GraphQlSchema};
+use kakarot_rpc::eth_rpc::ipc::{ipc_path, run_ipc_server};
+use kakarot_rpc::eth_rpc::keystore::Keystore;
+use kakarot_rpc::eth_rpc::middleware::auth::{remove_protected_methods, AuthConfig};
+use kakarot_rpc::eth_rpc::middleware::namespaces::DisabledNamespaces;
 use kakarot_rpc::eth_rpc::rpc::KakarotRpcModuleBuilder;
-use kakarot_rpc::eth_rpc::run_server;
+use kakarot_rpc::eth_rpc::servers::admin_rpc::LogFilterHandle;
+use kakarot_rpc::eth_rpc::servers::starknet_rpc::StarknetRpc;
+use kakarot_rpc::eth_rpc::shutdown::ShutdownCoordinator;
+use kakarot_rpc::eth_rpc::signer::Signers;
+use kakarot_rpc::eth_rpc::{run_auth_server, run_server};
+use kakarot_rpc::export::{export_blocks, ExportConfig};
+use kakarot_rpc::import::{import_blocks, ImportConfig};
+use kakarot_rpc::telemetry::{self, TelemetryConfig};
+use kakarot_rpc::tracing::cache::{start_trace_backfill_service, TraceCache};
 use mongodb::options::{DatabaseOptions, ReadConcern, WriteConcern};
+use prometheus::Registry;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
+use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter};
 
 enum StarknetProvider {
-    JsonRpcClient(JsonRpcClient<HttpTransport>),
+    JsonRpcClient(JsonRpcClient<MetricsTransport<RetryTransport<FailoverTransport<PooledTransport<HttpTransport>>>>>),
     SequencerGatewayProvider(SequencerGatewayProvider),
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
+    // The values of the config file are used for the environment variables which aren't set
+    let config = Config::load(std::env::args().skip(1))?;
+    config.apply_to_env();
+    // The Kakarot deployment selected with the --chain flag provides the remaining contract addresses
+    let chain_spec = ChainSpec::from_args(std::env::args().skip(1))?;
+    if let Some(chain_spec) = &chain_spec {
+        chain_spec.apply_to_env();
+    }
     // Environment variables are safe to use after this
-    let filter = tracing_subscriber::EnvFilter::try_from_default_env()?;
-    tracing_subscriber::FmtSubscriber::builder().with_env_filter(filter).finish().try_init()?;
+    // The log filter can be replaced at runtime with admin_setLogLevel
+    let (filter, log_filter) = reload::Layer::new(EnvFilter::try_from_default_env()?);
+    // The spans are exported to the OTLP collector set with OTEL_EXPORTER_OTLP_ENDPOINT, if any
+    let telemetry = TelemetryConfig::from_env()?;
+    let telemetry_layer = telemetry.as_ref().map(TelemetryConfig::layer).transpose()?;
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(telemetry_layer)
+        .try_init()?;
+
+    // The endpoint given by --url is load tested instead of serving the RPC with the bench command
+    if let Some(bench) = BenchConfig::from_args(std::env::args().skip(1))? {
+        for report in run_bench(&bench).await? {
+            println!("{report}");
+        }
+        return Ok(());
+    }
+
+    // The blocks are exported instead of being served with the export command
+    let export = ExportConfig::from_args(std::env::args().skip(1))?;
+
+    // The built-in indexer is enabled with the --index flag or in the config file
+    let index = export.is_none() && (config.features.index || std::env::args().skip(1).any(|arg| arg == "--index"));
 
     let starknet_config = KakarotRpcConfig::from_env()?;
 
-    let rpc_config = RPCConfig::from_env()?;
+    // The Starknet providers, the dev API, the passthrough and the proof provider share a pool of
+    // connections, each provider being bounded to STARKNET_HTTP_MAX_CONNECTIONS concurrent requests
+    let pool = HttpPoolConfig::from_env()?;
+    let http_client = pool.build_client()?;
+
+    // The Anvil compatible dev API is served with the --dev flag or in the config file
+    let katana_dev = if config.features.dev || std::env::args().skip(1).any(|arg| arg == "--dev") {
+        Some(KatanaDevClient::new(starknet_config.network.provider_url()?, http_client.clone()))
+    } else {
+        None
+    };
 
+    // The starknet_ methods are proxied to the Starknet provider with the --starknet-passthrough
+    // flag or in the config file
+    let starknet_passthrough = if config.features.starknet_passthrough
+        || std::env::args().skip(1).any(|arg| arg == "--starknet-passthrough")
+    {
+        Some(StarknetRpc::from_env(starknet_config.network.provider_url()?, http_client.clone())?)
+    } else {
+        None
+    };
+
+    // The CORS policy and the virtual hosts can be overridden with the --http.corsdomain,
+    // --http.corsmethods, --http.corsheaders and --http.vhosts flags
+    let mut rpc_config = RPCConfig::from_env()?;
+    rpc_config.cors = rpc_config.cors.with_args(std::env::args().skip(1))?;
+    // The RPC is also served over the Unix domain socket set with the --ipcpath flag, if any
+    let ipc_path = ipc_path(std::env::args().skip(1))?;
+    // The APIs served by each server can be restricted with the --http.api, --authrpc.api and
+    // --ipc.api flags
+    let apis = RpcApiConfig::from_env()?.with_args(std::env::args().skip(1))?;
+
+    // Creating the prometheus registry to register the metrics
+    let registry = Registry::new();
+    let starknet_metrics = StarknetMetrics::new(&registry)?;
+
+    // Health of the Starknet JSON-RPC providers, reported by net_peerCount
+    let mut provider_health = None;
     let starknet_provider = match &starknet_config.network {
-        Network::Madara | Network::Katana | Network::Sharingan => {
-            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::with_http(&starknet_config).unwrap().build())
-        }
-        Network::JsonRpcProvider(url) => {
-            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::new(HttpTransport::new(url.clone())).build())
+        Network::Madara
+        | Network::Katana
+        | Network::Sharingan
+        | Network::JsonRpcProvider(_)
+        | Network::JsonRpcProviders(_) => {
+            let pool_metrics = HttpPoolMetrics::new(&registry)?;
+            let transports = starknet_config
+                .network
+                .provider_urls()?
+                .into_iter()
+                .map(|url| {
+                    let transport = HttpTransport::new_with_client(url.clone(), http_client.clone());
+                    PooledTransport::new(transport, &url, pool.max_connections, &pool_metrics)
+                })
+                .collect();
+            let transport = FailoverTransport::new(transports, CircuitBreakerConfig::from_env()?)
+                .with_broadcast(*STARKNET_BROADCAST_TRANSACTIONS);
+            provider_health = Some(transport.health());
+            // Requests failing on all the providers with a transient error are retried
+            let transport =
+                RetryTransport::new(transport, RetryPolicy::from_env()?).with_metrics(starknet_metrics.clone());
+            StarknetProvider::JsonRpcClient(
+                JsonRpcClientBuilder::new(MetricsTransport::new(transport, starknet_metrics)).build(),
+            )
         }
         _ => StarknetProvider::SequencerGatewayProvider(
             SequencerGatewayProviderBuilder::new(&starknet_config.network).build(),
@@ -50,6 +164,41 @@ async fn main() -> Result<()> {
         &var("MONGO_DATABASE_NAME").expect("Missing MONGO_DATABASE_NAME from .env"),
         DatabaseOptions::builder().read_concern(ReadConcern::MAJORITY).write_concern(WriteConcern::MAJORITY).build(),
     ));
+    // The indexes are created by the first RPC connecting to the database. A read only
+    // database user can't create them, in which case the logs are queried without them.
+    if let Err(err) = db.create_log_indexes().await {
+        tracing::warn!("Failed to create the indexes of the logs collection: {err}");
+    }
+    if let Err(err) = db.create_transaction_indexes().await {
+        tracing::warn!("Failed to create the indexes of the transactions collections: {err}");
+    }
+    if let Err(err) = db.create_transfer_indexes().await {
+        tracing::warn!("Failed to create the indexes of the transfers collection: {err}");
+    }
+    if let Err(err) = db.create_internal_transaction_indexes().await {
+        tracing::warn!("Failed to create the indexes of the internal transactions collection: {err}");
+    }
+    // The transaction traces are cached in the database, unless TRACE_CACHE_TTL is set to 0
+    let trace_cache = TraceCache::from_env(db.clone());
+    if let Some(trace_cache) = &trace_cache {
+        if let Err(err) = trace_cache.create_indexes().await {
+            tracing::warn!("Failed to create the indexes of the traces collection: {err}");
+        }
+    }
+
+    // The blocks of a file written by the export command are written to the database with the
+    // import command, instead of serving the RPC
+    if let Some(import) = ImportConfig::from_args(std::env::args().skip(1))? {
+        import_blocks(&db, &import).await?;
+        return Ok(());
+    }
+
+    // The old records of the database are pruned once with the prune command, instead of
+    // serving the RPC
+    if let Some(pruning) = PruningConfig::from_args(std::env::args().skip(1))? {
+        prune_database(&db, &pruning).await?;
+        return Ok(());
+    }
 
     // Get the deployer nonce and set the value in the DEPLOY_WALLET_NONCE
     #[cfg(feature = "hive")]
@@ -72,28 +221,221 @@ async fn main() -> Result<()> {
         *nonce = deployer_nonce;
     }
 
-    let kakarot_rpc_module = match starknet_provider {
+    // Stops the servers and the background services on SIGTERM or SIGINT
+    let mut shutdown = ShutdownCoordinator::from_env();
+
+    // The retention policies are applied every PRUNE_INTERVAL seconds, if any
+    let pruning = PruningConfig::from_env();
+    if export.is_none() && pruning.is_enabled() && *PRUNE_INTERVAL > 0 {
+        shutdown.spawn_service(start_pruning_service(db.clone(), pruning, shutdown.signal()));
+    }
+
+    // The admin namespace is only served by the authenticated server
+    let auth_config = AuthConfig::from_env()?;
+    let disabled_namespaces = DisabledNamespaces::default();
+
+    // Like geth, the operator's accounts are only unlocked on a public server with the
+    // --allow-insecure-unlock flag, since any website visited by a user could use them otherwise
+    let allow_insecure_unlock =
+        config.features.allow_insecure_unlock || std::env::args().skip(1).any(|arg| arg == "--allow-insecure-unlock");
+    let unlock = auth_config.is_some() || allow_insecure_unlock;
+
+    // The transactions and messages of the operator's accounts are signed by the RPC, if any
+    let signers = match Signers::from_env()? {
+        Some(_) if !unlock => {
+            tracing::warn!(
+                "The signers aren't served without authentication, set RPC_AUTH_API_KEYS or RPC_AUTH_JWT_SECRET, \
+                 or pass --allow-insecure-unlock"
+            );
+            None
+        }
+        signers => signers,
+    };
+    // The accounts of the keystore are managed with the personal namespace, which unlocks them and
+    // writes new keystores, so it's gated like the signers
+    let keystore = match Keystore::from_env() {
+        Some(_) if !unlock => {
+            tracing::warn!(
+                "The personal namespace isn't served without authentication, set RPC_AUTH_API_KEYS or \
+                 RPC_AUTH_JWT_SECRET, or pass --allow-insecure-unlock"
+            );
+            None
+        }
+        keystore => keystore,
+    };
+    // The faucet is served with the --faucet flag or in the config file
+    let faucet = if config.features.faucet || std::env::args().skip(1).any(|arg| arg == "--faucet") {
+        let faucet = Faucet::new(&FaucetConfig::from_env()?)?.with_metrics(FaucetMetrics::new(&registry)?);
+        tracing::info!("Faucet dripping from {}", faucet.account());
+        Some(Arc::new(faucet))
+    } else {
+        None
+    };
+
+    // The GraphQL queries are served on /graphql with the --graphql flag or in the config file
+    let graphql = config.features.graphql || std::env::args().skip(1).any(|arg| arg == "--graphql");
+
+    let options = RpcModuleOptions {
+        index,
+        graphql,
+        chain_spec,
+        export,
+        trace_cache,
+        provider_health,
+        log_filter: auth_config.is_some().then_some(log_filter),
+        disabled_namespaces: disabled_namespaces.clone(),
+        katana_dev,
+        signers,
+        keystore,
+        faucet,
+        starknet_passthrough,
+        http_client,
+        apis,
+    };
+    let rpc_module = match starknet_provider {
         StarknetProvider::JsonRpcClient(starknet_provider) => {
-            let starknet_provider = Arc::new(starknet_provider);
-            let eth_provider = EthDataProvider::new(db.clone(), starknet_provider).await?;
-            tokio::spawn(start_retry_service(eth_provider.clone()));
-            KakarotRpcModuleBuilder::new(eth_provider).rpc_module()?
+            rpc_module(Arc::new(starknet_provider), &db, &options, &mut shutdown).await?
         }
         StarknetProvider::SequencerGatewayProvider(starknet_provider) => {
-            let starknet_provider = Arc::new(starknet_provider);
-            let eth_provider = EthDataProvider::new(db.clone(), starknet_provider).await?;
-            tokio::spawn(start_retry_service(eth_provider.clone()));
-            KakarotRpcModuleBuilder::new(eth_provider).rpc_module()?
+            rpc_module(Arc::new(starknet_provider), &db, &options, &mut shutdown).await?
         }
     };
+    // The blocks were exported instead of serving the RPC
+    let Some((modules, graphql_schema)) = rpc_module else {
+        return Ok(());
+    };
+    let ServerModules { http: mut kakarot_rpc_module, authrpc: auth_rpc_module, ipc: ipc_rpc_module } = modules;
+
+    // Like geth's, the IPC server serves the protected methods, since only local clients can connect to it
+    if let Some(ipc_path) = ipc_path {
+        let ipc_server_handle = run_ipc_server(ipc_rpc_module, &ipc_path, disabled_namespaces.clone()).await?;
+        println!("IPC Server running on {}...", ipc_path.display());
+        shutdown.register_server(ipc_server_handle);
+    }
+
+    // When authentication is enabled, the protected methods are only served by the authenticated server
+    if let Some(auth_config) = auth_config {
+        remove_protected_methods(&mut kakarot_rpc_module);
 
-    let (socket_addr, server_handle) = run_server(kakarot_rpc_module, rpc_config).await?;
+        let (auth_socket_addr, auth_server_handle) =
+            run_auth_server(auth_rpc_module, auth_config, disabled_namespaces.clone()).await?;
+        println!("Authenticated RPC Server running on http://{auth_socket_addr}...");
+        shutdown.register_server(auth_server_handle);
+    }
+
+    let serves_graphql = graphql_schema.is_some();
+    let (socket_addr, server_handle) =
+        run_server(kakarot_rpc_module, rpc_config, registry, disabled_namespaces, telemetry.is_some(), graphql_schema)
+            .await?;
 
     let url = format!("http://{}", socket_addr);
+    let ws_url = format!("ws://{}", socket_addr);
 
-    println!("RPC Server running on {url}...");
+    println!("RPC Server running on {url} (websocket: {ws_url})...");
+    if serves_graphql {
+        println!("GraphQL queries served on {url}/graphql");
+    }
 
-    server_handle.stopped().await;
+    shutdown.register_server(server_handle);
+    shutdown.run_until_signal().await;
+    if telemetry.is_some() {
+        telemetry::shutdown();
+    }
 
     Ok(())
 }
+
+/// The services and the optional modules of the RPC, which don't depend on the Starknet provider.
+struct RpcModuleOptions {
+    index: bool,
+    graphql: bool,
+    chain_spec: Option<ChainSpec>,
+    export: Option<ExportConfig>,
+    trace_cache: Option<TraceCache>,
+    provider_health: Option<ProviderHealth>,
+    /// The handle of the log filter, only set when the admin namespace is served.
+    log_filter: Option<LogFilterHandle>,
+    disabled_namespaces: DisabledNamespaces,
+    katana_dev: Option<KatanaDevClient>,
+    signers: Option<Signers>,
+    keystore: Option<Keystore>,
+    faucet: Option<Arc<Faucet>>,
+    starknet_passthrough: Option<StarknetRpc>,
+    /// The HTTP client shared with the Starknet transports.
+    http_client: reqwest_011::Client,
+    apis: RpcApiConfig,
+}
+
+/// The RPC modules of the servers, restricted to their APIs.
+struct ServerModules {
+    http: RpcModule<()>,
+    authrpc: RpcModule<()>,
+    ipc: RpcModule<()>,
+}
+
+/// Starts the services using the Starknet provider and returns the RPC modules of the servers, along
+/// with the GraphQL schema if the GraphQL queries are served. Returns None if the blocks were
+/// exported instead.
+async fn rpc_module<SP>(
+    starknet_provider: SP,
+    db: &Database,
+    options: &RpcModuleOptions,
+    shutdown: &mut ShutdownCoordinator,
+) -> Result<Option<(ServerModules, Option<GraphQlSchema>)>>
+where
+    SP: starknet::providers::Provider + Clone + Send + Sync + 'static,
+{
+    let eth_provider = EthDataProvider::new(db.clone(), starknet_provider.clone())
+        .await?
+        .with_http_client(options.http_client.clone());
+    if options.index {
+        let indexer = Indexer::new(db.clone(), starknet_provider).with_cache(eth_provider.cache());
+        shutdown.spawn_service(start_indexer_service(indexer, shutdown.signal()));
+    }
+    if let Some(chain_spec) = &options.chain_spec {
+        chain_spec.check_chain_id(&eth_provider).await?;
+    }
+    if let Some(export) = &options.export {
+        export_blocks(&eth_provider, export).await?;
+        return Ok(None);
+    }
+    shutdown.spawn_service(start_retry_service(eth_provider.clone(), shutdown.signal()));
+    if let Some(trace_cache) = options.trace_cache.clone().filter(|_| *TRACE_BACKFILL_INTERVAL > 0) {
+        shutdown.spawn_service(start_trace_backfill_service(eth_provider.clone(), trace_cache, shutdown.signal()));
+    }
+
+    let graphql_schema = options.graphql.then(|| graphql_schema(eth_provider.clone()));
+    let mut builder = KakarotRpcModuleBuilder::new(eth_provider)
+        .with_net_status(options.provider_health.clone(), Some(shutdown.listening()));
+    if let Some(log_filter) = &options.log_filter {
+        builder = builder.with_admin(
+            options.provider_health.clone(),
+            Some(log_filter.clone()),
+            options.disabled_namespaces.clone(),
+        );
+    }
+    if let Some(trace_cache) = &options.trace_cache {
+        builder = builder.with_trace_cache(trace_cache.clone());
+    }
+    if let Some(katana_dev) = &options.katana_dev {
+        builder = builder.with_dev(katana_dev.clone());
+    }
+    if let Some(signers) = &options.signers {
+        builder = builder.with_signers(signers.clone());
+    }
+    if let Some(keystore) = &options.keystore {
+        builder = builder.with_personal(keystore.clone());
+    }
+    if let Some(faucet) = &options.faucet {
+        builder = builder.with_faucet(faucet.clone());
+    }
+    if let Some(starknet_passthrough) = &options.starknet_passthrough {
+        builder = builder.with_starknet_passthrough(starknet_passthrough.clone());
+    }
+    let modules = ServerModules {
+        http: builder.rpc_module_with_apis(options.apis.http.as_ref())?,
+        authrpc: builder.rpc_module_with_apis(options.apis.authrpc.as_ref())?,
+        ipc: builder.rpc_module_with_apis(options.apis.ipc.as_ref())?,
+    };
+    Ok(Some((modules, graphql_schema)))
+}
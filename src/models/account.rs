@@ -0,0 +1,63 @@
+use reth_primitives::{B256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// Type of the Kakarot account of an EVM address, as returned by `kakarot_getAccountType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountType {
+    /// No account is deployed at the Starknet address of the EVM address.
+    Undeployed,
+    /// The account of an externally owned account, which sent transactions or has no code.
+    Eoa,
+    /// The account of a contract, which holds its EVM bytecode.
+    Contract,
+}
+
+/// State of an account, as returned by `eth_getAccount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthAccount {
+    /// Keccak256 hash of the EVM bytecode of the account.
+    pub code_hash: B256,
+    /// Root of the storage tree of the Starknet contract of the account.
+    pub storage_root: B256,
+    pub balance: U256,
+    pub nonce: U64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_type_serialization() {
+        assert_eq!(serde_json::to_string(&AccountType::Eoa).unwrap(), "\"eoa\"");
+        assert_eq!(serde_json::to_string(&AccountType::Contract).unwrap(), "\"contract\"");
+        assert_eq!(serde_json::from_str::<AccountType>("\"undeployed\"").unwrap(), AccountType::Undeployed);
+    }
+
+    #[test]
+    fn test_eth_account_serialization() {
+        // Given
+        let account = EthAccount {
+            code_hash: reth_primitives::KECCAK_EMPTY,
+            storage_root: reth_primitives::constants::EMPTY_ROOT_HASH,
+            balance: U256::from(1000),
+            nonce: U64::from(2),
+        };
+
+        // When
+        let serialized = serde_json::to_value(account).unwrap();
+
+        // Then
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "codeHash": "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+                "storageRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+                "balance": "0x3e8",
+                "nonce": "0x2"
+            })
+        );
+    }
+}
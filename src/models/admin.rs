@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use crate::eth_provider::starknet::circuit_breaker::CircuitState;
+
+/// Status of a Starknet provider, as returned by `admin_providerHealth`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStatus {
+    /// Index of the provider in the list of provider URLs.
+    pub index: usize,
+    /// State of the circuit breaker of the provider.
+    pub state: CircuitState,
+}
@@ -8,13 +8,16 @@ use serde::{Deserialize, Serialize};
 use crate::eth_provider::error::EthApiError;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TokenBalance {
+    #[serde(rename = "contractAddress")]
     pub token_address: Address,
     pub token_balance: Option<U256>,
     pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TokenBalances {
     pub address: Address,
     pub token_balances: Vec<TokenBalance>,
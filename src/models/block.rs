@@ -1,8 +1,60 @@
 use crate::eth_provider::constant::STARKNET_MODULUS;
 use crate::{eth_provider::error::EthereumDataFormatError, into_via_try_wrapper};
-use reth_primitives::{BlockId as EthereumBlockId, BlockNumberOrTag, U256};
+use alloy_rlp::Encodable;
+use reth_primitives::constants::{EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH};
+use reth_primitives::{BlockId as EthereumBlockId, BlockNumberOrTag, B256, B64, U256};
+use reth_rpc_types::{Block, BlockTransactions, Header, RichBlock};
 use starknet::core::types::{BlockId as StarknetBlockId, BlockTag};
 
+/// A block returned by the RPC, completed with the fields which are required by the Ethereum
+/// specification but meaningless on Kakarot, as several SDKs fail to decode a block missing
+/// them: Kakarot blocks have no uncles, no withdrawals and use no blob gas.
+#[derive(Debug, Clone)]
+pub struct EthBlock {
+    header: Header,
+    transactions: BlockTransactions,
+}
+
+impl EthBlock {
+    /// Create a new [`EthBlock`], filling the fields missing from the header. The fields which
+    /// are set are kept.
+    pub fn new(mut header: Header, transactions: BlockTransactions) -> Self {
+        if header.uncles_hash.is_zero() {
+            header.uncles_hash = EMPTY_OMMER_ROOT_HASH;
+        }
+        header.withdrawals_root.get_or_insert(EMPTY_ROOT_HASH);
+        header.blob_gas_used.get_or_insert(0);
+        header.excess_blob_gas.get_or_insert(0);
+        header.parent_beacon_block_root.get_or_insert(B256::ZERO);
+        header.mix_hash.get_or_insert(B256::ZERO);
+        header.nonce.get_or_insert(B64::ZERO);
+        header.total_difficulty.get_or_insert(U256::ZERO);
+        Self { header, transactions }
+    }
+
+    /// Returns the size of the block, computed from its header as reth does.
+    /// `https://github.com/paradigmxyz/reth/blob/v0.2.0-beta.5/crates/rpc/rpc-types-compat/src/block.rs#L66`
+    pub fn size(&self) -> Result<U256, EthereumDataFormatError> {
+        let header = reth_primitives::Header::try_from(self.header.clone())
+            .map_err(|_| EthereumDataFormatError::PrimitiveError)?;
+        Ok(U256::from(header.length()))
+    }
+
+    /// Converts the block into the block returned by the RPC, with an empty list of uncles and
+    /// of withdrawals.
+    pub fn into_rich_block(self, size: Option<U256>) -> RichBlock {
+        Block {
+            header: self.header,
+            transactions: self.transactions,
+            uncles: Vec::new(),
+            size,
+            withdrawals: Some(Vec::new()),
+            other: Default::default(),
+        }
+        .into()
+    }
+}
+
 #[derive(Debug)]
 pub struct EthBlockId(EthereumBlockId);
 
@@ -68,6 +120,7 @@ impl From<EthBlockNumberOrTag> for StarknetBlockId {
 
 #[cfg(test)]
 mod tests {
+    use super::EthBlock;
     use crate::models::transaction::rpc_to_primitive_transaction;
     use std::str::FromStr;
 
@@ -205,4 +258,85 @@ mod tests {
         assert!(primitive_block.withdrawals.is_none());
         assert_eq!(primitive_block.ommers, Vec::default());
     }
+
+    /// The fields of a block required by the Ethereum JSON-RPC specification.
+    const SPEC_BLOCK_FIELDS: [&str; 26] = [
+        "hash",
+        "parentHash",
+        "sha3Uncles",
+        "miner",
+        "stateRoot",
+        "transactionsRoot",
+        "receiptsRoot",
+        "logsBloom",
+        "difficulty",
+        "number",
+        "gasLimit",
+        "gasUsed",
+        "timestamp",
+        "totalDifficulty",
+        "extraData",
+        "mixHash",
+        "nonce",
+        "baseFeePerGas",
+        "withdrawalsRoot",
+        "blobGasUsed",
+        "excessBlobGas",
+        "parentBeaconBlockRoot",
+        "size",
+        "transactions",
+        "uncles",
+        "withdrawals",
+    ];
+
+    #[test]
+    fn test_eth_block_fills_spec_fields() {
+        // Given
+        let header = reth_rpc_types::Header {
+            hash: Some(B256::with_last_byte(1)),
+            number: Some(1),
+            base_fee_per_gas: Some(1),
+            ..Default::default()
+        };
+
+        // When
+        let block = EthBlock::new(header, reth_rpc_types::BlockTransactions::Hashes(vec![]));
+        let size = block.size().unwrap();
+        let serialized = serde_json::to_value(block.into_rich_block(Some(size))).unwrap();
+
+        // Then
+        for field in SPEC_BLOCK_FIELDS {
+            assert!(serialized.get(field).is_some_and(|value| !value.is_null()), "missing field {field}");
+        }
+        assert_eq!(serialized["uncles"], serde_json::json!([]));
+        assert_eq!(serialized["withdrawals"], serde_json::json!([]));
+        assert_eq!(serialized["withdrawalsRoot"], serde_json::json!(reth_primitives::constants::EMPTY_ROOT_HASH));
+        assert_eq!(serialized["sha3Uncles"], serde_json::json!(reth_primitives::constants::EMPTY_OMMER_ROOT_HASH));
+        assert_eq!(serialized["blobGasUsed"], serde_json::json!("0x0"));
+        assert_eq!(serialized["excessBlobGas"], serde_json::json!("0x0"));
+    }
+
+    #[test]
+    fn test_eth_block_round_trip() {
+        // Given
+        let block = base_rpc_block();
+
+        // When
+        let eth_block = EthBlock::new(block.header.clone(), block.transactions.clone());
+        let size = eth_block.size().unwrap();
+        let rich_block = eth_block.into_rich_block(Some(size));
+        let serialized = serde_json::to_string(&rich_block).unwrap();
+        let deserialized: reth_rpc_types::Block = serde_json::from_str(&serialized).unwrap();
+        let primitive_block = Block::try_from(deserialized.clone()).unwrap();
+
+        // Then
+        // The fields which are set are kept, the missing ones are filled
+        assert_eq!(deserialized, rich_block.inner);
+        assert_eq!(deserialized.header.uncles_hash, block.header.uncles_hash);
+        assert_eq!(deserialized.header.blob_gas_used, Some(9));
+        assert_eq!(deserialized.header.withdrawals_root, Some(reth_primitives::constants::EMPTY_ROOT_HASH));
+        assert_eq!(deserialized.header.total_difficulty, Some(U256::ZERO));
+        assert_eq!(primitive_block.ommers, Vec::default());
+        assert_eq!(primitive_block.withdrawals.map(|withdrawals| withdrawals.len()), Some(0));
+    }
 }
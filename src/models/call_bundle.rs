@@ -0,0 +1,49 @@
+use reth_primitives::{Bytes, U64};
+use reth_rpc_types::{state::StateOverride, TransactionRequest};
+use serde::{Deserialize, Serialize};
+
+/// A call of a bundle simulated by `eth_callMany` or `debug_traceCallMany`. The state overrides
+/// are applied on top of the state left by the previous calls of the bundle, before the call.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleCall {
+    pub transaction: TransactionRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+}
+
+/// Result of a call of a bundle simulated by `eth_callMany`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleCallResult {
+    /// Output of the call, or revert data if the call reverted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Bytes>,
+    /// Reason of the failure of the call, if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub gas_used: U64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_call_serde() {
+        // Given
+        let call = serde_json::json!({
+            "transaction": { "to": "0x0000000000000000000000000000000000000001", "input": "0x1234" },
+            "stateOverrides": { "0x0000000000000000000000000000000000000001": { "balance": "0x1" } }
+        });
+
+        // When
+        let call: BundleCall = serde_json::from_value(call).expect("Invalid bundle call");
+        let result = serde_json::to_value(BundleCallResult { gas_used: U64::from(21_000), ..Default::default() })
+            .expect("Failed to serialize bundle call result");
+
+        // Then
+        assert_eq!(call.state_overrides.map(|overrides| overrides.len()), Some(1));
+        assert_eq!(result, serde_json::json!({ "gasUsed": "0x5208" }));
+    }
+}
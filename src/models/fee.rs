@@ -0,0 +1,158 @@
+use reth_primitives::{B256, U256};
+use serde::{Deserialize, Serialize};
+
+/// Token in which a Starknet fee is paid: ETH (wei) or STRK (fri).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeUnit {
+    Wei,
+    Fri,
+}
+
+/// Fees of a Kakarot transaction: the EVM fee charged to the sender and the fee actually paid on
+/// Starknet for the wrapping transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionFeeBreakdown {
+    /// Hash of the Ethereum transaction.
+    pub transaction_hash: B256,
+    /// Hash of the Starknet transaction wrapping it.
+    pub starknet_transaction_hash: B256,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    /// Fee charged to the sender, in wei: the gas used times the effective gas price.
+    pub evm_fee: U256,
+    /// Fee paid on Starknet, in the unit of its fee token.
+    pub starknet_fee: U256,
+    pub starknet_fee_unit: FeeUnit,
+    /// Starknet fee paid for each wei of EVM fee, or null if the EVM fee is zero. For a fee paid
+    /// in wei, a rate below 1 means the EVM fee marks up the Starknet fee. For a fee paid in fri,
+    /// it is the implied STRK/ETH conversion rate.
+    pub implied_conversion_rate: Option<f64>,
+}
+
+impl TransactionFeeBreakdown {
+    /// Create a new [`TransactionFeeBreakdown`], deriving the EVM fee and the conversion rate.
+    pub fn new(
+        transaction_hash: B256,
+        starknet_transaction_hash: B256,
+        gas_used: U256,
+        effective_gas_price: U256,
+        starknet_fee: U256,
+        starknet_fee_unit: FeeUnit,
+    ) -> Self {
+        let evm_fee = gas_used.saturating_mul(effective_gas_price);
+        let implied_conversion_rate = (!evm_fee.is_zero()).then(|| f64::from(starknet_fee) / f64::from(evm_fee));
+        Self {
+            transaction_hash,
+            starknet_transaction_hash,
+            gas_used,
+            effective_gas_price,
+            evm_fee,
+            starknet_fee,
+            starknet_fee_unit,
+            implied_conversion_rate,
+        }
+    }
+}
+
+/// Fees of the Kakarot transactions of a block, as returned by `kakarot_getBlockFeeBreakdown`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockFeeBreakdown {
+    pub block_hash: B256,
+    pub block_number: u64,
+    pub base_fee_per_gas: Option<U256>,
+    /// Price of the L1 gas on Starknet at this block, in wei.
+    pub l1_gas_price_wei: U256,
+    /// Price of the L1 gas on Starknet at this block, in fri.
+    pub l1_gas_price_fri: U256,
+    /// Total gas used by the Kakarot transactions.
+    pub gas_used: U256,
+    /// Total fee charged to the senders, in wei.
+    pub evm_fee: U256,
+    /// Total fee paid on Starknet in wei.
+    pub starknet_fee_wei: U256,
+    /// Total fee paid on Starknet in fri.
+    pub starknet_fee_fri: U256,
+    pub transactions: Vec<TransactionFeeBreakdown>,
+}
+
+impl BlockFeeBreakdown {
+    /// Create a new [`BlockFeeBreakdown`] summing the fees of the transactions.
+    pub fn new(
+        block_hash: B256,
+        block_number: u64,
+        base_fee_per_gas: Option<U256>,
+        l1_gas_price_wei: U256,
+        l1_gas_price_fri: U256,
+        transactions: Vec<TransactionFeeBreakdown>,
+    ) -> Self {
+        let sum = |f: &dyn Fn(&TransactionFeeBreakdown) -> U256| {
+            transactions.iter().fold(U256::ZERO, |total, transaction| total.saturating_add(f(transaction)))
+        };
+        let starknet_fee = |unit: FeeUnit| {
+            sum(&|transaction| {
+                if transaction.starknet_fee_unit == unit {
+                    transaction.starknet_fee
+                } else {
+                    U256::ZERO
+                }
+            })
+        };
+
+        Self {
+            block_hash,
+            block_number,
+            base_fee_per_gas,
+            l1_gas_price_wei,
+            l1_gas_price_fri,
+            gas_used: sum(&|transaction| transaction.gas_used),
+            evm_fee: sum(&|transaction| transaction.evm_fee),
+            starknet_fee_wei: starknet_fee(FeeUnit::Wei),
+            starknet_fee_fri: starknet_fee(FeeUnit::Fri),
+            transactions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_fee_breakdown() {
+        // Given
+        let transactions = vec![
+            TransactionFeeBreakdown::new(
+                B256::with_last_byte(1),
+                B256::with_last_byte(2),
+                U256::from(21_000),
+                U256::from(10),
+                U256::from(105_000),
+                FeeUnit::Wei,
+            ),
+            TransactionFeeBreakdown::new(
+                B256::with_last_byte(3),
+                B256::with_last_byte(4),
+                U256::from(50_000),
+                U256::ZERO,
+                U256::from(7),
+                FeeUnit::Fri,
+            ),
+        ];
+
+        // When
+        let breakdown =
+            BlockFeeBreakdown::new(B256::ZERO, 1, Some(U256::from(10)), U256::from(1), U256::from(2), transactions);
+
+        // Then
+        assert_eq!(breakdown.transactions[0].evm_fee, U256::from(210_000));
+        assert_eq!(breakdown.transactions[0].implied_conversion_rate, Some(0.5));
+        assert_eq!(breakdown.transactions[1].implied_conversion_rate, None);
+        assert_eq!(breakdown.gas_used, U256::from(71_000));
+        assert_eq!(breakdown.evm_fee, U256::from(210_000));
+        assert_eq!(breakdown.starknet_fee_wei, U256::from(105_000));
+        assert_eq!(breakdown.starknet_fee_fri, U256::from(7));
+    }
+}
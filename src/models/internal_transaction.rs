@@ -0,0 +1,201 @@
+use reth_primitives::{Address, B256, U256, U64};
+use reth_rpc_types::trace::parity::{Action, CallType, LocalizedTransactionTrace, TraceOutput};
+use serde::{Deserialize, Serialize};
+
+use super::pagination::Cursor;
+
+/// Type of an internal transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InternalTransactionType {
+    /// A call transferring value.
+    Call,
+    /// A contract deployed by a contract. The parity traces don't tell `CREATE` and `CREATE2`
+    /// apart, so that all the deployments are reported as `CREATE`.
+    Create,
+    /// A contract destroyed, sending its balance to the refund address.
+    Selfdestruct,
+}
+
+/// A value movement or a contract deployment made by a contract during a transaction, extracted
+/// from the parity traces of the transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalTransaction {
+    pub block_number: U64,
+    pub transaction_hash: B256,
+    pub transaction_index: U64,
+    /// Position of the internal transaction among the internal transactions of its block.
+    pub index: U64,
+    /// Position of the call in the call tree of the transaction.
+    pub trace_address: Vec<usize>,
+    #[serde(rename = "type")]
+    pub kind: InternalTransactionType,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
+impl InternalTransaction {
+    /// Returns the cursor pointing at the internal transaction.
+    pub fn cursor(&self) -> Cursor {
+        Cursor::new(self.block_number.to(), self.index.to())
+    }
+}
+
+/// Extracts the internal transactions of the parity traces of a block or of a transaction: the
+/// nested calls transferring value, and the nested contract deployments and destructions. The
+/// calls reverted by themselves or by one of their callers are skipped.
+pub fn internal_transactions(traces: &[LocalizedTransactionTrace]) -> Vec<InternalTransaction> {
+    let is_reverted = |trace: &LocalizedTransactionTrace| {
+        traces.iter().any(|other| {
+            other.transaction_hash == trace.transaction_hash
+                && other.trace.error.is_some()
+                && trace.trace.trace_address.starts_with(&other.trace.trace_address)
+        })
+    };
+
+    let mut internal_transactions = Vec::new();
+    for trace in traces.iter().filter(|trace| !trace.trace.trace_address.is_empty()) {
+        let (kind, from, to, value) = match &trace.trace.action {
+            Action::Call(call) if call.call_type == CallType::Call && call.value != U256::ZERO => {
+                (InternalTransactionType::Call, call.from, call.to, call.value)
+            }
+            Action::Create(create) => {
+                let to = match &trace.trace.result {
+                    Some(TraceOutput::Create(output)) => output.address,
+                    _ => Address::ZERO,
+                };
+                (InternalTransactionType::Create, create.from, to, create.value)
+            }
+            Action::Selfdestruct(selfdestruct) => (
+                InternalTransactionType::Selfdestruct,
+                selfdestruct.address,
+                selfdestruct.refund_address,
+                selfdestruct.balance,
+            ),
+            _ => continue,
+        };
+        if is_reverted(trace) {
+            continue;
+        }
+
+        internal_transactions.push(InternalTransaction {
+            block_number: U64::from(trace.block_number.unwrap_or_default()),
+            transaction_hash: trace.transaction_hash.unwrap_or_default(),
+            transaction_index: U64::from(trace.transaction_position.unwrap_or_default()),
+            index: U64::from(internal_transactions.len()),
+            trace_address: trace.trace.trace_address.clone(),
+            kind,
+            from,
+            to,
+            value,
+        });
+    }
+    internal_transactions
+}
+
+/// An internal operation, in the format of `ots_getInternalOperations`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalOperation {
+    /// 0 for a transfer, 1 for a self destruct, 2 for a `CREATE` and 3 for a `CREATE2`.
+    #[serde(rename = "type")]
+    pub kind: u8,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
+impl From<InternalTransaction> for InternalOperation {
+    fn from(transaction: InternalTransaction) -> Self {
+        let kind = match transaction.kind {
+            InternalTransactionType::Call => 0,
+            InternalTransactionType::Selfdestruct => 1,
+            InternalTransactionType::Create => 2,
+        };
+        Self { kind, from: transaction.from, to: transaction.to, value: transaction.value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_rpc_types::trace::parity::{CallAction, CreateAction, CreateOutput, TransactionTrace};
+
+    fn trace(trace_address: Vec<usize>, action: Action, error: Option<&str>) -> LocalizedTransactionTrace {
+        LocalizedTransactionTrace {
+            trace: TransactionTrace {
+                action,
+                error: error.map(ToString::to_string),
+                result: None,
+                subtraces: 0,
+                trace_address,
+            },
+            block_hash: Some(B256::ZERO),
+            block_number: Some(3),
+            transaction_hash: Some(B256::with_last_byte(1)),
+            transaction_position: Some(0),
+        }
+    }
+
+    fn call(call_type: CallType, to: u8, value: u64) -> Action {
+        Action::Call(CallAction {
+            from: Address::with_last_byte(1),
+            call_type,
+            gas: Default::default(),
+            input: Default::default(),
+            to: Address::with_last_byte(to),
+            value: U256::from(value),
+        })
+    }
+
+    #[test]
+    fn test_internal_transactions() {
+        // Given
+        let mut create = trace(
+            vec![2],
+            Action::Create(CreateAction {
+                from: Address::with_last_byte(1),
+                gas: Default::default(),
+                init: Default::default(),
+                value: U256::ZERO,
+            }),
+            None,
+        );
+        create.trace.result = Some(TraceOutput::Create(CreateOutput {
+            address: Address::with_last_byte(9),
+            code: Default::default(),
+            gas_used: Default::default(),
+        }));
+        let traces = vec![
+            // The top level call isn't an internal transaction
+            trace(vec![], call(CallType::Call, 1, 100), None),
+            trace(vec![0], call(CallType::Call, 2, 10), None),
+            // Calls without value and delegate calls don't move value
+            trace(vec![0, 0], call(CallType::Call, 3, 0), None),
+            trace(vec![0, 1], call(CallType::DelegateCall, 4, 10), None),
+            // The calls of a reverted call are reverted
+            trace(vec![1], call(CallType::Call, 5, 10), Some("Reverted")),
+            trace(vec![1, 0], call(CallType::Call, 6, 10), None),
+            create,
+        ];
+
+        // When
+        let internal_transactions = internal_transactions(&traces);
+
+        // Then
+        let summary = internal_transactions
+            .iter()
+            .map(|tx| (tx.index.to::<u64>(), tx.kind, tx.to, tx.value.to::<u64>()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (0, InternalTransactionType::Call, Address::with_last_byte(2), 10),
+                (1, InternalTransactionType::Create, Address::with_last_byte(9), 0),
+            ]
+        );
+        assert_eq!(internal_transactions[1].cursor(), Cursor::new(3, 1));
+        assert_eq!(InternalOperation::from(internal_transactions[1].clone()).kind, 2);
+    }
+}
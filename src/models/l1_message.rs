@@ -0,0 +1,96 @@
+use reth_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::L1HandlerTransaction;
+use starknet_crypto::FieldElement;
+
+use crate::models::felt::Felt252Wrapper;
+
+/// A message sent from L1 and consumed by an L1 handler transaction of the Kakarot contract, as
+/// returned by `kakarot_getL1Messages`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1Message {
+    /// Hash of the Starknet L1 handler transaction.
+    pub transaction_hash: B256,
+    pub block_hash: B256,
+    pub block_number: u64,
+    /// Address of the L1 contract which sent the message.
+    pub from_address: Address,
+    /// Starknet address of the contract handling the message.
+    pub to_address: B256,
+    /// Selector of the L1 handler.
+    pub entry_point_selector: B256,
+    /// Nonce of the message on the Starknet core contract.
+    pub nonce: U256,
+    pub payload: Vec<U256>,
+}
+
+impl L1Message {
+    /// Converts an L1 handler transaction of the given block. The first element of the calldata of
+    /// an L1 handler is the L1 sender, followed by the payload of the message. Returns None if the
+    /// calldata doesn't start with an Ethereum address.
+    pub fn from_l1_handler(
+        transaction: &L1HandlerTransaction,
+        block_hash: FieldElement,
+        block_number: u64,
+    ) -> Option<Self> {
+        let (from_address, payload) = transaction.calldata.split_first()?;
+        let from_address = Address::try_from(Felt252Wrapper::from(*from_address)).ok()?;
+
+        Some(Self {
+            transaction_hash: B256::from(transaction.transaction_hash.to_bytes_be()),
+            block_hash: B256::from(block_hash.to_bytes_be()),
+            block_number,
+            from_address,
+            to_address: B256::from(transaction.contract_address.to_bytes_be()),
+            entry_point_selector: B256::from(transaction.entry_point_selector.to_bytes_be()),
+            nonce: U256::from(transaction.nonce),
+            payload: payload.iter().map(|value| U256::from(Felt252Wrapper::from(*value))).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_l1_handler() {
+        // Given
+        let transaction = L1HandlerTransaction {
+            transaction_hash: FieldElement::from(1u8),
+            version: Default::default(),
+            nonce: 7,
+            contract_address: FieldElement::from(2u8),
+            entry_point_selector: FieldElement::from(3u8),
+            calldata: vec![FieldElement::from(0xdeadu64), FieldElement::from(4u8), FieldElement::from(5u8)],
+        };
+
+        // When
+        let message = L1Message::from_l1_handler(&transaction, FieldElement::from(6u8), 10).unwrap();
+
+        // Then
+        assert_eq!(message.transaction_hash, B256::with_last_byte(1));
+        assert_eq!(message.block_hash, B256::with_last_byte(6));
+        assert_eq!(message.from_address, Address::from_word(B256::from(U256::from(0xdead))));
+        assert_eq!(message.to_address, B256::with_last_byte(2));
+        assert_eq!(message.nonce, U256::from(7));
+        assert_eq!(message.payload, vec![U256::from(4), U256::from(5)]);
+    }
+
+    #[test]
+    fn test_from_l1_handler_without_sender() {
+        // Given
+        let transaction = L1HandlerTransaction {
+            transaction_hash: FieldElement::ONE,
+            version: Default::default(),
+            nonce: 0,
+            contract_address: FieldElement::ONE,
+            entry_point_selector: FieldElement::ONE,
+            calldata: vec![],
+        };
+
+        // Then
+        assert_eq!(L1Message::from_l1_handler(&transaction, FieldElement::ONE, 0), None);
+    }
+}
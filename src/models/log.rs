@@ -0,0 +1,118 @@
+use alloy_primitives::LogData;
+use lazy_static::lazy_static;
+use reth_primitives::{Address, Log, B256};
+use starknet::core::types::Event;
+use starknet::macros::selector;
+use starknet_crypto::FieldElement;
+
+/// Maximum number of topics of an EVM log, emitted by LOG4.
+pub const MAX_LOG_TOPICS: usize = 4;
+
+lazy_static! {
+    /// Selector of the event emitted by Kakarot at the end of the execution of a transaction.
+    pub static ref TRANSACTION_EXECUTED: FieldElement = selector!("transaction_executed");
+    // Selectors of the events emitted by Kakarot which aren't EVM logs
+    static ref IGNORED_EVENTS: [FieldElement; 5] = [
+        *TRANSACTION_EXECUTED,
+        selector!("evm_contract_deployed"),
+        selector!("Transfer"),
+        selector!("Approval"),
+        selector!("OwnershipTransferred"),
+    ];
+}
+
+/// Converts a Starknet event emitted by a Kakarot contract account into an EVM log.
+///
+/// The keys of the event are the EVM address of the contract, followed by the topics of the log,
+/// each split into its low and high 128 bits. Each element of the data of the event holds a byte
+/// of the data of the log. Returns None if the event isn't an EVM log or is malformed.
+pub fn starknet_event_to_log(event: &Event) -> Option<Log> {
+    let (address, topics) = event.keys.split_first()?;
+    if topics.len() % 2 != 0 || topics.len() / 2 > MAX_LOG_TOPICS || IGNORED_EVENTS.contains(address) {
+        return None;
+    }
+
+    let address = Address::from_slice(&address.to_bytes_be()[12..]);
+    let topics = topics.chunks_exact(2).map(|topic| to_topic(topic[0], topic[1])).collect::<Option<Vec<_>>>()?;
+    let data = event.data.iter().map(|felt| u8::try_from(*felt).ok()).collect::<Option<Vec<_>>>()?;
+
+    Some(Log { address, data: LogData::new_unchecked(topics, data.into()) })
+}
+
+/// Joins the low and high 128 bits of a topic. Returns None if a half exceeds 128 bits.
+fn to_topic(low: FieldElement, high: FieldElement) -> Option<B256> {
+    let low = u128::try_from(low).ok()?;
+    let high = u128::try_from(high).ok()?;
+
+    let mut topic = [0u8; 32];
+    topic[..16].copy_from_slice(&high.to_be_bytes());
+    topic[16..].copy_from_slice(&low.to_be_bytes());
+    Some(B256::from(topic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::U256;
+
+    #[test]
+    fn test_starknet_event_to_log() {
+        // Given
+        let event = Event {
+            from_address: FieldElement::ONE,
+            keys: vec![FieldElement::from(0xabu8), FieldElement::TWO, FieldElement::THREE],
+            data: vec![FieldElement::ONE, FieldElement::from(0xffu8)],
+        };
+
+        // When
+        let log = starknet_event_to_log(&event).unwrap();
+
+        // Then
+        assert_eq!(log.address, Address::with_last_byte(0xab));
+        assert_eq!(log.topics(), &[B256::from(U256::from(2) + (U256::from(3) << 128))]);
+        assert_eq!(log.data.data.as_ref(), &[1, 0xff]);
+        assert!(starknet_event_to_log(&Event { keys: vec![*TRANSACTION_EXECUTED], ..event }).is_none());
+    }
+
+    #[test]
+    fn test_starknet_event_to_log_topics() {
+        // Given
+        let keys = |topics: usize| {
+            std::iter::once(FieldElement::ONE).chain((0..topics * 2).map(FieldElement::from)).collect::<Vec<_>>()
+        };
+        let event = |keys| Event { from_address: FieldElement::ONE, keys, data: vec![] };
+
+        // When
+        let anonymous = starknet_event_to_log(&event(keys(0))).unwrap();
+        let log4 = starknet_event_to_log(&event(keys(MAX_LOG_TOPICS))).unwrap();
+
+        // Then
+        assert!(anonymous.topics().is_empty());
+        assert_eq!(log4.topics().len(), MAX_LOG_TOPICS);
+        assert_eq!(log4.topics()[3], B256::from(U256::from(6) + (U256::from(7) << 128)));
+        assert!(starknet_event_to_log(&event(keys(MAX_LOG_TOPICS + 1))).is_none());
+    }
+
+    #[test]
+    fn test_starknet_event_to_log_malformed() {
+        // Given
+        let topic_overflow = Event {
+            from_address: FieldElement::ONE,
+            keys: vec![
+                FieldElement::ONE,
+                FieldElement::from_hex_be("0x100000000000000000000000000000000").unwrap(),
+                FieldElement::ZERO,
+            ],
+            data: vec![],
+        };
+        let data_overflow = Event {
+            from_address: FieldElement::ONE,
+            keys: vec![FieldElement::ONE],
+            data: vec![FieldElement::from(0x100u16)],
+        };
+
+        // Then
+        assert!(starknet_event_to_log(&topic_overflow).is_none());
+        assert!(starknet_event_to_log(&data_overflow).is_none());
+    }
+}
@@ -1,4 +1,17 @@
+pub mod account;
+pub mod admin;
 pub mod balance;
 pub mod block;
+pub mod call_bundle;
+pub mod fee;
 pub mod felt;
+pub mod internal_transaction;
+pub mod l1_message;
+pub mod log;
+pub mod nft;
+pub mod otterscan;
+pub mod pagination;
+pub mod receipt;
+pub mod token;
 pub mod transaction;
+pub mod transfer;
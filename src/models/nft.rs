@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use reth_primitives::{Address, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use super::pagination::Cursor;
+use super::transfer::{TokenTransfer, TransferCategory, TransferOrder};
+
+/// Balance of an ERC721 or ERC1155 token held by an owner, derived from the indexed transfers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NftBalance {
+    pub contract_address: Address,
+    pub token_id: U256,
+    pub category: TransferCategory,
+    pub balance: U256,
+}
+
+/// Returns the ERC721 and ERC1155 tokens held by the owner after the transfers, which are sorted
+/// by block number and log index. The tokens are sorted by contract and id.
+pub fn nft_balances(owner: Address, transfers: impl IntoIterator<Item = TokenTransfer>) -> Vec<NftBalance> {
+    let mut balances = BTreeMap::<(Address, U256), (TransferCategory, U256)>::new();
+    for transfer in transfers {
+        // A transfer of the owner to itself doesn't change its balance
+        if transfer.from == transfer.to {
+            continue;
+        }
+        let amounts = match transfer.category {
+            TransferCategory::Erc721 => {
+                transfer.token_id.map(|token_id| (token_id, U256::from(1))).into_iter().collect()
+            }
+            TransferCategory::Erc1155 => transfer
+                .erc1155_metadata
+                .unwrap_or_default()
+                .into_iter()
+                .map(|metadata| (metadata.token_id, metadata.value))
+                .collect(),
+            TransferCategory::Erc20 => Vec::new(),
+        };
+
+        for (token_id, amount) in amounts {
+            let (_, balance) = balances.entry((transfer.address, token_id)).or_insert((transfer.category, U256::ZERO));
+            if transfer.to == owner {
+                *balance = balance.saturating_add(amount);
+            } else if transfer.from == owner {
+                *balance = balance.saturating_sub(amount);
+            }
+        }
+    }
+
+    balances
+        .into_iter()
+        .filter(|(_, (_, balance))| *balance != U256::ZERO)
+        .map(|((contract_address, token_id), (category, balance))| NftBalance {
+            contract_address,
+            token_id,
+            category,
+            balance,
+        })
+        .collect()
+}
+
+/// Parameters of `alchemy_getNFTs`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NftsRequest {
+    pub owner: Address,
+    #[serde(default)]
+    pub contract_addresses: Vec<Address>,
+    #[serde(default = "default_with_metadata")]
+    pub with_metadata: bool,
+    #[serde(default)]
+    pub page_key: Option<Cursor>,
+    #[serde(default)]
+    pub page_size: Option<U64>,
+}
+
+const fn default_with_metadata() -> bool {
+    true
+}
+
+/// Page of the tokens returned by `alchemy_getNFTs`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedNfts {
+    pub owned_nfts: Vec<OwnedNft>,
+    /// Key of the next page, absent on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_key: Option<Cursor>,
+    /// Number of tokens held by the owner, over all the pages.
+    pub total_count: U64,
+}
+
+/// A token held by an owner, in the format of `alchemy_getNFTs`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedNft {
+    pub contract: NftContract,
+    pub id: NftId,
+    /// Number of tokens held, in decimal.
+    pub balance: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_uri: Option<TokenUri>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftContract {
+    pub address: Address,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NftId {
+    pub token_id: U256,
+    pub token_metadata: NftTokenMetadata,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NftTokenMetadata {
+    /// `ERC721` or `ERC1155`.
+    pub token_type: String,
+}
+
+/// Metadata URI of a token. Kakarot doesn't run an IPFS gateway, so that the gateway URI is the
+/// raw URI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUri {
+    pub raw: String,
+    pub gateway: String,
+}
+
+impl OwnedNft {
+    /// Converts the balance, with the metadata URI of the token if resolved.
+    pub fn new(balance: NftBalance, token_uri: Option<String>) -> Self {
+        Self {
+            contract: NftContract { address: balance.contract_address },
+            id: NftId {
+                token_id: balance.token_id,
+                token_metadata: NftTokenMetadata { token_type: balance.category.as_str().to_uppercase() },
+            },
+            balance: balance.balance.to_string(),
+            token_uri: token_uri.map(|raw| TokenUri { gateway: raw.clone(), raw }),
+        }
+    }
+}
+
+/// Parameters of `alchemy_getNFTOwnershipHistory`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NftOwnershipHistoryRequest {
+    pub contract_address: Address,
+    pub token_id: U256,
+    #[serde(default)]
+    pub order: TransferOrder,
+    #[serde(default)]
+    pub max_count: Option<U64>,
+    #[serde(default)]
+    pub page_key: Option<Cursor>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::transfer::Erc1155Metadata;
+    use reth_primitives::B256;
+
+    fn transfer(from: Address, to: Address, category: TransferCategory, token_id: u64, value: u64) -> TokenTransfer {
+        let (token_id, erc1155_metadata) = match category {
+            TransferCategory::Erc1155 => {
+                (None, Some(vec![Erc1155Metadata { token_id: U256::from(token_id), value: U256::from(value) }]))
+            }
+            _ => (Some(U256::from(token_id)), None),
+        };
+        TokenTransfer {
+            block_number: U64::from(1),
+            block_hash: B256::ZERO,
+            block_timestamp: U64::ZERO,
+            transaction_hash: B256::ZERO,
+            log_index: U64::ZERO,
+            category,
+            address: Address::with_last_byte(category as u8 + 0x10),
+            from,
+            to,
+            value: None,
+            token_id,
+            erc1155_metadata,
+        }
+    }
+
+    #[test]
+    fn test_nft_balances() {
+        // Given
+        let owner = Address::with_last_byte(1);
+        let other = Address::with_last_byte(2);
+        let transfers = vec![
+            transfer(Address::ZERO, owner, TransferCategory::Erc721, 1, 0),
+            transfer(Address::ZERO, owner, TransferCategory::Erc721, 2, 0),
+            transfer(owner, other, TransferCategory::Erc721, 1, 0),
+            transfer(owner, owner, TransferCategory::Erc721, 2, 0),
+            transfer(Address::ZERO, owner, TransferCategory::Erc1155, 5, 10),
+            transfer(owner, other, TransferCategory::Erc1155, 5, 4),
+            transfer(Address::ZERO, owner, TransferCategory::Erc1155, 6, 1),
+            transfer(owner, other, TransferCategory::Erc1155, 6, 1),
+        ];
+
+        // When
+        let balances = nft_balances(owner, transfers);
+
+        // Then
+        let balances = balances
+            .into_iter()
+            .map(|balance| (balance.category, balance.token_id.to::<u64>(), balance.balance.to::<u64>()));
+        assert_eq!(
+            balances.collect::<Vec<_>>(),
+            vec![(TransferCategory::Erc721, 2, 1), (TransferCategory::Erc1155, 5, 6)]
+        );
+    }
+
+    #[test]
+    fn test_owned_nft() {
+        // Given
+        let balance = NftBalance {
+            contract_address: Address::with_last_byte(1),
+            token_id: U256::from(7),
+            category: TransferCategory::Erc1155,
+            balance: U256::from(12),
+        };
+
+        // When
+        let nft = OwnedNft::new(balance, Some("ipfs://token/7".to_string()));
+
+        // Then
+        let serialized = serde_json::to_value(&nft).unwrap();
+        assert_eq!(serialized["id"]["tokenId"], "0x7");
+        assert_eq!(serialized["id"]["tokenMetadata"]["tokenType"], "ERC1155");
+        assert_eq!(serialized["balance"], "12");
+        assert_eq!(serialized["tokenUri"]["raw"], "ipfs://token/7");
+    }
+}
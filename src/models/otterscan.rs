@@ -0,0 +1,84 @@
+use reth_primitives::{Address, B256, U256};
+use reth_rpc_types::{Block, BlockTransactions, RichBlock, Transaction, TransactionReceipt};
+use serde::{Deserialize, Serialize};
+
+/// Direction of a search of the transactions of an address, relative to a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchDirection {
+    /// Search the transactions in the blocks before the block.
+    Before,
+    /// Search the transactions in the blocks after the block.
+    After,
+}
+
+/// A block as returned by `ots_getBlockDetails`, without its transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsBlock {
+    #[serde(flatten)]
+    pub block: Block,
+    pub transaction_count: usize,
+}
+
+impl From<RichBlock> for OtsBlock {
+    fn from(block: RichBlock) -> Self {
+        let mut block = block.inner;
+        let transaction_count = match &block.transactions {
+            BlockTransactions::Full(transactions) => transactions.len(),
+            BlockTransactions::Hashes(hashes) => hashes.len(),
+            BlockTransactions::Uncle => 0,
+        };
+        block.transactions = BlockTransactions::Hashes(Vec::new());
+        Self { block, transaction_count }
+    }
+}
+
+/// Issuance of a block. Kakarot doesn't issue block or uncle rewards.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockIssuance {
+    pub block_reward: U256,
+    pub uncle_reward: U256,
+    pub issuance: U256,
+}
+
+/// Response of `ots_getBlockDetails`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockDetails {
+    pub block: OtsBlock,
+    pub issuance: BlockIssuance,
+    /// Sum of the fees paid by the transactions of the block.
+    pub total_fees: U256,
+}
+
+/// A transaction receipt along with the timestamp of its block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsTransactionReceipt {
+    #[serde(flatten)]
+    pub receipt: TransactionReceipt,
+    pub timestamp: u64,
+}
+
+/// Response of `ots_searchTransactionsBefore` and `ots_searchTransactionsAfter`.
+/// The transactions are sorted from the most recent to the oldest. The first page
+/// holds the most recent transactions, the last page holds the oldest transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsWithReceipts {
+    pub txs: Vec<Transaction>,
+    pub receipts: Vec<OtsTransactionReceipt>,
+    pub first_page: bool,
+    pub last_page: bool,
+}
+
+/// Response of `ots_getContractCreator`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractCreator {
+    /// Hash of the transaction which deployed the contract.
+    pub hash: B256,
+    /// Sender of the transaction which deployed the contract.
+    pub creator: Address,
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Continuation token of a paginated response, from which the next page starts. The token is
+/// opaque to the clients, which pass it back unchanged to get the next page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cursor {
+    /// Block from which the next page starts.
+    pub block_number: u64,
+    /// Position in the block from which the next page starts, e.g. the index of a transaction.
+    pub index: u64,
+}
+
+impl Cursor {
+    pub const fn new(block_number: u64, index: u64) -> Self {
+        Self { block_number, index }
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:016x}{:016x}", self.block_number, self.index)
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid cursor {s}");
+        let hex = s.strip_prefix("0x").filter(|hex| hex.len() == 32).ok_or_else(invalid)?;
+        let (block_number, index) = hex.split_at(16);
+        Ok(Self {
+            block_number: u64::from_str_radix(block_number, 16).map_err(|_| invalid())?,
+            index: u64::from_str_radix(index, 16).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl Serialize for Cursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cursor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cursor = String::deserialize(deserializer)?;
+        cursor.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A page of a paginated response, along with the cursor of the next page. The cursor is null
+/// on the last page. A page can be empty while the cursor isn't, in which case the next page
+/// must still be requested.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<Cursor>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        // Given
+        let cursor = Cursor::new(0x1234, 7);
+
+        // When
+        let serialized = serde_json::to_string(&cursor).unwrap();
+
+        // Then
+        assert_eq!(serialized, "\"0x00000000000012340000000000000007\"");
+        assert_eq!(serde_json::from_str::<Cursor>(&serialized).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_invalid_cursor() {
+        assert!(Cursor::from_str("0x1234").is_err());
+        assert!(Cursor::from_str("00000000000012340000000000000007").is_err());
+        assert!(Cursor::from_str("0x0000000000001234000000000000000g").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use reth_primitives::{Bloom, BloomInput, Bytes, TxType};
+use reth_rpc_types::{ReceiptEnvelope, TransactionReceipt};
+use serde::{Deserialize, Serialize};
+
+/// A transaction receipt along with the revert data of the transaction, if it reverted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReceiptWithRevertReason {
+    #[serde(flatten)]
+    pub receipt: TransactionReceipt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<Bytes>,
+}
+
+/// Sorts the receipts of a block by transaction index and recomputes the fields which depend
+/// on the logs or on the other receipts of the block: the logs bloom and the cumulative gas used.
+pub fn normalize_block_receipts(receipts: &mut [TransactionReceipt]) {
+    receipts.sort_by_key(|receipt| receipt.transaction_index);
+
+    let mut cumulative_gas_used = 0u128;
+    for receipt in receipts {
+        cumulative_gas_used = cumulative_gas_used.saturating_add(receipt.gas_used);
+        normalize_receipt(receipt, cumulative_gas_used);
+    }
+}
+
+/// Sets the cumulative gas used of the receipt and computes its logs bloom from its logs.
+fn normalize_receipt(receipt: &mut TransactionReceipt, cumulative_gas_used: u128) {
+    let Some(mut inner) = receipt.inner.as_receipt_with_bloom().cloned() else {
+        return;
+    };
+
+    let mut logs_bloom = Bloom::ZERO;
+    for log in &inner.receipt.logs {
+        logs_bloom.accrue(BloomInput::Raw(log.address().as_slice()));
+        for topic in log.topics() {
+            logs_bloom.accrue(BloomInput::Raw(topic.as_slice()));
+        }
+    }
+    inner.logs_bloom = logs_bloom;
+    inner.receipt.cumulative_gas_used = cumulative_gas_used;
+
+    receipt.inner = match Into::<u8>::into(receipt.transaction_type()).try_into() {
+        Ok(TxType::Legacy) => ReceiptEnvelope::Legacy(inner),
+        Ok(TxType::Eip2930) => ReceiptEnvelope::Eip2930(inner),
+        Ok(TxType::Eip1559) => ReceiptEnvelope::Eip1559(inner),
+        Ok(TxType::Eip4844) => ReceiptEnvelope::Eip4844(inner),
+        Err(_) => return,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::LogData;
+    use reth_primitives::{Address, B256};
+    use reth_rpc_types::{Log, Receipt, ReceiptWithBloom};
+
+    fn receipt(transaction_index: u64, gas_used: u128, logs: Vec<Log>) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: B256::ZERO,
+            transaction_index: Some(transaction_index),
+            block_hash: Some(B256::ZERO),
+            block_number: Some(1),
+            gas_used,
+            effective_gas_price: 0,
+            blob_gas_used: None,
+            blob_gas_price: None,
+            from: Address::ZERO,
+            to: None,
+            contract_address: None,
+            state_root: None,
+            inner: ReceiptEnvelope::Eip1559(ReceiptWithBloom {
+                receipt: Receipt { status: true, cumulative_gas_used: 0, logs },
+                logs_bloom: Bloom::ZERO,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_normalize_block_receipts() {
+        // Given
+        let address = Address::with_last_byte(1);
+        let topic = B256::with_last_byte(2);
+        let log = Log {
+            inner: reth_primitives::Log { address, data: LogData::new_unchecked(vec![topic], Bytes::new()) },
+            block_hash: None,
+            block_number: None,
+            block_timestamp: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            removed: false,
+        };
+        let mut receipts = vec![receipt(1, 300, vec![log]), receipt(0, 100, vec![])];
+
+        // When
+        normalize_block_receipts(&mut receipts);
+
+        // Then
+        assert_eq!(receipts[0].transaction_index, Some(0));
+        assert_eq!(receipts[0].inner.cumulative_gas_used(), 100);
+        assert_eq!(*receipts[0].inner.logs_bloom(), Bloom::ZERO);
+        assert_eq!(receipts[1].transaction_index, Some(1));
+        assert_eq!(receipts[1].inner.cumulative_gas_used(), 400);
+        let bloom = receipts[1].inner.logs_bloom();
+        assert!(bloom.contains_input(BloomInput::Raw(address.as_slice())));
+        assert!(bloom.contains_input(BloomInput::Raw(topic.as_slice())));
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Metadata of an ERC20 token, as returned by `alchemy_getTokenMetadata`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMetadata {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    /// Kakarot doesn't index token logos.
+    pub logo: Option<String>,
+}
@@ -1,8 +1,10 @@
+use alloy_rlp::Decodable;
 use reth_primitives::{
     AccessList, AccessListItem, Signature, TransactionKind, TransactionSigned, TxEip1559, TxEip2930, TxLegacy, TxType,
     U256,
 };
 
+use crate::eth_provider::constant::TRANSACTION_REPLACEMENT_PRICE_BUMP;
 use crate::eth_provider::error::{EthApiError, EthereumDataFormatError, SignatureError, TransactionError};
 
 pub fn rpc_to_primitive_transaction(
@@ -101,6 +103,82 @@ pub fn rpc_to_ec_recovered_transaction(
     Ok(tx_ec_recovered)
 }
 
+/// Decodes a raw transaction, as sent to `eth_sendRawTransaction`. The type of the transaction is
+/// read before decoding it, so that the transaction types which Kakarot doesn't support are
+/// rejected with an explicit error instead of a decoding error.
+pub fn decode_raw_transaction(raw: &[u8]) -> Result<TransactionSigned, EthApiError> {
+    match raw_transaction_type(raw)? {
+        TxType::Legacy | TxType::Eip2930 | TxType::Eip1559 => TransactionSigned::decode(&mut &raw[..])
+            .map_err(|_| EthApiError::EthereumDataFormat(EthereumDataFormatError::TransactionConversionError)),
+        TxType::Eip4844 => Err(TransactionError::BlobTransactionUnsupported.into()),
+    }
+}
+
+/// Returns the type of a raw transaction. A legacy transaction is an RLP list, while a typed
+/// transaction starts with its type byte, which can be wrapped in an RLP string.
+pub fn raw_transaction_type(raw: &[u8]) -> Result<TxType, EthereumDataFormatError> {
+    let mut buf = raw;
+    let first = *buf.first().ok_or(EthereumDataFormatError::TransactionConversionError)?;
+    if first >= alloy_rlp::EMPTY_LIST_CODE {
+        return Ok(TxType::Legacy);
+    }
+    if first >= alloy_rlp::EMPTY_STRING_CODE {
+        alloy_rlp::Header::decode(&mut buf).map_err(|_| EthereumDataFormatError::TransactionConversionError)?;
+    }
+
+    let tx_type = *buf.first().ok_or(EthereumDataFormatError::TransactionConversionError)?;
+    TxType::try_from(tx_type).map_err(|_| EthereumDataFormatError::TransactionConversionError)
+}
+
+/// Validates the fees of a transaction against the current base fee.
+/// For EIP-1559 transactions, the max fee per gas must cover the base fee and
+/// the max priority fee per gas can't exceed the max fee per gas. For other
+/// transactions, the gas price must cover the base fee.
+pub fn validate_transaction_fees(transaction: &TransactionSigned, base_fee: u128) -> Result<(), TransactionError> {
+    let max_fee_per_gas = transaction.max_fee_per_gas();
+    if let Some(max_priority_fee_per_gas) = transaction.max_priority_fee_per_gas() {
+        if max_priority_fee_per_gas > max_fee_per_gas {
+            return Err(TransactionError::TipAboveFeeCap(max_priority_fee_per_gas, max_fee_per_gas));
+        }
+    }
+    if max_fee_per_gas < base_fee {
+        return Err(TransactionError::FeeCapTooLow(max_fee_per_gas, base_fee));
+    }
+    Ok(())
+}
+
+/// Validates that the balance of the sender covers the maximum cost of the transaction, i.e. its
+/// gas limit at its fee cap plus its value.
+pub fn validate_transaction_balance(transaction: &TransactionSigned, balance: U256) -> Result<(), TransactionError> {
+    let cost = U256::from(transaction.max_fee_per_gas())
+        .saturating_mul(U256::from(transaction.gas_limit()))
+        .saturating_add(transaction.value());
+    if balance < cost {
+        return Err(TransactionError::InsufficientFunds(balance, cost));
+    }
+    Ok(())
+}
+
+/// Validates the fees of a transaction replacing a pending transaction with the same nonce.
+/// Both the fee cap and the tip must be bumped by at least [`TRANSACTION_REPLACEMENT_PRICE_BUMP`]
+/// percent, as for legacy transactions the gas price is used as both.
+pub fn validate_replacement_fees(
+    replacement: &TransactionSigned,
+    replaced: &reth_rpc_types::Transaction,
+) -> Result<(), TransactionError> {
+    let bumped = |fee: u128| fee.saturating_mul(100 + TRANSACTION_REPLACEMENT_PRICE_BUMP) / 100;
+
+    let replaced_fee_cap = replaced.max_fee_per_gas.or(replaced.gas_price).unwrap_or_default();
+    let replaced_tip = replaced.max_priority_fee_per_gas.or(replaced.gas_price).unwrap_or_default();
+    let fee_cap = replacement.max_fee_per_gas();
+    let tip = replacement.max_priority_fee_per_gas().unwrap_or(fee_cap);
+
+    if fee_cap < bumped(replaced_fee_cap) || tip < bumped(replaced_tip) {
+        return Err(TransactionError::ReplacementUnderpriced);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,4 +383,93 @@ mod tests {
 
         let _ = rpc_to_primitive_transaction(rpc_tx).unwrap();
     }
+
+    #[test]
+    fn test_validate_transaction_fees() {
+        // Given
+        let tx = rpc_to_primitive_transaction(eip1559_rpc_transaction()).unwrap();
+        let tx = TransactionSigned::from_transaction_and_signature(tx, Signature::default());
+
+        // Then
+        assert!(validate_transaction_fees(&tx, 30).is_ok());
+        assert!(matches!(validate_transaction_fees(&tx, 31), Err(TransactionError::FeeCapTooLow(30, 31))));
+    }
+
+    #[test]
+    fn test_validate_transaction_fees_tip_above_fee_cap() {
+        // Given
+        let mut rpc_tx = eip1559_rpc_transaction();
+        rpc_tx.max_priority_fee_per_gas = Some(40);
+        let tx = rpc_to_primitive_transaction(rpc_tx).unwrap();
+        let tx = TransactionSigned::from_transaction_and_signature(tx, Signature::default());
+
+        // Then
+        assert!(matches!(validate_transaction_fees(&tx, 0), Err(TransactionError::TipAboveFeeCap(40, 30))));
+    }
+
+    #[test]
+    fn test_validate_transaction_balance() {
+        // Given
+        let tx = rpc_to_primitive_transaction(eip1559_rpc_transaction()).unwrap();
+        let tx = TransactionSigned::from_transaction_and_signature(tx, Signature::default());
+        let cost = U256::from(tx.max_fee_per_gas()) * U256::from(tx.gas_limit()) + tx.value();
+
+        // Then
+        assert!(validate_transaction_balance(&tx, cost).is_ok());
+        assert!(matches!(
+            validate_transaction_balance(&tx, cost - U256::from(1)),
+            Err(TransactionError::InsufficientFunds(balance, want)) if balance == cost - U256::from(1) && want == cost
+        ));
+    }
+
+    #[test]
+    fn test_validate_replacement_fees() {
+        // Given
+        let replaced = eip1559_rpc_transaction();
+        let replacement = |max_fee_per_gas, max_priority_fee_per_gas| {
+            let rpc_tx = reth_rpc_types::Transaction {
+                max_fee_per_gas: Some(max_fee_per_gas),
+                max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+                ..eip1559_rpc_transaction()
+            };
+            let tx = rpc_to_primitive_transaction(rpc_tx).unwrap();
+            TransactionSigned::from_transaction_and_signature(tx, Signature::default())
+        };
+
+        // Then
+        assert!(validate_replacement_fees(&replacement(33, 11), &replaced).is_ok());
+        assert!(matches!(
+            validate_replacement_fees(&replacement(32, 11), &replaced),
+            Err(TransactionError::ReplacementUnderpriced)
+        ));
+        assert!(matches!(
+            validate_replacement_fees(&replacement(40, 10), &replaced),
+            Err(TransactionError::ReplacementUnderpriced)
+        ));
+    }
+
+    #[test]
+    fn test_decode_raw_blob_transaction() {
+        // Given
+        let blob_transaction = [0x03, 0xc0];
+        let wrapped_blob_transaction = [0x82, 0x03, 0xc0];
+
+        // When
+        let err = decode_raw_transaction(&blob_transaction).unwrap_err();
+        let wrapped_err = decode_raw_transaction(&wrapped_blob_transaction).unwrap_err();
+
+        // Then
+        assert!(matches!(err, EthApiError::Transaction(TransactionError::BlobTransactionUnsupported)));
+        assert!(matches!(wrapped_err, EthApiError::Transaction(TransactionError::BlobTransactionUnsupported)));
+    }
+
+    #[test]
+    fn test_raw_transaction_type() {
+        assert_eq!(raw_transaction_type(&[0xc0]).unwrap(), TxType::Legacy);
+        assert_eq!(raw_transaction_type(&[0x01, 0xc0]).unwrap(), TxType::Eip2930);
+        assert_eq!(raw_transaction_type(&[0x02, 0xc0]).unwrap(), TxType::Eip1559);
+        assert_eq!(raw_transaction_type(&[0x82, 0x02, 0xc0]).unwrap(), TxType::Eip1559);
+        assert!(raw_transaction_type(&[]).is_err());
+        assert!(raw_transaction_type(&[0x05, 0xc0]).is_err());
+    }
 }
@@ -0,0 +1,433 @@
+use std::str::FromStr;
+
+use ethers::abi::{ParamType, Token};
+use lazy_static::lazy_static;
+use reth_primitives::{keccak256, Address, BlockNumberOrTag, B256, U256, U64};
+use reth_rpc_types::Log;
+use serde::{Deserialize, Serialize};
+
+use super::pagination::Cursor;
+
+lazy_static! {
+    /// Topic of the `Transfer(address,address,uint256)` event, shared by ERC20 and ERC721.
+    pub static ref TRANSFER_TOPIC: B256 = keccak256("Transfer(address,address,uint256)");
+    /// Topic of the ERC1155 `TransferSingle(address,address,address,uint256,uint256)` event.
+    pub static ref TRANSFER_SINGLE_TOPIC: B256 = keccak256("TransferSingle(address,address,address,uint256,uint256)");
+    /// Topic of the ERC1155 `TransferBatch(address,address,address,uint256[],uint256[])` event.
+    pub static ref TRANSFER_BATCH_TOPIC: B256 = keccak256("TransferBatch(address,address,address,uint256[],uint256[])");
+}
+
+/// Category of a token transfer, as named by `alchemy_getAssetTransfers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferCategory {
+    Erc20,
+    Erc721,
+    Erc1155,
+}
+
+impl TransferCategory {
+    /// Returns the name of the category.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Erc20 => "erc20",
+            Self::Erc721 => "erc721",
+            Self::Erc1155 => "erc1155",
+        }
+    }
+}
+
+impl FromStr for TransferCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "erc20" => Ok(Self::Erc20),
+            "erc721" => Ok(Self::Erc721),
+            "erc1155" => Ok(Self::Erc1155),
+            _ => Err(format!("unsupported category {s}, expected erc20, erc721 or erc1155")),
+        }
+    }
+}
+
+/// Id and amount of an ERC1155 token transferred.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Erc1155Metadata {
+    pub token_id: U256,
+    pub value: U256,
+}
+
+/// A transfer of ERC20 tokens, of an ERC721 token or of ERC1155 tokens, decoded from a `Transfer`,
+/// `TransferSingle` or `TransferBatch` log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTransfer {
+    pub block_number: U64,
+    pub block_hash: B256,
+    pub block_timestamp: U64,
+    pub transaction_hash: B256,
+    /// Index of the log in its block.
+    pub log_index: U64,
+    pub category: TransferCategory,
+    /// Address of the token contract.
+    pub address: Address,
+    pub from: Address,
+    pub to: Address,
+    /// Amount of ERC20 tokens transferred.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    /// Id of the ERC721 token transferred.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<U256>,
+    /// Ids and amounts of the ERC1155 tokens transferred, a single transfer holding all the tokens
+    /// of a `TransferBatch` log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub erc1155_metadata: Option<Vec<Erc1155Metadata>>,
+}
+
+impl TokenTransfer {
+    /// Decodes the transfer of a `Transfer`, `TransferSingle` or `TransferBatch` log. The ERC20
+    /// transfers hold the amount in the data of the log, while the ERC721 transfers hold the token
+    /// id in a fourth topic. The ERC1155 transfers hold the operator, the sender and the recipient
+    /// in the topics, and the ids and amounts in the data. Returns None if the log isn't a
+    /// transfer or isn't mined yet.
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let topics = log.inner.data.topics();
+        let data = &log.inner.data.data;
+        let topic = topics.first()?;
+
+        let (category, value, token_id, erc1155_metadata) = match topics.len() {
+            3 if topic == &*TRANSFER_TOPIC && data.len() == 32 => {
+                (TransferCategory::Erc20, Some(U256::from_be_slice(data)), None, None)
+            }
+            4 if topic == &*TRANSFER_TOPIC && data.is_empty() => {
+                (TransferCategory::Erc721, None, Some(U256::from_be_bytes(topics[3].0)), None)
+            }
+            4 if topic == &*TRANSFER_SINGLE_TOPIC && data.len() == 64 => {
+                let metadata = Erc1155Metadata {
+                    token_id: U256::from_be_slice(&data[..32]),
+                    value: U256::from_be_slice(&data[32..]),
+                };
+                (TransferCategory::Erc1155, None, None, Some(vec![metadata]))
+            }
+            4 if topic == &*TRANSFER_BATCH_TOPIC => (TransferCategory::Erc1155, None, None, Some(decode_batch(data)?)),
+            _ => return None,
+        };
+        // The ERC1155 logs start with the operator
+        let (from, to) = match category {
+            TransferCategory::Erc1155 => (topics[2], topics[3]),
+            _ => (topics[1], topics[2]),
+        };
+
+        Some(Self {
+            block_number: U64::from(log.block_number?),
+            block_hash: log.block_hash?,
+            block_timestamp: U64::from(log.block_timestamp.unwrap_or_default()),
+            transaction_hash: log.transaction_hash?,
+            log_index: U64::from(log.log_index?),
+            category,
+            address: log.inner.address,
+            from: Address::from_word(from),
+            to: Address::from_word(to),
+            value,
+            token_id,
+            erc1155_metadata,
+        })
+    }
+
+    /// Returns the cursor pointing at the transfer.
+    pub fn cursor(&self) -> Cursor {
+        Cursor::new(self.block_number.to(), self.log_index.to())
+    }
+}
+
+/// Decodes the ids and the amounts of a `TransferBatch` log, ABI encoded as two `uint256[]`.
+fn decode_batch(data: &[u8]) -> Option<Vec<Erc1155Metadata>> {
+    let uint_array = ParamType::Array(Box::new(ParamType::Uint(256)));
+    let mut tokens = ethers::abi::decode(&[uint_array.clone(), uint_array], data).ok()?.into_iter();
+    let (Some(Token::Array(ids)), Some(Token::Array(values))) = (tokens.next(), tokens.next()) else {
+        return None;
+    };
+    if ids.len() != values.len() {
+        return None;
+    }
+
+    let to_u256 = |token: Token| {
+        let mut bytes = [0u8; 32];
+        token.into_uint()?.to_big_endian(&mut bytes);
+        Some(U256::from_be_bytes(bytes))
+    };
+    ids.into_iter()
+        .zip(values)
+        .map(|(id, value)| Some(Erc1155Metadata { token_id: to_u256(id)?, value: to_u256(value)? }))
+        .collect()
+}
+
+/// Filter of the token transfers, with the block range resolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransferFilter {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub from_address: Option<Address>,
+    pub to_address: Option<Address>,
+    /// Token contracts of the transfers, any contract if empty.
+    pub contract_addresses: Vec<Address>,
+    pub categories: Vec<TransferCategory>,
+    /// Id of the ERC721 or ERC1155 token transferred, any token if None.
+    pub token_id: Option<U256>,
+    /// Returns the most recent transfers first.
+    pub descending: bool,
+    /// Excludes the ERC20 transfers of a zero amount.
+    pub exclude_zero_value: bool,
+}
+
+/// Order of the transfers returned by `alchemy_getAssetTransfers`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Parameters of `alchemy_getAssetTransfers`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetTransfersRequest {
+    #[serde(default)]
+    pub from_block: Option<BlockNumberOrTag>,
+    #[serde(default)]
+    pub to_block: Option<BlockNumberOrTag>,
+    #[serde(default)]
+    pub from_address: Option<Address>,
+    #[serde(default)]
+    pub to_address: Option<Address>,
+    #[serde(default)]
+    pub contract_addresses: Vec<Address>,
+    pub category: Vec<String>,
+    #[serde(default)]
+    pub order: TransferOrder,
+    #[serde(default)]
+    pub with_metadata: bool,
+    #[serde(default = "default_exclude_zero_value")]
+    pub exclude_zero_value: bool,
+    #[serde(default)]
+    pub max_count: Option<U64>,
+    #[serde(default)]
+    pub page_key: Option<Cursor>,
+}
+
+const fn default_exclude_zero_value() -> bool {
+    true
+}
+
+/// Page of the transfers returned by `alchemy_getAssetTransfers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetTransfers {
+    pub transfers: Vec<AssetTransfer>,
+    /// Key of the next page, absent on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_key: Option<Cursor>,
+}
+
+/// A token transfer, in the format of `alchemy_getAssetTransfers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetTransfer {
+    pub block_num: U64,
+    /// `<transaction hash>:log:<log index>`
+    pub unique_id: String,
+    pub hash: B256,
+    pub from: Address,
+    pub to: Address,
+    /// Amount of ERC20 tokens transferred, in units of the token (i.e. divided by 10^decimals).
+    pub value: Option<f64>,
+    pub erc721_token_id: Option<U256>,
+    pub erc1155_metadata: Option<Vec<Erc1155Metadata>>,
+    pub token_id: Option<U256>,
+    /// Symbol of the token.
+    pub asset: Option<String>,
+    pub category: TransferCategory,
+    pub raw_contract: RawContract,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AssetTransferMetadata>,
+}
+
+/// Raw amount and contract of a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawContract {
+    pub value: Option<U256>,
+    pub address: Address,
+    pub decimal: Option<U64>,
+}
+
+/// Metadata of a transfer, returned with `withMetadata`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetTransferMetadata {
+    /// Timestamp of the block, in the ISO 8601 format.
+    pub block_timestamp: String,
+}
+
+impl AssetTransfer {
+    /// Converts the token transfer, with the symbol and the decimals of its token if known.
+    pub fn new(transfer: TokenTransfer, symbol: Option<String>, decimals: Option<u8>, with_metadata: bool) -> Self {
+        let value = transfer.value.zip(decimals).map(|(value, decimals)| to_units(value, decimals));
+        let metadata = with_metadata.then(|| AssetTransferMetadata {
+            block_timestamp: iso8601(transfer.block_timestamp.to()).unwrap_or_default(),
+        });
+        Self {
+            block_num: transfer.block_number,
+            unique_id: format!("{:#x}:log:{:#x}", transfer.transaction_hash, transfer.log_index),
+            hash: transfer.transaction_hash,
+            from: transfer.from,
+            to: transfer.to,
+            value,
+            erc721_token_id: transfer.token_id,
+            erc1155_metadata: transfer.erc1155_metadata,
+            token_id: transfer.token_id,
+            asset: symbol,
+            category: transfer.category,
+            raw_contract: RawContract {
+                value: transfer.value,
+                address: transfer.address,
+                decimal: decimals.map(U64::from),
+            },
+            metadata,
+        }
+    }
+}
+
+/// Converts an amount of the smallest unit of a token into units of the token.
+fn to_units(value: U256, decimals: u8) -> f64 {
+    let value: f64 = value.to_string().parse().unwrap_or(f64::INFINITY);
+    value / 10f64.powi(i32::from(decimals))
+}
+
+/// Formats a Unix timestamp in the ISO 8601 format.
+fn iso8601(timestamp: u64) -> Option<String> {
+    let millis = i64::try_from(timestamp).ok()?.checked_mul(1000)?;
+    mongodb::bson::DateTime::from_millis(millis).try_to_rfc3339_string().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Bytes, LogData};
+
+    fn transfer_log(topics: Vec<B256>, data: Bytes) -> Log {
+        Log {
+            inner: reth_primitives::Log {
+                address: Address::with_last_byte(0x10),
+                data: LogData::new_unchecked(topics, data),
+            },
+            block_hash: Some(B256::with_last_byte(1)),
+            block_number: Some(5),
+            block_timestamp: Some(1_700_000_000),
+            transaction_hash: Some(B256::with_last_byte(2)),
+            transaction_index: Some(0),
+            log_index: Some(3),
+            removed: false,
+        }
+    }
+
+    #[test]
+    fn test_erc20_transfer_from_log() {
+        // Given
+        let from = Address::with_last_byte(1);
+        let to = Address::with_last_byte(2);
+        let topics = vec![*TRANSFER_TOPIC, from.into_word(), to.into_word()];
+        let log = transfer_log(topics, U256::from(1000).to_be_bytes::<32>().into());
+
+        // When
+        let transfer = TokenTransfer::from_log(&log).unwrap();
+
+        // Then
+        assert_eq!(transfer.category, TransferCategory::Erc20);
+        assert_eq!((transfer.from, transfer.to), (from, to));
+        assert_eq!(transfer.value, Some(U256::from(1000)));
+        assert_eq!(transfer.token_id, None);
+        assert_eq!(transfer.cursor(), Cursor::new(5, 3));
+    }
+
+    #[test]
+    fn test_erc721_transfer_from_log() {
+        // Given
+        let topics = vec![
+            *TRANSFER_TOPIC,
+            Address::ZERO.into_word(),
+            Address::with_last_byte(2).into_word(),
+            B256::with_last_byte(7),
+        ];
+        let log = transfer_log(topics, Bytes::default());
+
+        // When
+        let transfer = TokenTransfer::from_log(&log).unwrap();
+
+        // Then
+        assert_eq!(transfer.category, TransferCategory::Erc721);
+        assert_eq!(transfer.value, None);
+        assert_eq!(transfer.token_id, Some(U256::from(7)));
+        assert_eq!(TransferCategory::from_str(transfer.category.as_str()), Ok(TransferCategory::Erc721));
+        assert!(TransferCategory::from_str("specialnft").is_err());
+    }
+
+    #[test]
+    fn test_erc1155_transfers_from_logs() {
+        // Given
+        let operator = Address::with_last_byte(9).into_word();
+        let (from, to) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let single_data = [U256::from(7).to_be_bytes::<32>(), U256::from(3).to_be_bytes::<32>()].concat();
+        let single =
+            transfer_log(vec![*TRANSFER_SINGLE_TOPIC, operator, from.into_word(), to.into_word()], single_data.into());
+        let uints = |values: &[u64]| Token::Array(values.iter().map(|v| Token::Uint((*v).into())).collect());
+        let batch_data = ethers::abi::encode(&[uints(&[7, 8]), uints(&[3, 4])]);
+        let batch =
+            transfer_log(vec![*TRANSFER_BATCH_TOPIC, operator, from.into_word(), to.into_word()], batch_data.into());
+
+        // When
+        let single = TokenTransfer::from_log(&single).unwrap();
+        let batch = TokenTransfer::from_log(&batch).unwrap();
+
+        // Then
+        let metadata =
+            |token_id: u64, value: u64| Erc1155Metadata { token_id: U256::from(token_id), value: U256::from(value) };
+        assert_eq!(single.category, TransferCategory::Erc1155);
+        assert_eq!((single.from, single.to), (from, to));
+        assert_eq!(single.erc1155_metadata, Some(vec![metadata(7, 3)]));
+        assert_eq!(batch.erc1155_metadata, Some(vec![metadata(7, 3), metadata(8, 4)]));
+    }
+
+    #[test]
+    fn test_other_log_isnt_transfer() {
+        let approval = keccak256("Approval(address,address,uint256)");
+        let log = transfer_log(vec![approval, B256::ZERO, B256::ZERO], U256::ZERO.to_be_bytes::<32>().into());
+        assert_eq!(TokenTransfer::from_log(&log), None);
+        // ERC1155 transfers or malformed transfers have another layout
+        let log = transfer_log(vec![*TRANSFER_TOPIC, B256::ZERO, B256::ZERO], Bytes::default());
+        assert_eq!(TokenTransfer::from_log(&log), None);
+    }
+
+    #[test]
+    fn test_asset_transfer() {
+        // Given
+        let topics = vec![*TRANSFER_TOPIC, B256::ZERO, Address::with_last_byte(2).into_word()];
+        let log = transfer_log(topics, U256::from(1_500_000).to_be_bytes::<32>().into());
+        let transfer = TokenTransfer::from_log(&log).unwrap();
+
+        // When
+        let asset_transfer = AssetTransfer::new(transfer, Some("USDC".to_string()), Some(6), true);
+
+        // Then
+        let serialized = serde_json::to_value(&asset_transfer).unwrap();
+        assert_eq!(serialized["value"], 1.5);
+        assert_eq!(serialized["asset"], "USDC");
+        assert_eq!(serialized["blockNum"], "0x5");
+        assert_eq!(serialized["rawContract"]["decimal"], "0x6");
+        assert_eq!(serialized["metadata"]["blockTimestamp"], "2023-11-14T22:13:20Z");
+        assert_eq!(serialized["uniqueId"], format!("{:#x}:log:0x3", B256::with_last_byte(2)));
+    }
+}
@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use eyre::{eyre, Result};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Sampler, Tracer};
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Export of the spans of the RPC calls, of the Starknet calls and of the database queries to an
+/// OpenTelemetry collector (e.g. Jaeger or Tempo), over OTLP/gRPC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryConfig {
+    /// Endpoint of the OTLP collector, e.g. `http://localhost:4317`.
+    pub endpoint: String,
+    /// Name of the service reported with the spans.
+    pub service_name: String,
+    /// Fraction (between 0 and 1) of the RPC calls which are traced.
+    pub sample_ratio: f64,
+}
+
+impl TelemetryConfig {
+    /// Create a new [`TelemetryConfig`] from the `OTEL_EXPORTER_OTLP_ENDPOINT`,
+    /// `OTEL_SERVICE_NAME` and `OTEL_TRACES_SAMPLER_ARG` environment variables. Returns None if
+    /// the endpoint isn't set, in which case the spans aren't exported.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        let Some(endpoint) = var("OTEL_EXPORTER_OTLP_ENDPOINT") else {
+            return Ok(None);
+        };
+        let sample_ratio = var("OTEL_TRACES_SAMPLER_ARG").map_or(Ok(1.), |ratio| {
+            let sample_ratio =
+                f64::from_str(ratio.trim()).map_err(|err| eyre!("Invalid OTEL_TRACES_SAMPLER_ARG {ratio}: {err}"))?;
+            if !(0. ..=1.).contains(&sample_ratio) {
+                return Err(eyre!("Invalid OTEL_TRACES_SAMPLER_ARG {ratio}: expected a ratio between 0 and 1"));
+            }
+            Ok(sample_ratio)
+        })?;
+
+        Ok(Some(Self {
+            endpoint: endpoint.trim().to_string(),
+            service_name: var("OTEL_SERVICE_NAME").unwrap_or_else(|| "kakarot-rpc".to_string()),
+            sample_ratio,
+        }))
+    }
+
+    /// Returns the layer exporting the spans to the collector. The spans are exported in batches
+    /// by a background task, which must run on a Tokio runtime.
+    pub fn layer<S>(&self) -> Result<OpenTelemetryLayer<S, Tracer>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(&self.endpoint))
+            .with_trace_config(
+                opentelemetry_sdk::trace::config()
+                    // The nested spans follow the sampling decision of the RPC call
+                    .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(self.sample_ratio))))
+                    .with_resource(Resource::new([KeyValue::new("service.name", self.service_name.clone())])),
+            )
+            .install_batch(opentelemetry_sdk::runtime::Tokio)
+            .map_err(|err| eyre!("Failed to start the OTLP exporter: {err}"))?;
+
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+}
+
+/// Exports the spans which haven't been exported yet. Must be called before exiting.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
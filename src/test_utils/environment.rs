@@ -0,0 +1,133 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use ethers::providers::{Http, Provider as EthersProvider};
+use ethers::signers::{LocalWallet, Signer};
+use eyre::{eyre, Result};
+use jsonrpsee::server::ServerHandle;
+use katana_primitives::genesis::Genesis;
+use starknet::core::types::{BlockId, BlockTag};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider as _};
+
+use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_provider::starknet::kakarot_core::KAKAROT_ADDRESS;
+use crate::test_utils::katana::genesis::{Initialized, KatanaGenesisBuilder};
+use crate::test_utils::katana::{load_genesis, Katana};
+use crate::test_utils::mongo::RANDOM_BYTES_SIZE;
+use crate::test_utils::rpc::start_kakarot_rpc_server_on_port;
+
+/// Builds a [`TestEnvironment`].
+#[derive(Debug)]
+pub struct TestEnvironmentBuilder {
+    /// Genesis of Katana, the genesis generated by `make katana-genesis` if None.
+    genesis: Option<KatanaGenesisBuilder<Initialized>>,
+    /// Size of the random bytes used to generate the documents of the database.
+    random_bytes_size: usize,
+}
+
+impl Default for TestEnvironmentBuilder {
+    fn default() -> Self {
+        Self { genesis: None, random_bytes_size: RANDOM_BYTES_SIZE }
+    }
+}
+
+impl TestEnvironmentBuilder {
+    /// Starts Katana from the genesis of the builder instead of the generated genesis, once
+    /// validated. The EOAs added with [`KatanaGenesisBuilder::with_eoas_from_mnemonic`] are
+    /// returned as signers.
+    pub fn with_genesis(mut self, genesis: KatanaGenesisBuilder<Initialized>) -> Self {
+        self.genesis = Some(genesis);
+        self
+    }
+
+    /// Sets the size of the random bytes used to generate the documents of the database.
+    pub const fn with_random_bytes_size(mut self, random_bytes_size: usize) -> Self {
+        self.random_bytes_size = random_bytes_size;
+        self
+    }
+
+    /// Starts Katana and its database, checks that Kakarot is deployed at `KAKAROT_ADDRESS` and
+    /// starts the RPC server on a free port.
+    pub async fn build(self) -> Result<TestEnvironment> {
+        let (genesis, private_keys) = match self.genesis {
+            Some(builder) => {
+                let report = builder.validate();
+                if !report.is_valid() {
+                    return Err(eyre!("Invalid genesis:\n{report}"));
+                }
+                let private_keys = builder.manifest().eoas.into_iter().map(|eoa| eoa.private_key).collect();
+                (Genesis::try_from(builder.build()?)?, private_keys)
+            }
+            None => (load_genesis(), Vec::new()),
+        };
+        let katana = Katana::with_genesis(genesis, self.random_bytes_size).await;
+
+        // The RPC reads the address of Kakarot from the environment, which must match the genesis
+        let starknet_provider = katana.eth_provider().starknet_provider().clone();
+        starknet_provider.get_class_hash_at(BlockId::Tag(BlockTag::Latest), *KAKAROT_ADDRESS).await.map_err(|err| {
+            eyre!("Kakarot isn't deployed at KAKAROT_ADDRESS, run `make katana-genesis` or check the .env file: {err}")
+        })?;
+
+        let chain_id = katana.eth_provider().chain_id().await?.unwrap_or_default().to::<u64>();
+        let signers = std::iter::once(katana.eoa.private_key)
+            .chain(private_keys)
+            .map(|private_key| Ok(LocalWallet::from_bytes(private_key.as_slice())?.with_chain_id(chain_id)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let (rpc_addr, rpc_handle) = start_kakarot_rpc_server_on_port(&katana, 0).await?;
+        let provider = EthersProvider::<Http>::try_from(format!("http://{rpc_addr}"))?;
+
+        Ok(TestEnvironment { katana, rpc_addr, rpc_handle, provider, signers })
+    }
+}
+
+/// Katana running Kakarot, along with its database and the RPC server serving them. Dropping the
+/// environment stops the RPC server, Katana and the database container.
+#[allow(missing_debug_implementations)]
+pub struct TestEnvironment {
+    katana: Katana,
+    rpc_addr: SocketAddr,
+    rpc_handle: ServerHandle,
+    provider: EthersProvider<Http>,
+    signers: Vec<LocalWallet>,
+}
+
+impl TestEnvironment {
+    /// Returns a builder of the environment.
+    pub fn builder() -> TestEnvironmentBuilder {
+        TestEnvironmentBuilder::default()
+    }
+
+    /// Returns the Katana test environment.
+    pub const fn katana(&self) -> &Katana {
+        &self.katana
+    }
+
+    /// Returns the address of the RPC server.
+    pub const fn rpc_addr(&self) -> SocketAddr {
+        self.rpc_addr
+    }
+
+    /// Returns an Ethereum provider connected to the RPC server.
+    pub const fn provider(&self) -> &EthersProvider<Http> {
+        &self.provider
+    }
+
+    /// Returns the funded signers, starting with the EOA of `EVM_PRIVATE_KEY`.
+    pub fn signers(&self) -> &[LocalWallet] {
+        &self.signers
+    }
+
+    /// Returns the Starknet provider connected to Katana.
+    pub fn starknet_provider(&self) -> Arc<JsonRpcClient<HttpTransport>> {
+        self.katana.eth_provider().starknet_provider().clone()
+    }
+}
+
+impl Drop for TestEnvironment {
+    fn drop(&mut self) {
+        // Katana and the database container are stopped when dropped
+        let _ = self.rpc_handle.stop();
+    }
+}
@@ -2,8 +2,8 @@ use rstest::*;
 use tracing_subscriber::{filter, FmtSubscriber};
 #[cfg(any(test, feature = "arbitrary", feature = "testing"))]
 use {
-    super::katana::Katana, super::mongo::RANDOM_BYTES_SIZE, crate::test_utils::evm_contract::KakarotEvmContract,
-    ethers::abi::Token,
+    super::environment::TestEnvironment, super::katana::Katana, super::mongo::RANDOM_BYTES_SIZE,
+    crate::test_utils::evm_contract::KakarotEvmContract, ethers::abi::Token,
 };
 
 /// This fixture deploys a counter contract on Katana.
@@ -92,6 +92,13 @@ pub async fn katana() -> Katana {
     Katana::new(RANDOM_BYTES_SIZE).await
 }
 
+/// This fixture starts Katana, its database and the RPC server serving them.
+#[cfg(any(test, feature = "arbitrary", feature = "testing"))]
+#[fixture]
+pub async fn test_environment() -> TestEnvironment {
+    TestEnvironment::builder().build().await.expect("Failed to start the test environment")
+}
+
 /// This fixture configures the tests. The following setup
 /// is used:
 /// - The log level is set to `info`
@@ -1,9 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::marker::PhantomData;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use ef_testing::evm_sequencer::account::KakarotAccount;
+use ethers::signers::coins_bip39::English;
 use ethers::signers::LocalWallet;
+use ethers::signers::MnemonicBuilder;
 use ethers::signers::Signer;
 use ethers::types::U256;
 use eyre::{eyre, OptionExt, Result};
@@ -20,23 +24,24 @@ use katana_primitives::{
 };
 use lazy_static::lazy_static;
 use rayon::prelude::*;
-use reth_primitives::B256;
-use serde::Serialize;
+use reth_primitives::{keccak256, Address, Bytes, B256};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_with::serde_as;
 use starknet::core::serde::unsigned_field_element::UfeHex;
 use starknet::core::types::contract::legacy::LegacyContractClass;
-use starknet::core::types::contract::SierraClass;
+use starknet::core::types::contract::{CompiledClass, SierraClass};
 use starknet::core::types::FieldElement;
 use starknet::core::utils::{get_contract_address, get_storage_var_address, get_udc_deployed_address, UdcUniqueness};
 use walkdir::WalkDir;
 
 use crate::test_utils::constants::{
-    ACCOUNT_CAIRO1_HELPERS_CLASS_HASH, ACCOUNT_EVM_ADDRESS, ACCOUNT_IMPLEMENTATION,
+    ACCOUNT_CAIRO1_HELPERS_CLASS_HASH, ACCOUNT_EVM_ADDRESS, ACCOUNT_IMPLEMENTATION, ACCOUNT_NONCE,
     KAKAROT_ACCOUNT_CONTRACT_CLASS_HASH, KAKAROT_BASE_FEE, KAKAROT_BLOCK_GAS_LIMIT, KAKAROT_CAIRO1_HELPERS_CLASS_HASH,
     KAKAROT_COINBASE, KAKAROT_EVM_TO_STARKNET_ADDRESS, KAKAROT_NATIVE_TOKEN_ADDRESS, KAKAROT_PREV_RANDAO,
     KAKAROT_UNINITIALIZED_ACCOUNT_CLASS_HASH, OWNABLE_OWNER,
 };
+use crate::test_utils::evm_contract::{EvmContract, KakarotEvmContract};
 
 lazy_static! {
     static ref SALT: FieldElement = FieldElement::from_bytes_be(&[0u8; 32]).unwrap();
@@ -46,10 +51,151 @@ lazy_static! {
 #[derive(Serialize, Debug)]
 pub struct Hex(#[serde_as(as = "UfeHex")] pub FieldElement);
 
+/// Storage slots of the Solmate ERC20 contract.
+const ERC20_NAME_SLOT: reth_primitives::U256 = reth_primitives::U256::from_limbs([0, 0, 0, 0]);
+const ERC20_SYMBOL_SLOT: reth_primitives::U256 = reth_primitives::U256::from_limbs([1, 0, 0, 0]);
+const ERC20_TOTAL_SUPPLY_SLOT: reth_primitives::U256 = reth_primitives::U256::from_limbs([2, 0, 0, 0]);
+const ERC20_BALANCE_OF_SLOT: reth_primitives::U256 = reth_primitives::U256::from_limbs([3, 0, 0, 0]);
+
+/// The fee token of the genesis, which is also the native token of Kakarot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeeToken {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Address of the token, Katana's default fee token address if None.
+    pub address: Option<ContractAddress>,
+    /// Class hash of the token, Katana's default ERC20 class if None.
+    pub class: Option<FieldElement>,
+}
+
+impl Default for FeeToken {
+    fn default() -> Self {
+        Self { name: "Ether".to_string(), symbol: "ETH".to_string(), decimals: 18, address: None, class: None }
+    }
+}
+
+impl FeeToken {
+    /// Returns the address the token is deployed at.
+    pub fn address(&self) -> ContractAddress {
+        self.address.unwrap_or(DEFAULT_FEE_TOKEN_ADDRESS)
+    }
+}
+
+/// A problem found in the genesis by [`KatanaGenesisBuilder::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenesisDiagnostic {
+    /// A contract or an account is deployed with a class which isn't declared.
+    MissingClass { address: ContractAddress, class_hash: FieldElement },
+    /// The class of the fee token isn't declared.
+    MissingFeeTokenClass(FieldElement),
+    /// An address is used by a contract and an account, or by the fee token.
+    AddressCollision(ContractAddress),
+    /// A storage key of the contract deployed at the fee token address is also written by the
+    /// balances and allowances of the fee token.
+    StorageCollision { address: ContractAddress, key: StorageKey },
+    /// The coinbase doesn't hold any fee token. Only a warning, the coinbase is funded by the fees.
+    UnfundedCoinbase(ContractAddress),
+}
+
+impl GenesisDiagnostic {
+    /// Returns true if the diagnostic prevents Katana from starting with the genesis, or makes
+    /// the genesis inconsistent.
+    pub const fn is_error(&self) -> bool {
+        !matches!(self, Self::UnfundedCoinbase(_))
+    }
+}
+
+impl fmt::Display for GenesisDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingClass { address, class_hash } => {
+                write!(f, "contract {:#x} uses the undeclared class {class_hash:#x}", address.0)
+            }
+            Self::MissingFeeTokenClass(class_hash) => {
+                write!(f, "the fee token uses the undeclared class {class_hash:#x}")
+            }
+            Self::AddressCollision(address) => write!(f, "several contracts are deployed at {:#x}", address.0),
+            Self::StorageCollision { address, key } => {
+                write!(f, "storage key {key:#x} of {:#x} is also written by the fee token", address.0)
+            }
+            Self::UnfundedCoinbase(address) => write!(f, "the coinbase {:#x} doesn't hold any fee token", address.0),
+        }
+    }
+}
+
+/// The diagnostics of a genesis returned by [`KatanaGenesisBuilder::validate`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GenesisReport {
+    pub diagnostics: Vec<GenesisDiagnostic>,
+}
+
+impl GenesisReport {
+    /// Returns true if none of the diagnostics is an error.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns the diagnostics which are errors.
+    pub fn errors(&self) -> impl Iterator<Item = &GenesisDiagnostic> {
+        self.diagnostics.iter().filter(|diagnostic| diagnostic.is_error())
+    }
+
+    /// Returns the diagnostics which are warnings.
+    pub fn warnings(&self) -> impl Iterator<Item = &GenesisDiagnostic> {
+        self.diagnostics.iter().filter(|diagnostic| !diagnostic.is_error())
+    }
+}
+
+impl fmt::Display for GenesisReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            let severity = if diagnostic.is_error() { "error" } else { "warning" };
+            writeln!(f, "{severity}: {diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct KatanaManifest {
     pub declarations: HashMap<String, Hex>,
     pub deployments: HashMap<String, Hex>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub compiled_class_hashes: HashMap<String, Hex>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub eoas: Vec<ManifestEoa>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ManifestEoa {
+    pub address: Address,
+    pub private_key: B256,
+}
+
+/// Serializable state of an initialized [`KatanaGenesisBuilder`]. The classes are referenced
+/// by the path of their artifact and their class hash, which avoids recomputing the class hashes
+/// when the fixture is reloaded.
+#[derive(Serialize, Deserialize, Debug)]
+struct KatanaGenesisFixture {
+    coinbase: FieldElement,
+    classes: Vec<FixtureClass>,
+    class_hashes: HashMap<String, FieldElement>,
+    #[serde(default)]
+    compiled_class_hashes: HashMap<String, FieldElement>,
+    contracts: HashMap<ContractAddress, GenesisContractJson>,
+    accounts: HashMap<ContractAddress, GenesisAccountJson>,
+    fee_token_storage: HashMap<StorageKey, StorageValue>,
+    #[serde(default)]
+    fee_token: FeeToken,
+    cache: HashMap<String, FieldElement>,
+    eoas: Vec<B256>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct FixtureClass {
+    path: PathBuf,
+    class_hash: Option<FieldElement>,
 }
 
 #[derive(Debug, Clone)]
@@ -63,11 +209,16 @@ pub struct Initialized;
 pub struct KatanaGenesisBuilder<T> {
     coinbase: FieldElement,
     classes: Vec<GenesisClassJson>,
+    class_paths: Vec<PathBuf>,
     class_hashes: HashMap<String, FieldElement>,
+    /// Compiled class hashes of the Sierra classes, computed from their compiled (CASM) artifact.
+    compiled_class_hashes: HashMap<String, FieldElement>,
     contracts: HashMap<ContractAddress, GenesisContractJson>,
     accounts: HashMap<ContractAddress, GenesisAccountJson>,
     fee_token_storage: HashMap<StorageKey, StorageValue>,
+    fee_token: FeeToken,
     cache: HashMap<String, FieldElement>,
+    eoas: Vec<B256>,
     status: PhantomData<T>,
 }
 
@@ -90,11 +241,15 @@ impl<T> KatanaGenesisBuilder<T> {
         KatanaGenesisBuilder {
             coinbase: self.coinbase,
             classes: self.classes,
+            class_paths: self.class_paths,
             class_hashes: self.class_hashes,
+            compiled_class_hashes: self.compiled_class_hashes,
             contracts: self.contracts,
             accounts: self.accounts,
             fee_token_storage: self.fee_token_storage,
+            fee_token: self.fee_token,
             cache: self.cache,
+            eoas: self.eoas,
             status: PhantomData::<State>,
         }
     }
@@ -123,6 +278,11 @@ impl<T> KatanaGenesisBuilder<T> {
         self
     }
 
+    /// Returns the fee token of the genesis.
+    pub const fn fee_token(&self) -> &FeeToken {
+        &self.fee_token
+    }
+
     fn kakarot_class_hash(&self) -> Result<FieldElement> {
         self.class_hashes.get("kakarot").cloned().ok_or_eyre("Missing Kakarot class hash")
     }
@@ -145,11 +305,15 @@ impl Default for KatanaGenesisBuilder<Uninitialized> {
         KatanaGenesisBuilder {
             coinbase: FieldElement::ZERO,
             classes: vec![],
+            class_paths: vec![],
             class_hashes: HashMap::new(),
+            compiled_class_hashes: HashMap::new(),
             contracts: HashMap::new(),
             accounts: HashMap::new(),
             fee_token_storage: HashMap::new(),
+            fee_token: FeeToken::default(),
             cache: HashMap::new(),
+            eoas: vec![],
             status: PhantomData::<Uninitialized>,
         }
     }
@@ -157,44 +321,76 @@ impl Default for KatanaGenesisBuilder<Uninitialized> {
 
 impl KatanaGenesisBuilder<Uninitialized> {
     /// Load the classes from the given path. Computes the class hashes and stores them in the builder.
+    /// Both the legacy (Cairo 0) and the Sierra (Cairo 1) classes are loaded, named after their
+    /// artifact without its extensions. The compiled (CASM) artifacts of the Sierra classes aren't
+    /// declared, only their compiled class hash is stored.
     #[must_use]
     pub fn load_classes(mut self, path: PathBuf) -> KatanaGenesisBuilder<Loaded> {
         let entries = WalkDir::new(path).into_iter().filter(|e| e.is_ok() && e.as_ref().unwrap().file_type().is_file());
-        let classes = entries
+        let artifacts = entries
             .par_bridge()
             .map(|entry| {
                 let path = entry.unwrap().path().to_path_buf();
                 let artifact = fs::read_to_string(&path).expect("Failed to read artifact");
-                (
-                    path,
-                    GenesisClassJson {
-                        class: PathOrFullArtifact::Artifact(
-                            serde_json::from_str(&artifact).expect("Failed to parse artifact"),
-                        ),
-                        class_hash: None,
-                    },
-                )
+                (path, serde_json::from_str::<Value>(&artifact).expect("Failed to parse artifact"))
             })
             .collect::<Vec<_>>();
+        let (compiled_classes, classes): (Vec<_>, Vec<_>) =
+            artifacts.into_iter().partition(|(_, artifact)| is_compiled_class(artifact));
 
-        self.class_hashes = classes
+        self.compiled_class_hashes = compiled_classes
             .par_iter()
-            .filter_map(|(path, class)| {
-                let artifact = match &class.class {
-                    PathOrFullArtifact::Artifact(artifact) => artifact,
-                    PathOrFullArtifact::Path(_) => unreachable!("Expected artifact"),
-                };
-                let class_hash = compute_class_hash(artifact).ok()?;
-                Some((path.file_stem().unwrap().to_str().unwrap().to_string(), class_hash))
+            .filter_map(|(path, artifact)| {
+                let compiled_class = serde_json::from_value::<CompiledClass>(artifact.clone()).ok()?;
+                Some((artifact_name(path), compiled_class.class_hash().ok()?))
             })
             .collect();
-        self.classes = classes.into_iter().map(|(_, class)| class).collect();
+        self.class_hashes = classes
+            .par_iter()
+            .filter_map(|(path, artifact)| Some((artifact_name(path), compute_class_hash(artifact).ok()?)))
+            .collect();
+        (self.class_paths, self.classes) = classes
+            .into_iter()
+            .map(|(path, artifact)| {
+                (path, GenesisClassJson { class: PathOrFullArtifact::Artifact(artifact), class_hash: None })
+            })
+            .unzip();
 
         self.update_state()
     }
 }
 
 impl KatanaGenesisBuilder<Loaded> {
+    /// Sets the fee token of the genesis, which defaults to Katana's "Ether" token. The token is
+    /// also the native token of Kakarot, so it must be set before adding Kakarot, whose address
+    /// depends on it. The class, if any, must be one of the loaded classes.
+    #[must_use]
+    pub fn with_fee_token(
+        mut self,
+        name: impl Into<String>,
+        symbol: impl Into<String>,
+        decimals: u8,
+        address: Option<ContractAddress>,
+        class: Option<FieldElement>,
+    ) -> Self {
+        self.fee_token = FeeToken { name: name.into(), symbol: symbol.into(), decimals, address, class };
+        self
+    }
+
+    /// Uses the class of the artifact for the class `name` looked up by the builder (`kakarot`,
+    /// `account_contract`, `uninitialized_account` or `cairo1_helpers`), e.g.
+    /// `with_class_override("account_contract", "kakarot_AccountContract")` selects a Sierra
+    /// account class instead of the legacy one. Must be called before adding Kakarot.
+    pub fn with_class_override(mut self, name: &str, artifact: &str) -> Result<Self> {
+        let class_hash = *self.class_hashes.get(artifact).ok_or_else(|| eyre!("Missing class {artifact}"))?;
+        self.class_hashes.insert(name.to_string(), class_hash);
+        match self.compiled_class_hashes.get(artifact).copied() {
+            Some(compiled_class_hash) => self.compiled_class_hashes.insert(name.to_string(), compiled_class_hash),
+            None => self.compiled_class_hashes.remove(name),
+        };
+        Ok(self)
+    }
+
     /// Add the Kakarot contract to the genesis. Updates the state to [Initialized].
     /// Once in the [Initialized] status, the builder can be built.
     pub fn with_kakarot(mut self, coinbase_address: FieldElement) -> Result<KatanaGenesisBuilder<Initialized>> {
@@ -204,6 +400,7 @@ impl KatanaGenesisBuilder<Loaded> {
         let uninitialized_account_class_hash = self.uninitialized_account_class_hash()?;
         let cairo1_helpers_class_hash = self.cairo1_helpers_class_hash()?;
         let block_gas_limit = 20_000_000u64.into();
+        let fee_token_address = self.fee_token.address();
         // Construct the kakarot contract address. Based on the constructor args from
         // https://github.com/kkrt-labs/kakarot/blob/main/src/kakarot/kakarot.cairo#L23
         let kakarot_address = ContractAddress::new(get_udc_deployed_address(
@@ -212,7 +409,7 @@ impl KatanaGenesisBuilder<Loaded> {
             &UdcUniqueness::NotUnique,
             &[
                 FieldElement::ZERO,
-                DEFAULT_FEE_TOKEN_ADDRESS.0,
+                fee_token_address.0,
                 account_contract_class_hash,
                 uninitialized_account_class_hash,
                 cairo1_helpers_class_hash,
@@ -226,7 +423,7 @@ impl KatanaGenesisBuilder<Loaded> {
 
         // Construct the kakarot contract storage.
         let kakarot_storage = [
-            (storage_addr(KAKAROT_NATIVE_TOKEN_ADDRESS)?, *DEFAULT_FEE_TOKEN_ADDRESS),
+            (storage_addr(KAKAROT_NATIVE_TOKEN_ADDRESS)?, fee_token_address.0),
             (storage_addr(KAKAROT_ACCOUNT_CONTRACT_CLASS_HASH)?, account_contract_class_hash),
             (storage_addr(KAKAROT_UNINITIALIZED_ACCOUNT_CLASS_HASH)?, uninitialized_account_class_hash),
             (storage_addr(KAKAROT_CAIRO1_HELPERS_CLASS_HASH)?, cairo1_helpers_class_hash),
@@ -297,6 +494,162 @@ impl KatanaGenesisBuilder<Initialized> {
         Ok(self)
     }
 
+    /// Add `count` funded EOAs to the genesis, derived from the mnemonic using the BIP-44
+    /// derivation path `m/44'/60'/0'/0/{index}`. The derived private keys are exposed in the
+    /// manifest.
+    pub fn with_eoas_from_mnemonic(mut self, mnemonic: &str, count: u32, funding: U256) -> Result<Self> {
+        for index in 0..count {
+            let wallet = MnemonicBuilder::<English>::default().phrase(mnemonic).index(index)?.build()?;
+            let private_key = B256::from_slice(wallet.signer().to_bytes().as_slice());
+            self = self.with_eoa(private_key)?.fund(private_key, funding)?;
+            self.eoas.push(private_key);
+        }
+        Ok(self)
+    }
+
+    /// Add a contract account to the genesis, deployed at the given EVM address with the given
+    /// bytecode and EVM storage. The contract is deployed as if it was created by a transaction,
+    /// with a nonce of 1.
+    pub fn with_evm_contract(
+        mut self,
+        evm_address: Address,
+        bytecode: Bytes,
+        storage: HashMap<reth_primitives::U256, reth_primitives::U256>,
+    ) -> Result<Self> {
+        let kakarot_address = self.cache_load("kakarot_address")?;
+        let account_contract_class_hash = self.account_contract_class_hash()?;
+        let cairo1_helpers_class_hash = self.cairo1_helpers_class_hash()?;
+
+        // Set the bytecode and the EVM storage of the contract account
+        let storage = storage.into_iter().collect::<Vec<_>>();
+        let kakarot_account =
+            KakarotAccount::new(&evm_address, &bytecode, reth_primitives::U256::ZERO, &storage, false)?;
+        let mut contract_storage: HashMap<StorageKey, StorageValue> =
+            kakarot_account.storage().iter().map(|(k, v)| ((*k.0.key()).into(), (*v).into())).collect();
+        contract_storage.extend([
+            (storage_addr(ACCOUNT_IMPLEMENTATION)?, account_contract_class_hash),
+            (storage_addr(ACCOUNT_NONCE)?, FieldElement::ONE),
+            (storage_addr(OWNABLE_OWNER)?, kakarot_address),
+            (storage_addr(ACCOUNT_CAIRO1_HELPERS_CLASS_HASH)?, cairo1_helpers_class_hash),
+        ]);
+
+        let contract = GenesisContractJson {
+            class: Some(account_contract_class_hash),
+            balance: None,
+            nonce: None,
+            storage: Some(contract_storage),
+        };
+
+        let evm_address = FieldElement::from_byte_slice_be(evm_address.as_slice())?;
+        let starknet_address = self.compute_starknet_address(evm_address)?;
+        self.contracts.insert(starknet_address, contract);
+
+        // Set the allowance for the contract account to the Kakarot contract.
+        let key = get_storage_var_address("ERC20_allowances", &[*starknet_address, kakarot_address])?;
+        let storage = [(key, u128::MAX.into()), (key + 1u8.into(), u128::MAX.into())].into_iter();
+        self.fee_token_storage.extend(storage);
+
+        // Write the address to the Kakarot evm to starknet mapping
+        let kakarot_address = ContractAddress::new(kakarot_address);
+        let kakarot_contract = self.contracts.get_mut(&kakarot_address).ok_or_eyre("Kakarot contract missing")?;
+        kakarot_contract
+            .storage
+            .get_or_insert_with(HashMap::new)
+            .extend([(get_storage_var_address(KAKAROT_EVM_TO_STARKNET_ADDRESS, &[evm_address])?, starknet_address.0)]);
+
+        Ok(self)
+    }
+
+    /// Add a Solmate ERC20 contract to the genesis, deployed at the given EVM address. The name,
+    /// symbol and balances are written to the contract's storage and the total supply is set to
+    /// the sum of the balances.
+    pub fn with_erc20(
+        self,
+        evm_address: Address,
+        name: &str,
+        symbol: &str,
+        decimals: u8,
+        balances: &[(Address, reth_primitives::U256)],
+    ) -> Result<Self> {
+        let bytecode = erc20_deployed_bytecode(decimals)?;
+
+        let mut storage = HashMap::new();
+        storage.extend(solidity_string_storage(ERC20_NAME_SLOT, name));
+        storage.extend(solidity_string_storage(ERC20_SYMBOL_SLOT, symbol));
+
+        let mut total_supply = reth_primitives::U256::ZERO;
+        for (owner, balance) in balances {
+            let slot = keccak256(
+                [B256::left_padding_from(owner.as_slice()).as_slice(), &ERC20_BALANCE_OF_SLOT.to_be_bytes::<32>()]
+                    .concat(),
+            );
+            storage.insert(reth_primitives::U256::from_be_bytes(slot.0), *balance);
+            total_supply = total_supply.checked_add(*balance).ok_or_eyre("ERC20 total supply overflow")?;
+        }
+        storage.insert(ERC20_TOTAL_SUPPLY_SLOT, total_supply);
+
+        self.with_evm_contract(evm_address, bytecode, storage)
+    }
+
+    /// Write the state of the builder to a fixture file, which can be reloaded using
+    /// [`KatanaGenesisBuilder::from_fixture`].
+    pub fn to_fixture(&self, path: impl AsRef<Path>) -> Result<()> {
+        let classes = self
+            .class_paths
+            .iter()
+            .map(|path| FixtureClass {
+                path: path.clone(),
+                class_hash: self.class_hashes.get(&artifact_name(path)).copied(),
+            })
+            .collect();
+        let fixture = KatanaGenesisFixture {
+            coinbase: self.coinbase,
+            classes,
+            class_hashes: self.class_hashes.clone(),
+            compiled_class_hashes: self.compiled_class_hashes.clone(),
+            contracts: self.contracts.clone(),
+            accounts: self.accounts.clone(),
+            fee_token_storage: self.fee_token_storage.clone(),
+            fee_token: self.fee_token.clone(),
+            cache: self.cache.clone(),
+            eoas: self.eoas.clone(),
+        };
+        fs::write(path, serde_json::to_string(&fixture)?)?;
+        Ok(())
+    }
+
+    /// Load a builder from a fixture file written by [`KatanaGenesisBuilder::to_fixture`]. The
+    /// class artifacts are read from their paths, but their class hashes are not recomputed.
+    pub fn from_fixture(path: impl AsRef<Path>) -> Result<Self> {
+        let fixture: KatanaGenesisFixture = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let classes = fixture
+            .classes
+            .par_iter()
+            .map(|class| -> Result<GenesisClassJson> {
+                let artifact = fs::read_to_string(&class.path)?;
+                Ok(GenesisClassJson {
+                    class: PathOrFullArtifact::Artifact(serde_json::from_str(&artifact)?),
+                    class_hash: class.class_hash,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(KatanaGenesisBuilder {
+            coinbase: fixture.coinbase,
+            classes,
+            class_paths: fixture.classes.into_iter().map(|class| class.path).collect(),
+            class_hashes: fixture.class_hashes,
+            compiled_class_hashes: fixture.compiled_class_hashes,
+            contracts: fixture.contracts,
+            accounts: fixture.accounts,
+            fee_token_storage: fixture.fee_token_storage,
+            fee_token: fixture.fee_token,
+            cache: fixture.cache,
+            eoas: fixture.eoas,
+            status: PhantomData::<Initialized>,
+        })
+    }
+
     /// Fund the starknet address deployed for the evm address of the passed private key
     /// with the given amount of tokens.
     pub fn fund(mut self, pk: B256, amount: U256) -> Result<Self> {
@@ -329,12 +682,12 @@ impl KatanaGenesisBuilder<Initialized> {
             gas_prices: GasPrices::default(),
             classes: self.classes,
             fee_token: FeeTokenConfigJson {
-                name: "Ether".to_string(),
-                symbol: "ETH".to_string(),
-                decimals: 18,
+                name: self.fee_token.name,
+                symbol: self.fee_token.symbol,
+                decimals: self.fee_token.decimals,
                 storage: Some(self.fee_token_storage),
-                address: None,
-                class: None,
+                address: self.fee_token.address,
+                class: self.fee_token.class,
             },
             universal_deployer: None,
             accounts: self.accounts,
@@ -342,11 +695,76 @@ impl KatanaGenesisBuilder<Initialized> {
         })
     }
 
+    /// Checks the genesis for the problems which would otherwise make Katana fail at boot with
+    /// an opaque error, or start with an inconsistent state: undeclared classes, addresses used
+    /// twice, storage keys of the fee token written twice and an unfunded coinbase.
+    pub fn validate(&self) -> GenesisReport {
+        let mut diagnostics = Vec::new();
+
+        let declared = self
+            .class_hashes
+            .values()
+            .copied()
+            .chain(self.classes.iter().filter_map(|class| class.class_hash))
+            .collect::<HashSet<_>>();
+        let classes = self
+            .contracts
+            .iter()
+            .filter_map(|(address, contract)| Some((*address, contract.class?)))
+            .chain(self.accounts.iter().filter_map(|(address, account)| Some((*address, account.class?))));
+        for (address, class_hash) in classes {
+            if !declared.contains(&class_hash) {
+                diagnostics.push(GenesisDiagnostic::MissingClass { address, class_hash });
+            }
+        }
+        if let Some(class_hash) = self.fee_token.class.filter(|class_hash| !declared.contains(class_hash)) {
+            diagnostics.push(GenesisDiagnostic::MissingFeeTokenClass(class_hash));
+        }
+
+        let fee_token_address = self.fee_token.address();
+        for address in self.contracts.keys() {
+            if self.accounts.contains_key(address) || *address == fee_token_address {
+                diagnostics.push(GenesisDiagnostic::AddressCollision(*address));
+            }
+        }
+        if self.accounts.contains_key(&fee_token_address) {
+            diagnostics.push(GenesisDiagnostic::AddressCollision(fee_token_address));
+        }
+
+        if let Some(storage) = self.contracts.get(&fee_token_address).and_then(|contract| contract.storage.as_ref()) {
+            for key in storage.keys().filter(|key| self.fee_token_storage.contains_key(key)) {
+                diagnostics.push(GenesisDiagnostic::StorageCollision { address: fee_token_address, key: *key });
+            }
+        }
+
+        if let Ok(coinbase) = self.compute_starknet_address(self.coinbase) {
+            let funded = get_storage_var_address("ERC20_balances", &[coinbase.0]).is_ok_and(|key| {
+                [key, key + FieldElement::ONE]
+                    .iter()
+                    .any(|key| self.fee_token_storage.get(key).is_some_and(|value| *value != FieldElement::ZERO))
+            });
+            if !funded {
+                diagnostics.push(GenesisDiagnostic::UnfundedCoinbase(coinbase));
+            }
+        }
+
+        GenesisReport { diagnostics }
+    }
+
     /// Returns the manifest of the genesis.
     pub fn manifest(&self) -> KatanaManifest {
         KatanaManifest {
             declarations: self.class_hashes().clone().into_iter().map(|(k, v)| (k, Hex(v))).collect(),
             deployments: self.cache().clone().into_iter().map(|(k, v)| (k, Hex(v))).collect(),
+            compiled_class_hashes: self.compiled_class_hashes().clone().into_iter().map(|(k, v)| (k, Hex(v))).collect(),
+            eoas: self
+                .eoas
+                .iter()
+                .filter_map(|pk| {
+                    let wallet = LocalWallet::from_bytes(pk.as_slice()).ok()?;
+                    Some(ManifestEoa { address: Address::from_slice(wallet.address().as_bytes()), private_key: *pk })
+                })
+                .collect(),
         }
     }
 
@@ -380,19 +798,355 @@ impl KatanaGenesisBuilder<Initialized> {
     pub fn class_hashes(&self) -> &HashMap<String, FieldElement> {
         &self.class_hashes
     }
+
+    /// Returns the compiled class hashes of the Sierra classes, by class name.
+    pub fn compiled_class_hashes(&self) -> &HashMap<String, FieldElement> {
+        &self.compiled_class_hashes
+    }
 }
 
 fn compute_class_hash(class: &Value) -> Result<FieldElement> {
     match serde_json::from_value::<SierraClass>(class.clone()) {
         Ok(sierra) => Ok(sierra.class_hash()?),
         Err(_) => {
-            let casm: LegacyContractClass =
-                serde_json::from_value(class.clone()).expect("Failed to parse class code v0");
-            Ok(casm.class_hash()?)
+            let legacy: LegacyContractClass = serde_json::from_value(class.clone())?;
+            Ok(legacy.class_hash()?)
         }
     }
 }
 
+/// Returns true if the artifact is a compiled (CASM) class, which only holds the bytecode of a
+/// Sierra class.
+fn is_compiled_class(artifact: &Value) -> bool {
+    artifact.get("bytecode").is_some() && artifact.get("sierra_program").is_none() && artifact.get("program").is_none()
+}
+
+/// Returns the name of the class of an artifact: its file name without the `.json` extension and
+/// the `.contract_class` or `.compiled_contract_class` suffix of the Scarb artifacts, so that a
+/// Sierra class and its compiled class share the same name.
+fn artifact_name(path: &Path) -> String {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    let name = name.strip_suffix(".json").unwrap_or(name);
+    [".compiled_contract_class", ".contract_class"]
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name)
+        .to_string()
+}
+
 fn storage_addr(var_name: &str) -> Result<FieldElement> {
     Ok(get_storage_var_address(var_name, &[])?)
 }
+
+/// Returns the deployed bytecode of the Solmate ERC20 contract, with the `decimals` immutable set.
+/// The other immutables (initial chain id and domain separator) are left to zero, which makes the
+/// contract recompute the domain separator on each call.
+fn erc20_deployed_bytecode(decimals: u8) -> Result<Bytes> {
+    let contract = KakarotEvmContract::load_contract_bytecode("ERC20")?;
+    let deployed_bytecode = contract.deployed_bytecode.ok_or_eyre("No deployed bytecode found")?;
+    let mut bytecode = deployed_bytecode
+        .bytecode
+        .and_then(|b| b.object.as_bytes().cloned())
+        .ok_or_eyre("No deployed bytecode found")?
+        .to_vec();
+
+    // Immutables are referenced by the id of their declaration, which follows the declaration
+    // order in the source: `decimals` is the first immutable declared in the Solmate ERC20.
+    let decimals_references = deployed_bytecode
+        .immutable_references
+        .iter()
+        .min_by_key(|(id, _)| id.parse::<u64>().unwrap_or(u64::MAX))
+        .map(|(_, offsets)| offsets)
+        .ok_or_eyre("Missing decimals immutable")?;
+    let value = reth_primitives::U256::from(decimals).to_be_bytes::<32>();
+    for offset in decimals_references {
+        let (start, length) = (offset.start as usize, offset.length as usize);
+        let slice = bytecode.get_mut(start..start + length).ok_or_eyre("Invalid immutable reference")?;
+        slice.copy_from_slice(&value[32 - length..]);
+    }
+
+    Ok(bytecode.into())
+}
+
+/// Returns the storage of a Solidity string stored at the given slot. Strings shorter than 32
+/// bytes are stored in the slot along with their length, longer strings are stored starting
+/// from the keccak of the slot.
+fn solidity_string_storage(
+    slot: reth_primitives::U256,
+    value: &str,
+) -> Vec<(reth_primitives::U256, reth_primitives::U256)> {
+    let bytes = value.as_bytes();
+    if bytes.len() < 32 {
+        let mut word = [0u8; 32];
+        word[..bytes.len()].copy_from_slice(bytes);
+        word[31] = (bytes.len() * 2) as u8;
+        return vec![(slot, reth_primitives::U256::from_be_bytes(word))];
+    }
+
+    let data_slot = reth_primitives::U256::from_be_bytes(keccak256(slot.to_be_bytes::<32>()).0);
+    let mut storage = vec![(slot, reth_primitives::U256::from(bytes.len() * 2 + 1))];
+    storage.extend(bytes.chunks(32).enumerate().map(|(i, chunk)| {
+        let mut word = [0u8; 32];
+        word[..chunk.len()].copy_from_slice(chunk);
+        (data_slot + reth_primitives::U256::from(i), reth_primitives::U256::from_be_bytes(word))
+    }));
+    storage
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use reth_primitives::U256 as RethU256;
+
+    use super::*;
+    use crate::eth_provider::utils::split_u256;
+    use crate::test_utils::constants::ACCOUNT_STORAGE;
+
+    lazy_static! {
+        static ref ROOT: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+        static ref LOADED_BUILDER: KatanaGenesisBuilder<Loaded> =
+            KatanaGenesisBuilder::default().load_classes(ROOT.join("lib/kakarot/build"));
+        static ref GENESIS_BUILDER: KatanaGenesisBuilder<Initialized> =
+            LOADED_BUILDER.clone().with_kakarot(FieldElement::ZERO).unwrap();
+    }
+
+    #[test]
+    fn test_with_evm_contract() {
+        // Given
+        let evm_address = Address::from([0x42u8; 20]);
+        let bytecode = Bytes::from(vec![0x60, 0x00, 0x60, 0x00, 0xf3]);
+        let storage = HashMap::from([(RethU256::from(1), RethU256::from(u128::MAX) + RethU256::from(2))]);
+
+        // When
+        let builder = GENESIS_BUILDER.clone().with_evm_contract(evm_address, bytecode, storage.clone()).unwrap();
+
+        // Then
+        let evm_address = FieldElement::from_byte_slice_be(evm_address.as_slice()).unwrap();
+        let starknet_address = builder.compute_starknet_address(evm_address).unwrap();
+        let contract = builder.contracts.get(&starknet_address).unwrap();
+        assert_eq!(contract.class, Some(builder.account_contract_class_hash().unwrap()));
+
+        let contract_storage = contract.storage.as_ref().unwrap();
+        assert_eq!(contract_storage.get(&storage_addr(ACCOUNT_NONCE).unwrap()), Some(&FieldElement::ONE));
+        for (key, value) in storage {
+            let key = get_storage_var_address(ACCOUNT_STORAGE, &split_u256::<FieldElement>(key)).unwrap();
+            let low = RethU256::from_be_slice(contract_storage.get(&key).unwrap().to_bytes_be().as_slice());
+            let high =
+                RethU256::from_be_slice(contract_storage.get(&(key + 1u8.into())).unwrap().to_bytes_be().as_slice());
+            assert_eq!(low + (high << 128), value);
+        }
+
+        let kakarot_address = ContractAddress::new(builder.cache_load("kakarot_address").unwrap());
+        let kakarot_storage = builder.contracts.get(&kakarot_address).unwrap().storage.as_ref().unwrap();
+        let mapping_key = get_storage_var_address(KAKAROT_EVM_TO_STARKNET_ADDRESS, &[evm_address]).unwrap();
+        assert_eq!(kakarot_storage.get(&mapping_key), Some(&starknet_address.0));
+    }
+
+    #[test]
+    fn test_solidity_string_storage_short() {
+        // Given
+        let slot = RethU256::from(1);
+
+        // When
+        let storage = solidity_string_storage(slot, "TT");
+
+        // Then
+        let mut expected = [0u8; 32];
+        expected[..2].copy_from_slice(b"TT");
+        expected[31] = 4;
+        assert_eq!(storage, vec![(slot, RethU256::from_be_bytes(expected))]);
+    }
+
+    #[test]
+    fn test_solidity_string_storage_long() {
+        // Given
+        let slot = RethU256::ZERO;
+        let value = "a".repeat(40);
+
+        // When
+        let storage = solidity_string_storage(slot, &value);
+
+        // Then
+        let data_slot = RethU256::from_be_bytes(keccak256(slot.to_be_bytes::<32>()).0);
+        let mut second_word = [0u8; 32];
+        second_word[..8].copy_from_slice(&[b'a'; 8]);
+        assert_eq!(
+            storage,
+            vec![
+                (slot, RethU256::from(81)),
+                (data_slot, RethU256::from_be_bytes([b'a'; 32])),
+                (data_slot + RethU256::from(1), RethU256::from_be_bytes(second_word)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_erc20() {
+        // Given
+        let token = Address::from([0x11u8; 20]);
+        let balances =
+            [(Address::from([0x22u8; 20]), RethU256::from(100)), (Address::from([0x33u8; 20]), RethU256::from(50))];
+
+        // When
+        let builder = GENESIS_BUILDER.clone().with_erc20(token, "Test", "TT", 18, &balances).unwrap();
+
+        // Then
+        let evm_address = FieldElement::from_byte_slice_be(token.as_slice()).unwrap();
+        let starknet_address = builder.compute_starknet_address(evm_address).unwrap();
+        let contract_storage = builder.contracts.get(&starknet_address).unwrap().storage.clone().unwrap();
+        let read = |slot: RethU256| {
+            let key = get_storage_var_address(ACCOUNT_STORAGE, &split_u256::<FieldElement>(slot)).unwrap();
+            let low = RethU256::from_be_slice(contract_storage.get(&key).unwrap().to_bytes_be().as_slice());
+            let high =
+                RethU256::from_be_slice(contract_storage.get(&(key + 1u8.into())).unwrap().to_bytes_be().as_slice());
+            low + (high << 128)
+        };
+
+        assert_eq!(read(ERC20_TOTAL_SUPPLY_SLOT), RethU256::from(150));
+        for (owner, balance) in balances {
+            let slot = keccak256(
+                [B256::left_padding_from(owner.as_slice()).as_slice(), &ERC20_BALANCE_OF_SLOT.to_be_bytes::<32>()]
+                    .concat(),
+            );
+            assert_eq!(read(RethU256::from_be_bytes(slot.0)), balance);
+        }
+    }
+
+    #[test]
+    fn test_with_eoas_from_mnemonic() {
+        // Given
+        let mnemonic = "test test test test test test test test test test test junk";
+
+        // When
+        let builder = GENESIS_BUILDER.clone().with_eoas_from_mnemonic(mnemonic, 2, U256::from(1_000_000u64)).unwrap();
+
+        // Then
+        let manifest = builder.manifest();
+        assert_eq!(manifest.eoas.len(), 2);
+        // First account derived from the default Anvil/Hardhat mnemonic
+        assert_eq!(manifest.eoas[0].address, Address::from_str("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap());
+        for eoa in manifest.eoas {
+            let evm_address = FieldElement::from_byte_slice_be(eoa.address.as_slice()).unwrap();
+            let starknet_address = builder.compute_starknet_address(evm_address).unwrap();
+            let contract = builder.contracts.get(&starknet_address).unwrap();
+            assert_eq!(contract.balance, Some(U256::from(1_000_000u64)));
+        }
+    }
+
+    #[test]
+    fn test_with_fee_token() {
+        // Given
+        let address = ContractAddress::new(FieldElement::from(0x5452u32));
+
+        // When
+        let builder = LOADED_BUILDER
+            .clone()
+            .with_fee_token("Starknet Token", "STRK", 18, Some(address), None)
+            .with_kakarot(FieldElement::ZERO)
+            .unwrap();
+
+        // Then
+        let kakarot_address = ContractAddress::new(builder.cache_load("kakarot_address").unwrap());
+        assert_ne!(kakarot_address.0, GENESIS_BUILDER.cache_load("kakarot_address").unwrap());
+        let kakarot_storage = builder.contracts.get(&kakarot_address).unwrap().storage.clone().unwrap();
+        assert_eq!(kakarot_storage.get(&storage_addr(KAKAROT_NATIVE_TOKEN_ADDRESS).unwrap()), Some(&address.0));
+
+        let genesis = builder.build().unwrap();
+        assert_eq!(genesis.fee_token.name, "Starknet Token");
+        assert_eq!(genesis.fee_token.symbol, "STRK");
+        assert_eq!(genesis.fee_token.address, Some(address));
+    }
+
+    #[test]
+    fn test_artifact_name() {
+        assert_eq!(artifact_name(Path::new("build/kakarot.json")), "kakarot");
+        assert_eq!(
+            artifact_name(Path::new("build/kakarot_AccountContract.contract_class.json")),
+            "kakarot_AccountContract"
+        );
+        assert_eq!(
+            artifact_name(Path::new("build/kakarot_AccountContract.compiled_contract_class.json")),
+            "kakarot_AccountContract"
+        );
+    }
+
+    #[test]
+    fn test_is_compiled_class() {
+        assert!(is_compiled_class(&serde_json::json!({"prime": "0x1", "bytecode": [], "hints": []})));
+        assert!(!is_compiled_class(&serde_json::json!({"sierra_program": [], "entry_points_by_type": {}})));
+        assert!(!is_compiled_class(&serde_json::json!({"program": {}, "entry_points_by_type": {}})));
+    }
+
+    #[test]
+    fn test_with_class_override() {
+        // Given
+        let builder = LOADED_BUILDER.clone();
+        let uninitialized_account_class_hash = builder.uninitialized_account_class_hash().unwrap();
+
+        // When
+        let builder = builder.with_class_override("account_contract", "uninitialized_account").unwrap();
+
+        // Then
+        assert_eq!(builder.account_contract_class_hash().unwrap(), uninitialized_account_class_hash);
+        assert!(LOADED_BUILDER.clone().with_class_override("account_contract", "missing").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        // Given
+        let mut builder = GENESIS_BUILDER.clone().with_eoa(B256::from([1u8; 32])).unwrap();
+        let address = ContractAddress::new(FieldElement::from(0x1234u32));
+        let class_hash = FieldElement::from(0xdeadu32);
+        builder.contracts.insert(
+            address,
+            GenesisContractJson { class: Some(class_hash), balance: None, nonce: None, storage: None },
+        );
+        builder.accounts.insert(
+            address,
+            GenesisAccountJson {
+                public_key: FieldElement::ONE,
+                private_key: None,
+                balance: None,
+                nonce: None,
+                class: None,
+                storage: None,
+            },
+        );
+
+        // When
+        let valid = GENESIS_BUILDER.validate();
+        let invalid = builder.validate();
+
+        // Then
+        assert!(valid.is_valid());
+        let coinbase = GENESIS_BUILDER.compute_starknet_address(FieldElement::ZERO).unwrap();
+        assert_eq!(valid.warnings().collect::<Vec<_>>(), vec![&GenesisDiagnostic::UnfundedCoinbase(coinbase)]);
+        assert!(!invalid.is_valid());
+        assert!(invalid.diagnostics.contains(&GenesisDiagnostic::MissingClass { address, class_hash }));
+        assert!(invalid.diagnostics.contains(&GenesisDiagnostic::AddressCollision(address)));
+        assert_eq!(invalid.errors().count(), 2);
+    }
+
+    #[test]
+    fn test_fixture_round_trip() {
+        // Given
+        let path = std::env::temp_dir().join(format!("kakarot-genesis-{}.fixture.json", std::process::id()));
+        let builder = GENESIS_BUILDER.clone().with_eoa(B256::from([1u8; 32])).unwrap();
+
+        // When
+        builder.to_fixture(&path).unwrap();
+        let reloaded = KatanaGenesisBuilder::from_fixture(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Then
+        assert_eq!(reloaded.class_hashes(), builder.class_hashes());
+        assert_eq!(reloaded.cache(), builder.cache());
+        assert_eq!(reloaded.classes.len(), builder.classes.len());
+        assert_eq!(reloaded.class_paths, builder.class_paths);
+        assert_eq!(
+            serde_json::to_value(reloaded.build().unwrap().contracts).unwrap(),
+            serde_json::to_value(builder.build().unwrap().contracts).unwrap()
+        );
+    }
+}
@@ -5,21 +5,33 @@ use std::path::Path;
 use std::sync::Arc;
 
 use dojo_test_utils::sequencer::{Environment, StarknetConfig, TestSequencer};
+use eyre::{eyre, OptionExt, Result};
+use futures::TryStreamExt;
 use katana_primitives::block::GasPrices;
 use katana_primitives::chain::ChainId;
 use katana_primitives::genesis::json::GenesisJson;
 use katana_primitives::genesis::Genesis;
-use mongodb::bson::doc;
+use mongodb::bson::{doc, Document};
 use mongodb::options::{UpdateModifications, UpdateOptions};
+use starknet::core::types::{
+    BlockId, BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV1, InvokeTransaction, MaybePendingBlockWithTxs,
+    Transaction as StarknetTransaction,
+};
 use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::JsonRpcClient;
+use starknet::providers::{JsonRpcClient, Provider as _};
 
-use crate::eth_provider::database::types::{header::StoredHeader, transaction::StoredTransaction};
+use crate::eth_provider::database::types::{
+    header::StoredHeader, internal_transaction::StoredInternalTransaction, log::StoredLog,
+    transaction::StoredTransaction, transfer::StoredTransfer,
+};
+use crate::eth_provider::database::CollectionName;
 use crate::eth_provider::utils::{format_hex, into_filter};
 use crate::eth_provider::{
     constant::{HASH_HEX_STRING_LEN, U64_HEX_STRING_LEN},
     provider::EthDataProvider,
 };
+use crate::models::internal_transaction::InternalTransaction;
+use crate::models::transfer::TokenTransfer;
 use crate::test_utils::eoa::KakarotEOA;
 
 #[cfg(any(test, feature = "arbitrary", feature = "testing"))]
@@ -27,12 +39,13 @@ use {
     super::mongo::{CollectionDB, MongoFuzzer, StoredData, DOCKER_CLI},
     dojo_test_utils::sequencer::SequencerConfig,
     reth_primitives::{TxType, B256},
-    reth_rpc_types::{Header, Transaction},
+    reth_rpc_types::{Header, Log, Transaction},
     std::str::FromStr as _,
     testcontainers::{Container, GenericImage},
 };
 
-fn load_genesis() -> Genesis {
+/// Loads the genesis generated by `make katana-genesis`.
+pub fn load_genesis() -> Genesis {
     let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(".katana/genesis.json");
     let genesis_json = GenesisJson::load(path).expect("Failed to load genesis.json, run `make katana-genesis`");
     Genesis::try_from(genesis_json).expect("Failed to convert GenesisJson to Genesis")
@@ -41,6 +54,11 @@ fn load_genesis() -> Genesis {
 /// Returns a `StarknetConfig` instance customized for Kakarot.
 /// If `with_dumped_state` is true, the config will be initialized with the dumped state.
 pub fn katana_config() -> StarknetConfig {
+    katana_config_with_genesis(load_genesis())
+}
+
+/// Returns a `StarknetConfig` instance customized for Kakarot, starting from the given genesis.
+pub fn katana_config_with_genesis(genesis: Genesis) -> StarknetConfig {
     let max_steps = std::u32::MAX;
     StarknetConfig {
         disable_fee: true,
@@ -51,15 +69,19 @@ pub fn katana_config() -> StarknetConfig {
             validate_max_steps: max_steps,
             gas_price: GasPrices { eth: 1, strk: 0 },
         },
-        genesis: load_genesis(),
+        genesis,
         ..Default::default()
     }
 }
 
 /// Returns a `TestSequencer` configured for Kakarot.
 #[cfg(any(test, feature = "arbitrary", feature = "testing"))]
-async fn katana_sequencer() -> TestSequencer {
-    TestSequencer::start(SequencerConfig { no_mining: false, block_time: None, messaging: None }, katana_config()).await
+async fn katana_sequencer(genesis: Genesis) -> TestSequencer {
+    TestSequencer::start(
+        SequencerConfig { no_mining: false, block_time: None, messaging: None },
+        katana_config_with_genesis(genesis),
+    )
+    .await
 }
 
 /// Represents the Katana test environment.
@@ -76,21 +98,42 @@ pub struct Katana {
     /// Option to store the Docker container instance.
     /// It holds `Some` when the container is running, and `None` otherwise.
     pub container: Option<Container<'static, GenericImage>>,
+    /// The snapshots taken with [`Katana::snapshot`], indexed by their id.
+    snapshots: Vec<KatanaSnapshot>,
+    /// The genesis of the sequencer, used to restart it on [`Katana::revert_to`].
+    genesis: Genesis,
+}
+
+/// A snapshot of the Katana test environment.
+#[derive(Debug, Clone)]
+struct KatanaSnapshot {
+    /// The latest Starknet block number at the time of the snapshot.
+    block_number: u64,
+    /// The documents of each database collection at the time of the snapshot.
+    collections: HashMap<String, Vec<Document>>,
 }
 
 impl<'a> Katana {
     #[cfg(any(test, feature = "arbitrary", feature = "testing"))]
     pub async fn new(rnd_bytes_size: usize) -> Self {
-        let sequencer = katana_sequencer().await;
+        Self::with_genesis(load_genesis(), rnd_bytes_size).await
+    }
+
+    /// Starts the Katana test environment from the given genesis, e.g. built with the
+    /// [`genesis::KatanaGenesisBuilder`].
+    #[cfg(any(test, feature = "arbitrary", feature = "testing"))]
+    pub async fn with_genesis(genesis: Genesis, rnd_bytes_size: usize) -> Self {
+        let sequencer = katana_sequencer(genesis.clone()).await;
         let starknet_provider = Arc::new(JsonRpcClient::new(HttpTransport::new(sequencer.url())));
 
-        Self::initialize(sequencer, starknet_provider, rnd_bytes_size).await
+        Self::initialize(sequencer, genesis, starknet_provider, rnd_bytes_size).await
     }
 
     /// Initializes the Katana test environment.
     #[cfg(any(test, feature = "arbitrary", feature = "testing"))]
     async fn initialize(
         sequencer: TestSequencer,
+        genesis: Genesis,
         starknet_provider: Arc<JsonRpcClient<HttpTransport>>,
         rnd_bytes_size: usize,
     ) -> Self {
@@ -137,7 +180,7 @@ impl<'a> Katana {
         let eoa = KakarotEOA::new(pk, eth_provider);
 
         // Return a new instance of Katana with initialized fields.
-        Self { sequencer, eoa, mock_data, port, container: Some(container) }
+        Self { sequencer, eoa, mock_data, port, container: Some(container), snapshots: Vec::new(), genesis }
     }
 
     pub fn eth_provider(&self) -> Arc<EthDataProvider<Arc<JsonRpcClient<HttpTransport>>>> {
@@ -153,6 +196,84 @@ impl<'a> Katana {
         &self.sequencer
     }
 
+    /// Takes a snapshot of the chain and database state, which can be restored using
+    /// [`Katana::revert_to`]. Returns the id of the snapshot.
+    pub async fn snapshot(&mut self) -> Result<usize> {
+        let provider = self.eth_provider();
+        let block_number = provider.starknet_provider().block_number().await?;
+
+        let database = provider.database().inner();
+        let mut collections = HashMap::new();
+        for name in database.list_collection_names(None).await? {
+            let documents = database.collection::<Document>(&name).find(None, None).await?.try_collect().await?;
+            collections.insert(name, documents);
+        }
+
+        self.snapshots.push(KatanaSnapshot { block_number, collections });
+        Ok(self.snapshots.len() - 1)
+    }
+
+    /// Reverts the chain and database state to the snapshot with the given id. The snapshot and
+    /// all the snapshots taken after it are discarded.
+    ///
+    /// Katana doesn't support reverting its state, so a new sequencer is started from the same
+    /// genesis and the transactions included up to the snapshot are replayed on it.
+    pub async fn revert_to(&mut self, snapshot_id: usize) -> Result<()> {
+        let snapshot = self.snapshots.get(snapshot_id).cloned().ok_or_eyre("Unknown snapshot id")?;
+        self.snapshots.truncate(snapshot_id);
+
+        // Collect the transactions to replay from the current sequencer.
+        let provider = self.eth_provider();
+        let mut transactions = Vec::new();
+        for block_number in 0..=snapshot.block_number {
+            let block = match provider.starknet_provider().get_block_with_txs(BlockId::Number(block_number)).await? {
+                MaybePendingBlockWithTxs::Block(block) => block,
+                MaybePendingBlockWithTxs::PendingBlock(_) => return Err(eyre!("Block {block_number} is pending")),
+            };
+            for transaction in block.transactions {
+                let StarknetTransaction::Invoke(InvokeTransaction::V1(invoke)) = transaction else {
+                    return Err(eyre!("Only invoke v1 transactions can be replayed"));
+                };
+                transactions.push(BroadcastedInvokeTransaction::V1(BroadcastedInvokeTransactionV1 {
+                    sender_address: invoke.sender_address,
+                    calldata: invoke.calldata,
+                    max_fee: invoke.max_fee,
+                    signature: invoke.signature,
+                    nonce: invoke.nonce,
+                    is_query: false,
+                }));
+            }
+        }
+
+        // Replace the sequencer, dropping the previous one stops it.
+        self.sequencer = katana_sequencer(self.genesis.clone()).await;
+        let starknet_provider = Arc::new(JsonRpcClient::new(HttpTransport::new(self.sequencer.url())));
+        for transaction in transactions {
+            starknet_provider.add_invoke_transaction(transaction).await?;
+        }
+        while starknet_provider.block_number().await? < snapshot.block_number {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        // Restore the database collections.
+        let database = provider.database().clone();
+        for name in database.inner().list_collection_names(None).await? {
+            let collection = database.inner().collection::<Document>(&name);
+            collection.delete_many(doc! {}, None).await?;
+            match snapshot.collections.get(&name) {
+                Some(documents) if !documents.is_empty() => {
+                    collection.insert_many(documents, None).await?;
+                }
+                _ => {}
+            }
+        }
+
+        let eth_provider = Arc::new(EthDataProvider::new(database, starknet_provider).await?);
+        self.eoa = KakarotEOA::new(self.eoa.private_key, eth_provider);
+
+        Ok(())
+    }
+
     /// Adds transactions to the database along with a corresponding header.
     pub async fn add_transactions_with_header_to_database(&self, txs: Vec<Transaction>, header: Header) {
         let provider = self.eth_provider();
@@ -201,6 +322,88 @@ impl<'a> Katana {
             .expect("Failed to update block number");
     }
 
+    /// Adds logs to the database, with their block number padded like the indexed logs.
+    pub async fn add_logs_to_database(&self, logs: Vec<Log>) {
+        let provider = self.eth_provider();
+        let documents = logs
+            .into_iter()
+            .map(|log| {
+                let block_number = log.block_number.expect("Failed to get block number");
+                let mut document = mongodb::bson::to_document(&StoredLog { log }).expect("Failed to serialize log");
+                document
+                    .get_document_mut("log")
+                    .expect("Failed to get log")
+                    .insert("blockNumber", format_hex(block_number, U64_HEX_STRING_LEN));
+                document
+            })
+            .collect::<Vec<_>>();
+
+        provider
+            .database()
+            .inner()
+            .collection::<Document>(StoredLog::collection_name())
+            .insert_many(documents, None)
+            .await
+            .expect("Failed to insert logs");
+    }
+
+    /// Adds the internal transactions to the database, with their numbers padded as written by
+    /// the trace backfill.
+    pub async fn add_internal_transactions_to_database(&self, internal_transactions: Vec<InternalTransaction>) {
+        let provider = self.eth_provider();
+        let documents = internal_transactions
+            .into_iter()
+            .map(|internal_tx| {
+                let numbers = [
+                    ("blockNumber", internal_tx.block_number.to::<u64>()),
+                    ("transactionIndex", internal_tx.transaction_index.to::<u64>()),
+                    ("index", internal_tx.index.to::<u64>()),
+                ];
+                let mut document = mongodb::bson::to_document(&StoredInternalTransaction { internal_tx })
+                    .expect("Failed to serialize internal transaction");
+                let inner = document.get_document_mut("internal_tx").expect("Failed to get internal transaction");
+                for (key, number) in numbers {
+                    inner.insert(key, format_hex(number, U64_HEX_STRING_LEN));
+                }
+                document
+            })
+            .collect::<Vec<_>>();
+
+        provider
+            .database()
+            .inner()
+            .collection::<Document>(StoredInternalTransaction::collection_name())
+            .insert_many(documents, None)
+            .await
+            .expect("Failed to insert internal transactions");
+    }
+
+    /// Adds the token transfers to the database, with their block number and log index padded
+    /// as written by the indexer.
+    pub async fn add_transfers_to_database(&self, transfers: Vec<TokenTransfer>) {
+        let provider = self.eth_provider();
+        let documents = transfers
+            .into_iter()
+            .map(|transfer| {
+                let (block_number, log_index) = (transfer.block_number.to::<u64>(), transfer.log_index.to::<u64>());
+                let mut document =
+                    mongodb::bson::to_document(&StoredTransfer { transfer }).expect("Failed to serialize transfer");
+                let inner = document.get_document_mut("transfer").expect("Failed to get transfer");
+                inner.insert("blockNumber", format_hex(block_number, U64_HEX_STRING_LEN));
+                inner.insert("logIndex", format_hex(log_index, U64_HEX_STRING_LEN));
+                document
+            })
+            .collect::<Vec<_>>();
+
+        provider
+            .database()
+            .inner()
+            .collection::<Document>(StoredTransfer::collection_name())
+            .insert_many(documents, None)
+            .await
+            .expect("Failed to insert transfers");
+    }
+
     /// Retrieves the first stored transaction
     pub fn first_transaction(&self) -> Option<Transaction> {
         self.mock_data
@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod environment;
 pub mod eoa;
 pub mod evm_contract;
 pub mod fixtures;
@@ -6,5 +7,6 @@ pub mod hive;
 pub mod katana;
 pub mod macros;
 pub mod mongo;
+pub mod openrpc;
 pub mod rpc;
 pub mod tx_waiter;
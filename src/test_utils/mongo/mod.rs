@@ -179,6 +179,8 @@ impl MongoFuzzer {
         for collection in CollectionDB::iter() {
             self.update_collection(collection).await;
         }
+        self.mongodb.create_log_indexes().await.expect("Failed to create the log indexes");
+        self.mongodb.create_transaction_indexes().await.expect("Failed to create the transaction indexes");
 
         self.mongodb.clone()
     }
@@ -0,0 +1,99 @@
+use reth_primitives::{Address, B256};
+use serde_json::{json, Value};
+
+use crate::eth_rpc::openrpc::OpenRpcSpec;
+
+/// Default path of the OpenRPC spec of the Ethereum execution APIs, fetched by `make openrpc-spec`.
+const DEFAULT_OPENRPC_SPEC_PATH: &str = ".openrpc/openrpc.json";
+
+/// Prefixes of the methods changing the state, which aren't called by the conformance tests.
+const STATE_CHANGING_PREFIXES: [&str; 2] = ["eth_send", "eth_sign"];
+
+/// Loads the OpenRPC spec at `OPENRPC_SPEC_PATH`, which defaults to the spec fetched by
+/// `make openrpc-spec`.
+pub fn load_openrpc_spec() -> OpenRpcSpec {
+    let path = std::env::var("OPENRPC_SPEC_PATH").unwrap_or_else(|_| DEFAULT_OPENRPC_SPEC_PATH.to_string());
+    OpenRpcSpec::from_file(&path)
+        .unwrap_or_else(|err| panic!("Failed to load the OpenRPC spec, run `make openrpc-spec`: {err}"))
+}
+
+/// Values from which the params of the calls of the conformance tests are generated.
+#[derive(Debug, Clone)]
+pub struct ConformanceParams {
+    pub block_hash: B256,
+    pub transaction_hash: B256,
+    pub address: Address,
+}
+
+impl ConformanceParams {
+    /// Returns the params of a call to the method, generated from the names of its params in the
+    /// spec. The optional params following the first one which can't be generated are left out.
+    /// Returns None if the method changes the state or if one of its required params can't be
+    /// generated, e.g. a filter identifier.
+    pub fn generate(&self, spec: &OpenRpcSpec, method: &str) -> Option<Vec<Value>> {
+        if STATE_CHANGING_PREFIXES.iter().any(|prefix| method.starts_with(prefix)) {
+            return None;
+        }
+
+        let mut params = Vec::new();
+        for param in spec.params(method)? {
+            match self.param(&param.name) {
+                Some(value) => params.push(value),
+                None if param.required => return None,
+                None => break,
+            }
+        }
+        Some(params)
+    }
+
+    /// Returns the value of the param of the given name in the spec.
+    fn param(&self, name: &str) -> Option<Value> {
+        Some(match name.to_lowercase().as_str() {
+            "block" | "newestblock" => json!("latest"),
+            "block hash" => json!(self.block_hash),
+            "transaction hash" => json!(self.transaction_hash),
+            "address" => json!(self.address),
+            "hydrated transactions" => json!(true),
+            "transaction index" | "storage slot" => json!("0x0"),
+            "storagekeys" => json!([]),
+            "blockcount" => json!("0x1"),
+            "rewardpercentiles" => json!([50]),
+            "transaction" => json!({ "from": self.address, "to": self.address, "value": "0x0" }),
+            "filter" => json!({ "fromBlock": "latest", "toBlock": "latest" }),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        // Given
+        let param = |name: &str, required: bool| json!({ "name": name, "required": required, "schema": {} });
+        let spec = OpenRpcSpec::from_value(json!({
+            "methods": [
+                { "name": "eth_getBalance", "params": [param("Address", true), param("Block", false)] },
+                { "name": "eth_feeHistory", "params": [param("blockCount", true), param("unknown", false), param("newestBlock", false)] },
+                { "name": "eth_getFilterLogs", "params": [param("Filter Identifier", true)] },
+                { "name": "eth_sendRawTransaction", "params": [param("Transaction", true)] }
+            ]
+        }))
+        .unwrap();
+        let params = ConformanceParams { block_hash: B256::ZERO, transaction_hash: B256::ZERO, address: Address::ZERO };
+
+        // When
+        let balance = params.generate(&spec, "eth_getBalance");
+        let fee_history = params.generate(&spec, "eth_feeHistory");
+        let filter_logs = params.generate(&spec, "eth_getFilterLogs");
+        let send = params.generate(&spec, "eth_sendRawTransaction");
+
+        // Then
+        assert_eq!(balance, Some(vec![json!(Address::ZERO), json!("latest")]));
+        assert_eq!(fee_history, Some(vec![json!("0x1")]));
+        assert_eq!(filter_logs, None);
+        assert_eq!(send, None);
+    }
+}
@@ -1,9 +1,13 @@
 use super::katana::Katana;
+use crate::eth_provider::starknet::http_pool::HttpPoolConfig;
+use crate::eth_provider::starknet::katana::KatanaDevClient;
 use crate::eth_rpc::config::RPCConfig;
+use crate::eth_rpc::middleware::namespaces::DisabledNamespaces;
 use crate::eth_rpc::rpc::KakarotRpcModuleBuilder;
 use crate::eth_rpc::run_server;
 use jsonrpsee::server::ServerHandle;
 use lazy_static::lazy_static;
+use prometheus::Registry;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::net::SocketAddr;
@@ -74,16 +78,39 @@ async fn get_next_port() -> u16 {
 /// }
 /// ```
 ///
+/// The admin namespace is served without authentication, so that it can be tested.
+///
 /// allow(dead_code) is used because this function is used in tests,
 /// and each test is compiled separately, so the compiler thinks this function is unused
 #[allow(dead_code)]
 pub async fn start_kakarot_rpc_server(katana: &Katana) -> Result<(SocketAddr, ServerHandle), eyre::Report> {
+    start_kakarot_rpc_server_on_port(katana, get_next_port().await).await
+}
+
+/// Starts the Kakarot RPC server on the given port, 0 letting the OS pick a free port.
+/// The returned address holds the port the server listens on.
+#[allow(dead_code)]
+pub async fn start_kakarot_rpc_server_on_port(
+    katana: &Katana,
+    port: u16,
+) -> Result<(SocketAddr, ServerHandle), eyre::Report> {
+    let disabled_namespaces = DisabledNamespaces::default();
     Ok(run_server(
-        KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module()?,
+        KakarotRpcModuleBuilder::new(katana.eth_provider())
+            .with_admin(None, None, disabled_namespaces.clone())
+            .with_dev(KatanaDevClient::new(
+                katana.sequencer().url(),
+                HttpPoolConfig::default().build_client().expect("Failed to build the HTTP client"),
+            ))
+            .rpc_module()?,
         #[cfg(feature = "testing")]
-        RPCConfig::new_test_config_from_port(get_next_port().await),
+        RPCConfig::new_test_config_from_port(port),
         #[cfg(not(feature = "testing"))]
-        RPCConfig::from_port(get_next_port().await),
+        RPCConfig::from_port(port),
+        Registry::new(),
+        disabled_namespaces,
+        false,
+        None,
     )
     .await?)
 }
@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use reth_primitives::{B256, U256};
 use reth_revm::primitives::{BlockEnv, CfgEnv, Env, EnvWithHandlerCfg, HandlerCfg, SpecId};
 use reth_rpc_types::{BlockId, BlockTransactions, Header};
@@ -19,6 +21,8 @@ pub struct TracerBuilder<P: EthereumProvider + Send + Sync, Status = Floating> {
     eth_provider: P,
     env: Env,
     block: Option<reth_rpc_types::Block>,
+    deadline: Option<Instant>,
+    max_response_size: Option<usize>,
     _phantom: std::marker::PhantomData<Status>,
 }
 
@@ -29,7 +33,14 @@ impl<P: EthereumProvider + Send + Sync + Clone> TracerBuilder<P, Floating> {
 
         let env = Env { cfg, ..Default::default() };
 
-        Ok(Self { eth_provider, env, block: None, _phantom: std::marker::PhantomData })
+        Ok(Self {
+            eth_provider,
+            env,
+            block: None,
+            deadline: None,
+            max_response_size: None,
+            _phantom: std::marker::PhantomData,
+        })
     }
 
     /// Sets the block to trace
@@ -40,10 +51,27 @@ impl<P: EthereumProvider + Send + Sync + Clone> TracerBuilder<P, Floating> {
             eth_provider: self.eth_provider.clone(),
             env: self.env.clone(),
             block: maybe_block,
+            deadline: self.deadline,
+            max_response_size: self.max_response_size,
             _phantom: std::marker::PhantomData,
         })
     }
 
+    /// Sets the block to trace to the block containing the given transaction.
+    /// Returns an error if the transaction can't be found or is still pending.
+    pub async fn with_transaction_hash(self, transaction_hash: B256) -> TracerResult<TracerBuilder<P, Pinned>> {
+        let transaction = self
+            .eth_provider
+            .transaction_by_hash(transaction_hash)
+            .await?
+            .ok_or(EthApiError::TransactionNotFound(transaction_hash))?;
+
+        // we can't trace a pending transaction
+        let block_number = transaction.block_number.ok_or(EthApiError::UnknownBlock)?;
+
+        self.with_block_id(BlockId::Number(block_number.into())).await
+    }
+
     /// Fetches a block from the Ethereum provider given a block id
     ///
     /// # Returns
@@ -70,6 +98,28 @@ impl<P: EthereumProvider + Send + Sync + Clone> TracerBuilder<P, Floating> {
 }
 
 impl<P: EthereumProvider + Send + Sync + Clone> TracerBuilder<P, Pinned> {
+    /// Sets the maximum duration of the tracing of the block, starting now.
+    /// The tracing fails once the duration is exceeded.
+    #[must_use]
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Sets the instant after which the tracing of the block fails.
+    #[must_use]
+    pub const fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of the serialized traces of the block. The tracing of the
+    /// whole block fails once the size is exceeded, while a page of traces stops before it.
+    #[must_use]
+    pub const fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = Some(max_response_size);
+        self
+    }
+
     /// Builds the tracer. Returns None if the block was not found during the call to
     /// `with_block_id`.
     pub fn build(self) -> TracerResult<Option<Tracer<P>>> {
@@ -88,7 +138,14 @@ impl<P: EthereumProvider + Send + Sync + Clone> TracerBuilder<P, Pinned> {
         // DB should use the state of the parent block
         let db = EthDatabaseSnapshot::new(self.eth_provider, BlockId::Hash(block.header.parent_hash.into()));
 
-        Ok(Some(Tracer { env, transactions, cfg: KakarotEvmConfig, db }))
+        Ok(Some(Tracer {
+            env,
+            transactions,
+            cfg: KakarotEvmConfig,
+            db,
+            deadline: self.deadline,
+            max_response_size: self.max_response_size,
+        }))
     }
 
     /// Init an EnvWithHandlerCfg.
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mongodb::bson::{doc, DateTime};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+use reth_primitives::{BlockNumberOrTag, B256};
+use reth_revm::tracing::TracingInspectorConfig;
+use reth_rpc_types::trace::geth::{GethDebugTracingOptions, TraceResult};
+use reth_rpc_types::trace::parity::LocalizedTransactionTrace;
+use reth_rpc_types::BlockId;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::time::sleep;
+
+use super::builder::TracerBuilder;
+use crate::eth_provider::constant::{
+    HASH_HEX_STRING_LEN, TRACE_BACKFILL_INTERVAL, TRACE_CACHE_MAX_SIZE, TRACE_CACHE_TTL,
+};
+use crate::eth_provider::database::types::trace::StoredTrace;
+use crate::eth_provider::database::Database;
+use crate::eth_provider::error::{EthApiError, KakarotError};
+use crate::eth_provider::indexer::write_internal_transactions;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
+use crate::eth_provider::utils::into_filter;
+use crate::eth_rpc::shutdown::ShutdownSignal;
+use crate::models::internal_transaction::internal_transactions;
+
+/// Kind of the cached parity traces.
+pub const PARITY_TRACE_KIND: &str = "parity";
+
+/// Returns the kind of the cached Geth traces generated with the given options.
+pub fn geth_trace_kind(opts: &GethDebugTracingOptions) -> String {
+    format!("geth:{}", serde_json::to_string(opts).unwrap_or_default())
+}
+
+/// Cache of the transaction traces, stored in the database. The traces expire `ttl` seconds
+/// after being cached, and the traces larger than `max_size` bytes aren't cached. The cache is
+/// best effort: a failing database read or write is logged and the trace is generated again.
+#[derive(Debug, Clone)]
+pub struct TraceCache {
+    database: Database,
+    ttl: u64,
+    max_size: usize,
+}
+
+impl TraceCache {
+    pub const fn new(database: Database, ttl: u64, max_size: usize) -> Self {
+        Self { database, ttl, max_size }
+    }
+
+    /// Returns the cache configured by the `TRACE_CACHE_TTL` and `TRACE_CACHE_MAX_SIZE`
+    /// environment variables, or None if the cache is disabled.
+    pub fn from_env(database: Database) -> Option<Self> {
+        (*TRACE_CACHE_TTL > 0).then(|| Self::new(database, *TRACE_CACHE_TTL, *TRACE_CACHE_MAX_SIZE))
+    }
+
+    /// Creates the indexes of the traces collection: the traces are queried by transaction hash
+    /// and kind, pruned by block number, and removed by MongoDB once expired. Creating an index which already exists is
+    /// a no-op, but changing the TTL of an existing index fails.
+    pub async fn create_indexes(&self) -> Result<(), KakarotError> {
+        let indexes = [
+            IndexModel::builder()
+                .keys(doc! {"hash": 1, "kind": 1})
+                .options(IndexOptions::builder().name("trace_hash_kind".to_string()).unique(true).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! {"block_number": 1})
+                .options(IndexOptions::builder().name("trace_block_number".to_string()).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! {"created_at": 1})
+                .options(
+                    IndexOptions::builder()
+                        .name("trace_created_at".to_string())
+                        .expire_after(Duration::from_secs(self.ttl))
+                        .build(),
+                )
+                .build(),
+        ];
+        self.database.collection::<StoredTrace>().create_indexes(indexes, None).await?;
+        Ok(())
+    }
+
+    /// Returns the cached trace of the transaction, if it exists and isn't expired.
+    pub async fn get<T: DeserializeOwned>(&self, hash: B256, kind: &str) -> Option<T> {
+        let stored = match self.database.get_one::<StoredTrace>(self.filter(hash, kind), None).await {
+            Ok(stored) => stored?,
+            Err(err) => {
+                tracing::warn!("Failed to read the trace of {hash} from the cache: {err}");
+                return None;
+            }
+        };
+        // MongoDB removes the expired documents periodically, they can still be read in between
+        let age = DateTime::now().timestamp_millis().saturating_sub(stored.created_at.timestamp_millis());
+        if age > (self.ttl as i64).saturating_mul(1000) {
+            return None;
+        }
+        serde_json::from_str(&stored.trace).ok()
+    }
+
+    /// Caches the trace of the transaction included in the given block, unless it is larger than
+    /// the maximum size.
+    pub async fn insert<T: Serialize>(&self, hash: B256, block_number: u64, kind: &str, trace: &T) {
+        let Ok(trace) = serde_json::to_string(trace) else {
+            return;
+        };
+        if trace.len() > self.max_size {
+            return;
+        }
+        let stored = StoredTrace { hash, block_number, kind: kind.to_string(), trace, created_at: DateTime::now() };
+        if let Err(err) = self.database.update_one(stored, self.filter(hash, kind), true).await {
+            tracing::warn!("Failed to write the trace of {hash} to the cache: {err}");
+        }
+    }
+
+    fn filter(&self, hash: B256, kind: &str) -> mongodb::bson::Document {
+        let mut filter = into_filter("hash", &hash, HASH_HEX_STRING_LEN);
+        filter.insert("kind", kind);
+        filter
+    }
+}
+
+/// Traces the transactions of the new blocks every `TRACE_BACKFILL_INTERVAL` seconds, caches
+/// their parity traces and their default Geth traces, and stores the internal transactions
+/// extracted from their parity traces. The backfill starts from the latest block
+/// at the time the service is started: the traces of the older transactions are cached when they
+/// are first requested.
+pub async fn start_trace_backfill_service<P>(eth_provider: P, cache: TraceCache, mut shutdown: ShutdownSignal)
+where
+    P: EthereumProvider + Send + Sync,
+{
+    let mut next_block = None;
+    loop {
+        match eth_provider.block_number().await {
+            Ok(latest) => {
+                let latest = latest.to::<u64>();
+                let mut block_number = next_block.unwrap_or(latest);
+                while block_number <= latest {
+                    if let Err(err) = backfill_block(&eth_provider, &cache, block_number).await {
+                        tracing::error!("Error while backfilling the traces of block {block_number}: {err:?}");
+                        break;
+                    }
+                    block_number += 1;
+                }
+                next_block = Some(block_number);
+            }
+            Err(err) => tracing::error!("Error while backfilling the traces: {err:?}"),
+        }
+
+        tokio::select! {
+            _ = sleep(Duration::from_secs(*TRACE_BACKFILL_INTERVAL)) => {}
+            _ = shutdown.recv() => return,
+        }
+    }
+}
+
+/// Traces the transactions of the block, caches their traces and stores their internal
+/// transactions.
+async fn backfill_block<P>(eth_provider: &P, cache: &TraceCache, block_number: u64) -> EthProviderResult<()>
+where
+    P: EthereumProvider + Send + Sync,
+{
+    let block_id = BlockId::Number(BlockNumberOrTag::Number(block_number));
+    let provider = Arc::new(eth_provider);
+
+    let Some(tracer) = TracerBuilder::new(provider.clone()).await?.with_block_id(block_id).await?.build()? else {
+        return Ok(());
+    };
+    let traces = tracer.trace_block(TracingInspectorConfig::default_parity())?.unwrap_or_default();
+    if traces.is_empty() {
+        return Ok(());
+    }
+    write_internal_transactions(&cache.database, internal_transactions(&traces)).await?;
+    let mut traces_by_transaction: HashMap<B256, Vec<LocalizedTransactionTrace>> = HashMap::new();
+    for trace in traces {
+        if let Some(hash) = trace.transaction_hash {
+            traces_by_transaction.entry(hash).or_default().push(trace);
+        }
+    }
+    for (hash, traces) in &traces_by_transaction {
+        cache.insert(*hash, block_number, PARITY_TRACE_KIND, traces).await;
+    }
+
+    let opts = GethDebugTracingOptions::default();
+    let tracer =
+        TracerBuilder::new(provider).await?.with_block_id(block_id).await?.build()?.ok_or(EthApiError::UnknownBlock)?;
+    let kind = geth_trace_kind(&opts);
+    for result in tracer.debug_block(opts)?.unwrap_or_default() {
+        if let TraceResult::Success { result, tx_hash: Some(hash) } = result {
+            cache.insert(hash, block_number, &kind, &result).await;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_rpc_types::trace::geth::{GethDebugBuiltInTracerType, GethDebugTracerType};
+
+    use super::*;
+
+    #[test]
+    fn test_geth_trace_kind() {
+        // Given
+        let default_opts = GethDebugTracingOptions::default();
+        let call_opts = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer)),
+            ..Default::default()
+        };
+
+        // When
+        let default_kind = geth_trace_kind(&default_opts);
+        let call_kind = geth_trace_kind(&call_opts);
+
+        // Then
+        assert!(default_kind.starts_with("geth:"));
+        assert_ne!(default_kind, call_kind);
+        assert_eq!(default_kind, geth_trace_kind(&GethDebugTracingOptions::default()));
+        assert_ne!(default_kind, PARITY_TRACE_KIND);
+    }
+}
@@ -8,9 +8,9 @@ use reth_primitives::{Address, B256, U256};
 use reth_revm::{
     db::{AccountState, CacheDB, DbAccount},
     primitives::{Account, AccountInfo, Bytecode},
-    Database, DatabaseCommit,
+    Database, DatabaseCommit, DatabaseRef,
 };
-use reth_rpc_types::{serde_helpers::JsonStorageKey, BlockId, BlockNumberOrTag};
+use reth_rpc_types::{serde_helpers::JsonStorageKey, state::StateOverride, BlockId, BlockNumberOrTag};
 use tokio::runtime::Handle;
 
 #[derive(Debug)]
@@ -25,6 +25,56 @@ impl<P: EthereumProvider + Send + Sync> EthDatabaseSnapshot<P> {
     }
 }
 
+impl<P: EthereumProvider + Send + Sync> EthDatabaseSnapshot<P> {
+    /// Applies the state overrides to the snapshot. The overridden storage of an
+    /// account replaces its whole storage, while the storage diff only replaces
+    /// the given slots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a non-async runtime.
+    pub(crate) fn apply_state_overrides(&mut self, state_overrides: StateOverride) -> Result<(), EthApiError> {
+        for (address, account_override) in state_overrides {
+            let mut info = self.basic(address)?.unwrap_or_default();
+            if let Some(balance) = account_override.balance {
+                info.balance = balance;
+            }
+            if let Some(nonce) = account_override.nonce {
+                info.nonce = nonce.to();
+            }
+            if let Some(code) = account_override.code {
+                let bytecode = Bytecode::new_raw(code);
+                info.code_hash = bytecode.hash_slow();
+                info.code = Some(bytecode);
+            }
+            self.cache.insert_account_info(address, info);
+
+            match (account_override.state, account_override.state_diff) {
+                (Some(_), Some(_)) => return Err(EthApiError::InvalidStateOverride(address)),
+                (Some(state), None) => {
+                    let account = self.cache.accounts.entry(address).or_default();
+                    account.storage =
+                        state.into_iter().map(|(slot, value)| (U256::from_be_bytes(slot.0), value)).collect();
+                    account.account_state = AccountState::StorageCleared;
+                }
+                (None, Some(state_diff)) => {
+                    let account = self.cache.accounts.entry(address).or_default();
+                    account
+                        .storage
+                        .extend(state_diff.into_iter().map(|(slot, value)| (U256::from_be_bytes(slot.0), value)));
+                }
+                (None, None) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Overrides the hash of the block with the given number.
+    pub(crate) fn insert_block_hash(&mut self, number: u64, hash: B256) {
+        self.cache.block_hashes.insert(U256::from(number), hash);
+    }
+}
+
 impl<P: EthereumProvider + Send + Sync> Database for EthDatabaseSnapshot<P> {
     type Error = EthApiError;
 
@@ -70,6 +120,10 @@ impl<P: EthereumProvider + Send + Sync> Database for EthDatabaseSnapshot<P> {
             if let Some(storage) = account.storage.get(&index) {
                 return Ok(*storage);
             }
+            // The storage of the account was replaced by a state override
+            if matches!(account.account_state, AccountState::StorageCleared) {
+                return Ok(U256::ZERO);
+            }
         }
 
         let storage = Handle::current().block_on(async {
@@ -114,6 +168,94 @@ impl<P: EthereumProvider + Send + Sync> Database for EthDatabaseSnapshot<P> {
     }
 }
 
+/// Read-only access to the snapshot. Used in order to build the prestate traces,
+/// which require to access the state prior to the execution of a transaction.
+/// Values are read from the cache if present, or fetched from the provider otherwise,
+/// without updating the cache.
+impl<P: EthereumProvider + Send + Sync> DatabaseRef for EthDatabaseSnapshot<P> {
+    type Error = EthApiError;
+
+    /// Returns the account information for the given address.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a non-async runtime.
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(account) = self.cache.accounts.get(&address) {
+            return Ok(Some(account.info.clone()));
+        }
+
+        let account_info = Handle::current().block_on(async {
+            let bytecode = self.cache.db.get_code(address, Some(self.block_id)).await?;
+            let bytecode = Bytecode::new_raw(bytecode);
+            let code_hash = bytecode.hash_slow();
+
+            let nonce = self.cache.db.transaction_count(address, Some(self.block_id)).await?.to();
+            let balance = self.cache.db.balance(address, Some(self.block_id)).await?;
+
+            Result::<_, EthApiError>::Ok(AccountInfo { nonce, balance, code: Some(bytecode), code_hash })
+        })?;
+
+        Ok(Some(account_info))
+    }
+
+    /// Returns the code for the given code hash.
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Ok(self.cache.contracts.get(&code_hash).cloned().unwrap_or_default())
+    }
+
+    /// Returns the storage value for the given address and index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a non-async runtime.
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if let Some(account) = self.cache.accounts.get(&address) {
+            if let Some(storage) = account.storage.get(&index) {
+                return Ok(*storage);
+            }
+            // The storage of the account was replaced by a state override
+            if matches!(account.account_state, AccountState::StorageCleared) {
+                return Ok(U256::ZERO);
+            }
+        }
+
+        let storage = Handle::current().block_on(async {
+            self.cache
+                .db
+                .storage_at(address, JsonStorageKey(B256::from_slice(&index.to_be_bytes::<32>())), Some(self.block_id))
+                .await
+        })?;
+
+        Ok(U256::from_be_bytes(storage.0))
+    }
+
+    /// Returns the block hash for the given block number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a non-async runtime.
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        if let Some(hash) = self.cache.block_hashes.get(&number) {
+            return Ok(*hash);
+        }
+
+        let block_number = number.try_into().map_err(|_| EthereumDataFormatError::PrimitiveError)?;
+        Handle::current().block_on(async {
+            let hash = self
+                .cache
+                .db
+                .block_by_number(BlockNumberOrTag::Number(block_number), false)
+                .await?
+                .ok_or(EthApiError::UnknownBlock)?
+                .header
+                .hash
+                .unwrap_or_default();
+            Result::<_, EthApiError>::Ok(hash)
+        })
+    }
+}
+
 impl<P: EthereumProvider + Send + Sync> DatabaseCommit for EthDatabaseSnapshot<P> {
     fn commit(&mut self, changes: HashMap<Address, Account>) {
         changes.into_iter().for_each(|(address, account)| {
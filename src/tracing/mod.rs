@@ -1,30 +1,46 @@
 pub mod builder;
+pub mod cache;
 mod config;
 mod database;
 
+use std::collections::HashSet;
+use std::time::Instant;
+
 use eyre::eyre;
 use reth_primitives::revm::env::tx_env_with_recovered;
 use reth_primitives::ruint::FromUintError;
-use reth_revm::primitives::{Env, EnvWithHandlerCfg};
+use reth_primitives::{Bytes, B256, U256, U64};
+use reth_revm::access_list::AccessListInspector;
+use reth_revm::inspectors::NoOpInspector;
+use reth_revm::precompile::{PrecompileSpecId, Precompiles};
+use reth_revm::primitives::{
+    BlockEnv, EVMError, Env, EnvWithHandlerCfg, ExecutionResult, HaltReason, TransactTo, TxEnv,
+};
 use reth_revm::tracing::{TracingInspector, TracingInspectorConfig};
-use reth_revm::DatabaseCommit;
-use reth_rpc_types::trace::geth::TraceResult;
+use reth_revm::{Database, DatabaseCommit};
+use reth_rpc_types::trace::geth::{GethTrace, TraceResult};
 use reth_rpc_types::{
+    state::StateOverride,
     trace::{
         geth::{GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingOptions},
-        parity::LocalizedTransactionTrace,
+        parity::{LocalizedTransactionTrace, TraceResults, TraceType},
     },
-    TransactionInfo,
+    AccessListWithGasUsed, BlockOverrides, TransactionInfo, TransactionRequest,
 };
+use serde::Serialize;
 
 use self::config::KakarotEvmConfig;
 use self::database::EthDatabaseSnapshot;
 use crate::{
     eth_provider::{
-        error::{EthApiError, TransactionError},
+        constant::ESTIMATE_GAS_ERROR_RATIO,
+        error::{EthApiError, EvmError, KakarotError, TransactionError},
         provider::EthereumProvider,
     },
-    models::transaction::rpc_to_ec_recovered_transaction,
+    models::{
+        call_bundle::{BundleCall, BundleCallResult},
+        transaction::rpc_to_ec_recovered_transaction,
+    },
 };
 
 pub type TracerResult<T> = Result<T, EthApiError>;
@@ -35,107 +51,411 @@ pub struct Tracer<P: EthereumProvider + Send + Sync> {
     cfg: KakarotEvmConfig,
     env: EnvWithHandlerCfg,
     db: EthDatabaseSnapshot<P>,
+    /// Instant after which the tracing of the block fails.
+    deadline: Option<Instant>,
+    /// Maximum size (in bytes) of the serialized traces of the block.
+    max_response_size: Option<usize>,
 }
 
 impl<P: EthereumProvider + Send + Sync + Clone> Tracer<P> {
+    /// Returns the number of the traced block.
+    pub fn block_number(&self) -> u64 {
+        self.env.env.block.number.try_into().unwrap_or_default()
+    }
+
     /// Trace the block in the parity format.
+    /// Fails if the traces exceed the maximum response size.
     pub fn trace_block(
         self,
         tracing_config: TracingInspectorConfig,
     ) -> TracerResult<Option<Vec<LocalizedTransactionTrace>>> {
-        let transact_to_parity_trace =
-            |cfg: KakarotEvmConfig,
-             env: EnvWithHandlerCfg,
-             db: &mut EthDatabaseSnapshot<P>,
-             tx: &reth_rpc_types::Transaction|
-             -> TracerResult<(Vec<LocalizedTransactionTrace>, reth_revm::primitives::State)> {
-                let block_base_fee = env
-                    .env
-                    .block
-                    .basefee
-                    .try_into()
-                    .map_err(|err: FromUintError<u128>| TransactionError::Tracing(err.into()))?;
-
-                // Set up the inspector and transact the transaction
-                let mut inspector = TracingInspector::new(tracing_config);
-                let mut evm = cfg.evm_with_env_and_inspector(db, env, &mut inspector);
-                let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
-                // we drop the evm to avoid cloning the inspector
-                drop(evm);
+        let max_response_size = self.max_response_size;
+        let (traces, next_transaction) = self.trace_block_page(tracing_config, 0)?;
+        if next_transaction.is_some() {
+            return Err(EthApiError::ResponseTooLarge(max_response_size.unwrap_or_default()));
+        }
 
-                let parity_builder = inspector.into_parity_builder();
+        Ok(Some(traces))
+    }
 
-                let transaction_info = TransactionInfo {
-                    hash: Some(tx.hash),
-                    index: tx.transaction_index,
-                    block_hash: tx.block_hash,
-                    block_number: tx.block_number,
-                    base_fee: Some(block_base_fee),
-                };
+    /// Trace the transactions of the block in the parity format, starting at the transaction
+    /// with the given index, until the traces reach the maximum response size. Returns the index
+    /// of the first transaction which wasn't traced, if any.
+    pub fn trace_block_page(
+        self,
+        tracing_config: TracingInspectorConfig,
+        first_transaction: usize,
+    ) -> TracerResult<(Vec<LocalizedTransactionTrace>, Option<usize>)> {
+        let transact_to_parity_trace = |cfg: KakarotEvmConfig,
+                                        env: EnvWithHandlerCfg,
+                                        db: &mut EthDatabaseSnapshot<P>,
+                                        tx: &reth_rpc_types::Transaction|
+         -> TracerResult<(
+            Vec<LocalizedTransactionTrace>,
+            reth_revm::primitives::State,
+        )> { transact_and_get_parity_trace(cfg, env, db, tx, tracing_config) };
 
-                Ok((parity_builder.into_localized_transaction_traces(transaction_info), res.state))
-            };
+        self.trace_block_in_place(first_transaction, transact_to_parity_trace)
+    }
 
-        let traces = self.trace_block_in_place(transact_to_parity_trace)?;
+    /// Trace the transaction in the parity format.
+    /// Replays all the previous transactions of the block before tracing the transaction.
+    pub fn trace_transaction(
+        self,
+        transaction_hash: B256,
+        tracing_config: TracingInspectorConfig,
+    ) -> TracerResult<Option<Vec<LocalizedTransactionTrace>>> {
+        let transact_to_parity_trace = |cfg: KakarotEvmConfig,
+                                        env: EnvWithHandlerCfg,
+                                        db: &mut EthDatabaseSnapshot<P>,
+                                        tx: &reth_rpc_types::Transaction|
+         -> TracerResult<(
+            Vec<LocalizedTransactionTrace>,
+            reth_revm::primitives::State,
+        )> { transact_and_get_parity_trace(cfg, env, db, tx, tracing_config) };
 
-        Ok(Some(traces))
+        self.trace_transaction_in_place(transaction_hash, transact_to_parity_trace)
+    }
+
+    /// Replays the transaction and returns the requested parity trace types.
+    /// Replays all the previous transactions of the block before tracing the transaction.
+    pub fn replay_transaction(
+        self,
+        transaction_hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> TracerResult<Option<TraceResults>> {
+        let transact_to_trace_results = |cfg: KakarotEvmConfig,
+                                         env: EnvWithHandlerCfg,
+                                         db: &mut EthDatabaseSnapshot<P>,
+                                         _tx: &reth_rpc_types::Transaction|
+         -> TracerResult<(TraceResults, reth_revm::primitives::State)> {
+            let mut inspector = TracingInspector::new(TracingInspectorConfig::from_parity_config(&trace_types));
+            let mut evm = cfg.evm_with_env_and_inspector(&mut *db, env, &mut inspector);
+
+            let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+            // we drop the evm to avoid cloning the inspector
+            drop(evm);
+            // The changes of the transaction aren't committed yet, so the database
+            // still holds the state prior to the transaction, which is needed for the state diff.
+            let trace_results =
+                inspector.into_parity_builder().into_trace_results_with_state(&res, &trace_types, &*db)?;
+            Ok((trace_results, res.state))
+        };
+
+        self.trace_transaction_in_place(transaction_hash, transact_to_trace_results)
     }
 
     /// Returns the debug trace in the Geth.
-    /// Currently only supports the call tracer or the default tracer.
+    /// Currently only supports the call tracer, the prestate tracer or the default tracer.
+    /// Fails if the traces exceed the maximum response size.
     pub fn debug_block(self, opts: GethDebugTracingOptions) -> TracerResult<Option<Vec<TraceResult>>> {
+        let max_response_size = self.max_response_size;
+        let (traces, next_transaction) = self.debug_block_page(opts, 0)?;
+        if next_transaction.is_some() {
+            return Err(EthApiError::ResponseTooLarge(max_response_size.unwrap_or_default()));
+        }
+
+        Ok(Some(traces))
+    }
+
+    /// Returns the debug traces in the Geth format of the transactions of the block, starting at
+    /// the transaction with the given index, until the traces reach the maximum response size.
+    /// Returns the index of the first transaction which wasn't traced, if any.
+    pub fn debug_block_page(
+        self,
+        opts: GethDebugTracingOptions,
+        first_transaction: usize,
+    ) -> TracerResult<(Vec<TraceResult>, Option<usize>)> {
         let transact_to_geth_trace = |cfg: KakarotEvmConfig,
                                       env: EnvWithHandlerCfg,
                                       db: &mut EthDatabaseSnapshot<P>,
                                       tx: &reth_rpc_types::Transaction|
          -> TracerResult<(Vec<TraceResult>, reth_revm::primitives::State)> {
-            let GethDebugTracingOptions { tracer_config, config, tracer, .. } = opts.clone();
-
-            if let Some(tracer) = tracer {
-                return match tracer {
-                    GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer) => {
-                        let call_config = tracer_config
-                            .clone()
-                            .into_call_config()
-                            .map_err(|err| EthApiError::Transaction(TransactionError::Tracing(err.into())))?;
-                        let mut inspector =
-                            TracingInspector::new(TracingInspectorConfig::from_geth_call_config(&call_config));
-                        let mut evm = cfg.evm_with_env_and_inspector(db, env, &mut inspector);
-
-                        let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
-                        // we drop the evm to avoid cloning the inspector
-                        drop(evm);
-                        let call_frame = inspector.into_geth_builder().geth_call_traces(
-                            tracer_config.into_call_config().map_err(|err| TransactionError::Tracing(err.into()))?,
-                            res.result.gas_used(),
-                        );
-                        Ok((
-                            vec![TraceResult::Success { result: call_frame.into(), tx_hash: Some(tx.hash) }],
-                            res.state,
-                        ))
-                    }
-                    _ => Err(EthApiError::Transaction(TransactionError::Tracing(
-                        eyre!("only call tracer is currently supported").into(),
-                    ))),
-                };
+            let (trace, state) = transact_and_get_geth_trace(cfg, env, db, opts.clone())?;
+            Ok((vec![TraceResult::Success { result: trace, tx_hash: Some(tx.hash) }], state))
+        };
+
+        self.trace_block_in_place(first_transaction, transact_to_geth_trace)
+    }
+
+    /// Returns the debug trace in the Geth format for the given transaction.
+    /// Replays all the previous transactions of the block before tracing the transaction.
+    pub fn debug_transaction(
+        self,
+        transaction_hash: B256,
+        opts: GethDebugTracingOptions,
+    ) -> TracerResult<Option<GethTrace>> {
+        let transact_to_geth_trace = |cfg: KakarotEvmConfig,
+                                      env: EnvWithHandlerCfg,
+                                      db: &mut EthDatabaseSnapshot<P>,
+                                      _tx: &reth_rpc_types::Transaction|
+         -> TracerResult<(GethTrace, reth_revm::primitives::State)> {
+            transact_and_get_geth_trace(cfg, env, db, opts.clone())
+        };
+
+        self.trace_transaction_in_place(transaction_hash, transact_to_geth_trace)
+    }
+
+    /// Creates an [EIP-2930](https://eips.ethereum.org/EIPS/eip-2930) access list for the request,
+    /// along with the gas used by the request once the access list is included. All the transactions
+    /// of the block are replayed before simulating the request, in order to simulate the request on
+    /// top of the state of the block.
+    pub fn create_access_list(self, request: TransactionRequest) -> TracerResult<AccessListWithGasUsed> {
+        tokio::task::block_in_place(move || {
+            let mut db = self.db;
+            replay_transactions(&self.cfg, &self.env, &self.transactions, &mut db)?;
+
+            // The request is simulated as a call: the base fee is ignored if no gas price is provided.
+            let mut block_env = self.env.env.block.clone();
+            if request.gas_price.is_none() && request.max_fee_per_gas.is_none() {
+                block_env.basefee = U256::ZERO;
             }
+            let initial_access_list = request.access_list.clone().unwrap_or_default();
+            let mut tx_env = tx_env_from_request(request, block_env.gas_limit);
 
-            // default tracer
-            let mut inspector = TracingInspector::new(TracingInspectorConfig::from_geth_config(&config));
-            let mut evm = cfg.evm_with_env_and_inspector(db, env, &mut inspector);
+            let from = tx_env.caller;
+            let to = match tx_env.transact_to {
+                TransactTo::Call(to) => to,
+                TransactTo::Create(_) => {
+                    // The nonce of the sender determines the address of the created contract.
+                    let nonce = db.basic(from)?.unwrap_or_default().nonce;
+                    from.create(nonce)
+                }
+            };
+            let precompiles = Precompiles::new(PrecompileSpecId::from_spec_id(self.env.handler_cfg.spec_id));
+            let mut inspector =
+                AccessListInspector::new(initial_access_list, from, to, precompiles.addresses().copied());
 
-            let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
-            // we drop the evm to avoid cloning the inspector
+            let env = EnvWithHandlerCfg {
+                env: Env::boxed(self.env.env.cfg.clone(), block_env.clone(), tx_env.clone()),
+                handler_cfg: self.env.handler_cfg,
+            };
+            let mut evm = self.cfg.evm_with_env_and_inspector(&mut db, env, &mut inspector);
+            evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
             drop(evm);
-            let gas_used = res.result.gas_used();
-            let return_value = res.result.into_output().unwrap_or_default();
-            let frame = inspector.into_geth_builder().geth_traces(gas_used, return_value, config);
-            Ok((vec![TraceResult::Success { result: frame.into(), tx_hash: Some(tx.hash) }], res.state))
+            let access_list = inspector.into_access_list();
+
+            // Execute the request again with the access list in order to compute the gas used.
+            tx_env.access_list = access_list
+                .0
+                .iter()
+                .map(|item| (item.address, item.storage_keys.iter().map(|key| U256::from_be_bytes(key.0)).collect()))
+                .collect();
+            let env = EnvWithHandlerCfg {
+                env: Env::boxed(self.env.env.cfg.clone(), block_env, tx_env),
+                handler_cfg: self.env.handler_cfg,
+            };
+            let mut evm = self.cfg.evm_with_env_and_inspector(&mut db, env, NoOpInspector);
+            let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+
+            TracerResult::Ok(AccessListWithGasUsed { access_list, gas_used: U256::from(res.result.gas_used()) })
+        })
+    }
+
+    /// Executes the request as a call on top of the state of the block, once the state overrides
+    /// and the block overrides are applied. Returns the output of the call.
+    pub fn call(
+        self,
+        request: TransactionRequest,
+        state_overrides: StateOverride,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> TracerResult<Bytes> {
+        tokio::task::block_in_place(move || {
+            let mut db = self.db;
+            replay_transactions(&self.cfg, &self.env, &self.transactions, &mut db)?;
+            db.apply_state_overrides(state_overrides)?;
+
+            let mut block_env = self.env.env.block.clone();
+            if let Some(block_overrides) = block_overrides {
+                apply_block_overrides(*block_overrides, &mut block_env, &mut db);
+            }
+            // The request is simulated as a call: the base fee is ignored if no gas price is provided.
+            if request.gas_price.is_none() && request.max_fee_per_gas.is_none() {
+                block_env.basefee = U256::ZERO;
+            }
+            let tx_env = tx_env_from_request(request, block_env.gas_limit);
+
+            let env = EnvWithHandlerCfg {
+                env: Env::boxed(self.env.env.cfg.clone(), block_env, tx_env),
+                handler_cfg: self.env.handler_cfg,
+            };
+            let mut evm = self.cfg.evm_with_env_and_inspector(&mut db, env, NoOpInspector);
+            let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+
+            match res.result {
+                ExecutionResult::Success { output, .. } => Ok(output.into_data()),
+                result => Err(execution_error(result)),
+            }
+        })
+    }
+
+    /// Estimates the gas limit required by the request, once the state overrides are applied
+    /// on top of the state of the block. The estimate is found by binary searching the lowest
+    /// gas limit for which the execution of the request succeeds.
+    pub fn estimate_gas(self, request: TransactionRequest, state_overrides: StateOverride) -> TracerResult<U256> {
+        tokio::task::block_in_place(move || {
+            let mut db = self.db;
+            replay_transactions(&self.cfg, &self.env, &self.transactions, &mut db)?;
+            db.apply_state_overrides(state_overrides)?;
+
+            // The request is simulated as a call: the base fee is ignored if no gas price is provided.
+            let mut block_env = self.env.env.block.clone();
+            if request.gas_price.is_none() && request.max_fee_per_gas.is_none() {
+                block_env.basefee = U256::ZERO;
+            }
+            let tx_env = tx_env_from_request(request, block_env.gas_limit);
+
+            let mut transact = |gas_limit: u64| {
+                let env = EnvWithHandlerCfg {
+                    env: Env::boxed(self.env.env.cfg.clone(), block_env.clone(), TxEnv { gas_limit, ..tx_env.clone() }),
+                    handler_cfg: self.env.handler_cfg,
+                };
+                let mut evm = self.cfg.evm_with_env_and_inspector(&mut db, env, NoOpInspector);
+                evm.transact().map_err(|err| EthApiError::from(TransactionError::Tracing(err.into())))
+            };
+
+            // The execution with the gas limit of the request is expected to succeed, otherwise its error is returned.
+            let mut highest = tx_env.gas_limit;
+            let res = transact(highest)?;
+            let gas_used = match res.result {
+                ExecutionResult::Success { gas_used, .. } => gas_used,
+                result => return Err(execution_error(result)),
+            };
+
+            // The gas used is a lower bound of the gas limit: the refunds and
+            // the 63/64 rule can make the execution fail with a gas limit equal to the gas used.
+            let mut lowest = gas_used;
+            if transact(lowest).is_ok_and(|res| res.result.is_success()) {
+                return Ok(U256::from(lowest));
+            }
+
+            // Binary search the lowest gas limit for which the execution succeeds,
+            // stopping once the estimate is within the error ratio.
+            while lowest < highest && (highest - lowest) as f64 / highest as f64 > ESTIMATE_GAS_ERROR_RATIO {
+                let mid = lowest + (highest - lowest) / 2;
+                match transact(mid) {
+                    Ok(res) if res.result.is_success() => highest = mid,
+                    _ => lowest = mid + 1,
+                }
+            }
+
+            TracerResult::Ok(U256::from(highest))
+        })
+    }
+
+    /// Simulates the calls of the bundle in sequence on top of the state of the block, once the
+    /// block overrides are applied. Each call is executed on the state left by the previous calls,
+    /// once its own state overrides are applied. A failing call doesn't interrupt the bundle: its
+    /// failure is reported in its result and its changes are discarded.
+    pub fn call_many(
+        self,
+        calls: Vec<BundleCall>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> TracerResult<Vec<BundleCallResult>> {
+        let transact_to_call_result = |cfg: KakarotEvmConfig,
+                                       env: EnvWithHandlerCfg,
+                                       db: &mut EthDatabaseSnapshot<P>|
+         -> TracerResult<(BundleCallResult, reth_revm::primitives::State)> {
+            let mut evm = cfg.evm_with_env_and_inspector(db, env, NoOpInspector);
+            let res = match evm.transact() {
+                Ok(res) => res,
+                // The transaction is invalid and isn't executed, e.g. the sender can't pay for it
+                Err(EVMError::Transaction(err)) => {
+                    let result = BundleCallResult { error: Some(err.to_string()), ..Default::default() };
+                    return Ok((result, Default::default()));
+                }
+                Err(err) => return Err(TransactionError::Tracing(err.into()).into()),
+            };
+
+            let gas_used = U64::from(res.result.gas_used());
+            let result = match res.result {
+                ExecutionResult::Success { output, .. } => {
+                    BundleCallResult { value: Some(output.into_data()), error: None, gas_used }
+                }
+                ExecutionResult::Revert { output, .. } => {
+                    BundleCallResult { value: Some(output), error: Some("execution reverted".to_string()), gas_used }
+                }
+                ExecutionResult::Halt { reason, .. } => {
+                    BundleCallResult { value: None, error: Some(format!("{reason:?}")), gas_used }
+                }
+            };
+            Ok((result, res.state))
         };
 
-        let traces = self.trace_block_in_place(transact_to_geth_trace)?;
+        self.simulate_bundle_in_place(calls, block_overrides, transact_to_call_result)
+    }
 
-        Ok(Some(traces))
+    /// Returns the debug traces in the Geth format of the calls of the bundle, simulated in
+    /// sequence on top of the state of the block. Each call is executed on the state left by the
+    /// previous calls, once its own state overrides are applied.
+    pub fn debug_call_many(
+        self,
+        calls: Vec<BundleCall>,
+        block_overrides: Option<Box<BlockOverrides>>,
+        opts: GethDebugTracingOptions,
+    ) -> TracerResult<Vec<GethTrace>> {
+        let transact_to_geth_trace = |cfg: KakarotEvmConfig,
+                                      env: EnvWithHandlerCfg,
+                                      db: &mut EthDatabaseSnapshot<P>|
+         -> TracerResult<(GethTrace, reth_revm::primitives::State)> {
+            transact_and_get_geth_trace(cfg, env, db, opts.clone())
+        };
+
+        self.simulate_bundle_in_place(calls, block_overrides, transact_to_geth_trace)
+    }
+
+    /// Simulates the calls of a bundle using tokio::task::block_in_place, on a single snapshot of
+    /// the state of the block. The transactions of the block are replayed first, then each call
+    /// is executed with the `transact` closure and its changes are committed to the database.
+    fn simulate_bundle_in_place<T, F>(
+        self,
+        calls: Vec<BundleCall>,
+        block_overrides: Option<Box<BlockOverrides>>,
+        transact: F,
+    ) -> TracerResult<Vec<T>>
+    where
+        F: Fn(
+            KakarotEvmConfig,
+            EnvWithHandlerCfg,
+            &mut EthDatabaseSnapshot<P>,
+        ) -> TracerResult<(T, reth_revm::primitives::State)>,
+    {
+        tokio::task::block_in_place(move || {
+            let mut db = self.db;
+            replay_transactions(&self.cfg, &self.env, &self.transactions, &mut db)?;
+
+            let mut block_env = self.env.env.block.clone();
+            if let Some(block_overrides) = block_overrides {
+                apply_block_overrides(*block_overrides, &mut block_env, &mut db);
+            }
+
+            let mut results = Vec::with_capacity(calls.len());
+            for BundleCall { transaction, state_overrides } in calls {
+                if self.deadline.is_some_and(|deadline| Instant::now() > deadline) {
+                    return Err(EthApiError::TracingTimeout);
+                }
+                if let Some(state_overrides) = state_overrides {
+                    db.apply_state_overrides(state_overrides)?;
+                }
+
+                // The call is simulated as a call: the base fee is ignored if no gas price is provided.
+                let mut call_block_env = block_env.clone();
+                if transaction.gas_price.is_none() && transaction.max_fee_per_gas.is_none() {
+                    call_block_env.basefee = U256::ZERO;
+                }
+                let tx_env = tx_env_from_request(transaction, call_block_env.gas_limit);
+                let env = EnvWithHandlerCfg {
+                    env: Env::boxed(self.env.env.cfg.clone(), call_block_env, tx_env),
+                    handler_cfg: self.env.handler_cfg,
+                };
+
+                let (result, state_changes) = transact(self.cfg.clone(), env, &mut db)?;
+                db.commit(state_changes);
+                results.push(result);
+            }
+
+            TracerResult::Ok(results)
+        })
     }
 
     /// Traces a block using tokio::task::block_in_place. This is needed in order to enter a blocking context
@@ -143,8 +463,17 @@ impl<P: EthereumProvider + Send + Sync + Clone> Tracer<P> {
     /// `Handle::current().block_on(async { ... })`
     /// The function `transact_and_get_traces` closure uses the `cfg`, `env` and `db` to create an evm
     /// which is then used to transact and trace the transaction.
-    fn trace_block_in_place<T, F>(self, transact_and_get_traces: F) -> TracerResult<Vec<T>>
+    ///
+    /// The transactions preceding `first_transaction` are executed without tracing. The tracing
+    /// stops before the transaction whose traces would exceed the maximum response size, whose
+    /// index is returned. Fails if the traces of the first traced transaction exceed it alone.
+    fn trace_block_in_place<T, F>(
+        self,
+        first_transaction: usize,
+        transact_and_get_traces: F,
+    ) -> TracerResult<(Vec<T>, Option<usize>)>
     where
+        T: Serialize,
         F: Fn(
             KakarotEvmConfig,
             EnvWithHandlerCfg,
@@ -153,11 +482,19 @@ impl<P: EthereumProvider + Send + Sync + Clone> Tracer<P> {
         ) -> TracerResult<(Vec<T>, reth_revm::primitives::State)>,
     {
         tokio::task::block_in_place(move || {
-            let mut traces = Vec::with_capacity(self.transactions.len());
-            let mut transactions = self.transactions.iter().peekable();
+            let mut traces = Vec::with_capacity(self.transactions.len().saturating_sub(first_transaction));
+            let mut traces_size = 0;
             let mut db = self.db;
 
-            while let Some(tx) = transactions.next() {
+            let (skipped, traced) = self.transactions.split_at(first_transaction.min(self.transactions.len()));
+            replay_transactions(&self.cfg, &self.env, skipped, &mut db)?;
+
+            let mut transactions = traced.iter().enumerate().peekable();
+            while let Some((index, tx)) = transactions.next() {
+                if self.deadline.is_some_and(|deadline| Instant::now() > deadline) {
+                    return Err(EthApiError::TracingTimeout);
+                }
+
                 // Convert the transaction to an ec recovered transaction and update the env with it
                 let tx_ec_recovered = rpc_to_ec_recovered_transaction(tx.clone())?;
                 let tx_env = tx_env_with_recovered(&tx_ec_recovered);
@@ -167,6 +504,16 @@ impl<P: EthereumProvider + Send + Sync + Clone> Tracer<P> {
                 };
 
                 let (res, state_changes) = transact_and_get_traces(self.cfg.clone(), env, &mut db, tx)?;
+
+                if let Some(max_response_size) = self.max_response_size {
+                    traces_size += serialized_size(&res);
+                    if traces_size > max_response_size {
+                        if index == 0 {
+                            return Err(EthApiError::ResponseTooLarge(max_response_size));
+                        }
+                        return Ok((traces, Some(first_transaction + index)));
+                    }
+                }
                 traces.extend(res);
 
                 // Only commit to the database if there are more transactions to process.
@@ -175,7 +522,267 @@ impl<P: EthereumProvider + Send + Sync + Clone> Tracer<P> {
                 }
             }
 
-            TracerResult::Ok(traces)
+            TracerResult::Ok((traces, None))
+        })
+    }
+
+    /// Traces a single transaction of the block using tokio::task::block_in_place. All the
+    /// transactions preceding the traced transaction in the block are executed without tracing
+    /// and committed to the database, in order to trace the transaction on the correct state.
+    ///
+    /// Returns None if the transaction isn't part of the block.
+    fn trace_transaction_in_place<T, F>(
+        self,
+        transaction_hash: B256,
+        transact_and_get_trace: F,
+    ) -> TracerResult<Option<T>>
+    where
+        F: Fn(
+            KakarotEvmConfig,
+            EnvWithHandlerCfg,
+            &mut EthDatabaseSnapshot<P>,
+            &reth_rpc_types::Transaction,
+        ) -> TracerResult<(T, reth_revm::primitives::State)>,
+    {
+        tokio::task::block_in_place(move || {
+            let mut db = self.db;
+
+            for tx in &self.transactions {
+                // Convert the transaction to an ec recovered transaction and update the env with it
+                let tx_ec_recovered = rpc_to_ec_recovered_transaction(tx.clone())?;
+                let tx_env = tx_env_with_recovered(&tx_ec_recovered);
+                let env = EnvWithHandlerCfg {
+                    env: Env::boxed(self.env.env.cfg.clone(), self.env.env.block.clone(), tx_env),
+                    handler_cfg: self.env.handler_cfg,
+                };
+
+                if tx.hash == transaction_hash {
+                    let (res, _) = transact_and_get_trace(self.cfg.clone(), env, &mut db, tx)?;
+                    return Ok(Some(res));
+                }
+
+                // Execute the transaction without tracing and commit the changes to the database.
+                let mut evm = self.cfg.evm_with_env_and_inspector(&mut db, env, NoOpInspector);
+                let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+                drop(evm);
+                db.commit(res.state);
+            }
+
+            TracerResult::Ok(None)
         })
     }
 }
+
+/// Replays the transactions without tracing and commits the changes to the database.
+fn replay_transactions<P: EthereumProvider + Send + Sync>(
+    cfg: &KakarotEvmConfig,
+    env: &EnvWithHandlerCfg,
+    transactions: &[reth_rpc_types::Transaction],
+    db: &mut EthDatabaseSnapshot<P>,
+) -> TracerResult<()> {
+    for tx in transactions {
+        let tx_ec_recovered = rpc_to_ec_recovered_transaction(tx.clone())?;
+        let tx_env = tx_env_with_recovered(&tx_ec_recovered);
+        let env = EnvWithHandlerCfg {
+            env: Env::boxed(env.env.cfg.clone(), env.env.block.clone(), tx_env),
+            handler_cfg: env.handler_cfg,
+        };
+
+        let mut evm = cfg.evm_with_env_and_inspector(&mut *db, env, NoOpInspector);
+        let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+        drop(evm);
+        db.commit(res.state);
+    }
+    Ok(())
+}
+
+/// Returns the size (in bytes) of the value serialized as JSON, without allocating it.
+fn serialized_size<T: Serialize>(value: &T) -> usize {
+    struct ByteCounter(usize);
+
+    impl std::io::Write for ByteCounter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = ByteCounter(0);
+    // Writing to the counter never fails
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
+/// Applies the block overrides to the block environment. The overridden
+/// block hashes are inserted in the database.
+fn apply_block_overrides<P: EthereumProvider + Send + Sync>(
+    block_overrides: BlockOverrides,
+    block_env: &mut BlockEnv,
+    db: &mut EthDatabaseSnapshot<P>,
+) {
+    let BlockOverrides { number, difficulty, time, gas_limit, coinbase, random, base_fee, block_hash } =
+        block_overrides;
+
+    if let Some(number) = number {
+        block_env.number = number;
+    }
+    if let Some(difficulty) = difficulty {
+        block_env.difficulty = difficulty;
+    }
+    if let Some(time) = time {
+        block_env.timestamp = U256::from(time);
+    }
+    if let Some(gas_limit) = gas_limit {
+        block_env.gas_limit = U256::from(gas_limit);
+    }
+    if let Some(coinbase) = coinbase {
+        block_env.coinbase = coinbase;
+    }
+    if let Some(random) = random {
+        block_env.prevrandao = Some(random);
+    }
+    if let Some(base_fee) = base_fee {
+        block_env.basefee = base_fee;
+    }
+    for (number, hash) in block_hash.unwrap_or_default() {
+        db.insert_block_hash(number, hash);
+    }
+}
+
+/// Converts the result of a failed execution into an error.
+fn execution_error(result: ExecutionResult) -> EthApiError {
+    let error = match result {
+        ExecutionResult::Halt { reason: HaltReason::OutOfGas(_), .. } => EvmError::OutOfGas,
+        ExecutionResult::Revert { output, .. } => EvmError::Revert(output),
+        ExecutionResult::Halt { reason, .. } => EvmError::Other(format!("{reason:?}")),
+        ExecutionResult::Success { .. } => EvmError::Other("execution succeeded".to_string()),
+    };
+    KakarotError::from(error).into()
+}
+
+/// Converts a transaction request into a transaction environment. The gas limit of the
+/// request is capped to the block gas limit.
+fn tx_env_from_request(request: TransactionRequest, block_gas_limit: U256) -> TxEnv {
+    let block_gas_limit: u64 = block_gas_limit.saturating_to();
+    let gas_limit = request.gas.map_or(block_gas_limit, |gas| gas.try_into().unwrap_or(u64::MAX).min(block_gas_limit));
+
+    TxEnv {
+        caller: request.from.unwrap_or_default(),
+        gas_limit,
+        gas_price: U256::from(request.gas_price.or(request.max_fee_per_gas).unwrap_or_default()),
+        gas_priority_fee: request.max_priority_fee_per_gas.map(U256::from),
+        transact_to: request.to.map_or_else(TransactTo::create, TransactTo::Call),
+        value: request.value.unwrap_or_default(),
+        data: request.input.into_input().unwrap_or_default(),
+        nonce: request.nonce,
+        chain_id: request.chain_id,
+        access_list: request
+            .access_list
+            .map(|access_list| {
+                access_list
+                    .0
+                    .into_iter()
+                    .map(|item| {
+                        (item.address, item.storage_keys.into_iter().map(|key| U256::from_be_bytes(key.0)).collect())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Transacts the transaction described by the environment and returns its traces in the parity format.
+fn transact_and_get_parity_trace<P: EthereumProvider + Send + Sync>(
+    cfg: KakarotEvmConfig,
+    env: EnvWithHandlerCfg,
+    db: &mut EthDatabaseSnapshot<P>,
+    tx: &reth_rpc_types::Transaction,
+    tracing_config: TracingInspectorConfig,
+) -> TracerResult<(Vec<LocalizedTransactionTrace>, reth_revm::primitives::State)> {
+    let block_base_fee =
+        env.env.block.basefee.try_into().map_err(|err: FromUintError<u128>| TransactionError::Tracing(err.into()))?;
+
+    // Set up the inspector and transact the transaction
+    let mut inspector = TracingInspector::new(tracing_config);
+    let mut evm = cfg.evm_with_env_and_inspector(db, env, &mut inspector);
+    let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+    // we drop the evm to avoid cloning the inspector
+    drop(evm);
+
+    let parity_builder = inspector.into_parity_builder();
+
+    let transaction_info = TransactionInfo {
+        hash: Some(tx.hash),
+        index: tx.transaction_index,
+        block_hash: tx.block_hash,
+        block_number: tx.block_number,
+        base_fee: Some(block_base_fee),
+    };
+
+    Ok((parity_builder.into_localized_transaction_traces(transaction_info), res.state))
+}
+
+/// Transacts the transaction described by the environment and returns its trace in the Geth
+/// format. Supports the call tracer, the prestate tracer and the default struct logger.
+fn transact_and_get_geth_trace<P: EthereumProvider + Send + Sync>(
+    cfg: KakarotEvmConfig,
+    env: EnvWithHandlerCfg,
+    db: &mut EthDatabaseSnapshot<P>,
+    opts: GethDebugTracingOptions,
+) -> TracerResult<(GethTrace, reth_revm::primitives::State)> {
+    let GethDebugTracingOptions { tracer_config, config, tracer, .. } = opts;
+
+    if let Some(tracer) = tracer {
+        return match tracer {
+            GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer) => {
+                let call_config = tracer_config
+                    .into_call_config()
+                    .map_err(|err| EthApiError::Transaction(TransactionError::Tracing(err.into())))?;
+                let mut inspector = TracingInspector::new(TracingInspectorConfig::from_geth_call_config(&call_config));
+                let mut evm = cfg.evm_with_env_and_inspector(db, env, &mut inspector);
+
+                let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+                // we drop the evm to avoid cloning the inspector
+                drop(evm);
+                let call_frame = inspector.into_geth_builder().geth_call_traces(call_config, res.result.gas_used());
+                Ok((call_frame.into(), res.state))
+            }
+            GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer) => {
+                let prestate_config = tracer_config
+                    .into_pre_state_config()
+                    .map_err(|err| EthApiError::Transaction(TransactionError::Tracing(err.into())))?;
+                let mut inspector =
+                    TracingInspector::new(TracingInspectorConfig::from_geth_prestate_config(&prestate_config));
+                let mut evm = cfg.evm_with_env_and_inspector(&mut *db, env, &mut inspector);
+
+                let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+                // we drop the evm to avoid cloning the inspector
+                drop(evm);
+                // The changes of the transaction aren't committed yet, so the database
+                // still holds the state prior to the transaction.
+                let prestate_frame = inspector.into_geth_builder().geth_prestate_traces(&res, prestate_config, &*db)?;
+                Ok((prestate_frame.into(), res.state))
+            }
+            _ => Err(EthApiError::Transaction(TransactionError::Tracing(
+                eyre!("only call and prestate tracers are currently supported").into(),
+            ))),
+        };
+    }
+
+    // default tracer
+    let mut inspector = TracingInspector::new(TracingInspectorConfig::from_geth_config(&config));
+    let mut evm = cfg.evm_with_env_and_inspector(db, env, &mut inspector);
+
+    let res = evm.transact().map_err(|err| TransactionError::Tracing(err.into()))?;
+    // we drop the evm to avoid cloning the inspector
+    drop(evm);
+    let gas_used = res.result.gas_used();
+    let return_value = res.result.into_output().unwrap_or_default();
+    let frame = inspector.into_geth_builder().geth_traces(gas_used, return_value, config);
+    Ok((frame.into(), res.state))
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "testing")]
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::test_utils::eoa::Eoa as _;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use kakarot_rpc::test_utils::rpc::start_kakarot_rpc_server;
+use kakarot_rpc::test_utils::rpc::RawRpcParamsBuilder;
+use reth_primitives::{sign_message, Address, Bytes, Transaction, TransactionKind, TransactionSigned, TxEip1559, U256};
+use rstest::*;
+use serde_json::Value;
+
+/// Calls the given method with the params and returns the raw response.
+async fn call(port: u16, builder: RawRpcParamsBuilder) -> Value {
+    let reqwest_client = reqwest::Client::new();
+    let res = reqwest_client
+        .post(format!("http://localhost:{}", port))
+        .header("Content-Type", "application/json")
+        .body(builder.build())
+        .send()
+        .await
+        .expect("Failed to call Admin RPC");
+    let response = res.text().await.expect("Failed to get response body");
+    serde_json::from_str(&response).expect("Failed to deserialize response body")
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_admin_set_namespace_enabled(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let port = server_addr.port();
+
+    // When
+    let disabled =
+        call(port, RawRpcParamsBuilder::new("admin_setNamespaceEnabled").add_param("trace").add_param(false)).await;
+    let trace_call = call(port, RawRpcParamsBuilder::new("trace_block").add_param("0x1")).await;
+    let enabled =
+        call(port, RawRpcParamsBuilder::new("admin_setNamespaceEnabled").add_param("trace").add_param(true)).await;
+    let invalid =
+        call(port, RawRpcParamsBuilder::new("admin_setNamespaceEnabled").add_param("eth").add_param(false)).await;
+
+    // Then
+    assert_eq!(disabled["result"], serde_json::json!(["trace"]));
+    assert_eq!(trace_call["error"]["code"], -32004);
+    assert_eq!(enabled["result"], serde_json::json!([]));
+    assert_eq!(invalid["error"]["code"], -32602);
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_admin_dump_and_load_mempool(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let port = server_addr.port();
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        chain_id: 1,
+        nonce: 0,
+        gas_limit: 21000,
+        to: TransactionKind::Call(Address::random()),
+        value: U256::from(1000),
+        input: Bytes::default(),
+        max_fee_per_gas: 875000000,
+        max_priority_fee_per_gas: 0,
+        access_list: Default::default(),
+    });
+    let signature = sign_message(katana.eoa().private_key(), transaction.signature_hash()).unwrap();
+    let transaction_signed = TransactionSigned::from_transaction_and_signature(transaction, signature);
+    katana
+        .eth_provider()
+        .send_raw_transaction(transaction_signed.envelope_encoded())
+        .await
+        .expect("Failed to send transaction");
+
+    // When
+    let dump = call(port, RawRpcParamsBuilder::new("admin_dumpMempool")).await;
+    let load = call(port, RawRpcParamsBuilder::new("admin_loadMempool").add_param(dump["result"].clone())).await;
+
+    // Then
+    let pending = dump["result"].as_array().expect("Expected an array of transactions");
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0]["hash"], serde_json::json!(transaction_signed.hash()));
+    // The transaction is already pending
+    assert_eq!(load["result"], 0);
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_admin_flush_caches(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let port = server_addr.port();
+    let _ = call(port, RawRpcParamsBuilder::new("eth_getBlockByNumber").add_param("0x1").add_param(false)).await;
+
+    // When
+    let flushed = call(port, RawRpcParamsBuilder::new("admin_flushCaches")).await;
+    let flushed_again = call(port, RawRpcParamsBuilder::new("admin_flushCaches")).await;
+
+    // Then
+    assert!(flushed["result"].as_u64().is_some());
+    assert_eq!(flushed_again["result"], 0);
+    drop(server_handle);
+}
@@ -1,14 +1,19 @@
 #![cfg(feature = "testing")]
 use ethers::abi::Token;
+use kakarot_rpc::client::{AlchemyApiClient, KakarotRpcClient};
 use kakarot_rpc::models::balance::TokenBalances;
 use kakarot_rpc::models::felt::Felt252Wrapper;
+use kakarot_rpc::models::nft::{NftOwnershipHistoryRequest, NftsRequest};
+use kakarot_rpc::models::transfer::{
+    AssetTransfers, AssetTransfersRequest, Erc1155Metadata, TokenTransfer, TransferCategory, TransferOrder,
+};
 use kakarot_rpc::test_utils::eoa::Eoa as _;
 use kakarot_rpc::test_utils::evm_contract::KakarotEvmContract;
-use kakarot_rpc::test_utils::fixtures::{erc20, setup};
+use kakarot_rpc::test_utils::fixtures::{erc20, katana, setup};
 use kakarot_rpc::test_utils::katana::Katana;
 use kakarot_rpc::test_utils::rpc::start_kakarot_rpc_server;
 use kakarot_rpc::test_utils::rpc::RawRpcParamsBuilder;
-use reth_primitives::{Address, U256};
+use reth_primitives::{Address, BlockNumberOrTag, B256, U256, U64};
 use rstest::*;
 use serde_json::Value;
 
@@ -56,6 +61,8 @@ async fn test_token_balances(#[future] erc20: (Katana, KakarotEvmContract), _set
         .expect("Failed to call Alchemy RPC");
     let response = res.text().await.expect("Failed to get response body");
     let raw: Value = serde_json::from_str(&response).expect("Failed to deserialize response body");
+    // The response follows the Alchemy format
+    assert_eq!(raw["result"]["tokenBalances"][0]["contractAddress"], serde_json::json!(erc20_address));
     let balances: TokenBalances =
         serde_json::from_value(raw.get("result").cloned().unwrap()).expect("Failed to deserialize response body");
     let erc20_balance = balances.token_balances[0].token_balance.expect("Failed to get ERC20 balance");
@@ -63,3 +70,170 @@ async fn test_token_balances(#[future] erc20: (Katana, KakarotEvmContract), _set
     assert_eq!(amount, erc20_balance);
     drop(server_handle);
 }
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_token_metadata(#[future] erc20: (Katana, KakarotEvmContract), _setup: ()) {
+    // Given
+    let katana = erc20.0;
+    let erc20 = erc20.1;
+    let erc20_address: Address =
+        Felt252Wrapper::from(erc20.evm_address).try_into().expect("Failed to convert EVM address");
+
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // When
+    let client =
+        KakarotRpcClient::new(format!("http://localhost:{}", server_addr.port())).expect("Failed to create client");
+    let metadata = client.alchemy().token_metadata(erc20_address).await.expect("Failed to call Alchemy RPC");
+
+    // Then
+    assert_eq!(metadata.name.as_deref(), Some("Test"));
+    assert_eq!(metadata.symbol.as_deref(), Some("TT"));
+    assert_eq!(metadata.decimals, Some(18));
+    assert_eq!(metadata.logo, None);
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_asset_transfers(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let sender = Address::with_last_byte(0xaa);
+    let token = Address::with_last_byte(0xbb);
+    let transfer = |block_number: u64, log_index: u64, value: u64| TokenTransfer {
+        block_number: U64::from(block_number),
+        block_hash: B256::with_last_byte(block_number as u8),
+        block_timestamp: U64::from(1_700_000_000),
+        transaction_hash: B256::with_last_byte(log_index as u8),
+        log_index: U64::from(log_index),
+        category: TransferCategory::Erc20,
+        address: token,
+        from: sender,
+        to: Address::with_last_byte(0xcc),
+        value: Some(U256::from(value)),
+        token_id: None,
+        erc1155_metadata: None,
+    };
+    katana
+        .add_transfers_to_database(vec![
+            transfer(1, 0, 10),
+            transfer(1, 1, 0),
+            transfer(1, 2, 20),
+            transfer(2, 0, 30),
+            transfer(3, 0, 40),
+        ])
+        .await;
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let client =
+        KakarotRpcClient::new(format!("http://localhost:{}", server_addr.port())).expect("Failed to create client");
+    let request = AssetTransfersRequest {
+        from_block: Some(BlockNumberOrTag::Number(0)),
+        to_block: Some(BlockNumberOrTag::Number(3)),
+        from_address: Some(sender),
+        to_address: None,
+        contract_addresses: vec![],
+        category: vec!["erc20".to_string()],
+        order: TransferOrder::Asc,
+        with_metadata: false,
+        exclude_zero_value: true,
+        max_count: Some(U64::from(2)),
+        page_key: None,
+    };
+
+    // When
+    let first_page = client.alchemy().asset_transfers(request.clone()).await.expect("Failed to call Alchemy RPC");
+    let second_page = client
+        .alchemy()
+        .asset_transfers(AssetTransfersRequest { page_key: first_page.page_key, ..request })
+        .await
+        .expect("Failed to call Alchemy RPC");
+
+    // Then
+    // The transfer of a zero amount is excluded by default
+    let block_and_value = |page: &AssetTransfers| {
+        page.transfers.iter().map(|t| (t.block_num.to::<u64>(), t.raw_contract.value)).collect::<Vec<_>>()
+    };
+    assert_eq!(block_and_value(&first_page), vec![(1, Some(U256::from(10))), (1, Some(U256::from(20)))]);
+    assert_eq!(block_and_value(&second_page), vec![(2, Some(U256::from(30))), (3, Some(U256::from(40)))]);
+    assert_eq!(second_page.page_key, None);
+    assert_eq!(first_page.transfers[0].raw_contract.address, token);
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_nfts(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let owner = Address::with_last_byte(0xaa);
+    let other = Address::with_last_byte(0xcc);
+    let (erc721, erc1155) = (Address::with_last_byte(0x21), Address::with_last_byte(0x55));
+    let transfer = |block_number: u64, address: Address, from: Address, to: Address, token_id: u64| {
+        let (category, token_id, erc1155_metadata) = if address == erc1155 {
+            let metadata = Erc1155Metadata { token_id: U256::from(token_id), value: U256::from(5) };
+            (TransferCategory::Erc1155, None, Some(vec![metadata]))
+        } else {
+            (TransferCategory::Erc721, Some(U256::from(token_id)), None)
+        };
+        TokenTransfer {
+            block_number: U64::from(block_number),
+            block_hash: B256::with_last_byte(block_number as u8),
+            block_timestamp: U64::from(1_700_000_000),
+            transaction_hash: B256::with_last_byte(block_number as u8),
+            log_index: U64::ZERO,
+            category,
+            address,
+            from,
+            to,
+            value: None,
+            token_id,
+            erc1155_metadata,
+        }
+    };
+    katana
+        .add_transfers_to_database(vec![
+            transfer(1, erc721, Address::ZERO, owner, 1),
+            transfer(2, erc721, Address::ZERO, owner, 2),
+            transfer(3, erc721, owner, other, 1),
+            transfer(4, erc1155, Address::ZERO, owner, 9),
+        ])
+        .await;
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let client =
+        KakarotRpcClient::new(format!("http://localhost:{}", server_addr.port())).expect("Failed to create client");
+
+    // When
+    let nfts = client
+        .alchemy()
+        .nfts(NftsRequest { owner, contract_addresses: vec![], with_metadata: false, page_key: None, page_size: None })
+        .await
+        .expect("Failed to call Alchemy RPC");
+    let history = client
+        .alchemy()
+        .nft_ownership_history(NftOwnershipHistoryRequest {
+            contract_address: erc721,
+            token_id: U256::from(1),
+            order: TransferOrder::Asc,
+            max_count: None,
+            page_key: None,
+        })
+        .await
+        .expect("Failed to call Alchemy RPC");
+
+    // Then
+    let owned =
+        nfts.owned_nfts.iter().map(|nft| (nft.contract.address, nft.id.token_id.to::<u64>(), nft.balance.as_str()));
+    assert_eq!(owned.collect::<Vec<_>>(), vec![(erc721, 2, "1"), (erc1155, 9, "5")]);
+    assert_eq!(nfts.total_count, U64::from(2));
+    assert_eq!(nfts.page_key, None);
+
+    let owners = history.transfers.iter().map(|transfer| (transfer.from, transfer.to)).collect::<Vec<_>>();
+    assert_eq!(owners, vec![(Address::ZERO, owner), (owner, other)]);
+    drop(server_handle);
+}
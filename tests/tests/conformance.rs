@@ -0,0 +1,61 @@
+#![cfg(feature = "testing")]
+use std::collections::HashSet;
+
+use kakarot_rpc::eth_rpc::rpc::KakarotRpcModuleBuilder;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use kakarot_rpc::test_utils::openrpc::{load_openrpc_spec, ConformanceParams};
+use kakarot_rpc::test_utils::rpc::start_kakarot_rpc_server;
+use rstest::*;
+use serde_json::{json, Value};
+
+/// Calls the method with the params and returns the response.
+async fn call(port: u16, method: &str, params: Vec<Value>) -> Value {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let response = reqwest::Client::new()
+        .post(format!("http://localhost:{port}"))
+        .header("Content-Type", "application/json")
+        .body(request.to_string())
+        .send()
+        .await
+        .expect("Failed to call the RPC");
+    let response = response.text().await.expect("Failed to get response body");
+    serde_json::from_str(&response).expect("Failed to deserialize response body")
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_spec_conformance(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let spec = load_openrpc_spec();
+    let transaction = katana.most_recent_transaction().expect("Missing transaction");
+    let params = ConformanceParams {
+        block_hash: transaction.block_hash.expect("Missing block hash"),
+        transaction_hash: transaction.hash,
+        address: transaction.from,
+    };
+    let module = KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module().expect("Failed to build module");
+    let implemented = module.method_names().collect::<HashSet<_>>();
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // When
+    let mut covered = Vec::new();
+    let mut violations = Vec::new();
+    for method in spec.methods().filter(|method| implemented.contains(method)) {
+        let Some(params) = params.generate(&spec, method) else { continue };
+        let response = call(server_addr.port(), method, params).await;
+        // The errors aren't described by the spec
+        let Some(result) = response.get("result") else { continue };
+        covered.push(method);
+        let method_violations = spec.validate_result(method, result).unwrap_or_default();
+        violations.extend(method_violations.into_iter().map(|violation| format!("{method} {violation}")));
+    }
+
+    // Then
+    assert!(!covered.is_empty(), "No method of the spec was called");
+    assert!(violations.is_empty(), "The responses violate the spec:\n{}", violations.join("\n"));
+
+    drop(server_handle);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "testing")]
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use kakarot_rpc::test_utils::rpc::start_kakarot_rpc_server;
+use kakarot_rpc::test_utils::rpc::RawRpcParamsBuilder;
+use reth_primitives::U64;
+use rstest::*;
+use serde_json::Value;
+use starknet::providers::Provider as _;
+
+/// Calls the given dev method with the params and returns the raw response.
+async fn call_dev(port: u16, builder: RawRpcParamsBuilder) -> Value {
+    let reqwest_client = reqwest::Client::new();
+    let res = reqwest_client
+        .post(format!("http://localhost:{}", port))
+        .header("Content-Type", "application/json")
+        .body(builder.build())
+        .send()
+        .await
+        .expect("Failed to call Dev RPC");
+    let response = res.text().await.expect("Failed to get response body");
+    serde_json::from_str(&response).expect("Failed to deserialize response body")
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_evm_mine(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let starknet_provider = katana.eth_provider().starknet_provider().clone();
+    let block_number = starknet_provider.block_number().await.unwrap();
+
+    // When
+    let response = call_dev(server_addr.port(), RawRpcParamsBuilder::new("evm_mine")).await;
+
+    // Then
+    assert_eq!(response["result"], "0x0");
+    assert_eq!(starknet_provider.block_number().await.unwrap(), block_number + 1);
+
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_evm_increase_time(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // When
+    call_dev(server_addr.port(), RawRpcParamsBuilder::new("evm_increaseTime").add_param(60)).await;
+    let response = call_dev(server_addr.port(), RawRpcParamsBuilder::new("evm_increaseTime").add_param(40)).await;
+
+    // Then
+    let total: U64 = serde_json::from_value(response["result"].clone()).expect("Failed to deserialize result");
+    assert_eq!(total, U64::from(100));
+
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_send_transaction_not_impersonated(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let request = serde_json::json!({ "from": "0x0000000000000000000000000000000000000001", "value": "0x1" });
+
+    // When
+    let response =
+        call_dev(server_addr.port(), RawRpcParamsBuilder::new("eth_sendTransaction").add_param(request)).await;
+
+    // Then
+    assert!(response.get("error").is_some());
+
+    drop(server_handle);
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "testing")]
+use ethers::providers::Middleware;
+use ethers::signers::Signer;
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::test_utils::environment::TestEnvironment;
+use kakarot_rpc::test_utils::fixtures::{setup, test_environment};
+use rstest::*;
+use starknet::providers::Provider;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_environment_spin_up(#[future] test_environment: TestEnvironment, _setup: ()) {
+    // Given
+    let provider = test_environment.provider();
+    let eth_provider = test_environment.katana().eth_provider();
+
+    // When
+    let chain_id = provider.get_chainid().await.expect("Failed to get chain id");
+    let signer = test_environment.signers().first().expect("Missing signer");
+    let balance = provider.get_balance(signer.address(), None).await.expect("Failed to get balance");
+
+    // Then
+    // The RPC server serves the provider of Katana
+    assert_eq!(chain_id.as_u64(), eth_provider.chain_id().await.unwrap().unwrap_or_default().to::<u64>());
+    assert_eq!(signer.chain_id(), chain_id.as_u64());
+    assert!(!balance.is_zero());
+    assert!(test_environment.starknet_provider().block_number().await.is_ok());
+}
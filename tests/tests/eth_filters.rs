@@ -0,0 +1,177 @@
+#![cfg(feature = "testing")]
+use futures::TryStreamExt;
+use kakarot_rpc::eth_provider::database::types::log::{StoredLog, StoredRemovedLog};
+use kakarot_rpc::eth_provider::database::CollectionName;
+use kakarot_rpc::eth_provider::error::EthApiError;
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::eth_rpc::filters::FilterManager;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use mongodb::bson::{doc, Document};
+use reth_primitives::{BlockId, BlockNumberOrTag, B256, U64};
+use reth_rpc_types::{Filter, FilterChanges, Header};
+use rstest::*;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_filter(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let provider = katana.eth_provider();
+    let filters = FilterManager::default();
+
+    // When
+    let id = filters.new_block_filter(&provider).await.expect("Failed to install block filter");
+    let changes = filters.filter_changes(&provider, id).await.expect("Failed to get filter changes");
+
+    // Then
+    // No block was produced since the filter was installed
+    assert!(matches!(changes, FilterChanges::Hashes(hashes) if hashes.is_empty()));
+    // Block filters don't have logs
+    assert!(matches!(filters.filter_logs(&provider, id).await, Err(EthApiError::FilterNotFound(_))));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_logs_filter(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let provider = katana.eth_provider();
+    let filters = FilterManager::default();
+
+    // When
+    let id = filters.new_logs_filter(&provider, Filter::default()).await.expect("Failed to install logs filter");
+    let logs = filters.filter_logs(&provider, id).await.expect("Failed to get filter logs");
+
+    // Then
+    // The filter logs contain all the logs matching the filter, including the ones
+    // emitted before the filter was installed
+    let logs = match logs {
+        FilterChanges::Logs(logs) => logs,
+        _ => panic!("Expected logs"),
+    };
+    assert!(!logs.is_empty());
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_uninstall_filter(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let provider = katana.eth_provider();
+    let filters = FilterManager::default();
+    let id = filters.new_pending_transaction_filter(&provider).await.expect("Failed to install filter");
+
+    // When
+    let removed = filters.uninstall(id);
+
+    // Then
+    assert!(removed);
+    assert!(!filters.uninstall(id));
+    assert!(!filters.uninstall(U64::from(0xdead)));
+    assert!(matches!(filters.filter_changes(&provider, id).await, Err(EthApiError::FilterNotFound(_))));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_filter_reorg(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let provider = katana.eth_provider();
+    let filters = FilterManager::default();
+    let latest = provider
+        .header(&BlockId::Number(BlockNumberOrTag::Latest))
+        .await
+        .expect("Failed to get latest header")
+        .expect("Missing latest header");
+    let number = latest.number.expect("Missing block number") + 1;
+    let header = |hash| Header {
+        number: Some(number),
+        hash: Some(hash),
+        parent_hash: latest.hash.unwrap_or_default(),
+        ..latest.clone()
+    };
+
+    let id = filters.new_block_filter(&provider).await.expect("Failed to install block filter");
+    katana.add_transactions_with_header_to_database(vec![], header(B256::with_last_byte(1))).await;
+    let changes = filters.filter_changes(&provider, id).await.expect("Failed to get filter changes");
+    assert!(matches!(changes, FilterChanges::Hashes(hashes) if hashes == vec![B256::with_last_byte(1)]));
+
+    // When
+    // The block is replaced by another block at the same height
+    provider.database().rollback_from(number).await.expect("Failed to roll back");
+    katana.add_transactions_with_header_to_database(vec![], header(B256::with_last_byte(2))).await;
+    let changes = filters.filter_changes(&provider, id).await.expect("Failed to get filter changes");
+
+    // Then
+    assert!(matches!(changes, FilterChanges::Hashes(hashes) if hashes == vec![B256::with_last_byte(2)]));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rollback_removed_logs(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let provider = katana.eth_provider();
+    let log = provider
+        .database()
+        .get_one::<StoredLog>(None, None)
+        .await
+        .expect("Failed to get log")
+        .expect("Missing log")
+        .log;
+    let block_number = log.block_number.expect("Missing block number");
+    let block_hash = log.block_hash.expect("Missing block hash");
+
+    // When
+    provider.database().rollback_from(block_number).await.expect("Failed to roll back");
+
+    // Then
+    let removed = provider.removed_logs(Filter::default(), vec![block_hash]).await.expect("Failed to get removed logs");
+    assert!(removed.iter().all(|removed| removed.removed && removed.block_hash == Some(block_hash)));
+    assert!(removed
+        .iter()
+        .any(|removed| removed.transaction_hash == log.transaction_hash && removed.log_index == log.log_index));
+    let remaining = provider
+        .database()
+        .count::<StoredLog>(doc! {"log.blockHash": format!("{block_hash:#x}")})
+        .await
+        .expect("Failed to count logs");
+    assert_eq!(remaining, 0);
+    // Logs of other blocks are not returned
+    assert!(provider.removed_logs(Filter::default(), vec![B256::ZERO]).await.expect("Failed to get logs").is_empty());
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rollback_interrupted(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let provider = katana.eth_provider();
+    let database = provider.database();
+    let log = database.get_one::<StoredLog>(None, None).await.expect("Failed to get log").expect("Missing log").log;
+    let block_number = log.block_number.expect("Missing block number");
+    let block_hash = format!("{:#x}", log.block_hash.expect("Missing block hash"));
+    let logs = database.inner().collection::<Document>(StoredLog::collection_name());
+    let block_logs: Vec<Document> = logs
+        .find(doc! {"log.blockHash": &block_hash}, None)
+        .await
+        .expect("Failed to find logs")
+        .try_collect()
+        .await
+        .expect("Failed to collect logs");
+
+    // When
+    // The rollback is interrupted after copying the logs to the removed logs, and run again
+    database.rollback_from(block_number).await.expect("Failed to roll back");
+    logs.insert_many(block_logs.clone(), None).await.expect("Failed to insert logs");
+    database.rollback_from(block_number).await.expect("Failed to roll back again");
+
+    // Then
+    let removed =
+        database.count::<StoredRemovedLog>(doc! {"log.blockHash": &block_hash}).await.expect("Failed to count logs");
+    assert_eq!(removed, block_logs.len() as u64);
+    let remaining =
+        database.count::<StoredLog>(doc! {"log.blockHash": &block_hash}).await.expect("Failed to count logs");
+    assert_eq!(remaining, 0);
+}
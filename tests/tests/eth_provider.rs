@@ -1,11 +1,14 @@
 #![cfg(feature = "testing")]
 use std::str::FromStr;
 
-use kakarot_rpc::eth_provider::constant::{HASH_HEX_STRING_LEN, STARKNET_MODULUS, TRANSACTION_MAX_RETRIES};
+use kakarot_rpc::eth_provider::constant::{BLOB_BASE_FEE, HASH_HEX_STRING_LEN, TRANSACTION_MAX_RETRIES};
 use kakarot_rpc::eth_provider::database::types::transaction::{StoredPendingTransaction, StoredTransaction};
+use kakarot_rpc::eth_provider::error::{EthApiError, TransactionError};
 use kakarot_rpc::eth_provider::provider::EthereumProvider;
 use kakarot_rpc::eth_provider::utils::into_filter;
+use kakarot_rpc::models::account::AccountType;
 use kakarot_rpc::models::felt::Felt252Wrapper;
+use kakarot_rpc::models::internal_transaction::{InternalTransaction, InternalTransactionType};
 use kakarot_rpc::test_utils::eoa::Eoa as _;
 use kakarot_rpc::test_utils::evm_contract::EvmContract;
 use kakarot_rpc::test_utils::fixtures::{contract_empty, counter, katana, setup};
@@ -13,10 +16,10 @@ use kakarot_rpc::test_utils::mongo::{BLOCK_HASH, BLOCK_NUMBER};
 use kakarot_rpc::test_utils::{evm_contract::KakarotEvmContract, katana::Katana};
 use reth_primitives::serde_helper::{JsonStorageKey, U64HexOrNumber};
 use reth_primitives::transaction::Signature;
+use reth_primitives::{keccak256, Address, BlockNumberOrTag, BloomInput, Bytes, TransactionSigned, B256, U256, U64};
 use reth_primitives::{sign_message, Transaction, TransactionKind, TxEip1559};
-use reth_primitives::{Address, BlockNumberOrTag, Bytes, TransactionSigned, B256, U256, U64};
 use reth_rpc_types::request::TransactionInput;
-use reth_rpc_types::{Filter, FilterChanges, RpcBlockHash, TransactionRequest};
+use reth_rpc_types::{BlockTransactions, Filter, FilterChanges, RpcBlockHash, TransactionRequest};
 use rstest::*;
 use starknet::core::types::BlockTag;
 use starknet_crypto::FieldElement;
@@ -95,16 +98,43 @@ async fn test_block_by_number(#[future] katana: Katana, _setup: ()) {
     assert_eq!(block.header.number, Some(block_number));
 
     // When: Retrieving finalized block
-    let block = eth_provider.block_by_number(BlockNumberOrTag::Finalized, false).await.unwrap().unwrap();
+    let finalized = eth_provider.block_by_number(BlockNumberOrTag::Finalized, false).await;
 
-    // Then: Ensure the retrieved block has the same block number as the most recent transaction
-    assert_eq!(block.header.number, Some(block_number));
+    // Then: Ensure no block is finalized, as Katana doesn't settle its blocks on L1
+    assert!(matches!(finalized, Err(EthApiError::UnknownBlock)));
 
     // When: Retrieving safe block
-    let block = eth_provider.block_by_number(BlockNumberOrTag::Safe, false).await.unwrap().unwrap();
+    let safe = eth_provider.block_by_number(BlockNumberOrTag::Safe, false).await;
 
-    // Then: Ensure the retrieved block has the same block number as the most recent transaction
-    assert_eq!(block.header.number, Some(block_number));
+    // Then: Ensure no block is safe, as Katana doesn't settle its blocks on L1
+    assert!(matches!(safe, Err(EthApiError::UnknownBlock)));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_full_block_by_number(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let block_number = BlockNumberOrTag::Number(BLOCK_NUMBER);
+
+    // When
+    let full_block = eth_provider.block_by_number(block_number, true).await.unwrap().unwrap();
+    let block = eth_provider.block_by_number(block_number, false).await.unwrap().unwrap();
+
+    // Then
+    let BlockTransactions::Full(transactions) = full_block.inner.transactions else {
+        panic!("Expected full transactions");
+    };
+    assert_eq!(transactions.len(), 3);
+    let BlockTransactions::Hashes(mut hashes) = block.inner.transactions else {
+        panic!("Expected transaction hashes");
+    };
+    let mut full_hashes = transactions.iter().map(|tx| tx.hash).collect::<Vec<_>>();
+    full_hashes.sort_unstable();
+    hashes.sort_unstable();
+    assert_eq!(full_hashes, hashes);
+    assert_eq!(full_block.inner.header, block.inner.header);
 }
 
 #[rstest]
@@ -264,6 +294,27 @@ async fn test_get_code(#[future] counter: (Katana, KakarotEvmContract), _setup:
     assert_eq!(bytecode, Bytes::from(expected));
 }
 
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_account(#[future] counter: (Katana, KakarotEvmContract), _setup: ()) {
+    // Given
+    let katana: Katana = counter.0;
+    let counter = counter.1;
+    let eth_provider = katana.eth_provider();
+    let counter_address: Felt252Wrapper = counter.evm_address.into();
+    let counter_address = counter_address.try_into().expect("Failed to convert EVM address");
+
+    // When
+    let account = eth_provider.account(counter_address, None).await.unwrap();
+
+    // Then
+    let code = eth_provider.get_code(counter_address, None).await.unwrap();
+    assert_eq!(account.code_hash, keccak256(code));
+    assert_eq!(account.nonce, U64::from(1));
+    assert_eq!(account.balance, eth_provider.balance(counter_address, None).await.unwrap());
+}
+
 #[rstest]
 #[awt]
 #[tokio::test(flavor = "multi_thread")]
@@ -282,6 +333,32 @@ async fn test_get_logs(#[future] katana: Katana, _setup: ()) {
     assert!(!logs.is_empty());
 }
 
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_logs_page(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let provider = katana.eth_provider();
+    let FilterChanges::Logs(logs) = provider.get_logs(Filter::default()).await.expect("Failed to get logs") else {
+        panic!("Expected logs");
+    };
+
+    // When
+    let mut paged_logs = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = provider.logs_page(Filter::default(), cursor).await.expect("Failed to get logs page");
+        paged_logs.extend(page.items);
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    // Then
+    assert_eq!(paged_logs, logs);
+}
+
 #[rstest]
 #[awt]
 #[tokio::test(flavor = "multi_thread")]
@@ -336,10 +413,13 @@ async fn test_estimate_gas(#[future] counter: (Katana, KakarotEvmContract), _set
     };
 
     // When
-    let estimate = eth_provider.estimate_gas(request, None).await.unwrap();
+    let estimate = eth_provider.estimate_gas(request.clone(), None).await.unwrap();
 
     // Then
     assert!(estimate > U256::from(0));
+    // The estimate is enough for the execution to succeed
+    let request = TransactionRequest { gas: Some(estimate.to()), ..request };
+    assert!(eth_provider.call(request, None).await.is_ok());
 }
 
 #[rstest]
@@ -372,10 +452,61 @@ async fn test_fee_history(#[future] katana: Katana, _setup: ()) {
     // to the total number of blocks.
     assert_eq!(fee_history.gas_used_ratio.len(), nbr_blocks);
 
+    // Verify that the blob fields are reported for each block, as no blob gas is ever used.
+    assert_eq!(fee_history.base_fee_per_blob_gas, vec![BLOB_BASE_FEE; nbr_blocks + 1]);
+    assert_eq!(fee_history.blob_gas_used_ratio, vec![0.; nbr_blocks]);
+
     // Verify that the oldest block in the fee history is equal to zero.
     assert_eq!(fee_history.oldest_block, 0);
 }
 
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fee_history_reward_percentiles(#[future] katana: Katana, _setup: ()) {
+    // Retrieve the Ethereum provider from the Katana instance.
+    let eth_provider = katana.eth_provider();
+
+    // Retrieve the most recent block number.
+    let newest_block = katana.most_recent_transaction().unwrap().block_number.unwrap();
+    let nbr_blocks = katana.count_block();
+
+    // Call the fee_history method of the Ethereum provider with reward percentiles.
+    let fee_history = eth_provider
+        .fee_history(U64HexOrNumber::from(u64::MAX), BlockNumberOrTag::Number(newest_block), Some(vec![25., 75.]))
+        .await
+        .unwrap();
+
+    // Verify that a reward is returned for each block and each percentile.
+    let reward = fee_history.reward.expect("Missing reward");
+    assert_eq!(reward.len(), nbr_blocks);
+    assert!(reward.iter().all(|rewards| rewards.len() == 2 && rewards[0] <= rewards[1]));
+
+    // Verify that unordered percentiles are rejected.
+    let err = eth_provider
+        .fee_history(U64HexOrNumber::from(u64::MAX), BlockNumberOrTag::Number(newest_block), Some(vec![75., 25.]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, kakarot_rpc::eth_provider::error::EthApiError::InvalidRewardPercentiles));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_max_priority_fee_per_gas(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+
+    // When
+    let tip = eth_provider.max_priority_fee_per_gas().await.unwrap();
+    let gas_price = eth_provider.gas_price().await.unwrap();
+
+    // Then
+    // The suggestion is cached until a new block is produced
+    assert_eq!(eth_provider.max_priority_fee_per_gas().await.unwrap(), tip);
+    assert!(gas_price >= tip);
+}
+
 #[rstest]
 #[awt]
 #[tokio::test(flavor = "multi_thread")]
@@ -444,6 +575,11 @@ async fn test_block_receipts(#[future] katana: Katana, _setup: ()) {
     assert_eq!(receipt.transaction_index, transaction.transaction_index);
     assert_eq!(receipt.block_hash, transaction.block_hash);
     assert_eq!(receipt.block_number, transaction.block_number);
+    // The cumulative gas used and the logs bloom are computed from the receipts of the block
+    assert_eq!(receipt.inner.cumulative_gas_used(), receipt.gas_used);
+    for log in receipt.inner.logs() {
+        assert!(receipt.inner.logs_bloom().contains_input(BloomInput::Raw(log.address().as_slice())));
+    }
 
     // Then: Retrieve receipts by block hash
     let receipts = eth_provider
@@ -491,21 +627,33 @@ async fn test_to_starknet_block_id(#[future] katana: Katana, _setup: ()) {
 
     // Then: Ensure the converted StarkNet block identifiers match the expected values
     assert_eq!(pending_starknet_block_id, starknet::core::types::BlockId::Number(transaction.block_number.unwrap()));
-    assert_eq!(
-        some_starknet_block_hash,
-        starknet::core::types::BlockId::Hash(
-            FieldElement::from_bytes_be(
-                &U256::from_be_slice(transaction.block_hash.unwrap().as_slice())
-                    .wrapping_rem(STARKNET_MODULUS)
-                    .to_be_bytes()
-            )
-            .unwrap()
-        )
-    );
+    assert_eq!(some_starknet_block_hash, starknet::core::types::BlockId::Number(transaction.block_number.unwrap()));
     assert_eq!(pending_block_tag_starknet, starknet::core::types::BlockId::Tag(BlockTag::Pending));
     assert!(unknown_starknet_block_number.is_err());
 }
 
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_historical_state_unknown_block(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let eoa_address = katana.eoa().evm_address().expect("Failed to get eoa address");
+    let unknown_block_hash = reth_rpc_types::BlockId::Hash(RpcBlockHash::from(B256::repeat_byte(0xff)));
+    let unknown_block_number = reth_rpc_types::BlockId::Number(BlockNumberOrTag::Number(u64::MAX));
+
+    // When
+    let balance = eth_provider.balance(eoa_address, Some(unknown_block_hash)).await;
+    let code = eth_provider.get_code(eoa_address, Some(unknown_block_number)).await;
+    let storage =
+        eth_provider.storage_at(eoa_address, JsonStorageKey::from(U256::ZERO), Some(unknown_block_hash)).await;
+
+    // Then: the state isn't silently read at the latest block
+    assert!(matches!(balance, Err(EthApiError::UnknownBlock)));
+    assert!(matches!(code, Err(EthApiError::UnknownBlockNumber)));
+    assert!(matches!(storage, Err(EthApiError::UnknownBlock)));
+}
+
 #[rstest]
 #[awt]
 #[tokio::test(flavor = "multi_thread")]
@@ -550,6 +698,127 @@ async fn test_send_raw_transaction(#[future] katana: Katana, _setup: ()) {
     assert!(tx.block_number.is_none());
 }
 
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pending_block_and_nonce(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let eoa = katana.eoa();
+    let eoa_address = eoa.evm_address().expect("Failed to get eoa address");
+    let nonce = eth_provider.transaction_count(eoa_address, None).await.expect("Failed to get nonce");
+
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        chain_id: 1,
+        nonce: nonce.to(),
+        gas_limit: 21000,
+        to: TransactionKind::Call(Address::random()),
+        value: U256::from(1000),
+        input: Bytes::default(),
+        max_fee_per_gas: 875000000,
+        max_priority_fee_per_gas: 0,
+        access_list: Default::default(),
+    });
+    let signature = sign_message(eoa.private_key(), transaction.signature_hash()).unwrap();
+    let transaction_signed = TransactionSigned::from_transaction_and_signature(transaction, signature);
+
+    // When
+    eth_provider.send_raw_transaction(transaction_signed.envelope_encoded()).await.expect("Failed to send transaction");
+
+    // Then
+    // The pending nonce accounts for the transaction of the pending pool
+    let pending_nonce = eth_provider
+        .transaction_count(eoa_address, Some(reth_rpc_types::BlockId::Number(BlockNumberOrTag::Pending)))
+        .await
+        .expect("Failed to get pending nonce");
+    assert_eq!(pending_nonce, nonce + U256::from(1));
+
+    // The pending block contains the transaction of the pending pool
+    let latest_block_number = eth_provider.block_number().await.expect("Failed to get block number");
+    let pending_block = eth_provider
+        .block_by_number(BlockNumberOrTag::Pending, false)
+        .await
+        .expect("Failed to get pending block")
+        .expect("Missing pending block");
+    assert_eq!(pending_block.header.number, Some(latest_block_number.to::<u64>() + 1));
+    assert!(pending_block.header.hash.is_none());
+    match pending_block.transactions {
+        reth_rpc_types::BlockTransactions::Hashes(hashes) => assert!(hashes.contains(&transaction_signed.hash())),
+        _ => panic!("Expected transaction hashes"),
+    }
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_send_raw_transaction_underpriced_replacement(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let eoa = katana.eoa();
+    let eoa_address = eoa.evm_address().expect("Failed to get eoa address");
+    let nonce: u64 = eth_provider.transaction_count(eoa_address, None).await.expect("Failed to get nonce").to();
+
+    let sign = |value: u64| {
+        let transaction = Transaction::Eip1559(TxEip1559 {
+            chain_id: 1,
+            nonce,
+            gas_limit: 21000,
+            to: TransactionKind::Call(Address::random()),
+            value: U256::from(value),
+            input: Bytes::default(),
+            max_fee_per_gas: 875000000,
+            max_priority_fee_per_gas: 0,
+            access_list: Default::default(),
+        });
+        let signature = sign_message(eoa.private_key(), transaction.signature_hash()).unwrap();
+        TransactionSigned::from_transaction_and_signature(transaction, signature)
+    };
+    let transaction = sign(1000);
+    eth_provider.send_raw_transaction(transaction.envelope_encoded()).await.expect("Failed to send transaction");
+
+    // When
+    let replacement = sign(2000);
+    let res = eth_provider.send_raw_transaction(replacement.envelope_encoded()).await;
+
+    // Then
+    assert!(matches!(res, Err(EthApiError::Transaction(TransactionError::ReplacementUnderpriced))));
+    let tx: StoredPendingTransaction =
+        eth_provider.database().get_one(None, None).await.expect("Failed to get transaction").unwrap();
+    assert_eq!(tx.tx.hash, transaction.hash());
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_send_raw_transaction_already_known(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let eoa = katana.eoa();
+    let eoa_address = eoa.evm_address().expect("Failed to get eoa address");
+    let nonce: u64 = eth_provider.transaction_count(eoa_address, None).await.expect("Failed to get nonce").to();
+
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        chain_id: 1,
+        nonce,
+        gas_limit: 21000,
+        to: TransactionKind::Call(Address::random()),
+        value: U256::from(1000),
+        input: Bytes::default(),
+        max_fee_per_gas: 875000000,
+        max_priority_fee_per_gas: 0,
+        access_list: Default::default(),
+    });
+    let signature = sign_message(eoa.private_key(), transaction.signature_hash()).unwrap();
+    let transaction_signed = TransactionSigned::from_transaction_and_signature(transaction, signature);
+    eth_provider.send_raw_transaction(transaction_signed.envelope_encoded()).await.expect("Failed to send transaction");
+
+    // When
+    let res = eth_provider.send_raw_transaction(transaction_signed.envelope_encoded()).await;
+
+    // Then
+    assert!(matches!(res, Err(EthApiError::Transaction(TransactionError::AlreadyKnown))));
+}
+
 #[rstest]
 #[awt]
 #[tokio::test(flavor = "multi_thread")]
@@ -588,6 +857,46 @@ async fn test_send_raw_transaction_wrong_signature(#[future] katana: Katana, _se
     assert!(tx.is_none());
 }
 
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_send_raw_transaction_insufficient_funds(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let chain_id = eth_provider.chain_id().await.unwrap().unwrap_or_default().to();
+
+    // Create a transaction from a fresh key, whose account isn't deployed nor funded
+    let private_key = B256::random();
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        chain_id,
+        nonce: 0,
+        gas_limit: 21000,
+        to: TransactionKind::Call(Address::random()),
+        value: U256::from(1000),
+        input: Bytes::default(),
+        max_fee_per_gas: 875000000,
+        max_priority_fee_per_gas: 0,
+        access_list: Default::default(),
+    });
+    let signature = sign_message(private_key, transaction.signature_hash()).unwrap();
+    let transaction_signed = TransactionSigned::from_transaction_and_signature(transaction, signature);
+    let sender = transaction_signed.recover_signer().unwrap();
+
+    // When
+    let result = eth_provider.send_raw_transaction(transaction_signed.envelope_encoded()).await;
+
+    // Then
+    assert!(matches!(
+        result,
+        Err(EthApiError::Transaction(TransactionError::InsufficientFunds(balance, _))) if balance == U256::ZERO
+    ));
+    let account_type = eth_provider.account_type(sender, None).await.expect("Failed to get account type");
+    assert_eq!(account_type, AccountType::Undeployed);
+    let tx: Option<StoredPendingTransaction> =
+        eth_provider.database().get_one(None, None).await.expect("Failed to get transaction");
+    assert!(tx.is_none());
+}
+
 #[rstest]
 #[awt]
 #[tokio::test(flavor = "multi_thread")]
@@ -673,7 +982,7 @@ async fn test_retry_transactions(#[future] katana: Katana, _setup: ()) {
         .expect("Failed to insert pending transaction in database");
 
     // Insert the transaction into the pending transactions collection with TRANSACTION_MAX_RETRIES + 1 retry
-    // Shouldn't be retried as it has reached the maximum number of retries
+    // Shouldn't be retried as it has reached the maximum number of retries, and should be marked as failed
     let transaction2 = katana.eoa().mock_transaction_with_nonce(1).expect("Failed to get mock transaction");
     eth_provider
         .database()
@@ -720,12 +1029,167 @@ async fn test_retry_transactions(#[future] katana: Katana, _setup: ()) {
         .await
         .expect("Failed get pending transactions");
 
-    // Ensure that the spurious transactions are dropped from the pending transactions collection
-    assert_eq!(pending_transactions.len(), 1);
+    // Ensure that the mined transaction is dropped from the pending transactions collection
+    assert_eq!(pending_transactions.len(), 2);
 
     // Ensure that the retry is incremented for the first transaction
-    assert_eq!(pending_transactions.first().unwrap().retries, 1);
+    let pending_transaction1 = pending_transactions.iter().find(|tx| tx.tx == transaction1).unwrap();
+    assert_eq!(pending_transaction1.retries, 1);
+    assert!(pending_transaction1.failed_block.is_none());
+
+    // Ensure that the second transaction is marked as failed and is no longer returned
+    let pending_transaction2 = pending_transactions.iter().find(|tx| tx.tx == transaction2).unwrap();
+    assert!(pending_transaction2.failed_block.is_some());
+    assert!(eth_provider.transaction_by_hash(transaction2.hash).await.unwrap().is_none());
+    assert_eq!(eth_provider.pending_transactions().await.unwrap(), vec![pending_transaction1.tx.clone()]);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recover_pending_transactions(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let nonce: u64 = eth_provider.transaction_count(katana.eoa().evm_address().unwrap(), None).await.unwrap().to();
+
+    // Insert a transaction whose submission to Starknet was interrupted
+    let transaction1 = katana.eoa().mock_transaction_with_nonce(nonce).expect("Failed to get mock transaction");
+    eth_provider
+        .database()
+        .update_one::<StoredPendingTransaction>(
+            StoredPendingTransaction { unsubmitted: true, ..transaction1.clone().into() },
+            into_filter("tx.hash", &transaction1.hash, HASH_HEX_STRING_LEN),
+            true,
+        )
+        .await
+        .expect("Failed to insert pending transaction in database");
+
+    // Insert an interrupted transaction whose nonce was already used, which shouldn't be resubmitted
+    if let Some(used_nonce) = nonce.checked_sub(1) {
+        let transaction2 =
+            katana.eoa().mock_transaction_with_nonce(used_nonce).expect("Failed to get mock transaction");
+        eth_provider
+            .database()
+            .update_one::<StoredPendingTransaction>(
+                StoredPendingTransaction { unsubmitted: true, ..transaction2.clone().into() },
+                into_filter("tx.hash", &transaction2.hash, HASH_HEX_STRING_LEN),
+                true,
+            )
+            .await
+            .expect("Failed to insert pending transaction in database");
+    }
+
+    // Insert a transaction which was submitted to Starknet, which is left to the retry service
+    let transaction3 = katana.eoa().mock_transaction_with_nonce(nonce + 1).expect("Failed to get mock transaction");
+    eth_provider
+        .database()
+        .update_one::<StoredPendingTransaction>(
+            transaction3.clone().into(),
+            into_filter("tx.hash", &transaction3.hash, HASH_HEX_STRING_LEN),
+            true,
+        )
+        .await
+        .expect("Failed to insert pending transaction in database");
+
+    // When
+    let recovered_transactions =
+        eth_provider.recover_pending_transactions().await.expect("Failed to recover pending transactions");
+
+    // Then
+    assert_eq!(recovered_transactions.len(), 1);
+    let mut pending_transactions = eth_provider
+        .database()
+        .get::<StoredPendingTransaction>(None, None)
+        .await
+        .expect("Failed get pending transactions");
+    pending_transactions.sort_by_key(|tx| tx.tx.nonce);
+    assert_eq!(pending_transactions.len(), 2);
+    assert_eq!(pending_transactions[0].tx, transaction1);
+    assert!(!pending_transactions[0].unsubmitted);
+    assert_eq!(pending_transactions[1].tx, transaction3);
+    assert_eq!(pending_transactions[1].retries, 0);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_evm_address(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let eoa = katana.eoa();
+    let starknet_address = eoa.starknet_address().unwrap();
 
-    // Ensure that the transaction1 is still in the pending transactions collection
-    assert_eq!(pending_transactions.first().unwrap().tx, transaction1);
+    // When
+    let evm_address = eth_provider.evm_address(starknet_address, None).await.unwrap();
+    let unknown_address = eth_provider.evm_address(FieldElement::from(0xdeadu64), None).await.unwrap();
+
+    // Then
+    assert_eq!(evm_address, Some(eoa.evm_address().unwrap()));
+    assert_eq!(unknown_address, None);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_account_type(#[future] counter: (Katana, KakarotEvmContract), _setup: ()) {
+    // Given
+    let katana = counter.0;
+    let counter = counter.1;
+    let eth_provider = katana.eth_provider();
+    let eoa = katana.eoa();
+    let counter_address: Felt252Wrapper = counter.evm_address.into();
+    let counter_address = counter_address.try_into().expect("Failed to convert EVM address");
+
+    // When
+    let eoa_type = eth_provider.account_type(eoa.evm_address().unwrap(), None).await.unwrap();
+    let counter_type = eth_provider.account_type(counter_address, None).await.unwrap();
+    let undeployed_type = eth_provider.account_type(Address::random(), None).await.unwrap();
+
+    // Then
+    assert_eq!(eoa_type, AccountType::Eoa);
+    assert_eq!(counter_type, AccountType::Contract);
+    assert_eq!(undeployed_type, AccountType::Undeployed);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_internal_transactions(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let address = Address::with_last_byte(0xaa);
+    let internal_tx = |block_number: u64, index: u64, from: Address, to: Address| InternalTransaction {
+        block_number: U64::from(block_number),
+        transaction_hash: B256::with_last_byte(block_number as u8),
+        transaction_index: U64::ZERO,
+        index: U64::from(index),
+        trace_address: vec![0],
+        kind: InternalTransactionType::Call,
+        from,
+        to,
+        value: U256::from(1),
+    };
+    let other = Address::with_last_byte(0xbb);
+    katana
+        .add_internal_transactions_to_database(vec![
+            internal_tx(1, 0, address, other),
+            internal_tx(1, 1, other, other),
+            internal_tx(1, 2, other, address),
+            internal_tx(2, 0, address, other),
+        ])
+        .await;
+
+    // When
+    let first_page = eth_provider.internal_transactions(address, 0, 2, None, 2).await.unwrap();
+    let second_page = eth_provider.internal_transactions(address, 0, 2, first_page.next_cursor, 2).await.unwrap();
+    let by_hash = eth_provider.transaction_internal_transactions(B256::with_last_byte(1)).await.unwrap();
+
+    // Then
+    let positions = |items: &[InternalTransaction]| {
+        items.iter().map(|tx| (tx.block_number.to::<u64>(), tx.index.to::<u64>())).collect::<Vec<_>>()
+    };
+    assert_eq!(positions(&first_page.items), vec![(1, 0), (1, 2)]);
+    assert_eq!(positions(&second_page.items), vec![(2, 0)]);
+    assert_eq!(second_page.next_cursor, None);
+    assert_eq!(positions(&by_hash), vec![(1, 0), (1, 1), (1, 2)]);
 }
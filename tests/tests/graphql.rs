@@ -0,0 +1,53 @@
+#![cfg(feature = "testing")]
+use kakarot_rpc::eth_rpc::graphql::graphql_schema;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use rstest::*;
+use serde_json::json;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_graphql_transaction(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let schema = graphql_schema(katana.eth_provider());
+    let transaction = katana.most_recent_transaction().expect("Missing transaction");
+    let query = format!(
+        r#"{{ transaction(hash: "{}") {{ hash nonce from {{ address }} block {{ hash }} }} }}"#,
+        transaction.hash
+    );
+
+    // When
+    let response = schema.execute(query).await;
+
+    // Then
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().expect("Failed to convert the response");
+    assert_eq!(
+        data,
+        json!({
+            "transaction": {
+                "hash": transaction.hash,
+                "nonce": transaction.nonce,
+                "from": { "address": format!("{:#x}", transaction.from) },
+                "block": { "hash": transaction.block_hash },
+            }
+        })
+    );
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_graphql_blocks(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let schema = graphql_schema(katana.eth_provider());
+
+    // When
+    let response = schema.execute("{ blocks(from: 0, to: 1000) { number } }").await;
+
+    // Then
+    // The range exceeds the maximum number of blocks of a query
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(response.errors[0].message, "block range exceeds 100 blocks");
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "testing")]
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::test_utils::eoa::Eoa as _;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use reth_primitives::U256;
+use rstest::*;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snapshot_and_revert(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let mut katana = katana;
+    let address = katana.eoa().evm_address().unwrap();
+    let snapshot_id = katana.snapshot().await.expect("Failed to take snapshot");
+    let nonce_before = katana.eth_provider().transaction_count(address, None).await.unwrap();
+
+    katana.eoa().deploy_evm_contract(Some("Counter"), ()).await.expect("Failed to deploy Counter contract");
+    let nonce_after_deploy = katana.eth_provider().transaction_count(address, None).await.unwrap();
+    assert_eq!(nonce_after_deploy, nonce_before + U256::from(1));
+
+    // When
+    katana.revert_to(snapshot_id).await.expect("Failed to revert to snapshot");
+
+    // Then
+    let nonce = katana.eth_provider().transaction_count(address, None).await.unwrap();
+    assert_eq!(nonce, nonce_before);
+    assert!(katana.revert_to(snapshot_id).await.is_err());
+}
@@ -1,4 +1,15 @@
+pub mod admin_api;
 pub mod alchemy_api;
+pub mod conformance;
 pub mod debug_api;
+pub mod dev_api;
+pub mod environment;
+pub mod eth_filters;
 pub mod eth_provider;
+pub mod graphql;
+pub mod katana;
+pub mod ots_api;
+pub mod pruning;
+pub mod pubsub_api;
 pub mod trace_api;
+pub mod txpool_api;
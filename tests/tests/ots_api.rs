@@ -0,0 +1,63 @@
+#![cfg(feature = "testing")]
+use kakarot_rpc::eth_provider::database::types::transaction::{StoredPendingTransaction, StoredTransaction};
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::models::otterscan::SearchDirection;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use reth_primitives::U256;
+use rstest::*;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transaction_by_sender_and_nonce(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let transaction = katana.first_transaction().unwrap();
+
+    // When
+    let result =
+        eth_provider.transaction_by_sender_and_nonce(transaction.from, U256::from(transaction.nonce)).await.unwrap();
+
+    // Then
+    assert_eq!(result.map(|tx| tx.hash), Some(transaction.hash));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transaction_indexes(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let database = katana.eth_provider().database().clone();
+
+    // When
+    let transactions_indexes = database.collection::<StoredTransaction>().list_index_names().await.unwrap();
+    let pending_indexes = database.collection::<StoredPendingTransaction>().list_index_names().await.unwrap();
+
+    // Then
+    assert!(transactions_indexes.contains(&"tx_from_nonce".to_string()));
+    assert!(pending_indexes.contains(&"tx_from_nonce".to_string()));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_transactions(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let transaction = katana.most_recent_transaction().unwrap();
+
+    // When
+    let (before, _) = eth_provider.search_transactions(transaction.from, 0, 1, SearchDirection::Before).await.unwrap();
+    let (after, _) = eth_provider.search_transactions(transaction.from, 0, 1, SearchDirection::After).await.unwrap();
+
+    // Then
+    // Searching before the block 0 starts from the most recent transaction
+    assert_eq!(before.first().map(|tx| tx.hash), Some(transaction.hash));
+    assert!(!after.is_empty());
+    // The transactions are sorted from the most recent to the oldest
+    for transactions in [before, after] {
+        assert!(transactions.windows(2).all(|pair| pair[0].block_number >= pair[1].block_number));
+        assert!(transactions.iter().all(|tx| tx.from == transaction.from || tx.to == Some(transaction.from)));
+    }
+}
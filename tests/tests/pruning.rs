@@ -0,0 +1,62 @@
+#![cfg(feature = "testing")]
+use kakarot_rpc::eth_provider::constant::BLOCK_NUMBER_HEX_STRING_LEN;
+use kakarot_rpc::eth_provider::database::types::{
+    header::StoredHeader,
+    log::{StoredLog, StoredRemovedLog},
+    trace::StoredTrace,
+};
+use kakarot_rpc::eth_provider::database::CollectionName;
+use kakarot_rpc::eth_provider::pruning::{prune_database, PruningConfig};
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use mongodb::bson::{doc, DateTime, Document};
+use reth_primitives::B256;
+use rstest::*;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_prune_database(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let database = katana.eth_provider().database().clone();
+    let latest = database
+        .get_one::<StoredHeader>(None, doc! {"header.number": -1})
+        .await
+        .unwrap()
+        .and_then(|stored| stored.header.number)
+        .unwrap();
+    let headers_count = database.count::<StoredHeader>(None).await.unwrap();
+    let trace = |hash: u8, block_number: u64| StoredTrace {
+        hash: B256::with_last_byte(hash),
+        block_number,
+        kind: "parity".to_string(),
+        trace: "[]".to_string(),
+        created_at: DateTime::now(),
+    };
+    database.collection::<StoredTrace>().insert_many([trace(1, 0), trace(2, latest)], None).await.unwrap();
+    let removed_log = |block_number: u64| {
+        doc! {"log": {"blockNumber": format!("{block_number:#0width$x}", width = BLOCK_NUMBER_HEX_STRING_LEN + 2)}}
+    };
+    database
+        .inner()
+        .collection::<Document>(StoredRemovedLog::collection_name())
+        .insert_many([removed_log(0), removed_log(latest)], None)
+        .await
+        .unwrap();
+
+    // When
+    let config = PruningConfig { logs: Some(1), traces: Some(1) };
+    let summary = prune_database(&database, &config).await.unwrap();
+
+    // Then
+    let logs = database.get::<StoredLog>(None, None).await.unwrap();
+    assert!(logs.iter().all(|stored| stored.log.block_number == Some(latest)));
+    let traces = database.get::<StoredTrace>(None, None).await.unwrap();
+    assert_eq!(traces.into_iter().map(|trace| trace.block_number).collect::<Vec<_>>(), vec![latest]);
+    assert_eq!(summary.traces, 1);
+    // The logs removed by the reorgs are pruned with the same retention as the logs
+    assert_eq!(summary.removed_logs, 1);
+    assert_eq!(database.count::<StoredRemovedLog>(None).await.unwrap(), 1);
+    // The headers are never pruned
+    assert_eq!(database.count::<StoredHeader>(None).await.unwrap(), headers_count);
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "testing")]
+use std::time::Duration;
+
+use kakarot_rpc::eth_provider::database::types::log::StoredLog;
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::eth_rpc::api::pubsub_api::EthPubSubApiServer;
+use kakarot_rpc::eth_rpc::servers::pubsub_rpc::EthPubSubRpc;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use reth_primitives::{BlockId, BlockNumberOrTag, B256};
+use reth_rpc_types::{Header, Log};
+use rstest::*;
+use serde_json::json;
+
+/// Maximum duration to wait for an item of a subscription, which polls the provider every second.
+const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns the latest header of the provider.
+async fn latest_header(katana: &Katana) -> Header {
+    katana
+        .eth_provider()
+        .header(&BlockId::Number(BlockNumberOrTag::Latest))
+        .await
+        .expect("Failed to get latest header")
+        .expect("Missing latest header")
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_subscribe_new_heads(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let module = EthPubSubRpc::new(katana.eth_provider()).into_rpc();
+    let latest = latest_header(&katana).await;
+    let number = latest.number.expect("Missing block number") + 1;
+    let header = |number, hash| Header { number: Some(number), hash: Some(hash), ..latest.clone() };
+    let mut subscription =
+        module.subscribe_unbounded("eth_subscribe", [json!("newHeads")]).await.expect("Failed to subscribe");
+
+    // When
+    // The second block is indexed before the first one
+    katana.add_transactions_with_header_to_database(vec![], header(number + 1, B256::with_last_byte(2))).await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    katana.add_transactions_with_header_to_database(vec![], header(number, B256::with_last_byte(1))).await;
+
+    // Then
+    // The headers are sent once, in order
+    for hash in [B256::with_last_byte(1), B256::with_last_byte(2)] {
+        let (received, _) = tokio::time::timeout(SUBSCRIPTION_TIMEOUT, subscription.next::<Header>())
+            .await
+            .expect("Timed out waiting for header")
+            .expect("Subscription closed")
+            .expect("Failed to get header");
+        assert_eq!(received.hash, Some(hash));
+    }
+    assert!(tokio::time::timeout(Duration::from_secs(2), subscription.next::<Header>()).await.is_err());
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_subscribe_logs(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let provider = katana.eth_provider();
+    let module = EthPubSubRpc::new(provider.clone()).into_rpc();
+    let latest = latest_header(&katana).await;
+    let number = latest.number.expect("Missing block number") + 1;
+    let header = |hash| Header { number: Some(number), hash: Some(hash), ..latest.clone() };
+    let log = provider
+        .database()
+        .get_one::<StoredLog>(None, None)
+        .await
+        .expect("Failed to get log")
+        .expect("Missing log")
+        .log;
+    let block_hash = B256::with_last_byte(1);
+    let mut subscription =
+        module.subscribe_unbounded("eth_subscribe", [json!("logs"), json!({})]).await.expect("Failed to subscribe");
+
+    // When
+    katana.add_logs_to_database(vec![Log { block_number: Some(number), block_hash: Some(block_hash), ..log }]).await;
+    katana.add_transactions_with_header_to_database(vec![], header(block_hash)).await;
+    let (added, _) = tokio::time::timeout(SUBSCRIPTION_TIMEOUT, subscription.next::<Log>())
+        .await
+        .expect("Timed out waiting for log")
+        .expect("Subscription closed")
+        .expect("Failed to get log");
+
+    // The block is replaced by another block without logs at the same height
+    provider.database().rollback_from(number).await.expect("Failed to roll back");
+    katana.add_transactions_with_header_to_database(vec![], header(B256::with_last_byte(2))).await;
+    let (removed, _) = tokio::time::timeout(SUBSCRIPTION_TIMEOUT, subscription.next::<Log>())
+        .await
+        .expect("Timed out waiting for log")
+        .expect("Subscription closed")
+        .expect("Failed to get log");
+
+    // Then
+    assert_eq!(added.block_hash, Some(block_hash));
+    assert!(!added.removed);
+    assert_eq!(removed.block_hash, Some(block_hash));
+    assert_eq!(removed.transaction_hash, added.transaction_hash);
+    assert!(removed.removed);
+}
@@ -1,6 +1,7 @@
 #![cfg(feature = "testing")]
 use ethers::abi::{Token, Tokenize};
 use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::models::call_bundle::BundleCallResult;
 use kakarot_rpc::test_utils::eoa::Eoa;
 use kakarot_rpc::test_utils::evm_contract::{
     EvmContract, KakarotEvmContract, TransactionInfo, TxCommonInfo, TxFeeMarketInfo, TxLegacyInfo,
@@ -9,9 +10,10 @@ use kakarot_rpc::test_utils::fixtures::{eip_3074_invoker, plain_opcodes, setup};
 use kakarot_rpc::test_utils::katana::Katana;
 use kakarot_rpc::test_utils::rpc::start_kakarot_rpc_server;
 use kakarot_rpc::test_utils::rpc::RawRpcParamsBuilder;
-use reth_primitives::{Address, B256, U256};
-use reth_rpc_types::trace::geth::TraceResult;
-use reth_rpc_types::trace::parity::LocalizedTransactionTrace;
+use reth_primitives::{Address, BlockNumberOrTag, Bytes, B256, U256, U64};
+use reth_rpc_types::trace::geth::{GethTrace, TraceResult};
+use reth_rpc_types::trace::parity::{LocalizedTransactionTrace, TraceResults};
+use reth_rpc_types::AccessListWithGasUsed;
 use rstest::*;
 use serde_json::{json, Value};
 use starknet::core::types::MaybePendingBlockWithTxHashes;
@@ -276,3 +278,276 @@ async fn test_trace_eip3074(#[future] eip_3074_invoker: (Katana, KakarotEvmContr
     assert!(traces.unwrap().len() == 2 * TRACING_TRANSACTIONS_COUNT);
     drop(server_handle);
 }
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_transaction(#[future] plain_opcodes: (Katana, KakarotEvmContract), _setup: ()) {
+    // Setup the Kakarot RPC server.
+    let katana = plain_opcodes.0;
+    let plain_opcodes = plain_opcodes.1;
+    tracing(&katana, &plain_opcodes, "createCounterAndInvoke", Box::new(|_| ())).await;
+
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // Get the last transaction of the traced block.
+    let transactions = katana
+        .eth_provider()
+        .block_transactions(Some(BlockNumberOrTag::Number(TRACING_BLOCK_NUMBER).into()))
+        .await
+        .expect("Failed to get block transactions")
+        .expect("Missing block");
+    let transaction_hash = transactions.last().expect("Missing transaction").hash;
+
+    // Send the debug_traceTransaction RPC request.
+    let reqwest_client = reqwest::Client::new();
+    let res = reqwest_client
+        .post(format!("http://localhost:{}", server_addr.port()))
+        .header("Content-Type", "application/json")
+        .body(
+            RawRpcParamsBuilder::new("debug_traceTransaction")
+                .add_param(transaction_hash)
+                .add_param(json!({
+                    "tracer": "callTracer",
+                    "tracerConfig": {
+                        "onlyTopCall": false
+                    },
+                    "timeout": "300s"
+                }))
+                .build(),
+        )
+        .send()
+        .await
+        .expect("Failed to call Debug RPC");
+    let response = res.text().await.expect("Failed to get response body");
+    let raw: Value = serde_json::from_str(&response).expect("Failed to deserialize response body");
+    let trace: GethTrace = serde_json::from_value(raw["result"].clone()).expect("Failed to deserialize result");
+
+    // We expect a call frame targeting the plain opcodes contract.
+    let plain_opcodes_address = Address::from_slice(&plain_opcodes.evm_address.to_bytes_be()[12..]);
+    match trace {
+        GethTrace::CallTracer(frame) => assert_eq!(frame.to, Some(plain_opcodes_address)),
+        _ => panic!("Expected a call tracer frame"),
+    }
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trace_transaction(#[future] plain_opcodes: (Katana, KakarotEvmContract), _setup: ()) {
+    // Setup the Kakarot RPC server.
+    let katana = plain_opcodes.0;
+    let plain_opcodes = plain_opcodes.1;
+    tracing(&katana, &plain_opcodes, "createCounterAndInvoke", Box::new(|_| ())).await;
+
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // Get the last transaction of the traced block.
+    let transactions = katana
+        .eth_provider()
+        .block_transactions(Some(BlockNumberOrTag::Number(TRACING_BLOCK_NUMBER).into()))
+        .await
+        .expect("Failed to get block transactions")
+        .expect("Missing block");
+    let transaction_hash = transactions.last().expect("Missing transaction").hash;
+
+    // Send the trace_transaction RPC request.
+    let reqwest_client = reqwest::Client::new();
+    let res = reqwest_client
+        .post(format!("http://localhost:{}", server_addr.port()))
+        .header("Content-Type", "application/json")
+        .body(RawRpcParamsBuilder::new("trace_transaction").add_param(transaction_hash).build())
+        .send()
+        .await
+        .expect("Failed to call Trace RPC");
+    let response = res.text().await.expect("Failed to get response body");
+    let raw: Value = serde_json::from_str(&response).expect("Failed to deserialize response body");
+    let traces: Option<Vec<LocalizedTransactionTrace>> =
+        serde_json::from_value(raw["result"].clone()).expect("Failed to deserialize result");
+
+    // We expect 3 traces for the transaction: CALL, CREATE, and CALL.
+    let traces = traces.expect("Missing traces");
+    assert_eq!(traces.len(), 3);
+    assert!(traces.iter().all(|trace| trace.transaction_hash == Some(transaction_hash)));
+
+    // Send the trace_replayTransaction RPC request.
+    let res = reqwest_client
+        .post(format!("http://localhost:{}", server_addr.port()))
+        .header("Content-Type", "application/json")
+        .body(
+            RawRpcParamsBuilder::new("trace_replayTransaction")
+                .add_param(transaction_hash)
+                .add_param(["trace", "stateDiff"])
+                .build(),
+        )
+        .send()
+        .await
+        .expect("Failed to call Trace RPC");
+    let response = res.text().await.expect("Failed to get response body");
+    let raw: Value = serde_json::from_str(&response).expect("Failed to deserialize response body");
+    let trace_results: TraceResults =
+        serde_json::from_value(raw["result"].clone()).expect("Failed to deserialize result");
+
+    assert_eq!(trace_results.trace.len(), 3);
+    assert!(trace_results.state_diff.is_some());
+    assert!(trace_results.vm_trace.is_none());
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_create_access_list(#[future] plain_opcodes: (Katana, KakarotEvmContract), _setup: ()) {
+    // Setup the Kakarot RPC server.
+    let katana = plain_opcodes.0;
+    let plain_opcodes = plain_opcodes.1;
+    tracing(&katana, &plain_opcodes, "createCounterAndInvoke", Box::new(|_| ())).await;
+
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // Prepare the request.
+    let eoa_address = katana.eoa().evm_address().expect("Failed to get eoa address");
+    let plain_opcodes_address = Address::from_slice(&plain_opcodes.evm_address.to_bytes_be()[12..]);
+    let input = plain_opcodes
+        .prepare_call_transaction("createCounterAndInvoke", (), &TransactionInfo::LegacyInfo(TxLegacyInfo::default()))
+        .expect("Failed to prepare call transaction")
+        .input()
+        .clone();
+
+    // Send the eth_createAccessList RPC request.
+    let reqwest_client = reqwest::Client::new();
+    let res = reqwest_client
+        .post(format!("http://localhost:{}", server_addr.port()))
+        .header("Content-Type", "application/json")
+        .body(
+            RawRpcParamsBuilder::new("eth_createAccessList")
+                .add_param(json!({
+                    "from": eoa_address,
+                    "to": plain_opcodes_address,
+                    "input": input,
+                }))
+                .add_param(format!("0x{:016x}", TRACING_BLOCK_NUMBER))
+                .build(),
+        )
+        .send()
+        .await
+        .expect("Failed to call Eth RPC");
+    let response = res.text().await.expect("Failed to get response body");
+    let raw: Value = serde_json::from_str(&response).expect("Failed to deserialize response body");
+    let access_list: AccessListWithGasUsed =
+        serde_json::from_value(raw["result"].clone()).expect("Failed to deserialize result");
+
+    // The sender is never part of the access list.
+    assert!(access_list.access_list.0.iter().all(|item| item.address != eoa_address));
+    assert!(access_list.gas_used > U256::ZERO);
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_with_overrides(#[future] plain_opcodes: (Katana, KakarotEvmContract), _setup: ()) {
+    // Setup the Kakarot RPC server.
+    let katana = plain_opcodes.0;
+    let plain_opcodes = plain_opcodes.1;
+    tracing(&katana, &plain_opcodes, "createCounterAndInvoke", Box::new(|_| ())).await;
+
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // Prepare the request: the code of the target is overridden with
+    // NUMBER PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN, returning the block number.
+    let eoa_address = katana.eoa().evm_address().expect("Failed to get eoa address");
+    let target = Address::from([0x42; 20]);
+
+    // Send the eth_call RPC request with a state override and a block override.
+    let reqwest_client = reqwest::Client::new();
+    let res = reqwest_client
+        .post(format!("http://localhost:{}", server_addr.port()))
+        .header("Content-Type", "application/json")
+        .body(
+            RawRpcParamsBuilder::new("eth_call")
+                .add_param(json!({
+                    "from": eoa_address,
+                    "to": target,
+                }))
+                .add_param(format!("0x{:016x}", TRACING_BLOCK_NUMBER))
+                .add_param(json!({ target.to_string(): { "code": "0x4360005260206000f3" } }))
+                .add_param(json!({ "number": "0x1234" }))
+                .build(),
+        )
+        .send()
+        .await
+        .expect("Failed to call Eth RPC");
+    let response = res.text().await.expect("Failed to get response body");
+    let raw: Value = serde_json::from_str(&response).expect("Failed to deserialize response body");
+    let output: Bytes = serde_json::from_value(raw["result"].clone()).expect("Failed to deserialize result");
+
+    // The overridden code is executed with the overridden block number.
+    assert_eq!(U256::from_be_slice(&output), U256::from(0x1234));
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_many(#[future] plain_opcodes: (Katana, KakarotEvmContract), _setup: ()) {
+    // Setup the Kakarot RPC server.
+    let katana = plain_opcodes.0;
+    let plain_opcodes = plain_opcodes.1;
+    tracing(&katana, &plain_opcodes, "createCounterAndInvoke", Box::new(|_| ())).await;
+
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // Prepare the bundle: the first call stores 1 in the slot 0 of the target, with the code
+    // PUSH1 1 PUSH1 0 SSTORE STOP. The second call returns the slot 0, with the code
+    // PUSH1 0 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN.
+    let eoa_address = katana.eoa().evm_address().expect("Failed to get eoa address");
+    let target = Address::from([0x42; 20]);
+    let bundle = json!([
+        {
+            "transaction": { "from": eoa_address, "to": target },
+            "stateOverrides": { target.to_string(): { "code": "0x600160005500" } }
+        },
+        {
+            "transaction": { "from": eoa_address, "to": target },
+            "stateOverrides": { target.to_string(): { "code": "0x60005460005260206000f3" } }
+        }
+    ]);
+
+    // Send the eth_callMany and debug_traceCallMany RPC requests.
+    let reqwest_client = reqwest::Client::new();
+    let mut responses = Vec::new();
+    for method in ["eth_callMany", "debug_traceCallMany"] {
+        let res = reqwest_client
+            .post(format!("http://localhost:{}", server_addr.port()))
+            .header("Content-Type", "application/json")
+            .body(
+                RawRpcParamsBuilder::new(method)
+                    .add_param(bundle.clone())
+                    .add_param(format!("0x{:016x}", TRACING_BLOCK_NUMBER))
+                    .build(),
+            )
+            .send()
+            .await
+            .expect("Failed to call Eth RPC");
+        let response = res.text().await.expect("Failed to get response body");
+        let raw: Value = serde_json::from_str(&response).expect("Failed to deserialize response body");
+        responses.push(raw["result"].clone());
+    }
+    let results: Vec<BundleCallResult> =
+        serde_json::from_value(responses[0].clone()).expect("Failed to deserialize result");
+    let traces: Vec<GethTrace> = serde_json::from_value(responses[1].clone()).expect("Failed to deserialize result");
+
+    // The second call reads the storage written by the first call.
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result.error.is_none() && result.gas_used > U64::ZERO));
+    assert_eq!(U256::from_be_slice(&results[1].value.clone().unwrap_or_default()), U256::from(1));
+    assert_eq!(traces.len(), 2);
+    drop(server_handle);
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "testing")]
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::test_utils::eoa::Eoa as _;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use kakarot_rpc::test_utils::rpc::start_kakarot_rpc_server;
+use kakarot_rpc::test_utils::rpc::RawRpcParamsBuilder;
+use reth_primitives::{sign_message, Address, Bytes, Transaction, TransactionKind, TransactionSigned, TxEip1559, U256};
+use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolStatus};
+use rstest::*;
+use serde_json::Value;
+
+/// Sends a transaction from the Katana EOA and returns it.
+async fn send_transaction(katana: &Katana) -> TransactionSigned {
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        chain_id: 1,
+        nonce: 0,
+        gas_limit: 21000,
+        to: TransactionKind::Call(Address::random()),
+        value: U256::from(1000),
+        input: Bytes::default(),
+        max_fee_per_gas: 875000000,
+        max_priority_fee_per_gas: 0,
+        access_list: Default::default(),
+    });
+    let signature = sign_message(katana.eoa().private_key(), transaction.signature_hash()).unwrap();
+    let transaction_signed = TransactionSigned::from_transaction_and_signature(transaction, signature);
+
+    katana
+        .eth_provider()
+        .send_raw_transaction(transaction_signed.envelope_encoded())
+        .await
+        .expect("Failed to send transaction");
+
+    transaction_signed
+}
+
+/// Calls the given txpool method with the params and returns the deserialized result.
+async fn call_txpool<T: serde::de::DeserializeOwned>(port: u16, builder: RawRpcParamsBuilder) -> T {
+    let reqwest_client = reqwest::Client::new();
+    let res = reqwest_client
+        .post(format!("http://localhost:{}", port))
+        .header("Content-Type", "application/json")
+        .body(builder.build())
+        .send()
+        .await
+        .expect("Failed to call Txpool RPC");
+    let response = res.text().await.expect("Failed to get response body");
+    let raw: Value = serde_json::from_str(&response).expect("Failed to deserialize response body");
+    serde_json::from_value(raw["result"].clone()).expect("Failed to deserialize result")
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_txpool_status(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let _ = send_transaction(&katana).await;
+
+    // When
+    let status: TxpoolStatus = call_txpool(server_addr.port(), RawRpcParamsBuilder::new("txpool_status")).await;
+
+    // Then
+    assert_eq!(status.pending, 1);
+    assert_eq!(status.queued, 0);
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_txpool_content(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let transaction = send_transaction(&katana).await;
+    let eoa_address = katana.eoa().evm_address().expect("Failed to get eoa address");
+
+    // When
+    let content: TxpoolContent = call_txpool(server_addr.port(), RawRpcParamsBuilder::new("txpool_content")).await;
+
+    // Then
+    let pending = content.pending.get(&eoa_address).expect("Missing sender in pending transactions");
+    assert_eq!(pending.get("0").expect("Missing nonce in pending transactions").hash, transaction.hash());
+    assert!(content.queued.is_empty());
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_txpool_content_from(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+    let transaction = send_transaction(&katana).await;
+    let eoa_address = katana.eoa().evm_address().expect("Failed to get eoa address");
+
+    // When
+    let content_from: TxpoolContentFrom =
+        call_txpool(server_addr.port(), RawRpcParamsBuilder::new("txpool_contentFrom").add_param(eoa_address)).await;
+    let content_from_random: TxpoolContentFrom =
+        call_txpool(server_addr.port(), RawRpcParamsBuilder::new("txpool_contentFrom").add_param(Address::random()))
+            .await;
+
+    // Then
+    assert_eq!(content_from.pending.len(), 1);
+    assert_eq!(content_from.pending.get("0").expect("Missing nonce in pending transactions").hash, transaction.hash());
+    assert!(content_from_random.pending.is_empty());
+    drop(server_handle);
+}